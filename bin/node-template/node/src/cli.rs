@@ -17,6 +17,9 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Print the genesis hash of the chain specification.
+	InspectGenesisHash(sc_cli::InspectGenesisHashCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
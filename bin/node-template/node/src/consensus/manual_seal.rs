@@ -0,0 +1,122 @@
+//! Instant seal consensus, enabled with the `manual-seal` feature.
+//!
+//! A block is authored as soon as a transaction enters the pool. Blocks carry an Aura pre-digest,
+//! so the runtime keeps working unchanged, but they are never finalized.
+
+use std::sync::Arc;
+use node_template_runtime::opaque::Block;
+use sc_consensus_manual_seal::{consensus::aura::AuraConsensusDataProvider, InstantSealParams};
+use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_telemetry::TelemetryHandle;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{digests::CompatibleDigestItem, sr25519::AuthoritySignature};
+use sp_runtime::{generic::BlockId, traits::Header as HeaderT};
+use super::StartParams;
+use crate::service::{FullClient, FullSelectChain};
+
+/// Instant seal needs no state besides the one shared by all consensus engines.
+pub struct Link;
+
+/// Creates an import queue which accepts any block.
+pub fn import_queue(
+	config: &Configuration,
+	client: Arc<FullClient>,
+	_select_chain: FullSelectChain,
+	task_manager: &TaskManager,
+	_telemetry: Option<TelemetryHandle>,
+) -> Result<(sp_consensus::DefaultImportQueue<Block, FullClient>, Link), ServiceError> {
+	let import_queue = sc_consensus_manual_seal::import_queue(
+		Box::new(client),
+		&task_manager.spawn_essential_handle(),
+		config.prometheus_registry(),
+	);
+
+	Ok((import_queue, Link))
+}
+
+/// Instant seal does not need any additional network protocols.
+pub fn configure_network(_config: &mut Configuration) {}
+
+/// Starts the instant seal authoring task if the node is an authority.
+pub fn start(params: StartParams) -> Result<(), ServiceError> {
+	let StartParams {
+		config,
+		link: Link,
+		client,
+		select_chain,
+		transaction_pool,
+		network: _,
+		keystore_container: _,
+		task_manager,
+		telemetry,
+	} = params;
+
+	if !config.role.is_authority() {
+		return Ok(())
+	}
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		config.prometheus_registry(),
+		telemetry,
+	);
+
+	let slot_duration = sc_consensus_aura::slot_duration(&*client)?.slot_duration();
+
+	let create_inherent_data_providers = {
+		let client = client.clone();
+
+		move |parent, ()| {
+			let parent_header = client.header(BlockId::Hash(parent));
+
+			async move {
+				let parent_header = parent_header?
+					.ok_or("Parent header not found")?;
+				let parent_slot = parent_header.digest()
+					.logs()
+					.iter()
+					.find_map(CompatibleDigestItem::<AuthoritySignature>::as_aura_pre_digest)
+					.map_or(0, |slot| *slot);
+
+				// the runtime requires strictly increasing slots which match the timestamp, so
+				// blocks authored within the same slot are pushed into the next one.
+				let now = *sp_timestamp::InherentDataProvider::from_system_time().timestamp();
+				let slot = std::cmp::max(now / slot_duration, parent_slot + 1);
+
+				let timestamp = sp_timestamp::InherentDataProvider::new(
+					(slot * slot_duration).into(),
+				);
+				let slot = sp_consensus_aura::inherents::InherentDataProvider::new(slot.into());
+
+				Ok::<_, Box<dyn std::error::Error + Send + Sync>>((timestamp, slot))
+			}
+		}
+	};
+
+	let instant_seal = sc_consensus_manual_seal::run_instant_seal(InstantSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client,
+		pool: transaction_pool.pool().clone(),
+		select_chain,
+		consensus_data_provider: Some(Box::new(
+			AuraConsensusDataProvider::<Block, FullClient>::new(),
+		)),
+		create_inherent_data_providers,
+	});
+
+	// the authoring task is considered essential, i.e. if it
+	// fails we take down the service with it.
+	task_manager.spawn_essential_handle().spawn_blocking("instant-seal", instant_seal);
+
+	Ok(())
+}
+
+/// Light clients can't follow a chain without finality.
+pub fn new_light(_config: Configuration) -> Result<TaskManager, ServiceError> {
+	Err(ServiceError::Other(
+		"Light clients are not supported when building with `manual-seal`.".into()
+	))
+}
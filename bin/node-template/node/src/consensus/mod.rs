@@ -0,0 +1,52 @@
+//! The consensus engine of the node, selected at build time.
+//!
+//! By default the node authors blocks with Aura and finalizes them with GRANDPA. Building with
+//! the `manual-seal` feature replaces both with instant seal, which authors a block as soon as a
+//! transaction arrives. That is handy during development, but never finalizes anything.
+//!
+//! Each engine exposes the same seams to the service:
+//!
+//! - `Link`, the consensus specific state handed from `new_partial` to `new_full`.
+//! - `import_queue`, which creates the import queue and the `Link`.
+//! - `configure_network`, to register any additional network protocols.
+//! - `start`, which spawns the authoring and finality tasks.
+//! - `new_light`, which builds a light client.
+
+use std::sync::Arc;
+use node_template_runtime::opaque::Block;
+use sc_service::{Configuration, KeystoreContainer, TaskManager};
+use sc_telemetry::TelemetryHandle;
+use sp_runtime::traits::Block as BlockT;
+use crate::service::{FullClient, FullPool, FullSelectChain};
+
+#[cfg(not(feature = "manual-seal"))]
+mod aura_grandpa;
+#[cfg(not(feature = "manual-seal"))]
+pub use aura_grandpa::*;
+
+#[cfg(feature = "manual-seal")]
+mod manual_seal;
+#[cfg(feature = "manual-seal")]
+pub use manual_seal::*;
+
+/// The components of a full node a consensus engine needs to start.
+pub struct StartParams<'a> {
+	/// The configuration of the node.
+	pub config: &'a Configuration,
+	/// The consensus specific state created along with the import queue.
+	pub link: Link,
+	/// The client of the node.
+	pub client: Arc<FullClient>,
+	/// The strategy used to select the best chain.
+	pub select_chain: FullSelectChain,
+	/// The transaction pool of the node.
+	pub transaction_pool: Arc<FullPool>,
+	/// The network service of the node.
+	pub network: Arc<sc_network::NetworkService<Block, <Block as BlockT>::Hash>>,
+	/// The keystore container of the node.
+	pub keystore_container: &'a KeystoreContainer,
+	/// The task manager to spawn the consensus tasks on.
+	pub task_manager: &'a TaskManager,
+	/// The telemetry handle, if telemetry is enabled.
+	pub telemetry: Option<TelemetryHandle>,
+}
@@ -47,6 +47,27 @@ pub enum Subcommand {
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
+	/// Measure the execution overhead of blocks and extrinsics.
+	#[structopt(
+		name = "benchmark-overhead",
+		about = "Benchmark the execution overhead of blocks and extrinsics."
+	)]
+	BenchmarkOverhead(frame_benchmarking_cli::OverheadCmd),
+
+	/// Benchmark the hardware and check it against the reference requirements.
+	#[structopt(
+		name = "benchmark-machine",
+		about = "Benchmark the hardware and check it against the reference requirements."
+	)]
+	BenchmarkMachine(frame_benchmarking_cli::MachineCmd),
+
+	/// Measure the storage read and write cost of the database.
+	#[structopt(
+		name = "benchmark-storage",
+		about = "Benchmark the storage read and write cost of the database."
+	)]
+	BenchmarkStorage(frame_benchmarking_cli::StorageCmd),
+
 	/// Try some experimental command on the runtime. This includes migration and runtime-upgrade
 	/// testing.
 	#[cfg(feature = "try-runtime")]
@@ -64,6 +85,9 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Print the genesis hash of the chain specification.
+	InspectGenesisHash(sc_cli::InspectGenesisHashCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -22,6 +22,7 @@ use node_runtime::{Block, RuntimeApi};
 use sc_cli::{Result, SubstrateCli, RuntimeVersion, Role, ChainSpec};
 use sc_service::PartialComponents;
 use crate::service::new_partial;
+use crate::command_helper::{BenchmarkExtrinsicBuilder, inherent_benchmark_data};
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
@@ -97,6 +98,28 @@ pub fn run() -> Result<()> {
 				You can enable it with `--features runtime-benchmarks`.".into())
 			}
 		}
+		Some(Subcommand::BenchmarkOverhead(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = new_partial(&config)?;
+				let ext_builder = BenchmarkExtrinsicBuilder::new(client.clone());
+				cmd.run(client, inherent_benchmark_data()?, &ext_builder)
+			})
+		}
+		Some(Subcommand::BenchmarkMachine(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| cmd.run(&config))
+		}
+		Some(Subcommand::BenchmarkStorage(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+
+			runner.sync_run(|config| {
+				let PartialComponents { backend, .. } = new_partial(&config)?;
+				cmd.run(config, backend)
+			})
+		}
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
@@ -105,6 +128,10 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
 		},
+		Some(Subcommand::InspectGenesisHash(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(config.chain_spec))
+		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -0,0 +1,64 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Contains code to setup the command invocations in [`super::command`] which would
+//! otherwise bloat that module.
+
+use crate::service::{create_extrinsic, FullClient};
+
+use node_runtime::{SystemCall, UncheckedExtrinsic};
+use node_primitives::Block;
+use sc_cli::Result;
+use sp_inherents::InherentData;
+use sp_keyring::Sr25519Keyring;
+
+use std::sync::Arc;
+
+/// Generates `System::Remark` extrinsics for the benchmarks.
+pub struct BenchmarkExtrinsicBuilder {
+	client: Arc<FullClient>,
+}
+
+impl BenchmarkExtrinsicBuilder {
+	/// Creates a new [`Self`] from the given client.
+	pub fn new(client: Arc<FullClient>) -> Self {
+		Self { client }
+	}
+}
+
+impl frame_benchmarking_cli::ExtrinsicBuilder<Block> for BenchmarkExtrinsicBuilder {
+	fn remark(&self, nonce: u32) -> std::result::Result<UncheckedExtrinsic, &'static str> {
+		Ok(create_extrinsic(
+			self.client.as_ref(),
+			Sr25519Keyring::Bob.pair(),
+			SystemCall::remark(vec![]),
+			nonce,
+		))
+	}
+}
+
+/// Generates inherent data for the benchmarks.
+///
+/// The timestamp is set to zero, which matches the slot of a chain without any blocks.
+pub fn inherent_benchmark_data() -> Result<InherentData> {
+	let mut inherent_data = InherentData::new();
+	let timestamp = sp_timestamp::InherentType::new(0);
+	inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+		.map_err(|e| format!("creating inherent data: {:?}", e))?;
+	Ok(inherent_data)
+}
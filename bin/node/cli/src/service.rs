@@ -27,22 +27,92 @@ use node_runtime::RuntimeApi;
 use sc_service::{
 	config::Configuration, error::Error as ServiceError, RpcHandlers, TaskManager,
 };
-use sp_inherents::InherentDataProviders;
 use sc_network::{Event, NetworkService};
 use sp_runtime::traits::Block as BlockT;
 use futures::prelude::*;
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use sc_client_api::{BlockBackend, ExecutorProvider, RemoteBackend};
 use node_executor::Executor;
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sc_consensus_babe::SlotProportion;
+use codec::Encode;
+use sp_core::Pair;
+use sp_runtime::{generic::{BlockId, Era, SignedPayload}, SaturatedConversion};
 
-type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
+/// The full client type definition.
+pub type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 type FullGrandpaBlockImport =
 	grandpa::GrandpaBlockImport<FullBackend, Block, FullClient, FullSelectChain>;
 type LightClient = sc_service::TLightClient<Block, RuntimeApi, Executor>;
 
+/// Registers the contracts profiling extension for every runtime call, feeding the
+/// contracts prometheus metrics.
+struct ContractsProfilingExtensions(Arc<pallet_contracts_rpc::ContractsMetrics>);
+
+impl sc_client_api::execution_extensions::ExtensionsFactory for ContractsProfilingExtensions {
+	fn extensions_for(&self, _: sp_core::offchain::Capabilities) -> sp_externalities::Extensions {
+		let mut extensions = sp_externalities::Extensions::new();
+		extensions.register(pallet_contracts_primitives::ProfilingExt::new(self.0.clone()));
+		extensions
+	}
+}
+
+/// Create a transaction calling `function`, signed by `sender` with the given `nonce`.
+///
+/// The transaction is valid on top of the best block of `client`.
+pub fn create_extrinsic(
+	client: &FullClient,
+	sender: sp_core::sr25519::Pair,
+	function: impl Into<node_runtime::Call>,
+	nonce: u32,
+) -> node_runtime::UncheckedExtrinsic {
+	let function = function.into();
+	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
+	let best_hash = client.chain_info().best_hash;
+	let best_block = client.chain_info().best_number;
+	let version = client.runtime_version_at(&BlockId::Hash(best_hash))
+		.expect("The runtime version of the best block can be queried; qed");
+
+	let period = node_runtime::BlockHashCount::get()
+		.checked_next_power_of_two()
+		.map(|c| c / 2)
+		.unwrap_or(2) as u64;
+	let extra: node_runtime::SignedExtra = (
+		frame_system::CheckSpecVersion::<node_runtime::Runtime>::new(),
+		frame_system::CheckTxVersion::<node_runtime::Runtime>::new(),
+		frame_system::CheckGenesis::<node_runtime::Runtime>::new(),
+		frame_system::CheckEra::<node_runtime::Runtime>::from(
+			Era::mortal(period, best_block.saturated_into()),
+		),
+		frame_system::CheckNonce::<node_runtime::Runtime>::from(nonce),
+		frame_system::CheckWeight::<node_runtime::Runtime>::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::<node_runtime::Runtime>::from(0),
+	);
+
+	let raw_payload = SignedPayload::from_raw(
+		function.clone(),
+		extra.clone(),
+		(
+			version.spec_version,
+			version.transaction_version,
+			genesis_hash,
+			best_hash,
+			(),
+			(),
+			(),
+		),
+	);
+	let signature = raw_payload.using_encoded(|e| sender.sign(e));
+
+	node_runtime::UncheckedExtrinsic::new_signed(
+		function,
+		sp_runtime::AccountId32::from(sender.public()).into(),
+		node_runtime::Signature::Sr25519(signature),
+		extra,
+	)
+}
+
 pub fn new_partial(
 	config: &Configuration,
 ) -> Result<sc_service::PartialComponents<
@@ -79,6 +149,15 @@ pub fn new_partial(
 		)?;
 	let client = Arc::new(client);
 
+	if let Some(registry) = config.prometheus_registry() {
+		match pallet_contracts_rpc::ContractsMetrics::register(registry) {
+			Ok(metrics) => client.execution_extensions().set_extensions_factory(
+				Box::new(ContractsProfilingExtensions(Arc::new(metrics))),
+			),
+			Err(err) => log::warn!("Failed to register contracts prometheus metrics: {}", err),
+		}
+	}
+
 	let telemetry = telemetry
 		.map(|(worker, telemetry)| {
 			task_manager.spawn_handle().spawn("telemetry", worker.run());
@@ -109,15 +188,24 @@ pub fn new_partial(
 		client.clone(),
 	)?;
 
-	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
-
+	let slot_duration = babe_link.config().slot_duration();
 	let import_queue = sc_consensus_babe::import_queue(
 		babe_link.clone(),
 		block_import.clone(),
 		Some(Box::new(justification_import)),
 		client.clone(),
 		select_chain.clone(),
-		inherent_data_providers.clone(),
+		move |_, ()| async move {
+			let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+			let slot =
+				sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_duration(
+					*timestamp,
+					std::time::Duration::from_millis(slot_duration),
+				);
+
+			Ok((timestamp, slot))
+		},
 		&task_manager.spawn_essential_handle(),
 		config.prometheus_registry(),
 		sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
@@ -183,14 +271,12 @@ pub fn new_partial(
 		select_chain,
 		import_queue,
 		transaction_pool,
-		inherent_data_providers,
 		other: (rpc_extensions_builder, import_setup, rpc_setup, telemetry),
 	})
 }
 
 pub struct NewFullBase {
 	pub task_manager: TaskManager,
-	pub inherent_data_providers: InherentDataProviders,
 	pub client: Arc<FullClient>,
 	pub network: Arc<NetworkService<Block, <Block as BlockT>::Hash>>,
 	pub network_status_sinks: sc_service::NetworkStatusSinks<Block>,
@@ -213,7 +299,6 @@ pub fn new_full_base(
 		keystore_container,
 		select_chain,
 		transaction_pool,
-		inherent_data_providers,
 		other: (rpc_extensions_builder, import_setup, rpc_setup, mut telemetry),
 	} = new_partial(&config)?;
 
@@ -237,6 +322,7 @@ pub fn new_full_base(
 			client: client.clone(),
 			transaction_pool: transaction_pool.clone(),
 			spawn_handle: task_manager.spawn_handle(),
+			spawn_essential_handle: task_manager.spawn_essential_handle(),
 			import_queue,
 			on_demand: None,
 			block_announce_validator_builder: None,
@@ -290,6 +376,8 @@ pub fn new_full_base(
 		let can_author_with =
 			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
 
+		let client_clone = client.clone();
+		let slot_duration = babe_link.config().slot_duration();
 		let babe_config = sc_consensus_babe::BabeParams {
 			keystore: keystore_container.sync_keystore(),
 			client: client.clone(),
@@ -297,7 +385,25 @@ pub fn new_full_base(
 			env: proposer,
 			block_import,
 			sync_oracle: network.clone(),
-			inherent_data_providers: inherent_data_providers.clone(),
+			create_inherent_data_providers: move |parent, ()| {
+				let client_clone = client_clone.clone();
+				async move {
+					let uncles = sc_consensus_uncles::create_uncles_inherent_data_provider(
+						&*client_clone,
+						parent,
+					)?;
+
+					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+					let slot =
+						sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_duration(
+							*timestamp,
+							std::time::Duration::from_millis(slot_duration),
+						);
+
+					Ok((timestamp, slot, uncles))
+				}
+			},
 			force_authoring,
 			backoff_authoring_blocks,
 			babe_link,
@@ -378,7 +484,6 @@ pub fn new_full_base(
 	network_starter.start_network();
 	Ok(NewFullBase {
 		task_manager,
-		inherent_data_providers,
 		client,
 		network,
 		network_status_sinks,
@@ -458,15 +563,24 @@ pub fn new_light_base(
 		client.clone(),
 	)?;
 
-	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
-
+	let slot_duration = babe_link.config().slot_duration();
 	let import_queue = sc_consensus_babe::import_queue(
 		babe_link,
 		babe_block_import,
 		Some(Box::new(justification_import)),
 		client.clone(),
 		select_chain.clone(),
-		inherent_data_providers.clone(),
+		move |_, ()| async move {
+			let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+			let slot =
+				sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_duration(
+					*timestamp,
+					std::time::Duration::from_millis(slot_duration),
+				);
+
+			Ok((timestamp, slot))
+		},
 		&task_manager.spawn_essential_handle(),
 		config.prometheus_registry(),
 		sp_consensus::NeverCanAuthor,
@@ -479,6 +593,7 @@ pub fn new_light_base(
 			client: client.clone(),
 			transaction_pool: transaction_pool.clone(),
 			spawn_handle: task_manager.spawn_handle(),
+			spawn_essential_handle: task_manager.spawn_essential_handle(),
 			import_queue,
 			on_demand: Some(on_demand.clone()),
 			block_announce_validator_builder: None,
@@ -556,6 +671,7 @@ mod tests {
 		traits::Verify,
 	};
 	use sp_timestamp;
+	use sp_inherents::InherentDataProvider;
 	use sp_keyring::AccountKeyring;
 	use sc_service_test::TestNetNode;
 	use crate::service::{new_full_base, new_light_base, NewFullBase};
@@ -592,7 +708,7 @@ mod tests {
 			|config| {
 				let mut setup_handles = None;
 				let NewFullBase {
-					task_manager, inherent_data_providers, client, network, transaction_pool, ..
+					task_manager, client, network, transaction_pool, ..
 				} = new_full_base(config,
 					|
 						block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
@@ -605,17 +721,13 @@ mod tests {
 				let node = sc_service_test::TestNetComponents::new(
 					task_manager, client, network, transaction_pool
 				);
-				Ok((node, (inherent_data_providers, setup_handles.unwrap())))
+				Ok((node, setup_handles.unwrap()))
 			},
 			|config| {
 				let (keep_alive, _, client, network, transaction_pool) = new_light_base(config)?;
 				Ok(sc_service_test::TestNetComponents::new(keep_alive, client, network, transaction_pool))
 			},
-			|service, &mut (ref inherent_data_providers, (ref mut block_import, ref babe_link))| {
-				let mut inherent_data = inherent_data_providers
-					.create_inherent_data()
-					.expect("Creates inherent data.");
-
+			|service, &mut (ref mut block_import, ref babe_link)| {
 				let parent_id = BlockId::number(service.client().chain_info().best_number);
 				let parent_header = service.client().header(&parent_id).unwrap().unwrap();
 				let parent_hash = parent_header.hash();
@@ -650,7 +762,6 @@ mod tests {
 				// even though there's only one authority some slots might be empty,
 				// so we must keep trying the next slots until we can claim one.
 				let babe_pre_digest = loop {
-					inherent_data.replace_data(sp_timestamp::INHERENT_IDENTIFIER, &(slot * SLOT_DURATION));
 					if let Some(babe_pre_digest) = sc_consensus_babe::test_helpers::claim_slot(
 						slot.into(),
 						&parent_header,
@@ -666,6 +777,11 @@ mod tests {
 
 				digest.push(<DigestItem as CompatibleDigestItem>::babe_pre_digest(babe_pre_digest));
 
+				let inherent_data = (
+					sp_timestamp::InherentDataProvider::new(slot * SLOT_DURATION),
+					sp_consensus_babe::inherents::InherentDataProvider::new(slot.into()),
+				).create_inherent_data().expect("Creates inherent data");
+
 				let new_block = futures::executor::block_on(async move {
 					let proposer = proposer_factory.init(&parent_header).await;
 					proposer.unwrap().propose(
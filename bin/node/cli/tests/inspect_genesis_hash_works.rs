@@ -0,0 +1,44 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn inspect_genesis_hash_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+
+	let genesis_hash = || {
+		let output = Command::new(cargo_bin("substrate"))
+			.args(&["inspect-genesis-hash", "--dev", "-d"])
+			.arg(base_path.path())
+			.output()
+			.unwrap();
+		assert!(output.status.success());
+		String::from_utf8(output.stdout).unwrap()
+	};
+
+	let hash = genesis_hash();
+	assert!(hash.trim().starts_with("0x"));
+	assert_eq!(hash.trim().len(), 66);
+
+	// The genesis is deterministic and no database is created.
+	assert_eq!(hash, genesis_hash());
+	assert!(!base_path.path().join("chains/dev/db").exists());
+}
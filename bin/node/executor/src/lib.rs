@@ -27,5 +27,8 @@ native_executor_instance!(
 	pub Executor,
 	node_runtime::api::dispatch,
 	node_runtime::native_version,
-	frame_benchmarking::benchmarking::HostFunctions,
+	(
+		frame_benchmarking::benchmarking::HostFunctions,
+		pallet_contracts_primitives::contracts_profiling::HostFunctions,
+	),
 );
@@ -289,11 +289,13 @@ mod tests {
 		let b1 = BlockAddress::from_str("1234");
 		let b2 = BlockAddress::from_str("0");
 		let b3 = BlockAddress::from_str("0x0012345f");
+		let b4 = BlockAddress::from_str("0x3BfC20f0B9aFcAcE800D73D2191166FF16540258");
 
 
 		assert_eq!(b0, Ok(BlockAddress::Hash(
 			"3BfC20f0B9aFcAcE800D73D2191166FF16540258".parse().unwrap()
 		)));
+		assert_eq!(b4, b0);
 		assert_eq!(b1, Ok(BlockAddress::Number(1234)));
 		assert_eq!(b2, Ok(BlockAddress::Number(0)));
 		assert_eq!(b3, Ok(BlockAddress::Bytes(vec![0, 0x12, 0x34, 0x5f])));
@@ -309,6 +311,7 @@ mod tests {
 		let b1 = ExtrinsicAddress::from_str("1234:0");
 		let b2 = ExtrinsicAddress::from_str("0 0");
 		let b3 = ExtrinsicAddress::from_str("0x0012345f");
+		let b4 = ExtrinsicAddress::from_str("0x3BfC20f0B9aFcAcE800D73D2191166FF16540258:5");
 
 
 		assert_eq!(e0, Err("Extrinsic index missing: example \"5:0\"".into()));
@@ -325,5 +328,6 @@ mod tests {
 			0
 		)));
 		assert_eq!(b3, Ok(ExtrinsicAddress::Bytes(vec![0, 0x12, 0x34, 0x5f])));
+		assert_eq!(b4, b0);
 	}
 }
@@ -89,6 +89,8 @@ use impls::Author;
 
 /// Constant values used within the runtime.
 pub mod constants;
+/// Thresholds of the voter bags used by the staking election.
+pub mod voter_bags;
 use constants::{time::*, currency::*};
 use sp_runtime::generic::Era;
 
@@ -351,6 +353,7 @@ impl pallet_babe::Config for Runtime {
 	type HandleEquivocation =
 		pallet_babe::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
 
+	type DisabledValidators = Session;
 	type WeightInfo = ();
 }
 
@@ -375,6 +378,8 @@ parameter_types! {
 
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = MaxLocks;
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = Balance;
 	type DustRemoval = ();
 	type Event = Event;
@@ -496,7 +501,7 @@ impl pallet_staking::Config for Runtime {
 		pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>
 	>;
 	type SessionInterface = Self;
-	type RewardCurve = RewardCurve;
+	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type NextNewSession = Session;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type ElectionLookahead = ElectionLookahead;
@@ -508,9 +513,21 @@ impl pallet_staking::Config for Runtime {
 	// a single extrinsic.
 	type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
 	type ElectionProvider = ElectionProviderMultiPhase;
+	type SortedListProvider = BagsList;
 	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const BagThresholds: &'static [u64] = &voter_bags::THRESHOLDS;
+}
+
+impl pallet_bags_list::Config for Runtime {
+	type Event = Event;
+	type VoteWeightProvider = Staking;
+	type WeightInfo = pallet_bags_list::weights::SubstrateWeight<Runtime>;
+	type BagThresholds = BagThresholds;
+}
+
 parameter_types! {
 	// phase durations. 1/4 of the last session for each.
 	pub const SignedPhase: u32 = EPOCH_DURATION_IN_BLOCKS / 4;
@@ -529,6 +546,13 @@ parameter_types! {
 		.get(DispatchClass::Normal)
 		.max_extrinsic.expect("Normal extrinsics have a weight limit configured; qed")
 		.saturating_sub(BlockExecutionWeight::get());
+	// Solution can occupy 90% of normal block size
+	pub MinerMaxLength: u32 = Perbill::from_rational_approximation(9u32, 10) *
+		*RuntimeBlockLength::get()
+		.max
+		.get(DispatchClass::Normal);
+	// Only the nominators with the highest vote weight make it into the snapshot.
+	pub const VoterSnapshotPerBlock: u32 = 10_000;
 }
 
 impl pallet_election_provider_multi_phase::Config for Runtime {
@@ -539,10 +563,16 @@ impl pallet_election_provider_multi_phase::Config for Runtime {
 	type SolutionImprovementThreshold = MinSolutionScoreBump;
 	type MinerMaxIterations = MinerMaxIterations;
 	type MinerMaxWeight = MinerMaxWeight;
+	type MinerMaxLength = MinerMaxLength;
 	type MinerTxPriority = MultiPhaseUnsignedPriority;
 	type DataProvider = Staking;
+	type VoterSnapshotPerBlock = VoterSnapshotPerBlock;
 	type OnChainAccuracy = Perbill;
 	type CompactSolution = pallet_staking::CompactAssignments;
+	type Solver = sp_npos_elections::SequentialPhragmen<
+		AccountId,
+		pallet_election_provider_multi_phase::CompactAccuracyOf<Self>,
+	>;
 	type Fallback = Fallback;
 	type WeightInfo = pallet_election_provider_multi_phase::weights::SubstrateWeight<Runtime>;
 	type BenchmarkingConfig = ();
@@ -805,6 +835,8 @@ impl pallet_contracts::Config for Runtime {
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type MaxCodeSize = MaxCodeSize;
+	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+	type InstantiateFilter = ();
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -1123,6 +1155,7 @@ construct_runtime!(
 		Mmr: pallet_mmr::{Module, Storage},
 		Lottery: pallet_lottery::{Module, Call, Storage, Event<T>},
 		Gilt: pallet_gilt::{Module, Call, Storage, Event<T>, Config},
+		BagsList: pallet_bags_list::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -1164,6 +1197,11 @@ pub type Executive = frame_executive::Executive<
 	Runtime,
 	AllModules,
 	(),
+	(
+		pallet_contracts::migration::v4::Migration<Runtime>,
+		pallet_contracts::migration::v6::Migration<Runtime>,
+		pallet_contracts::migration::v7::Migration<Runtime>,
+	),
 >;
 
 /// MMR helper types.
@@ -1199,6 +1237,14 @@ impl_runtime_apis! {
 		fn metadata() -> OpaqueMetadata {
 			Runtime::metadata().into()
 		}
+
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+			Runtime::metadata_at_version(version).map(Into::into)
+		}
+
+		fn metadata_versions() -> Vec<u32> {
+			Runtime::metadata_versions()
+		}
 	}
 
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
@@ -1321,6 +1367,10 @@ impl_runtime_apis! {
 				key_owner_proof,
 			)
 		}
+
+		fn disabled_validators() -> Vec<sp_consensus_babe::AuthorityIndex> {
+			Session::disabled_validators()
+		}
 	}
 
 	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
@@ -1335,9 +1385,13 @@ impl_runtime_apis! {
 		}
 	}
 
-	impl pallet_contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
-		for Runtime
-	{
+	impl pallet_contracts_rpc_runtime_api::ContractsApi<
+		Block,
+		AccountId,
+		Balance,
+		BlockNumber,
+		Hash,
+	> for Runtime {
 		fn call(
 			origin: AccountId,
 			dest: AccountId,
@@ -1348,6 +1402,17 @@ impl_runtime_apis! {
 			Contracts::bare_call(origin, dest, value, gas_limit, input_data)
 		}
 
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: pallet_contracts_primitives::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId> {
+			Contracts::bare_instantiate(origin, endowment, gas_limit, code, data, salt)
+		}
+
 		fn get_storage(
 			address: AccountId,
 			key: [u8; 32],
@@ -1462,6 +1527,7 @@ impl_runtime_apis! {
 
 			add_benchmark!(params, batches, pallet_assets, Assets);
 			add_benchmark!(params, batches, pallet_babe, Babe);
+			add_benchmark!(params, batches, pallet_bags_list, BagsList);
 			add_benchmark!(params, batches, pallet_balances, Balances);
 			add_benchmark!(params, batches, pallet_bounties, Bounties);
 			add_benchmark!(params, batches, pallet_collective, Council);
@@ -26,7 +26,7 @@ use sp_runtime::{generic::BlockId, Justification, Storage};
 use sp_runtime::traits::{Block as BlockT, NumberFor, HashFor};
 use sp_state_machine::{
 	ChangesTrieState, ChangesTrieStorage as StateChangesTrieStorage, ChangesTrieTransaction,
-	StorageCollection, ChildStorageCollection, OffchainChangesCollection,
+	StorageCollection, ChildStorageCollection, OffchainChangesCollection, IndexOperation,
 };
 use sp_storage::{StorageData, StorageKey, PrefixedStorageKey, ChildInfo};
 use crate::{
@@ -179,6 +179,14 @@ pub trait BlockImportOperation<Block: BlockT> {
 		 Ok(())
 	}
 
+	/// Add transaction index operations.
+	fn update_transaction_index(
+		&mut self,
+		_index: Vec<IndexOperation>,
+	) -> sp_blockchain::Result<()> {
+		Ok(())
+	}
+
 	/// Inject changes trie data into the database.
 	fn update_changes_trie(
 		&mut self,
@@ -487,6 +495,16 @@ pub trait Backend<Block: BlockT>: AuxStore + Send + Sync {
 	/// something that the import of a block would interfere with, e.g. importing
 	/// a new block or calculating the best head.
 	fn get_import_lock(&self) -> &RwLock<()>;
+
+	/// Keep the state and the body of the given block available, even if it is pruned in the
+	/// meantime, until the block is unpinned with [`Backend::unpin_block`].
+	///
+	/// Pins are reference counted, every successful call must be matched by a call to
+	/// `unpin_block`.
+	fn pin_block(&self, hash: &Block::Hash) -> sp_blockchain::Result<()>;
+
+	/// Release a pin of the given block taken with [`Backend::pin_block`].
+	fn unpin_block(&self, hash: &Block::Hash);
 }
 
 /// Changes trie storage that supports pruning.
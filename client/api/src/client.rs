@@ -106,6 +106,15 @@ pub trait BlockBackend<Block: BlockT> {
 	fn have_extrinsic(&self, hash: &Block::Hash) -> sp_blockchain::Result<bool> {
 		Ok(self.extrinsic(hash)?.is_some())
 	}
+
+	/// Get indexed transaction data by content hash.
+	fn indexed_transaction(&self, hash: &Block::Hash) -> sp_blockchain::Result<Option<Vec<u8>>>;
+
+	/// Get all data indexed by the given block, in the order it was indexed.
+	fn block_indexed_body(
+		&self,
+		id: &BlockId<Block>,
+	) -> sp_blockchain::Result<Option<Vec<Vec<u8>>>>;
 }
 
 /// Provide a list of potential uncle headers for a given block.
@@ -737,6 +737,13 @@ impl<Block: BlockT> backend::Backend<Block> for Backend<Block> where Block::Hash
 	fn get_import_lock(&self) -> &RwLock<()> {
 		&self.import_lock
 	}
+
+	fn pin_block(&self, _: &Block::Hash) -> sp_blockchain::Result<()> {
+		// Nothing is ever pruned.
+		Ok(())
+	}
+
+	fn unpin_block(&self, _: &Block::Hash) {}
 }
 
 impl<Block: BlockT> backend::LocalBackend<Block> for Backend<Block> where Block::Hash: Ord {}
@@ -70,4 +70,19 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&PrefixedStorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Given a `BlockId` iterate over all storage values starting at `start_key` exclusively,
+	/// and collect them until the combined size of keys and values exceeds `size_limit`.
+	///
+	/// The child trie given by `child_info` is iterated, or the top trie if it is `None`.
+	/// Child tries are not entered while iterating the top trie, their roots are returned as
+	/// regular entries. Returns the collected key-value pairs and a flag that is `true` if
+	/// there are no more values after the last returned one.
+	fn storage_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
 }
@@ -85,7 +85,8 @@ pub enum Role {
 ///
 ///    4. Put addresses and signature as a record with the authority id as a key on a Kademlia DHT.
 ///
-/// When constructed with either [`Role::PublishAndDiscover`] or [`Role::Publish`] a [`Worker`] will
+/// When constructed with either [`Role::PublishAndDiscover`] or [`Role::Discover`] a [`Worker`]
+/// will
 ///
 ///    1. Retrieve the current and next set of authorities.
 ///
@@ -93,10 +94,10 @@ pub enum Role {
 ///
 ///    3. Validate the signatures of the retrieved key value pairs.
 ///
-///    4. Add the retrieved external addresses as priority nodes to the
-///    network peerset.
+///    4. Cache the retrieved external addresses of each authority.
 ///
-///    5. Allow querying of the collected addresses via the [`crate::Service`].
+///    5. Allow querying of the collected addresses via the [`crate::Service`], e.g. for a
+///    network protocol to preferentially connect to other authorities.
 pub struct Worker<Client, Network, Block, DhtEventStream> {
 	/// Channel receiver for messages send by a [`crate::Service`].
 	from_service: Fuse<mpsc::Receiver<ServicetoWorkerMsg>>,
@@ -149,8 +150,7 @@ where
 		// example due to being not yet fully bootstrapped on the DHT. Thus one should retry rather
 		// sooner than later. On the other hand, a long running node is likely well connected and
 		// thus timely retries are not needed. For this reasoning use an exponentially increasing
-		// interval for `publish_interval`, `query_interval` and `priority_group_set_interval`
-		// instead of a constant interval.
+		// interval for `publish_interval` and `query_interval` instead of a constant interval.
 		let publish_interval = ExpIncInterval::new(
 			Duration::from_secs(2),
 			config.max_publish_interval,
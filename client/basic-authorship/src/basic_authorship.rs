@@ -30,6 +30,7 @@ use log::{error, info, debug, trace, warn};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Hash as HashT, Header as HeaderT, DigestFor, BlakeTwo256},
+	Percent, SaturatedConversion,
 };
 use sp_transaction_pool::{TransactionPool, InPoolTransaction};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_INFO};
@@ -40,7 +41,9 @@ use sp_blockchain::{HeaderBackend, ApplyExtrinsicFailed::Validity, Error::ApplyE
 use std::marker::PhantomData;
 
 use prometheus_endpoint::Registry as PrometheusRegistry;
-use sc_proposer_metrics::MetricsLink as PrometheusMetrics;
+use sc_proposer_metrics::{
+	EndProposingReason, MetricsLink as PrometheusMetrics, SkippedTransactionReason,
+};
 
 /// Default maximum block size in bytes used by [`Proposer`].
 ///
@@ -51,6 +54,17 @@ use sc_proposer_metrics::MetricsLink as PrometheusMetrics;
 /// transferred to other nodes.
 pub const DEFAULT_MAX_BLOCK_SIZE: usize = 4 * 1024 * 1024 + 512;
 
+/// Default value for the soft deadline used by [`Proposer`], as a fraction of the time left for
+/// pushing transactions.
+///
+/// Can be overwritten by [`ProposerFactory::set_soft_deadline`].
+pub const DEFAULT_SOFT_DEADLINE_PERCENT: Percent = Percent::from_percent(50);
+
+/// If the block is full we will attempt to push at most this number of transactions before
+/// quitting for real, unless the soft deadline has not been reached yet.
+/// It allows us to increase block utilization.
+pub const MAX_SKIPPED_TRANSACTIONS: usize = 8;
+
 /// Proposer factory.
 pub struct ProposerFactory<A, B, C, PR> {
 	spawn_handle: Box<dyn SpawnNamed>,
@@ -61,6 +75,8 @@ pub struct ProposerFactory<A, B, C, PR> {
 	/// Prometheus Link,
 	metrics: PrometheusMetrics,
 	max_block_size: usize,
+	/// The soft deadline, as a fraction of the time left for pushing transactions.
+	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
 	/// phantom member to pin the `Backend`/`ProofRecording` type.
 	_phantom: PhantomData<(B, PR)>,
@@ -82,6 +98,7 @@ impl<A, B, C> ProposerFactory<A, B, C, DisableProofRecording> {
 			transaction_pool,
 			metrics: PrometheusMetrics::new(prometheus),
 			max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			client,
 			_phantom: PhantomData,
@@ -106,6 +123,7 @@ impl<A, B, C> ProposerFactory<A, B, C, EnableProofRecording> {
 			transaction_pool,
 			metrics: PrometheusMetrics::new(prometheus),
 			max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+			soft_deadline_percent: DEFAULT_SOFT_DEADLINE_PERCENT,
 			telemetry,
 			_phantom: PhantomData,
 		}
@@ -120,6 +138,18 @@ impl<A, B, C, PR> ProposerFactory<A, B, C, PR> {
 	pub fn set_maximum_block_size(&mut self, size: usize) {
 		self.max_block_size = size;
 	}
+
+	/// Set the soft deadline percentage.
+	///
+	/// Once the block is full, the proposer keeps trying to push transactions from the pool until
+	/// this fraction of the time left for pushing transactions has elapsed. Setting it to zero
+	/// makes the proposer give up after [`MAX_SKIPPED_TRANSACTIONS`] transactions did not fit.
+	///
+	/// The default value for the soft deadline is:
+	/// [`DEFAULT_SOFT_DEADLINE_PERCENT`].
+	pub fn set_soft_deadline(&mut self, percent: Percent) {
+		self.soft_deadline_percent = percent;
+	}
 }
 
 impl<B, Block, C, A, PR> ProposerFactory<A, B, C, PR>
@@ -153,6 +183,7 @@ impl<B, Block, C, A, PR> ProposerFactory<A, B, C, PR>
 			now,
 			metrics: self.metrics.clone(),
 			max_block_size: self.max_block_size,
+			soft_deadline_percent: self.soft_deadline_percent,
 			telemetry: self.telemetry.clone(),
 			_phantom: PhantomData,
 		};
@@ -196,6 +227,7 @@ pub struct Proposer<B, Block: BlockT, C, A: TransactionPool, PR> {
 	now: Box<dyn Fn() -> time::Instant + Send + Sync>,
 	metrics: PrometheusMetrics,
 	max_block_size: usize,
+	soft_deadline_percent: Percent,
 	telemetry: Option<TelemetryHandle>,
 	_phantom: PhantomData<(B, PR)>,
 }
@@ -265,11 +297,6 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 		inherent_digests: DigestFor<Block>,
 		deadline: time::Instant,
 	) -> Result<Proposal<Block, backend::TransactionFor<B, Block>, PR::Proof>, sp_blockchain::Error> {
-		/// If the block is full we will attempt to push at most
-		/// this number of transactions before quitting for real.
-		/// It allows us to increase block utilization.
-		const MAX_SKIPPED_TRANSACTIONS: usize = 8;
-
 		let mut block_builder = self.client.new_block_at(
 			&self.parent_id,
 			inherent_digests,
@@ -297,7 +324,12 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 		let mut unqueue_invalid = Vec::new();
 
 		let mut t1 = self.transaction_pool.ready_at(self.parent_number).fuse();
-		let mut t2 = futures_timer::Delay::new(deadline.saturating_duration_since((self.now)()) / 8).fuse();
+		let now = (self.now)();
+		let left = deadline.saturating_duration_since(now);
+		let left_micros: u64 = left.as_micros().saturated_into();
+		let soft_deadline = now
+			+ time::Duration::from_micros(self.soft_deadline_percent.mul_floor(left_micros));
+		let mut t2 = futures_timer::Delay::new(left / 8).fuse();
 
 		let pending_iterator = select! {
 			res = t1 => res,
@@ -313,12 +345,15 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 
 		debug!("Attempting to push transactions from the pool.");
 		debug!("Pool status: {:?}", self.transaction_pool.status());
+		let mut end_reason = EndProposingReason::NoMoreTransactions;
 		for pending_tx in pending_iterator {
-			if (self.now)() > deadline {
+			let now = (self.now)();
+			if now > deadline {
 				debug!(
 					"Consensus deadline reached when pushing block transactions, \
 					proceeding with proposing."
 				);
+				end_reason = EndProposingReason::HitDeadline;
 				break;
 			}
 
@@ -331,18 +366,30 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 				}
 				Err(ApplyExtrinsicFailed(Validity(e)))
 						if e.exhausted_resources() => {
+					self.metrics.report(|metrics| {
+						metrics.report_skipped_transaction(SkippedTransactionReason::ExhaustedResources)
+					});
 					if skipped < MAX_SKIPPED_TRANSACTIONS {
 						skipped += 1;
 						debug!(
 							"Block seems full, but will try {} more transactions before quitting.",
 							MAX_SKIPPED_TRANSACTIONS - skipped,
 						);
+					} else if now < soft_deadline {
+						debug!(
+							"Block seems full, but we still have time before the soft deadline, \
+							so we will try a bit more before quitting."
+						);
 					} else {
 						debug!("Block is full, proceed with proposing.");
+						end_reason = EndProposingReason::HitBlockLimit;
 						break;
 					}
 				}
 				Err(e) if skipped > 0 => {
+					self.metrics.report(|metrics| {
+						metrics.report_skipped_transaction(SkippedTransactionReason::Invalid)
+					});
 					trace!(
 						"[{:?}] Ignoring invalid transaction when skipping: {}",
 						pending_tx_hash,
@@ -350,6 +397,9 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 					);
 				}
 				Err(e) => {
+					self.metrics.report(|metrics| {
+						metrics.report_skipped_transaction(SkippedTransactionReason::Invalid)
+					});
 					debug!("[{:?}] Invalid transaction: {}", pending_tx_hash, e);
 					unqueue_invalid.push(pending_tx_hash);
 				}
@@ -364,6 +414,7 @@ impl<A, B, Block, C, PR> Proposer<B, Block, C, A, PR>
 			|metrics| {
 				metrics.number_of_transactions.set(block.extrinsics().len() as u64);
 				metrics.block_constructed.observe(block_timer.elapsed().as_secs_f64());
+				metrics.report_end_proposing_reason(end_reason);
 			}
 		);
 
@@ -612,6 +663,80 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_keep_adding_transactions_after_exhausting_resources_before_soft_deadline() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		);
+
+		// One transaction that fits, more resource exhausting ones than we are allowed to skip and
+		// finally a transaction with the lowest priority that fits again.
+		let alice_transfer = |nonce| Transfer {
+			amount: 1,
+			nonce,
+			from: AccountKeyring::Alice.into(),
+			to: Default::default(),
+		};
+		let extrinsics = std::iter::once(alice_transfer(0).into_signed_tx())
+			.chain(
+				(1..=MAX_SKIPPED_TRANSACTIONS as u64 + 1)
+					.map(|nonce| alice_transfer(nonce).into_resources_exhausting_tx())
+			)
+			.chain(std::iter::once(Transfer {
+				amount: 0,
+				nonce: 0,
+				from: AccountKeyring::Bob.into(),
+				to: Default::default(),
+			}.into_signed_tx()))
+			.collect();
+
+		futures::executor::block_on(
+			txpool.submit_at(&BlockId::number(0), SOURCE, extrinsics)
+		).unwrap();
+
+		futures::executor::block_on(
+			txpool.maintain(chain_event(
+				client.header(&BlockId::Number(0u64))
+					.expect("header get error")
+					.expect("there should be header")
+			))
+		);
+
+		let mut proposer_factory = ProposerFactory::new(
+			spawner.clone(),
+			client.clone(),
+			txpool.clone(),
+			None,
+			None,
+		);
+		let mut propose_block = |soft_deadline| {
+			proposer_factory.set_soft_deadline(soft_deadline);
+			let proposer = proposer_factory.init_with_now(
+				&client.header(&BlockId::number(0)).unwrap().unwrap(),
+				Box::new(move || time::Instant::now()),
+			);
+
+			let deadline = time::Duration::from_secs(600);
+			futures::executor::block_on(
+				proposer.propose(Default::default(), Default::default(), deadline)
+			).map(|r| r.block).unwrap()
+		};
+
+		// the soft deadline is already reached, so we give up after skipping enough transactions.
+		let block = propose_block(Percent::zero());
+		assert_eq!(block.extrinsics().len(), 1);
+
+		// there is still time before the soft deadline, so the last transaction is included.
+		let block = propose_block(Percent::from_percent(100));
+		assert_eq!(block.extrinsics().len(), 2);
+	}
+
 	#[test]
 	fn should_not_remove_invalid_transactions_when_skipping() {
 		// given
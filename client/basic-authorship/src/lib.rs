@@ -72,4 +72,7 @@
 
 mod basic_authorship;
 
-pub use crate::basic_authorship::{ProposerFactory, Proposer, DEFAULT_MAX_BLOCK_SIZE};
+pub use crate::basic_authorship::{
+	ProposerFactory, Proposer, DEFAULT_MAX_BLOCK_SIZE, DEFAULT_SOFT_DEADLINE_PERCENT,
+	MAX_SKIPPED_TRANSACTIONS,
+};
@@ -0,0 +1,34 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! BEEFY gadget specific errors
+//!
+//! Used for BEEFY gadget internal error handling only
+
+/// Errors that can occur in the BEEFY gadget.
+#[derive(Debug, derive_more::Display)]
+pub enum Error {
+	/// Error while accessing the keystore.
+	#[display(fmt = "Keystore error: {}", _0)]
+	Keystore(String),
+	/// The produced signature is invalid.
+	#[display(fmt = "Signature error: {}", _0)]
+	Signature(String),
+}
+
+impl std::error::Error for Error {}
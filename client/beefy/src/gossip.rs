@@ -0,0 +1,415 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use codec::{Decode, Encode};
+use log::{debug, trace};
+use parking_lot::RwLock;
+
+use sc_network::{PeerId, ReputationChange};
+use sc_network_gossip::{MessageIntent, ValidationResult, Validator, ValidatorContext};
+use sp_runtime::traits::{Block, Hash, Header, NumberFor, Zero};
+use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+
+use sp_beefy::{
+	crypto::{AuthorityId as Public, AuthoritySignature as Signature},
+	MmrRootHash, ValidatorSet, VoteMessage,
+};
+
+use crate::keystore::BeefyKeystore;
+
+// Reputation changes of peers sending BEEFY votes.
+mod cost {
+	use sc_network::ReputationChange as Rep;
+	pub(super) const UNDECODABLE_MESSAGE: Rep = Rep::new(-200, "BEEFY: Undecodable message");
+	pub(super) const UNKNOWN_VOTER: Rep = Rep::new(-150, "BEEFY: Unknown voter");
+	pub(super) const BAD_SIGNATURE: Rep = Rep::new(-100, "BEEFY: Bad signature");
+	pub(super) const OUTDATED_MESSAGE: Rep = Rep::new(-50, "BEEFY: Outdated message");
+	pub(super) const FUTURE_MESSAGE: Rep = Rep::new(-10, "BEEFY: Future message");
+}
+
+mod benefit {
+	use sc_network::ReputationChange as Rep;
+	pub(super) const VOTE_MESSAGE: Rep = Rep::new(100, "BEEFY: Round vote message");
+}
+
+/// A reputation change of a peer, reported by the gossip validator.
+pub(crate) struct PeerReport {
+	pub who: PeerId,
+	pub cost_benefit: ReputationChange,
+}
+
+/// A vote message as gossiped between BEEFY voters.
+pub(crate) type BeefyVoteMessage<B> = VoteMessage<MmrRootHash, NumberFor<B>, Public, Signature>;
+
+/// Gossip engine messages topic
+pub(crate) fn topic<B>() -> B::Hash
+where
+	B: Block,
+{
+	<<B::Header as Header>::Hashing as Hash>::hash(b"beefy")
+}
+
+/// BEEFY gossip validator
+///
+/// Validate BEEFY gossip messages and limit the number of live BEEFY voting rounds.
+///
+/// Allows messages for rounds that have not been concluded yet, signed by a member of the active
+/// validator set. Votes for rounds at or below the best concluded round are considered expired
+/// and are no longer propagated. Votes for blocks above the best GRANDPA finalized block are
+/// rejected, which bounds the number of rounds a voter has to keep track of.
+///
+/// All messaging is handled in a single BEEFY global topic.
+pub(crate) struct GossipValidator<B>
+where
+	B: Block,
+{
+	topic: B::Hash,
+	best_concluded: RwLock<Option<NumberFor<B>>>,
+	best_grandpa: RwLock<NumberFor<B>>,
+	validator_set: RwLock<ValidatorSet<Public>>,
+	report_sender: TracingUnboundedSender<PeerReport>,
+}
+
+impl<B> GossipValidator<B>
+where
+	B: Block,
+{
+	/// Create a new gossip validator along with the stream of peer reputation changes that
+	/// have to be reported to the gossip engine.
+	pub fn new() -> (GossipValidator<B>, TracingUnboundedReceiver<PeerReport>) {
+		let (tx, rx) = tracing_unbounded("mpsc_beefy_gossip_validator");
+		let validator = GossipValidator {
+			topic: topic::<B>(),
+			best_concluded: RwLock::new(None),
+			best_grandpa: RwLock::new(Zero::zero()),
+			validator_set: RwLock::new(ValidatorSet::empty()),
+			report_sender: tx,
+		};
+
+		(validator, rx)
+	}
+
+	/// Note the active validator set.
+	///
+	/// Only votes of its members for its id are accepted from now on.
+	pub fn note_validator_set(&self, validator_set: ValidatorSet<Public>) {
+		*self.validator_set.write() = validator_set;
+	}
+
+	/// Note a concluded voting round.
+	///
+	/// Votes for this round and all rounds before it will be considered expired.
+	pub fn conclude_round(&self, round: NumberFor<B>) {
+		let mut best_concluded = self.best_concluded.write();
+
+		if best_concluded.map_or(true, |best| round > best) {
+			*best_concluded = Some(round);
+		}
+	}
+
+	/// Note the best GRANDPA finalized block.
+	///
+	/// Votes for rounds above it are rejected.
+	pub fn note_best_grandpa(&self, number: NumberFor<B>) {
+		*self.best_grandpa.write() = number;
+	}
+
+	fn is_expired(&self, round: NumberFor<B>) -> bool {
+		self.best_concluded.read().map_or(false, |best| round <= best)
+	}
+
+	fn report(&self, who: PeerId, cost_benefit: ReputationChange) {
+		let _ = self.report_sender.unbounded_send(PeerReport { who, cost_benefit });
+	}
+
+	/// Validate a vote and return the reputation change of its sender.
+	fn validate_vote(&self, msg: &BeefyVoteMessage<B>) -> Result<(), ReputationChange> {
+		if self.is_expired(msg.commitment.block_number) {
+			return Err(cost::OUTDATED_MESSAGE);
+		}
+		if msg.commitment.block_number > *self.best_grandpa.read() {
+			return Err(cost::FUTURE_MESSAGE);
+		}
+
+		{
+			let validator_set = self.validator_set.read();
+			if msg.commitment.validator_set_id != validator_set.id {
+				return Err(cost::OUTDATED_MESSAGE);
+			}
+			if !validator_set.validators.contains(&msg.id) {
+				return Err(cost::UNKNOWN_VOTER);
+			}
+		}
+
+		if !BeefyKeystore::verify(&msg.id, &msg.signature, &msg.commitment.encode()) {
+			return Err(cost::BAD_SIGNATURE);
+		}
+
+		Ok(())
+	}
+}
+
+impl<B> Validator<B> for GossipValidator<B>
+where
+	B: Block,
+{
+	fn validate(
+		&self,
+		_context: &mut dyn ValidatorContext<B>,
+		sender: &PeerId,
+		mut data: &[u8],
+	) -> ValidationResult<B::Hash> {
+		let msg = match BeefyVoteMessage::<B>::decode(&mut data) {
+			Ok(msg) => msg,
+			Err(_) => {
+				self.report(sender.clone(), cost::UNDECODABLE_MESSAGE);
+				return ValidationResult::Discard;
+			},
+		};
+
+		// Nothing can be validated before the active validator set is known, which is no
+		// fault of the sender.
+		if self.validator_set.read().validators.is_empty() {
+			trace!(target: "beefy", "🥩 Discarding message before the validator set is known");
+			return ValidationResult::Discard;
+		}
+
+		match self.validate_vote(&msg) {
+			Ok(()) => {
+				self.report(sender.clone(), benefit::VOTE_MESSAGE);
+				ValidationResult::ProcessAndKeep(self.topic)
+			},
+			Err(cost) => {
+				debug!(
+					target: "beefy",
+					"🥩 Discarding message: {:?}, from: {:?}, reason: {}",
+					msg,
+					sender,
+					cost.reason,
+				);
+				self.report(sender.clone(), cost);
+				ValidationResult::Discard
+			},
+		}
+	}
+
+	fn message_expired<'a>(&'a self) -> Box<dyn FnMut(B::Hash, &[u8]) -> bool + 'a> {
+		Box::new(move |_topic, mut data| {
+			let msg = match BeefyVoteMessage::<B>::decode(&mut data) {
+				Ok(vote) => vote,
+				Err(_) => return true,
+			};
+
+			let expired = self.is_expired(msg.commitment.block_number);
+
+			trace!(
+				target: "beefy",
+				"🥩 Message for round #{} expired: {}",
+				msg.commitment.block_number,
+				expired,
+			);
+
+			expired
+		})
+	}
+
+	fn message_allowed<'a>(
+		&'a self,
+	) -> Box<dyn FnMut(&PeerId, MessageIntent, &B::Hash, &[u8]) -> bool + 'a> {
+		Box::new(move |_who, _intent, _topic, mut data| {
+			let msg = match BeefyVoteMessage::<B>::decode(&mut data) {
+				Ok(vote) => vote,
+				Err(_) => return false,
+			};
+
+			let allowed = !self.is_expired(msg.commitment.block_number);
+
+			trace!(
+				target: "beefy",
+				"🥩 Message for round #{} allowed: {}",
+				msg.commitment.block_number,
+				allowed,
+			);
+
+			allowed
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use sc_network_gossip::Validator as _;
+	use sp_application_crypto::Pair as _;
+	use sp_beefy::{crypto::Pair, Commitment};
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper};
+
+	use super::*;
+
+	type TestBlock = RawBlock<ExtrinsicWrapper<u64>>;
+
+	struct TestContext;
+	impl<B: Block> ValidatorContext<B> for TestContext {
+		fn broadcast_topic(&mut self, _topic: B::Hash, _force: bool) {}
+		fn broadcast_message(&mut self, _topic: B::Hash, _message: Vec<u8>, _force: bool) {}
+		fn send_message(&mut self, _who: &PeerId, _message: Vec<u8>) {}
+		fn send_topic(&mut self, _who: &PeerId, _topic: B::Hash, _force: bool) {}
+	}
+
+	fn pair(seed: &str) -> Pair {
+		Pair::from_string(seed, None).unwrap()
+	}
+
+	fn vote(pair: &Pair, block_number: u64, validator_set_id: u64) -> Vec<u8> {
+		let commitment = Commitment {
+			payload: MmrRootHash::default(),
+			block_number,
+			validator_set_id,
+		};
+		let signature = pair.sign(&commitment.encode());
+		BeefyVoteMessage::<TestBlock> { commitment, id: pair.public(), signature }.encode()
+	}
+
+	/// A validator for the validator set with id 1 consisting of Alice and Bob, with block 10
+	/// finalized.
+	fn validator() -> (GossipValidator<TestBlock>, TracingUnboundedReceiver<PeerReport>) {
+		let (validator, reports) = GossipValidator::<TestBlock>::new();
+		validator.note_best_grandpa(10);
+		validator.note_validator_set(ValidatorSet {
+			validators: vec![pair("//Alice").public(), pair("//Bob").public()],
+			id: 1,
+		});
+		(validator, reports)
+	}
+
+	/// Validate `data` and return whether it was kept along with the reported reputation change.
+	fn validate(
+		validator: &GossipValidator<TestBlock>,
+		reports: &mut TracingUnboundedReceiver<PeerReport>,
+		data: &[u8],
+	) -> (bool, Option<ReputationChange>) {
+		let sender = PeerId::random();
+		let kept = match validator.validate(&mut TestContext, &sender, data) {
+			ValidationResult::ProcessAndKeep(_) => true,
+			_ => false,
+		};
+		let report = reports.try_next().ok().flatten().map(|report| {
+			assert_eq!(report.who, sender);
+			report.cost_benefit
+		});
+		(kept, report)
+	}
+
+	#[test]
+	fn keeps_votes_of_members() {
+		let (validator, mut reports) = validator();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 5, 1)),
+			(true, Some(benefit::VOTE_MESSAGE)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_of_non_members() {
+		let (validator, mut reports) = validator();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Charlie"), 5, 1)),
+			(false, Some(cost::UNKNOWN_VOTER)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_for_concluded_rounds() {
+		let (validator, mut reports) = validator();
+		validator.conclude_round(5);
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 5, 1)),
+			(false, Some(cost::OUTDATED_MESSAGE)),
+		);
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 6, 1)),
+			(true, Some(benefit::VOTE_MESSAGE)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_for_blocks_above_best_grandpa() {
+		let (validator, mut reports) = validator();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 11, 1)),
+			(false, Some(cost::FUTURE_MESSAGE)),
+		);
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 10, 1)),
+			(true, Some(benefit::VOTE_MESSAGE)),
+		);
+
+		validator.note_best_grandpa(11);
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 11, 1)),
+			(true, Some(benefit::VOTE_MESSAGE)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_of_other_validator_sets() {
+		let (validator, mut reports) = validator();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 5, 0)),
+			(false, Some(cost::OUTDATED_MESSAGE)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_with_bad_signature() {
+		let (validator, mut reports) = validator();
+		let mut msg = BeefyVoteMessage::<TestBlock>::decode(
+			&mut &vote(&pair("//Alice"), 5, 1)[..],
+		).unwrap();
+		msg.id = pair("//Bob").public();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &msg.encode()),
+			(false, Some(cost::BAD_SIGNATURE)),
+		);
+	}
+
+	#[test]
+	fn discards_undecodable_messages() {
+		let (validator, mut reports) = validator();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &[1, 2, 3]),
+			(false, Some(cost::UNDECODABLE_MESSAGE)),
+		);
+	}
+
+	#[test]
+	fn discards_votes_without_penalty_before_validator_set_is_known() {
+		let (validator, mut reports) = GossipValidator::<TestBlock>::new();
+
+		assert_eq!(
+			validate(&validator, &mut reports, &vote(&pair("//Alice"), 5, 0)),
+			(false, None),
+		);
+	}
+}
@@ -0,0 +1,140 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::convert::TryInto;
+
+use sp_application_crypto::{Pair as _, Public as _};
+use sp_core::crypto::CryptoTypePublicPair;
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+
+use sp_beefy::{
+	crypto::{AuthorityId as Public, AuthoritySignature as Signature, Pair},
+	KEY_TYPE,
+};
+
+use crate::error::Error;
+
+/// A BEEFY specific keystore implemented as a `Newtype`. This is basically a
+/// wrapper around [`sp_keystore::SyncCryptoStore`] and allows to customize
+/// common cryptographic functionality.
+pub(crate) struct BeefyKeystore(Option<SyncCryptoStorePtr>);
+
+impl BeefyKeystore {
+	/// Check if the keystore contains a private key for one of the public keys
+	/// contained in `keys`. A public key with a matching private key is known
+	/// as a local authority id.
+	///
+	/// Return the public key for which we also do have a private key. If no
+	/// matching private key is found, `None` will be returned.
+	pub fn authority_id(&self, keys: &[Public]) -> Option<Public> {
+		let store = self.0.as_ref()?;
+
+		keys.iter()
+			.find(|key| SyncCryptoStore::has_keys(&**store, &[(key.to_raw_vec(), KEY_TYPE)]))
+			.cloned()
+	}
+
+	/// Sign `message` with the `public` key.
+	///
+	/// Note that `message` usually will be pre-hashed before being signed.
+	///
+	/// Return the message signature or an error in case of failure.
+	pub fn sign(&self, public: &Public, message: &[u8]) -> Result<Signature, Error> {
+		let store = self.0.clone().ok_or_else(|| Error::Keystore("no Keystore".into()))?;
+
+		let public: CryptoTypePublicPair = public.to_public_crypto_pair();
+
+		let sig = SyncCryptoStore::sign_with(&*store, KEY_TYPE, &public, message)
+			.map_err(|e| Error::Keystore(e.to_string()))?
+			.ok_or_else(|| Error::Signature("key not found in keystore".into()))?;
+
+		sig.clone().try_into().map_err(|_| {
+			Error::Signature(format!("invalid signature {:?} for key {:?}", sig, public))
+		})
+	}
+
+	/// Use the `public` key to verify that `sig` is a valid signature for `message`.
+	///
+	/// Return `true` if the signature is authentic, `false` otherwise.
+	pub fn verify(public: &Public, sig: &Signature, message: &[u8]) -> bool {
+		Pair::verify(sig, message, public)
+	}
+}
+
+impl From<Option<SyncCryptoStorePtr>> for BeefyKeystore {
+	fn from(store: Option<SyncCryptoStorePtr>) -> BeefyKeystore {
+		BeefyKeystore(store)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use sc_keystore::LocalKeystore;
+	use sp_core::{ecdsa, Pair as _};
+
+	use super::*;
+
+	fn keystore() -> (SyncCryptoStorePtr, tempfile::TempDir) {
+		let dir = tempfile::tempdir().unwrap();
+		let store: SyncCryptoStorePtr = Arc::new(LocalKeystore::open(dir.path(), None).unwrap());
+		(store, dir)
+	}
+
+	#[test]
+	fn authority_id_works() {
+		let (store, _dir) = keystore();
+
+		let alice: Public = SyncCryptoStore::ecdsa_generate_new(&*store, KEY_TYPE, Some("//Alice"))
+			.unwrap()
+			.into();
+		let bob: Public = ecdsa::Pair::from_string("//Bob", None).unwrap().public().into();
+
+		let store: BeefyKeystore = Some(store).into();
+
+		assert_eq!(store.authority_id(&[bob.clone(), alice.clone()]), Some(alice));
+		assert_eq!(store.authority_id(&[bob]), None);
+	}
+
+	#[test]
+	fn sign_and_verify_works() {
+		let (store, _dir) = keystore();
+
+		let alice: Public = SyncCryptoStore::ecdsa_generate_new(&*store, KEY_TYPE, Some("//Alice"))
+			.unwrap()
+			.into();
+
+		let store: BeefyKeystore = Some(store).into();
+
+		let msg = b"are you involved or commited?";
+		let sig = store.sign(&alice, msg).unwrap();
+
+		assert!(BeefyKeystore::verify(&alice, &sig, msg));
+		assert!(!BeefyKeystore::verify(&alice, &sig, b"a different message"));
+	}
+
+	#[test]
+	fn sign_without_keystore_fails() {
+		let store: BeefyKeystore = None.into();
+		let alice: Public = ecdsa::Pair::from_string("//Alice", None).unwrap().public().into();
+
+		assert!(store.sign(&alice, b"message").is_err());
+		assert_eq!(store.authority_id(&[alice]), None);
+	}
+}
@@ -0,0 +1,191 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use sp_beefy::{
+	crypto::{AuthorityId as Public, AuthoritySignature as Signature},
+	ValidatorSet, ValidatorSetId,
+};
+
+/// Votes collected for a single round.
+#[derive(Default)]
+struct RoundTracker {
+	votes: Vec<(Public, Signature)>,
+}
+
+impl RoundTracker {
+	fn add_vote(&mut self, vote: (Public, Signature)) -> bool {
+		// this needs to handle equivocations in the future
+		if self.votes.iter().any(|(id, _)| *id == vote.0) {
+			return false;
+		}
+
+		self.votes.push(vote);
+		true
+	}
+
+	fn is_done(&self, threshold: usize) -> bool {
+		self.votes.len() >= threshold
+	}
+}
+
+/// Keeps track of all voting rounds of a single validator set.
+///
+/// A round is identified by the voted on payload and the block number.
+pub(crate) struct Rounds<Payload, Number> {
+	rounds: BTreeMap<(Payload, Number), RoundTracker>,
+	validator_set: ValidatorSet<Public>,
+}
+
+impl<P, N> Rounds<P, N>
+where
+	P: Ord,
+	N: Ord + Copy,
+{
+	pub fn new(validator_set: ValidatorSet<Public>) -> Self {
+		Rounds {
+			rounds: BTreeMap::new(),
+			validator_set,
+		}
+	}
+
+	pub fn validator_set_id(&self) -> ValidatorSetId {
+		self.validator_set.id
+	}
+
+	pub fn validators(&self) -> &[Public] {
+		&self.validator_set.validators
+	}
+
+	/// Add a vote to `round`.
+	///
+	/// Votes of unknown validators and duplicate votes are ignored. Returns `true` if the
+	/// vote has been added.
+	pub fn add_vote(&mut self, round: (P, N), vote: (Public, Signature)) -> bool {
+		if self.validator_set.validators.iter().any(|id| vote.0 == *id) {
+			self.rounds.entry(round).or_default().add_vote(vote)
+		} else {
+			false
+		}
+	}
+
+	/// Return `true` if `round` has collected enough votes to be concluded.
+	pub fn is_done(&self, round: &(P, N)) -> bool {
+		self.rounds
+			.get(round)
+			.map_or(false, |tracker| tracker.is_done(self.validator_set.threshold()))
+	}
+
+	/// Conclude `round` and drop all rounds for earlier blocks.
+	///
+	/// Returns the signatures collected for `round`, ordered as the validator set. Validators
+	/// which did not vote have a `None` signature.
+	pub fn conclude(&mut self, round: &(P, N)) -> Option<Vec<Option<Signature>>> {
+		let signatures = self.rounds.remove(round)?.votes;
+
+		let number = round.1;
+		self.rounds = std::mem::take(&mut self.rounds)
+			.into_iter()
+			.filter(|((_, n), _)| *n > number)
+			.collect();
+
+		Some(
+			self.validator_set
+				.validators
+				.iter()
+				.map(|authority_id| {
+					signatures
+						.iter()
+						.find(|(id, _)| id == authority_id)
+						.map(|(_, sig)| sig.clone())
+				})
+				.collect(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use sp_core::{ecdsa, Pair};
+
+	use super::*;
+
+	fn pair(seed: &str) -> ecdsa::Pair {
+		ecdsa::Pair::from_string(&format!("//{}", seed), None).unwrap()
+	}
+
+	fn vote(seed: &str, msg: &[u8]) -> (Public, Signature) {
+		let pair = pair(seed);
+		(pair.public().into(), pair.sign(msg).into())
+	}
+
+	fn rounds() -> Rounds<u8, u64> {
+		let validators = ["Alice", "Bob", "Charlie", "Dave"]
+			.iter()
+			.map(|seed| pair(seed).public().into())
+			.collect();
+
+		Rounds::new(ValidatorSet { validators, id: 42 })
+	}
+
+	#[test]
+	fn add_vote_ignores_unknown_and_duplicate_votes() {
+		let mut rounds = rounds();
+
+		assert!(rounds.add_vote((1, 10), vote("Alice", b"a")));
+		assert!(!rounds.add_vote((1, 10), vote("Alice", b"a")));
+		assert!(!rounds.add_vote((1, 10), vote("Eve", b"a")));
+		// the same validator may vote in a different round
+		assert!(rounds.add_vote((1, 11), vote("Alice", b"b")));
+	}
+
+	#[test]
+	fn round_is_done_at_threshold() {
+		let mut rounds = rounds();
+		let round = (1, 10);
+
+		assert!(rounds.add_vote(round, vote("Alice", b"a")));
+		assert!(rounds.add_vote(round, vote("Bob", b"a")));
+		assert!(!rounds.is_done(&round));
+
+		assert!(rounds.add_vote(round, vote("Dave", b"a")));
+		assert!(rounds.is_done(&round));
+	}
+
+	#[test]
+	fn conclude_returns_ordered_signatures_and_prunes_older_rounds() {
+		let mut rounds = rounds();
+		let round = (1, 10);
+
+		let alice = vote("Alice", b"a");
+		let dave = vote("Dave", b"a");
+		rounds.add_vote(round, dave.clone());
+		rounds.add_vote(round, alice.clone());
+		rounds.add_vote((2, 9), vote("Bob", b"b"));
+		rounds.add_vote((3, 11), vote("Bob", b"c"));
+
+		assert_eq!(
+			rounds.conclude(&round),
+			Some(vec![Some(alice.1), None, None, Some(dave.1)]),
+		);
+		assert_eq!(rounds.conclude(&round), None);
+		assert_eq!(rounds.conclude(&(2, 9)), None);
+		assert!(rounds.conclude(&(3, 11)).is_some());
+	}
+}
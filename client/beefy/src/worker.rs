@@ -0,0 +1,442 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+
+use codec::{Codec, Decode, Encode};
+use futures::{future, FutureExt, StreamExt};
+use log::{debug, error, info, trace, warn};
+use parking_lot::Mutex;
+
+use sc_client_api::{Backend, FinalityNotification, FinalityNotifications};
+use sc_network_gossip::GossipEngine;
+
+use sp_arithmetic::traits::AtLeast32Bit;
+use sp_utils::mpsc::TracingUnboundedReceiver;
+use sp_runtime::{
+	generic::{BlockId, OpaqueDigestItemId},
+	traits::{Block, Header, NumberFor},
+	SaturatedConversion,
+};
+
+use sp_beefy::{
+	crypto::{AuthorityId as Public, AuthoritySignature as Signature},
+	BeefyApi, Commitment, ConsensusLog, MmrRootHash, SignedCommitment, ValidatorSet,
+	VoteMessage, BEEFY_ENGINE_ID,
+};
+
+use crate::{
+	gossip::{topic, BeefyVoteMessage, GossipValidator, PeerReport},
+	keystore::BeefyKeystore,
+	notification, round, Client,
+};
+
+pub(crate) struct WorkerParams<B, BE, C>
+where
+	B: Block,
+{
+	pub client: Arc<C>,
+	pub backend: Arc<BE>,
+	pub key_store: BeefyKeystore,
+	pub signed_commitment_sender: notification::BeefySignedCommitmentSender<B>,
+	pub gossip_engine: GossipEngine<B>,
+	pub gossip_validator: Arc<GossipValidator<B>>,
+	pub gossip_report_stream: TracingUnboundedReceiver<PeerReport>,
+	pub min_block_delta: u32,
+}
+
+/// A BEEFY worker plays the BEEFY protocol
+pub(crate) struct BeefyWorker<B, C, BE>
+where
+	B: Block,
+	BE: Backend<B>,
+	C: Client<B, BE>,
+{
+	client: Arc<C>,
+	key_store: BeefyKeystore,
+	signed_commitment_sender: notification::BeefySignedCommitmentSender<B>,
+	gossip_engine: Arc<Mutex<GossipEngine<B>>>,
+	gossip_validator: Arc<GossipValidator<B>>,
+	/// Reputation changes of peers reported by the gossip validator
+	gossip_report_stream: TracingUnboundedReceiver<PeerReport>,
+	/// Min delta in block numbers between two blocks, BEEFY should vote on
+	min_block_delta: u32,
+	rounds: round::Rounds<MmrRootHash, NumberFor<B>>,
+	finality_notifications: FinalityNotifications<B>,
+	/// Best block we received a GRANDPA notification for
+	best_grandpa_block: NumberFor<B>,
+	/// Best block a BEEFY voting round has been concluded for
+	best_beefy_block: Option<NumberFor<B>>,
+	// keep rustc happy
+	_backend: PhantomData<BE>,
+}
+
+impl<B, C, BE> BeefyWorker<B, C, BE>
+where
+	B: Block,
+	BE: Backend<B>,
+	C: Client<B, BE>,
+	C::Api: BeefyApi<B>,
+{
+	/// Return a new BEEFY worker instance.
+	///
+	/// Note that a BEEFY worker is only fully functional if a corresponding
+	/// BEEFY pallet has been deployed on-chain.
+	///
+	/// The BEEFY pallet is needed in order to keep track of the BEEFY authority set.
+	pub(crate) fn new(worker_params: WorkerParams<B, BE, C>) -> Self {
+		let WorkerParams {
+			client,
+			backend: _,
+			key_store,
+			signed_commitment_sender,
+			gossip_engine,
+			gossip_validator,
+			gossip_report_stream,
+			min_block_delta,
+		} = worker_params;
+
+		gossip_validator.note_best_grandpa(client.info().finalized_number);
+
+		BeefyWorker {
+			client: client.clone(),
+			key_store,
+			signed_commitment_sender,
+			gossip_engine: Arc::new(Mutex::new(gossip_engine)),
+			gossip_validator,
+			gossip_report_stream,
+			min_block_delta,
+			rounds: round::Rounds::new(ValidatorSet::empty()),
+			finality_notifications: client.finality_notification_stream(),
+			best_grandpa_block: client.info().finalized_number,
+			best_beefy_block: None,
+			_backend: PhantomData,
+		}
+	}
+
+	/// Return `true` if BEEFY should vote on block `number`.
+	fn should_vote_on(&self, number: NumberFor<B>) -> bool {
+		let best_beefy_block = match self.best_beefy_block {
+			Some(block) => block,
+			// no round has been concluded for the current validator set yet, vote on any
+			// finalized block to get the protocol going.
+			None => return true,
+		};
+
+		let target = vote_target(self.best_grandpa_block, best_beefy_block, self.min_block_delta);
+
+		trace!(
+			target: "beefy",
+			"🥩 should_vote_on: #{:?}, next_block_to_vote_on: #{:?}",
+			number,
+			target,
+		);
+
+		number >= target
+	}
+
+	/// Return the current active validator set at header `header`.
+	///
+	/// Note that the validator set could be `None`. This is the case if we don't find
+	/// a BEEFY authority set change and we can't fetch the authority set from the
+	/// BEEFY on-chain state.
+	///
+	/// Such a failure is usually an indication that the BEEFY pallet has not been deployed (yet).
+	fn validator_set(&self, header: &B::Header) -> Option<ValidatorSet<Public>> {
+		if let Some(new) = find_authorities_change::<B, Public>(header) {
+			Some(new)
+		} else {
+			let at = BlockId::hash(header.hash());
+			self.client.runtime_api().validator_set(&at).ok()
+		}
+	}
+
+	fn handle_finality_notification(&mut self, notification: FinalityNotification<B>) {
+		trace!(target: "beefy", "🥩 Finality notification: {:?}", notification);
+
+		let number = *notification.header.number();
+		self.best_grandpa_block = number;
+		self.gossip_validator.note_best_grandpa(number);
+
+		if let Some(active) = self.validator_set(&notification.header) {
+			// An authority set change, or learning about the genesis set, starts new voting
+			// rounds. Note that this implicitly drops all rounds of the previous set which have
+			// not been concluded yet.
+			if active.id != self.rounds.validator_set_id() || self.rounds.validators().is_empty() {
+				debug!(target: "beefy", "🥩 New active validator set id: {:?}", active);
+
+				// the first block of a new validator set has to be voted on
+				self.best_beefy_block = None;
+				self.gossip_validator.note_validator_set(active.clone());
+				self.rounds = round::Rounds::new(active);
+			}
+		}
+
+		if !self.should_vote_on(number) {
+			return;
+		}
+
+		let hash = notification.header.hash();
+
+		let authority_id = if let Some(id) = self.key_store.authority_id(self.rounds.validators()) {
+			debug!(target: "beefy", "🥩 Local authority id: {:?}", id);
+			id
+		} else {
+			debug!(target: "beefy", "🥩 Missing validator id - can't vote for: {:?}", hash);
+			return;
+		};
+
+		let mmr_root = if let Some(root) = find_mmr_root_digest::<B, Public>(&notification.header) {
+			root
+		} else {
+			warn!(target: "beefy", "🥩 No MMR root digest found for: {:?}", hash);
+			return;
+		};
+
+		let commitment = Commitment {
+			payload: mmr_root,
+			block_number: number,
+			validator_set_id: self.rounds.validator_set_id(),
+		};
+
+		let signature = match self.key_store.sign(&authority_id, &commitment.encode()) {
+			Ok(sig) => sig,
+			Err(err) => {
+				warn!(target: "beefy", "🥩 Error signing commitment: {:?}", err);
+				return;
+			}
+		};
+
+		let message = VoteMessage {
+			commitment,
+			id: authority_id,
+			signature,
+		};
+
+		let encoded_message = message.encode();
+
+		debug!(target: "beefy", "🥩 Sent vote message: {:?}", message);
+
+		self.handle_vote(
+			(message.commitment.payload, message.commitment.block_number),
+			(message.id, message.signature),
+		);
+
+		self.gossip_engine.lock().gossip_message(topic::<B>(), encoded_message, false);
+	}
+
+	fn handle_vote(&mut self, round: (MmrRootHash, NumberFor<B>), vote: (Public, Signature)) {
+		if !is_live_round(round.1, self.best_beefy_block, self.best_grandpa_block) {
+			trace!(target: "beefy", "🥩 Ignoring vote for round #{} which is not live", round.1);
+			return;
+		}
+
+		let vote_added = self.rounds.add_vote(round, vote);
+
+		if vote_added && self.rounds.is_done(&round) {
+			if let Some(signatures) = self.rounds.conclude(&round) {
+				let signed_commitment = SignedCommitment::<NumberFor<B>, MmrRootHash> {
+					commitment: Commitment {
+						payload: round.0,
+						block_number: round.1,
+						validator_set_id: self.rounds.validator_set_id(),
+					},
+					signatures,
+				};
+
+				info!(
+					target: "beefy",
+					"🥩 Round #{} concluded, committed: {:?}.",
+					round.1,
+					signed_commitment,
+				);
+
+				self.signed_commitment_sender.notify(signed_commitment);
+				self.gossip_validator.conclude_round(round.1);
+				self.best_beefy_block = Some(round.1);
+			}
+		}
+	}
+
+	pub(crate) async fn run(mut self) {
+		let mut votes = Box::pin(self.gossip_engine.lock().messages_for(topic::<B>()).filter_map(
+			|notification| async move {
+				trace!(target: "beefy", "🥩 Got vote message: {:?}", notification);
+
+				BeefyVoteMessage::<B>::decode(&mut &notification.message[..]).ok()
+			},
+		));
+
+		loop {
+			let engine = self.gossip_engine.clone();
+			let gossip_engine = future::poll_fn(|cx| engine.lock().poll_unpin(cx));
+
+			futures::select! {
+				notification = self.finality_notifications.next().fuse() => {
+					if let Some(notification) = notification {
+						self.handle_finality_notification(notification);
+					} else {
+						return;
+					}
+				},
+				vote = votes.next().fuse() => {
+					if let Some(vote) = vote {
+						if vote.commitment.validator_set_id != self.rounds.validator_set_id() {
+							debug!(
+								target: "beefy",
+								"🥩 Ignoring vote for other validator set: {:?}",
+								vote,
+							);
+							continue;
+						}
+
+						self.handle_vote(
+							(vote.commitment.payload, vote.commitment.block_number),
+							(vote.id, vote.signature),
+						);
+					} else {
+						return;
+					}
+				},
+				report = self.gossip_report_stream.next().fuse() => {
+					if let Some(PeerReport { who, cost_benefit }) = report {
+						self.gossip_engine.lock().report(who, cost_benefit);
+					}
+				},
+				_ = gossip_engine.fuse() => {
+					error!(target: "beefy", "🥩 Gossip engine has terminated.");
+					return;
+				}
+			}
+		}
+	}
+}
+
+/// Extract the MMR root hash from a digest in the given header, if it exists.
+fn find_mmr_root_digest<B, Id>(header: &B::Header) -> Option<MmrRootHash>
+where
+	B: Block,
+	Id: Codec,
+{
+	header.digest().logs().iter().find_map(|log| {
+		match log.try_to::<ConsensusLog<Id>>(OpaqueDigestItemId::Consensus(&BEEFY_ENGINE_ID)) {
+			Some(ConsensusLog::MmrRoot(root)) => Some(root),
+			_ => None,
+		}
+	})
+}
+
+/// Scan the `header` digest log for a BEEFY validator set change. Return either the new
+/// validator set or `None` in case no validator set change has been signaled.
+fn find_authorities_change<B, Id>(header: &B::Header) -> Option<ValidatorSet<Id>>
+where
+	B: Block,
+	Id: Codec,
+{
+	let id = OpaqueDigestItemId::Consensus(&BEEFY_ENGINE_ID);
+
+	let filter = |log: ConsensusLog<Id>| match log {
+		ConsensusLog::AuthoritiesChange(validator_set) => Some(validator_set),
+		_ => None,
+	};
+
+	header.digest().convert_first(|l| l.try_to(id).and_then(filter))
+}
+
+/// Return `true` if votes for the round of block `number` are accepted.
+///
+/// Rounds at or below the best BEEFY block are concluded. Rounds above the best GRANDPA block
+/// can't be voted on by us yet, tracking them would let peers grow the rounds without bound.
+fn is_live_round<N: Ord>(number: N, best_beefy: Option<N>, best_grandpa: N) -> bool {
+	best_beefy.map_or(true, |best| number > best) && number <= best_grandpa
+}
+
+/// Calculate next block number to vote on
+///
+/// The next block is at least `min_delta` blocks after the best BEEFY block. If GRANDPA is
+/// further ahead, the distance grows to the largest power of two not exceeding half of the
+/// gap, so that BEEFY catches up in a logarithmic number of rounds.
+fn vote_target<N>(best_grandpa: N, best_beefy: N, min_delta: u32) -> N
+where
+	N: AtLeast32Bit + Copy + Debug,
+{
+	let diff = best_grandpa.saturating_sub(best_beefy);
+	let diff = diff.saturated_into::<u32>() / 2;
+	let target = best_beefy + min_delta.max(prev_power_of_two(diff)).into();
+
+	trace!(
+		target: "beefy",
+		"🥩 vote target - diff: {:?}, target: {:?}, best_grandpa: {:?}, best_beefy: {:?}",
+		diff,
+		target,
+		best_grandpa,
+		best_beefy,
+	);
+
+	target
+}
+
+/// Largest power of two not exceeding `n`, or zero for `n == 0`.
+fn prev_power_of_two(n: u32) -> u32 {
+	if n == 0 {
+		0
+	} else {
+		1 << (31 - n.leading_zeros())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vote_target_respects_min_delta() {
+		assert_eq!(vote_target(10u32, 10, 1), 11);
+		assert_eq!(vote_target(11u32, 10, 1), 11);
+		assert_eq!(vote_target(11u32, 10, 4), 14);
+		assert_eq!(vote_target(0u32, 0, 8), 8);
+	}
+
+	#[test]
+	fn vote_target_catches_up_in_powers_of_two() {
+		assert_eq!(vote_target(14u32, 10, 1), 12);
+		assert_eq!(vote_target(20u32, 10, 1), 14);
+		assert_eq!(vote_target(100u32, 0, 1), 32);
+		assert_eq!(vote_target(100u32, 0, 64), 64);
+	}
+
+	#[test]
+	fn live_rounds_are_between_best_beefy_and_best_grandpa() {
+		assert!(is_live_round(5u32, None, 10));
+		assert!(is_live_round(10u32, None, 10));
+		assert!(!is_live_round(11u32, None, 10));
+
+		assert!(!is_live_round(4u32, Some(4), 10));
+		assert!(is_live_round(5u32, Some(4), 10));
+		assert!(!is_live_round(11u32, Some(4), 10));
+	}
+
+	#[test]
+	fn prev_power_of_two_works() {
+		assert_eq!(prev_power_of_two(0), 0);
+		assert_eq!(prev_power_of_two(1), 1);
+		assert_eq!(prev_power_of_two(5), 4);
+		assert_eq!(prev_power_of_two(8), 8);
+		assert_eq!(prev_power_of_two(u32::max_value()), 1 << 31);
+	}
+}
@@ -75,6 +75,27 @@ impl Into<sc_tracing::TracingReceiver> for TracingReceiver {
 	}
 }
 
+arg_enum! {
+	/// The format used to print log entries.
+	#[allow(missing_docs)]
+	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+	pub enum LogFormat {
+		// Human readable lines.
+		Text,
+		// One JSON object per line.
+		Json,
+	}
+}
+
+impl Into<sc_tracing::logging::LogFormat> for LogFormat {
+	fn into(self) -> sc_tracing::logging::LogFormat {
+		match self {
+			LogFormat::Text => sc_tracing::logging::LogFormat::Text,
+			LogFormat::Json => sc_tracing::logging::LogFormat::Json,
+		}
+	}
+}
+
 arg_enum! {
 	#[allow(missing_docs)]
 	#[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -164,12 +185,15 @@ impl Into<sc_service::config::RpcMethods> for RpcMethods {
 }
 
 /// Database backend
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Database {
 	/// Facebooks RocksDB
 	RocksDb,
 	/// ParityDb. <https://github.com/paritytech/parity-db/>
 	ParityDb,
+	/// Detect whether there is an existing database. Use it, if there is, if not, create new
+	/// instance of RocksDb.
+	Auto,
 }
 
 impl std::str::FromStr for Database {
@@ -178,10 +202,12 @@ impl std::str::FromStr for Database {
 	fn from_str(s: &str) -> Result<Self, String> {
 		if s.eq_ignore_ascii_case("rocksdb") {
 			Ok(Self::RocksDb)
-		} else if s.eq_ignore_ascii_case("paritydb-experimental") {
+		} else if s.eq_ignore_ascii_case("paritydb") || s.eq_ignore_ascii_case("paritydb-experimental") {
 			Ok(Self::ParityDb)
+		} else if s.eq_ignore_ascii_case("auto") {
+			Ok(Self::Auto)
 		} else {
-			Err(format!("Unknwon variant `{}`, known variants: {:?}", s, Self::variants()))
+			Err(format!("Unknown variant `{}`, known variants: {:?}", s, Self::variants()))
 		}
 	}
 }
@@ -189,7 +215,7 @@ impl std::str::FromStr for Database {
 impl Database {
 	/// Returns all the variants of this enum to be shown in the cli.
 	pub fn variants() -> &'static [&'static str] {
-		&["rocksdb", "paritydb-experimental"]
+		&["rocksdb", "paritydb", "paritydb-experimental", "auto"]
 	}
 }
 
@@ -204,6 +230,27 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// Syncing mode.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	pub enum SyncMode {
+		// Full sync. Download and verify all blocks.
+		Full,
+		// Download block headers and the latest state, then continue with full sync.
+		Fast,
+	}
+}
+
+impl Into<sc_network::config::SyncMode> for SyncMode {
+	fn into(self) -> sc_network::config::SyncMode {
+		match self {
+			SyncMode::Full => sc_network::config::SyncMode::Full,
+			SyncMode::Fast => sc_network::config::SyncMode::Fast,
+		}
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
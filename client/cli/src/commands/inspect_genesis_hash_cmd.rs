@@ -0,0 +1,49 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::SharedParams;
+use crate::CliConfiguration;
+use sc_service::ChainSpec;
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `inspect-genesis-hash` command used to print the genesis hash of a chain specification.
+///
+/// The genesis storage is built in memory, so this doesn't require a database.
+#[derive(Debug, StructOpt)]
+pub struct InspectGenesisHashCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl InspectGenesisHashCmd {
+	/// Run the inspect-genesis-hash command
+	pub fn run<B: BlockT>(&self, spec: Box<dyn ChainSpec>) -> error::Result<()> {
+		let hash = sc_service::chain_ops::genesis_hash::<B>(&*spec)?;
+		println!("{:?}", hash);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectGenesisHashCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
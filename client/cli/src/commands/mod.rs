@@ -31,6 +31,7 @@ mod generate;
 mod insert_key;
 mod inspect_node_key;
 mod inspect_key;
+mod inspect_genesis_hash_cmd;
 mod key;
 pub mod utils;
 
@@ -45,6 +46,7 @@ pub use self::{
 	generate::GenerateCmd,
 	insert_key::InsertKeyCmd,
 	inspect_key::InspectKeyCmd,
+	inspect_genesis_hash_cmd::InspectGenesisHashCmd,
 	generate_node_key::GenerateNodeKeyCmd,
 	inspect_node_key::InspectNodeKeyCmd,
 	key::KeySubcommand,
@@ -39,7 +39,7 @@ pub struct SignCmd {
 	#[structopt(long)]
 	message: Option<String>,
 
-	/// The message on STDIN is hex-encoded data
+	/// The message, on STDIN or given with `--message`, is hex-encoded data
 	#[structopt(long)]
 	hex: bool,
 
@@ -90,6 +90,23 @@ mod test {
 			seed,
 			"--message",
 			&seed[2..],
+			"--hex",
+			"--password",
+			"12345"
+		]);
+		assert!(sign.run().is_ok());
+	}
+
+	#[test]
+	fn sign_plain_message() {
+		let seed = "0xad1fb77243b536b90cfe5f0d351ab1b1ac40e3890b41dc64f766ee56340cfca5";
+
+		let sign = SignCmd::from_iter(&[
+			"sign",
+			"--suri",
+			seed,
+			"--message",
+			"not hex, but still signable",
 			"--password",
 			"12345"
 		]);
@@ -249,12 +249,16 @@ pub fn decode_hex<T: AsRef<[u8]>>(message: T) -> Result<Vec<u8>, Error> {
 	Ok(hex::decode(message)?)
 }
 
-/// checks if message is Some, otherwise reads message from stdin and optionally decodes hex
+/// Reads the message from `msg` if given, otherwise from stdin, optionally decoding it as hex.
 pub fn read_message(msg: Option<&String>, should_decode: bool) -> Result<Vec<u8>, Error> {
 	let mut message = vec![];
 	match msg {
 		Some(m) => {
-			message = decode_hex(m)?;
+			message = if should_decode {
+				decode_hex(m)?
+			} else {
+				m.as_bytes().to_vec()
+			};
 		},
 		None => {
 			std::io::stdin().lock().read_to_end(&mut message)?;
@@ -42,7 +42,7 @@ pub struct VerifyCmd {
 	#[structopt(long)]
 	message: Option<String>,
 
-	/// The message on STDIN is hex-encoded data
+	/// The message, on STDIN or given with `--message`, is hex-encoded data
 	#[structopt(long)]
 	hex: bool,
 
@@ -32,8 +32,8 @@ use sc_service::config::{
 	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
 	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 };
-use sc_service::{ChainSpec, TracingReceiver, KeepBlocks, TransactionStorageMode};
-use sc_tracing::logging::LoggerBuilder;
+use sc_service::{ChainSpec, TracingReceiver, KeepBlocks, TransactionStorageMode, InformantOutputFormat};
+use sc_tracing::logging::{LoggerBuilder, LogFormat};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
@@ -211,6 +211,16 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or(TransactionStorageMode::BlockBody))
 	}
 
+	/// Get the minimum disk space, in MiB, that must remain available to the database.
+	///
+	/// By default this is retrieved from `DatabaseParams` if it is available. Otherwise its `0`,
+	/// which disables the check.
+	fn db_storage_threshold(&self) -> Result<u64> {
+		Ok(self.database_params()
+			.map(|x| x.db_storage_threshold())
+			.unwrap_or_default())
+	}
+
 	/// Get the database backend variant.
 	///
 	/// By default this is retrieved from `DatabaseParams` if it is available. Otherwise its `None`.
@@ -225,13 +235,36 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		cache_size: usize,
 		database: Database,
 	) -> Result<DatabaseConfig> {
+		let rocksdb_path = base_path.join("db");
+		let paritydb_path = base_path.join("paritydb");
+
+		// refuse to silently start a second, empty, database next to an existing one.
+		let other_path = match database {
+			Database::RocksDb => Some(&paritydb_path),
+			Database::ParityDb => Some(&rocksdb_path),
+			Database::Auto => None,
+		};
+		if let Some(other_path) = other_path.filter(|path| path.exists()) {
+			return Err(crate::Error::Input(format!(
+				"Found an existing database at {} which does not match the selected `{:?}` backend. \
+				Remove it or select the matching backend with `--database`.",
+				other_path.display(),
+				database,
+			)));
+		}
+
 		Ok(match database {
 			Database::RocksDb => DatabaseConfig::RocksDb {
-				path: base_path.join("db"),
+				path: rocksdb_path,
 				cache_size,
 			},
 			Database::ParityDb => DatabaseConfig::ParityDb {
-				path: base_path.join("paritydb"),
+				path: paritydb_path,
+			},
+			Database::Auto => DatabaseConfig::Auto {
+				paritydb_path,
+				rocksdb_path,
+				cache_size,
 			},
 		})
 	}
@@ -305,6 +338,14 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or_default()
 	}
 
+	/// Get the path where compiled WASM runtimes are cached.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `None`.
+	fn wasm_runtime_cache_path(&self, config_dir: &PathBuf) -> Option<PathBuf> {
+		self.import_params()
+			.and_then(|x| x.wasm_runtime_cache_path(config_dir))
+	}
+
 	/// Get the execution strategies.
 	///
 	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its
@@ -480,7 +521,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		let net_config_dir = config_dir.join(DEFAULT_NETWORK_CONFIG_PATH);
 		let client_id = C::client_id();
 		let database_cache_size = self.database_cache_size()?.unwrap_or(128);
-		let database = self.database()?.unwrap_or(Database::RocksDb);
+		let database = self.database()?.unwrap_or(Database::Auto);
 		let node_key = self.node_key(&net_config_dir)?;
 		let role = self.role(is_dev)?;
 		let max_runtime_instances = self.max_runtime_instances()?.unwrap_or(8);
@@ -515,8 +556,10 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			state_pruning: self.state_pruning(unsafe_pruning, &role)?,
 			keep_blocks: self.keep_blocks()?,
 			transaction_storage: self.database_transaction_storage()?,
+			db_storage_threshold: self.db_storage_threshold()?,
 			wasm_method: self.wasm_method()?,
 			wasm_runtime_overrides: self.wasm_runtime_overrides(),
+			wasm_runtime_cache_path: self.wasm_runtime_cache_path(&config_dir),
 			execution_strategies: self.execution_strategies(is_dev, is_validator)?,
 			rpc_http: self.rpc_http(DCV::rpc_http_listen_port())?,
 			rpc_ws: self.rpc_ws(DCV::rpc_ws_listen_port())?,
@@ -540,7 +583,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			announce_block: self.announce_block()?,
 			role,
 			base_path: Some(base_path),
-			informant_output_format: Default::default(),
+			informant_output_format: self.informant_output_format()?,
 		})
 	}
 
@@ -564,6 +607,29 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.shared_params().disable_log_color())
 	}
 
+	/// Get the format used to print log entries.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn log_format(&self) -> Result<LogFormat> {
+		Ok(self.shared_params().log_format())
+	}
+
+	/// Get the file to write log entries to, along with its maximum size in bytes.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn log_file(&self) -> Result<Option<(PathBuf, u64)>> {
+		Ok(self.shared_params().log_file())
+	}
+
+	/// Get the output format of the informant, derived from the log settings.
+	fn informant_output_format(&self) -> Result<InformantOutputFormat> {
+		let structured = self.log_format()? == LogFormat::Json;
+		Ok(InformantOutputFormat {
+			enable_color: !structured && !self.disable_log_color()? && self.log_file()?.is_none(),
+			structured,
+		})
+	}
+
 	/// Initialize substrate. This must be done only once per process.
 	///
 	/// This method:
@@ -586,6 +652,12 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			logger.with_colors(false);
 		}
 
+		logger.with_log_format(self.log_format()?);
+
+		if let Some((path, max_size)) = self.log_file()? {
+			logger.with_log_file(path, max_size);
+		}
+
 		logger.init()?;
 
 		if let Some(new_limit) = fdlimit::raise_fd_limit() {
@@ -45,6 +45,13 @@ pub struct DatabaseParams {
 	/// in the block body column.
 	#[structopt(long)]
 	pub storage_chain: bool,
+
+	/// Required available space, in MiB, on the database volume.
+	///
+	/// The node is stopped when the space available to the database drops below this
+	/// threshold. Setting it to `0` disables the check.
+	#[structopt(long = "db-storage-threshold", value_name = "MiB", default_value = "1024")]
+	pub db_storage_threshold: u64,
 }
 
 impl DatabaseParams {
@@ -58,6 +65,11 @@ impl DatabaseParams {
 		self.database_cache_size
 	}
 
+	/// Required available space on the database volume, in MiB.
+	pub fn db_storage_threshold(&self) -> u64 {
+		self.db_storage_threshold
+	}
+
 	/// Transaction storage scheme.
 	pub fn transaction_storage(&self) -> TransactionStorageMode {
 		if self.storage_chain {
@@ -62,6 +62,13 @@ pub struct ImportParams {
 	#[structopt(long, value_name = "PATH", parse(from_os_str))]
 	pub wasm_runtime_overrides: Option<PathBuf>,
 
+	/// Disable the on-disk cache of compiled WASM runtimes.
+	///
+	/// By default, runtimes compiled with `--wasm-execution Compiled` are cached under the base
+	/// path so that restarts do not have to compile them again.
+	#[structopt(long = "no-wasm-runtime-cache")]
+	pub no_wasm_runtime_cache: bool,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub execution_strategies: ExecutionStrategiesParams,
@@ -93,6 +100,15 @@ impl ImportParams {
 		self.wasm_runtime_overrides.clone()
 	}
 
+	/// Get the directory where compiled WASM runtimes are cached, if the cache is enabled.
+	pub fn wasm_runtime_cache_path(&self, config_dir: &PathBuf) -> Option<PathBuf> {
+		if self.no_wasm_runtime_cache {
+			None
+		} else {
+			Some(config_dir.clone())
+		}
+	}
+
 	/// Get execution strategies for the parameters
 	pub fn execution_strategies(&self, is_dev: bool, is_validator: bool) -> ExecutionStrategies {
 		let exec = &self.execution_strategies;
@@ -190,3 +206,19 @@ pub struct ExecutionStrategiesParams {
 	)]
 	pub execution: Option<ExecutionStrategy>,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wasm_runtime_cache_is_stored_under_the_config_dir_unless_disabled() {
+		let config_dir = PathBuf::from("/base/chains/dev");
+
+		let params = ImportParams::from_iter(&["import"]);
+		assert_eq!(params.wasm_runtime_cache_path(&config_dir), Some(config_dir.clone()));
+
+		let params = ImportParams::from_iter(&["import", "--no-wasm-runtime-cache"]);
+		assert_eq!(params.wasm_runtime_cache_path(&config_dir), None);
+	}
+}
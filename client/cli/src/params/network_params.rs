@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::arg_enums::SyncMode;
 use crate::params::node_key_params::NodeKeyParams;
 use sc_network::{
 	config::{NetworkConfiguration, NodeKeyConfig, NonReservedPeerMode, SetConfig, TransportConfig},
@@ -92,6 +93,32 @@ pub struct NetworkParams {
 	)]
 	pub max_parallel_downloads: u32,
 
+	/// Maximum number of transactions received from a single peer that may be awaiting
+	/// validation at any moment.
+	///
+	/// Further transactions sent by that peer are ignored until some of them are validated.
+	#[structopt(
+		long = "max-pending-transactions-per-peer",
+		value_name = "COUNT",
+		default_value = "512"
+	)]
+	pub max_pending_transactions_per_peer: usize,
+
+	/// Blockchain syncing mode.
+	///
+	/// - `Full`: Download and validate full blockchain history.
+	///
+	/// - `Fast`: Download block headers, then the state of the latest block, and continue with
+	///   full sync from there.
+	#[structopt(
+		long = "sync",
+		value_name = "SYNC_MODE",
+		possible_values = &SyncMode::variants(),
+		case_insensitive = true,
+		default_value = "Full"
+	)]
+	pub sync: SyncMode,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub node_key_params: NodeKeyParams,
@@ -181,6 +208,8 @@ impl NetworkParams {
 				wasm_external_transport: None,
 			},
 			max_parallel_downloads: self.max_parallel_downloads,
+			max_pending_transactions_per_peer: self.max_pending_transactions_per_peer,
+			sync_mode: self.sync.into(),
 			enable_dht_random_walk: !self.reserved_only,
 			allow_non_globals_in_dht,
 			kademlia_disjoint_query_paths: self.kademlia_disjoint_query_paths,
@@ -23,18 +23,33 @@ use structopt::StructOpt;
 /// Parameters to define the pruning mode
 #[derive(Debug, StructOpt)]
 pub struct PruningParams {
-	/// Specify the state pruning mode, a number of blocks to keep or 'archive'.
+	/// Specify the state pruning mode.
+	///
+	/// This mode specifies when the block's state (ie, storage) should be pruned (ie, removed)
+	/// from the database.
+	///
+	/// Possible values:
+	///  'archive' Keep the state of all blocks.
+	///  'archive-canonical' Keep only the state of finalized blocks.
+	///  number Keep the state of the last number of finalized blocks.
 	///
 	/// Default is to keep all block states if the node is running as a
 	/// validator (i.e. 'archive'), otherwise state is only kept for the last
 	/// 256 blocks.
-	#[structopt(long = "pruning", value_name = "PRUNING_MODE")]
-	pub pruning: Option<String>,
-	/// Specify the number of finalized blocks to keep in the database.
+	#[structopt(long = "state-pruning", alias = "pruning", value_name = "PRUNING_MODE")]
+	pub state_pruning: Option<String>,
+	/// Specify the blocks pruning mode.
+	///
+	/// This mode specifies when the block's body (including justifications)
+	/// should be pruned (ie, removed) from the database.
+	///
+	/// Possible values:
+	///  'archive' Keep all blocks.
+	///  number Keep the last number of finalized blocks.
 	///
 	/// Default is to keep all blocks.
-	#[structopt(long, value_name = "COUNT")]
-	pub keep_blocks: Option<u32>,
+	#[structopt(long = "blocks-pruning", alias = "keep-blocks", value_name = "PRUNING_MODE")]
+	pub blocks_pruning: Option<String>,
 }
 
 impl PruningParams {
@@ -44,8 +59,9 @@ impl PruningParams {
 		// `ArchiveAll`), otherwise we keep state for the last 256 blocks. if the
 		// node is an authority and pruning is enabled explicitly, then we error
 		// unless `unsafe_pruning` is set.
-		Ok(match &self.pruning {
+		Ok(match &self.state_pruning {
 			Some(ref s) if s == "archive" => PruningMode::ArchiveAll,
+			Some(ref s) if s == "archive-canonical" => PruningMode::ArchiveCanonical,
 			None if role.is_authority() => PruningMode::ArchiveAll,
 			None => PruningMode::default(),
 			Some(s) => {
@@ -58,7 +74,7 @@ impl PruningParams {
 				}
 
 				PruningMode::keep_blocks(s.parse().map_err(|_| {
-					error::Error::Input("Invalid pruning mode specified".to_string())
+					error::Error::Input("Invalid state pruning mode specified".to_string())
 				})?)
 			}
 		})
@@ -66,9 +82,66 @@ impl PruningParams {
 
 	/// Get the block pruning value from the parameters
 	pub fn keep_blocks(&self) -> error::Result<KeepBlocks> {
-		Ok(match self.keep_blocks {
-			Some(n) => KeepBlocks::Some(n),
+		Ok(match &self.blocks_pruning {
+			Some(ref s) if s == "archive" => KeepBlocks::All,
 			None => KeepBlocks::All,
+			Some(s) => KeepBlocks::Some(s.parse().map_err(|_| {
+				error::Error::Input("Invalid blocks pruning mode specified".to_string())
+			})?),
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn params(state_pruning: Option<&str>, blocks_pruning: Option<&str>) -> PruningParams {
+		PruningParams {
+			state_pruning: state_pruning.map(Into::into),
+			blocks_pruning: blocks_pruning.map(Into::into),
+		}
+	}
+
+	#[test]
+	fn state_pruning_modes_are_parsed() {
+		let role = Role::Full;
+		assert_eq!(
+			params(Some("archive"), None).state_pruning(false, &role).unwrap(),
+			PruningMode::ArchiveAll,
+		);
+		assert_eq!(
+			params(Some("archive-canonical"), None).state_pruning(false, &role).unwrap(),
+			PruningMode::ArchiveCanonical,
+		);
+		assert_eq!(
+			params(Some("1024"), None).state_pruning(false, &role).unwrap(),
+			PruningMode::keep_blocks(1024),
+		);
+		assert_eq!(params(None, None).state_pruning(false, &role).unwrap(), PruningMode::default());
+		assert!(params(Some("everything"), None).state_pruning(false, &role).is_err());
+	}
+
+	#[test]
+	fn validators_refuse_unsafe_state_pruning() {
+		let role = Role::Authority;
+		assert_eq!(params(None, None).state_pruning(false, &role).unwrap(), PruningMode::ArchiveAll);
+		assert_eq!(
+			params(Some("archive-canonical"), None).state_pruning(false, &role).unwrap(),
+			PruningMode::ArchiveCanonical,
+		);
+		assert!(params(Some("256"), None).state_pruning(false, &role).is_err());
+		assert_eq!(
+			params(Some("256"), None).state_pruning(true, &role).unwrap(),
+			PruningMode::keep_blocks(256),
+		);
+	}
+
+	#[test]
+	fn blocks_pruning_modes_are_parsed() {
+		assert!(matches!(params(None, Some("archive")).keep_blocks().unwrap(), KeepBlocks::All));
+		assert!(matches!(params(None, None).keep_blocks().unwrap(), KeepBlocks::All));
+		assert!(matches!(params(None, Some("16")).keep_blocks().unwrap(), KeepBlocks::Some(16)));
+		assert!(params(None, Some("some")).keep_blocks().is_err());
+	}
+}
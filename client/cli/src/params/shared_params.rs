@@ -19,7 +19,7 @@
 use sc_service::config::BasePath;
 use std::path::PathBuf;
 use structopt::StructOpt;
-use crate::arg_enums::TracingReceiver;
+use crate::arg_enums::{LogFormat, TracingReceiver};
 
 /// Shared parameters used by all `CoreParams`.
 #[derive(Debug, StructOpt)]
@@ -50,6 +50,32 @@ pub struct SharedParams {
 	#[structopt(long)]
 	pub disable_log_color: bool,
 
+	/// The format used to print log entries.
+	///
+	/// `json` prints one JSON object per line, with all the fields of a log entry as keys,
+	/// and never uses colors. The informant then also emits its output as structured fields.
+	#[structopt(
+		long,
+		value_name = "FORMAT",
+		possible_values = &LogFormat::variants(),
+		case_insensitive = true,
+		default_value = "Text"
+	)]
+	pub log_format: LogFormat,
+
+	/// Write log entries to the given file instead of the standard error output.
+	///
+	/// The file is rotated once it exceeds `--log-file-max-size`. Up to five rotated files are
+	/// kept next to it with the suffixes `.1` (most recent) to `.5` (oldest).
+	#[structopt(long, value_name = "PATH", parse(from_os_str))]
+	pub log_file: Option<PathBuf>,
+
+	/// Maximum size of the log file in MiB before it is rotated.
+	///
+	/// Only has an effect together with `--log-file`.
+	#[structopt(long, value_name = "MiB", default_value = "100")]
+	pub log_file_max_size: u64,
+
 	/// Disable feature to dynamically update and reload the log filter.
 	///
 	/// By default this feature is enabled, however it leads to a small performance decrease.
@@ -108,6 +134,16 @@ impl SharedParams {
 		self.disable_log_color
 	}
 
+	/// The format used to print log entries.
+	pub fn log_format(&self) -> sc_tracing::logging::LogFormat {
+		self.log_format.into()
+	}
+
+	/// The file to write log entries to, along with its maximum size in bytes.
+	pub fn log_file(&self) -> Option<(PathBuf, u64)> {
+		self.log_file.clone().map(|path| (path, self.log_file_max_size.saturating_mul(1024 * 1024)))
+	}
+
 	/// Is log reloading disabled
 	pub fn is_log_filter_reloading_disabled(&self) -> bool {
 		self.disable_log_reloading
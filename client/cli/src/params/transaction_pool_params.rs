@@ -29,6 +29,18 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Maximum number of transactions in the future queue of the transaction pool.
+	///
+	/// Defaults to a tenth of `--pool-limit`.
+	#[structopt(long = "pool-future-limit", value_name = "COUNT")]
+	pub pool_future_limit: Option<usize>,
+
+	/// Maximum number of kilobytes of all transactions stored in the future queue of the pool.
+	///
+	/// Defaults to a tenth of `--pool-kbytes`.
+	#[structopt(long = "pool-future-kbytes", value_name = "COUNT")]
+	pub pool_future_kbytes: Option<usize>,
 }
 
 impl TransactionPoolParams {
@@ -42,8 +54,10 @@ impl TransactionPoolParams {
 
 		// future queue
 		let factor = 10;
-		opts.future.count = self.pool_limit / factor;
-		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
+		opts.future.count = self.pool_future_limit.unwrap_or(self.pool_limit / factor);
+		opts.future.total_bytes = self.pool_future_kbytes
+			.map(|kbytes| kbytes * 1024)
+			.unwrap_or(self.pool_kbytes * 1024 / factor);
 
 		opts
 	}
@@ -76,7 +76,7 @@ pub use sp_consensus_babe::{
 pub use sp_consensus::SyncOracle;
 pub use sc_consensus_slots::SlotProportion;
 use std::{
-	collections::HashMap, sync::Arc, u64, pin::Pin, time::{Instant, Duration},
+	collections::HashMap, sync::Arc, u64, pin::Pin, time::Instant,
 	any::Any, borrow::Cow, convert::TryInto,
 };
 use sp_consensus::{ImportResult, CanAuthorWith, import_queue::BoxJustificationImport};
@@ -89,7 +89,7 @@ use sp_runtime::{
 };
 use sp_api::{ProvideRuntimeApi, NumberFor};
 use parking_lot::Mutex;
-use sp_inherents::{InherentDataProviders, InherentData};
+use sp_inherents::{CreateInherentDataProviders, InherentDataProvider, InherentData};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_TRACE, CONSENSUS_DEBUG};
 use sp_consensus::{
 	BlockImport, Environment, Proposer, BlockCheckParams,
@@ -97,7 +97,6 @@ use sp_consensus::{
 	SelectChain, SlotData, import_queue::{Verifier, BasicQueue, DefaultImportQueue, CacheKeyId},
 };
 use sp_consensus_babe::inherents::BabeInherentData;
-use sp_timestamp::TimestampInherentData;
 use sc_client_api::{
 	backend::AuxStore, BlockchainEvents, ProvideUncles,
 };
@@ -110,8 +109,8 @@ use futures::prelude::*;
 use log::{debug, info, log, trace, warn};
 use prometheus_endpoint::Registry;
 use sc_consensus_slots::{
-	SlotInfo, SlotCompatible, StorageChanges, CheckedHeader, check_equivocation,
-	BackoffAuthoringBlocksStrategy
+	SlotInfo, StorageChanges, CheckedHeader, check_equivocation,
+	BackoffAuthoringBlocksStrategy, InherentDataProviderExt,
 };
 use sc_consensus_epochs::{
 	descendent_query, SharedEpochChanges, EpochChangesFor, Epoch as EpochT, ViableEpochDescriptor,
@@ -213,9 +212,9 @@ pub enum Error<B: BlockT> {
 	/// Multiple BABE config change digests
 	#[display(fmt = "Multiple BABE config change digests, rejecting!")]
 	MultipleConfigChangeDigests,
-	/// Could not extract timestamp and slot
-	#[display(fmt = "Could not extract timestamp and slot: {:?}", _0)]
-	Extraction(sp_consensus::Error),
+	/// Could not create the inherent data providers
+	#[display(fmt = "Could not create inherent data providers: {}", _0)]
+	CreateInherentDataProviders(Box<dyn std::error::Error + Send + Sync>),
 	/// Could not fetch epoch
 	#[display(fmt = "Could not fetch epoch at {:?}", _0)]
 	FetchEpoch(B::Hash),
@@ -237,6 +236,9 @@ pub enum Error<B: BlockT> {
 	/// Slot author not found
 	#[display(fmt = "Slot author not found")]
 	SlotAuthorNotFound,
+	/// The author is disabled in the current session.
+	#[display(fmt = "Block authored by disabled authority {:?}", _0)]
+	DisabledAuthority(AuthorityId),
 	/// Secondary slot assignments are disabled for the current epoch.
 	#[display(fmt = "Secondary slot assignments are disabled for the current epoch.")]
 	SecondarySlotAssignmentsDisabled,
@@ -272,7 +274,10 @@ pub enum Error<B: BlockT> {
 	ParentBlockNoAssociatedWeight(B::Hash),
 	#[display(fmt = "Checking inherents failed: {}", _0)]
 	/// Check Inherents error
-	CheckInherents(String),
+	CheckInherents(sp_inherents::Error),
+	/// Unhandled check inherents error
+	#[display(fmt = "Checking inherents unhandled error: {}", "String::from_utf8_lossy(_0)")]
+	CheckInherentsUnhandled(sp_inherents::InherentIdentifier),
 	/// Client error
 	Client(sp_blockchain::Error),
 	/// Runtime Api error.
@@ -322,7 +327,7 @@ impl Config {
 				&b, |v| v == 1,
 			)?;
 			let has_api_v2 = a.has_api_with::<dyn BabeApi<B>, _>(
-				&b, |v| v == 2,
+				&b, |v| v >= 2,
 			)?;
 
 			if has_api_v1 {
@@ -360,7 +365,7 @@ impl std::ops::Deref for Config {
 }
 
 /// Parameters for BABE.
-pub struct BabeParams<B: BlockT, C, E, I, SO, SC, CAW, BS> {
+pub struct BabeParams<B: BlockT, C, E, I, SO, SC, CAW, BS, CIDP> {
 	/// The keystore that manages the keys of the node.
 	pub keystore: SyncCryptoStorePtr,
 
@@ -381,8 +386,12 @@ pub struct BabeParams<B: BlockT, C, E, I, SO, SC, CAW, BS> {
 	/// A sync oracle
 	pub sync_oracle: SO,
 
-	/// Providers for inherent data.
-	pub inherent_data_providers: InherentDataProviders,
+	/// Something that can create the inherent data providers.
+	///
+	/// The created inherent data providers need to provide the timestamp and the BABE slot, see
+	/// [`InherentDataProviderExt`]. The uncles can be provided through
+	/// `sc_consensus_uncles::create_uncles_inherent_data_provider`.
+	pub create_inherent_data_providers: CIDP,
 
 	/// Force authoring of blocks even if we are offline
 	pub force_authoring: bool,
@@ -408,21 +417,21 @@ pub struct BabeParams<B: BlockT, C, E, I, SO, SC, CAW, BS> {
 }
 
 /// Start the babe worker.
-pub fn start_babe<B, C, SC, E, I, SO, CAW, BS, Error>(BabeParams {
+pub fn start_babe<B, C, SC, E, I, SO, CIDP, CAW, BS, Error>(BabeParams {
 	keystore,
 	client,
 	select_chain,
 	env,
 	block_import,
 	sync_oracle,
-	inherent_data_providers,
+	create_inherent_data_providers,
 	force_authoring,
 	backoff_authoring_blocks,
 	babe_link,
 	can_author_with,
 	block_proposal_slot_portion,
 	telemetry,
-}: BabeParams<B, C, E, I, SO, SC, CAW, BS>) -> Result<
+}: BabeParams<B, C, E, I, SO, SC, CAW, BS, CIDP>) -> Result<
 	BabeWorker<B>,
 	sp_consensus::Error,
 > where
@@ -440,6 +449,8 @@ pub fn start_babe<B, C, SC, E, I, SO, CAW, BS, Error>(BabeParams {
 	SO: SyncOracle + Send + Sync + Clone + 'static,
 	CAW: CanAuthorWith<B> + Send + 'static,
 	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + 'static,
+	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
 {
 	const HANDLE_BUFFER_SIZE: usize = 1024;
 
@@ -461,21 +472,13 @@ pub fn start_babe<B, C, SC, E, I, SO, CAW, BS, Error>(BabeParams {
 		telemetry,
 	};
 
-	register_babe_inherent_data_provider(&inherent_data_providers, config.slot_duration())?;
-	sc_consensus_uncles::register_uncles_inherent_data_provider(
-		client.clone(),
-		select_chain.clone(),
-		&inherent_data_providers,
-	)?;
-
 	info!(target: "babe", "👶 Starting BABE Authorship worker");
 	let inner = sc_consensus_slots::start_slot_worker(
 		config.0.clone(),
 		select_chain,
 		worker,
 		sync_oracle,
-		inherent_data_providers,
-		babe_link.time_source,
+		create_inherent_data_providers,
 		can_author_with,
 	);
 
@@ -674,7 +677,7 @@ where
 
 	fn claim_slot(
 		&self,
-		_parent_header: &B::Header,
+		parent_header: &B::Header,
 		slot: Slot,
 		epoch_descriptor: &ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>,
 	) -> Option<Self::Claim> {
@@ -688,6 +691,25 @@ where
 			&self.keystore,
 		);
 
+		// A block of a disabled authority would be rejected, so there is no point in authoring it.
+		if let Some((pre_digest, _)) = &s {
+			match is_authority_disabled(
+				&*self.client,
+				&BlockId::Hash(parent_header.hash()),
+				pre_digest.authority_index(),
+			) {
+				Ok(false) => {},
+				Ok(true) => {
+					debug!(target: "babe", "Not claiming slot {}, our authority is disabled", slot);
+					return None;
+				},
+				Err(err) => {
+					warn!(target: "babe", "Failed to check for disabled authorities: {:?}", err);
+					return None;
+				},
+			}
+		}
+
 		if s.is_some() {
 			debug!(target: "babe", "Claimed slot {}", slot);
 		}
@@ -850,6 +872,27 @@ where
 	}
 }
 
+/// Whether the authority with the given index is disabled in the state of `at`, i.e. whether the
+/// runtime would reject a block it authors on top of `at`.
+///
+/// Runtimes before version 3 of the [`BabeApi`] do not expose their disabled authorities, in which
+/// case no authority is considered disabled.
+fn is_authority_disabled<B, C>(
+	client: &C,
+	at: &BlockId<B>,
+	authority_index: sp_consensus_babe::AuthorityIndex,
+) -> Result<bool, sp_api::ApiError> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: BabeApi<B>,
+{
+	let api = client.runtime_api();
+	if !api.has_api_with::<dyn BabeApi<B>, _>(at, |v| v >= 3)? {
+		return Ok(false);
+	}
+	api.disabled_validators(at).map(|disabled| disabled.contains(&authority_index))
+}
+
 /// Extract the BABE pre digest from the given header. Pre-runtime digests are
 /// mandatory, the function will return `Err` if none is found.
 pub fn find_pre_digest<B: BlockT>(header: &B::Header) -> Result<PreDigest, Error<B>> {
@@ -912,27 +955,9 @@ fn find_next_config_digest<B: BlockT>(header: &B::Header)
 	Ok(config_digest)
 }
 
-#[derive(Default, Clone)]
-struct TimeSource(Arc<Mutex<(Option<Duration>, Vec<(Instant, u64)>)>>);
-
-impl SlotCompatible for TimeSource {
-	fn extract_timestamp_and_slot(
-		&self,
-		data: &InherentData,
-	) -> Result<(u64, Slot, std::time::Duration), sp_consensus::Error> {
-		trace!(target: "babe", "extract timestamp");
-		data.timestamp_inherent_data()
-			.and_then(|t| data.babe_inherent_data().map(|a| (t, a)))
-			.map_err(Into::into)
-			.map_err(sp_consensus::Error::InherentData)
-			.map(|(x, y)| (*x, y, self.0.lock().0.take().unwrap_or_default()))
-	}
-}
-
 /// State that must be shared between the import queue and the authoring logic.
 #[derive(Clone)]
 pub struct BabeLink<Block: BlockT> {
-	time_source: TimeSource,
 	epoch_changes: SharedEpochChanges<Block, Epoch>,
 	config: Config,
 }
@@ -950,30 +975,31 @@ impl<Block: BlockT> BabeLink<Block> {
 }
 
 /// A verifier for Babe blocks.
-pub struct BabeVerifier<Block: BlockT, Client, SelectChain, CAW> {
+pub struct BabeVerifier<Block: BlockT, Client, SelectChain, CAW, CIDP> {
 	client: Arc<Client>,
 	select_chain: SelectChain,
-	inherent_data_providers: sp_inherents::InherentDataProviders,
+	create_inherent_data_providers: CIDP,
 	config: Config,
 	epoch_changes: SharedEpochChanges<Block, Epoch>,
-	time_source: TimeSource,
 	can_author_with: CAW,
 	telemetry: Option<TelemetryHandle>,
 }
 
-impl<Block, Client, SelectChain, CAW> BabeVerifier<Block, Client, SelectChain, CAW>
+impl<Block, Client, SelectChain, CAW, CIDP> BabeVerifier<Block, Client, SelectChain, CAW, CIDP>
 where
 	Block: BlockT,
 	Client: AuxStore + HeaderBackend<Block> + HeaderMetadata<Block> + ProvideRuntimeApi<Block>,
 	Client::Api: BlockBuilderApi<Block> + BabeApi<Block>,
 	SelectChain: sp_consensus::SelectChain<Block>,
 	CAW: CanAuthorWith<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()>,
 {
-	fn check_inherents(
+	async fn check_inherents(
 		&self,
 		block: Block,
 		block_id: BlockId<Block>,
 		inherent_data: InherentData,
+		create_inherent_data_providers: CIDP::InherentDataProviders,
 	) -> Result<(), Error<Block>> {
 		if let Err(e) = self.can_author_with.can_author_with(&block_id) {
 			debug!(
@@ -992,14 +1018,15 @@ where
 		).map_err(Error::RuntimeApi)?;
 
 		if !inherent_res.ok() {
-			inherent_res
-				.into_errors()
-				.try_for_each(|(i, e)| {
-					Err(Error::CheckInherents(self.inherent_data_providers.error_to_string(&i, &e)))
-				})
-		} else {
-			Ok(())
+			for (i, e) in inherent_res.into_errors() {
+				match create_inherent_data_providers.try_handle_error(&i, &e).await {
+					Some(res) => res.map_err(|e| Error::CheckInherents(e))?,
+					None => return Err(Error::CheckInherentsUnhandled(i)),
+				}
+			}
 		}
+
+		Ok(())
 	}
 
 	fn check_and_report_equivocation(
@@ -1083,8 +1110,8 @@ where
 	}
 }
 
-impl<Block, Client, SelectChain, CAW> Verifier<Block>
-	for BabeVerifier<Block, Client, SelectChain, CAW>
+impl<Block, Client, SelectChain, CAW, CIDP> Verifier<Block>
+	for BabeVerifier<Block, Client, SelectChain, CAW, CIDP>
 where
 	Block: BlockT,
 	Client: HeaderMetadata<Block, Error = sp_blockchain::Error> + HeaderBackend<Block> + ProvideRuntimeApi<Block>
@@ -1092,6 +1119,8 @@ where
 	Client::Api: BlockBuilderApi<Block> + BabeApi<Block>,
 	SelectChain: sp_consensus::SelectChain<Block>,
 	CAW: CanAuthorWith<Block> + Send + Sync,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
 {
 	fn verify(
 		&mut self,
@@ -1110,45 +1139,53 @@ where
 		);
 
 		debug!(target: "babe", "We have {:?} logs in this header", header.digest().logs().len());
-		let mut inherent_data = self
-			.inherent_data_providers
+		let hash = header.hash();
+		let parent_hash = *header.parent_hash();
+
+		let create_inherent_data_providers = futures::executor::block_on(
+			self.create_inherent_data_providers.create_inherent_data_providers(parent_hash, ())
+		).map_err(Error::<Block>::CreateInherentDataProviders)?;
+
+		let mut inherent_data = create_inherent_data_providers
 			.create_inherent_data()
 			.map_err(Error::<Block>::Runtime)?;
 
-		let (_, slot_now, _) = self.time_source.extract_timestamp_and_slot(&inherent_data)
-			.map_err(Error::<Block>::Extraction)?;
-
-		let hash = header.hash();
-		let parent_hash = *header.parent_hash();
+		let slot_now = create_inherent_data_providers.slot();
 
 		let parent_header_metadata = self.client.header_metadata(parent_hash)
 			.map_err(Error::<Block>::FetchParentHeader)?;
 
 		let pre_digest = find_pre_digest::<Block>(&header)?;
-		let epoch_changes = self.epoch_changes.lock();
-		let epoch_descriptor = epoch_changes.epoch_descriptor_for_child_of(
-			descendent_query(&*self.client),
-			&parent_hash,
-			parent_header_metadata.number,
-			pre_digest.slot(),
-		)
-			.map_err(|e| Error::<Block>::ForkTree(Box::new(e)))?
-			.ok_or_else(|| Error::<Block>::FetchEpoch(parent_hash))?;
-		let viable_epoch = epoch_changes.viable_epoch(
-			&epoch_descriptor,
-			|slot| Epoch::genesis(&self.config, slot)
-		).ok_or_else(|| Error::<Block>::FetchEpoch(parent_hash))?;
-
-		// We add one to the current slot to allow for some small drift.
-		// FIXME #1019 in the future, alter this queue to allow deferring of headers
-		let v_params = verification::VerificationParams {
-			header: header.clone(),
-			pre_digest: Some(pre_digest),
-			slot_now: slot_now + 1,
-			epoch: viable_epoch.as_ref(),
+		// The epoch changes must not be locked while the inherents are checked below, that may
+		// take a while and would block every other import and authoring task.
+		let (checked_header, epoch_descriptor) = {
+			let epoch_changes = self.epoch_changes.lock();
+			let epoch_descriptor = epoch_changes.epoch_descriptor_for_child_of(
+				descendent_query(&*self.client),
+				&parent_hash,
+				parent_header_metadata.number,
+				pre_digest.slot(),
+			)
+				.map_err(|e| Error::<Block>::ForkTree(Box::new(e)))?
+				.ok_or_else(|| Error::<Block>::FetchEpoch(parent_hash))?;
+			let viable_epoch = epoch_changes.viable_epoch(
+				&epoch_descriptor,
+				|slot| Epoch::genesis(&self.config, slot)
+			).ok_or_else(|| Error::<Block>::FetchEpoch(parent_hash))?;
+
+			// We add one to the current slot to allow for some small drift.
+			// FIXME #1019 in the future, alter this queue to allow deferring of headers
+			let v_params = verification::VerificationParams {
+				header: header.clone(),
+				pre_digest: Some(pre_digest),
+				slot_now: slot_now + 1,
+				epoch: viable_epoch.as_ref(),
+			};
+
+			(verification::check_header::<Block>(v_params)?, epoch_descriptor)
 		};
 
-		match verification::check_header::<Block>(v_params)? {
+		match checked_header {
 			CheckedHeader::Checked(pre_header, verified_info) => {
 				let babe_pre_digest = verified_info.pre_digest.as_babe_pre_digest()
 					.expect("check_header always returns a pre-digest digest item; qed");
@@ -1167,6 +1204,16 @@ where
 					warn!(target: "babe", "Error checking/reporting BABE equivocation: {:?}", err);
 				}
 
+				// The runtime would panic when importing a block of a disabled authority.
+				let disabled = is_authority_disabled(
+					&*self.client,
+					&BlockId::Hash(parent_hash),
+					babe_pre_digest.authority_index(),
+				).map_err(Error::<Block>::RuntimeApi)?;
+				if disabled {
+					return Err(Error::<Block>::DisabledAuthority(verified_info.author).into());
+				}
+
 				// if the body is passed through, we need to use the runtime
 				// to check that the internally-set timestamp in the inherents
 				// actually matches the slot set in the seal.
@@ -1174,11 +1221,12 @@ where
 					inherent_data.babe_replace_inherent_data(slot);
 					let block = Block::new(pre_header.clone(), inner_body);
 
-					self.check_inherents(
+					futures::executor::block_on(self.check_inherents(
 						block.clone(),
 						BlockId::Hash(parent_hash),
 						inherent_data,
-					)?;
+						create_inherent_data_providers,
+					))?;
 
 					let (_, inner_body) = block.deconstruct();
 					body = Some(inner_body);
@@ -1217,22 +1265,6 @@ where
 	}
 }
 
-/// Register the babe inherent data provider, if not registered already.
-pub fn register_babe_inherent_data_provider(
-	inherent_data_providers: &InherentDataProviders,
-	slot_duration: u64,
-) -> Result<(), sp_consensus::Error> {
-	debug!(target: "babe", "Registering");
-	if !inherent_data_providers.has_provider(&sp_consensus_babe::inherents::INHERENT_IDENTIFIER) {
-		inherent_data_providers
-			.register_provider(sp_consensus_babe::inherents::InherentDataProvider::new(slot_duration))
-			.map_err(Into::into)
-			.map_err(sp_consensus::Error::InherentData)
-	} else {
-		Ok(())
-	}
-}
-
 /// A block-import handler for BABE.
 ///
 /// This scans each imported block for epoch change signals. The signals are
@@ -1297,6 +1329,13 @@ impl<Block, Client, Inner> BlockImport<Block> for BabeBlockImport<Block, Client,
 		// early exit if block already in chain, otherwise the check for
 		// epoch changes will error when trying to re-import an epoch change
 		match self.client.status(BlockId::Hash(hash)) {
+			Ok(sp_blockchain::BlockStatus::InChain) if block.imported_state.is_some() => {
+				// Only the state is imported for an existing block, epoch changes were already
+				// handled when the header was imported.
+				let _ = block.take_intermediate::<BabeIntermediate<Block>>(INTERMEDIATE_KEY);
+				block.fork_choice = Some(ForkChoiceStrategy::Custom(false));
+				return self.inner.import_block(block, new_cache).map_err(Into::into)
+			},
 			Ok(sp_blockchain::BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
 			Ok(sp_blockchain::BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
@@ -1574,7 +1613,6 @@ pub fn block_import<Client, Block: BlockT, I>(
 	let epoch_changes = aux_schema::load_epoch_changes::<Block, _>(&*client, &config)?;
 	let link = BabeLink {
 		epoch_changes: epoch_changes.clone(),
-		time_source: Default::default(),
 		config: config.clone(),
 	};
 
@@ -1605,13 +1643,13 @@ pub fn block_import<Client, Block: BlockT, I>(
 ///
 /// The block import object provided must be the `BabeBlockImport` or a wrapper
 /// of it, otherwise crucial import logic will be omitted.
-pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CAW>(
+pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CAW, CIDP>(
 	babe_link: BabeLink<Block>,
 	block_import: Inner,
 	justification_import: Option<BoxJustificationImport<Block>>,
 	client: Arc<Client>,
 	select_chain: SelectChain,
-	inherent_data_providers: InherentDataProviders,
+	create_inherent_data_providers: CIDP,
 	spawner: &impl sp_core::traits::SpawnEssentialNamed,
 	registry: Option<&Registry>,
 	can_author_with: CAW,
@@ -1625,15 +1663,14 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CAW>(
 	Client::Api: BlockBuilderApi<Block> + BabeApi<Block> + ApiExt<Block>,
 	SelectChain: sp_consensus::SelectChain<Block> + 'static,
 	CAW: CanAuthorWith<Block> + Send + Sync + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
 {
-	register_babe_inherent_data_provider(&inherent_data_providers, babe_link.config.slot_duration)?;
-
 	let verifier = BabeVerifier {
 		select_chain,
-		inherent_data_providers,
+		create_inherent_data_providers,
 		config: babe_link.config,
 		epoch_changes: babe_link.epoch_changes,
-		time_source: babe_link.time_source,
 		can_author_with,
 		telemetry,
 		client,
@@ -47,6 +47,8 @@ use rand_chacha::{
 };
 use sc_keystore::LocalKeystore;
 use sp_application_crypto::key_types::BABE;
+use sp_consensus_babe::inherents::InherentDataProvider;
+use sp_timestamp::InherentDataProvider as TimestampInherentDataProvider;
 
 type Item = DigestItem<Hash>;
 
@@ -223,7 +225,17 @@ type TestSelectChain = substrate_test_runtime_client::LongestChain<
 >;
 
 pub struct TestVerifier {
-	inner: BabeVerifier<TestBlock, PeersFullClient, TestSelectChain, AlwaysCanAuthor>,
+	inner: BabeVerifier<
+		TestBlock,
+		PeersFullClient,
+		TestSelectChain,
+		AlwaysCanAuthor,
+		Box<dyn CreateInherentDataProviders<
+			TestBlock,
+			(),
+			InherentDataProviders = (TimestampInherentDataProvider, InherentDataProvider)
+		>>
+	>,
 	mutator: Mutator,
 }
 
@@ -246,7 +258,6 @@ impl Verifier<TestBlock> for TestVerifier {
 
 pub struct PeerData {
 	link: BabeLink<TestBlock>,
-	inherent_data_providers: InherentDataProviders,
 	block_import: Mutex<
 		Option<BoxBlockImport<TestBlock, TransactionFor<substrate_test_runtime_client::Backend, TestBlock>>>
 	>,
@@ -272,8 +283,6 @@ impl TestNetFactory for BabeTestNet {
 		)
 	{
 		let client = client.as_full().expect("only full clients are tested");
-		let inherent_data_providers = InherentDataProviders::new();
-
 		let config = Config::get_or_compute(&*client).expect("config available");
 		let (block_import, link) = crate::block_import(
 			config,
@@ -289,7 +298,7 @@ impl TestNetFactory for BabeTestNet {
 		(
 			BlockImportAdapter::new_full(block_import),
 			None,
-			Some(PeerData { link, inherent_data_providers, block_import: data_block_import }),
+			Some(PeerData { link, block_import: data_block_import }),
 		)
 	}
 
@@ -310,15 +319,23 @@ impl TestNetFactory for BabeTestNet {
 		let data = maybe_link.as_ref().expect("babe link always provided to verifier instantiation");
 
 		let (_, longest_chain) = TestClientBuilder::new().build_with_longest_chain();
+		let slot_duration = data.link.config.slot_duration;
 
 		TestVerifier {
 			inner: BabeVerifier {
 				client: client.clone(),
 				select_chain: longest_chain,
-				inherent_data_providers: data.inherent_data_providers.clone(),
+				create_inherent_data_providers: Box::new(move |_, _| async move {
+					let timestamp = TimestampInherentDataProvider::from_system_time();
+					let slot = InherentDataProvider::from_timestamp_and_duration(
+						*timestamp,
+						Duration::from_millis(slot_duration),
+					);
+
+					Ok((timestamp, slot))
+				}),
 				config: data.link.config.clone(),
 				epoch_changes: data.link.epoch_changes.clone(),
-				time_source: data.link.time_source.clone(),
 				can_author_with: AlwaysCanAuthor,
 				telemetry: None,
 			},
@@ -393,6 +410,7 @@ fn run_one_test(
 		let mut got_other = false;
 
 		let data = peer.data.as_ref().expect("babe link set up during initialization");
+		let slot_duration = data.link.config.slot_duration;
 
 		let environ = DummyFactory {
 			client: client.clone(),
@@ -426,7 +444,15 @@ fn run_one_test(
 			client,
 			env: environ,
 			sync_oracle: DummyOracle,
-			inherent_data_providers: data.inherent_data_providers.clone(),
+			create_inherent_data_providers: move |_, _| async move {
+				let timestamp = TimestampInherentDataProvider::from_system_time();
+				let slot = InherentDataProvider::from_timestamp_and_duration(
+					*timestamp,
+					Duration::from_millis(slot_duration),
+				);
+
+				Ok((timestamp, slot))
+			},
 			force_authoring: false,
 			backoff_authoring_blocks: Some(BackoffAuthoringOnFinalizedHeadLagging::default()),
 			babe_link: data.link.clone(),
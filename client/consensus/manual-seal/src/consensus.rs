@@ -23,6 +23,7 @@ use sp_runtime::traits::{Block as BlockT, DigestFor};
 use sp_inherents::InherentData;
 use sp_consensus::BlockImportParams;
 
+pub mod aura;
 pub mod babe;
 
 /// Consensus data provider, manual seal uses this trait object for authoring blocks valid 
@@ -0,0 +1,127 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Aura consensus data provider
+
+use super::ConsensusDataProvider;
+use crate::Error;
+use std::marker::PhantomData;
+
+use sp_api::{ProvideRuntimeApi, TransactionFor};
+use sp_consensus::BlockImportParams;
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem, inherents::AuraInherentData, sr25519::AuthoritySignature,
+};
+use sp_inherents::InherentData;
+use sp_runtime::{
+	generic::Digest,
+	traits::{Block as BlockT, DigestFor, DigestItemFor},
+};
+
+/// Provides Aura-compatible predigests, so that blocks authored by manual seal are accepted by
+/// Aura runtimes.
+///
+/// The slot of each block is taken from the Aura inherent data. The runtime requires the slot to
+/// increase from block to block and to match the timestamp, so the inherent data providers must
+/// make sure of that.
+pub struct AuraConsensusDataProvider<B, C> {
+	_phantom: PhantomData<(B, C)>,
+}
+
+impl<B, C> AuraConsensusDataProvider<B, C> {
+	/// Creates a new instance of the [`AuraConsensusDataProvider`].
+	pub fn new() -> Self {
+		Self { _phantom: PhantomData }
+	}
+}
+
+impl<B, C> Default for AuraConsensusDataProvider<B, C> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<B, C> ConsensusDataProvider<B> for AuraConsensusDataProvider<B, C>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + Send + Sync,
+{
+	type Transaction = TransactionFor<C, B>;
+
+	fn create_digest(
+		&self,
+		_parent: &B::Header,
+		inherents: &InherentData,
+	) -> Result<DigestFor<B>, Error> {
+		let slot = inherents.aura_inherent_data()?;
+
+		// the signature type is irrelevant for a pre-digest.
+		let logs = vec![
+			<DigestItemFor<B> as CompatibleDigestItem<AuthoritySignature>>::aura_pre_digest(slot),
+		];
+
+		Ok(Digest { logs })
+	}
+
+	fn append_block_import(
+		&self,
+		_parent: &B::Header,
+		_params: &mut BlockImportParams<B, Self::Transaction>,
+		_inherents: &InherentData,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_consensus_aura::inherents::INHERENT_IDENTIFIER;
+	use sp_consensus_slots::Slot;
+	use substrate_test_runtime_client::{TestClient, runtime::{Block, Header}};
+
+	fn parent() -> Header {
+		Header {
+			parent_hash: Default::default(),
+			number: 1,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		}
+	}
+
+	#[test]
+	fn creates_pre_digest_with_inherent_slot() {
+		let provider = AuraConsensusDataProvider::<Block, TestClient>::new();
+		let mut inherents = InherentData::new();
+		inherents.put_data(INHERENT_IDENTIFIER, &Slot::from(42u64)).unwrap();
+
+		let digest = provider.create_digest(&parent(), &inherents).unwrap();
+		let slots = digest.logs.iter()
+			.filter_map(CompatibleDigestItem::<AuthoritySignature>::as_aura_pre_digest)
+			.collect::<Vec<_>>();
+		assert_eq!(slots, vec![Slot::from(42u64)]);
+	}
+
+	#[test]
+	fn fails_without_slot() {
+		let provider = AuraConsensusDataProvider::<Block, TestClient>::new();
+
+		assert!(provider.create_digest(&parent(), &InherentData::new()).is_err());
+	}
+}
@@ -26,11 +26,11 @@ use sp_consensus::{
 	import_queue::{Verifier, BasicQueue, CacheKeyId, BoxBlockImport},
 };
 use sp_blockchain::HeaderBackend;
-use sp_inherents::InherentDataProviders;
+use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::{traits::Block as BlockT, Justification};
 use sc_client_api::backend::{Backend as ClientBackend, Finalizer};
 use sc_transaction_pool::txpool;
-use std::{sync::Arc, marker::PhantomData};
+use std::{sync::Arc, marker::PhantomData, time::Duration};
 use prometheus_endpoint::Registry;
 
 mod error;
@@ -90,7 +90,7 @@ pub fn import_queue<Block, Transaction>(
 }
 
 /// Params required to start the instant sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC, CS> {
+pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC, CS, CIDP> {
 	/// Block import instance for well. importing blocks.
 	pub block_import: BI,
 
@@ -113,12 +113,12 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool
 	/// Digest provider for inclusion in blocks.
 	pub consensus_data_provider: Option<Box<dyn ConsensusDataProvider<B, Transaction = TransactionFor<C, B>>>>,
 
-	/// Provider for inherents to include in blocks.
-	pub inherent_data_providers: InherentDataProviders,
+	/// Something that can create the inherent data providers.
+	pub create_inherent_data_providers: CIDP,
 }
 
 /// Params required to start the manual sealing authorship task.
-pub struct InstantSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC> {
+pub struct InstantSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC, CIDP> {
 	/// Block import instance for well. importing blocks.
 	pub block_import: BI,
 
@@ -137,12 +137,42 @@ pub struct InstantSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpoo
 	/// Digest provider for inclusion in blocks.
 	pub consensus_data_provider: Option<Box<dyn ConsensusDataProvider<B, Transaction = TransactionFor<C, B>>>>,
 
-	/// Provider for inherents to include in blocks.
-	pub inherent_data_providers: InherentDataProviders,
+	/// Something that can create the inherent data providers.
+	pub create_inherent_data_providers: CIDP,
+}
+
+/// Params required to start the interval sealing authorship task.
+pub struct IntervalSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, A: txpool::ChainApi, SC, CIDP> {
+	/// Block import instance for well. importing blocks.
+	pub block_import: BI,
+
+	/// The environment we are producing blocks for.
+	pub env: E,
+
+	/// Client instance
+	pub client: Arc<C>,
+
+	/// Shared reference to the transaction pool.
+	pub pool: Arc<txpool::Pool<A>>,
+
+	/// SelectChain strategy.
+	pub select_chain: SC,
+
+	/// Digest provider for inclusion in blocks.
+	pub consensus_data_provider: Option<Box<dyn ConsensusDataProvider<B, Transaction = TransactionFor<C, B>>>>,
+
+	/// Something that can create the inherent data providers.
+	pub create_inherent_data_providers: CIDP,
+
+	/// Time between two blocks.
+	pub interval: Duration,
+
+	/// Whether the blocks should be finalized right after they are imported.
+	pub finalize: bool,
 }
 
 /// Creates the background authorship task for the manual seal engine.
-pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
+pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS, CIDP>(
 	ManualSealParams {
 		mut block_import,
 		mut env,
@@ -150,10 +180,10 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 		pool,
 		mut commands_stream,
 		select_chain,
-		inherent_data_providers,
+		create_inherent_data_providers,
 		consensus_data_provider,
 		..
-	}: ManualSealParams<B, BI, E, C, A, SC, CS>
+	}: ManualSealParams<B, BI, E, C, A, SC, CS, CIDP>
 )
 	where
 		A: txpool::ChainApi<Block=B> + 'static,
@@ -167,6 +197,7 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 		CS: Stream<Item=EngineCommand<<B as BlockT>::Hash>> + Unpin + 'static,
 		SC: SelectChain<B> + 'static,
 		TransactionFor<C, B>: 'static,
+		CIDP: CreateInherentDataProviders<B, ()>,
 {
 	while let Some(command) = commands_stream.next().await {
 		match command {
@@ -185,7 +216,7 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 						env: &mut env,
 						select_chain: &select_chain,
 						block_import: &mut block_import,
-						inherent_data_provider: &inherent_data_providers,
+						create_inherent_data_providers: &create_inherent_data_providers,
 						consensus_data_provider: consensus_data_provider.as_ref().map(|p| &**p),
 						pool: pool.clone(),
 						client: client.clone(),
@@ -210,7 +241,7 @@ pub async fn run_manual_seal<B, BI, CB, E, C, A, SC, CS>(
 /// runs the background authorship task for the instant seal engine.
 /// instant-seal creates a new block for every transaction imported into
 /// the transaction pool.
-pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
+pub async fn run_instant_seal<B, BI, CB, E, C, A, SC, CIDP>(
 	InstantSealParams {
 		block_import,
 		env,
@@ -218,9 +249,9 @@ pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
 		pool,
 		select_chain,
 		consensus_data_provider,
-		inherent_data_providers,
+		create_inherent_data_providers,
 		..
-	}: InstantSealParams<B, BI, E, C, A, SC>
+	}: InstantSealParams<B, BI, E, C, A, SC, CIDP>
 )
 	where
 		A: txpool::ChainApi<Block=B> + 'static,
@@ -233,6 +264,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
 		E::Proposer: Proposer<B, Transaction = TransactionFor<C, B>>,
 		SC: SelectChain<B> + 'static,
 		TransactionFor<C, B>: 'static,
+		CIDP: CreateInherentDataProviders<B, ()>,
 {
 	// instant-seal creates blocks as soon as transactions are imported
 	// into the transaction pool.
@@ -256,7 +288,62 @@ pub async fn run_instant_seal<B, BI, CB, E, C, A, SC>(
 			commands_stream,
 			select_chain,
 			consensus_data_provider,
-			inherent_data_providers,
+			create_inherent_data_providers,
+		}
+	).await
+}
+
+/// runs the background authorship task for the interval seal engine.
+/// interval-seal creates a new block every `interval`, whether or not there
+/// are transactions in the pool.
+pub async fn run_interval_seal<B, BI, CB, E, C, A, SC, CIDP>(
+	IntervalSealParams {
+		block_import,
+		env,
+		client,
+		pool,
+		select_chain,
+		consensus_data_provider,
+		create_inherent_data_providers,
+		interval,
+		finalize,
+	}: IntervalSealParams<B, BI, E, C, A, SC, CIDP>
+)
+	where
+		A: txpool::ChainApi<Block=B> + 'static,
+		B: BlockT + 'static,
+		BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
+			+ Send + Sync + 'static,
+		C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+		CB: ClientBackend<B> + 'static,
+		E: Environment<B> + 'static,
+		E::Proposer: Proposer<B, Transaction = TransactionFor<C, B>>,
+		SC: SelectChain<B> + 'static,
+		TransactionFor<C, B>: 'static,
+		CIDP: CreateInherentDataProviders<B, ()>,
+{
+	let commands_stream = stream::unfold((), move |()| {
+		futures_timer::Delay::new(interval).map(move |_| {
+			let command = EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize,
+				parent_hash: None,
+				sender: None,
+			};
+			Some((command, ()))
+		})
+	});
+
+	run_manual_seal(
+		ManualSealParams {
+			block_import,
+			env,
+			client,
+			pool,
+			commands_stream: Box::pin(commands_stream),
+			select_chain,
+			consensus_data_provider,
+			create_inherent_data_providers,
 		}
 	).await
 }
@@ -275,7 +362,6 @@ mod tests {
 	use sp_transaction_pool::{TransactionPool, MaintainedTransactionPool, TransactionSource};
 	use sp_runtime::generic::BlockId;
 	use sp_consensus::ImportedAux;
-	use sp_inherents::InherentDataProviders;
 	use sc_basic_authorship::ProposerFactory;
 	use sc_client_api::BlockBackend;
 
@@ -290,7 +376,6 @@ mod tests {
 		let builder = TestClientBuilder::new();
 		let (client, select_chain) = builder.build_with_longest_chain();
 		let client = Arc::new(client);
-		let inherent_data_providers = InherentDataProviders::new();
 		let spawner = sp_core::testing::TaskExecutor::new();
 		let pool = Arc::new(BasicPool::with_revalidation_type(
 			Options::default(), true.into(), api(), None, RevalidationType::Full, spawner.clone(),
@@ -325,7 +410,7 @@ mod tests {
 				pool: pool.pool().clone(),
 				commands_stream,
 				select_chain,
-				inherent_data_providers,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
 				consensus_data_provider: None,
 			}
 		);
@@ -357,12 +442,57 @@ mod tests {
 		assert!(client.header(&BlockId::Number(1)).unwrap().is_some())
 	}
 
+	#[tokio::test]
+	async fn interval_seal() {
+		let builder = TestClientBuilder::new();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(), true.into(), api(), None, RevalidationType::Full, spawner.clone(),
+		));
+		let env = ProposerFactory::new(
+			spawner.clone(),
+			client.clone(),
+			pool.clone(),
+			None,
+			None,
+		);
+		// this test checks that empty blocks are created and finalized on every tick.
+		let future = run_interval_seal(
+			IntervalSealParams {
+				block_import: client.clone(),
+				env,
+				client: client.clone(),
+				pool: pool.pool().clone(),
+				select_chain,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
+				consensus_data_provider: None,
+				interval: Duration::from_millis(10),
+				finalize: true,
+			}
+		);
+		std::thread::spawn(|| {
+			let mut rt = tokio::runtime::Runtime::new().unwrap();
+			// spawn the background authorship task
+			rt.block_on(future);
+		});
+
+		let finalized = tokio::time::timeout(Duration::from_secs(30), async {
+			while client.info().finalized_number < 2 {
+				futures_timer::Delay::new(Duration::from_millis(10)).await;
+			}
+		}).await;
+		assert!(finalized.is_ok(), "two blocks were not finalized in time");
+		// assert that the blocks were created without any transaction.
+		assert!(client.header(&BlockId::Number(2)).unwrap().is_some())
+	}
+
 	#[tokio::test]
 	async fn manual_seal_and_finalization() {
 		let builder = TestClientBuilder::new();
 		let (client, select_chain) = builder.build_with_longest_chain();
 		let client = Arc::new(client);
-		let inherent_data_providers = InherentDataProviders::new();
 		let spawner = sp_core::testing::TaskExecutor::new();
 		let pool = Arc::new(BasicPool::with_revalidation_type(
 			Options::default(), true.into(), api(), None, RevalidationType::Full, spawner.clone(),
@@ -385,7 +515,7 @@ mod tests {
 				commands_stream,
 				select_chain,
 				consensus_data_provider: None,
-				inherent_data_providers,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
 			}
 		);
 		std::thread::spawn(|| {
@@ -437,7 +567,6 @@ mod tests {
 		let builder = TestClientBuilder::new();
 		let (client, select_chain) = builder.build_with_longest_chain();
 		let client = Arc::new(client);
-		let inherent_data_providers = InherentDataProviders::new();
 		let pool_api = api();
 		let spawner = sp_core::testing::TaskExecutor::new();
 		let pool = Arc::new(BasicPool::with_revalidation_type(
@@ -461,7 +590,7 @@ mod tests {
 				commands_stream,
 				select_chain,
 				consensus_data_provider: None,
-				inherent_data_providers,
+				create_inherent_data_providers: |_, _| async { Ok(()) },
 			}
 		);
 		std::thread::spawn(|| {
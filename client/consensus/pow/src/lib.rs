@@ -30,8 +30,12 @@
 //! for the auxiliary storage. It is also possible to just use the runtime
 //! as the storage, but it is not recommended as it won't work well with light
 //! clients.
+//!
+//! Mining can also be done outside of the node, by exposing the worker returned by
+//! `start_mining_worker` over RPC with [`rpc::Pow`].
 
 mod worker;
+pub mod rpc;
 
 pub use crate::worker::{MiningWorker, MiningMetadata, MiningBuild};
 
@@ -49,7 +53,7 @@ use sp_runtime::generic::{BlockId, Digest, DigestItem};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use sp_api::ProvideRuntimeApi;
 use sp_consensus_pow::{Seal, TotalDifficulty, POW_ENGINE_ID};
-use sp_inherents::{InherentDataProviders, InherentData};
+use sp_inherents::{CreateInherentDataProviders, InherentDataProvider};
 use sp_consensus::{
 	BlockImportParams, BlockOrigin, ForkChoiceStrategy, SyncOracle, Environment, Proposer,
 	SelectChain, Error as ConsensusError, CanAuthorWith, BlockImport, BlockCheckParams, ImportResult,
@@ -61,7 +65,6 @@ use codec::{Encode, Decode};
 use prometheus_endpoint::Registry;
 use sc_client_api;
 use log::*;
-use sp_timestamp::{InherentError as TIError, TimestampInherentData};
 
 use crate::worker::UntilImportedOrTimeout;
 
@@ -75,8 +78,6 @@ pub enum Error<B: BlockT> {
 	InvalidSeal,
 	#[display(fmt = "PoW validation error: preliminary verification failed")]
 	FailedPreliminaryVerify,
-	#[display(fmt = "Rejecting block too far in future")]
-	TooFarInFuture,
 	#[display(fmt = "Fetching best header failed using select chain: {:?}", _0)]
 	BestHeaderSelectChain(ConsensusError),
 	#[display(fmt = "Fetching best header failed: {:?}", _0)]
@@ -92,7 +93,14 @@ pub enum Error<B: BlockT> {
 	#[display(fmt = "Creating inherents failed: {}", _0)]
 	CreateInherents(sp_inherents::Error),
 	#[display(fmt = "Checking inherents failed: {}", _0)]
-	CheckInherents(String),
+	CheckInherents(sp_inherents::Error),
+	#[display(
+		fmt = "Checking inherents unknown error for identifier: {:?}",
+		"String::from_utf8_lossy(_0)",
+	)]
+	CheckInherentsUnknownInherent(sp_inherents::InherentIdentifier),
+	#[display(fmt = "Creating inherent data providers failed: {}", _0)]
+	CreateInherentDataProviders(Box<dyn std::error::Error + Send + Sync>),
 	#[display(fmt = "Multiple pre-runtime digests")]
 	MultiplePreRuntimeDigests,
 	Client(sp_blockchain::Error),
@@ -200,18 +208,18 @@ pub trait PowAlgorithm<B: BlockT> {
 }
 
 /// A block importer for PoW.
-pub struct PowBlockImport<B: BlockT, I, C, S, Algorithm, CAW> {
+pub struct PowBlockImport<B: BlockT, I, C, S, Algorithm, CAW, CIDP> {
 	algorithm: Algorithm,
 	inner: I,
 	select_chain: S,
 	client: Arc<C>,
-	inherent_data_providers: sp_inherents::InherentDataProviders,
+	create_inherent_data_providers: Arc<CIDP>,
 	check_inherents_after: <<B as BlockT>::Header as HeaderT>::Number,
 	can_author_with: CAW,
 }
 
-impl<B: BlockT, I: Clone, C, S: Clone, Algorithm: Clone, CAW: Clone> Clone
-	for PowBlockImport<B, I, C, S, Algorithm, CAW>
+impl<B: BlockT, I: Clone, C, S: Clone, Algorithm: Clone, CAW: Clone, CIDP> Clone
+	for PowBlockImport<B, I, C, S, Algorithm, CAW, CIDP>
 {
 	fn clone(&self) -> Self {
 		Self {
@@ -219,14 +227,14 @@ impl<B: BlockT, I: Clone, C, S: Clone, Algorithm: Clone, CAW: Clone> Clone
 			inner: self.inner.clone(),
 			select_chain: self.select_chain.clone(),
 			client: self.client.clone(),
-			inherent_data_providers: self.inherent_data_providers.clone(),
+			create_inherent_data_providers: self.create_inherent_data_providers.clone(),
 			check_inherents_after: self.check_inherents_after.clone(),
 			can_author_with: self.can_author_with.clone(),
 		}
 	}
 }
 
-impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> where
+impl<B, I, C, S, Algorithm, CAW, CIDP> PowBlockImport<B, I, C, S, Algorithm, CAW, CIDP> where
 	B: BlockT,
 	I: BlockImport<B, Transaction = sp_api::TransactionFor<C, B>> + Send + Sync,
 	I::Error: Into<ConsensusError>,
@@ -234,6 +242,7 @@ impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> wher
 	C::Api: BlockBuilderApi<B>,
 	Algorithm: PowAlgorithm<B>,
 	CAW: CanAuthorWith<B>,
+	CIDP: CreateInherentDataProviders<B, ()>,
 {
 	/// Create a new block import suitable to be used in PoW
 	pub fn new(
@@ -242,7 +251,7 @@ impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> wher
 		algorithm: Algorithm,
 		check_inherents_after: <<B as BlockT>::Header as HeaderT>::Number,
 		select_chain: S,
-		inherent_data_providers: sp_inherents::InherentDataProviders,
+		create_inherent_data_providers: CIDP,
 		can_author_with: CAW,
 	) -> Self {
 		Self {
@@ -251,20 +260,16 @@ impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> wher
 			algorithm,
 			check_inherents_after,
 			select_chain,
-			inherent_data_providers,
+			create_inherent_data_providers: Arc::new(create_inherent_data_providers),
 			can_author_with,
 		}
 	}
 
-	fn check_inherents(
+	async fn check_inherents(
 		&self,
 		block: B,
 		block_id: BlockId<B>,
-		inherent_data: InherentData,
-		timestamp_now: u64,
 	) -> Result<(), Error<B>> {
-		const MAX_TIMESTAMP_DRIFT_SECS: u64 = 60;
-
 		if *block.header().number() < self.check_inherents_after {
 			return Ok(())
 		}
@@ -279,6 +284,14 @@ impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> wher
 			return Ok(())
 		}
 
+		let create_inherent_data_providers = self.create_inherent_data_providers
+			.create_inherent_data_providers(*block.header().parent_hash(), ())
+			.await
+			.map_err(Error::CreateInherentDataProviders)?;
+
+		let inherent_data = create_inherent_data_providers.create_inherent_data()
+			.map_err(Error::CreateInherents)?;
+
 		let inherent_res = self.client.runtime_api().check_inherents(
 			&block_id,
 			block,
@@ -286,28 +299,21 @@ impl<B, I, C, S, Algorithm, CAW> PowBlockImport<B, I, C, S, Algorithm, CAW> wher
 		).map_err(|e| Error::Client(e.into()))?;
 
 		if !inherent_res.ok() {
-			inherent_res
-				.into_errors()
-				.try_for_each(|(i, e)| match TIError::try_from(&i, &e) {
-					Some(TIError::ValidAtTimestamp(timestamp)) => {
-						if timestamp > timestamp_now + MAX_TIMESTAMP_DRIFT_SECS {
-							return Err(Error::TooFarInFuture);
-						}
-
-						Ok(())
-					},
-					Some(TIError::Other(e)) => Err(Error::Runtime(e)),
-					None => Err(Error::CheckInherents(
-						self.inherent_data_providers.error_to_string(&i, &e)
-					)),
-				})
-		} else {
-			Ok(())
+			for (identifier, error) in inherent_res.into_errors() {
+				match create_inherent_data_providers.try_handle_error(&identifier, &error).await {
+					Some(res) => res.map_err(Error::CheckInherents)?,
+					None => return Err(Error::CheckInherentsUnknownInherent(identifier)),
+				}
+			}
 		}
+
+		Ok(())
 	}
 }
 
-impl<B, I, C, S, Algorithm, CAW> BlockImport<B> for PowBlockImport<B, I, C, S, Algorithm, CAW> where
+impl<B, I, C, S, Algorithm, CAW, CIDP> BlockImport<B>
+	for PowBlockImport<B, I, C, S, Algorithm, CAW, CIDP>
+where
 	B: BlockT,
 	I: BlockImport<B, Transaction = sp_api::TransactionFor<C, B>> + Send + Sync,
 	I::Error: Into<ConsensusError>,
@@ -317,6 +323,7 @@ impl<B, I, C, S, Algorithm, CAW> BlockImport<B> for PowBlockImport<B, I, C, S, A
 	Algorithm: PowAlgorithm<B>,
 	Algorithm::Difficulty: 'static,
 	CAW: CanAuthorWith<B>,
+	CIDP: CreateInherentDataProviders<B, ()>,
 {
 	type Error = ConsensusError;
 	type Transaction = sp_api::TransactionFor<C, B>;
@@ -342,18 +349,12 @@ impl<B, I, C, S, Algorithm, CAW> BlockImport<B> for PowBlockImport<B, I, C, S, A
 		let mut aux = PowAux::read::<_, B>(self.client.as_ref(), &parent_hash)?;
 
 		if let Some(inner_body) = block.body.take() {
-			let inherent_data = self.inherent_data_providers
-				.create_inherent_data().map_err(|e| e.into_string())?;
-			let timestamp_now = inherent_data.timestamp_inherent_data().map_err(|e| e.into_string())?;
-
 			let check_block = B::new(block.header.clone(), inner_body);
 
-			self.check_inherents(
+			futures::executor::block_on(self.check_inherents(
 				check_block.clone(),
 				BlockId::Hash(parent_hash),
-				inherent_data,
-				*timestamp_now,
-			)?;
+			))?;
 
 			block.body = Some(check_block.deconstruct().1);
 		}
@@ -481,20 +482,6 @@ impl<B: BlockT, Algorithm> Verifier<B> for PowVerifier<B, Algorithm> where
 	}
 }
 
-/// Register the PoW inherent data provider, if not registered already.
-pub fn register_pow_inherent_data_provider(
-	inherent_data_providers: &InherentDataProviders,
-) -> Result<(), sp_consensus::Error> {
-	if !inherent_data_providers.has_provider(&sp_timestamp::INHERENT_IDENTIFIER) {
-		inherent_data_providers
-			.register_provider(sp_timestamp::InherentDataProvider)
-			.map_err(Into::into)
-			.map_err(sp_consensus::Error::InherentData)
-	} else {
-		Ok(())
-	}
-}
-
 /// The PoW import queue type.
 pub type PowImportQueue<B, Transaction> = BasicQueue<B, Transaction>;
 
@@ -503,7 +490,6 @@ pub fn import_queue<B, Transaction, Algorithm>(
 	block_import: BoxBlockImport<B, Transaction>,
 	justification_import: Option<BoxJustificationImport<B>>,
 	algorithm: Algorithm,
-	inherent_data_providers: InherentDataProviders,
 	spawner: &impl sp_core::traits::SpawnEssentialNamed,
 	registry: Option<&Registry>,
 ) -> Result<
@@ -514,8 +500,6 @@ pub fn import_queue<B, Transaction, Algorithm>(
 	Transaction: Send + Sync + 'static,
 	Algorithm: PowAlgorithm<B> + Clone + Send + Sync + 'static,
 {
-	register_pow_inherent_data_provider(&inherent_data_providers)?;
-
 	let verifier = PowVerifier::new(algorithm);
 
 	Ok(BasicQueue::new(
@@ -536,7 +520,7 @@ pub fn import_queue<B, Transaction, Algorithm>(
 ///
 /// `pre_runtime` is a parameter that allows a custom additional pre-runtime digest to be inserted
 /// for blocks being built. This can encode authorship information, or just be a graffiti.
-pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
+pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW, CIDP>(
 	block_import: BoxBlockImport<Block, sp_api::TransactionFor<C, Block>>,
 	client: Arc<C>,
 	select_chain: S,
@@ -544,7 +528,7 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 	mut env: E,
 	mut sync_oracle: SO,
 	pre_runtime: Option<Vec<u8>>,
-	inherent_data_providers: sp_inherents::InherentDataProviders,
+	create_inherent_data_providers: CIDP,
 	timeout: Duration,
 	build_time: Duration,
 	can_author_with: CAW,
@@ -562,11 +546,10 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 	E::Proposer: Proposer<Block, Transaction = sp_api::TransactionFor<C, Block>>,
 	SO: SyncOracle + Clone + Send + Sync + 'static,
 	CAW: CanAuthorWith<Block> + Clone + Send + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: Send,
 {
-	if let Err(_) = register_pow_inherent_data_provider(&inherent_data_providers) {
-		warn!("Registering inherent data provider for timestamp failed");
-	}
-
+	let create_inherent_data_providers = Arc::new(create_inherent_data_providers);
 	let timer = UntilImportedOrTimeout::new(client.import_notification_stream(), timeout);
 	let worker = Arc::new(Mutex::new(MiningWorker::<Block, Algorithm, C, _> {
 		build: None,
@@ -629,18 +612,7 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 		};
 
 		let awaiting_proposer = env.init(&best_header);
-		let inherent_data = match inherent_data_providers.create_inherent_data() {
-			Ok(x) => x,
-			Err(err) => {
-				warn!(
-					target: "pow",
-					"Unable to propose new block for authoring. \
-					 Creating inherent data failed: {:?}",
-					err,
-				);
-				return Either::Left(future::ready(()))
-			},
-		};
+		let create_inherent_data_providers = create_inherent_data_providers.clone();
 		let mut inherent_digest = Digest::<Block::Hash>::default();
 		if let Some(pre_runtime) = &pre_runtime {
 			inherent_digest.push(DigestItem::PreRuntime(POW_ENGINE_ID, pre_runtime.to_vec()));
@@ -649,6 +621,35 @@ pub fn start_mining_worker<Block, C, S, Algorithm, E, SO, CAW>(
 		let pre_runtime = pre_runtime.clone();
 
 		Either::Right(async move {
+			let inherent_data_providers = match create_inherent_data_providers
+				.create_inherent_data_providers(best_hash, ())
+				.await
+			{
+				Ok(x) => x,
+				Err(err) => {
+					warn!(
+						target: "pow",
+						"Unable to propose new block for authoring. \
+						 Creating inherent data providers failed: {:?}",
+						err,
+					);
+					return
+				},
+			};
+
+			let inherent_data = match inherent_data_providers.create_inherent_data() {
+				Ok(x) => x,
+				Err(err) => {
+					warn!(
+						target: "pow",
+						"Unable to propose new block for authoring. \
+						 Creating inherent data failed: {:?}",
+						err,
+					);
+					return
+				},
+			};
+
 			let proposer = match awaiting_proposer.await {
 				Ok(x) => x,
 				Err(err) => {
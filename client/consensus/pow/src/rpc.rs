@@ -0,0 +1,262 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC interface for external PoW miners.
+//!
+//! Miners poll `pow_getWork` for the pre-hash and difficulty of the current mining build, and
+//! submit a seal for it with `pow_submitWork`. Both methods are unsafe, as they give control over
+//! block production of the node.
+
+use std::sync::Arc;
+use codec::Encode;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use parking_lot::Mutex;
+use sc_rpc_api::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+use crate::{MiningWorker, PowAlgorithm};
+
+pub use self::gen_client::Client as PowClient;
+
+/// The work a miner should find a seal for.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Work<Hash> {
+	/// Currently known best hash which the pre-hash is built on.
+	pub best_hash: Hash,
+	/// Mining pre-hash.
+	pub pre_hash: Hash,
+	/// SCALE-encoded mining target difficulty.
+	pub difficulty: Bytes,
+}
+
+/// RPC trait that allows external miners to fetch work from and submit seals to the node.
+#[rpc]
+pub trait PowApi<Hash> {
+	/// Returns the current work, or `None` if the node is not building blocks (e.g. because it is
+	/// major syncing).
+	#[rpc(name = "pow_getWork")]
+	fn work(&self) -> Result<Option<Work<Hash>>>;
+
+	/// Submits a seal for the work with the given `pre_hash`. Returns `true` if the seal was
+	/// valid and the block was imported, and `false` if the seal is invalid or the work is no
+	/// longer current.
+	#[rpc(name = "pow_submitWork")]
+	fn submit_work(&self, pre_hash: Hash, seal: Bytes) -> Result<bool>;
+}
+
+/// A struct that implements the [`PowApi`] on top of a [`MiningWorker`].
+pub struct Pow<Block, Algorithm, C, Proof> where
+	Block: BlockT,
+	Algorithm: PowAlgorithm<Block>,
+	C: sp_api::ProvideRuntimeApi<Block>,
+{
+	worker: Arc<Mutex<MiningWorker<Block, Algorithm, C, Proof>>>,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<Block, Algorithm, C, Proof> Pow<Block, Algorithm, C, Proof> where
+	Block: BlockT,
+	Algorithm: PowAlgorithm<Block>,
+	C: sp_api::ProvideRuntimeApi<Block>,
+{
+	/// Create a new `Pow` RPC handler for the worker returned by `start_mining_worker`.
+	pub fn new(
+		worker: Arc<Mutex<MiningWorker<Block, Algorithm, C, Proof>>>,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { worker, deny_unsafe }
+	}
+}
+
+impl<Block, Algorithm, C, Proof> PowApi<Block::Hash> for Pow<Block, Algorithm, C, Proof> where
+	Block: BlockT,
+	Algorithm: PowAlgorithm<Block> + 'static,
+	Algorithm::Difficulty: 'static,
+	C: sp_api::ProvideRuntimeApi<Block> + 'static,
+	Proof: 'static,
+	MiningWorker<Block, Algorithm, C, Proof>: Send,
+{
+	fn work(&self) -> Result<Option<Work<Block::Hash>>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.worker.lock().metadata().map(|metadata| Work {
+			best_hash: metadata.best_hash,
+			pre_hash: metadata.pre_hash,
+			difficulty: metadata.difficulty.encode().into(),
+		}))
+	}
+
+	fn submit_work(&self, pre_hash: Block::Hash, seal: Bytes) -> Result<bool> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let mut worker = self.worker.lock();
+		if worker.metadata().map(|metadata| metadata.pre_hash) != Some(pre_hash) {
+			log::debug!(target: "pow", "Discarding seal submitted for stale work {:?}", pre_hash);
+			return Ok(false)
+		}
+
+		Ok(worker.submit(seal.to_vec()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+	use sp_api::{StateBackendFor, StorageChanges, TransactionFor};
+	use sp_blockchain::well_known_cache_keys::Id as CacheKeyId;
+	use sp_consensus::{
+		BlockCheckParams, BlockImport, BlockImportParams, Error as ConsensusError, ImportResult,
+		Proposal,
+	};
+	use sp_runtime::{generic::BlockId, traits::Header as _};
+	use substrate_test_runtime_client::{TestClient, runtime::{Block, Header}};
+	use crate::{Error, MiningBuild, MiningMetadata, Seal};
+
+	type Hash = <Block as BlockT>::Hash;
+
+	const VALID_SEAL: &[u8] = b"valid";
+
+	struct TestAlgorithm;
+
+	impl PowAlgorithm<Block> for TestAlgorithm {
+		type Difficulty = u128;
+
+		fn difficulty(&self, _parent: Hash) -> std::result::Result<u128, Error<Block>> {
+			Ok(1)
+		}
+
+		fn verify(
+			&self,
+			_parent: &BlockId<Block>,
+			_pre_hash: &Hash,
+			_pre_digest: Option<&[u8]>,
+			seal: &Seal,
+			_difficulty: u128,
+		) -> std::result::Result<bool, Error<Block>> {
+			Ok(seal == VALID_SEAL)
+		}
+	}
+
+	struct TestBlockImport;
+
+	impl BlockImport<Block> for TestBlockImport {
+		type Error = ConsensusError;
+		type Transaction = TransactionFor<TestClient, Block>;
+
+		fn check_block(
+			&mut self,
+			_block: BlockCheckParams<Block>,
+		) -> std::result::Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(false))
+		}
+
+		fn import_block(
+			&mut self,
+			_block: BlockImportParams<Block, Self::Transaction>,
+			_cache: HashMap<CacheKeyId, Vec<u8>>,
+		) -> std::result::Result<ImportResult, Self::Error> {
+			Ok(ImportResult::imported(true))
+		}
+	}
+
+	type TestWorker = MiningWorker<Block, TestAlgorithm, TestClient, ()>;
+
+	fn pre_hash() -> Hash {
+		[1; 32].into()
+	}
+
+	fn worker() -> Arc<Mutex<TestWorker>> {
+		let header = Header::new(
+			1,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		);
+		let storage_changes = StorageChanges::<StateBackendFor<TestClient, Block>, Block> {
+			main_storage_changes: Default::default(),
+			child_storage_changes: Default::default(),
+			offchain_storage_changes: Default::default(),
+			transaction: Default::default(),
+			transaction_storage_root: Default::default(),
+			changes_trie_transaction: None,
+			transaction_index_changes: Default::default(),
+		};
+		let build = MiningBuild {
+			metadata: MiningMetadata {
+				best_hash: Default::default(),
+				pre_hash: pre_hash(),
+				pre_runtime: None,
+				difficulty: 1,
+			},
+			proposal: Proposal {
+				block: Block::new(header, Vec::new()),
+				proof: (),
+				storage_changes,
+			},
+		};
+
+		Arc::new(Mutex::new(MiningWorker {
+			build: Some(build),
+			algorithm: TestAlgorithm,
+			block_import: Box::new(TestBlockImport),
+		}))
+	}
+
+	#[test]
+	fn submit_work_is_unsafe() {
+		let pow = Pow::new(worker(), DenyUnsafe::Yes);
+
+		assert!(pow.work().is_err());
+		assert!(pow.submit_work(pre_hash(), VALID_SEAL.to_vec().into()).is_err());
+	}
+
+	#[test]
+	fn invalid_seal_keeps_the_build() {
+		let worker = worker();
+		let pow = Pow::new(worker.clone(), DenyUnsafe::No);
+
+		assert_eq!(pow.submit_work(pre_hash(), b"invalid".to_vec().into()).unwrap(), false);
+		assert_eq!(pow.work().unwrap().map(|work| work.pre_hash), Some(pre_hash()));
+		assert!(worker.lock().metadata().is_some());
+	}
+
+	#[test]
+	fn seal_for_stale_work_is_rejected() {
+		let worker = worker();
+		let pow = Pow::new(worker.clone(), DenyUnsafe::No);
+
+		let stale_pre_hash = [2; 32].into();
+		assert_eq!(pow.submit_work(stale_pre_hash, VALID_SEAL.to_vec().into()).unwrap(), false);
+		assert!(worker.lock().metadata().is_some());
+	}
+
+	#[test]
+	fn valid_seal_imports_the_build() {
+		let worker = worker();
+		let pow = Pow::new(worker.clone(), DenyUnsafe::No);
+
+		assert_eq!(pow.submit_work(pre_hash(), VALID_SEAL.to_vec().into()).unwrap(), true);
+		assert!(worker.lock().metadata().is_none());
+	}
+}
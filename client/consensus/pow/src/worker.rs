@@ -94,8 +94,11 @@ impl<Block, Algorithm, C, Proof> MiningWorker<Block, Algorithm, C, Proof> where
 
 	/// Submit a mined seal. The seal will be validated again. Returns true if the submission is
 	/// successful.
+	///
+	/// The current build is only consumed once the seal was verified, an invalid seal leaves it
+	/// in place.
 	pub fn submit(&mut self, seal: Seal) -> bool {
-		if let Some(build) = self.build.take() {
+		if let Some(build) = self.build.as_ref() {
 			match self.algorithm.verify(
 				&BlockId::Hash(build.metadata.best_hash),
 				&build.metadata.pre_hash,
@@ -121,6 +124,7 @@ impl<Block, Algorithm, C, Proof> MiningWorker<Block, Algorithm, C, Proof> where
 				},
 			}
 
+			let build = self.build.take().expect("the build was checked to exist above; qed");
 			let seal = DigestItem::Seal(POW_ENGINE_ID, seal);
 			let (header, body) = build.proposal.block.deconstruct();
 
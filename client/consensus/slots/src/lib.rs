@@ -42,7 +42,7 @@ use sp_api::{ProvideRuntimeApi, ApiRef};
 use sp_arithmetic::traits::BaseArithmetic;
 use sp_consensus::{BlockImport, Proposer, SyncOracle, SelectChain, CanAuthorWith, SlotData};
 use sp_consensus_slots::Slot;
-use sp_inherents::{InherentData, InherentDataProviders};
+use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header, HashFor, NumberFor}
@@ -402,83 +402,103 @@ impl<B: BlockT, T: SimpleSlotWorker<B>> SlotWorker<B, <T::Proposer as Proposer<B
 	}
 }
 
-/// Slot compatible inherent data.
-pub trait SlotCompatible {
-	/// Extract timestamp and slot from inherent data.
-	fn extract_timestamp_and_slot(
-		&self,
-		inherent: &InherentData,
-	) -> Result<(u64, Slot, std::time::Duration), sp_consensus::Error>;
+/// A type that can provide the timestamp and slot of the inherent data providers of a slot based
+/// consensus engine.
+///
+/// It is implemented for tuples of inherent data providers whose first element derefs to the
+/// [`sp_timestamp::Timestamp`] and whose second element derefs to the [`Slot`].
+pub trait InherentDataProviderExt {
+	/// The current timestamp that will be found in the [`InherentData`](sp_inherents::InherentData).
+	fn timestamp(&self) -> sp_timestamp::Timestamp;
+
+	/// The current slot that will be found in the [`InherentData`](sp_inherents::InherentData).
+	fn slot(&self) -> Slot;
 }
 
+macro_rules! impl_inherent_data_provider_ext_tuple {
+	( T, S $(, $TN:ident)* $( , )?) => {
+		impl<T, S, $( $TN ),*>  InherentDataProviderExt for (T, S, $($TN),*)
+		where
+			T: Deref<Target = sp_timestamp::Timestamp>,
+			S: Deref<Target = Slot>,
+		{
+			fn timestamp(&self) -> sp_timestamp::Timestamp {
+				*self.0.deref()
+			}
+
+			fn slot(&self) -> Slot {
+				*self.1.deref()
+			}
+		}
+	}
+}
+
+impl_inherent_data_provider_ext_tuple!(T, S);
+impl_inherent_data_provider_ext_tuple!(T, S, A);
+impl_inherent_data_provider_ext_tuple!(T, S, A, B);
+impl_inherent_data_provider_ext_tuple!(T, S, A, B, C);
+impl_inherent_data_provider_ext_tuple!(T, S, A, B, C, D);
+
 /// Start a new slot worker.
 ///
 /// Every time a new slot is triggered, `worker.on_slot` is called and the future it returns is
 /// polled until completion, unless we are major syncing.
-pub fn start_slot_worker<B, C, W, T, SO, SC, CAW, Proof>(
+///
+/// The inherent data for every slot is created by the inherent data providers that
+/// `create_inherent_data_providers` creates on top of the current best block.
+pub async fn start_slot_worker<B, C, W, T, SO, CIDP, CAW, Proof>(
 	slot_duration: SlotDuration<T>,
 	client: C,
 	mut worker: W,
 	mut sync_oracle: SO,
-	inherent_data_providers: InherentDataProviders,
-	timestamp_extractor: SC,
+	create_inherent_data_providers: CIDP,
 	can_author_with: CAW,
-) -> impl Future<Output = ()>
+)
 where
 	B: BlockT,
 	C: SelectChain<B>,
 	W: SlotWorker<B, Proof>,
 	SO: SyncOracle + Send,
-	SC: SlotCompatible + Unpin,
 	T: SlotData + Clone,
+	CIDP: CreateInherentDataProviders<B, ()> + Send,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
 	CAW: CanAuthorWith<B> + Send,
 {
 	let SlotDuration(slot_duration) = slot_duration;
 
-	// rather than use a timer interval, we schedule our waits ourselves
-	Slots::<SC>::new(
-		slot_duration.slot_duration(),
-		inherent_data_providers,
-		timestamp_extractor,
-	).inspect_err(|e| debug!(target: "slots", "Faulty timer: {:?}", e))
-		.try_for_each(move |slot_info| {
-			// only propose when we are not syncing.
-			if sync_oracle.is_major_syncing() {
-				debug!(target: "slots", "Skipping proposal slot due to sync.");
-				return Either::Right(future::ready(Ok(())));
+	let mut slots = Slots::new(
+		Duration::from_millis(slot_duration.slot_duration()),
+		create_inherent_data_providers,
+		client,
+	);
+
+	loop {
+		let (slot_info, chain_head) = match slots.next_slot().await {
+			Ok(r) => r,
+			Err(e) => {
+				warn!(target: "slots", "Error while polling for next slot: {:?}", e);
+				return;
 			}
+		};
 
-			let slot = slot_info.slot;
-			let chain_head = match client.best_chain() {
-				Ok(x) => x,
-				Err(e) => {
-					warn!(target: "slots", "Unable to author block in slot {}. \
-					no best block header: {:?}", slot, e);
-					return Either::Right(future::ready(Ok(())));
-				}
-			};
+		// only propose when we are not syncing.
+		if sync_oracle.is_major_syncing() {
+			debug!(target: "slots", "Skipping proposal slot due to sync.");
+			continue;
+		}
 
-			if let Err(err) = can_author_with.can_author_with(&BlockId::Hash(chain_head.hash())) {
-				warn!(
-					target: "slots",
-					"Unable to author block in slot {},. `can_author_with` returned: {} \
-					Probably a node update is required!",
-					slot,
-					err,
-				);
-				Either::Right(future::ready(Ok(())))
-			} else {
-				Either::Left(
-					worker.on_slot(chain_head, slot_info)
-						.then(|_| future::ready(Ok(())))
-				)
-			}
-		}).then(|res| {
-			if let Err(err) = res {
-				warn!(target: "slots", "Slots stream terminated with an error: {:?}", err);
-			}
-			future::ready(())
-		})
+		if let Err(err) = can_author_with.can_author_with(&BlockId::Hash(chain_head.hash())) {
+			warn!(
+				target: "slots",
+				"Unable to author block in slot {},. `can_author_with` returned: {} \
+				Probably a node update is required!",
+				slot_info.slot,
+				err,
+			);
+		} else {
+			let _ = worker.on_slot(chain_head, slot_info).await;
+		}
+	}
 }
 
 /// A header which has been checked
@@ -20,12 +20,12 @@
 //!
 //! This is used instead of `futures_timer::Interval` because it was unreliable.
 
-use super::{SlotCompatible, Slot};
-use sp_consensus::Error;
-use futures::{prelude::*, task::Context, task::Poll};
-use sp_inherents::{InherentData, InherentDataProviders};
+use super::{Slot, InherentDataProviderExt};
+use sp_consensus::{Error, SelectChain};
+use sp_inherents::{InherentData, CreateInherentDataProviders, InherentDataProvider};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
-use std::{pin::Pin, time::{Duration, Instant}};
+use std::time::{Duration, Instant};
 use futures_timer::Delay;
 
 /// Returns current duration since unix epoch.
@@ -62,84 +62,108 @@ pub struct SlotInfo {
 }
 
 /// A stream that returns every time there is a new slot.
-pub(crate) struct Slots<SC> {
+pub(crate) struct Slots<Block, C, IDP> {
 	last_slot: Slot,
 	slot_duration: Duration,
 	inner_delay: Option<Delay>,
-	inherent_data_providers: InherentDataProviders,
-	timestamp_extractor: SC,
+	create_inherent_data_providers: IDP,
+	client: C,
+	_phantom: std::marker::PhantomData<Block>,
 }
 
-impl<SC> Slots<SC> {
+impl<Block, C, IDP> Slots<Block, C, IDP> {
 	/// Create a new `Slots` stream.
 	pub fn new(
-		slot_duration: u64,
-		inherent_data_providers: InherentDataProviders,
-		timestamp_extractor: SC,
+		slot_duration: Duration,
+		create_inherent_data_providers: IDP,
+		client: C,
 	) -> Self {
 		Slots {
 			last_slot: 0.into(),
-			slot_duration: Duration::from_millis(slot_duration),
+			slot_duration,
 			inner_delay: None,
-			inherent_data_providers,
-			timestamp_extractor,
+			create_inherent_data_providers,
+			client,
+			_phantom: Default::default(),
 		}
 	}
 }
 
-impl<SC: SlotCompatible> Stream for Slots<SC> {
-	type Item = Result<SlotInfo, Error>;
-
-	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+impl<Block, C, IDP> Slots<Block, C, IDP>
+where
+	Block: BlockT,
+	C: SelectChain<Block>,
+	IDP: CreateInherentDataProviders<Block, ()>,
+	IDP::InherentDataProviders: InherentDataProviderExt,
+{
+	/// Returns a future that fires when the next slot starts, together with the chain head the
+	/// slot should be claimed on.
+	pub async fn next_slot(&mut self) -> Result<(SlotInfo, Block::Header), Error> {
 		loop {
-			let slot_duration = self.slot_duration;
 			self.inner_delay = match self.inner_delay.take() {
 				None => {
 					// schedule wait.
-					let wait_dur = time_until_next(duration_now(), slot_duration);
+					let wait_dur = time_until_next(duration_now(), self.slot_duration);
 					Some(Delay::new(wait_dur))
 				}
 				Some(d) => Some(d),
 			};
 
-			if let Some(ref mut inner_delay) = self.inner_delay {
-				match Future::poll(Pin::new(inner_delay), cx) {
-					Poll::Pending => return Poll::Pending,
-					Poll::Ready(()) => {}
-				}
+			if let Some(inner_delay) = self.inner_delay.take() {
+				inner_delay.await;
 			}
-
 			// timeout has fired.
 
-			let inherent_data = match self.inherent_data_providers.create_inherent_data() {
-				Ok(id) => id,
-				Err(err) => return Poll::Ready(Some(Err(sp_consensus::Error::InherentData(err)))),
-			};
-			let result = self.timestamp_extractor.extract_timestamp_and_slot(&inherent_data);
-			let (timestamp, slot, offset) = match result {
-				Ok(v) => v,
-				Err(err) => return Poll::Ready(Some(Err(err))),
-			};
+			let ends_in = time_until_next(duration_now(), self.slot_duration);
+
 			// reschedule delay for next slot.
-			let ends_in = offset +
-				time_until_next(Duration::from_millis(timestamp), slot_duration);
-			let ends_at = Instant::now() + ends_in;
 			self.inner_delay = Some(Delay::new(ends_in));
 
+			let ends_at = Instant::now() + ends_in;
+
+			let chain_head = match self.client.best_chain() {
+				Ok(x) => x,
+				Err(e) => {
+					log::warn!(
+						target: "slots",
+						"Unable to author block in slot. No best block header: {:?}",
+						e,
+					);
+					// Let's try at the next slot..
+					continue;
+				}
+			};
+
+			let inherent_data_providers = self.create_inherent_data_providers
+				.create_inherent_data_providers(chain_head.hash(), ())
+				.await?;
+
+			if Instant::now() > ends_at {
+				log::warn!(
+					target: "slots",
+					"Creating inherent data providers took more time than we had left for the slot.",
+				);
+			}
+
+			let timestamp = inherent_data_providers.timestamp();
+			let slot = inherent_data_providers.slot();
+			let inherent_data = inherent_data_providers.create_inherent_data()?;
+
 			// never yield the same slot twice.
 			if slot > self.last_slot {
 				self.last_slot = slot;
 
-				break Poll::Ready(Some(Ok(SlotInfo {
-					slot,
-					duration: self.slot_duration,
-					timestamp,
-					ends_at,
-					inherent_data,
-				})))
+				break Ok((
+					SlotInfo {
+						slot,
+						duration: self.slot_duration,
+						timestamp: *timestamp,
+						ends_at,
+						inherent_data,
+					},
+					chain_head,
+				))
 			}
 		}
 	}
 }
-
-impl<SC> Unpin for Slots<SC> {}
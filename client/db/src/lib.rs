@@ -66,7 +66,7 @@ use codec::{Decode, Encode};
 use hash_db::Prefix;
 use sp_trie::{MemoryDB, PrefixedMemoryDB, prefixed_key};
 use sp_database::Transaction;
-use sp_core::{Hasher, ChangesTrieConfiguration};
+use sp_core::{Hasher, ChangesTrieConfiguration, hexdisplay::HexDisplay};
 use sp_core::offchain::OffchainOverlayedChange;
 use sp_core::storage::{well_known_keys, ChildInfo};
 use sp_arithmetic::traits::Saturating;
@@ -76,7 +76,7 @@ use sp_runtime::traits::{
 };
 use sp_state_machine::{
 	DBValue, ChangesTrieTransaction, ChangesTrieCacheAction, UsageInfo as StateUsageInfo,
-	StorageCollection, ChildStorageCollection, OffchainChangesCollection,
+	StorageCollection, ChildStorageCollection, OffchainChangesCollection, IndexOperation,
 	backend::Backend as StateBackend, StateMachineStats,
 };
 use crate::utils::{DatabaseType, Meta, meta_keys, read_db, read_meta};
@@ -108,6 +108,11 @@ const DB_HASH_LEN: usize = 32;
 /// Hash type that this backend uses for the database.
 pub type DbHash = [u8; DB_HASH_LEN];
 
+/// Reference counts of indexed transactions updated by a pending database transaction.
+///
+/// These take precedence over the counts already committed to the database.
+type IndexRefCounts = HashMap<Vec<u8>, u32>;
+
 /// A reference tracking state.
 ///
 /// It makes sure that the hash we are using stays pinned in storage
@@ -289,7 +294,8 @@ pub enum TransactionStorageMode {
 	/// Store block body as an encoded list of full transactions in the BODY column
 	BlockBody,
 	/// Store a list of hashes in the BODY column and each transaction individually
-	/// in the TRANSACTION column.
+	/// in the TRANSACTION column. Data indexed with the transaction index API is stored in
+	/// the TRANSACTION column as well, and kept for as long as any block referencing it is.
 	StorageChain,
 }
 
@@ -310,6 +316,19 @@ pub enum DatabaseSettingsSrc {
 		path: PathBuf,
 	},
 
+	/// Open whichever database already exists at the given paths, creating a new RocksDb
+	/// database if there is none.
+	///
+	/// Refuses to open anything if both databases exist.
+	Auto {
+		/// Path to the ParityDb database.
+		paritydb_path: PathBuf,
+		/// Path to the RocksDb database.
+		rocksdb_path: PathBuf,
+		/// Cache size in MiB. Used only by RocksDb.
+		cache_size: usize,
+	},
+
 	/// Use a custom already-open database.
 	Custom(Arc<dyn Database<DbHash>>),
 }
@@ -320,6 +339,13 @@ impl DatabaseSettingsSrc {
 		match self {
 			DatabaseSettingsSrc::RocksDb { path, .. } => Some(path.as_path()),
 			DatabaseSettingsSrc::ParityDb { path, .. } => Some(path.as_path()),
+			DatabaseSettingsSrc::Auto { paritydb_path, rocksdb_path, .. } => {
+				if utils::is_paritydb(paritydb_path) {
+					Some(paritydb_path.as_path())
+				} else {
+					Some(rocksdb_path.as_path())
+				}
+			},
 			DatabaseSettingsSrc::Custom(_) => None,
 		}
 	}
@@ -327,6 +353,7 @@ impl DatabaseSettingsSrc {
 	pub fn supports_ref_counting(&self) -> bool {
 		match self {
 			DatabaseSettingsSrc::ParityDb { .. } => true,
+			DatabaseSettingsSrc::Auto { paritydb_path, .. } => utils::is_paritydb(paritydb_path),
 			_ => false,
 		}
 	}
@@ -337,6 +364,7 @@ impl std::fmt::Display for DatabaseSettingsSrc {
 		let name = match self {
 			DatabaseSettingsSrc::RocksDb { .. } => "RocksDb",
 			DatabaseSettingsSrc::ParityDb { .. } => "ParityDb",
+			DatabaseSettingsSrc::Auto { .. } => "Auto",
 			DatabaseSettingsSrc::Custom(_) => "Custom",
 		};
 		write!(f, "{}", name)
@@ -359,6 +387,9 @@ pub(crate) mod columns {
 	pub const CACHE: u32 = 10;
 	/// Transactions
 	pub const TRANSACTION: u32 = 11;
+	/// Indexed transaction data. Maps block lookup keys to the list of data hashes indexed or
+	/// renewed in the block, and data hashes to their reference count.
+	pub const BODY_INDEX: u32 = 12;
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -398,6 +429,15 @@ pub struct BlockchainDb<Block: BlockT> {
 	header_metadata_cache: Arc<HeaderMetadataCache<Block>>,
 	header_cache: Mutex<LinkedHashMap<Block::Hash, Option<Block::Header>>>,
 	transaction_storage: TransactionStorageMode,
+	pinned_blocks: RwLock<HashMap<Block::Hash, PinnedBlock<Block>>>,
+}
+
+/// A block pinned through `Backend::pin_block`.
+struct PinnedBlock<Block: BlockT> {
+	/// Number of times the block is pinned.
+	refs: u32,
+	/// Body of the block, kept in memory once it is pruned from the database.
+	body: Option<Vec<Block::Extrinsic>>,
 }
 
 impl<Block: BlockT> BlockchainDb<Block> {
@@ -414,9 +454,54 @@ impl<Block: BlockT> BlockchainDb<Block> {
 			header_metadata_cache: Arc::new(HeaderMetadataCache::default()),
 			header_cache: Default::default(),
 			transaction_storage,
+			pinned_blocks: Default::default(),
 		})
 	}
 
+	/// Add a reference to the pinned block `hash`.
+	fn pin(&self, hash: Block::Hash) {
+		self.pinned_blocks.write()
+			.entry(hash)
+			.or_insert(PinnedBlock { refs: 0, body: None })
+			.refs += 1;
+	}
+
+	/// Remove a reference to the pinned block `hash`, releasing it with the last reference.
+	fn unpin(&self, hash: &Block::Hash) {
+		let mut pinned_blocks = self.pinned_blocks.write();
+		if let Some(pinned) = pinned_blocks.get_mut(hash) {
+			pinned.refs -= 1;
+			if pinned.refs == 0 {
+				pinned_blocks.remove(hash);
+			}
+		}
+	}
+
+	/// Keep the body of the block `hash` in memory if it is pinned, as it is about to be
+	/// pruned from the database.
+	fn note_pruned_body(&self, hash: Block::Hash) -> ClientResult<()> {
+		if !self.pinned_blocks.read().contains_key(&hash) {
+			return Ok(())
+		}
+		let body = self.body(BlockId::Hash(hash))?;
+		if let Some(pinned) = self.pinned_blocks.write().get_mut(&hash) {
+			pinned.body = body;
+		}
+		Ok(())
+	}
+
+	/// Body of a pinned block that was pruned from the database.
+	fn pinned_body(&self, id: BlockId<Block>) -> ClientResult<Option<Vec<Block::Extrinsic>>> {
+		let hash = match id {
+			BlockId::Hash(hash) => hash,
+			BlockId::Number(number) => match self.hash(number)? {
+				Some(hash) => hash,
+				None => return Ok(None),
+			},
+		};
+		Ok(self.pinned_blocks.read().get(&hash).and_then(|pinned| pinned.body.clone()))
+	}
+
 	fn update_meta(
 		&self,
 		hash: Block::Hash,
@@ -531,7 +616,7 @@ impl<Block: BlockT> sc_client_api::blockchain::Backend<Block> for BlockchainDb<B
 					}
 				}
 			}
-			None => Ok(None),
+			None => self.pinned_body(id),
 		}
 	}
 
@@ -580,6 +665,35 @@ impl<Block: BlockT> sc_client_api::blockchain::Backend<Block> for BlockchainDb<B
 	fn have_extrinsic(&self, hash: &Block::Hash) -> ClientResult<bool> {
 		Ok(self.db.contains(columns::TRANSACTION, hash.as_ref()))
 	}
+
+	fn indexed_transaction(&self, hash: &Block::Hash) -> ClientResult<Option<Vec<u8>>> {
+		if !self.db.contains(columns::BODY_INDEX, hash.as_ref()) {
+			return Ok(None)
+		}
+		Ok(self.db.get(columns::TRANSACTION, hash.as_ref()))
+	}
+
+	fn block_indexed_body(&self, id: BlockId<Block>) -> ClientResult<Option<Vec<Vec<u8>>>> {
+		match self.transaction_storage {
+			TransactionStorageMode::BlockBody => Ok(None),
+			TransactionStorageMode::StorageChain => {
+				let indexed = match read_db(&*self.db, columns::KEY_LOOKUP, columns::BODY_INDEX, id)? {
+					Some(indexed) => Vec::<Vec<u8>>::decode(&mut &indexed[..]).map_err(|err|
+						sp_blockchain::Error::Backend(format!("Error decoding body index: {}", err))
+					)?,
+					None => return Ok(None),
+				};
+				let transactions: ClientResult<Vec<Vec<u8>>> = indexed.into_iter().map(|hash|
+					self.db.get(columns::TRANSACTION, &hash).ok_or_else(||
+						sp_blockchain::Error::Backend(
+							format!("Missing indexed transaction {}", HexDisplay::from(&hash))
+						)
+					)
+				).collect();
+				Ok(Some(transactions?))
+			},
+		}
+	}
 }
 
 impl<Block: BlockT> sc_client_api::blockchain::ProvideCache<Block> for BlockchainDb<Block> {
@@ -672,6 +786,7 @@ pub struct BlockImportOperation<Block: BlockT> {
 	storage_updates: StorageCollection,
 	child_storage_updates: ChildStorageCollection,
 	offchain_storage_updates: OffchainChangesCollection,
+	index_ops: Vec<IndexOperation>,
 	changes_trie_updates: MemoryDB<HashFor<Block>>,
 	changes_trie_build_cache_update: Option<ChangesTrieCacheAction<Block::Hash, NumberFor<Block>>>,
 	changes_trie_config_update: Option<Option<ChangesTrieConfiguration>>,
@@ -680,6 +795,8 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	/// The state was replaced by [`sc_client_api::backend::BlockImportOperation::reset_storage`].
+	reset_storage: bool,
 }
 
 impl<Block: BlockT> BlockImportOperation<Block> {
@@ -771,6 +888,7 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		self.db_updates = transaction;
 		self.changes_trie_config_update = Some(changes_trie_config);
 		self.commit_state = true;
+		self.reset_storage = true;
 		Ok(root)
 	}
 
@@ -808,6 +926,11 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		Ok(())
 	}
 
+	fn update_transaction_index(&mut self, index_ops: Vec<IndexOperation>) -> ClientResult<()> {
+		self.index_ops = index_ops;
+		Ok(())
+	}
+
 	fn mark_finalized(
 		&mut self,
 		block: BlockId<Block>,
@@ -1021,6 +1144,13 @@ impl<Block: BlockT> Backend<Block> {
 		})
 	}
 
+	/// Expose the database used by this backend, together with the column storing the state.
+	///
+	/// Should only be needed for benchmarking.
+	pub fn expose_db(&self) -> (Arc<dyn Database<DbHash>>, sp_database::ColumnId) {
+		(self.storage.db.clone(), columns::STATE)
+	}
+
 	/// Handle setting head within a transaction. `route_to` should be the last
 	/// block that existed in the database. `best_to` should be the best block
 	/// to be set.
@@ -1114,6 +1244,7 @@ impl<Block: BlockT> Backend<Block> {
 		justification: Option<Justification>,
 		changes_trie_cache_ops: &mut Option<DbChangesTrieStorageTransaction<Block>>,
 		finalization_displaced: &mut Option<FinalizationDisplaced<Block::Hash, NumberFor<Block>>>,
+		index_ref_counts: &mut IndexRefCounts,
 	) -> ClientResult<(Block::Hash, <Block::Header as HeaderT>::Number, bool, bool)> {
 		// TODO: ensure best chain contains this block.
 		let number = *header.number();
@@ -1126,6 +1257,7 @@ impl<Block: BlockT> Backend<Block> {
 			*hash,
 			changes_trie_cache_ops,
 			finalization_displaced,
+			index_ref_counts,
 		)?;
 
 		if let Some(justification) = justification {
@@ -1163,6 +1295,11 @@ impl<Block: BlockT> Backend<Block> {
 						implies existence of blocks with all numbers before it; qed")
 			};
 
+			if !sc_client_api::Backend::have_state_at(self, &hash, new_canonical.saturated_into()) {
+				// The block was imported without state, there is nothing to canonicalize.
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
 			let commit = self.storage.state_db.canonicalize_block(&hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
@@ -1178,12 +1315,16 @@ impl<Block: BlockT> Backend<Block> {
 	) -> ClientResult<()> {
 		let mut transaction = Transaction::new();
 		let mut finalization_displaced_leaves = None;
+		let mut index_ref_counts = IndexRefCounts::new();
 
 		operation.apply_aux(&mut transaction);
 		operation.apply_offchain(&mut transaction);
 
 		let mut meta_updates = Vec::with_capacity(operation.finalized_blocks.len());
-		let mut last_finalized_hash = self.blockchain.meta.read().finalized_hash;
+		let (best_num, mut last_finalized_hash, last_finalized_num) = {
+			let meta = self.blockchain.meta.read();
+			(meta.best_number, meta.finalized_hash, meta.finalized_number)
+		};
 
 		let mut changes_trie_cache_ops = None;
 		for (block, justification) in operation.finalized_blocks {
@@ -1198,6 +1339,7 @@ impl<Block: BlockT> Backend<Block> {
 				justification,
 				&mut changes_trie_cache_ops,
 				&mut finalization_displaced_leaves,
+				&mut index_ref_counts,
 			)?);
 			last_finalized_hash = block_hash;
 		}
@@ -1210,6 +1352,11 @@ impl<Block: BlockT> Backend<Block> {
 			// blocks are keyed by number + hash.
 			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
 
+			// The header may already be present if only the state is imported for it.
+			let existing_header = number <= best_num &&
+				sc_client_api::blockchain::HeaderBackend::header(&self.blockchain, BlockId::Hash(hash))?
+					.is_some();
+
 			let (enacted, retracted) = if pending_block.leaf_state.is_best() {
 				self.set_head_with_transaction(&mut transaction, parent_hash, (number, hash))?
 			} else {
@@ -1238,6 +1385,13 @@ impl<Block: BlockT> Backend<Block> {
 							hashes.push(hash);
 						}
 						transaction.set_from_vec(columns::BODY, &lookup_key, hashes.encode());
+						self.apply_index_ops(
+							&mut transaction,
+							&mut index_ref_counts,
+							&lookup_key,
+							body,
+							std::mem::take(&mut operation.index_ops),
+						);
 					},
 				}
 			}
@@ -1312,6 +1466,14 @@ impl<Block: BlockT> Backend<Block> {
 				).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
 				apply_state_commit(&mut transaction, commit);
 
+				if operation.reset_storage && !number.is_zero() && number <= last_finalized_num {
+					// The state of an already finalized block was imported, canonicalize it
+					// right away.
+					let commit = self.storage.state_db.canonicalize_block(&hash)
+						.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+					apply_state_commit(&mut transaction, commit);
+				}
+
 				// Check if need to finalize. Genesis is always finalized instantly.
 				let finalized = number_u64 == 0 || pending_block.leaf_state.is_final();
 				finalized
@@ -1350,6 +1512,7 @@ impl<Block: BlockT> Backend<Block> {
 					hash,
 					&mut changes_trie_cache_ops,
 					&mut finalization_displaced_leaves,
+					&mut index_ref_counts,
 				)?;
 			} else {
 				// canonicalize blocks which are old enough, regardless of finality.
@@ -1358,29 +1521,31 @@ impl<Block: BlockT> Backend<Block> {
 
 			debug!(target: "db", "DB Commit {:?} ({}), best = {}", hash, number, is_best);
 
-			let displaced_leaf = {
+			let displaced_leaf = if existing_header {
+				None
+			} else {
 				let mut leaves = self.blockchain.leaves.write();
 				let displaced_leaf = leaves.import(hash, number, parent_hash);
 				leaves.prepare_transaction(&mut transaction, columns::META, meta_keys::LEAF_PREFIX);
 
+				let mut children = children::read_children(
+					&*self.storage.db,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+				)?;
+				children.push(hash);
+				children::write_children(
+					&mut transaction,
+					columns::META,
+					meta_keys::CHILDREN_PREFIX,
+					parent_hash,
+					children,
+				);
+
 				displaced_leaf
 			};
 
-			let mut children = children::read_children(
-				&*self.storage.db,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-			)?;
-			children.push(hash);
-			children::write_children(
-				&mut transaction,
-				columns::META,
-				meta_keys::CHILDREN_PREFIX,
-				parent_hash,
-				children,
-			);
-
 			meta_updates.push((hash, number, pending_block.leaf_state.is_best(), finalized));
 
 			Some((pending_block.header, number, hash, enacted, retracted, displaced_leaf, is_best, cache))
@@ -1465,14 +1630,18 @@ impl<Block: BlockT> Backend<Block> {
 		f_header: &Block::Header,
 		f_hash: Block::Hash,
 		changes_trie_cache_ops: &mut Option<DbChangesTrieStorageTransaction<Block>>,
-		displaced: &mut Option<FinalizationDisplaced<Block::Hash, NumberFor<Block>>>
+		displaced: &mut Option<FinalizationDisplaced<Block::Hash, NumberFor<Block>>>,
+		index_ref_counts: &mut IndexRefCounts,
 	) -> ClientResult<()> {
 		let f_num = f_header.number().clone();
 
-		if self.storage.state_db.best_canonical().map(|c| f_num.saturated_into::<u64>() > c).unwrap_or(true) {
-			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
-			transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
+		let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
+		transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
 
+		// Blocks imported without state (e.g. during state sync) have nothing to canonicalize.
+		if sc_client_api::Backend::have_state_at(self, &f_hash, f_num) &&
+			self.storage.state_db.best_canonical().map(|c| f_num.saturated_into::<u64>() > c).unwrap_or(true)
+		{
 			let commit = self.storage.state_db.canonicalize_block(&f_hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
 			apply_state_commit(transaction, commit);
@@ -1490,7 +1659,7 @@ impl<Block: BlockT> Backend<Block> {
 			}
 		}
 
-		self.prune_blocks(transaction, f_num)?;
+		self.prune_blocks(transaction, index_ref_counts, f_num)?;
 		let new_displaced = self.blockchain.leaves.write().finalize_height(f_num);
 		match displaced {
 			x @ &mut None => *x = Some(new_displaced),
@@ -1500,9 +1669,104 @@ impl<Block: BlockT> Backend<Block> {
 		Ok(())
 	}
 
+	/// Store data indexed by the given block and bump the reference count of renewed data.
+	fn apply_index_ops(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		index_ref_counts: &mut IndexRefCounts,
+		lookup_key: &[u8],
+		body: &[Block::Extrinsic],
+		index_ops: Vec<IndexOperation>,
+	) {
+		let mut indexed = Vec::with_capacity(index_ops.len());
+		let mut ref_counts = HashMap::new();
+		for op in index_ops {
+			match op {
+				IndexOperation::Insert { extrinsic, hash, size } => {
+					let extrinsic = match body.get(extrinsic as usize) {
+						Some(extrinsic) => extrinsic.encode(),
+						None => {
+							warn!(target: "db", "Indexed extrinsic #{} is missing from the block", extrinsic);
+							continue
+						},
+					};
+					let size = std::cmp::min(size as usize, extrinsic.len());
+					transaction.set(columns::TRANSACTION, &hash, &extrinsic[extrinsic.len() - size..]);
+					*ref_counts.entry(hash.clone()).or_insert(0u32) += 1;
+					indexed.push(hash);
+				},
+				IndexOperation::Renew { hash, .. } => {
+					let is_stored = match index_ref_counts.get(&hash) {
+						Some(count) => *count > 0,
+						None => self.storage.db.contains(columns::TRANSACTION, &hash),
+					};
+					if !is_stored && !ref_counts.contains_key(&hash) {
+						debug!(target: "db", "Renewed transaction data {} is not stored", HexDisplay::from(&hash));
+						continue
+					}
+					*ref_counts.entry(hash.clone()).or_insert(0u32) += 1;
+					indexed.push(hash);
+				},
+			}
+		}
+		if indexed.is_empty() {
+			return
+		}
+		for (hash, count) in ref_counts {
+			let count = self.index_ref_count(index_ref_counts, &hash) + count;
+			transaction.set_from_vec(columns::BODY_INDEX, &hash, count.encode());
+			index_ref_counts.insert(hash, count);
+		}
+		transaction.set_from_vec(columns::BODY_INDEX, lookup_key, indexed.encode());
+	}
+
+	/// Release data indexed by the given block, removing it once no block references it.
+	fn prune_index(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		index_ref_counts: &mut IndexRefCounts,
+		id: BlockId<Block>,
+	) -> ClientResult<()> {
+		let indexed = match read_db(&*self.storage.db, columns::KEY_LOOKUP, columns::BODY_INDEX, id)? {
+			Some(indexed) => Vec::<Vec<u8>>::decode(&mut &indexed[..]).map_err(|err|
+				sp_blockchain::Error::Backend(format!("Error decoding body index: {}", err))
+			)?,
+			None => return Ok(()),
+		};
+		utils::remove_from_db(transaction, &*self.storage.db, columns::KEY_LOOKUP, columns::BODY_INDEX, id)?;
+		let mut ref_counts = HashMap::new();
+		for hash in indexed {
+			*ref_counts.entry(hash).or_insert(0u32) += 1;
+		}
+		for (hash, count) in ref_counts {
+			let remaining = self.index_ref_count(index_ref_counts, &hash).saturating_sub(count);
+			match remaining {
+				0 => {
+					transaction.remove(columns::BODY_INDEX, &hash);
+					transaction.remove(columns::TRANSACTION, &hash);
+				},
+				remaining => transaction.set_from_vec(columns::BODY_INDEX, &hash, remaining.encode()),
+			}
+			index_ref_counts.insert(hash, remaining);
+		}
+		Ok(())
+	}
+
+	/// Reference count of the indexed data with the given hash, taking the changes of the pending
+	/// database transaction into account.
+	fn index_ref_count(&self, index_ref_counts: &IndexRefCounts, hash: &[u8]) -> u32 {
+		if let Some(count) = index_ref_counts.get(hash) {
+			return *count
+		}
+		self.storage.db.get(columns::BODY_INDEX, hash)
+			.and_then(|count| Decode::decode(&mut &count[..]).ok())
+			.unwrap_or(0)
+	}
+
 	fn prune_blocks(
 		&self,
 		transaction: &mut Transaction<DbHash>,
+		index_ref_counts: &mut IndexRefCounts,
 		finalized: NumberFor<Block>,
 	) -> ClientResult<()> {
 		if let KeepBlocks::Some(keep_blocks) = self.keep_blocks {
@@ -1515,6 +1779,9 @@ impl<Block: BlockT> Backend<Block> {
 			match read_db(&*self.storage.db, columns::KEY_LOOKUP, columns::BODY, BlockId::<Block>::number(number))? {
 				Some(body) => {
 					debug!(target: "db", "Removing block #{}", number);
+					if let Some(hash) = self.blockchain.hash(number)? {
+						self.blockchain.note_pruned_body(hash)?;
+					}
 					utils::remove_from_db(
 						transaction,
 						&*self.storage.db,
@@ -1535,6 +1802,11 @@ impl<Block: BlockT> Backend<Block> {
 									format!("Error decoding body list: {}", err)
 								)),
 							}
+							self.prune_index(
+								transaction,
+								index_ref_counts,
+								BlockId::<Block>::number(number),
+							)?;
 						}
 					}
 				}
@@ -1601,6 +1873,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			storage_updates: Default::default(),
 			child_storage_updates: Default::default(),
 			offchain_storage_updates: Default::default(),
+			index_ops: Default::default(),
 			changes_trie_config_update: None,
 			changes_trie_updates: MemoryDB::default(),
 			changes_trie_build_cache_update: None,
@@ -1608,6 +1881,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			reset_storage: false,
 		})
 	}
 
@@ -1661,6 +1935,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			justification,
 			&mut changes_trie_cache_ops,
 			&mut displaced,
+			&mut IndexRefCounts::new(),
 		)?;
 		self.storage.db.commit(transaction)?;
 		self.blockchain.update_meta(hash, number, is_best, is_finalized);
@@ -1906,6 +2181,19 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	fn get_import_lock(&self) -> &RwLock<()> {
 		&*self.import_lock
 	}
+
+	fn pin_block(&self, hash: &Block::Hash) -> ClientResult<()> {
+		self.storage.state_db.pin(hash).map_err(|_| sp_blockchain::Error::UnknownBlock(
+			format!("State already discarded for {:?}", hash)
+		))?;
+		self.blockchain.pin(*hash);
+		Ok(())
+	}
+
+	fn unpin_block(&self, hash: &Block::Hash) {
+		self.storage.state_db.unpin(hash);
+		self.blockchain.unpin(hash);
+	}
 }
 
 impl<Block: BlockT> sc_client_api::backend::LocalBackend<Block> for Backend<Block> {}
@@ -1959,6 +2247,18 @@ pub(crate) mod tests {
 		changes: Option<Vec<(Vec<u8>, Vec<u8>)>>,
 		extrinsics_root: H256,
 		body: Vec<ExtrinsicWrapper<u64>>,
+	) -> H256 {
+		insert_block_with_index(backend, number, parent_hash, changes, extrinsics_root, body, Vec::new())
+	}
+
+	pub fn insert_block_with_index(
+		backend: &Backend<Block>,
+		number: u64,
+		parent_hash: H256,
+		changes: Option<Vec<(Vec<u8>, Vec<u8>)>>,
+		extrinsics_root: H256,
+		body: Vec<ExtrinsicWrapper<u64>>,
+		index: Vec<IndexOperation>,
 	) -> H256 {
 		use sp_runtime::testing::Digest;
 
@@ -1987,6 +2287,7 @@ pub(crate) mod tests {
 		backend.begin_state_operation(&mut op, block_id).unwrap();
 		op.set_block_data(header, Some(body), None, NewBlockState::Best).unwrap();
 		op.update_changes_trie((changes_trie_update, ChangesTrieCacheAction::Clear)).unwrap();
+		op.update_transaction_index(index).unwrap();
 		backend.commit_operation(op).unwrap();
 
 		header_hash
@@ -2613,4 +2914,145 @@ pub(crate) mod tests {
 			assert_eq!(Some(vec![4.into()]), bc.body(BlockId::hash(blocks[4])).unwrap());
 		}
 	}
+
+	#[test]
+	fn pinned_blocks_survive_pruning() {
+		for storage in &[TransactionStorageMode::BlockBody, TransactionStorageMode::StorageChain] {
+			let backend = Backend::<Block>::new_test_with_tx_storage(2, 0, *storage);
+			let mut blocks = Vec::new();
+			let mut prev_hash = Default::default();
+			for i in 0 .. 5 {
+				let hash = insert_block(&backend, i, prev_hash, None, Default::default(), vec![i.into()]);
+				if i == 1 {
+					backend.pin_block(&hash).unwrap();
+				}
+				blocks.push(hash);
+				prev_hash = hash;
+			}
+
+			{
+				let mut op = backend.begin_operation().unwrap();
+				backend.begin_state_operation(&mut op, BlockId::Hash(blocks[4])).unwrap();
+				for i in 1 .. 5 {
+					op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+				}
+				backend.commit_operation(op).unwrap();
+			}
+			let bc = backend.blockchain();
+			assert_eq!(None, bc.body(BlockId::hash(blocks[0])).unwrap());
+			assert_eq!(Some(vec![1.into()]), bc.body(BlockId::hash(blocks[1])).unwrap());
+			assert_eq!(Some(vec![1.into()]), bc.body(BlockId::number(1)).unwrap());
+			assert!(backend.state_at(BlockId::hash(blocks[1])).is_ok());
+
+			backend.unpin_block(&blocks[1]);
+			assert_eq!(None, bc.body(BlockId::hash(blocks[1])).unwrap());
+		}
+	}
+
+	#[test]
+	fn indexed_data_is_released_with_last_reference() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(2, 0, TransactionStorageMode::StorageChain);
+		let x0 = ExtrinsicWrapper::from(0u64).encode();
+		let x0_hash = <HashFor<Block> as sp_core::Hasher>::hash(&x0[1..]);
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		for i in 0 .. 6 {
+			let index = match i {
+				1 => vec![IndexOperation::Insert {
+					extrinsic: 0,
+					hash: x0_hash.as_ref().to_vec(),
+					size: (x0.len() - 1) as u32,
+				}],
+				3 => vec![IndexOperation::Renew {
+					extrinsic: 0,
+					hash: x0_hash.as_ref().to_vec(),
+				}],
+				_ => Vec::new(),
+			};
+			let hash = insert_block_with_index(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				index,
+			);
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		let finalize = |range: std::ops::Range<usize>, head: usize| {
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(blocks[head])).unwrap();
+			for i in range {
+				op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+			}
+			backend.commit_operation(op).unwrap();
+		};
+
+		let bc = backend.blockchain();
+		assert_eq!(bc.indexed_transaction(&x0_hash).unwrap().unwrap(), &x0[1..]);
+		// Block 1 is pruned, but the data is still referenced by block 3.
+		finalize(1 .. 5, 4);
+		assert_eq!(None, bc.body(BlockId::hash(blocks[1])).unwrap());
+		assert_eq!(bc.indexed_transaction(&x0_hash).unwrap().unwrap(), &x0[1..]);
+		// Block 3 is pruned as well.
+		finalize(5 .. 6, 5);
+		assert_eq!(None, bc.body(BlockId::hash(blocks[3])).unwrap());
+		assert_eq!(None, bc.indexed_transaction(&x0_hash).unwrap());
+	}
+
+	#[test]
+	fn indexed_data_references_are_counted_within_one_commit() {
+		let backend = Backend::<Block>::new_test_with_tx_storage(2, 0, TransactionStorageMode::StorageChain);
+		let x0 = ExtrinsicWrapper::from(0u64).encode();
+		let x0_hash = <HashFor<Block> as sp_core::Hasher>::hash(&x0[1..]);
+		let mut blocks = Vec::new();
+		let mut prev_hash = Default::default();
+		for i in 0 .. 8 {
+			let index = match i {
+				1 => vec![IndexOperation::Insert {
+					extrinsic: 0,
+					hash: x0_hash.as_ref().to_vec(),
+					size: (x0.len() - 1) as u32,
+				}],
+				2 | 3 => vec![IndexOperation::Renew {
+					extrinsic: 0,
+					hash: x0_hash.as_ref().to_vec(),
+				}],
+				_ => Vec::new(),
+			};
+			let hash = insert_block_with_index(
+				&backend,
+				i,
+				prev_hash,
+				None,
+				Default::default(),
+				vec![i.into()],
+				index,
+			);
+			blocks.push(hash);
+			prev_hash = hash;
+		}
+
+		let finalize = |range: std::ops::Range<usize>, head: usize| {
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(blocks[head])).unwrap();
+			for i in range {
+				op.mark_finalized(BlockId::Hash(blocks[i]), None).unwrap();
+			}
+			backend.commit_operation(op).unwrap();
+		};
+
+		let bc = backend.blockchain();
+		// Blocks 1 and 2 are pruned in the same commit, block 3 still references the data.
+		finalize(1 .. 5, 4);
+		assert_eq!(None, bc.body(BlockId::hash(blocks[2])).unwrap());
+		assert_eq!(bc.indexed_transaction(&x0_hash).unwrap().unwrap(), &x0[1..]);
+		// The last reference goes away with block 3.
+		finalize(5 .. 6, 5);
+		assert_eq!(None, bc.body(BlockId::hash(blocks[3])).unwrap());
+		assert_eq!(None, bc.indexed_transaction(&x0_hash).unwrap());
+	}
 }
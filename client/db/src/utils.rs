@@ -37,7 +37,7 @@ use crate::{DatabaseSettings, DatabaseSettingsSrc, Database, DbHash};
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
 #[cfg(any(feature = "with-kvdb-rocksdb", feature = "with-parity-db", feature = "test-helpers", test))]
-pub const NUM_COLUMNS: u32 = 12;
+pub const NUM_COLUMNS: u32 = 13;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: u32 = 0;
 
@@ -212,77 +212,22 @@ pub fn open_database<Block: BlockT>(
 	config: &DatabaseSettings,
 	db_type: DatabaseType,
 ) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
-	#[allow(unused)]
-	fn db_open_error(feat: &'static str) -> sp_blockchain::Error {
-		sp_blockchain::Error::Backend(
-			format!("`{}` feature not enabled, database can not be opened", feat),
-		)
-	}
-
 	let db: Arc<dyn Database<DbHash>> = match &config.source {
-		#[cfg(any(feature = "with-kvdb-rocksdb", test))]
-		DatabaseSettingsSrc::RocksDb { path, cache_size } => {
-			// first upgrade database to required version
-			crate::upgrade::upgrade_db::<Block>(&path, db_type)?;
-
-			// and now open database assuming that it has the latest version
-			let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
-			let path = path.to_str()
-				.ok_or_else(|| sp_blockchain::Error::Backend("Invalid database path".into()))?;
-
-			let mut memory_budget = std::collections::HashMap::new();
-			match db_type {
-				DatabaseType::Full => {
-					let state_col_budget = (*cache_size as f64 * 0.9) as usize;
-					let other_col_budget = (cache_size - state_col_budget) / (NUM_COLUMNS as usize - 1);
-
-					for i in 0..NUM_COLUMNS {
-						if i == crate::columns::STATE {
-							memory_budget.insert(i, state_col_budget);
-						} else {
-							memory_budget.insert(i, other_col_budget);
-						}
-					}
-					log::trace!(
-						target: "db",
-						"Open RocksDB database at {}, state column budget: {} MiB, others({}) column cache: {} MiB",
-						path,
-						state_col_budget,
-						NUM_COLUMNS,
-						other_col_budget,
-					);
-				},
-				DatabaseType::Light => {
-					let col_budget = cache_size / (NUM_COLUMNS as usize);
-					for i in 0..NUM_COLUMNS {
-						memory_budget.insert(i, col_budget);
-					}
-					log::trace!(
-						target: "db",
-						"Open RocksDB light database at {}, column cache: {} MiB",
-						path,
-						col_budget,
-					);
-				}
+		DatabaseSettingsSrc::RocksDb { path, cache_size } =>
+			open_kvdb_rocksdb::<Block>(path, db_type, *cache_size)?,
+		DatabaseSettingsSrc::ParityDb { path } => open_parity_db(path, db_type)?,
+		DatabaseSettingsSrc::Auto { paritydb_path, rocksdb_path, cache_size } => {
+			match (is_paritydb(paritydb_path), is_rocksdb(rocksdb_path)) {
+				(true, true) => return Err(sp_blockchain::Error::Backend(format!(
+					"Found both a ParityDb database at {} and a RocksDb database at {}. \
+					Refusing to pick one, please remove the one that is not in use or \
+					select the database explicitly with `--database`.",
+					paritydb_path.display(),
+					rocksdb_path.display(),
+				))),
+				(true, false) => open_parity_db(paritydb_path, db_type)?,
+				(false, _) => open_kvdb_rocksdb::<Block>(rocksdb_path, db_type, *cache_size)?,
 			}
-			db_config.memory_budget = memory_budget;
-
-			let db = kvdb_rocksdb::Database::open(&db_config, &path)
-				.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
-			sp_database::as_database(db)
-		},
-		#[cfg(not(any(feature = "with-kvdb-rocksdb", test)))]
-		DatabaseSettingsSrc::RocksDb { .. } => {
-			return Err(db_open_error("with-kvdb-rocksdb"));
-		},
-		#[cfg(feature = "with-parity-db")]
-		DatabaseSettingsSrc::ParityDb { path } => {
-			crate::parity_db::open(&path, db_type)
-				.map_err(|e| sp_blockchain::Error::Backend(format!("{:?}", e)))?
-		},
-		#[cfg(not(feature = "with-parity-db"))]
-		DatabaseSettingsSrc::ParityDb { .. } => {
-			return Err(db_open_error("with-parity-db"))
 		},
 		DatabaseSettingsSrc::Custom(db) => db.clone(),
 	};
@@ -292,6 +237,106 @@ pub fn open_database<Block: BlockT>(
 	Ok(db)
 }
 
+/// Whether `path` contains a RocksDb database, i.e. the `CURRENT` file that RocksDb keeps
+/// next to its data.
+pub(crate) fn is_rocksdb(path: &std::path::Path) -> bool {
+	path.join("CURRENT").is_file()
+}
+
+/// Whether `path` contains a ParityDb database, i.e. the `metadata` file of ParityDb.
+pub(crate) fn is_paritydb(path: &std::path::Path) -> bool {
+	path.join("metadata").is_file()
+}
+
+#[allow(unused)]
+fn db_open_error(feat: &'static str) -> sp_blockchain::Error {
+	sp_blockchain::Error::Backend(
+		format!("`{}` feature not enabled, database can not be opened", feat),
+	)
+}
+
+#[cfg(any(feature = "with-kvdb-rocksdb", test))]
+fn open_kvdb_rocksdb<Block: BlockT>(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	// first upgrade database to required version
+	crate::upgrade::upgrade_db::<Block>(&path, db_type)?;
+
+	// and now open database assuming that it has the latest version
+	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+	let path = path.to_str()
+		.ok_or_else(|| sp_blockchain::Error::Backend("Invalid database path".into()))?;
+
+	let mut memory_budget = std::collections::HashMap::new();
+	match db_type {
+		DatabaseType::Full => {
+			let state_col_budget = (cache_size as f64 * 0.9) as usize;
+			let other_col_budget = (cache_size - state_col_budget) / (NUM_COLUMNS as usize - 1);
+
+			for i in 0..NUM_COLUMNS {
+				if i == crate::columns::STATE {
+					memory_budget.insert(i, state_col_budget);
+				} else {
+					memory_budget.insert(i, other_col_budget);
+				}
+			}
+			log::trace!(
+				target: "db",
+				"Open RocksDB database at {}, state column budget: {} MiB, others({}) column cache: {} MiB",
+				path,
+				state_col_budget,
+				NUM_COLUMNS,
+				other_col_budget,
+			);
+		},
+		DatabaseType::Light => {
+			let col_budget = cache_size / (NUM_COLUMNS as usize);
+			for i in 0..NUM_COLUMNS {
+				memory_budget.insert(i, col_budget);
+			}
+			log::trace!(
+				target: "db",
+				"Open RocksDB light database at {}, column cache: {} MiB",
+				path,
+				col_budget,
+			);
+		}
+	}
+	db_config.memory_budget = memory_budget;
+
+	let db = kvdb_rocksdb::Database::open(&db_config, &path)
+		.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
+	Ok(sp_database::as_database(db))
+}
+
+#[cfg(not(any(feature = "with-kvdb-rocksdb", test)))]
+fn open_kvdb_rocksdb<Block: BlockT>(
+	_path: &std::path::Path,
+	_db_type: DatabaseType,
+	_cache_size: usize,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	Err(db_open_error("with-kvdb-rocksdb"))
+}
+
+#[cfg(feature = "with-parity-db")]
+fn open_parity_db(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	crate::parity_db::open(path, db_type)
+		.map_err(|e| sp_blockchain::Error::Backend(format!("{:?}", e)))
+}
+
+#[cfg(not(feature = "with-parity-db"))]
+fn open_parity_db(
+	_path: &std::path::Path,
+	_db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	Err(db_open_error("with-parity-db"))
+}
+
 /// Check database type.
 pub fn check_database_type(db: &dyn Database<DbHash>, db_type: DatabaseType) -> sp_blockchain::Result<()> {
 	match db.get(COLUMN_META, meta_keys::TYPE) {
@@ -469,4 +514,57 @@ mod tests {
 		assert_eq!(DatabaseType::Full.as_str(), "full");
 		assert_eq!(DatabaseType::Light.as_str(), "light");
 	}
+
+	fn auto_settings(base_path: &std::path::Path) -> DatabaseSettings {
+		DatabaseSettings {
+			state_cache_size: 0,
+			state_cache_child_ratio: None,
+			state_pruning: sc_state_db::PruningMode::ArchiveAll,
+			source: DatabaseSettingsSrc::Auto {
+				paritydb_path: base_path.join("paritydb"),
+				rocksdb_path: base_path.join("db"),
+				cache_size: 128,
+			},
+			keep_blocks: crate::KeepBlocks::All,
+			transaction_storage: crate::TransactionStorageMode::BlockBody,
+		}
+	}
+
+	#[test]
+	fn auto_database_defaults_to_rocksdb() {
+		let base_path = tempfile::TempDir::new().unwrap();
+		let settings = auto_settings(base_path.path());
+
+		open_database::<Block>(&settings, DatabaseType::Full).unwrap();
+
+		assert!(base_path.path().join("db").exists());
+		assert!(!base_path.path().join("paritydb").exists());
+		assert_eq!(settings.source.path(), Some(base_path.path().join("db").as_path()));
+	}
+
+	#[test]
+	fn auto_database_ignores_empty_directories() {
+		let base_path = tempfile::TempDir::new().unwrap();
+		std::fs::create_dir_all(base_path.path().join("db")).unwrap();
+		std::fs::create_dir_all(base_path.path().join("paritydb")).unwrap();
+		let settings = auto_settings(base_path.path());
+
+		assert_eq!(settings.source.path(), Some(base_path.path().join("db").as_path()));
+		assert!(!settings.source.supports_ref_counting());
+		open_database::<Block>(&settings, DatabaseType::Full).unwrap();
+
+		assert!(is_rocksdb(&base_path.path().join("db")));
+		assert!(!is_paritydb(&base_path.path().join("paritydb")));
+	}
+
+	#[test]
+	fn auto_database_refuses_to_mix_databases() {
+		let base_path = tempfile::TempDir::new().unwrap();
+		for (dir, marker) in &[("db", "CURRENT"), ("paritydb", "metadata")] {
+			std::fs::create_dir_all(base_path.path().join(dir)).unwrap();
+			std::fs::write(base_path.path().join(dir).join(marker), b"").unwrap();
+		}
+
+		assert!(open_database::<Block>(&auto_settings(base_path.path()), DatabaseType::Full).is_err());
+	}
 }
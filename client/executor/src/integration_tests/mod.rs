@@ -185,6 +185,20 @@ fn panicking_should_work(wasm_method: WasmExecutionMethod) {
 	assert!(output.is_err());
 }
 
+test_wasm_execution!(panic_message_is_reported);
+fn panic_message_is_reported(wasm_method: WasmExecutionMethod) {
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+
+	let error = call_in_wasm(
+		"test_panic",
+		&[],
+		wasm_method,
+		&mut ext,
+	).unwrap_err();
+	assert!(error.starts_with("Runtime panicked: panicked at 'test panic'"), "{}", error);
+}
+
 test_wasm_execution!(storage_should_work);
 fn storage_should_work(wasm_method: WasmExecutionMethod) {
 	let mut ext = TestExternalities::default();
@@ -669,6 +683,75 @@ fn parallel_execution(wasm_method: WasmExecutionMethod) {
 	}
 }
 
+test_wasm_execution!(instance_pool_reports_hits_and_misses);
+fn instance_pool_reports_hits_and_misses(wasm_method: WasmExecutionMethod) {
+	let registry = prometheus_endpoint::Registry::new();
+	let executor = crate::WasmExecutor::new(
+		wasm_method,
+		Some(1024),
+		HostFunctions::host_functions(),
+		8,
+		None,
+	).with_prometheus_registry(Some(&registry));
+	let code_hash = blake2_256(wasm_binary_unwrap()).to_vec();
+
+	let mut ext = TestExternalities::default();
+	let mut ext = ext.ext();
+	for _ in 0..3 {
+		executor.call_in_wasm(
+			&wasm_binary_unwrap()[..],
+			Some(code_hash.clone()),
+			"test_empty_return",
+			&[],
+			&mut ext,
+			sp_core::traits::MissingHostFunctions::Allow,
+		).unwrap();
+	}
+
+	let counter = |name: &str| registry.gather()
+		.iter()
+		.find(|family| family.get_name() == name)
+		.map(|family| family.get_metric()[0].get_counter().get_value())
+		.unwrap();
+	// The first call instantiates the runtime, the following calls reuse that instance.
+	assert_eq!(counter("executor_instance_pool_misses"), 1.0);
+	assert_eq!(counter("executor_instance_pool_hits"), 2.0);
+	assert_eq!(counter("executor_instance_pool_exhausted"), 0.0);
+}
+
+#[test]
+#[cfg(feature = "wasmtime")]
+fn compiled_runtime_is_cached_on_disk() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let executor = crate::WasmExecutor::new(
+		WasmExecutionMethod::Compiled,
+		Some(1024),
+		HostFunctions::host_functions(),
+		8,
+		None,
+	).with_cache_path(Some(cache_dir.path().to_path_buf()));
+
+	let mut ext = TestExternalities::default();
+	executor.call_in_wasm(
+		&wasm_binary_unwrap()[..],
+		Some(blake2_256(wasm_binary_unwrap()).to_vec()),
+		"test_empty_return",
+		&[],
+		&mut ext.ext(),
+		sp_core::traits::MissingHostFunctions::Allow,
+	).unwrap();
+
+	fn files(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+		std::fs::read_dir(dir).unwrap()
+			.map(|entry| entry.unwrap().path())
+			.flat_map(|path| if path.is_dir() { files(&path) } else { vec![path] })
+			.collect()
+	}
+	// Besides the cache config, the cache holds the compiled runtime.
+	let cached = files(&cache_dir.path().join("wasmtime"));
+	assert!(cached.iter().any(|file| !file.ends_with("cache-config.toml")));
+}
+
 test_wasm_execution!(wasm_tracing_should_work);
 fn wasm_tracing_should_work(wasm_method: WasmExecutionMethod) {
 
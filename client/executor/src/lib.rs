@@ -34,6 +34,7 @@
 #[macro_use]
 mod native_executor;
 mod wasm_runtime;
+mod metrics;
 #[cfg(test)]
 mod integration_tests;
 
@@ -0,0 +1,60 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the runtime instance pool.
+
+use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+
+/// Instance pool metrics.
+#[derive(Clone)]
+pub(crate) struct Metrics {
+	/// Number of calls served by a cached runtime instance.
+	pub instance_pool_hits: Counter<U64>,
+	/// Number of calls that had to create a new instance for a free pool slot.
+	pub instance_pool_misses: Counter<U64>,
+	/// Number of calls that found every pool slot busy and used a throwaway instance.
+	pub instance_pool_exhausted: Counter<U64>,
+}
+
+impl Metrics {
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			instance_pool_hits: register(
+				Counter::new(
+					"executor_instance_pool_hits",
+					"Number of runtime calls served by a cached runtime instance",
+				)?,
+				registry,
+			)?,
+			instance_pool_misses: register(
+				Counter::new(
+					"executor_instance_pool_misses",
+					"Number of runtime calls that created a new instance in a free pool slot",
+				)?,
+				registry,
+			)?,
+			instance_pool_exhausted: register(
+				Counter::new(
+					"executor_instance_pool_exhausted",
+					"Number of runtime calls that found all pooled instances busy",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
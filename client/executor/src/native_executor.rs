@@ -19,6 +19,7 @@
 use crate::{
 	RuntimeInfo, error::{Error, Result},
 	wasm_runtime::{RuntimeCache, WasmExecutionMethod},
+	metrics::Metrics,
 };
 
 use std::{
@@ -43,6 +44,7 @@ use sp_wasm_interface::{HostFunctions, Function};
 use sc_executor_common::wasm_runtime::{WasmInstance, WasmModule, InvokeMethod};
 use sp_externalities::ExternalitiesExt as _;
 use sp_tasks::new_async_externalities;
+use prometheus_endpoint::Registry;
 
 /// Default num of pages for the heap
 const DEFAULT_HEAP_PAGES: u64 = 1024;
@@ -106,6 +108,8 @@ pub struct WasmExecutor {
 	/// The path to a directory which the executor can leverage for a file cache, e.g. put there
 	/// compiled artifacts.
 	cache_path: Option<PathBuf>,
+	/// Instance pool metrics, if a Prometheus registry was provided.
+	metrics: Option<Metrics>,
 }
 
 impl WasmExecutor {
@@ -140,9 +144,47 @@ impl WasmExecutor {
 			cache: Arc::new(RuntimeCache::new(max_runtime_instances, cache_path.clone())),
 			max_runtime_instances,
 			cache_path,
+			metrics: None,
 		}
 	}
 
+	/// Report runtime instance pool hits and misses to the given Prometheus `registry`.
+	///
+	/// This replaces the runtime cache, so it should be called before the executor is used.
+	pub fn with_prometheus_registry(mut self, registry: Option<&Registry>) -> Self {
+		self.metrics = registry.and_then(|registry|
+			Metrics::register(registry)
+				.map_err(|err| log::warn!(
+					target: "wasm-runtime",
+					"Failed to register executor prometheus metrics: {}",
+					err,
+				))
+				.ok()
+		);
+		self.reset_cache();
+		self
+	}
+
+	/// Store compiled runtime artifacts under `cache_path`, so that they survive restarts.
+	///
+	/// Only the compiled execution method makes use of this. The cached artifacts are keyed by
+	/// the hash of the runtime code and the compiler version, so a runtime is only recompiled
+	/// when either of them changes.
+	///
+	/// This replaces the runtime cache, so it should be called before the executor is used.
+	pub fn with_cache_path(mut self, cache_path: Option<PathBuf>) -> Self {
+		self.cache_path = cache_path;
+		self.reset_cache();
+		self
+	}
+
+	fn reset_cache(&mut self) {
+		self.cache = Arc::new(
+			RuntimeCache::new(self.max_runtime_instances, self.cache_path.clone())
+				.with_metrics(self.metrics.clone())
+		);
+	}
+
 	/// Execute the given closure `f` with the latest runtime (based on `runtime_code`).
 	///
 	/// The closure `f` is expected to return `Err(_)` when there happened a `panic!` in native code
@@ -292,6 +334,20 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			wasm: wasm_executor,
 		}
 	}
+
+	/// Report runtime instance pool hits and misses to the given Prometheus `registry`.
+	pub fn with_prometheus_registry(mut self, registry: Option<&Registry>) -> Self {
+		self.wasm = self.wasm.with_prometheus_registry(registry);
+		self
+	}
+
+	/// Store compiled runtime artifacts of the fallback Wasm executor under `cache_path`.
+	///
+	/// See [`WasmExecutor::with_cache_path`] for details.
+	pub fn with_cache_path(mut self, cache_path: Option<PathBuf>) -> Self {
+		self.wasm = self.wasm.with_cache_path(cache_path);
+		self
+	}
 }
 
 impl<D: NativeExecutionDispatch> RuntimeInfo for NativeExecutor<D> {
@@ -30,6 +30,7 @@ use sp_version::RuntimeVersion;
 use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
 use sc_executor_common::wasm_runtime::{WasmModule, WasmInstance};
+use crate::metrics::Metrics;
 
 use sp_wasm_interface::Function;
 
@@ -70,6 +71,7 @@ impl VersionedRuntime {
 	fn with_instance<'c, R, F>(
 		&self,
 		ext: &mut dyn Externalities,
+		metrics: Option<&Metrics>,
 		f: F,
 	) -> Result<R, Error>
 		where F: FnOnce(
@@ -91,6 +93,14 @@ impl VersionedRuntime {
 					.map(|r| Ok((r, false)))
 					.unwrap_or_else(|| self.module.new_instance().map(|i| (i, true)))?;
 
+				if let Some(metrics) = metrics {
+					if new_inst {
+						metrics.instance_pool_misses.inc();
+					} else {
+						metrics.instance_pool_hits.inc();
+					}
+				}
+
 				let result = f(&self.module, &*instance, self.version.as_ref(), ext);
 				if let Err(e) = &result {
 					if new_inst {
@@ -123,6 +133,9 @@ impl VersionedRuntime {
 			},
 			None => {
 				log::warn!(target: "wasm-runtime", "Ran out of free WASM instances");
+				if let Some(metrics) = metrics {
+					metrics.instance_pool_exhausted.inc();
+				}
 
 				// Allocate a new instance
 				let instance = self.module.new_instance()?;
@@ -154,6 +167,8 @@ pub struct RuntimeCache {
 	/// The size of the instances cache for each runtime.
 	max_runtime_instances: usize,
 	cache_path: Option<PathBuf>,
+	/// Instance pool metrics, if a Prometheus registry was provided.
+	metrics: Option<Metrics>,
 }
 
 impl RuntimeCache {
@@ -169,9 +184,16 @@ impl RuntimeCache {
 			runtimes: Default::default(),
 			max_runtime_instances,
 			cache_path,
+			metrics: None,
 		}
 	}
 
+	/// Report instance pool hits and misses to the given `metrics`.
+	pub(crate) fn with_metrics(mut self, metrics: Option<Metrics>) -> Self {
+		self.metrics = metrics;
+		self
+	}
+
 	/// Prepares a WASM module instance and executes given function for it.
 	///
 	/// This uses internal cache to find avaiable instance or create a new one.
@@ -270,7 +292,7 @@ impl RuntimeCache {
 		}
 		drop(runtimes);
 
-		Ok(runtime.with_instance(ext, f))
+		Ok(runtime.with_instance(ext, self.metrics.as_ref(), f))
 	}
 }
 
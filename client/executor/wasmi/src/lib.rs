@@ -46,6 +46,7 @@ struct FunctionExecutor<'a> {
 	host_functions: &'a [&'static dyn Function],
 	allow_missing_func_imports: bool,
 	missing_functions: &'a [String],
+	panic_message: Option<String>,
 }
 
 impl<'a> FunctionExecutor<'a> {
@@ -65,6 +66,7 @@ impl<'a> FunctionExecutor<'a> {
 			host_functions,
 			allow_missing_func_imports,
 			missing_functions,
+			panic_message: None,
 		})
 	}
 }
@@ -124,6 +126,10 @@ impl<'a> FunctionContext for FunctionExecutor<'a> {
 	fn sandbox(&mut self) -> &mut dyn Sandbox {
 		self
 	}
+
+	fn register_panic_error_message(&mut self, message: &str) {
+		self.panic_message = Some(message.to_owned());
+	}
 }
 
 impl<'a> Sandbox for FunctionExecutor<'a> {
@@ -503,7 +509,11 @@ fn call_in_wasm_module(
 				"Failed to execute code with {} pages",
 				memory.current_size().0,
 			);
-			Err(e.into())
+			// A trap that follows a panic is only noise, report the panic message instead.
+			match function_executor.panic_message.take() {
+				Some(message) => Err(Error::RuntimePanicked(message)),
+				None => Err(e.into()),
+			}
 		},
 		_ => Err(Error::InvalidReturn),
 	}
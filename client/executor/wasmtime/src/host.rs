@@ -54,6 +54,7 @@ pub struct HostState {
 	sandbox_store: RefCell<sandbox::Store<SupervisorFuncRef>>,
 	allocator: RefCell<FreeingBumpHeapAllocator>,
 	instance: Rc<InstanceWrapper>,
+	panic_message: RefCell<Option<String>>,
 }
 
 impl HostState {
@@ -63,9 +64,15 @@ impl HostState {
 			sandbox_store: RefCell::new(sandbox::Store::new()),
 			allocator: RefCell::new(allocator),
 			instance,
+			panic_message: RefCell::new(None),
 		}
 	}
 
+	/// Takes the message of the panic that was registered by the runtime, if any.
+	pub fn take_panic_message(&self) -> Option<String> {
+		self.panic_message.borrow_mut().take()
+	}
+
 	/// Materialize `HostContext` that can be used to invoke a substrate host `dyn Function`.
 	pub fn materialize<'a>(&'a self) -> HostContext<'a> {
 		HostContext(self)
@@ -156,6 +163,10 @@ impl<'a> sp_wasm_interface::FunctionContext for HostContext<'a> {
 	fn sandbox(&mut self) -> &mut dyn Sandbox {
 		self
 	}
+
+	fn register_panic_error_message(&mut self, message: &str) {
+		*self.panic_message.borrow_mut() = Some(message.to_owned());
+	}
 }
 
 impl<'a> Sandbox for HostContext<'a> {
@@ -27,7 +27,7 @@ use std::rc::Rc;
 use std::sync::Arc;
 use std::path::Path;
 use sc_executor_common::{
-	error::{Result, WasmError},
+	error::{Error, Result, WasmError},
 	wasm_runtime::{WasmModule, WasmInstance, InvokeMethod},
 };
 use sp_allocator::FreeingBumpHeapAllocator;
@@ -206,7 +206,11 @@ fn perform_call(
 	let ret = state_holder::with_initialized_state(&host_state, || -> Result<_> {
 		Ok(unpack_ptr_and_len(entrypoint.call(data_ptr, data_len)?))
 	});
-	let (output_ptr, output_len) = ret?;
+	// A trap that follows a panic is only noise, report the panic message instead.
+	let (output_ptr, output_len) = ret.map_err(|e| match host_state.take_panic_message() {
+		Some(message) => Error::RuntimePanicked(message),
+		None => e,
+	})?;
 	let output = extract_output_data(&instance_wrapper, output_ptr, output_len)?;
 
 	Ok(output)
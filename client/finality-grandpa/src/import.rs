@@ -440,6 +440,12 @@ impl<BE, Block: BlockT, Client, SC> BlockImport<Block>
 		// early exit if block already in chain, otherwise the check for
 		// authority changes will error when trying to re-import a change block
 		match self.inner.status(BlockId::Hash(hash)) {
+			Ok(BlockStatus::InChain) if block.imported_state.is_some() => {
+				// Only the state is imported for an existing block, authority set changes were
+				// already handled when the header was imported.
+				block.justification.take();
+				return (&*self.inner).import_block(block, new_cache)
+			},
 			Ok(BlockStatus::InChain) => return Ok(ImportResult::AlreadyInChain),
 			Ok(BlockStatus::Unknown) => {},
 			Err(e) => return Err(ConsensusError::ClientImport(e.to_string())),
@@ -103,7 +103,21 @@ impl<B: BlockT> InformantDisplay<B> {
 			),
 		};
 
-		if self.format.enable_color {
+		if self.format.structured {
+			tracing::info!(
+				target: "substrate",
+				status = %status.trim(),
+				sync_target = ?net_status.best_seen_block,
+				peers = num_connected_peers as u64,
+				best_number = %best_number,
+				best_hash = ?best_hash,
+				finalized_number = %finalized_number,
+				finalized_hash = ?info.chain.finalized_hash,
+				bytes_inbound_per_sec = avg_bytes_per_sec_inbound,
+				bytes_outbound_per_sec = avg_bytes_per_sec_outbound,
+				"Status",
+			)
+		} else if self.format.enable_color {
 			info!(
 				target: "substrate",
 				"{} {}{} ({} peers), best: #{} ({}), finalized #{} ({}), {} {}",
@@ -39,12 +39,17 @@ pub struct OutputFormat {
 	///
 	/// Is enabled by default.
 	pub enable_color: bool,
+	/// Emit the informant output as structured `tracing` fields instead of a formatted line.
+	///
+	/// Meant to be used together with JSON logs. Is disabled by default.
+	pub structured: bool,
 }
 
 impl Default for OutputFormat {
 	fn default() -> Self {
 		Self {
 			enable_color: true,
+			structured: false,
 		}
 	}
 }
@@ -103,11 +108,11 @@ where
 
 	future::join(
 		display_notifications,
-		display_block_import(client),
+		display_block_import(client, format),
 	).map(|_| ())
 }
 
-fn display_block_import<B: BlockT, C>(client: Arc<C>) -> impl Future<Output = ()>
+fn display_block_import<B: BlockT, C>(client: Arc<C>, format: OutputFormat) -> impl Future<Output = ()>
 where
 	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
 	<C as HeaderMetadata<B>>::Error: Display,
@@ -132,6 +137,18 @@ where
 				);
 
 				match maybe_ancestor {
+					Ok(ref ancestor) if ancestor.hash != *last_hash && format.structured => {
+						tracing::info!(
+							target: "substrate",
+							from_number = %last_num,
+							from_hash = ?last_hash,
+							to_number = %n.header.number(),
+							to_hash = ?n.hash,
+							common_number = %ancestor.number,
+							common_hash = ?ancestor.hash,
+							"Reorg",
+						);
+					},
 					Ok(ref ancestor) if ancestor.hash != *last_hash => info!(
 						"♻️  Reorg on #{},{} to #{},{}, common ancestor #{},{}",
 						Colour::Red.bold().paint(format!("{}", last_num)), last_hash,
@@ -158,12 +175,21 @@ where
 				last_blocks.pop_front();
 			}
 
-			info!(
-				target: "substrate",
-				"✨ Imported #{} ({})",
-				Colour::White.bold().paint(format!("{}", n.header.number())),
-				n.hash,
-			);
+			if format.structured {
+				tracing::info!(
+					target: "substrate",
+					number = %n.header.number(),
+					hash = ?n.hash,
+					"Imported",
+				);
+			} else {
+				info!(
+					target: "substrate",
+					"✨ Imported #{} ({})",
+					Colour::White.bold().paint(format!("{}", n.header.number())),
+					n.hash,
+				);
+			}
 		}
 
 		future::ready(())
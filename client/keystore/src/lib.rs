@@ -27,6 +27,12 @@ use sp_keystore::Error as TraitError;
 mod local;
 pub use local::LocalKeystore;
 
+/// Remote keystore implementation
+#[cfg(unix)]
+mod remote;
+#[cfg(unix)]
+pub use remote::RemoteKeystore;
+
 /// Keystore error.
 #[derive(Debug, derive_more::Display, derive_more::From)]
 pub enum Error {
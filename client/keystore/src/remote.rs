@@ -0,0 +1,531 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Remote keystore implementation.
+//!
+//! Forwards all requests to a signing service listening on a Unix socket, so that the keys never
+//! have to be on the node's host. Requests and responses are JSON-RPC 2.0 messages, one per line.
+//! The service has to implement the following methods, with the parameters given by position:
+//!
+//! - `keystore_publicKeys(crypto_type, key_type) -> [public]`
+//! - `keystore_generateNew(crypto_type, key_type, seed?) -> public`
+//! - `keystore_insertUnknown(key_type, suri, public) -> null`
+//! - `keystore_keys(key_type) -> [[crypto_type, public]]`
+//! - `keystore_hasKeys([[public, key_type]]) -> bool`
+//! - `keystore_signWith(key_type, [crypto_type, public], message) -> signature?`
+//! - `keystore_sr25519VrfSign(key_type, public, transcript) -> vrf_signature?`
+//!
+//! Public keys, messages, signatures and transcripts are `0x` prefixed hex strings, seeds and
+//! SURIs are plain strings. Key types and crypto types are arrays of their 4 bytes. The VRF
+//! transcript is the SCALE encoded `VRFTranscriptData` and the VRF signature is the serde
+//! encoding of `VRFSignature`.
+//!
+//! Requests are blocking. The async [`CryptoStore`] implementation executes them on separate
+//! threads, so that the executor is not blocked while waiting for the signing service.
+
+use std::{
+	convert::TryFrom,
+	io::{BufRead, BufReader, Write},
+	os::unix::net::UnixStream,
+	path::{Path, PathBuf},
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	thread,
+	time::Duration,
+};
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId, Public},
+	Bytes, Encode,
+};
+use sp_keystore::{
+	CryptoStore,
+	Error as TraitError,
+	SyncCryptoStore,
+	vrf::{VRFTranscriptData, VRFSignature},
+};
+use sp_application_crypto::{ed25519, sr25519, ecdsa};
+
+use crate::Result;
+
+const LOG_TARGET: &str = "keystore";
+
+/// The default time to wait for the signing service to accept or answer a request.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A connection to the signing service.
+struct Connection {
+	reader: BufReader<UnixStream>,
+	writer: UnixStream,
+}
+
+impl Connection {
+	fn open(path: &Path, timeout: Duration) -> Result<Self> {
+		let writer = UnixStream::connect(path)?;
+		writer.set_read_timeout(Some(timeout))?;
+		writer.set_write_timeout(Some(timeout))?;
+		let reader = BufReader::new(writer.try_clone()?);
+		Ok(Connection { reader, writer })
+	}
+
+	/// Send `request` and wait for the response.
+	fn request(&mut self, request: &Value) -> Result<Value> {
+		let mut request = serde_json::to_vec(request)?;
+		request.push(b'\n');
+		self.writer.write_all(&request)?;
+
+		let mut response = String::new();
+		self.reader.read_line(&mut response)?;
+		Ok(serde_json::from_str(&response)?)
+	}
+}
+
+/// A keystore that forwards all requests to a remote signing service.
+///
+/// Clones share their connections to the signing service.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+	path: PathBuf,
+	timeout: Duration,
+	/// Connections that are not used by any request at the moment.
+	idle_connections: Arc<Mutex<Vec<Connection>>>,
+	next_id: Arc<AtomicU64>,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signing service listening on the Unix socket at `uri`.
+	///
+	/// `uri` is either a path or a `unix://` URI. Concurrent requests use separate connections
+	/// which are opened on demand.
+	pub fn open(uri: &str) -> Result<Self> {
+		let path = PathBuf::from(uri.strip_prefix("unix://").unwrap_or(uri));
+		let connection = Connection::open(&path, DEFAULT_TIMEOUT)?;
+		Ok(RemoteKeystore {
+			path,
+			timeout: DEFAULT_TIMEOUT,
+			idle_connections: Arc::new(Mutex::new(vec![connection])),
+			next_id: Arc::new(AtomicU64::new(0)),
+		})
+	}
+
+	/// Fail requests that are not answered by the signing service within `timeout`.
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		for connection in self.idle_connections.lock().iter() {
+			let _ = connection.writer.set_read_timeout(Some(timeout));
+			let _ = connection.writer.set_write_timeout(Some(timeout));
+		}
+		self
+	}
+
+	/// Call `method` on the signing service.
+	fn call<R: DeserializeOwned>(
+		&self,
+		method: &str,
+		params: Value,
+	) -> std::result::Result<R, TraitError> {
+		let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+		let request = json!({
+			"jsonrpc": "2.0",
+			"id": id,
+			"method": method,
+			"params": params,
+		});
+
+		// The lock is only held to take an idle connection, never while waiting for the
+		// signing service.
+		let idle_connection = self.idle_connections.lock().pop();
+		let response = idle_connection
+			.map_or_else(|| Connection::open(&self.path, self.timeout), Ok)
+			.and_then(|mut connection| {
+				connection.request(&request).map(|response| (connection, response))
+			});
+		// A connection that failed is dropped since a late response could still arrive on it.
+		let (connection, response) = match response {
+			Ok(response) => response,
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Remote keystore request failed: {}", e);
+				return Err(TraitError::Unavailable)
+			},
+		};
+
+		if response.get("id") != Some(&json!(id)) {
+			return Err(TraitError::Other("Remote keystore answered another request".into()))
+		}
+		self.idle_connections.lock().push(connection);
+
+		if let Some(error) = response.get("error") {
+			return Err(TraitError::Other(format!("Remote keystore error: {}", error)))
+		}
+		let result = response.get("result").cloned().unwrap_or(Value::Null);
+		serde_json::from_value(result)
+			.map_err(|e| TraitError::Other(format!("Invalid remote keystore response: {}", e)))
+	}
+
+	fn public_keys<T: Public>(&self, crypto_type: CryptoTypeId, id: KeyTypeId) -> Vec<T> {
+		self.call::<Vec<Bytes>>("keystore_publicKeys", json!([crypto_type, id]))
+			.and_then(|keys| keys.iter().map(|k| public_from_bytes(k)).collect())
+			.unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Failed to get public keys: {}", e);
+				Vec::new()
+			})
+	}
+
+	fn generate_new<T: Public>(
+		&self,
+		crypto_type: CryptoTypeId,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<T, TraitError> {
+		self.call::<Bytes>("keystore_generateNew", json!([crypto_type, id, seed]))
+			.and_then(|k| public_from_bytes(&k))
+	}
+
+	/// Execute `request` on a separate thread and wait for its result without blocking the
+	/// executor.
+	async fn spawn_blocking<R: Send + 'static>(
+		&self,
+		request: impl FnOnce(&RemoteKeystore) -> R + Send + 'static,
+	) -> R {
+		let keystore = self.clone();
+		let (tx, rx) = oneshot::channel();
+		thread::spawn(move || {
+			let _ = tx.send(request(&keystore));
+		});
+		rx.await.expect("The result is always sent unless the request panicked; qed")
+	}
+}
+
+/// Convert a public key returned by the signing service, checking its length.
+fn public_from_bytes<T: Public>(bytes: &[u8]) -> std::result::Result<T, TraitError> {
+	T::try_from(bytes).map_err(|_| TraitError::ValidationError(
+		format!("Remote keystore returned a public key of invalid length {}", bytes.len()),
+	))
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn keys(&self, id: KeyTypeId) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		self.spawn_blocking(move |keystore| SyncCryptoStore::keys(keystore, id)).await
+	}
+
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.spawn_blocking(move |keystore| SyncCryptoStore::sr25519_public_keys(keystore, id))
+			.await
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.spawn_blocking(move |keystore| {
+			SyncCryptoStore::sr25519_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.spawn_blocking(move |keystore| SyncCryptoStore::ed25519_public_keys(keystore, id))
+			.await
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.spawn_blocking(move |keystore| {
+			SyncCryptoStore::ed25519_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.spawn_blocking(move |keystore| SyncCryptoStore::ecdsa_public_keys(keystore, id))
+			.await
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.spawn_blocking(move |keystore| {
+			SyncCryptoStore::ecdsa_generate_new(keystore, id, seed.as_deref())
+		}).await
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> std::result::Result<(), ()> {
+		let (suri, public) = (suri.to_owned(), public.to_vec());
+		self.spawn_blocking(move |keystore| {
+			SyncCryptoStore::insert_unknown(keystore, id, &suri, &public)
+		}).await
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys.to_vec();
+		self.spawn_blocking(move |keystore| SyncCryptoStore::has_keys(keystore, &public_keys))
+			.await
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		self.spawn_blocking(move |keystore| SyncCryptoStore::supported_keys(keystore, id, keys))
+			.await
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> std::result::Result<Option<Vec<u8>>, TraitError> {
+		let (key, msg) = (key.clone(), msg.to_vec());
+		self.spawn_blocking(move |keystore| SyncCryptoStore::sign_with(keystore, id, &key, &msg))
+			.await
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> std::result::Result<Option<VRFSignature>, TraitError> {
+		let public = *public;
+		self.spawn_blocking(move |keystore| {
+			SyncCryptoStore::sr25519_vrf_sign(keystore, key_type, &public, transcript_data)
+		}).await
+	}
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn keys(
+		&self,
+		id: KeyTypeId
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		self.call::<Vec<(CryptoTypeId, Bytes)>>("keystore_keys", json!([id]))
+			.map(|keys| keys.into_iter().map(|(t, k)| CryptoTypePublicPair(t, k.0)).collect())
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>
+	) -> std::result::Result<Vec<CryptoTypePublicPair>, TraitError> {
+		let all_keys = SyncCryptoStore::keys(self, id)?;
+		Ok(keys.into_iter().filter(|key| all_keys.contains(key)).collect())
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> std::result::Result<Option<Vec<u8>>, TraitError> {
+		let key = (key.0, Bytes(key.1.clone()));
+		self.call::<Option<Bytes>>("keystore_signWith", json!([id, key, Bytes(msg.to_vec())]))
+			.map(|signature| signature.map(|s| s.0))
+	}
+
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(sr25519::CRYPTO_ID, key_type)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		self.generate_new(sr25519::CRYPTO_ID, id, seed)
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(ed25519::CRYPTO_ID, key_type)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		self.generate_new(ed25519::CRYPTO_ID, id, seed)
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(ecdsa::CRYPTO_ID, key_type)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		self.generate_new(ecdsa::CRYPTO_ID, id, seed)
+	}
+
+	fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8])
+		-> std::result::Result<(), ()>
+	{
+		self.call::<()>("keystore_insertUnknown", json!([key_type, suri, Bytes(public.to_vec())]))
+			.map_err(|e| log::warn!(target: LOG_TARGET, "Failed to insert key: {}", e))
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys.iter()
+			.map(|(k, t)| (Bytes(k.clone()), *t))
+			.collect::<Vec<_>>();
+		self.call("keystore_hasKeys", json!([public_keys]))
+			.unwrap_or_else(|e| {
+				log::warn!(target: LOG_TARGET, "Failed to check keys: {}", e);
+				false
+			})
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> std::result::Result<Option<VRFSignature>, TraitError> {
+		self.call(
+			"keystore_sr25519VrfSign",
+			json!([key_type, Bytes(public.to_raw_vec()), Bytes(transcript_data.encode())]),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{os::unix::net::UnixListener, thread};
+	use tempfile::TempDir;
+
+	/// Serve a single connection, answering each request with the result returned by `handle`.
+	fn serve(
+		listener: UnixListener,
+		handle: impl Fn(&str, &Value) -> Value + Send + 'static,
+	) -> thread::JoinHandle<()> {
+		thread::spawn(move || {
+			let (stream, _) = listener.accept().unwrap();
+			let mut writer = stream.try_clone().unwrap();
+			for line in BufReader::new(stream).lines() {
+				let request: Value = serde_json::from_str(&line.unwrap()).unwrap();
+				let method = request["method"].as_str().unwrap();
+				let result = handle(method, &request["params"]);
+				let response = json!({ "jsonrpc": "2.0", "id": request["id"], "result": result });
+				writeln!(writer, "{}", response).unwrap();
+			}
+		})
+	}
+
+	#[test]
+	fn forwards_requests_to_the_signing_service() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("signer.sock");
+		let listener = UnixListener::bind(&path).unwrap();
+
+		let public = sr25519::Public::from_raw([1; 32]);
+		let server = serve(listener, move |method, params| match method {
+			"keystore_publicKeys" => json!([Bytes(public.to_raw_vec())]),
+			"keystore_signWith" => {
+				assert_eq!(params[2], json!(Bytes(b"message".to_vec())));
+				json!(Bytes(vec![4, 2]))
+			},
+			"keystore_hasKeys" => json!(true),
+			"keystore_keys" => json!([[sr25519::CRYPTO_ID, Bytes(public.to_raw_vec())]]),
+			_ => Value::Null,
+		});
+
+		let keystore = RemoteKeystore::open(&format!("unix://{}", path.display())).unwrap();
+		let key_type = KeyTypeId(*b"test");
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, key_type), vec![public]);
+		assert!(SyncCryptoStore::has_keys(&keystore, &[(public.to_raw_vec(), key_type)]));
+		assert_eq!(
+			SyncCryptoStore::sign_with(
+				&keystore,
+				key_type,
+				&CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_raw_vec()),
+				b"message",
+			).unwrap(),
+			Some(vec![4, 2]),
+		);
+
+		// The async implementation forwards to the same connection.
+		assert_eq!(
+			futures::executor::block_on(CryptoStore::keys(&keystore, key_type)).unwrap(),
+			vec![CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_raw_vec())],
+		);
+		assert_eq!(
+			futures::executor::block_on(CryptoStore::sr25519_public_keys(&keystore, key_type)),
+			vec![public],
+		);
+
+		drop(keystore);
+		server.join().unwrap();
+	}
+
+	#[test]
+	fn rejects_public_keys_of_invalid_length() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("signer.sock");
+		let listener = UnixListener::bind(&path).unwrap();
+		let server = serve(listener, |_, _| json!(Bytes(vec![1; 31])));
+
+		let keystore = RemoteKeystore::open(path.to_str().unwrap()).unwrap();
+		let key_type = KeyTypeId(*b"test");
+		assert!(matches!(
+			SyncCryptoStore::sr25519_generate_new(&keystore, key_type, None),
+			Err(TraitError::ValidationError(_)),
+		));
+		assert!(SyncCryptoStore::sr25519_public_keys(&keystore, key_type).is_empty());
+
+		drop(keystore);
+		server.join().unwrap();
+	}
+
+	#[test]
+	fn requests_time_out() {
+		let temp_dir = TempDir::new().unwrap();
+		let path = temp_dir.path().join("signer.sock");
+		let listener = UnixListener::bind(&path).unwrap();
+		let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+		// Accept the connection but never answer.
+		let server = thread::spawn(move || {
+			let _stream = listener.accept().unwrap();
+			let _ = done_rx.recv();
+		});
+
+		let keystore = RemoteKeystore::open(path.to_str().unwrap())
+			.unwrap()
+			.with_timeout(Duration::from_millis(100));
+		assert!(matches!(
+			SyncCryptoStore::keys(&keystore, KeyTypeId(*b"test")),
+			Err(TraitError::Unavailable),
+		));
+
+		done_tx.send(()).unwrap();
+		server.join().unwrap();
+	}
+}
@@ -241,6 +241,13 @@ impl<S, Block> ClientBackend<Block> for Backend<S, HashFor<Block>>
 	fn get_import_lock(&self) -> &RwLock<()> {
 		&self.import_lock
 	}
+
+	fn pin_block(&self, _: &Block::Hash) -> ClientResult<()> {
+		// Light clients don't store bodies and state that could be pruned.
+		Ok(())
+	}
+
+	fn unpin_block(&self, _: &Block::Hash) {}
 }
 
 impl<S, Block> RemoteBackend<Block> for Backend<S, HashFor<Block>>
@@ -79,6 +79,11 @@ pub struct Behaviour<B: BlockT> {
 	/// [`request_responses::RequestResponsesBehaviour`].
 	#[behaviour(ignore)]
 	block_request_protocol_name: String,
+
+	/// Protocol name used to send out state requests via
+	/// [`request_responses::RequestResponsesBehaviour`].
+	#[behaviour(ignore)]
+	state_request_protocol_name: String,
 }
 
 /// Event generated by `Behaviour`.
@@ -181,6 +186,7 @@ impl<B: BlockT> Behaviour<B> {
 		light_client_request_sender: light_client_requests::sender::LightClientRequestSender<B>,
 		disco_config: DiscoveryConfig,
 		block_request_protocol_config: request_responses::ProtocolConfig,
+		state_request_protocol_config: request_responses::ProtocolConfig,
 		bitswap: Option<Bitswap<B>>,
 		light_client_request_protocol_config: request_responses::ProtocolConfig,
 		// All remaining request protocol configs.
@@ -190,6 +196,9 @@ impl<B: BlockT> Behaviour<B> {
 		let block_request_protocol_name = block_request_protocol_config.name.to_string();
 		request_response_protocols.push(block_request_protocol_config);
 
+		let state_request_protocol_name = state_request_protocol_config.name.to_string();
+		request_response_protocols.push(state_request_protocol_config);
+
 		request_response_protocols.push(light_client_request_protocol_config);
 
 		Ok(Behaviour {
@@ -203,6 +212,7 @@ impl<B: BlockT> Behaviour<B> {
 			events: VecDeque::new(),
 
 			block_request_protocol_name,
+			state_request_protocol_name,
 		})
 	}
 
@@ -324,6 +334,21 @@ Behaviour<B> {
 					&target, &self.block_request_protocol_name, buf, pending_response, IfDisconnected::ImmediateError,
 				);
 			},
+			CustomMessageOutcome::StateRequest { target, request, pending_response } => {
+				let mut buf = Vec::with_capacity(request.encoded_len());
+				if let Err(err) = request.encode(&mut buf) {
+					log::warn!(
+						target: "sync",
+						"Failed to encode state request {:?}: {:?}",
+						request, err
+					);
+					return
+				}
+
+				self.request_responses.send_request(
+					&target, &self.state_request_protocol_name, buf, pending_response, IfDisconnected::ImmediateError,
+				);
+			},
 			CustomMessageOutcome::NotificationStreamOpened { remote, protocol, roles, notifications_sink } => {
 				self.events.push_back(BehaviourOut::NotificationStreamOpened {
 					remote,
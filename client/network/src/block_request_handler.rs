@@ -36,17 +36,25 @@ use std::cmp::min;
 use std::sync::Arc;
 use std::time::Duration;
 use std::hash::{Hasher, Hash};
+use wasm_timer::Instant;
 
 const LOG_TARGET: &str = "sync";
 const MAX_BLOCKS_IN_RESPONSE: usize = 128;
 const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
 const MAX_NUMBER_OF_SAME_REQUESTS_PER_PEER: usize = 2;
+/// Maximum number of block requests a single peer may send within [`RATE_LIMIT_WINDOW`].
+const MAX_REQUESTS_PER_PEER_PER_WINDOW: u32 = 64;
+/// Length of the window over which [`MAX_REQUESTS_PER_PEER_PER_WINDOW`] is enforced.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(10);
 
 mod rep {
 	use super::ReputationChange as Rep;
 
 	/// Reputation change when a peer sent us the same request multiple times.
 	pub const SAME_REQUEST: Rep = Rep::new(i32::min_value(), "Same block request multiple times");
+
+	/// Reputation change when a peer exceeded the block request rate limit.
+	pub const TOO_MANY_REQUESTS: Rep = Rep::new(-(1 << 12), "Too many block requests");
 }
 
 /// Generates a [`ProtocolConfig`] for the block request protocol, refusing incoming requests.
@@ -112,6 +120,9 @@ pub struct BlockRequestHandler<B: BlockT> {
 	///
 	/// This is used to check if a peer is spamming us with the same request.
 	seen_requests: LruCache<SeenRequestsKey<B>, SeenRequestsValue>,
+	/// Maps from peer to the start of its current rate limiting window and the number of requests
+	/// it sent within that window.
+	request_rates: LruCache<PeerId, (Instant, u32)>,
 }
 
 impl<B: BlockT> BlockRequestHandler<B> {
@@ -134,8 +145,9 @@ impl<B: BlockT> BlockRequestHandler<B> {
 		protocol_config.inbound_queue = Some(tx);
 
 		let seen_requests = LruCache::new(num_peer_hint * 2);
+		let request_rates = LruCache::new(num_peer_hint);
 
-		(Self { client, request_receiver, seen_requests }, protocol_config)
+		(Self { client, request_receiver, seen_requests, request_rates }, protocol_config)
 	}
 
 	/// Run [`BlockRequestHandler`].
@@ -161,6 +173,15 @@ impl<B: BlockT> BlockRequestHandler<B> {
 		pending_response: oneshot::Sender<OutgoingResponse>,
 		peer: &PeerId,
 	) -> Result<(), HandleRequestError> {
+		if self.is_rate_limited(peer) {
+			pending_response.send(OutgoingResponse {
+				result: Err(()),
+				reputation_changes: vec![rep::TOO_MANY_REQUESTS],
+			}).map_err(|_| HandleRequestError::SendResponse)?;
+
+			return Err(HandleRequestError::RateLimited);
+		}
+
 		let request = crate::schema::v1::BlockRequest::decode(&payload[..])?;
 
 		let from_block_id = match request.from_block.ok_or(HandleRequestError::MissingFromField)? {
@@ -257,6 +278,23 @@ impl<B: BlockT> BlockRequestHandler<B> {
 		}).map_err(|_| HandleRequestError::SendResponse)
 	}
 
+	/// Count a request of `peer` and return `true` if it sent more than
+	/// [`MAX_REQUESTS_PER_PEER_PER_WINDOW`] requests within the current [`RATE_LIMIT_WINDOW`].
+	fn is_rate_limited(&mut self, peer: &PeerId) -> bool {
+		let now = Instant::now();
+
+		match self.request_rates.get_mut(peer) {
+			Some((window_start, requests)) if now.duration_since(*window_start) < RATE_LIMIT_WINDOW => {
+				*requests = requests.saturating_add(1);
+				*requests > MAX_REQUESTS_PER_PEER_PER_WINDOW
+			},
+			_ => {
+				self.request_rates.put(*peer, (now, 1));
+				false
+			}
+		}
+	}
+
 	fn get_block_response(
 		&self,
 		attributes: BlockAttributes,
@@ -349,4 +387,59 @@ enum HandleRequestError {
 	Client(sp_blockchain::Error),
 	#[display(fmt = "Failed to send response.")]
 	SendResponse,
+	#[display(fmt = "Peer exceeded the block request rate limit.")]
+	RateLimited,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_matches::assert_matches;
+	use substrate_test_runtime_client::runtime::Block;
+
+	fn handler() -> BlockRequestHandler<Block> {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		BlockRequestHandler::new(&ProtocolId::from("test"), client, 2).0
+	}
+
+	#[test]
+	fn rate_limit_is_enforced_per_peer() {
+		let mut handler = handler();
+		let peer = PeerId::random();
+		let other_peer = PeerId::random();
+
+		for _ in 0..MAX_REQUESTS_PER_PEER_PER_WINDOW {
+			assert!(!handler.is_rate_limited(&peer));
+		}
+		assert!(handler.is_rate_limited(&peer));
+		assert!(!handler.is_rate_limited(&other_peer));
+	}
+
+	#[test]
+	fn rate_limit_is_reset_after_window() {
+		let mut handler = handler();
+		let peer = PeerId::random();
+
+		let expired_window = Instant::now() - RATE_LIMIT_WINDOW;
+		handler.request_rates.put(peer, (expired_window, MAX_REQUESTS_PER_PEER_PER_WINDOW));
+		assert!(!handler.is_rate_limited(&peer));
+		assert_eq!(handler.request_rates.get(&peer).map(|(_, requests)| *requests), Some(1));
+	}
+
+	#[test]
+	fn rate_limited_requests_are_refused_and_reported() {
+		let mut handler = handler();
+		let peer = PeerId::random();
+		handler.request_rates.put(peer, (Instant::now(), MAX_REQUESTS_PER_PEER_PER_WINDOW));
+
+		let (tx, mut rx) = oneshot::channel();
+		assert_matches!(
+			handler.handle_request(Vec::new(), tx, &peer),
+			Err(HandleRequestError::RateLimited)
+		);
+
+		let response = rx.try_recv().unwrap().unwrap();
+		assert_eq!(response.result, Err(()));
+		assert_eq!(response.reputation_changes, vec![rep::TOO_MANY_REQUESTS]);
+	}
 }
@@ -129,6 +129,10 @@
 //! requests for information about blocks. Each request is the encoding of a `BlockRequest` and
 //! each response is the encoding of a `BlockResponse`, as defined in the `api.v1.proto` file in
 //! this source tree.
+//! - **`/<protocol-id>/state/1`** is a request-response protocol (see below) that lets one
+//! download the storage of a block in chunks, used when syncing in fast mode. Each request is the
+//! encoding of a `StateRequest` and each response is the encoding of a `StateResponse`, as
+//! defined in the `api.v1.proto` file in this source tree.
 //! - **`/<protocol-id>/light/2`** is a request-response protocol (see below) that lets one perform
 //! light-client-related requests for information about the state. Each request is the encoding of
 //! a `light::Request` and each response is the encoding of a `light::Response`, as defined in the
@@ -264,6 +268,7 @@ pub mod config;
 pub mod error;
 pub mod gossip;
 pub mod network_state;
+pub mod state_request_handler;
 pub mod transactions;
 
 #[doc(inline)]
@@ -184,7 +184,7 @@ mod tests {
 
 	fn send_receive(request: sender::Request<Block>, pool: &LocalPool) {
 		let client = Arc::new(substrate_test_runtime_client::new());
-		let (handler, protocol_config) = handler::LightClientRequestHandler::new(&protocol_id(), client);
+		let (handler, protocol_config) = handler::LightClientRequestHandler::new(&protocol_id(), client, 50);
 		pool.spawner().spawn_obj(handler.run().boxed().into()).unwrap();
 
 		let (_peer_set, peer_set_handle) = peerset();
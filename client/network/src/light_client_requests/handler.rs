@@ -31,6 +31,7 @@ use crate::{
 use crate::request_responses::{IncomingRequest, OutgoingResponse, ProtocolConfig};
 use futures::{channel::mpsc,  prelude::*};
 use prost::Message;
+use lru::LruCache;
 use sc_client_api::{
 	StorageProof,
 	light
@@ -47,23 +48,45 @@ use sp_runtime::{
 use std::{
 	collections::{BTreeMap},
 	sync::Arc,
+	time::Duration,
 };
 use log::debug;
+use wasm_timer::Instant;
 
 const LOG_TARGET: &str = "light-client-request-handler";
+/// Maximum number of light client requests a single peer may send within [`RATE_LIMIT_WINDOW`].
+const MAX_REQUESTS_PER_PEER_PER_WINDOW: u32 = 64;
+/// Length of the window over which [`MAX_REQUESTS_PER_PEER_PER_WINDOW`] is enforced.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(10);
+
+mod rep {
+	use super::ReputationChange as Rep;
+
+	/// Reputation change when a peer sent us a malformed or unanswerable request.
+	pub const BAD_REQUEST: Rep = Rep::new(-(1 << 12), "bad request");
+
+	/// Reputation change when a peer exceeded the light client request rate limit.
+	pub const TOO_MANY_REQUESTS: Rep = Rep::new(-(1 << 12), "Too many light client requests");
+}
 
 /// Handler for incoming light client requests from a remote peer.
 pub struct LightClientRequestHandler<B: Block> {
 	request_receiver: mpsc::Receiver<IncomingRequest>,
 	/// Blockchain client.
 	client: Arc<dyn Client<B>>,
+	/// Maps from peer to the start of its current rate limiting window and the number of requests
+	/// it sent within that window.
+	request_rates: LruCache<PeerId, (Instant, u32)>,
 }
 
 impl<B: Block> LightClientRequestHandler<B> {
-	/// Create a new [`crate::block_request_handler::BlockRequestHandler`].
+	/// Create a new [`LightClientRequestHandler`].
+	///
+	/// `num_peer_hint` is the number of peers, full and light, whose request rates are tracked.
 	pub fn new(
 		protocol_id: &ProtocolId,
 		client: Arc<dyn Client<B>>,
+		num_peer_hint: usize,
 	) -> (Self, ProtocolConfig) {
 		// For now due to lack of data on light client request handling in production systems, this
 		// value is chosen to match the block request limit.
@@ -72,7 +95,9 @@ impl<B: Block> LightClientRequestHandler<B> {
 		let mut protocol_config = super::generate_protocol_config(protocol_id);
 		protocol_config.inbound_queue = Some(tx);
 
-		(Self { client, request_receiver }, protocol_config)
+		let request_rates = LruCache::new(num_peer_hint);
+
+		(Self { client, request_receiver, request_rates }, protocol_config)
 	}
 
 	/// Run [`LightClientRequestHandler`].
@@ -104,9 +129,8 @@ impl<B: Block> LightClientRequestHandler<B> {
 					);
 
 					let reputation_changes = match e {
-						HandleRequestError::BadRequest(_) => {
-							vec![ReputationChange::new(-(1 << 12), "bad request")]
-						}
+						HandleRequestError::BadRequest(_) => vec![rep::BAD_REQUEST],
+						HandleRequestError::RateLimited => vec![rep::TOO_MANY_REQUESTS],
 						_ => Vec::new(),
 					};
 
@@ -129,6 +153,10 @@ impl<B: Block> LightClientRequestHandler<B> {
 		peer: PeerId,
 		payload: Vec<u8>,
 	) -> Result<Vec<u8>, HandleRequestError> {
+		if self.is_rate_limited(&peer) {
+			return Err(HandleRequestError::RateLimited);
+		}
+
 		let request = schema::v1::light::Request::decode(&payload[..])?;
 
 		let response = match &request.request {
@@ -153,6 +181,23 @@ impl<B: Block> LightClientRequestHandler<B> {
 		Ok(data)
 	}
 
+	/// Record a request from the given peer and return whether it exceeds the per-peer limit of
+	/// [`MAX_REQUESTS_PER_PEER_PER_WINDOW`] requests within [`RATE_LIMIT_WINDOW`].
+	fn is_rate_limited(&mut self, peer: &PeerId) -> bool {
+		let now = Instant::now();
+
+		match self.request_rates.get_mut(peer) {
+			Some((window_start, requests)) if now.duration_since(*window_start) < RATE_LIMIT_WINDOW => {
+				*requests = requests.saturating_add(1);
+				*requests > MAX_REQUESTS_PER_PEER_PER_WINDOW
+			},
+			_ => {
+				self.request_rates.put(*peer, (now, 1));
+				false
+			}
+		}
+	}
+
 	fn on_remote_call_request(
 		&mut self,
 		peer: &PeerId,
@@ -384,6 +429,8 @@ enum HandleRequestError {
 	/// Encoding or decoding of some data failed.
 	#[display(fmt = "codec error: {}", _0)]
 	Codec(codec::Error),
+	#[display(fmt = "Peer exceeded the light client request rate limit.")]
+	RateLimited,
 }
 
 fn fmt_keys(first: Option<&Vec<u8>>, last: Option<&Vec<u8>>) -> String {
@@ -397,3 +444,38 @@ fn fmt_keys(first: Option<&Vec<u8>>, last: Option<&Vec<u8>>) -> String {
 		String::from("n/a")
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_test_runtime_client::runtime::Block;
+
+	fn handler() -> LightClientRequestHandler<Block> {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		LightClientRequestHandler::new(&ProtocolId::from("test"), client, 2).0
+	}
+
+	#[test]
+	fn rate_limit_is_enforced_per_peer() {
+		let mut handler = handler();
+		let peer = PeerId::random();
+		let other_peer = PeerId::random();
+
+		for _ in 0..MAX_REQUESTS_PER_PEER_PER_WINDOW {
+			assert!(!handler.is_rate_limited(&peer));
+		}
+		assert!(handler.is_rate_limited(&peer));
+		assert!(!handler.is_rate_limited(&other_peer));
+	}
+
+	#[test]
+	fn rate_limit_is_reset_after_window() {
+		let mut handler = handler();
+		let peer = PeerId::random();
+
+		let expired_window = Instant::now() - RATE_LIMIT_WINDOW;
+		handler.request_rates.put(peer, (expired_window, MAX_REQUESTS_PER_PEER_PER_WINDOW));
+		assert!(!handler.is_rate_limited(&peer));
+		assert_eq!(handler.request_rates.get(&peer).map(|(_, requests)| *requests), Some(1));
+	}
+}
@@ -187,6 +187,8 @@ struct Peer<B: BlockT> {
 		message::BlockRequest<B>,
 		oneshot::Receiver<Result<Vec<u8>, RequestFailure>>,
 	)>,
+	/// Current state request, if any. Started by emitting [`CustomMessageOutcome::StateRequest`].
+	state_request: Option<oneshot::Receiver<Result<Vec<u8>, RequestFailure>>>,
 	/// Holds a set of blocks known to this peer.
 	known_blocks: LruHashSet<B::Hash>,
 }
@@ -262,6 +264,7 @@ impl<B: BlockT> Protocol<B> {
 	) -> error::Result<(Protocol<B>, sc_peerset::PeersetHandle, Vec<(PeerId, Multiaddr)>)> {
 		let info = chain.info();
 		let sync = ChainSync::new(
+			network_config.sync_mode,
 			config.roles,
 			chain.clone(),
 			&info,
@@ -292,7 +295,7 @@ impl<B: BlockT> Protocol<B> {
 
 		let mut known_addresses = Vec::new();
 
-		let (peerset, peerset_handle) = {
+		let (mut peerset, peerset_handle) = {
 			let mut sets = Vec::with_capacity(NUM_HARDCODED_PEERSETS + network_config.extra_sets.len());
 
 			let mut default_sets_reserved = HashSet::new();
@@ -341,6 +344,10 @@ impl<B: BlockT> Protocol<B> {
 			})
 		};
 
+		if let Some(registry) = metrics_registry {
+			peerset.register_metrics(registry)?;
+		}
+
 		let block_announces_protocol: Cow<'static, str> = Cow::from({
 			let mut proto = String::new();
 			proto.push_str("/");
@@ -413,6 +420,11 @@ impl<B: BlockT> Protocol<B> {
 		self.behaviour.requested_peers(HARDCODED_PEERSETS_SYNC)
 	}
 
+	/// Returns the list of reserved peers of the default set.
+	pub fn reserved_peers(&self) -> impl Iterator<Item = &PeerId> {
+		self.behaviour.reserved_peers(HARDCODED_PEERSETS_SYNC)
+	}
+
 	/// Returns the number of discovered nodes that we keep in memory.
 	pub fn num_discovered_peers(&self) -> usize {
 		self.behaviour.num_discovered_peers()
@@ -636,6 +648,25 @@ impl<B: BlockT> Protocol<B> {
 		}
 	}
 
+	/// Must be called in response to a [`CustomMessageOutcome::StateRequest`] being emitted.
+	/// Must contain the same `PeerId` that has been emitted.
+	pub fn on_state_response(
+		&mut self,
+		peer_id: PeerId,
+		response: crate::schema::v1::StateResponse,
+	) -> CustomMessageOutcome<B> {
+		match self.sync.on_state_data(&peer_id, response) {
+			Ok(sync::OnStateData::Import(origin, block)) =>
+				CustomMessageOutcome::BlockImport(origin, vec![block]),
+			Ok(sync::OnStateData::Continue) => CustomMessageOutcome::None,
+			Err(sync::BadPeer(id, repu)) => {
+				self.behaviour.disconnect_peer(&id, HARDCODED_PEERSETS_SYNC);
+				self.peerset_handle.report_peer(id, repu);
+				CustomMessageOutcome::None
+			}
+		}
+	}
+
 	/// Perform time based maintenance.
 	///
 	/// > **Note**: This method normally doesn't have to be called except for testing purposes.
@@ -717,6 +748,7 @@ impl<B: BlockT> Protocol<B> {
 				best_number: status.best_number
 			},
 			block_request: None,
+			state_request: None,
 			known_blocks: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_BLOCKS)
 				.expect("Constant is nonzero")),
 		};
@@ -1129,6 +1161,24 @@ fn prepare_block_request<B: BlockT>(
 	}
 }
 
+fn prepare_state_request<B: BlockT>(
+	peers: &mut HashMap<PeerId, Peer<B>>,
+	who: PeerId,
+	request: crate::schema::v1::StateRequest,
+) -> CustomMessageOutcome<B> {
+	let (tx, rx) = oneshot::channel();
+
+	if let Some(ref mut peer) = peers.get_mut(&who) {
+		peer.state_request = Some(rx);
+	}
+
+	CustomMessageOutcome::StateRequest {
+		target: who,
+		request,
+		pending_response: tx,
+	}
+}
+
 /// Outcome of an incoming custom message.
 #[derive(Debug)]
 #[must_use]
@@ -1158,6 +1208,12 @@ pub enum CustomMessageOutcome<B: BlockT> {
 		request: crate::schema::v1::BlockRequest,
 		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 	},
+	/// A new state request must be emitted.
+	StateRequest {
+		target: PeerId,
+		request: crate::schema::v1::StateRequest,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
 	/// Peer has a reported a new head of chain.
 	PeerNewBest(PeerId, NumberFor<B>),
 	/// Now connected to a new peer for syncing purposes.
@@ -1292,6 +1348,52 @@ impl<B: BlockT> NetworkBehaviour for Protocol<B> {
 			self.pending_messages.push_back(ev);
 		}
 
+		let mut finished_state_requests = Vec::new();
+		for (id, peer) in self.peers.iter_mut() {
+			if let Peer { state_request: Some(pending_response), .. } = peer {
+				match pending_response.poll_unpin(cx) {
+					Poll::Ready(Ok(Ok(resp))) => {
+						peer.state_request.take();
+
+						let protobuf_response = match crate::schema::v1::StateResponse::decode(&resp[..]) {
+							Ok(proto) => proto,
+							Err(e) => {
+								trace!(target: "sync", "Failed to decode state response from peer {:?}: {:?}.", id, e);
+								self.peerset_handle.report_peer(id.clone(), rep::BAD_MESSAGE);
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+								continue;
+							}
+						};
+
+						finished_state_requests.push((id.clone(), protobuf_response));
+					},
+					Poll::Ready(Ok(Err(e))) => {
+						peer.state_request.take();
+						trace!(target: "sync", "State request to peer {:?} failed: {:?}.", id, e);
+
+						if let RequestFailure::Network(OutboundFailure::Timeout) = e {
+							self.peerset_handle.report_peer(id.clone(), rep::TIMEOUT);
+						}
+						self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+					},
+					Poll::Ready(Err(oneshot::Canceled)) => {
+						peer.state_request.take();
+						trace!(
+							target: "sync",
+							"State request to peer {:?} failed due to oneshot being canceled.",
+							id,
+						);
+						self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+					},
+					Poll::Pending => {},
+				}
+			}
+		}
+		for (id, protobuf_response) in finished_state_requests {
+			let ev = self.on_state_response(id, protobuf_response);
+			self.pending_messages.push_back(ev);
+		}
+
 		while let Poll::Ready(Some(())) = self.tick_timeout.poll_next_unpin(cx) {
 			self.tick();
 		}
@@ -1304,6 +1406,10 @@ impl<B: BlockT> NetworkBehaviour for Protocol<B> {
 			let event = prepare_block_request(&mut self.peers, id, request);
 			self.pending_messages.push_back(event);
 		}
+		if let Some((id, request)) = self.sync.state_request() {
+			let event = prepare_state_request(&mut self.peers, id, request);
+			self.pending_messages.push_back(event);
+		}
 
 		// Check if there is any block announcement validation finished.
 		while let Poll::Ready(result) = self.sync.poll_block_announce_validation(cx) {
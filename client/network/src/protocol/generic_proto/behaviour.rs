@@ -600,6 +600,11 @@ impl GenericProto {
 		self.peerset.debug_info()
 	}
 
+	/// Returns the list of reserved peers of the given set.
+	pub fn reserved_peers(&self, set_id: sc_peerset::SetId) -> impl Iterator<Item = &PeerId> {
+		self.peerset.reserved_peers(set_id)
+	}
+
 	/// Function that is called when the peerset wants us to connect to a peer.
 	fn peerset_report_connect(&mut self, peer_id: PeerId, set_id: sc_peerset::SetId) {
 		// If `PeerId` is unknown to us, insert an entry, start dialing, and return early.
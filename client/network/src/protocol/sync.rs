@@ -39,8 +39,11 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 use crate::protocol::message::{
 	self, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse, Roles,
 };
+use crate::config::SyncMode;
+use crate::schema::v1::{StateRequest, StateResponse};
 use either::Either;
 use extra_requests::ExtraRequests;
+use state::StateSync;
 use libp2p::PeerId;
 use log::{debug, trace, warn, info, error};
 use sp_runtime::{
@@ -59,6 +62,7 @@ use futures::{task::Poll, Future, stream::FuturesUnordered, FutureExt, StreamExt
 
 mod blocks;
 mod extra_requests;
+mod state;
 
 /// Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
@@ -124,6 +128,9 @@ mod rep {
 
 	/// Peer response data does not have requested bits.
 	pub const BAD_RESPONSE: Rep = Rep::new(-(1 << 12), "Incomplete response");
+
+	/// Peer sent us state data that could not be verified.
+	pub const BAD_STATE: Rep = Rep::new(-(1 << 29), "Bad state");
 }
 
 enum PendingRequests {
@@ -209,6 +216,10 @@ pub struct ChainSync<B: BlockT> {
 	>,
 	/// Stats per peer about the number of concurrent block announce validations.
 	block_announce_validation_per_peer_stats: HashMap<PeerId, usize>,
+	/// Current syncing mode.
+	mode: SyncMode,
+	/// State download in progress, if any.
+	state_sync: Option<StateSync<B>>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -281,6 +292,8 @@ pub enum PeerSyncState<B: BlockT> {
 	DownloadingStale(B::Hash),
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
+	/// Downloading a chunk of the state of the state sync target block.
+	DownloadingState,
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -344,6 +357,15 @@ impl<B: BlockT> OnBlockData<B> {
 	}
 }
 
+/// Result of [`ChainSync::on_state_data`].
+#[derive(Debug)]
+pub enum OnStateData<B: BlockT> {
+	/// The block and state that should be imported.
+	Import(BlockOrigin, IncomingBlock<B>),
+	/// The state is not complete yet, more state requests need to be made.
+	Continue,
+}
+
 /// Result of [`ChainSync::poll_block_announce_validation`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PollBlockAnnounceValidation<H> {
@@ -442,15 +464,26 @@ enum HasSlotForBlockAnnounceValidation {
 impl<B: BlockT> ChainSync<B> {
 	/// Create a new instance.
 	pub fn new(
+		mode: SyncMode,
 		role: Roles,
 		client: Arc<dyn crate::chain::Client<B>>,
 		info: &BlockchainInfo<B>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
 	) -> Self {
+		// There is no point in downloading the state if we already have the state of our best
+		// block, e.g. after a restart of an already synced node.
+		let mode = match mode {
+			SyncMode::Fast if !role.is_full() => SyncMode::Full,
+			SyncMode::Fast if !info.best_number.is_zero() &&
+				client.block_status(&BlockId::Hash(info.best_hash))
+					.map_or(false, |s| s == BlockStatus::InChainWithState) => SyncMode::Full,
+			mode => mode,
+		};
+
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
-		if role.is_full() {
+		if role.is_full() && mode == SyncMode::Full {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -471,6 +504,8 @@ impl<B: BlockT> ChainSync<B> {
 			downloaded_blocks: 0,
 			block_announce_validation: Default::default(),
 			block_announce_validation_per_peer_stats: Default::default(),
+			mode,
+			state_sync: None,
 		}
 	}
 
@@ -712,7 +747,7 @@ impl<B: BlockT> ChainSync<B> {
 
 	/// Get an iterator over all block requests of all peers.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (&PeerId, BlockRequest<B>)> + '_ {
-		if self.pending_requests.is_empty() {
+		if self.pending_requests.is_empty() || self.state_sync.is_some() {
 			return Either::Left(std::iter::empty())
 		}
 		if self.queue_blocks.len() > MAX_IMPORTING_BLOCKS {
@@ -789,6 +824,129 @@ impl<B: BlockT> ChainSync<B> {
 		Either::Right(iter)
 	}
 
+	/// Get a state request, if any.
+	///
+	/// In [`SyncMode::Fast`] the state download starts once all headers up to the best block of
+	/// our peers have been imported. Block requests are paused until the state is imported.
+	pub fn state_request(&mut self) -> Option<(PeerId, StateRequest)> {
+		if self.mode != SyncMode::Fast {
+			return None
+		}
+		if self.peers.values().any(|peer| peer.state == PeerSyncState::DownloadingState) {
+			// Only one pending state request is allowed.
+			return None
+		}
+
+		if self.state_sync.is_none() && self.is_ready_for_state_sync() {
+			match self.client.header(BlockId::Hash(self.best_queued_hash)) {
+				Ok(Some(header)) => {
+					info!(
+						target: "sync",
+						"Starting state sync for #{} ({})",
+						self.best_queued_number,
+						self.best_queued_hash,
+					);
+					self.state_sync = Some(StateSync::new(header));
+				},
+				Ok(None) => {
+					debug!(target: "sync", "Missing header of state sync target {}", self.best_queued_hash);
+				},
+				Err(e) => {
+					debug!(target: "sync", "Error reading header of state sync target: {:?}", e);
+				},
+			}
+		}
+
+		let sync = self.state_sync.as_ref()?;
+		if sync.is_complete() {
+			return None
+		}
+		for (id, peer) in self.peers.iter_mut() {
+			if peer.state.is_available() && peer.best_number >= sync.target_block_num() {
+				trace!(target: "sync", "New state request to {}", id);
+				peer.state = PeerSyncState::DownloadingState;
+				return Some((id.clone(), sync.next_request()))
+			}
+		}
+		None
+	}
+
+	/// Whether all headers up to the best block of our peers have been imported and no block
+	/// data is in flight, so that the state of our best block can be downloaded.
+	fn is_ready_for_state_sync(&self) -> bool {
+		!self.peers.is_empty() &&
+			!self.best_queued_number.is_zero() &&
+			self.queue_blocks.is_empty() &&
+			self.status().state == SyncState::Idle &&
+			self.peers.values().all(|peer| matches!(
+				peer.state,
+				PeerSyncState::Available | PeerSyncState::DownloadingJustification(_)
+			))
+	}
+
+	/// Handle a response from the remote to a state request that we made.
+	///
+	/// Returns the block that must be imported together with its state once the state download
+	/// is complete.
+	pub fn on_state_data(
+		&mut self,
+		who: &PeerId,
+		response: StateResponse,
+	) -> Result<OnStateData<B>, BadPeer> {
+		match self.peers.get_mut(who) {
+			Some(peer) if peer.state == PeerSyncState::DownloadingState => {
+				peer.state = PeerSyncState::Available;
+			},
+			_ => {
+				debug!(target: "sync", "Ignored obsolete state response from {}", who);
+				return Ok(OnStateData::Continue)
+			},
+		}
+
+		let sync = match self.state_sync.as_mut() {
+			Some(sync) => sync,
+			None => {
+				debug!(target: "sync", "Ignored state response from {}, no state sync is active", who);
+				return Ok(OnStateData::Continue)
+			},
+		};
+
+		debug!(
+			target: "sync",
+			"Importing state data from {} with {} keys",
+			who,
+			response.entries.len(),
+		);
+		match sync.import(response) {
+			state::ImportResult::Import(hash, header, state) => {
+				info!(
+					target: "sync",
+					"State sync downloaded {} bytes of state for #{} ({}), importing",
+					sync.imported_bytes(),
+					header.number(),
+					hash,
+				);
+				self.queue_blocks.insert(hash);
+				let block = IncomingBlock {
+					hash,
+					header: Some(header),
+					body: None,
+					justification: None,
+					origin: None,
+					allow_missing_state: true,
+					import_existing: true,
+					state: Some(state),
+				};
+				Ok(OnStateData::Import(BlockOrigin::NetworkInitialSync, block))
+			},
+			state::ImportResult::Continue => Ok(OnStateData::Continue),
+			state::ImportResult::BadResponse => {
+				debug!(target: "sync", "Bad state data received from {}", who);
+				Err(BadPeer(who.clone(), rep::BAD_STATE))
+			},
+		}
+	}
+
 	/// Handle a response from the remote to a block request that we made.
 	///
 	/// `request` must be the original request that triggered `response`.
@@ -831,6 +989,7 @@ impl<B: BlockT> ChainSync<B> {
 										origin: block_data.origin,
 										allow_missing_state: true,
 										import_existing: false,
+										state: None,
 									}
 								}).collect()
 						}
@@ -850,6 +1009,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									state: None,
 								}
 							}).collect()
 						}
@@ -945,7 +1105,8 @@ impl<B: BlockT> ChainSync<B> {
 						}
 
 						| PeerSyncState::Available
-						| PeerSyncState::DownloadingJustification(..) => Vec::new()
+						| PeerSyncState::DownloadingJustification(..)
+						| PeerSyncState::DownloadingState => Vec::new()
 					}
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
@@ -959,6 +1120,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							state: None,
 						}
 					}).collect()
 				}
@@ -1076,6 +1238,22 @@ impl<B: BlockT> ChainSync<B> {
 				has_error = true;
 			}
 
+			if self.state_sync.as_ref().map_or(false, |sync| sync.target() == hash) {
+				if let Ok(BlockImportResult::ImportedUnknown(number, _, _)) = result {
+					info!(target: "sync", "State of #{} ({}) imported, switching to full sync", number, hash);
+					self.state_sync = None;
+					self.mode = SyncMode::Full;
+					if self.role.is_full() {
+						self.required_block_attributes |= BlockAttributes::BODY;
+					}
+					output.extend(self.restart());
+				} else {
+					warn!(target: "sync", "💔 Error importing state of {}: {:?}", hash, result);
+					// Download the state again.
+					self.state_sync = None;
+				}
+			}
+
 			match result {
 				Ok(BlockImportResult::ImportedKnown(number, who)) => {
 					if let Some(peer) = who.and_then(|p| self.peers.get_mut(&p)) {
@@ -1918,6 +2096,7 @@ mod test {
 		let peer_id = PeerId::random();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
@@ -1989,6 +2168,7 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
@@ -2155,6 +2335,7 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
@@ -2269,6 +2450,7 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
@@ -2388,6 +2570,7 @@ mod test {
 		let info = client.info();
 
 		let mut sync = ChainSync::new(
+			SyncMode::Full,
 			Roles::AUTHORITY,
 			client.clone(),
 			&info,
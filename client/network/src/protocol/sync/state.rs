@@ -0,0 +1,388 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+use codec::Encode;
+use crate::schema::v1::{StateRequest, StateResponse};
+use log::debug;
+use sp_consensus::ImportedState;
+use sp_core::storage::{
+	well_known_keys, ChildInfo, ChildType, PrefixedStorageKey, Storage, StorageChild,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, HashFor};
+use sp_trie::{Layout, TrieConfiguration};
+
+/// Downloads the flat key-value state of a single block in chunks and verifies it against the
+/// state root of the block header.
+///
+/// The top trie is downloaded first. Afterwards every child trie whose root is found in the top
+/// trie is downloaded and verified against that root. The entries of every response are written
+/// straight into the storage that is handed over for import, no intermediate copy is kept.
+pub struct StateSync<B: BlockT> {
+	target_block: B::Hash,
+	target_header: B::Header,
+	/// Child trie that is currently downloaded and its expected root. `None` while the top trie
+	/// is downloaded.
+	current_child: Option<(ChildInfo, Vec<u8>)>,
+	last_key: Vec<u8>,
+	/// Whether any entry of the trie that is currently downloaded was received.
+	has_entries: bool,
+	/// The verified tries and the trie that is currently downloaded.
+	state: Storage,
+	/// Child tries that are still to be downloaded, in reverse order.
+	pending_children: Vec<(ChildInfo, Vec<u8>)>,
+	complete: bool,
+	imported_bytes: u64,
+}
+
+/// Import state chunk result.
+pub enum ImportResult<B: BlockT> {
+	/// State is complete and verified, the block should be imported.
+	Import(B::Hash, B::Header, ImportedState<B>),
+	/// Continue downloading.
+	Continue,
+	/// Bad state chunk.
+	BadResponse,
+}
+
+impl<B: BlockT> StateSync<B> {
+	/// Create a new instance downloading the state of the block with the given header.
+	pub fn new(target: B::Header) -> Self {
+		StateSync {
+			target_block: target.hash(),
+			target_header: target,
+			current_child: None,
+			last_key: Vec::new(),
+			has_entries: false,
+			state: Storage::default(),
+			pending_children: Vec::new(),
+			complete: false,
+			imported_bytes: 0,
+		}
+	}
+
+	/// Entries of the trie that is currently downloaded.
+	fn current_entries(&mut self) -> &mut BTreeMap<Vec<u8>, Vec<u8>> {
+		match &self.current_child {
+			Some((child_info, _)) => {
+				&mut self.state.children_default
+					.entry(child_info.storage_key().to_vec())
+					.or_insert_with(|| StorageChild {
+						data: Default::default(),
+						child_info: child_info.clone(),
+					})
+					.data
+			},
+			None => &mut self.state.top,
+		}
+	}
+
+	/// Validate and import a state response.
+	pub fn import(&mut self, response: StateResponse) -> ImportResult<B> {
+		if response.entries.is_empty() && !response.complete {
+			debug!(target: "sync", "Bad state response: no entries");
+			return ImportResult::BadResponse
+		}
+		if self.complete {
+			debug!(target: "sync", "Unexpected state response after completion");
+			return ImportResult::BadResponse
+		}
+
+		// Validate the whole chunk before accepting any of it.
+		let mut last_key = if self.has_entries { Some(&self.last_key) } else { None };
+		for entry in &response.entries {
+			// Keys must be sent in order and must not repeat.
+			if last_key.map_or(false, |last| entry.key <= *last) {
+				debug!(target: "sync", "Bad state response: keys are not in ascending order");
+				return ImportResult::BadResponse
+			}
+			last_key = Some(&entry.key);
+		}
+
+		if let Some(last) = response.entries.last() {
+			self.last_key = last.key.clone();
+			self.has_entries = true;
+		}
+		let mut imported_bytes = 0;
+		let entries = self.current_entries();
+		for entry in response.entries {
+			imported_bytes += (entry.key.len() + entry.value.len()) as u64;
+			entries.insert(entry.key, entry.value);
+		}
+		self.imported_bytes += imported_bytes;
+
+		if !response.complete {
+			return ImportResult::Continue
+		}
+
+		let root = Layout::<HashFor<B>>::trie_root(self.current_entries().iter());
+		let expected_root = match &self.current_child {
+			Some((_, child_root)) => &child_root[..],
+			None => self.target_header.state_root().as_ref(),
+		};
+		if root.as_ref() != expected_root {
+			debug!(
+				target: "sync",
+				"Bad state response: state root mismatch, expected {:?}, got {:?}",
+				expected_root,
+				root,
+			);
+			// We can't tell which of the chunks was bad, start over with the current trie.
+			let discarded = std::mem::take(self.current_entries());
+			let discarded_bytes: usize = discarded.iter()
+				.map(|(key, value)| key.len() + value.len())
+				.sum();
+			self.imported_bytes = self.imported_bytes.saturating_sub(discarded_bytes as u64);
+			self.last_key.clear();
+			self.has_entries = false;
+			return ImportResult::BadResponse
+		}
+
+		if self.current_child.take().is_none() {
+			// The child roots are recalculated from the child tries on import, so they are moved
+			// out of the top trie.
+			let child_keys: Vec<_> = self.state.top.keys()
+				.filter(|key| well_known_keys::is_child_storage_key(key))
+				.cloned()
+				.collect();
+			for key in child_keys.into_iter().rev() {
+				let root = self.state.top.remove(&key).unwrap_or_default();
+				match ChildType::from_prefixed_key(&PrefixedStorageKey::new(key)) {
+					Some((ChildType::ParentKeyId, storage_key)) =>
+						self.pending_children.push((ChildInfo::new_default(storage_key), root)),
+					// Such a state can't be imported, it fails the state root check on import.
+					None => debug!(target: "sync", "Skipping child trie of unknown type"),
+				}
+			}
+		}
+
+		self.last_key.clear();
+		self.has_entries = false;
+		if let Some(child) = self.pending_children.pop() {
+			self.current_child = Some(child);
+			return ImportResult::Continue
+		}
+
+		self.complete = true;
+		ImportResult::Import(
+			self.target_block,
+			self.target_header.clone(),
+			ImportedState {
+				block: self.target_block,
+				state: std::mem::take(&mut self.state),
+			},
+		)
+	}
+
+	/// Produce next state request.
+	pub fn next_request(&self) -> StateRequest {
+		StateRequest {
+			block: self.target_block.encode(),
+			start: self.last_key.clone(),
+			child_storage_key: self.current_child.as_ref()
+				.map(|(child_info, _)| child_info.prefixed_storage_key().into_inner())
+				.unwrap_or_default(),
+		}
+	}
+
+	/// Check if the state is complete.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// Returns target block number.
+	pub fn target_block_num(&self) -> NumberFor<B> {
+		*self.target_header.number()
+	}
+
+	/// Returns target block hash.
+	pub fn target(&self) -> B::Hash {
+		self.target_block
+	}
+
+	/// Returns the number of key-value bytes downloaded so far.
+	pub fn imported_bytes(&self) -> u64 {
+		self.imported_bytes
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::schema::v1::StateEntry;
+	use sp_runtime::traits::Hash;
+	use substrate_test_runtime_client::runtime::{Block, Header};
+
+	fn header_with_state(state: &[(Vec<u8>, Vec<u8>)]) -> Header {
+		Header {
+			parent_hash: Default::default(),
+			number: 10,
+			state_root: HashFor::<Block>::trie_root(state.to_vec()),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		}
+	}
+
+	fn response(entries: &[(Vec<u8>, Vec<u8>)], complete: bool) -> StateResponse {
+		StateResponse {
+			entries: entries.iter()
+				.map(|(key, value)| StateEntry { key: key.clone(), value: value.clone() })
+				.collect(),
+			complete,
+		}
+	}
+
+	#[test]
+	fn imports_state_in_chunks() {
+		let state = vec![
+			(b"a".to_vec(), b"1".to_vec()),
+			(b"b".to_vec(), b"2".to_vec()),
+			(b"c".to_vec(), b"3".to_vec()),
+		];
+		let header = header_with_state(&state);
+		let mut sync = StateSync::<Block>::new(header.clone());
+
+		assert!(sync.next_request().start.is_empty());
+		assert!(matches!(sync.import(response(&state[..2], false)), ImportResult::Continue));
+		assert_eq!(sync.next_request().start, b"b".to_vec());
+		match sync.import(response(&state[2..], true)) {
+			ImportResult::Import(hash, imported_header, imported) => {
+				assert_eq!(hash, header.hash());
+				assert_eq!(imported_header, header);
+				assert_eq!(imported.state.top, state.into_iter().collect());
+				assert!(imported.state.children_default.is_empty());
+			},
+			_ => panic!("State should be imported"),
+		}
+		assert!(sync.is_complete());
+	}
+
+	#[test]
+	fn imports_child_tries() {
+		let child_state = vec![(b"x".to_vec(), b"1".to_vec()), (b"y".to_vec(), b"2".to_vec())];
+		let child_root = HashFor::<Block>::trie_root(child_state.clone());
+		let child_key = ChildInfo::new_default(b"child").prefixed_storage_key().into_inner();
+		let state = vec![
+			(child_key.clone(), child_root.as_ref().to_vec()),
+			(b"a".to_vec(), b"1".to_vec()),
+		];
+		let mut sync = StateSync::<Block>::new(header_with_state(&state));
+
+		assert!(matches!(sync.import(response(&state, true)), ImportResult::Continue));
+		let request = sync.next_request();
+		assert_eq!(request.child_storage_key, child_key);
+		assert!(request.start.is_empty());
+
+		// A child trie that does not match the root in the top trie is rejected.
+		assert!(matches!(
+			sync.import(response(&child_state[..1], true)),
+			ImportResult::BadResponse,
+		));
+		assert!(!sync.is_complete());
+
+		assert!(matches!(sync.import(response(&child_state[..1], false)), ImportResult::Continue));
+		assert_eq!(sync.next_request().start, b"x".to_vec());
+		match sync.import(response(&child_state[1..], true)) {
+			ImportResult::Import(_, _, imported) => {
+				let child_info = ChildInfo::new_default(b"child");
+				// The child root is not part of the imported top trie.
+				assert_eq!(imported.state.top, state[1..].iter().cloned().collect());
+				assert_eq!(
+					imported.state.children_default.get(&b"child".to_vec()),
+					Some(&StorageChild { data: child_state.into_iter().collect(), child_info }),
+				);
+			},
+			_ => panic!("State should be imported"),
+		}
+		assert!(sync.is_complete());
+	}
+
+	#[test]
+	fn imports_state_over_many_responses() {
+		let child_tries = [b"first".to_vec(), b"second".to_vec()];
+		let child_states: Vec<Vec<_>> = child_tries.iter()
+			.map(|name| (0u8..6).map(|i| (vec![i], name.clone())).collect())
+			.collect();
+		let mut state: Vec<_> = (0u8..9).map(|i| (vec![b'a', i], vec![i])).collect();
+		for (name, child_state) in child_tries.iter().zip(&child_states) {
+			let child_key = ChildInfo::new_default(name).prefixed_storage_key().into_inner();
+			let child_root = HashFor::<Block>::trie_root(child_state.clone());
+			state.push((child_key, child_root.as_ref().to_vec()));
+		}
+		state.sort();
+		let total_bytes: usize = state.iter().chain(child_states.iter().flatten())
+			.map(|(key, value)| key.len() + value.len())
+			.sum();
+		let mut sync = StateSync::<Block>::new(header_with_state(&state));
+
+		// The top trie, then every child trie in order, each in chunks of up to four entries.
+		let mut tries = vec![(Vec::new(), state.clone())];
+		for (name, child_state) in child_tries.iter().zip(&child_states) {
+			let child_key = ChildInfo::new_default(name).prefixed_storage_key().into_inner();
+			tries.push((child_key, child_state.clone()));
+		}
+		let mut result = None;
+		for (child_key, entries) in tries {
+			let chunks: Vec<_> = entries.chunks(4).collect();
+			for (i, chunk) in chunks.iter().enumerate() {
+				let request = sync.next_request();
+				assert_eq!(request.child_storage_key, child_key);
+				let start = if i == 0 { Vec::new() } else { chunks[i - 1][3].0.clone() };
+				assert_eq!(request.start, start);
+				match sync.import(response(chunk, i == chunks.len() - 1)) {
+					ImportResult::Continue => {},
+					ImportResult::Import(_, _, imported) => result = Some(imported),
+					ImportResult::BadResponse => panic!("Response should be accepted"),
+				}
+			}
+		}
+
+		let imported = result.expect("State should be imported");
+		assert!(sync.is_complete());
+		assert_eq!(sync.imported_bytes(), total_bytes as u64);
+		let top = state.into_iter().filter(|(key, _)| !well_known_keys::is_child_storage_key(key));
+		assert_eq!(imported.state.top, top.collect());
+		assert_eq!(imported.state.children_default.len(), 2);
+		for (name, child_state) in child_tries.iter().zip(child_states) {
+			assert_eq!(
+				imported.state.children_default.get(name).map(|child| &child.data),
+				Some(&child_state.into_iter().collect()),
+			);
+		}
+	}
+
+	#[test]
+	fn rejects_state_with_wrong_root() {
+		let state = vec![(b"a".to_vec(), b"1".to_vec())];
+		let mut sync = StateSync::<Block>::new(header_with_state(&state));
+
+		assert!(matches!(
+			sync.import(response(&[(b"a".to_vec(), b"2".to_vec())], true)),
+			ImportResult::BadResponse,
+		));
+	}
+
+	#[test]
+	fn rejects_unordered_keys() {
+		let state = vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())];
+		let mut sync = StateSync::<Block>::new(header_with_state(&state));
+
+		assert!(matches!(sync.import(response(&state[1..], false)), ImportResult::Continue));
+		assert!(matches!(sync.import(response(&state[..1], true)), ImportResult::BadResponse));
+	}
+}
@@ -177,7 +177,8 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 		}
 
 		let transactions_handler_proto = transactions::TransactionsHandlerPrototype::new(
-			params.protocol_id.clone()
+			params.protocol_id.clone(),
+			params.network_config.max_pending_transactions_per_peer,
 		);
 		params.network_config.extra_sets.insert(0, transactions_handler_proto.set_config());
 
@@ -339,6 +340,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 					light_client_request_sender,
 					discovery_config,
 					params.block_request_protocol_config,
+					params.state_request_protocol_config,
 					bitswap,
 					params.light_client_request_protocol_config,
 					params.network_config.request_response_protocols,
@@ -473,6 +475,11 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 		self.network_service.user_protocol().num_active_peers()
 	}
 
+	/// Returns the list of reserved peers.
+	pub fn reserved_peers(&self) -> impl Iterator<Item = &PeerId> {
+		self.network_service.user_protocol().reserved_peers()
+	}
+
 	/// Current global sync state.
 	pub fn sync_state(&self) -> SyncState {
 		self.network_service.user_protocol().sync_state()
@@ -79,6 +79,7 @@ mod rep {
 
 struct Metrics {
 	propagated_transactions: Counter<U64>,
+	ignored_transactions: Counter<U64>,
 }
 
 impl Metrics {
@@ -88,6 +89,10 @@ impl Metrics {
 				"sync_propagated_transactions",
 				"Number of transactions propagated to at least one peer",
 			)?, r)?,
+			ignored_transactions: register(Counter::new(
+				"sync_ignored_transactions",
+				"Number of received transactions ignored because too many were pending validation",
+			)?, r)?,
 		})
 	}
 }
@@ -116,11 +121,15 @@ impl<H: ExHashT> Future for PendingTransaction<H> {
 /// Prototype for a [`TransactionsHandler`].
 pub struct TransactionsHandlerPrototype {
 	protocol_name: Cow<'static, str>,
+	max_pending_transactions_per_peer: usize,
 }
 
 impl TransactionsHandlerPrototype {
 	/// Create a new instance.
-	pub fn new(protocol_id: ProtocolId) -> Self {
+	///
+	/// At most `max_pending_transactions_per_peer` transactions received from a single peer are
+	/// validated concurrently. Further transactions from that peer are ignored.
+	pub fn new(protocol_id: ProtocolId, max_pending_transactions_per_peer: usize) -> Self {
 		TransactionsHandlerPrototype {
 			protocol_name: Cow::from({
 				let mut proto = String::new();
@@ -128,7 +137,8 @@ impl TransactionsHandlerPrototype {
 				proto.push_str(protocol_id.as_ref());
 				proto.push_str("/transactions/1");
 				proto
-			})
+			}),
+			max_pending_transactions_per_peer,
 		}
 	}
 
@@ -167,6 +177,7 @@ impl TransactionsHandlerPrototype {
 			propagate_timeout: Box::pin(interval(PROPAGATE_TIMEOUT)),
 			pending_transactions: FuturesUnordered::new(),
 			pending_transactions_peers: HashMap::new(),
+			max_pending_transactions_per_peer: self.max_pending_transactions_per_peer,
 			gossip_enabled: gossip_enabled.clone(),
 			service,
 			event_stream,
@@ -236,6 +247,8 @@ pub struct TransactionsHandler<B: BlockT + 'static, H: ExHashT> {
 	/// imported. This prevents that we import the same transaction
 	/// multiple times concurrently.
 	pending_transactions_peers: HashMap<H, Vec<PeerId>>,
+	/// Maximum number of transactions of a single peer awaiting validation.
+	max_pending_transactions_per_peer: usize,
 	/// Network service to use to send messages and manage peers.
 	service: Arc<NetworkService<B, H>>,
 	/// Stream of networking events.
@@ -255,9 +268,29 @@ pub struct TransactionsHandler<B: BlockT + 'static, H: ExHashT> {
 struct Peer<H: ExHashT> {
 	/// Holds a set of transactions known to this peer.
 	known_transactions: LruHashSet<H>,
+	/// Number of transactions sent by this peer that are awaiting validation.
+	pending_transactions: usize,
 	role: ObservedRole,
 }
 
+impl<H: ExHashT> Peer<H> {
+	/// Note a transaction of this peer that awaits validation.
+	///
+	/// Returns `false` without noting the transaction if `limit` transactions are pending already.
+	fn note_pending_transaction(&mut self, limit: usize) -> bool {
+		if self.pending_transactions >= limit {
+			return false;
+		}
+		self.pending_transactions += 1;
+		true
+	}
+
+	/// Note that the validation of a transaction of this peer finished.
+	fn note_validated_transaction(&mut self) {
+		self.pending_transactions = self.pending_transactions.saturating_sub(1);
+	}
+}
+
 impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 	/// Turns the [`TransactionsHandler`] into a future that should run forever and not be
 	/// interrupted.
@@ -322,6 +355,7 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 				let _was_in = self.peers.insert(remote, Peer {
 					known_transactions: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_TRANSACTIONS)
 						.expect("Constant is nonzero")),
+					pending_transactions: 0,
 					role,
 				});
 				debug_assert!(_was_in.is_none());
@@ -374,13 +408,30 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 
 		trace!(target: "sync", "Received {} transactions from {}", transactions.len(), who);
 		if let Some(ref mut peer) = self.peers.get_mut(&who) {
-			for t in transactions {
+			let total = transactions.len();
+			for (index, t) in transactions.into_iter().enumerate() {
 				if self.pending_transactions.len() > MAX_PENDING_TRANSACTIONS {
 					debug!(
 						target: "sync",
 						"Ignoring any further transactions that exceed `MAX_PENDING_TRANSACTIONS`({}) limit",
 						MAX_PENDING_TRANSACTIONS,
 					);
+					if let Some(ref metrics) = self.metrics {
+						metrics.ignored_transactions.inc_by((total - index) as _);
+					}
+					break;
+				}
+
+				if !peer.note_pending_transaction(self.max_pending_transactions_per_peer) {
+					debug!(
+						target: "sync",
+						"Ignoring any further transactions from {} that exceed the per peer limit ({})",
+						who,
+						self.max_pending_transactions_per_peer,
+					);
+					if let Some(ref metrics) = self.metrics {
+						metrics.ignored_transactions.inc_by((total - index) as _);
+					}
 					break;
 				}
 
@@ -406,6 +457,10 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 	}
 
 	fn on_handle_transaction_import(&mut self, who: PeerId, import: TransactionImport) {
+		if let Some(peer) = self.peers.get_mut(&who) {
+			peer.note_validated_transaction();
+		}
+
 		match import {
 			TransactionImport::KnownGood => self.service.report_peer(who, rep::ANY_TRANSACTION_REFUND),
 			TransactionImport::NewGood => self.service.report_peer(who, rep::GOOD_TRANSACTION),
@@ -486,3 +541,41 @@ impl<B: BlockT + 'static, H: ExHashT> TransactionsHandler<B, H> {
 		self.transaction_pool.on_broadcasted(propagated_to);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	fn peer() -> Peer<H256> {
+		Peer {
+			known_transactions: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_TRANSACTIONS)
+				.expect("Constant is nonzero")),
+			pending_transactions: 0,
+			role: ObservedRole::Full,
+		}
+	}
+
+	#[test]
+	fn pending_transactions_per_peer_are_capped() {
+		let mut peer = peer();
+
+		assert!(peer.note_pending_transaction(2));
+		assert!(peer.note_pending_transaction(2));
+		assert!(!peer.note_pending_transaction(2));
+		assert_eq!(peer.pending_transactions, 2);
+
+		// A finished validation makes room for another transaction.
+		peer.note_validated_transaction();
+		assert!(peer.note_pending_transaction(2));
+		assert!(!peer.note_pending_transaction(2));
+	}
+
+	#[test]
+	fn validated_transactions_do_not_underflow() {
+		let mut peer = peer();
+
+		peer.note_validated_transaction();
+		assert_eq!(peer.pending_transactions, 0);
+	}
+}
@@ -21,6 +21,8 @@
 mod block_import;
 #[cfg(test)]
 mod sync;
+#[cfg(test)]
+mod transactions;
 
 use std::{
 	borrow::Cow, collections::HashMap, pin::Pin, sync::Arc, marker::PhantomData,
@@ -30,6 +32,7 @@ use std::{
 use libp2p::build_multiaddr;
 use log::trace;
 use sc_network::block_request_handler::{self, BlockRequestHandler};
+use sc_network::state_request_handler::{self, StateRequestHandler};
 use sc_network::light_client_requests::{self, handler::LightClientRequestHandler};
 use sp_blockchain::{
 	HeaderBackend, Result as ClientResult,
@@ -56,13 +59,14 @@ use sc_network::{
 	NetworkWorker, NetworkService, config::{ProtocolId, MultiaddrWithPeerId, NonReservedPeerMode},
 	Multiaddr,
 };
-use sc_network::config::{NetworkConfiguration, NonDefaultSetConfig, TransportConfig};
+use sc_network::config::{NetworkConfiguration, NonDefaultSetConfig, SyncMode, TransportConfig};
+use sc_network::config::{TransactionImport, TransactionImportFuture, TransactionPool};
 use libp2p::PeerId;
 use parking_lot::Mutex;
-use sp_core::H256;
+use sp_core::{H256, storage::Storage};
 use sc_network::config::ProtocolConfig;
 use sp_runtime::generic::{BlockId, OpaqueDigestItemId};
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor};
 use sp_runtime::Justification;
 use substrate_test_runtime_client::{self, AccountKeyring};
 use sc_service::client::Client;
@@ -610,6 +614,57 @@ pub struct FullPeerConfig {
 	///
 	/// If `None`, it will be connected to all other peers.
 	pub connect_to_peers: Option<Vec<usize>>,
+	/// Syncing mode.
+	pub sync_mode: SyncMode,
+	/// Extra genesis storage of the peer.
+	pub extra_storage: Option<Storage>,
+	/// Transaction pool that is used to import and propagate transactions.
+	///
+	/// If `None`, the peer will discard all incoming transactions.
+	pub transaction_pool: Option<Arc<dyn TransactionPool<Hash, Block>>>,
+}
+
+/// A transaction pool that imports every transaction it is given, without validating it.
+///
+/// Can be passed to a full peer to test the propagation of transactions.
+#[derive(Default)]
+pub struct TestTransactionPool {
+	transactions: Mutex<HashMap<Hash, Extrinsic>>,
+}
+
+impl TestTransactionPool {
+	/// Adds a transaction to the pool, returning its hash.
+	pub fn push(&self, transaction: Extrinsic) -> Hash {
+		let hash = <Block as BlockT>::Hashing::hash_of(&transaction);
+		self.transactions.lock().insert(hash, transaction);
+		hash
+	}
+
+	/// Returns whether the pool contains the transaction with the given hash.
+	pub fn contains(&self, hash: &Hash) -> bool {
+		self.transactions.lock().contains_key(hash)
+	}
+}
+
+impl TransactionPool<Hash, Block> for TestTransactionPool {
+	fn transactions(&self) -> Vec<(Hash, Extrinsic)> {
+		self.transactions.lock().iter().map(|(h, t)| (*h, t.clone())).collect()
+	}
+
+	fn hash_of(&self, transaction: &Extrinsic) -> Hash {
+		<Block as BlockT>::Hashing::hash_of(transaction)
+	}
+
+	fn import(&self, transaction: Extrinsic) -> TransactionImportFuture {
+		self.push(transaction);
+		Box::pin(future::ready(TransactionImport::NewGood))
+	}
+
+	fn on_broadcasted(&self, _: HashMap<Hash, Vec<String>>) {}
+
+	fn transaction(&self, hash: &Hash) -> Option<Extrinsic> {
+		self.transactions.lock().get(hash).cloned()
+	}
 }
 
 pub trait TestNetFactory: Sized {
@@ -667,10 +722,21 @@ pub trait TestNetFactory: Sized {
 
 	/// Add a full peer.
 	fn add_full_peer_with_config(&mut self, config: FullPeerConfig) {
-		let test_client_builder = match config.keep_blocks {
+		let mut test_client_builder = match config.keep_blocks {
 			Some(keep_blocks) => TestClientBuilder::with_pruning_window(keep_blocks),
 			None => TestClientBuilder::with_default_backend(),
 		};
+		if let Some(storage) = config.extra_storage {
+			for (key, value) in storage.top {
+				test_client_builder = test_client_builder.add_extra_storage(key, value);
+			}
+			for (_, child) in storage.children_default {
+				for (key, value) in child.data {
+					test_client_builder = test_client_builder
+						.add_extra_child_storage(&child.child_info, key, value);
+				}
+			}
+		}
 		let backend = test_client_builder.backend();
 		let (c, longest_chain) = test_client_builder.build_with_longest_chain();
 		let client = Arc::new(c);
@@ -707,6 +773,7 @@ pub trait TestNetFactory: Sized {
 		network_config.transport = TransportConfig::MemoryOnly;
 		network_config.listen_addresses = vec![listen_addr.clone()];
 		network_config.allow_non_globals_in_dht = true;
+		network_config.sync_mode = config.sync_mode;
 		network_config.extra_sets = config.notifications_protocols.into_iter().map(|p| {
 			NonDefaultSetConfig {
 				notifications_protocol: p,
@@ -736,8 +803,18 @@ pub trait TestNetFactory: Sized {
 			protocol_config
 		};
 
+		let state_request_protocol_config = {
+			let (handler, protocol_config) = StateRequestHandler::new(
+				&protocol_id,
+				client.clone(),
+				50,
+			);
+			self.spawn_task(handler.run().boxed());
+			protocol_config
+		};
+
 		let light_client_request_protocol_config = {
-			let (handler, protocol_config) = LightClientRequestHandler::new(&protocol_id, client.clone());
+			let (handler, protocol_config) = LightClientRequestHandler::new(&protocol_id, client.clone(), 50);
 			self.spawn_task(handler.run().boxed());
 			protocol_config
 		};
@@ -749,13 +826,15 @@ pub trait TestNetFactory: Sized {
 			network_config,
 			chain: client.clone(),
 			on_demand: None,
-			transaction_pool: Arc::new(EmptyTransactionPool),
+			transaction_pool: config.transaction_pool
+				.unwrap_or_else(|| Arc::new(EmptyTransactionPool)),
 			protocol_id,
 			import_queue,
 			block_announce_validator: config.block_announce_validator
 				.unwrap_or_else(|| Box::new(DefaultBlockAnnounceValidator)),
 			metrics_registry: None,
 			block_request_protocol_config,
+			state_request_protocol_config,
 			light_client_request_protocol_config,
 		}).unwrap();
 
@@ -830,6 +909,10 @@ pub trait TestNetFactory: Sized {
 			&protocol_id,
 		);
 
+		let state_request_protocol_config = state_request_handler::generate_protocol_config(
+			&protocol_id,
+		);
+
 		let light_client_request_protocol_config =
 			light_client_requests::generate_protocol_config(&protocol_id);
 
@@ -846,6 +929,7 @@ pub trait TestNetFactory: Sized {
 			block_announce_validator: Box::new(DefaultBlockAnnounceValidator),
 			metrics_registry: None,
 			block_request_protocol_config,
+			state_request_protocol_config,
 			light_client_request_protocol_config,
 		}).unwrap();
 
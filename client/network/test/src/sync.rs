@@ -979,3 +979,93 @@ fn multiple_requests_are_accepted_as_long_as_they_are_not_fulfilled() {
 		Poll::Ready(())
 	}));
 }
+
+#[test]
+fn syncs_state() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(Default::default());
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Fast,
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+	net.peer(1).push_blocks(64, false);
+
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+
+		let client = net.peer(2).client().as_full().expect("peer 2 is a full peer; qed");
+		if client.block_status(&BlockId::Number(64)).unwrap() != sp_consensus::BlockStatus::InChainWithState {
+			return Poll::Pending;
+		}
+
+		Poll::Ready(())
+	}));
+
+	// Blocks before the state sync target were only imported as headers.
+	let client = net.peer(2).client().as_full().unwrap();
+	assert_eq!(client.block_status(&BlockId::Number(63)).unwrap(), sp_consensus::BlockStatus::InChainPruned);
+
+	// Once the state is imported the node continues with a regular full sync.
+	net.peer(0).push_blocks(1, false);
+	net.peer(1).push_blocks(1, false);
+	net.block_until_sync();
+	assert_eq!(net.peer(2).client().info().best_number, 65);
+}
+
+#[test]
+fn syncs_state_with_child_trie() {
+	use sc_client_api::StorageProvider;
+	use sp_core::storage::{ChildInfo, StorageChild, StorageData, StorageKey};
+
+	sp_tracing::try_init_simple();
+	let child_info = ChildInfo::new_default(b"child");
+	let storage = || {
+		let mut storage = Storage::default();
+		storage.children_default.insert(child_info.storage_key().to_vec(), StorageChild {
+			data: vec![(b"key".to_vec(), b"value".to_vec())].into_iter().collect(),
+			child_info: child_info.clone(),
+		});
+		Some(storage)
+	};
+
+	let mut net = TestNet::new(0);
+	net.add_full_peer_with_config(FullPeerConfig {
+		extra_storage: storage(),
+		..Default::default()
+	});
+	net.add_full_peer_with_config(FullPeerConfig {
+		extra_storage: storage(),
+		..Default::default()
+	});
+	net.add_full_peer_with_config(FullPeerConfig {
+		sync_mode: SyncMode::Fast,
+		extra_storage: storage(),
+		..Default::default()
+	});
+	net.peer(0).push_blocks(64, false);
+	net.peer(1).push_blocks(64, false);
+
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+
+		let client = net.peer(2).client().as_full().expect("peer 2 is a full peer; qed");
+		let status = client.block_status(&BlockId::Number(64)).unwrap();
+		if status != sp_consensus::BlockStatus::InChainWithState {
+			return Poll::Pending;
+		}
+
+		Poll::Ready(())
+	}));
+
+	let client = net.peer(2).client().as_full().unwrap();
+	let key = StorageKey(b"key".to_vec());
+	assert_eq!(
+		client.child_storage(&BlockId::Number(64), &child_info, &key).unwrap(),
+		Some(StorageData(b"value".to_vec())),
+	);
+	// Honest peers serving child tries must not be disconnected.
+	assert_eq!(net.peer(2).num_peers(), 2);
+}
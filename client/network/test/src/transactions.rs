@@ -0,0 +1,55 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use futures::executor::block_on;
+use super::*;
+
+fn transfer() -> Extrinsic {
+	Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+		amount: 42,
+		nonce: 0,
+	}.into_signed_tx()
+}
+
+#[test]
+fn transactions_are_propagated_to_full_peers() {
+	sp_tracing::try_init_simple();
+	let mut net = TestNet::new(0);
+	let pools: Vec<_> = (0..3).map(|_| Arc::new(TestTransactionPool::default())).collect();
+	for pool in &pools {
+		net.add_full_peer_with_config(FullPeerConfig {
+			transaction_pool: Some(pool.clone()),
+			..Default::default()
+		});
+	}
+	net.block_until_connected();
+
+	let hash = pools[0].push(transfer());
+	net.peer(0).network_service().propagate_transaction(hash);
+
+	block_on(futures::future::poll_fn::<(), _>(|cx| {
+		net.poll(cx);
+		if pools.iter().all(|pool| pool.contains(&hash)) {
+			Poll::Ready(())
+		} else {
+			Poll::Pending
+		}
+	}));
+}
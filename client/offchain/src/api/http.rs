@@ -39,6 +39,13 @@ use std::sync::Arc;
 use hyper::{Client as HyperClient, Body, client};
 use hyper_rustls::HttpsConnector;
 
+/// Maximum number of HTTP requests a single offchain worker can have in preparation or in
+/// progress at the same time.
+///
+/// Requests only free their slot once they have failed or their response body has been fully
+/// read, which prevents a worker from exhausting the connections of the shared pool.
+const MAX_CONCURRENT_REQUESTS: usize = 64;
+
 /// Wrapper struct used for keeping the hyper_rustls client running.
 #[derive(Clone)]
 pub struct SharedClient(Arc<HyperClient<HttpsConnector<client::HttpConnector>, Body>>);
@@ -140,6 +147,14 @@ impl HttpApi {
 		*request.method_mut() = hyper::Method::from_bytes(method.as_bytes()).map_err(|_| ())?;
 		*request.uri_mut() = hyper::Uri::from_maybe_shared(uri.to_owned()).map_err(|_| ())?;
 
+		if self.requests.len() >= MAX_CONCURRENT_REQUESTS {
+			error!(
+				"Offchain worker exceeded the limit of {} concurrent HTTP requests",
+				MAX_CONCURRENT_REQUESTS,
+			);
+			return Err(());
+		}
+
 		let new_id = self.next_id;
 		debug_assert!(!self.requests.contains_key(&new_id));
 		match self.next_id.0.checked_add(1) {
@@ -779,6 +794,27 @@ mod tests {
 		};
 	}
 
+	#[test]
+	fn request_start_limits_concurrent_requests() {
+		let (mut api, addr) = build_api_server!();
+
+		let ids = (0..super::MAX_CONCURRENT_REQUESTS)
+			.map(|_| api.request_start("GET", &format!("http://{}", addr)).unwrap())
+			.collect::<Vec<_>>();
+
+		match api.request_start("GET", &format!("http://{}", addr)) {
+			Err(()) => {}
+			Ok(_) => panic!()
+		};
+
+		// Reading a response until the end frees its slot.
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+		let mut buf = vec![0; 2048];
+		while api.response_read_body(ids[0], &mut buf, Some(deadline)).unwrap() != 0 {}
+
+		api.request_start("GET", &format!("http://{}", addr)).unwrap();
+	}
+
 	#[test]
 	fn request_add_header_invalid_call() {
 		let (mut api, addr) = build_api_server!();
@@ -37,8 +37,9 @@ mod peersstate;
 use std::{collections::HashSet, collections::VecDeque};
 use futures::prelude::*;
 use log::{debug, error, trace};
+use prometheus_endpoint::{register, Counter, CounterVec, Opts, PrometheusError, Registry, U64};
 use serde_json::json;
-use std::{collections::HashMap, pin::Pin, task::{Context, Poll}, time::Duration};
+use std::{collections::HashMap, fmt, pin::Pin, task::{Context, Poll}, time::Duration};
 use wasm_timer::Instant;
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedSender, TracingUnboundedReceiver};
 
@@ -252,6 +253,38 @@ pub struct Peerset {
 	created: Instant,
 	/// Last time when we updated the reputations of connected nodes.
 	latest_time_update: Instant,
+	/// Prometheus metrics, if registered through [`Peerset::register_metrics`].
+	metrics: Option<Metrics>,
+}
+
+/// Prometheus metrics of a [`Peerset`].
+struct Metrics {
+	reputation_changes: CounterVec<U64>,
+	banned_peers: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Metrics {
+			reputation_changes: register(CounterVec::new(
+				Opts::new(
+					"sub_libp2p_peerset_reputation_changes_total",
+					"Number of reputation changes reported to the peerset manager, by reason",
+				),
+				&["reason"],
+			)?, registry)?,
+			banned_peers: register(Counter::new(
+				"sub_libp2p_peerset_banned_peers_total",
+				"Number of times a peer was disconnected for falling under the banned threshold",
+			)?, registry)?,
+		})
+	}
+}
+
+impl fmt::Debug for Metrics {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Metrics").finish()
+	}
 }
 
 impl Peerset {
@@ -279,6 +312,7 @@ impl Peerset {
 				message_queue: VecDeque::new(),
 				created: now,
 				latest_time_update: now,
+				metrics: None,
 			}
 		};
 
@@ -300,6 +334,17 @@ impl Peerset {
 		(peerset, handle)
 	}
 
+	/// Registers the Prometheus metrics of this peerset in the given registry.
+	pub fn register_metrics(&mut self, registry: &Registry) -> Result<(), PrometheusError> {
+		self.metrics = Some(Metrics::register(registry)?);
+		Ok(())
+	}
+
+	/// Returns the list of reserved peers of the given set.
+	pub fn reserved_peers(&self, set_id: SetId) -> impl Iterator<Item = &PeerId> {
+		self.reserved_nodes[set_id.0].0.iter()
+	}
+
 	fn on_add_reserved_peer(&mut self, set_id: SetId, peer_id: PeerId) {
 		let newly_inserted = self.reserved_nodes[set_id.0].0.insert(peer_id.clone());
 		if !newly_inserted {
@@ -410,6 +455,10 @@ impl Peerset {
 		// We want reputations to be up-to-date before adjusting them.
 		self.update_time();
 
+		if let Some(metrics) = &self.metrics {
+			metrics.reputation_changes.with_label_values(&[change.reason]).inc();
+		}
+
 		let mut reputation = self.data.peer_reputation(peer_id.clone());
 		reputation.add_reputation(change.value);
 		if reputation.reputation() >= BANNED_THRESHOLD {
@@ -425,6 +474,10 @@ impl Peerset {
 
 		drop(reputation);
 
+		if let Some(metrics) = &self.metrics {
+			metrics.banned_peers.inc();
+		}
+
 		for set_index in 0..self.data.num_sets() {
 			if let peersstate::Peer::Connected(peer) = self.data.peer(set_index, &peer_id) {
 				let peer = peer.disconnect();
@@ -890,4 +943,38 @@ mod tests {
 
 		futures::executor::block_on(fut);
 	}
+
+	#[test]
+	fn test_peerset_reputation_metrics() {
+		let (mut peerset, handle) = Peerset::from_config(PeersetConfig {
+			sets: vec![SetConfig {
+				in_peers: 25,
+				out_peers: 25,
+				bootnodes: vec![],
+				reserved_nodes: Default::default(),
+				reserved_only: false,
+			}],
+		});
+		peerset.register_metrics(&prometheus_endpoint::Registry::new()).unwrap();
+
+		let peer_id = PeerId::random();
+		handle.report_peer(peer_id.clone(), ReputationChange::new(-10, "Bad message"));
+		handle.report_peer(peer_id.clone(), ReputationChange::new(-10, "Bad message"));
+		handle.report_peer(peer_id.clone(), ReputationChange::new(BANNED_THRESHOLD, "Misbehaved"));
+
+		let fut = futures::future::poll_fn(move |cx| {
+			// We need one polling for the messages to be processed.
+			assert_eq!(Stream::poll_next(Pin::new(&mut peerset), cx), Poll::Pending);
+
+			let metrics = peerset.metrics.as_ref().unwrap();
+			let changes = |reason| metrics.reputation_changes.with_label_values(&[reason]).get();
+			assert_eq!(changes("Bad message"), 2);
+			assert_eq!(changes("Misbehaved"), 1);
+			assert_eq!(metrics.banned_peers.get(), 1);
+
+			Poll::Ready(())
+		});
+
+		futures::executor::block_on(fut);
+	}
 }
@@ -18,7 +18,9 @@
 
 //! Prometheus basic proposer metrics.
 
-use prometheus_endpoint::{register, PrometheusError, Registry, Histogram, HistogramOpts, Gauge, U64};
+use prometheus_endpoint::{
+	register, PrometheusError, Registry, Histogram, HistogramOpts, Gauge, U64, CounterVec, Opts,
+};
 
 /// Optional shareable link to basic authorship metrics.
 #[derive(Clone, Default)]
@@ -40,11 +42,50 @@ impl MetricsLink {
 	}
 }
 
+/// The reason why proposing a block ended.
+pub enum EndProposingReason {
+	/// There are no more transactions in the pool.
+	NoMoreTransactions,
+	/// The deadline for proposing was reached.
+	HitDeadline,
+	/// The block ran out of resources and the soft deadline was reached.
+	HitBlockLimit,
+}
+
+impl EndProposingReason {
+	fn as_str(&self) -> &'static str {
+		match self {
+			EndProposingReason::NoMoreTransactions => "no_more_transactions",
+			EndProposingReason::HitDeadline => "hit_deadline",
+			EndProposingReason::HitBlockLimit => "hit_block_limit",
+		}
+	}
+}
+
+/// The reason why a transaction from the pool was not included in a block.
+pub enum SkippedTransactionReason {
+	/// The transaction did not fit into the block.
+	ExhaustedResources,
+	/// The transaction was invalid.
+	Invalid,
+}
+
+impl SkippedTransactionReason {
+	fn as_str(&self) -> &'static str {
+		match self {
+			SkippedTransactionReason::ExhaustedResources => "exhausted_resources",
+			SkippedTransactionReason::Invalid => "invalid",
+		}
+	}
+}
+
 /// Authorship metrics.
 #[derive(Clone)]
 pub struct Metrics {
 	pub block_constructed: Histogram,
 	pub number_of_transactions: Gauge<U64>,
+	pub skipped_transactions: CounterVec<U64>,
+	pub end_proposing_reason: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -64,6 +105,36 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			skipped_transactions: register(
+				CounterVec::new(
+					Opts::new(
+						"proposer_skipped_transactions",
+						"Number of pool transactions not included in the block, by reason",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+			end_proposing_reason: register(
+				CounterVec::new(
+					Opts::new(
+						"proposer_end_proposal_reason",
+						"The reason why the block proposing was ended",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
 		})
 	}
+
+	/// Report that a transaction was skipped for the given `reason`.
+	pub fn report_skipped_transaction(&self, reason: SkippedTransactionReason) {
+		self.skipped_transactions.with_label_values(&[reason.as_str()]).inc();
+	}
+
+	/// Report the reason why proposing a block ended.
+	pub fn report_end_proposing_reason(&self, reason: EndProposingReason) {
+		self.end_proposing_reason.with_label_values(&[reason.as_str()]).inc();
+	}
 }
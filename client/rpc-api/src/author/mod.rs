@@ -52,6 +52,12 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[rpc(name = "author_rotateKeys")]
 	fn rotate_keys(&self) -> Result<Bytes>;
 
+	/// Generate new session keys and returns the corresponding public keys with their key types.
+	///
+	/// Each key type is the four character identifier of the key type, e.g. `babe`.
+	#[rpc(name = "author_rotateKeysTyped")]
+	fn rotate_keys_typed(&self) -> Result<Vec<(Bytes, String)>>;
+
 	/// Checks if the keystore has private keys for the given session public keys.
 	///
 	/// `session_keys` is the SCALE encoded session keys object from the runtime.
@@ -62,6 +68,8 @@ pub trait AuthorApi<Hash, BlockHash> {
 
 	/// Checks if the keystore has private keys for the given public key and key type.
 	///
+	/// `key_type` is the four character identifier of the key type, e.g. `babe`.
+	///
 	/// Returns `true` if a private key could be found.
 	#[rpc(name = "author_hasKey")]
 	fn has_key(&self, public_key: Bytes, key_type: String) -> Result<bool>;
@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Events emitted by the `chainHead` subscriptions.
+
+use serde::{Serialize, Deserialize};
+use sp_version::RuntimeVersion;
+
+/// Runtime of a block reported in a `newBlock` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum RuntimeEvent {
+	/// The runtime could be loaded and its version queried.
+	Valid {
+		/// Version of the runtime.
+		spec: RuntimeVersion,
+	},
+	/// The runtime version could not be retrieved.
+	Invalid {
+		/// Reason why the version could not be retrieved.
+		error: String,
+	},
+}
+
+/// Event generated by `chainHead_unstable_follow`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum FollowEvent<Hash> {
+	/// The first event of the subscription, carrying the latest finalized block.
+	#[serde(rename_all = "camelCase")]
+	Initialized {
+		/// Hash of the latest finalized block.
+		finalized_block_hash: Hash,
+		/// Runtime of the finalized block, if runtime updates were requested.
+		#[serde(skip_serializing_if = "Option::is_none", default)]
+		finalized_block_runtime: Option<RuntimeEvent>,
+	},
+	/// A new non-finalized block has been imported.
+	#[serde(rename_all = "camelCase")]
+	NewBlock {
+		/// Hash of the new block.
+		block_hash: Hash,
+		/// Hash of the parent of the new block.
+		parent_block_hash: Hash,
+		/// The new runtime, if runtime updates were requested and the block changed it.
+		#[serde(skip_serializing_if = "Option::is_none", default)]
+		new_runtime: Option<RuntimeEvent>,
+	},
+	/// The best block of the chain has changed.
+	#[serde(rename_all = "camelCase")]
+	BestBlockChanged {
+		/// Hash of the new best block.
+		best_block_hash: Hash,
+	},
+	/// One or more blocks have been finalized.
+	#[serde(rename_all = "camelCase")]
+	Finalized {
+		/// Newly finalized blocks, ordered from the oldest to the newest.
+		finalized_block_hashes: Vec<Hash>,
+		/// Blocks that can no longer be finalized.
+		pruned_block_hashes: Vec<Hash>,
+	},
+	/// The subscription has been terminated by the server, e.g. because the consumer did not
+	/// unpin blocks fast enough. No further events are generated.
+	Stop,
+}
+
+/// Event generated by the `chainHead_unstable_body`, `chainHead_unstable_storage` and
+/// `chainHead_unstable_call` queries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "event")]
+pub enum ChainHeadEvent<T> {
+	/// The query has completed successfully.
+	Done {
+		/// Result of the query.
+		result: T,
+	},
+	/// The data is not available on this node.
+	Inaccessible {
+		/// Reason why the data is not available.
+		error: String,
+	},
+	/// The query has failed.
+	Error {
+		/// Reason of the failure.
+		error: String,
+	},
+	/// The follow subscription the query refers to does not exist or has been stopped.
+	Disjoint,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn follow_event_serialization() {
+		let event = FollowEvent::<u64>::NewBlock {
+			block_hash: 2,
+			parent_block_hash: 1,
+			new_runtime: None,
+		};
+		assert_eq!(
+			serde_json::to_string(&event).unwrap(),
+			r#"{"event":"newBlock","blockHash":2,"parentBlockHash":1}"#,
+		);
+
+		let event = FollowEvent::<u64>::Finalized {
+			finalized_block_hashes: vec![1, 2],
+			pruned_block_hashes: vec![],
+		};
+		assert_eq!(
+			serde_json::to_string(&event).unwrap(),
+			r#"{"event":"finalized","finalizedBlockHashes":[1,2],"prunedBlockHashes":[]}"#,
+		);
+
+		assert_eq!(
+			serde_json::to_string(&FollowEvent::<u64>::Stop).unwrap(),
+			r#"{"event":"stop"}"#,
+		);
+	}
+
+	#[test]
+	fn chain_head_event_serialization() {
+		let event = ChainHeadEvent::Done { result: 42u32 };
+		assert_eq!(serde_json::to_string(&event).unwrap(), r#"{"event":"done","result":42}"#);
+		assert_eq!(
+			serde_json::to_string(&ChainHeadEvent::<u32>::Disjoint).unwrap(),
+			r#"{"event":"disjoint"}"#,
+		);
+	}
+}
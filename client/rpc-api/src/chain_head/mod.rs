@@ -0,0 +1,160 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate `chainHead` API.
+//!
+//! Unlike the legacy `chain_*` subscriptions, every block reported by `chainHead_unstable_follow`
+//! is pinned for the lifetime of the subscription until explicitly released with
+//! `chainHead_unstable_unpin`. Queries against the body, storage or runtime of a block are only
+//! answered for pinned blocks.
+
+pub mod error;
+pub mod event;
+
+use jsonrpc_core::Result as RpcResult;
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use sp_core::{Bytes, storage::{StorageKey, StorageData}};
+use self::error::Result;
+use self::event::{ChainHeadEvent, FollowEvent};
+
+pub use self::gen_client::Client as ChainHeadClient;
+
+/// Substrate `chainHead` API
+#[rpc]
+pub trait ChainHeadApi<Hash> {
+	/// RPC metadata
+	type Metadata;
+
+	/// Track the state of the head of the chain: the finalized, non-finalized and best blocks.
+	///
+	/// If `runtime_updates` is `true`, `newBlock` events report whether the block changed the
+	/// runtime version compared to its parent.
+	#[pubsub(
+		subscription = "chainHead_unstable_followEvent",
+		subscribe,
+		name = "chainHead_unstable_follow"
+	)]
+	fn follow(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<FollowEvent<Hash>>,
+		runtime_updates: bool,
+	);
+
+	/// Stop tracking the head of the chain and release all pinned blocks.
+	#[pubsub(
+		subscription = "chainHead_unstable_followEvent",
+		unsubscribe,
+		name = "chainHead_unstable_unfollow"
+	)]
+	fn unfollow(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+
+	/// Retrieve the body of a pinned block.
+	#[pubsub(
+		subscription = "chainHead_unstable_bodyEvent",
+		subscribe,
+		name = "chainHead_unstable_body"
+	)]
+	fn body(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Vec<Bytes>>>,
+		follow_subscription: SubscriptionId,
+		hash: Hash,
+	);
+
+	/// Stop a `chainHead_unstable_body` query.
+	#[pubsub(
+		subscription = "chainHead_unstable_bodyEvent",
+		unsubscribe,
+		name = "chainHead_unstable_stopBody"
+	)]
+	fn stop_body(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+
+	/// Retrieve a storage entry at the state of a pinned block.
+	#[pubsub(
+		subscription = "chainHead_unstable_storageEvent",
+		subscribe,
+		name = "chainHead_unstable_storage"
+	)]
+	fn storage(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Option<StorageData>>>,
+		follow_subscription: SubscriptionId,
+		hash: Hash,
+		key: StorageKey,
+	);
+
+	/// Stop a `chainHead_unstable_storage` query.
+	#[pubsub(
+		subscription = "chainHead_unstable_storageEvent",
+		unsubscribe,
+		name = "chainHead_unstable_stopStorage"
+	)]
+	fn stop_storage(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+
+	/// Call a runtime function at the state of a pinned block.
+	#[pubsub(
+		subscription = "chainHead_unstable_callEvent",
+		subscribe,
+		name = "chainHead_unstable_call"
+	)]
+	fn call(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Bytes>>,
+		follow_subscription: SubscriptionId,
+		hash: Hash,
+		function: String,
+		call_parameters: Bytes,
+	);
+
+	/// Stop a `chainHead_unstable_call` query.
+	#[pubsub(
+		subscription = "chainHead_unstable_callEvent",
+		unsubscribe,
+		name = "chainHead_unstable_stopCall"
+	)]
+	fn stop_call(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
+
+	/// Retrieve the SCALE-encoded header of a pinned block.
+	#[rpc(name = "chainHead_unstable_header")]
+	fn header(&self, follow_subscription: SubscriptionId, hash: Hash) -> Result<Option<Bytes>>;
+
+	/// Release a block previously pinned by `chainHead_unstable_follow`.
+	#[rpc(name = "chainHead_unstable_unpin")]
+	fn unpin(&self, follow_subscription: SubscriptionId, hash: Hash) -> Result<()>;
+}
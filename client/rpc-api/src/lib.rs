@@ -34,6 +34,7 @@ pub use policy::DenyUnsafe;
 
 pub mod author;
 pub mod chain;
+pub mod chain_head;
 pub mod offchain;
 pub mod state;
 pub mod child_state;
@@ -107,6 +107,23 @@ pub trait StateApi<Hash> {
 	#[rpc(name = "state_getReadProof")]
 	fn read_proof(&self, keys: Vec<StorageKey>, hash: Option<Hash>) -> FutureResult<ReadProof<Hash>>;
 
+	/// Re-executes the given block on top of its parent state and returns the spans and
+	/// storage access events recorded while doing so.
+	///
+	/// `targets` is a comma separated list of span targets, optionally with a level
+	/// (e.g. "pallet,frame=debug"), and defaults to "pallet,frame,state". `storage_keys` is
+	/// a comma separated list of hex encoded storage key prefixes (without `0x`); only storage
+	/// events touching matching keys are returned. When omitted, all events are returned.
+	///
+	/// This method is unsafe and can be expensive, as it executes the full block.
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(
+		&self,
+		block: Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse>;
+
 	/// New runtime version subscription
 	#[pubsub(
 		subscription = "state_runtimeVersion",
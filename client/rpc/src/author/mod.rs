@@ -118,6 +118,25 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 		).map(Into::into).map_err(|e| Error::Client(Box::new(e)))
 	}
 
+	fn rotate_keys_typed(&self) -> Result<Vec<(Bytes, String)>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let best_block_hash = self.client.info().best_hash;
+		let at = generic::BlockId::Hash(best_block_hash);
+		let runtime_api = self.client.runtime_api();
+		let session_keys = runtime_api.generate_session_keys(&at, None)
+			.map_err(|e| Error::Client(Box::new(e)))?;
+		let keys = runtime_api.decode_session_keys(&at, session_keys)
+			.map_err(|e| Error::Client(Box::new(e)))?
+			.ok_or_else(|| Error::InvalidSessionKeys)?;
+
+		Ok(keys.into_iter()
+			.map(|(public, key_type)| {
+				(public.into(), String::from_utf8_lossy(&key_type.0).into_owned())
+			})
+			.collect())
+	}
+
 	fn has_session_keys(&self, session_keys: Bytes) -> Result<bool> {
 		self.deny_unsafe.check_if_safe()?;
 
@@ -259,6 +259,31 @@ fn should_rotate_keys() {
 	assert!(sr25519_public_keys.contains(&CryptoTypePublicPair(sr25519::CRYPTO_ID, session_keys.sr25519.to_raw_vec())));
 }
 
+#[test]
+fn should_rotate_keys_typed() {
+	let setup = TestSetup::default();
+	let p = setup.author();
+
+	let new_public_keys = p.rotate_keys_typed().expect("Rotates the keys");
+
+	let ed25519_public_keys = SyncCryptoStore::keys(&*setup.keystore, ED25519).unwrap();
+	let sr25519_public_keys = SyncCryptoStore::keys(&*setup.keystore, SR25519).unwrap();
+
+	assert_eq!(new_public_keys.len(), 3);
+	assert!(new_public_keys.iter().any(|(public, key_type)| {
+		key_type == "ed25" &&
+			ed25519_public_keys.contains(&CryptoTypePublicPair(ed25519::CRYPTO_ID, public.to_vec()))
+	}));
+	assert!(new_public_keys.iter().any(|(public, key_type)| {
+		key_type == "sr25" &&
+			sr25519_public_keys.contains(&CryptoTypePublicPair(sr25519::CRYPTO_ID, public.to_vec()))
+	}));
+
+	for (public, key_type) in new_public_keys {
+		assert!(p.has_key(public, key_type).expect("Key type is valid"));
+	}
+}
+
 #[test]
 fn test_has_session_keys() {
 	let setup = TestSetup::default();
@@ -313,4 +338,11 @@ fn test_has_key() {
 			).map_err(|e| mem::discriminant(&e)),
 		);
 	}
+
+	// Key types are exactly four bytes long.
+	assert_eq!(
+		Err(mem::discriminant(&Error::BadKeyType)),
+		p.has_key(alice_key_pair.public().to_raw_vec().into(), "ed25519".to_string())
+			.map_err(|e| mem::discriminant(&e)),
+	);
 }
@@ -0,0 +1,564 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Substrate `chainHead` API.
+
+mod subscription;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use codec::Encode;
+use futures::{future, stream, StreamExt, TryStreamExt};
+use log::warn;
+use rpc::{
+	Result as RpcResult,
+	futures::{stream as stream01, Future, Sink},
+};
+use serde::Serialize;
+
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId, manager::SubscriptionManager};
+use sc_client_api::{
+	BlockchainEvents, BlockBackend, CallExecutor, ExecutorProvider, StorageProvider,
+	backend::Backend,
+};
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, storage::{StorageKey, StorageData}};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header, NumberFor, One}};
+
+use self::error::{Error, Result};
+use self::event::{ChainHeadEvent, FollowEvent, RuntimeEvent};
+use self::subscription::{SubscriptionHandle, SubscriptionManagement};
+
+pub use sc_rpc_api::chain_head::*;
+
+/// Default maximum number of blocks a single follow subscription can keep pinned.
+///
+/// Once the limit is reached the subscription is stopped with a `stop` event.
+pub const MAX_PINNED_BLOCKS: usize = 512;
+
+/// `chainHead` API implementation for full nodes.
+pub struct ChainHead<BE, Block: BlockT, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Backend of the client, used to keep pinned blocks from being pruned.
+	backend: Arc<BE>,
+	/// Current subscriptions.
+	subscriptions: SubscriptionManager,
+	/// Blocks pinned by each follow subscription.
+	follow_subscriptions: Arc<SubscriptionManagement<Block, BE>>,
+	/// Maximum number of blocks pinned by a single follow subscription.
+	max_pinned_blocks: usize,
+}
+
+impl<BE: Backend<Block>, Block: BlockT, Client> ChainHead<BE, Block, Client> {
+	/// Create new `chainHead` API RPC handler.
+	pub fn new(
+		client: Arc<Client>,
+		backend: Arc<BE>,
+		subscriptions: SubscriptionManager,
+		max_pinned_blocks: usize,
+	) -> Self {
+		Self {
+			client,
+			backend,
+			subscriptions,
+			follow_subscriptions: Arc::new(SubscriptionManagement::new()),
+			max_pinned_blocks,
+		}
+	}
+}
+
+impl<BE, Block, Client> ChainHead<BE, Block, Client>
+	where
+		Block: BlockT + 'static,
+		BE: Backend<Block> + 'static,
+		Client: BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block>
+			+ ExecutorProvider<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+{
+	/// Resolve the handle of a follow subscription, making sure the block is pinned by it.
+	fn pinned(
+		&self,
+		follow_subscription: &SubscriptionId,
+		hash: &Block::Hash,
+	) -> std::result::Result<(), ChainHeadEvent<()>> {
+		match self.follow_subscriptions.get(follow_subscription) {
+			None => Err(ChainHeadEvent::Disjoint),
+			Some(handle) if !handle.contains(hash) => Err(ChainHeadEvent::Error {
+				error: Error::InvalidBlock(format!("{:?}", hash)).to_string(),
+			}),
+			Some(_) => Ok(()),
+		}
+	}
+
+	/// Answer a one-shot query subscription with a single event.
+	fn respond<T, F>(
+		&self,
+		subscriber: Subscriber<ChainHeadEvent<T>>,
+		follow_subscription: SubscriptionId,
+		hash: Block::Hash,
+		query: F,
+	) where
+		T: Serialize + Send + 'static,
+		F: FnOnce() -> ChainHeadEvent<T>,
+	{
+		let event = match self.pinned(&follow_subscription, &hash) {
+			Ok(()) => query(),
+			Err(ChainHeadEvent::Error { error }) => ChainHeadEvent::Error { error },
+			Err(_) => ChainHeadEvent::Disjoint,
+		};
+
+		self.subscriptions.add(subscriber, |sink| {
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream01::iter_ok::<_, ()>(vec![Ok(event)]))
+				.map(|_| ())
+		});
+	}
+}
+
+impl<BE, Block, Client> ChainHeadApi<Block::Hash> for ChainHead<BE, Block, Client>
+	where
+		Block: BlockT + 'static,
+		BE: Backend<Block> + 'static,
+		Client: BlockBackend<Block> + HeaderBackend<Block> + BlockchainEvents<Block>
+			+ ExecutorProvider<Block> + StorageProvider<Block, BE> + Send + Sync + 'static,
+{
+	type Metadata = crate::Metadata;
+
+	fn follow(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<FollowEvent<Block::Hash>>,
+		runtime_updates: bool,
+	) {
+		let handle = SubscriptionHandle::new(self.backend.clone(), self.max_pinned_blocks);
+
+		let initial = match initial_events(&self.client, runtime_updates) {
+			Ok(events) => events,
+			Err(e) => {
+				warn!("Failed to generate initial `chainHead` events: {:?}", e);
+				vec![FollowEvent::Stop]
+			},
+		};
+
+		let client = self.client.clone();
+		let imports = self.client.import_notification_stream()
+			.map(move |notification| {
+				let mut events = vec![FollowEvent::NewBlock {
+					block_hash: notification.hash,
+					parent_block_hash: *notification.header.parent_hash(),
+					new_runtime: if runtime_updates {
+						new_runtime(&client, notification.hash, *notification.header.parent_hash())
+					} else {
+						None
+					},
+				}];
+				if notification.is_new_best {
+					events.push(FollowEvent::BestBlockChanged {
+						best_block_hash: notification.hash,
+					});
+				}
+				events
+			});
+
+		let client = self.client.clone();
+		let mut last_finalized = self.client.info().finalized_number;
+		let finality = self.client.finality_notification_stream()
+			.map(move |notification| {
+				let finalized_block_hashes = finalized_route(
+					&client,
+					&notification.header,
+					last_finalized,
+				);
+				last_finalized = *notification.header.number();
+				vec![FollowEvent::Finalized {
+					finalized_block_hashes,
+					pruned_block_hashes: Vec::new(),
+				}]
+			});
+
+		let client = self.client.clone();
+		let mut announced = HashSet::new();
+		let pin_handle = handle.clone();
+		let events = stream::iter(initial)
+			.chain(stream::select(imports, finality).map(stream::iter).flatten())
+			.map(move |event| track_pruned(&client, &mut announced, event))
+			.map(move |event| pin(&pin_handle, event))
+			.scan(false, |stopped, event| {
+				if *stopped {
+					return future::ready(None);
+				}
+				*stopped = matches!(event, FollowEvent::Stop);
+				future::ready(Some(event))
+			})
+			.map(|event| Ok::<_, ()>(Ok(event)))
+			.boxed()
+			.compat();
+
+		let end_handle = handle.clone();
+		let id = self.subscriptions.add(subscriber, move |sink| {
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(events)
+				.then(move |_| {
+					end_handle.stop();
+					Ok(())
+				})
+		});
+
+		self.follow_subscriptions.insert(id, handle);
+	}
+
+	fn unfollow(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		self.follow_subscriptions.remove(&id);
+		Ok(self.subscriptions.cancel(id))
+	}
+
+	fn body(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Vec<Bytes>>>,
+		follow_subscription: SubscriptionId,
+		hash: Block::Hash,
+	) {
+		self.respond(subscriber, follow_subscription, hash, || {
+			match self.client.block_body(&BlockId::Hash(hash)) {
+				Ok(Some(body)) => ChainHeadEvent::Done {
+					result: body.into_iter().map(|extrinsic| extrinsic.encode().into()).collect(),
+				},
+				Ok(None) => ChainHeadEvent::Inaccessible {
+					error: "Block body is not available".into(),
+				},
+				Err(e) => ChainHeadEvent::Error { error: e.to_string() },
+			}
+		})
+	}
+
+	fn stop_body(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+
+	fn storage(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Option<StorageData>>>,
+		follow_subscription: SubscriptionId,
+		hash: Block::Hash,
+		key: StorageKey,
+	) {
+		self.respond(subscriber, follow_subscription, hash, || {
+			match self.client.storage(&BlockId::Hash(hash), &key) {
+				Ok(result) => ChainHeadEvent::Done { result },
+				Err(e) => ChainHeadEvent::Error { error: e.to_string() },
+			}
+		})
+	}
+
+	fn stop_storage(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+
+	fn call(
+		&self,
+		_metadata: Self::Metadata,
+		subscriber: Subscriber<ChainHeadEvent<Bytes>>,
+		follow_subscription: SubscriptionId,
+		hash: Block::Hash,
+		function: String,
+		call_parameters: Bytes,
+	) {
+		self.respond(subscriber, follow_subscription, hash, || {
+			let result = self.client.executor().call(
+				&BlockId::Hash(hash),
+				&function,
+				&*call_parameters,
+				self.client.execution_extensions().strategies().other,
+				None,
+			);
+			match result {
+				Ok(result) => ChainHeadEvent::Done { result: result.into() },
+				Err(e) => ChainHeadEvent::Error { error: e.to_string() },
+			}
+		})
+	}
+
+	fn stop_call(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+
+	fn header(
+		&self,
+		follow_subscription: SubscriptionId,
+		hash: Block::Hash,
+	) -> Result<Option<Bytes>> {
+		let handle = self.follow_subscriptions.get(&follow_subscription)
+			.ok_or_else(|| Error::InvalidSubscriptionId(format!("{:?}", follow_subscription)))?;
+		if !handle.contains(&hash) {
+			return Err(Error::InvalidBlock(format!("{:?}", hash)));
+		}
+
+		self.client
+			.header(BlockId::Hash(hash))
+			.map(|header| header.map(|header| header.encode().into()))
+			.map_err(client_err)
+	}
+
+	fn unpin(&self, follow_subscription: SubscriptionId, hash: Block::Hash) -> Result<()> {
+		let handle = self.follow_subscriptions.get(&follow_subscription)
+			.ok_or_else(|| Error::InvalidSubscriptionId(format!("{:?}", follow_subscription)))?;
+		if handle.unpin(&hash) {
+			Ok(())
+		} else {
+			Err(Error::InvalidBlock(format!("{:?}", hash)))
+		}
+	}
+}
+
+/// Pin the block announced by `event`, replacing the event with `Stop` if the subscription
+/// has too many pinned blocks or the block can't be pinned.
+fn pin<Block, BE>(
+	handle: &SubscriptionHandle<Block, BE>,
+	event: FollowEvent<Block::Hash>,
+) -> FollowEvent<Block::Hash>
+	where
+		Block: BlockT,
+		BE: Backend<Block>,
+{
+	let hash = match event {
+		FollowEvent::Initialized { ref finalized_block_hash, .. } => finalized_block_hash.clone(),
+		FollowEvent::NewBlock { ref block_hash, .. } => block_hash.clone(),
+		_ => return event,
+	};
+
+	if handle.pin(hash) {
+		event
+	} else {
+		FollowEvent::Stop
+	}
+}
+
+/// Keep track of the announced blocks that are not finalized yet, and report those that can no
+/// longer be finalized in `finalized` events.
+fn track_pruned<Block, Client>(
+	client: &Arc<Client>,
+	announced: &mut HashSet<Block::Hash>,
+	event: FollowEvent<Block::Hash>,
+) -> FollowEvent<Block::Hash>
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block>,
+{
+	match event {
+		FollowEvent::NewBlock { block_hash, .. } => {
+			announced.insert(block_hash);
+			event
+		},
+		FollowEvent::Finalized { finalized_block_hashes, .. } => {
+			let pruned_block_hashes = pruned_blocks(client, announced, &finalized_block_hashes);
+			FollowEvent::Finalized { finalized_block_hashes, pruned_block_hashes }
+		},
+		event => event,
+	}
+}
+
+/// Remove the newly finalized blocks from `announced`, along with the blocks that are not
+/// descendants of the latest finalized block. The latter are returned.
+fn pruned_blocks<Block, Client>(
+	client: &Arc<Client>,
+	announced: &mut HashSet<Block::Hash>,
+	finalized_block_hashes: &[Block::Hash],
+) -> Vec<Block::Hash>
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block>,
+{
+	for hash in finalized_block_hashes {
+		announced.remove(hash);
+	}
+
+	let finalized_hash = match finalized_block_hashes.last() {
+		Some(hash) => *hash,
+		None => return Vec::new(),
+	};
+	let finalized_number = match client.number(finalized_hash) {
+		Ok(Some(number)) => number,
+		_ => return Vec::new(),
+	};
+
+	let pruned: Vec<_> = announced.iter()
+		.filter(|hash| !is_descendant(client, **hash, finalized_hash, finalized_number))
+		.cloned()
+		.collect();
+	for hash in &pruned {
+		announced.remove(hash);
+	}
+	pruned
+}
+
+/// Whether the block `hash` descends from the block `ancestor` at height `ancestor_number`.
+///
+/// Blocks whose headers are no longer available are considered to not descend from it.
+fn is_descendant<Block, Client>(
+	client: &Arc<Client>,
+	mut hash: Block::Hash,
+	ancestor: Block::Hash,
+	ancestor_number: NumberFor<Block>,
+) -> bool
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block>,
+{
+	loop {
+		match client.header(BlockId::Hash(hash)) {
+			Ok(Some(header)) if *header.number() > ancestor_number => hash = *header.parent_hash(),
+			Ok(Some(header)) => return *header.number() == ancestor_number && hash == ancestor,
+			_ => return false,
+		}
+	}
+}
+
+/// Events sent at the start of a follow subscription: the latest finalized block followed by
+/// its descendants up to the current best block.
+fn initial_events<Block, Client>(
+	client: &Arc<Client>,
+	runtime_updates: bool,
+) -> sp_blockchain::Result<Vec<FollowEvent<Block::Hash>>>
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block> + ExecutorProvider<Block>,
+{
+	let info = client.info();
+	let mut events = vec![FollowEvent::Initialized {
+		finalized_block_hash: info.finalized_hash,
+		finalized_block_runtime: if runtime_updates {
+			Some(runtime_event(client, info.finalized_hash))
+		} else {
+			None
+		},
+	}];
+
+	let mut route = Vec::new();
+	let mut hash = info.best_hash;
+	while hash != info.finalized_hash {
+		let header = client.header(BlockId::Hash(hash))?
+			.ok_or_else(|| sp_blockchain::Error::UnknownBlock(format!("{:?}", hash)))?;
+		if *header.number() <= info.finalized_number {
+			break;
+		}
+		route.push((hash, *header.parent_hash()));
+		hash = *header.parent_hash();
+	}
+
+	for (block_hash, parent_block_hash) in route.into_iter().rev() {
+		events.push(FollowEvent::NewBlock {
+			block_hash,
+			parent_block_hash,
+			new_runtime: if runtime_updates {
+				new_runtime(client, block_hash, parent_block_hash)
+			} else {
+				None
+			},
+		});
+	}
+
+	if info.best_hash != info.finalized_hash {
+		events.push(FollowEvent::BestBlockChanged { best_block_hash: info.best_hash });
+	}
+
+	Ok(events)
+}
+
+/// Hashes of the blocks finalized by `header`, from the oldest to the newest, given that
+/// `last_finalized` was the previously reported finalized block number.
+fn finalized_route<Block, Client>(
+	client: &Arc<Client>,
+	header: &Block::Header,
+	last_finalized: NumberFor<Block>,
+) -> Vec<Block::Hash>
+	where
+		Block: BlockT,
+		Client: HeaderBackend<Block>,
+{
+	let mut route = vec![header.hash()];
+	let mut parent = (*header.parent_hash(), *header.number());
+	while parent.1 > last_finalized + One::one() {
+		match client.header(BlockId::Hash(parent.0)) {
+			Ok(Some(header)) => {
+				route.push(header.hash());
+				parent = (*header.parent_hash(), *header.number());
+			},
+			_ => break,
+		}
+	}
+	route.reverse();
+	route
+}
+
+/// Runtime of the given block.
+fn runtime_event<Block, Client>(client: &Arc<Client>, hash: Block::Hash) -> RuntimeEvent
+	where
+		Block: BlockT,
+		Client: ExecutorProvider<Block>,
+{
+	match client.executor().runtime_version(&BlockId::Hash(hash)) {
+		Ok(spec) => RuntimeEvent::Valid { spec },
+		Err(e) => RuntimeEvent::Invalid { error: e.to_string() },
+	}
+}
+
+/// Runtime of the given block, if it differs from the runtime of its parent.
+fn new_runtime<Block, Client>(
+	client: &Arc<Client>,
+	hash: Block::Hash,
+	parent_hash: Block::Hash,
+) -> Option<RuntimeEvent>
+	where
+		Block: BlockT,
+		Client: ExecutorProvider<Block>,
+{
+	let runtime = runtime_event(client, hash);
+	let parent_runtime = runtime_event(client, parent_hash);
+	if runtime != parent_runtime {
+		Some(runtime)
+	} else {
+		None
+	}
+}
+
+fn client_err(err: sp_blockchain::Error) -> Error {
+	Error::Client(Box::new(err))
+}
@@ -0,0 +1,169 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Bookkeeping of the blocks pinned by `chainHead` follow subscriptions.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use parking_lot::RwLock;
+use jsonrpc_pubsub::SubscriptionId;
+use sc_client_api::backend::Backend;
+use sp_runtime::traits::Block as BlockT;
+
+/// Pinned blocks of a single follow subscription.
+struct PinnedBlocks<Block: BlockT, BE> {
+	/// Blocks currently pinned.
+	blocks: HashSet<Block::Hash>,
+	/// Maximum number of blocks that can be pinned at the same time.
+	max_pinned_blocks: usize,
+	/// The subscription has been stopped and no longer pins anything.
+	stopped: bool,
+	/// Backend that keeps the pinned blocks from being pruned.
+	backend: Arc<BE>,
+}
+
+/// Shared handle to the state of a single follow subscription.
+pub struct SubscriptionHandle<Block: BlockT, BE> {
+	inner: Arc<RwLock<PinnedBlocks<Block, BE>>>,
+}
+
+impl<Block: BlockT, BE> Clone for SubscriptionHandle<Block, BE> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone() }
+	}
+}
+
+impl<Block: BlockT, BE: Backend<Block>> SubscriptionHandle<Block, BE> {
+	/// Create a new handle that can pin up to `max_pinned_blocks` blocks in `backend`.
+	pub fn new(backend: Arc<BE>, max_pinned_blocks: usize) -> Self {
+		Self {
+			inner: Arc::new(RwLock::new(PinnedBlocks {
+				blocks: HashSet::new(),
+				max_pinned_blocks,
+				stopped: false,
+				backend,
+			})),
+		}
+	}
+
+	/// Pin the given block.
+	///
+	/// Returns `false` if the subscription is stopped, if pinning the block would exceed
+	/// the limit of pinned blocks or if the backend can't pin the block.
+	pub fn pin(&self, hash: Block::Hash) -> bool {
+		let mut inner = self.inner.write();
+		if inner.stopped {
+			return false;
+		}
+		if inner.blocks.contains(&hash) {
+			return true;
+		}
+		if inner.blocks.len() >= inner.max_pinned_blocks {
+			return false;
+		}
+		if inner.backend.pin_block(&hash).is_err() {
+			return false;
+		}
+		inner.blocks.insert(hash);
+		true
+	}
+
+	/// Unpin the given block.
+	///
+	/// Returns `false` if the block was not pinned.
+	pub fn unpin(&self, hash: &Block::Hash) -> bool {
+		let mut inner = self.inner.write();
+		if inner.blocks.remove(hash) {
+			inner.backend.unpin_block(hash);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Check whether the given block is pinned.
+	pub fn contains(&self, hash: &Block::Hash) -> bool {
+		self.inner.read().blocks.contains(hash)
+	}
+
+	/// Number of blocks currently pinned.
+	pub fn pinned_count(&self) -> usize {
+		self.inner.read().blocks.len()
+	}
+
+	/// Stop the subscription, releasing all pinned blocks.
+	pub fn stop(&self) {
+		let mut inner = self.inner.write();
+		inner.stopped = true;
+		let blocks = std::mem::take(&mut inner.blocks);
+		for hash in blocks {
+			inner.backend.unpin_block(&hash);
+		}
+	}
+
+	/// Check whether the subscription has been stopped.
+	pub fn is_stopped(&self) -> bool {
+		self.inner.read().stopped
+	}
+}
+
+/// All active follow subscriptions, indexed by subscription ID.
+pub struct SubscriptionManagement<Block: BlockT, BE> {
+	subscriptions: RwLock<HashMap<SubscriptionId, SubscriptionHandle<Block, BE>>>,
+}
+
+impl<Block: BlockT, BE: Backend<Block>> Default for SubscriptionManagement<Block, BE> {
+	fn default() -> Self {
+		Self { subscriptions: RwLock::new(HashMap::new()) }
+	}
+}
+
+impl<Block: BlockT, BE: Backend<Block>> SubscriptionManagement<Block, BE> {
+	/// Create a new, empty, instance.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register a new follow subscription.
+	///
+	/// Subscriptions that have been stopped by the server are dropped at the same time.
+	pub fn insert(&self, id: SubscriptionId, handle: SubscriptionHandle<Block, BE>) {
+		let mut subscriptions = self.subscriptions.write();
+		subscriptions.retain(|_, handle| !handle.is_stopped());
+		subscriptions.insert(id, handle);
+	}
+
+	/// Get the handle of an active follow subscription.
+	pub fn get(&self, id: &SubscriptionId) -> Option<SubscriptionHandle<Block, BE>> {
+		self.subscriptions.read()
+			.get(id)
+			.filter(|handle| !handle.is_stopped())
+			.cloned()
+	}
+
+	/// Remove a follow subscription, releasing all of its pinned blocks.
+	pub fn remove(&self, id: &SubscriptionId) -> bool {
+		match self.subscriptions.write().remove(id) {
+			Some(handle) => {
+				handle.stop();
+				true
+			},
+			None => false,
+		}
+	}
+}
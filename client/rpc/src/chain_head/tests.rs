@@ -0,0 +1,187 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+use assert_matches::assert_matches;
+use substrate_test_runtime_client::{
+	prelude::*,
+	sp_consensus::BlockOrigin,
+	runtime::{self, H256},
+};
+use sc_block_builder::BlockBuilderProvider;
+use futures::{executor, compat::{Future01CompatExt, Stream01CompatExt}};
+use futures01::{Stream as _, sync::mpsc::Receiver};
+use sc_client_api::in_mem;
+use crate::testing::TaskExecutor;
+
+fn in_mem_backend() -> Arc<in_mem::Backend<runtime::Block>> {
+	Arc::new(in_mem::Backend::new())
+}
+
+#[test]
+fn should_stop_when_too_many_blocks_are_pinned() {
+	let handle = SubscriptionHandle::new(in_mem_backend(), 1);
+
+	let event = FollowEvent::Initialized {
+		finalized_block_hash: H256::from_low_u64_be(1),
+		finalized_block_runtime: None,
+	};
+	assert_eq!(pin(&handle, event.clone()), event);
+	assert_eq!(handle.pinned_count(), 1);
+
+	let event = FollowEvent::NewBlock {
+		block_hash: H256::from_low_u64_be(2),
+		parent_block_hash: H256::from_low_u64_be(1),
+		new_runtime: None,
+	};
+	assert_eq!(pin(&handle, event.clone()), FollowEvent::Stop);
+
+	assert!(handle.unpin(&H256::from_low_u64_be(1)));
+	assert_eq!(pin(&handle, event.clone()), event);
+}
+
+#[test]
+fn should_release_pinned_blocks_on_unfollow() {
+	let management = SubscriptionManagement::new();
+	let handle = SubscriptionHandle::new(in_mem_backend(), MAX_PINNED_BLOCKS);
+	let id = SubscriptionId::String("follow".into());
+	assert!(handle.pin(H256::from_low_u64_be(1)));
+
+	management.insert(id.clone(), handle.clone());
+	assert!(management.get(&id).is_some());
+
+	assert!(management.remove(&id));
+	assert!(management.get(&id).is_none());
+	assert!(handle.is_stopped());
+	assert_eq!(handle.pinned_count(), 0);
+	assert!(!handle.pin(H256::from_low_u64_be(2)));
+}
+
+/// Wait for the next notification sent to `transport`.
+fn next_notification(transport: Receiver<String>) -> (String, Receiver<String>) {
+	let (notification, next) = executor::block_on(transport.into_future().compat()).unwrap();
+	(notification.expect("a notification must be sent"), next)
+}
+
+#[test]
+fn should_notify_initialized_and_new_blocks() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		MAX_PINNED_BLOCKS,
+	);
+
+	api.follow(Default::default(), subscriber, false);
+
+	// assert id assigned
+	let id = match executor::block_on(id.compat()) {
+		Ok(Ok(id)) => id,
+		_ => panic!("subscription id must be assigned"),
+	};
+
+	// assert `initialized` sent.
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(r#""event":"initialized""#));
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = block.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	// assert `newBlock` sent. The block is pinned before it is announced.
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(r#""event":"newBlock""#));
+	// assert `bestBlockChanged` sent.
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(r#""event":"bestBlockChanged""#));
+
+	// the imported block is pinned and can be unpinned exactly once.
+	assert_matches!(api.unpin(id.clone(), block_hash), Ok(()));
+	assert_matches!(api.unpin(id.clone(), block_hash), Err(Error::InvalidBlock(_)));
+	assert_matches!(
+		api.unpin(SubscriptionId::String("unknown".into()), block_hash),
+		Err(Error::InvalidSubscriptionId(_))
+	);
+
+	drop(api);
+	drop(client);
+
+	// no more notifications on this channel
+	assert_eq!(executor::block_on(Stream01CompatExt::compat(transport).into_future()).0, None);
+}
+
+#[test]
+fn should_report_pruned_forks_on_finalization() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	let builder = TestClientBuilder::new();
+	let backend = builder.backend();
+	let mut client = Arc::new(builder.build());
+	let api = ChainHead::new(
+		client.clone(),
+		backend,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		MAX_PINNED_BLOCKS,
+	);
+
+	api.follow(Default::default(), subscriber, false);
+	assert_matches!(executor::block_on(id.compat()), Ok(Ok(_)));
+
+	// assert `initialized` sent.
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(r#""event":"initialized""#));
+
+	// Import the new best block and a competing fork at the same height.
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let best_hash = block.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let mut builder = client.new_block_at(&BlockId::Number(0), Default::default(), false).unwrap();
+	builder.push_transfer(runtime::Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 42,
+		nonce: 0,
+	}).unwrap();
+	let fork = builder.build().unwrap().block;
+	let fork_hash = fork.hash();
+	client.import(BlockOrigin::Own, fork).unwrap();
+
+	// assert `newBlock` and `bestBlockChanged` sent for the best block, then `newBlock` for
+	// the fork.
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(&format!(r#""blockHash":"{:?}""#, best_hash)));
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(r#""event":"bestBlockChanged""#));
+	let (notification, transport) = next_notification(transport);
+	assert!(notification.contains(&format!(r#""blockHash":"{:?}""#, fork_hash)));
+
+	// Finalizing the best block prunes the fork.
+	client.finalize_block(BlockId::Hash(best_hash), None).unwrap();
+	let (notification, _) = next_notification(transport);
+	assert!(notification.contains(&format!(
+		r#""finalizedBlockHashes":["{:?}"],"prunedBlockHashes":["{:?}"]"#,
+		best_hash,
+		fork_hash,
+	)));
+}
@@ -32,6 +32,7 @@ pub use rpc::IoHandlerExtension as RpcExtension;
 
 pub mod author;
 pub mod chain;
+pub mod chain_head;
 pub mod offchain;
 pub mod state;
 pub mod system;
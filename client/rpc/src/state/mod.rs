@@ -40,7 +40,7 @@ use self::error::{Error, FutureResult};
 
 pub use sc_rpc_api::state::*;
 pub use sc_rpc_api::child_state::*;
-use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider};
+use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider, BlockBackend};
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
@@ -137,6 +137,14 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		keys: Vec<StorageKey>,
 	) -> FutureResult<ReadProof<Block::Hash>>;
 
+	/// Trace the execution of the given block.
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse>;
+
 	/// New runtime version subscription
 	fn subscribe_runtime_version(
 		&self,
@@ -178,7 +186,7 @@ pub fn new_full<BE, Block: BlockT, Client>(
 		BE: Backend<Block> + 'static,
 		Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 			+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-			+ CallApiAt<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+			+ CallApiAt<Block> + ProvideRuntimeApi<Block> + BlockBackend<Block> + Send + Sync + 'static,
 		Client::Api: Metadata<Block>,
 {
 	let child_backend = Box::new(
@@ -318,6 +326,19 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.read_proof(block, keys)
 	}
 
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::TraceBlockResponse> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return Box::new(result(Err(err.into())))
+		}
+
+		self.backend.trace_block(block, targets, storage_keys)
+	}
+
 	fn subscribe_storage(
 		&self,
 		meta: Self::Metadata,
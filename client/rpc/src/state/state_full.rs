@@ -43,7 +43,8 @@ use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error, Result}, client_err};
 use std::marker::PhantomData;
-use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
+use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider, BlockBackend};
+use sp_rpc::tracing::{TraceBlockResponse, TraceError};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -223,7 +224,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	BE: Backend<Block> + 'static,
 	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-		+ CallApiAt<Block> + ProvideRuntimeApi<Block>
+		+ CallApiAt<Block> + ProvideRuntimeApi<Block> + BlockBackend<Block>
 		+ Send + Sync + 'static,
 	Client::Api: Metadata<Block>,
 {
@@ -409,6 +410,25 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 		))
 	}
 
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<TraceBlockResponse> {
+		let executor = sc_tracing::block::BlockExecutor::new(
+			self.client.clone(),
+			block,
+			targets,
+			storage_keys,
+		);
+		let response = match executor.trace_block() {
+			Ok(trace) => TraceBlockResponse::BlockTrace(trace),
+			Err(e) => TraceBlockResponse::TraceError(TraceError { error: e.to_string() }),
+		};
+		Box::new(result(Ok(response)))
+	}
+
 	fn subscribe_runtime_version(
 		&self,
 		_meta: crate::Metadata,
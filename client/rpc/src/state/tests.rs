@@ -152,6 +152,32 @@ fn should_call_contract() {
 	)
 }
 
+#[test]
+fn should_report_runtime_panic_message() {
+	let client = Arc::new(
+		TestClientBuilder::new()
+			.set_execution_strategy(substrate_test_runtime_client::ExecutionStrategy::AlwaysWasm)
+			.build()
+	);
+	let genesis_hash = client.genesis_hash();
+	let (client, _child) = new_full(
+		client,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::No,
+	);
+
+	let error = client.call(
+		"TestAPI_balance_of".into(),
+		Bytes(vec![1, 2, 3]),
+		Some(genesis_hash).into(),
+	).wait().unwrap_err();
+	let message = "Bad input data provided to balance_of";
+	assert!(error.to_string().contains(message), "{}", error);
+
+	let error = rpc::Error::from(error);
+	assert!(error.data.map_or(false, |d| d.to_string().contains(message)));
+}
+
 #[test]
 fn should_notify_about_storage_changes() {
 	let (subscriber, id, transport) = Subscriber::new_test("test");
@@ -455,6 +481,41 @@ fn should_split_ranges() {
 	assert_eq!(split_range(100, Some(99)), (0..99, Some(99..100)));
 }
 
+#[test]
+fn should_return_verifiable_read_proof() {
+	use sp_runtime::traits::BlakeTwo256;
+
+	const KEY: &[u8] = b":mock";
+	const VALUE: &[u8] = b"hello world";
+	const MISSING_KEY: &[u8] = b":missing";
+
+	let client = Arc::new(TestClientBuilder::new()
+		.add_extra_storage(KEY.to_vec(), VALUE.to_vec())
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let state_root = client.header(&BlockId::Hash(genesis_hash)).unwrap().unwrap().state_root;
+	let (api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::No,
+	);
+
+	let keys = vec![StorageKey(KEY.to_vec()), StorageKey(MISSING_KEY.to_vec())];
+	let read_proof = api.read_proof(keys, Some(genesis_hash)).wait().unwrap();
+	assert_eq!(read_proof.at, genesis_hash);
+
+	let proof = sp_state_machine::StorageProof::new(
+		read_proof.proof.into_iter().map(|node| node.0).collect(),
+	);
+	let checked = sp_state_machine::read_proof_check::<BlakeTwo256, _>(
+		state_root,
+		proof,
+		&[KEY, MISSING_KEY],
+	).unwrap();
+
+	assert_eq!(checked.get(KEY), Some(&Some(VALUE.to_vec())));
+	assert_eq!(checked.get(MISSING_KEY), Some(&None));
+}
 
 #[test]
 fn should_return_runtime_version() {
@@ -480,6 +541,45 @@ fn should_return_runtime_version() {
 	assert_eq!(deserialized, runtime_version);
 }
 
+#[test]
+fn should_trace_block() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(vec![1], Some(vec![1])).unwrap();
+	let block = builder.build().unwrap().block;
+	let hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).unwrap();
+
+	let (api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::Yes,
+	);
+	assert_matches!(
+		api.trace_block(hash, None, None).wait(),
+		Err(Error::UnsafeRpcCalled(_))
+	);
+
+	let (api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		DenyUnsafe::No,
+	);
+	let trace = match api.trace_block(hash, None, None).wait().unwrap() {
+		sp_rpc::tracing::TraceBlockResponse::BlockTrace(trace) => trace,
+		sp_rpc::tracing::TraceBlockResponse::TraceError(e) => panic!("Tracing failed: {}", e.error),
+	};
+	assert_eq!(trace.block_hash, sp_core::hexdisplay::HexDisplay::from(&hash.as_ref()).to_string());
+	assert_eq!(trace.tracing_targets, "pallet,frame,state");
+	assert!(trace.events.iter().any(|e| {
+		e.data.string_values.get("method").map(String::as_str) == Some("Put") &&
+			e.data.string_values.get("key").map(String::as_str) == Some("01")
+	}));
+
+	let unknown = api.trace_block(H256::repeat_byte(0x42), None, None).wait().unwrap();
+	assert_matches!(unknown, sp_rpc::tracing::TraceBlockResponse::TraceError(_));
+}
+
 #[test]
 fn should_notify_on_runtime_version_initially() {
 	let (subscriber, id, transport) = Subscriber::new_test("test");
@@ -19,6 +19,7 @@
 use crate::{
 	error::Error, MallocSizeOfWasm, RpcHandlers, NetworkStatusSinks,
 	start_rpc_servers, build_network_future, TransactionPoolAdapter, TaskManager, SpawnTaskHandle,
+	SpawnEssentialTaskHandle,
 	metrics::MetricsService,
 	client::{light, Client, ClientConfig},
 	config::{Configuration, KeystoreConfig, PrometheusConfig},
@@ -43,10 +44,11 @@ use log::info;
 use sc_network::config::{Role, OnDemand};
 use sc_network::NetworkService;
 use sc_network::block_request_handler::{self, BlockRequestHandler};
+use sc_network::state_request_handler::{self, StateRequestHandler};
 use sc_network::light_client_requests::{self, handler::LightClientRequestHandler};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
-	Block as BlockT, HashFor, Zero, BlockIdTo,
+	Block as BlockT, HashFor, Zero, BlockIdTo, SaturatedConversion,
 };
 use sp_api::{ProvideRuntimeApi, CallApiAt};
 use sc_executor::{NativeExecutor, NativeExecutionDispatch, RuntimeInfo};
@@ -318,7 +320,9 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
-	);
+	)
+		.with_prometheus_registry(config.prometheus_registry())
+		.with_cache_path(config.wasm_runtime_cache_path.clone());
 
 	let chain_spec = &config.chain_spec;
 	let fork_blocks = get_extension::<ForkBlocks<TBl>>(chain_spec.extensions())
@@ -395,7 +399,9 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
-	);
+	)
+		.with_prometheus_registry(config.prometheus_registry())
+		.with_cache_path(config.wasm_runtime_cache_path.clone());
 
 	let db_storage = {
 		let db_settings = sc_client_db::DatabaseSettings {
@@ -603,8 +609,9 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 
 	let spawn_handle = task_manager.spawn_handle();
 
-	// Inform the tx pool about imported and finalized blocks.
-	spawn_handle.spawn(
+	// Inform the tx pool about imported and finalized blocks. The pool would silently stop being
+	// maintained without this task, so it is essential.
+	task_manager.spawn_essential_handle().spawn(
 		"txpool-notifications",
 		sc_transaction_pool::notification_future(client.clone(), transaction_pool.clone()),
 	);
@@ -618,15 +625,46 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		),
 	);
 
+	// Stop the node before the database runs out of disk space.
+	#[cfg(not(target_os = "unknown"))]
+	{
+		let path = config.database.path().map(|path| path.to_path_buf());
+		if let (Some(path), true) = (path, config.db_storage_threshold > 0) {
+			task_manager.spawn_essential_handle().spawn(
+				"storage-monitor",
+				crate::storage_monitor::storage_monitor(path, config.db_storage_threshold),
+			);
+		}
+	}
+
 	// Prometheus metrics.
 	let metrics_service = if let Some(PrometheusConfig { port, registry }) =
 		config.prometheus_config.clone()
 	{
 		// Set static metrics.
 		let metrics = MetricsService::with_prometheus(telemetry.clone(), &registry, &config)?;
+
+		// Report the node's health through the `/health` and `/readiness` endpoints.
+		let health: prometheus_endpoint::HealthSource = {
+			let client = client.clone();
+			let network = network.clone();
+			let should_have_peers = !config.network.boot_nodes.is_empty();
+
+			Arc::new(move || {
+				let info = client.info();
+				prometheus_endpoint::NodeHealth {
+					peers: network.num_connected(),
+					is_syncing: network.is_major_syncing(),
+					should_have_peers,
+					best_number: info.best_number.saturated_into::<u64>(),
+					finalized_number: info.finalized_number.saturated_into::<u64>(),
+				}
+			})
+		};
+
 		spawn_handle.spawn(
 			"prometheus-endpoint",
-			prometheus_endpoint::init_prometheus(port, registry).map(drop)
+			prometheus_endpoint::init_prometheus_with_health(port, registry, Some(health)).map(drop)
 		);
 
 		metrics
@@ -649,7 +687,7 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		rpc_middleware: sc_rpc_server::RpcMiddleware
 	| gen_handler(
 		deny_unsafe, rpc_middleware, &config, task_manager.spawn_handle(),
-		client.clone(), transaction_pool.clone(), keystore.clone(),
+		client.clone(), backend.clone(), transaction_pool.clone(), keystore.clone(),
 		on_demand.clone(), remote_blockchain.clone(), &*rpc_extensions_builder,
 		backend.offchain_storage(), system_rpc_tx.clone()
 	);
@@ -733,6 +771,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 	config: &Configuration,
 	spawn_handle: SpawnTaskHandle,
 	client: Arc<TCl>,
+	backend: Arc<TBackend>,
 	transaction_pool: Arc<TExPool>,
 	keystore: SyncCryptoStorePtr,
 	on_demand: Option<Arc<OnDemand<TBl>>>,
@@ -754,7 +793,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			sp_session::SessionKeys<TBl> +
 			sp_api::Metadata<TBl>,
 {
-	use sc_rpc::{chain, state, author, system, offchain};
+	use sc_rpc::{chain, chain_head, state, author, system, offchain};
 
 	let system_info = sc_rpc::system::SystemInfo {
 		chain_name: config.chain_spec.name().into(),
@@ -767,7 +806,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 	let task_executor = sc_rpc::SubscriptionTaskExecutor::new(spawn_handle);
 	let subscriptions = SubscriptionManager::new(Arc::new(task_executor.clone()));
 
-	let (chain, state, child_state) = if let (Some(remote_blockchain), Some(on_demand)) =
+	let (chain, state, child_state, chain_head) = if let (Some(remote_blockchain), Some(on_demand)) =
 		(remote_blockchain, on_demand) {
 		// Light clients
 		let chain = sc_rpc::chain::new_light(
@@ -783,7 +822,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			on_demand,
 			deny_unsafe,
 		);
-		(chain, state, child_state)
+		(chain, state, child_state, None)
 
 	} else {
 		// Full nodes
@@ -793,7 +832,13 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			subscriptions.clone(),
 			deny_unsafe,
 		);
-		(chain, state, child_state)
+		let chain_head = chain_head::ChainHeadApi::to_delegate(chain_head::ChainHead::new(
+			client.clone(),
+			backend,
+			subscriptions.clone(),
+			chain_head::MAX_PINNED_BLOCKS,
+		));
+		(chain, state, child_state, Some(chain_head))
 	};
 
 	let author = sc_rpc::author::Author::new(
@@ -815,6 +860,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			state::StateApi::to_delegate(state),
 			state::ChildStateApi::to_delegate(child_state),
 			chain::ChainApi::to_delegate(chain),
+			chain_head,
 			maybe_offchain_rpc,
 			author::AuthorApi::to_delegate(author),
 			system::SystemApi::to_delegate(system),
@@ -834,6 +880,9 @@ pub struct BuildNetworkParams<'a, TBl: BlockT, TExPool, TImpQu, TCl> {
 	pub transaction_pool: Arc<TExPool>,
 	/// A handle for spawning tasks.
 	pub spawn_handle: SpawnTaskHandle,
+	/// A handle for spawning essential tasks, such as the network worker, which shut down the
+	/// node when they end.
+	pub spawn_essential_handle: SpawnEssentialTaskHandle,
 	/// An import queue.
 	pub import_queue: TImpQu,
 	/// An optional, shared data fetcher for light clients.
@@ -865,8 +914,8 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		TImpQu: ImportQueue<TBl> + 'static,
 {
 	let BuildNetworkParams {
-		config, client, transaction_pool, spawn_handle, import_queue, on_demand,
-		block_announce_validator_builder,
+		config, client, transaction_pool, spawn_handle, spawn_essential_handle, import_queue,
+		on_demand, block_announce_validator_builder,
 	} = params;
 
 	let transaction_pool_adapter = Arc::new(TransactionPoolAdapter {
@@ -900,15 +949,35 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		}
 	};
 
+	let state_request_protocol_config = {
+		if matches!(config.role, Role::Light) {
+			// Allow outgoing requests but deny incoming requests.
+			state_request_handler::generate_protocol_config(&protocol_id)
+		} else {
+			// Allow both outgoing and incoming requests.
+			let (handler, protocol_config) = StateRequestHandler::new(
+				&protocol_id,
+				client.clone(),
+				config.network.default_peers_set.in_peers as usize
+					+ config.network.default_peers_set.out_peers as usize,
+			);
+			spawn_handle.spawn("state_request_handler", handler.run());
+			protocol_config
+		}
+	};
+
 	let light_client_request_protocol_config = {
 		if matches!(config.role, Role::Light) {
 			// Allow outgoing requests but deny incoming requests.
 			light_client_requests::generate_protocol_config(&protocol_id)
 		} else {
 			// Allow both outgoing and incoming requests.
+			// Light peers connect through the inbound slots of the default peer set.
 			let (handler, protocol_config) = LightClientRequestHandler::new(
 				&protocol_id,
 				client.clone(),
+				config.network.default_peers_set.in_peers as usize
+					+ config.network.default_peers_set.out_peers as usize,
 			);
 			spawn_handle.spawn("light_client_request_handler", handler.run());
 			protocol_config
@@ -938,6 +1007,7 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		block_announce_validator,
 		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		block_request_protocol_config,
+		state_request_protocol_config,
 		light_client_request_protocol_config,
 	};
 
@@ -981,7 +1051,10 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 	// issue, and ideally we would like to fix the network future to take as little time as
 	// possible, but we also take the extra harm-prevention measure to execute the networking
 	// future using `spawn_blocking`.
-	spawn_handle.spawn_blocking("network-worker", async move {
+	//
+	// The node can't do anything useful without its network, so this is an essential task: if it
+	// ever ends, the whole node is shut down.
+	spawn_essential_handle.spawn_blocking("network-worker", async move {
 		if network_start_rx.await.is_err() {
 			debug_assert!(false);
 			log::warn!(
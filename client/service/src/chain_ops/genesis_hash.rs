@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use crate::client::genesis::construct_genesis_block;
+use sc_chain_spec::ChainSpec;
+use sp_core::storage::well_known_keys;
+use sp_runtime::traits::{Block as BlockT, HashFor, Header as HeaderT};
+use sp_state_machine::InMemoryBackend;
+
+/// Compute the hash of the genesis block described by the given chain `spec`.
+///
+/// This builds the genesis storage in memory, so it doesn't require a database.
+pub fn genesis_hash<B: BlockT>(spec: &dyn ChainSpec) -> Result<B::Hash, Error> {
+	let storage = spec.as_storage_builder().build_storage()
+		.map_err(sp_blockchain::Error::Storage)?;
+
+	if storage.top.keys().any(|k| well_known_keys::is_child_storage_key(&k)) {
+		return Err(sp_blockchain::Error::GenesisInvalid.into());
+	}
+
+	let backend: InMemoryBackend<HashFor<B>> = storage.into();
+	let genesis_block = construct_genesis_block::<B>(*backend.root());
+
+	Ok(genesis_block.header().hash())
+}
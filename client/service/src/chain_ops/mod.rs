@@ -21,11 +21,13 @@
 mod check_block;
 mod export_blocks;
 mod export_raw_state;
+mod genesis_hash;
 mod import_blocks;
 mod revert_chain;
 
 pub use check_block::*;
 pub use export_blocks::*;
 pub use export_raw_state::*;
+pub use genesis_hash::*;
 pub use import_blocks::*;
 pub use revert_chain::*;
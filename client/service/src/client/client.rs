@@ -56,7 +56,7 @@ use sp_state_machine::{
 use sc_executor::RuntimeVersion;
 use sp_consensus::{
 	Error as ConsensusError, BlockStatus, BlockImportParams, BlockCheckParams,
-	ImportResult, BlockOrigin, ForkChoiceStrategy,
+	ImportResult, BlockOrigin, ForkChoiceStrategy, ImportedState,
 };
 use sp_blockchain::{
 	self as blockchain,
@@ -634,6 +634,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			fork_choice,
 			intermediates,
 			import_existing,
+			imported_state,
 			..
 		} = import_block;
 
@@ -673,6 +674,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 			import_existing,
+			imported_state,
 		);
 
 		if let Ok(ImportResult::Imported(ref aux)) = result {
@@ -712,6 +714,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		aux: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 		fork_choice: ForkChoiceStrategy,
 		import_existing: bool,
+		imported_state: Option<ImportedState<Block>>,
 	) -> sp_blockchain::Result<ImportResult> where
 		Self: ProvideRuntimeApi<Block>,
 		<Self as ProvideRuntimeApi<Block>>::Api: CoreApi<Block> +
@@ -730,8 +733,9 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		let info = self.backend.blockchain().info();
 
 		// the block is lower than our last finalized block so it must revert
-		// finality, refusing import.
-		if *import_headers.post().number() <= info.finalized_number {
+		// finality, refusing import. Importing the state of an existing block does not
+		// touch finality.
+		if imported_state.is_none() && *import_headers.post().number() <= info.finalized_number {
 			return Err(sp_blockchain::Error::NotInFinalizedChain);
 		}
 
@@ -743,8 +747,20 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			BlockOrigin::Genesis | BlockOrigin::NetworkInitialSync | BlockOrigin::File => false,
 		};
 
-		let storage_changes = match storage_changes {
-			Some(storage_changes) => {
+		let storage_changes = match (imported_state, storage_changes) {
+			(Some(imported_state), _) => {
+				// Start from an empty state and replace it with the imported one.
+				self.backend.begin_state_operation(&mut operation.op, BlockId::Hash(Default::default()))?;
+
+				let state_root = operation.op.reset_storage(imported_state.state)?;
+				if state_root != *import_headers.post().state_root() {
+					warn!("Error importing state of block {}: state root mismatch", hash);
+					return Err(Error::InvalidStateRoot);
+				}
+
+				None
+			},
+			(None, Some(storage_changes)) => {
 				self.backend.begin_state_operation(&mut operation.op, BlockId::Hash(parent_hash))?;
 
 				// ensure parent block is finalized to maintain invariant that
@@ -767,6 +783,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 					offchain_sc,
 					tx, _,
 					changes_trie_tx,
+					tx_index,
 				) = storage_changes.into_inner();
 
 				if self.config.offchain_indexing_api {
@@ -775,6 +792,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				operation.op.update_db_storage(tx)?;
 				operation.op.update_storage(main_sc.clone(), child_sc.clone())?;
+				operation.op.update_transaction_index(tx_index)?;
 
 				if let Some(changes_trie_transaction) = changes_trie_tx {
 					operation.op.update_changes_trie(changes_trie_transaction)?;
@@ -782,7 +800,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				Some((main_sc, child_sc))
 			},
-			None => None,
+			(None, None) => None,
 		};
 
 		let is_new_best = finalized || match fork_choice {
@@ -1283,6 +1301,45 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 			cht::size(),
 		)
 	}
+
+	fn storage_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		let state = self.state_at(id)?;
+		let mut entries = Vec::new();
+		let mut total_size = 0;
+		let mut current_key = start_key.to_vec();
+
+		let key_after = |key: &[u8]| match child_info {
+			Some(child_info) => state.next_child_storage_key(child_info, key),
+			None => state.next_storage_key(key),
+		};
+		let value_at = |key: &[u8]| match child_info {
+			Some(child_info) => state.child_storage(child_info, key),
+			None => state.storage(key),
+		};
+
+		while let Some(next_key) = key_after(&current_key)
+			.map_err(|e| sp_blockchain::Error::Backend(e.to_string()))?
+		{
+			if total_size >= size_limit {
+				return Ok((entries, false))
+			}
+
+			let value = value_at(&next_key)
+				.map_err(|e| sp_blockchain::Error::Backend(e.to_string()))?
+				.unwrap_or_default();
+			total_size += next_key.len() + value.len();
+			entries.push((next_key.clone(), value));
+			current_key = next_key;
+		}
+
+		Ok((entries, true))
+	}
 }
 
 
@@ -1776,7 +1833,8 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		{
 			BlockStatus::InChainWithState | BlockStatus::Queued if !import_existing  => return Ok(ImportResult::AlreadyInChain),
 			BlockStatus::InChainWithState | BlockStatus::Queued => {},
-			BlockStatus::InChainPruned => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned if !import_existing => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned => {},
 			BlockStatus::Unknown => {},
 			BlockStatus::KnownBad => return Ok(ImportResult::KnownBad),
 		}
@@ -1952,6 +2010,40 @@ impl<B, E, Block, RA> BlockBackend<Block> for Client<B, E, Block, RA>
 	fn have_extrinsic(&self, hash: &Block::Hash) -> sp_blockchain::Result<bool> {
 		self.backend.blockchain().have_extrinsic(hash)
 	}
+
+	fn indexed_transaction(&self, hash: &Block::Hash) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		self.backend.blockchain().indexed_transaction(hash)
+	}
+
+	fn block_indexed_body(
+		&self,
+		id: &BlockId<Block>,
+	) -> sp_blockchain::Result<Option<Vec<Vec<u8>>>> {
+		self.backend.blockchain().block_indexed_body(*id)
+	}
+}
+
+impl<B, E, Block, RA> sp_transaction_storage_proof::IndexedBody<Block> for Client<B, E, Block, RA>
+	where
+		B: backend::Backend<Block>,
+		E: CallExecutor<Block>,
+		Block: BlockT,
+{
+	fn block_indexed_body(
+		&self,
+		number: NumberFor<Block>,
+	) -> Result<Option<Vec<Vec<u8>>>, sp_transaction_storage_proof::Error> {
+		self.backend.blockchain().block_indexed_body(BlockId::number(number))
+			.map_err(|e| sp_transaction_storage_proof::Error::from(e.to_string()))
+	}
+
+	fn number(
+		&self,
+		hash: Block::Hash,
+	) -> Result<Option<NumberFor<Block>>, sp_transaction_storage_proof::Error> {
+		self.backend.blockchain().number(hash)
+			.map_err(|e| sp_transaction_storage_proof::Error::from(e.to_string()))
+	}
 }
 
 impl<B, E, Block, RA> backend::AuxStore for Client<B, E, Block, RA>
@@ -32,6 +32,7 @@ pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
 use sc_chain_spec::ChainSpec;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
+pub use sc_informant::OutputFormat as InformantOutputFormat;
 use prometheus_endpoint::Registry;
 #[cfg(not(target_os = "unknown"))]
 use tempfile::TempDir;
@@ -67,6 +68,10 @@ pub struct Configuration {
 	pub keep_blocks: KeepBlocks,
 	/// Transaction storage scheme.
 	pub transaction_storage: TransactionStorageMode,
+	/// Minimum disk space, in MiB, that must remain available to the database.
+	///
+	/// The node is stopped when less space is available. `0` disables the check.
+	pub db_storage_threshold: u64,
 	/// Chain configuration.
 	pub chain_spec: Box<dyn ChainSpec>,
 	/// Wasm execution method.
@@ -75,6 +80,9 @@ pub struct Configuration {
 	/// over on-chain runtimes when the spec version matches. Set to `None` to
 	/// disable overrides (default).
 	pub wasm_runtime_overrides: Option<PathBuf>,
+	/// Directory where compiled WASM runtimes are cached across restarts. Only used by the
+	/// compiled execution method. Set to `None` to disable the cache.
+	pub wasm_runtime_cache_path: Option<PathBuf>,
 	/// Execution strategies.
 	pub execution_strategies: ExecutionStrategies,
 	/// RPC over HTTP binding address. `None` if disabled.
@@ -125,7 +133,7 @@ pub struct Configuration {
 	/// Base path of the configuration
 	pub base_path: Option<BasePath>,
 	/// Configuration of the output format that the informant uses.
-	pub informant_output_format: sc_informant::OutputFormat,
+	pub informant_output_format: InformantOutputFormat,
 }
 
 /// Type for tasks spawned by the executor.
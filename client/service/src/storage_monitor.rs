@@ -0,0 +1,88 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Monitoring of the disk space available to the database.
+//!
+//! Running out of disk space in the middle of a database write can leave the database in a
+//! corrupted state. The storage monitor periodically checks the space available on the volume
+//! holding the database and stops the node, with an explicit error, before that happens.
+
+use std::{path::PathBuf, time::Duration};
+use log::{error, warn};
+
+/// How often the available space is checked.
+const POLLING_PERIOD: Duration = Duration::from_secs(5);
+
+/// Number of bytes in a MiB.
+const MIB: u64 = 1024 * 1024;
+
+/// Watch the space available to the database at `path`.
+///
+/// The returned future resolves once less than `threshold` MiB are available, which is meant
+/// to bring the node down when spawned as an essential task.
+pub(crate) async fn storage_monitor(path: PathBuf, threshold: u64) {
+	loop {
+		match fs2::available_space(&path) {
+			Ok(available) if available < threshold.saturating_mul(MIB) => {
+				error!(
+					"❌ Available space {} MiB for the database at {} dropped below the threshold \
+					of {} MiB set with `--db-storage-threshold`. Shutting down to avoid corrupting \
+					the database.",
+					available / MIB,
+					path.display(),
+					threshold,
+				);
+				return;
+			},
+			Ok(_) => {},
+			Err(e) => warn!(
+				"Failed to query the available space for the database at {}: {}",
+				path.display(),
+				e,
+			),
+		}
+
+		futures_timer::Delay::new(POLLING_PERIOD).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::FutureExt;
+
+	#[test]
+	fn resolves_when_space_is_below_threshold() {
+		let dir = tempfile::tempdir().unwrap();
+		let monitor = storage_monitor(dir.path().to_path_buf(), u64::max_value());
+		assert_eq!(monitor.now_or_never(), Some(()));
+	}
+
+	#[test]
+	fn keeps_running_while_space_is_above_threshold() {
+		let dir = tempfile::tempdir().unwrap();
+		assert!(storage_monitor(dir.path().to_path_buf(), 0).now_or_never().is_none());
+	}
+
+	#[test]
+	fn keeps_running_if_space_cannot_be_queried() {
+		let dir = tempfile::tempdir().unwrap();
+		let missing = dir.path().join("missing");
+		assert!(storage_monitor(missing, u64::max_value()).now_or_never().is_none());
+	}
+}
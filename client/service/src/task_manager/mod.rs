@@ -410,7 +410,8 @@ impl Metrics {
 			tasks_ended: register(CounterVec::new(
 				Opts::new(
 					"tasks_ended_total",
-					"Total number of tasks for which Future::poll has returned Ready(()) or panicked"
+					"Total number of tasks for which Future::poll has returned Ready(()) or panicked, \
+					or that were interrupted by the shutdown of the service"
 				),
 				&["task_name", "reason"]
 			)?, registry)?,
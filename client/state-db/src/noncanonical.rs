@@ -210,8 +210,10 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	pub fn insert<E: fmt::Debug>(&mut self, hash: &BlockHash, number: u64, parent_hash: &BlockHash, changeset: ChangeSet<Key>) -> Result<CommitSet<Key>, Error<E>> {
 		let mut commit = CommitSet::default();
 		let front_block_number = self.front_block_number();
-		if self.levels.is_empty() && self.last_canonicalized.is_none() && number > 0 {
-			// assume that parent was canonicalized
+		let is_ahead_of_canonical = self.last_canonicalized.as_ref().map_or(true, |&(_, n)| number > n + 1);
+		if self.levels.is_empty() && is_ahead_of_canonical && number > 0 {
+			// assume that parent was canonicalized. This is also the case when the state of a block
+			// far ahead of the last canonicalized one was imported by state sync.
 			let last_canonicalized = (parent_hash.clone(), number - 1);
 			commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), last_canonicalized.encode()));
 			self.last_canonicalized = Some(last_canonicalized);
@@ -549,6 +551,26 @@ mod tests {
 		overlay.insert::<io::Error>(&h2, 1, &h1, ChangeSet::default()).unwrap();
 	}
 
+	#[test]
+	fn insert_ahead_of_canonicalized_with_no_pending_blocks() {
+		let h1 = H256::random();
+		let h2 = H256::random();
+		let mut db = make_db(&[1, 2]);
+		let mut overlay = NonCanonicalOverlay::<H256, H256>::new(&db).unwrap();
+		db.commit(&overlay.insert::<io::Error>(&h1, 1, &H256::default(), make_changeset(&[3], &[])).unwrap());
+		let mut commit = CommitSet::default();
+		overlay.canonicalize::<io::Error>(&h1, &mut commit).unwrap();
+		db.commit(&commit);
+		overlay.apply_pending();
+		// Block 10 with an unknown parent, e.g. after importing the state of block 10.
+		db.commit(&overlay.insert::<io::Error>(&h2, 10, &H256::random(), make_changeset(&[4], &[])).unwrap());
+		assert_eq!(overlay.last_canonicalized_block_number(), Some(9));
+		assert!(overlay.have_block(&h2));
+		let overlay2 = NonCanonicalOverlay::<H256, H256>::new(&db).unwrap();
+		assert_eq!(overlay.levels, overlay2.levels);
+		assert_eq!(overlay.last_canonicalized, overlay2.last_canonicalized);
+	}
+
 	#[test]
 	#[should_panic]
 	fn insert_behind_panics() {
@@ -0,0 +1,375 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities for tracing block execution.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, atomic::{AtomicU64, Ordering}},
+	time::Instant,
+};
+
+use parking_lot::Mutex;
+use tracing::{
+	dispatcher, Dispatch, Level, Subscriber,
+	span::{Attributes, Id, Record},
+};
+use tracing_subscriber::CurrentSpan;
+
+use sc_client_api::BlockBackend;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::hexdisplay::HexDisplay;
+use sp_rpc::tracing::{BlockTrace, Data, Event, Span};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header}};
+use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+use crate::{parse_target, SpanDatum, TraceEvent, Values};
+
+/// Targets recorded when the caller does not provide any.
+const DEFAULT_TARGETS: &str = "pallet,frame,state";
+/// Target of the span wrapping the whole block execution.
+const TRACE_TARGET: &str = "block_trace";
+/// Only events carrying this field are recorded, which currently limits them to
+/// the storage accesses emitted by the state machine.
+const REQUIRED_EVENT_FIELD: &str = "method";
+
+/// Block tracing result type.
+pub type TraceBlockResult<T> = Result<T, Error>;
+
+/// Block tracing errors.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum Error {
+	#[error("Invalid block Id: {0}")]
+	InvalidBlockId(#[from] sp_blockchain::Error),
+	#[error("Missing block component: {0}")]
+	MissingBlockComponent(String),
+	#[error("Dispatch error: {0}")]
+	Dispatch(String),
+}
+
+/// A `Subscriber` collecting every span and event emitted while executing a single block.
+struct BlockSubscriber {
+	targets: Vec<(String, Level)>,
+	next_id: AtomicU64,
+	current_span: CurrentSpan,
+	spans: Mutex<HashMap<Id, SpanDatum>>,
+	events: Mutex<Vec<TraceEvent>>,
+}
+
+impl BlockSubscriber {
+	fn new(targets: &str) -> Self {
+		let mut targets: Vec<_> = targets.split(',').map(parse_target).collect();
+		// Wasm spans and events all share the same target on the native side, they are
+		// filtered by their original target once the block is executed.
+		targets.push((WASM_TRACE_IDENTIFIER.to_owned(), Level::TRACE));
+		BlockSubscriber {
+			targets,
+			next_id: AtomicU64::new(1),
+			current_span: CurrentSpan::default(),
+			spans: Mutex::new(HashMap::new()),
+			events: Mutex::new(Vec::new()),
+		}
+	}
+}
+
+impl Subscriber for BlockSubscriber {
+	fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+		if !metadata.is_span() && metadata.fields().field(REQUIRED_EVENT_FIELD).is_none() {
+			return false
+		}
+		self.targets.iter().any(|(target, level)| {
+			metadata.level() <= level && metadata.target().starts_with(target.as_str())
+		})
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed));
+		let mut values = Values::default();
+		attrs.record(&mut values);
+		let span_datum = SpanDatum {
+			id: id.clone(),
+			parent_id: attrs.parent().cloned().or_else(|| self.current_span.id()),
+			name: attrs.metadata().name().to_owned(),
+			target: attrs.metadata().target().to_owned(),
+			level: *attrs.metadata().level(),
+			line: attrs.metadata().line().unwrap_or(0),
+			start_time: Instant::now(),
+			overall_time: Default::default(),
+			values,
+		};
+		self.spans.lock().insert(id.clone(), span_datum);
+		id
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			values.record(&mut s.values);
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+	fn event(&self, event: &tracing::Event<'_>) {
+		let mut values = Values::default();
+		event.record(&mut values);
+		let trace_event = TraceEvent {
+			name: event.metadata().name(),
+			target: event.metadata().target().to_owned(),
+			level: *event.metadata().level(),
+			values,
+			parent_id: event.parent().cloned().or_else(|| self.current_span.id()),
+		};
+		self.events.lock().push(trace_event);
+	}
+
+	fn enter(&self, span: &Id) {
+		self.current_span.enter(span.clone());
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			s.start_time = Instant::now();
+		}
+	}
+
+	fn exit(&self, span: &Id) {
+		self.current_span.exit();
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			s.overall_time += s.start_time.elapsed();
+		}
+	}
+}
+
+/// Re-executes a block on top of its parent state and records the spans and events
+/// emitted while doing so.
+///
+/// Spans are filtered by `targets` (e.g. "pallet,frame,state") and events by the hex encoded
+/// storage key prefixes in `storage_keys`. `None` falls back to the default targets and
+/// respectively keeps all events.
+pub struct BlockExecutor<Block: BlockT, Client> {
+	client: Arc<Client>,
+	block: Block::Hash,
+	targets: Option<String>,
+	storage_keys: Option<String>,
+}
+
+impl<Block, Client> BlockExecutor<Block, Client>
+	where
+		Block: BlockT + 'static,
+		Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>
+			+ Send + Sync + 'static,
+		Client::Api: Core<Block>,
+{
+	/// Create a new `BlockExecutor`.
+	pub fn new(
+		client: Arc<Client>,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> Self {
+		Self { client, block, targets, storage_keys }
+	}
+
+	/// Execute the block and return everything recorded while doing so.
+	pub fn trace_block(&self) -> TraceBlockResult<BlockTrace> {
+		let id = BlockId::Hash(self.block);
+		let mut header = self.client.header(id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Header not found".into()))?;
+		let extrinsics = self.client.block_body(&id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Extrinsics not found".into()))?;
+		let parent_hash = *header.parent_hash();
+		// Seals are added by the consensus engine after the block is built and are removed
+		// again on import, the runtime never sees them.
+		header.digest_mut().logs.retain(|d| d.as_seal().is_none());
+		let block = Block::new(header, extrinsics);
+
+		let targets = self.targets.as_deref().unwrap_or(DEFAULT_TARGETS);
+		let dispatch = Dispatch::new(BlockSubscriber::new(targets));
+
+		dispatcher::with_default(&dispatch, || {
+			let span = tracing::info_span!(target: TRACE_TARGET, "trace_block");
+			let _enter = span.enter();
+			self.client.runtime_api().execute_block(&BlockId::Hash(parent_hash), block)
+		}).map_err(|e| Error::Dispatch(
+			format!("Failed to collect traces and execute block: {:?}", e)
+		))?;
+
+		let block_subscriber = dispatch.downcast_ref::<BlockSubscriber>()
+			.ok_or_else(|| Error::Dispatch(
+				"Cannot downcast Dispatch to BlockSubscriber after tracing block".into()
+			))?;
+		let spans = block_subscriber.spans.lock()
+			.drain()
+			.filter_map(|(_, s)| patch_and_filter(s, targets))
+			.collect();
+		let storage_keys = self.storage_keys.as_deref().unwrap_or_default();
+		let events = block_subscriber.events.lock()
+			.drain(..)
+			.filter(|e| event_key_filter(e, storage_keys))
+			.map(Into::into)
+			.collect();
+
+		Ok(BlockTrace {
+			block_hash: HexDisplay::from(&self.block.as_ref()).to_string(),
+			parent_hash: HexDisplay::from(&parent_hash.as_ref()).to_string(),
+			tracing_targets: targets.to_owned(),
+			storage_keys: storage_keys.to_owned(),
+			spans,
+			events,
+		})
+	}
+}
+
+/// Whether the storage key or prefix accessed by `event` starts with one of the given prefixes.
+///
+/// Keeps all events if no prefixes are given, including those without a storage key.
+fn event_key_filter(event: &TraceEvent, storage_keys: &str) -> bool {
+	if storage_keys.is_empty() {
+		return true
+	}
+	event.values.string_values.get("key")
+		.or_else(|| event.values.string_values.get("prefix"))
+		.map(|key| check_target(storage_keys, key, &event.level))
+		.unwrap_or(false)
+}
+
+/// Restore the original name and target of wasm spans and drop those not matching `targets`.
+fn patch_and_filter(mut span: SpanDatum, targets: &str) -> Option<Span> {
+	if span.name == WASM_TRACE_IDENTIFIER {
+		span.values.bool_values.insert("wasm".to_owned(), true);
+		if let Some(n) = span.values.string_values.remove(WASM_NAME_KEY) {
+			span.name = n;
+		}
+		if let Some(t) = span.values.string_values.remove(WASM_TARGET_KEY) {
+			span.target = t;
+		}
+		if !check_target(targets, &span.target, &span.level) {
+			return None
+		}
+	}
+	Some(span.into())
+}
+
+/// Check if `target` starts with any of the comma separated `targets` at the given level.
+fn check_target(targets: &str, target: &str, level: &Level) -> bool {
+	targets.split(',')
+		.map(parse_target)
+		.any(|(t, l)| target.starts_with(t.as_str()) && level <= &l)
+}
+
+impl From<SpanDatum> for Span {
+	fn from(s: SpanDatum) -> Self {
+		Span {
+			id: s.id.into_u64(),
+			parent_id: s.parent_id.map(|id| id.into_u64()),
+			wasm: s.values.bool_values.get("wasm").copied().unwrap_or(false),
+			name: s.name,
+			target: s.target,
+			overall_time: s.overall_time.as_nanos() as u64,
+		}
+	}
+}
+
+impl From<TraceEvent> for Event {
+	fn from(e: TraceEvent) -> Self {
+		Event {
+			target: e.target,
+			data: e.values.into(),
+			parent_id: e.parent_id.map(|id| id.into_u64()),
+		}
+	}
+}
+
+impl From<Values> for Data {
+	fn from(v: Values) -> Self {
+		let mut string_values = v.string_values;
+		string_values.extend(v.bool_values.into_iter().map(|(k, v)| (k, v.to_string())));
+		string_values.extend(v.i64_values.into_iter().map(|(k, v)| (k, v.to_string())));
+		string_values.extend(v.u64_values.into_iter().map(|(k, v)| (k, v.to_string())));
+		Data { string_values }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn check_target_matches_prefix_and_level() {
+		assert!(check_target("pallet,frame", "pallet_balances", &Level::TRACE));
+		assert!(check_target("pallet=info", "pallet_balances", &Level::INFO));
+		assert!(!check_target("pallet=info", "pallet_balances", &Level::DEBUG));
+		assert!(!check_target("frame", "pallet_balances", &Level::TRACE));
+		assert!(check_target("", "pallet_balances", &Level::TRACE));
+	}
+
+	#[test]
+	fn wasm_spans_are_patched_and_filtered() {
+		let span = |target: &str| {
+			let mut values = Values::default();
+			values.string_values.insert(WASM_NAME_KEY.to_owned(), "on_initialize".to_owned());
+			values.string_values.insert(WASM_TARGET_KEY.to_owned(), target.to_owned());
+			SpanDatum {
+				id: Id::from_u64(1),
+				parent_id: None,
+				name: WASM_TRACE_IDENTIFIER.to_owned(),
+				target: WASM_TRACE_IDENTIFIER.to_owned(),
+				level: Level::TRACE,
+				line: 0,
+				start_time: Instant::now(),
+				overall_time: Default::default(),
+				values,
+			}
+		};
+
+		let patched = patch_and_filter(span("pallet_balances"), "pallet").unwrap();
+		assert_eq!(patched.name, "on_initialize");
+		assert_eq!(patched.target, "pallet_balances");
+		assert!(patched.wasm);
+
+		assert!(patch_and_filter(span("frame_executive"), "pallet").is_none());
+	}
+
+	#[test]
+	fn event_key_filter_keeps_all_events_without_storage_keys() {
+		let event = |values: &[(&str, &str)]| TraceEvent {
+			name: "event",
+			target: "state".to_owned(),
+			level: Level::TRACE,
+			values: Values {
+				string_values: values.iter()
+					.map(|(k, v)| (k.to_string(), v.to_string()))
+					.collect(),
+				.. Default::default()
+			},
+			parent_id: None,
+		};
+		let get = event(&[("method", "Get"), ("key", "26aa394e")]);
+		let clear_prefix = event(&[("method", "ClearPrefix"), ("prefix", "26aa")]);
+		let child_kill = event(&[("method", "ChildKill"), ("child_info", "3a6368")]);
+
+		for e in &[&get, &clear_prefix, &child_kill] {
+			assert!(event_key_filter(e, ""));
+		}
+
+		assert!(event_key_filter(&get, "26aa"));
+		assert!(event_key_filter(&clear_prefix, "26aa"));
+		assert!(!event_key_filter(&child_kill, "26aa"));
+		assert!(!event_key_filter(&get, "3a6368"));
+	}
+}
@@ -28,6 +28,7 @@
 
 #![warn(missing_docs)]
 
+pub mod block;
 pub mod logging;
 
 use rustc_hash::FxHashMap;
@@ -86,6 +87,9 @@ pub trait TraceHandler: Send + Sync {
 #[derive(Debug)]
 pub struct TraceEvent {
 	/// Name of the event.
+	///
+	/// Events from the runtime keep the generic name on the native side. Their original
+	/// name is found in the values under `WASM_NAME_KEY`.
 	pub name: &'static str,
 	/// Target of the event.
 	pub target: String,
@@ -284,14 +288,24 @@ impl<S: Subscriber> Layer<S> for ProfilingLayer {
 	fn on_event(&self, event: &Event<'_>, _ctx: Context<S>) {
 		let mut values = Values::default();
 		event.record(&mut values);
-		let trace_event = TraceEvent {
+		let mut trace_event = TraceEvent {
 			name: event.metadata().name(),
 			target: event.metadata().target().to_owned(),
 			level: event.metadata().level().clone(),
 			values,
 			parent_id: event.parent().cloned().or_else(|| self.current_span.id()),
 		};
-		self.trace_handler.handle_event(trace_event);
+		if trace_event.name == WASM_TRACE_IDENTIFIER {
+			trace_event.values.bool_values.insert("wasm".to_owned(), true);
+			if let Some(t) = trace_event.values.string_values.remove(WASM_TARGET_KEY) {
+				trace_event.target = t;
+			}
+			if self.check_target(&trace_event.target, &trace_event.level) {
+				self.trace_handler.handle_event(trace_event);
+			}
+		} else {
+			self.trace_handler.handle_event(trace_event);
+		}
 	}
 
 	fn on_enter(&self, span: &Id, _ctx: Context<S>) {
@@ -501,6 +515,33 @@ mod tests {
 		assert_eq!(te1.values.string_values.remove(&"message".to_owned()).unwrap(), "test_event".to_owned());
 	}
 
+	#[test]
+	fn test_wasm_event_filtered_by_target() {
+		use sp_tracing::{WasmEntryAttributes, WasmLevel, WasmMetadata};
+
+		let (sub, _spans, events) = setup_subscriber();
+		let _sub_guard = tracing::subscriber::set_default(sub);
+		let emit = |target: &str| WasmEntryAttributes {
+			metadata: WasmMetadata {
+				name: b"wasm_event".to_vec(),
+				target: target.as_bytes().to_vec(),
+				level: WasmLevel::INFO,
+				..Default::default()
+			},
+			..Default::default()
+		}.emit();
+
+		emit("other_target");
+		assert_eq!(events.lock().len(), 0);
+
+		emit("test_target");
+		let te = events.lock().remove(0);
+		assert_eq!(te.name, WASM_TRACE_IDENTIFIER);
+		assert_eq!(te.target, "test_target");
+		assert_eq!(te.values.string_values.get(WASM_NAME_KEY).unwrap(), "wasm_event");
+		assert_eq!(te.values.bool_values.get("wasm"), Some(&true));
+	}
+
 	#[test]
 	fn test_event_parent_id() {
 		let (sub, spans, events) = setup_subscriber();
@@ -119,5 +119,5 @@ pub(crate) fn set_reload_handle(handle: Handle<EnvFilter, SCSubscriber>) {
 type SCSubscriber<
 	N = tracing_fmt::format::DefaultFields,
 	E = crate::logging::EventFormat<ChronoLocal>,
-	W = fn() -> std::io::Stderr,
+	W = crate::logging::LogWriter,
 > = layer::Layered<tracing_fmt::Layer<Registry, N, E, W>, Registry>;
@@ -19,7 +19,10 @@
 use ansi_term::Colour;
 use regex::Regex;
 use std::fmt::{self, Write};
-use tracing::{Event, Level, Subscriber};
+use tracing::{
+	field::{Field, Visit},
+	Event, Level, Subscriber,
+};
 use tracing_log::NormalizeEvent;
 use tracing_subscriber::{
 	field::RecordFields,
@@ -43,6 +46,10 @@ pub struct EventFormat<T = SystemTime> {
 	pub display_thread_name: bool,
 	/// Enable ANSI terminal colors for formatted output.
 	pub enable_color: bool,
+	/// Print every event as a single line JSON object instead of plain text.
+	///
+	/// Colors are always stripped from JSON output.
+	pub json: bool,
 }
 
 impl<T> EventFormat<T>
@@ -62,6 +69,10 @@ where
 		S: Subscriber + for<'a> LookupSpan<'a>,
 		N: for<'a> FormatFields<'a> + 'static,
 	{
+		if self.json {
+			return self.format_event_json(ctx, writer, event)
+		}
+
 		let writer = &mut MaybeColorWriter::new(self.enable_color, writer);
 		let normalized_meta = event.normalized_metadata();
 		let meta = normalized_meta.as_ref().unwrap_or_else(|| event.metadata());
@@ -106,6 +117,99 @@ where
 
 		writer.write()
 	}
+
+	/// Format the event as a JSON object, with all the event fields as keys of that object.
+	fn format_event_json<'b, S, N>(
+		&self,
+		ctx: CustomFmtContext<'b, S, N>,
+		writer: &mut dyn fmt::Write,
+		event: &Event,
+	) -> fmt::Result
+	where
+		S: Subscriber + for<'a> LookupSpan<'a>,
+		N: for<'a> FormatFields<'a> + 'static,
+	{
+		let normalized_meta = event.normalized_metadata();
+		let meta = normalized_meta.as_ref().unwrap_or_else(|| event.metadata());
+
+		let mut timestamp = String::new();
+		self.timer.format_time(&mut timestamp)?;
+
+		let mut object = serde_json::Map::new();
+		object.insert("timestamp".into(), timestamp.into());
+		object.insert("level".into(), meta.level().to_string().into());
+		object.insert("target".into(), meta.target().into());
+
+		if self.display_thread_name {
+			let current_thread = std::thread::current();
+			let name = current_thread.name()
+				.map(ToOwned::to_owned)
+				.unwrap_or_else(|| format!("{:?}", current_thread.id()));
+			object.insert("thread".into(), name.into());
+		}
+
+		if let Some(span) = ctx.lookup_current() {
+			let parents = span.parents();
+			for span in std::iter::once(span).chain(parents) {
+				let exts = span.extensions();
+				if let Some(prefix) = exts.get::<super::layers::Prefix>() {
+					let node = prefix.as_str().trim_end().trim_start_matches('[').trim_end_matches(']');
+					object.insert("node".into(), node.into());
+					break;
+				}
+			}
+		}
+
+		event.record(&mut JsonVisitor(&mut object));
+
+		let line = serde_json::to_string(&object).map_err(|_| fmt::Error)?;
+		writeln!(writer, "{}", line)
+	}
+}
+
+/// Collects the fields of an event into a JSON object.
+///
+/// The `log.*` fields added by `tracing-log` are skipped, as they are already part of the
+/// normalized metadata.
+struct JsonVisitor<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl<'a> JsonVisitor<'a> {
+	fn insert(&mut self, field: &Field, value: serde_json::Value) {
+		if !field.name().starts_with("log.") {
+			self.0.insert(field.name().into(), value);
+		}
+	}
+}
+
+impl<'a> Visit for JsonVisitor<'a> {
+	fn record_i64(&mut self, field: &Field, value: i64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_u64(&mut self, field: &Field, value: u64) {
+		self.insert(field, value.into());
+	}
+
+	fn record_bool(&mut self, field: &Field, value: bool) {
+		self.insert(field, value.into());
+	}
+
+	fn record_str(&mut self, field: &Field, value: &str) {
+		self.insert(field, strip_colors(value).into());
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		self.insert(field, strip_colors(&format!("{:?}", value)).into());
+	}
+}
+
+/// Remove all the ANSI color escape sequences from the given string.
+fn strip_colors(s: &str) -> String {
+	lazy_static::lazy_static! {
+		static ref RE: Regex = Regex::new("\x1b\\[[^m]+m").expect("Error initializing color regex");
+	}
+
+	RE.replace_all(s, "").into_owned()
 }
 
 // NOTE: the following code took inspiration from tracing-subscriber
@@ -316,13 +420,8 @@ impl<'a> MaybeColorWriter<'a> {
 
 	/// Write the buffered content to the `inner_writer`.
 	fn write(&mut self) -> fmt::Result {
-		lazy_static::lazy_static! {
-			static ref RE: Regex = Regex::new("\x1b\\[[^m]+m").expect("Error initializing color regex");
-		}
-
 		if !self.enable_color {
-			let replaced = RE.replace_all(&self.buffer, "");
-			self.inner_writer.write_str(&replaced)
+			self.inner_writer.write_str(&strip_colors(&self.buffer))
 		} else {
 			self.inner_writer.write_str(&self.buffer)
 		}
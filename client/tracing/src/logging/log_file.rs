@@ -0,0 +1,196 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Output of log entries to a file that is rotated once it grows too large.
+
+use parking_lot::Mutex;
+use std::{
+	fs::{self, File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Number of rotated log files that are kept next to the current one.
+const MAX_ROTATED_FILES: usize = 5;
+
+/// A log file that is rotated once it exceeds a maximum size.
+///
+/// On rotation `<path>` is renamed to `<path>.1`, `<path>.1` to `<path>.2` and so on. The oldest
+/// file is removed once there are more than [`MAX_ROTATED_FILES`] rotated files.
+///
+/// A single entry that is larger than the maximum size is still written as a whole, into a file
+/// of its own.
+pub(crate) struct RotatingFile {
+	path: PathBuf,
+	max_size: u64,
+	file: File,
+	size: u64,
+}
+
+impl RotatingFile {
+	fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+		let file = OpenOptions::new().create(true).append(true).open(&path)?;
+		let size = file.metadata()?.len();
+
+		Ok(Self { path, max_size, file, size })
+	}
+
+	fn rotated_path(&self, index: usize) -> PathBuf {
+		let mut path = self.path.clone().into_os_string();
+		path.push(format!(".{}", index));
+		path.into()
+	}
+
+	fn rotate(&mut self) -> io::Result<()> {
+		self.file.flush()?;
+
+		for index in (1..MAX_ROTATED_FILES).rev() {
+			let from = self.rotated_path(index);
+			if Path::exists(&from) {
+				fs::rename(from, self.rotated_path(index + 1))?;
+			}
+		}
+		fs::rename(&self.path, self.rotated_path(1))?;
+
+		self.file = File::create(&self.path)?;
+		self.size = 0;
+
+		Ok(())
+	}
+}
+
+impl Write for RotatingFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+			self.rotate()?;
+		}
+
+		self.file.write_all(buf)?;
+		self.size += buf.len() as u64;
+
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+/// Where log entries are written to.
+#[derive(Clone)]
+pub(crate) enum LogWriter {
+	/// Write to the standard error output.
+	Stderr,
+	/// Write to a log file that is rotated once it exceeds its maximum size.
+	File(Arc<Mutex<RotatingFile>>),
+}
+
+impl LogWriter {
+	/// Write to the log file at `path`, rotating it once it exceeds `max_size` bytes.
+	pub(crate) fn file(path: PathBuf, max_size: u64) -> io::Result<Self> {
+		Ok(LogWriter::File(Arc::new(Mutex::new(RotatingFile::open(path, max_size)?))))
+	}
+}
+
+impl MakeWriter for LogWriter {
+	type Writer = LogWriter;
+
+	fn make_writer(&self) -> Self::Writer {
+		self.clone()
+	}
+}
+
+impl Write for LogWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		match self {
+			LogWriter::Stderr => io::stderr().write(buf),
+			LogWriter::File(file) => file.lock().write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match self {
+			LogWriter::Stderr => io::stderr().flush(),
+			LogWriter::File(file) => file.lock().flush(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn log_file_is_rotated() {
+		let dir = std::env::temp_dir().join(format!("sc-tracing-log-file-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("node.log");
+
+		let mut writer = LogWriter::file(path.clone(), 10).unwrap();
+		writer.write_all(b"first\n").unwrap();
+		writer.write_all(b"second\n").unwrap();
+		for index in 0..MAX_ROTATED_FILES + 1 {
+			writer.write_all(format!("entry {}\n", index).as_bytes()).unwrap();
+		}
+
+		assert_eq!(fs::read_to_string(&path).unwrap(), format!("entry {}\n", MAX_ROTATED_FILES));
+		assert_eq!(
+			fs::read_to_string(dir.join("node.log.1")).unwrap(),
+			format!("entry {}\n", MAX_ROTATED_FILES - 1),
+		);
+		assert_eq!(
+			fs::read_to_string(dir.join(format!("node.log.{}", MAX_ROTATED_FILES))).unwrap(),
+			"entry 0\n",
+		);
+		assert!(!dir.join(format!("node.log.{}", MAX_ROTATED_FILES + 1)).exists());
+
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn log_files_do_not_exceed_max_size() {
+		let dir = std::env::temp_dir()
+			.join(format!("sc-tracing-log-file-size-{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let path = dir.join("node.log");
+		let max_size = 64;
+
+		let mut writer = LogWriter::file(path.clone(), max_size).unwrap();
+		for index in 0..20 {
+			writer.write_all(format!("log entry number {}\n", index).as_bytes()).unwrap();
+		}
+
+		let rotated = dir.join("node.log.1");
+		assert!(rotated.exists());
+		for file in &[&path, &rotated] {
+			let size = fs::metadata(file).unwrap().len();
+			assert!(size > 0 && size <= max_size, "{:?} has {} bytes", file, size);
+		}
+		// The rotated file ends with the entry that was written right before the current file.
+		let current = fs::read_to_string(&path).unwrap();
+		let first_current = current.lines().next().unwrap().to_owned();
+		let last_rotated = fs::read_to_string(&rotated).unwrap().lines().last().unwrap().to_owned();
+		let number = |line: &str| line.rsplit(' ').next().unwrap().parse::<u32>().unwrap();
+		assert_eq!(number(&last_rotated) + 1, number(&first_current));
+		assert!(current.ends_with("log entry number 19\n"));
+
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
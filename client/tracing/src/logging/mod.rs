@@ -25,11 +25,12 @@
 mod directives;
 mod event_format;
 mod layers;
+mod log_file;
 
 pub use directives::*;
 pub use sc_tracing_proc_macro::*;
 
-use std::io;
+use std::{io, path::PathBuf};
 use tracing::Subscriber;
 use tracing_subscriber::{
 	filter::LevelFilter,
@@ -45,6 +46,7 @@ use tracing_subscriber::{
 
 pub use event_format::*;
 pub use layers::*;
+use log_file::LogWriter;
 
 /// Logging Result typedef.
 pub type Result<T> = std::result::Result<T, Error>;
@@ -61,6 +63,21 @@ pub enum Error {
 	SetLoggerError(#[from] tracing_log::log_tracer::SetLoggerError),
 }
 
+/// The format used to print log entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+	/// Human readable lines, colored when printing to a terminal.
+	Text,
+	/// One JSON object per line, meant to be consumed by log aggregators.
+	Json,
+}
+
+impl Default for LogFormat {
+	fn default() -> Self {
+		Self::Text
+	}
+}
+
 macro_rules! enable_log_reloading {
 	($builder:expr) => {{
 		let builder = $builder.with_filter_reloading();
@@ -75,12 +92,14 @@ fn prepare_subscriber<N, E, F, W>(
 	directives: &str,
 	profiling_targets: Option<&str>,
 	force_colors: Option<bool>,
+	log_format: LogFormat,
+	writer: LogWriter,
 	builder_hook: impl Fn(
 		SubscriberBuilder<
 			format::DefaultFields,
 			EventFormat<ChronoLocal>,
 			EnvFilter,
-			fn() -> std::io::Stderr,
+			LogWriter,
 		>,
 	) -> SubscriberBuilder<N, E, F, W>,
 ) -> Result<impl Subscriber + for<'a> LookupSpan<'a>>
@@ -131,6 +150,12 @@ where
 		env_filter = env_filter.add_directive(
 			parse_default_directive("sc_tracing=trace").expect("provided directive is valid"),
 		);
+		// Spans and events coming from the runtime all share the `wasm_tracing` target on the
+		// native side. Let them through here, the `ProfilingLayer` filters them by their
+		// original target.
+		env_filter = env_filter.add_directive(
+			parse_default_directive("wasm_tracing=trace").expect("provided directive is valid"),
+		);
 	}
 
 	let max_level_hint = Layer::<FmtSubscriber>::max_level_hint(&env_filter);
@@ -154,8 +179,13 @@ where
 		_ => false,
 	};
 
-	let enable_color = force_colors.unwrap_or_else(|| atty::is(atty::Stream::Stderr));
-	let timer = ChronoLocal::with_format(if simple {
+	let json = log_format == LogFormat::Json;
+	let to_stderr = matches!(writer, LogWriter::Stderr);
+	let enable_color = !json &&
+		force_colors.unwrap_or_else(|| to_stderr && atty::is(atty::Stream::Stderr));
+	let timer = ChronoLocal::with_format(if json {
+		"%Y-%m-%dT%H:%M:%S%.3f%:z".to_string()
+	} else if simple {
 		"%Y-%m-%d %H:%M:%S".to_string()
 	} else {
 		"%Y-%m-%d %H:%M:%S%.3f".to_string()
@@ -167,11 +197,12 @@ where
 		display_level: !simple,
 		display_thread_name: !simple,
 		enable_color,
+		json,
 	};
 	let builder = FmtSubscriber::builder().with_env_filter(env_filter);
 
 	#[cfg(not(target_os = "unknown"))]
-	let builder = builder.with_writer(std::io::stderr as _);
+	let builder = builder.with_writer(writer);
 
 	#[cfg(target_os = "unknown")]
 	let builder = builder.with_writer(std::io::sink);
@@ -196,6 +227,8 @@ pub struct LoggerBuilder {
 	profiling: Option<(crate::TracingReceiver, String)>,
 	log_reloading: bool,
 	force_colors: Option<bool>,
+	log_format: LogFormat,
+	log_file: Option<(PathBuf, u64)>,
 }
 
 impl LoggerBuilder {
@@ -206,6 +239,8 @@ impl LoggerBuilder {
 			profiling: None,
 			log_reloading: true,
 			force_colors: None,
+			log_format: LogFormat::Text,
+			log_file: None,
 		}
 	}
 
@@ -231,16 +266,40 @@ impl LoggerBuilder {
 		self
 	}
 
+	/// Set the format used to print log entries.
+	///
+	/// [`LogFormat::Json`] always disables colors.
+	pub fn with_log_format(&mut self, log_format: LogFormat) -> &mut Self {
+		self.log_format = log_format;
+		self
+	}
+
+	/// Write log entries to the file at `path` instead of the standard error output.
+	///
+	/// The file is rotated once it grows larger than `max_size` bytes. Colors are disabled
+	/// unless explicitly enabled with [`LoggerBuilder::with_colors`].
+	pub fn with_log_file(&mut self, path: PathBuf, max_size: u64) -> &mut Self {
+		self.log_file = Some((path, max_size));
+		self
+	}
+
 	/// Initialize the global logger
 	///
 	/// This sets various global logging and tracing instances and thus may only be called once.
 	pub fn init(self) -> Result<()> {
+		let writer = match self.log_file {
+			Some((path, max_size)) => LogWriter::file(path, max_size)?,
+			None => LogWriter::Stderr,
+		};
+
 		if let Some((tracing_receiver, profiling_targets)) = self.profiling {
 			if self.log_reloading {
 				let subscriber = prepare_subscriber(
 					&self.directives,
 					Some(&profiling_targets),
 					self.force_colors,
+					self.log_format,
+					writer,
 					|builder| enable_log_reloading!(builder),
 				)?;
 				let profiling = crate::ProfilingLayer::new(tracing_receiver, &profiling_targets);
@@ -253,6 +312,8 @@ impl LoggerBuilder {
 					&self.directives,
 					Some(&profiling_targets),
 					self.force_colors,
+					self.log_format,
+					writer,
 					|builder| builder,
 				)?;
 				let profiling = crate::ProfilingLayer::new(tracing_receiver, &profiling_targets);
@@ -267,6 +328,8 @@ impl LoggerBuilder {
 					&self.directives,
 					None,
 					self.force_colors,
+					self.log_format,
+					writer,
 					|builder| enable_log_reloading!(builder),
 				)?;
 
@@ -278,6 +341,8 @@ impl LoggerBuilder {
 					&self.directives,
 					None,
 					self.force_colors,
+					self.log_format,
+					writer,
 					|builder| builder,
 				)?;
 
@@ -455,6 +520,42 @@ mod tests {
 		);
 	}
 
+	/// This is not an actual test, it is used by the `json_log_lines` test.
+	/// The given test will call the test executable and only execute this one test that
+	/// only prints a colored log line, prefixed by the node name, using the JSON format.
+	#[test]
+	fn json_log_lines_entrypoint() {
+		if env::var("ENABLE_LOGGING").is_ok() {
+			let mut builder = LoggerBuilder::new("");
+			builder.with_log_format(LogFormat::Json);
+			builder.init().unwrap();
+			json_log_lines_process();
+		}
+	}
+
+	#[crate::logging::prefix_logs_with(EXPECTED_NODE_NAME)]
+	fn json_log_lines_process() {
+		log::info!("{}", ansi_term::Colour::Yellow.paint(EXPECTED_LOG_MESSAGE));
+	}
+
+	#[test]
+	fn json_log_lines() {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env("ENABLE_LOGGING", "1")
+			.args(&["--nocapture", "json_log_lines_entrypoint"])
+			.output()
+			.unwrap();
+
+		let output = String::from_utf8(output.stderr).unwrap();
+		let line: serde_json::Value = serde_json::from_str(output.trim()).unwrap();
+		assert_eq!(line["message"], EXPECTED_LOG_MESSAGE);
+		assert_eq!(line["node"], EXPECTED_NODE_NAME);
+		assert_eq!(line["level"], "INFO");
+		assert_eq!(line["target"], "sc_tracing::logging::tests");
+		assert!(line.get("log.target").is_none());
+	}
+
 	#[test]
 	fn log_max_level_is_set_properly() {
 		fn run_test(rust_log: Option<String>, tracing_targets: Option<String>) -> String {
@@ -407,20 +407,25 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
+	/// We use a simplified approach: the transaction with the lowest priority is removed first.
+	/// Among transactions of the same priority the one that entered the queue first is removed.
+	/// For the ready queue that is the earliest inserted one, for the future queue the earliest
+	/// imported one.
 	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = vec![];
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
-			// find the worst transaction
+			// find the worst transaction: the one with the lowest priority,
+			// and the oldest one in case of a tie.
 			let minimal = self.ready
 				.fold(|minimal, current| {
 					let transaction = &current.transaction;
 					match minimal {
 						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
-							Some(transaction.clone())
-						},
+						Some(ref tx) if is_worse(
+							(transaction.transaction.priority, transaction.insertion_id),
+							(tx.transaction.priority, tx.insertion_id),
+						) => Some(transaction.clone()),
 						other => other,
 					}
 				});
@@ -433,14 +438,16 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		}
 
 		while future.is_exceeded(self.future.len(), self.future.bytes()) {
-			// find the worst transaction
+			// find the worst transaction: the one with the lowest priority,
+			// and the oldest one in case of a tie.
 			let minimal = self.future
 				.fold(|minimal, current| {
 					match minimal {
 						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
-							Some(current.clone())
-						},
+						Some(ref tx) if is_worse(
+							(current.transaction.priority, current.imported_at),
+							(tx.transaction.priority, tx.imported_at),
+						) => Some(current.clone()),
 						other => other,
 					}
 				});
@@ -527,6 +534,15 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	}
 }
 
+/// Returns `true` if a transaction with the given `(priority, age)` should be evicted
+/// before the `other` one.
+///
+/// Transactions with lower priority are evicted first, older ones are evicted first among
+/// the transactions of the same priority.
+fn is_worse<T: Ord>(candidate: (Priority, T), other: (Priority, T)) -> bool {
+	candidate < other
+}
+
 /// Queue limits
 #[derive(Debug, Clone)]
 pub struct Limit {
@@ -1118,6 +1134,58 @@ source: TransactionSource::External, requires: [03,02], provides: [04], data: [4
 		}.is_propagable(), false);
 	}
 
+	#[test]
+	fn should_enforce_limits_by_priority() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 2, total_bytes: 1024 };
+		for (hash, priority) in vec![(1u64, 5u64), (2, 1), (3, 5)] {
+			pool.import(Transaction {
+				data: vec![hash as u8],
+				bytes: 1,
+				hash,
+				priority,
+				valid_till: 64u64,
+				requires: vec![],
+				provides: vec![vec![hash as u8]],
+				propagate: true,
+				source: Source::External,
+			}).unwrap();
+		}
+		for (hash, priority) in vec![(4u64, 3u64), (5, 2), (6, 4)] {
+			pool.import(Transaction {
+				data: vec![hash as u8],
+				bytes: 1,
+				hash,
+				priority,
+				valid_till: 64u64,
+				requires: vec![vec![100 + hash as u8]],
+				provides: vec![],
+				propagate: true,
+				source: Source::External,
+			}).unwrap();
+		}
+		assert_eq!(pool.ready.len(), 3);
+		assert_eq!(pool.future.len(), 3);
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		let mut removed = removed.into_iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![2, 5]);
+		assert_eq!(pool.ready.len(), 2);
+		assert_eq!(pool.future.len(), 2);
+
+		// and when the priorities are equal the oldest ready one is evicted
+		let limit = Limit { count: 1, total_bytes: 1024 };
+		let removed = pool.enforce_limits(&limit, &limit);
+		let mut removed = removed.into_iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![1, 4]);
+	}
+
 	#[test]
 	fn should_reject_future_transactions() {
 		// given
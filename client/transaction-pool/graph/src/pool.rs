@@ -463,7 +463,9 @@ mod tests {
 	use sp_transaction_pool::TransactionStatus;
 	use sp_runtime::{
 		traits::Hash,
-		transaction_validity::{ValidTransaction, InvalidTransaction, TransactionSource},
+		transaction_validity::{
+			ValidTransaction, InvalidTransaction, UnknownTransaction, TransactionSource,
+		},
 	};
 	use codec::Encode;
 	use substrate_test_runtime::{Block, Extrinsic, Transfer, H256, AccountId, Hashing};
@@ -635,6 +637,48 @@ mod tests {
 		assert_matches!(res.unwrap_err(), error::Error::TemporarilyBanned);
 	}
 
+	#[test]
+	fn should_ban_transactions_repeatedly_invalid_on_revalidation() {
+		// given
+		let pool = pool();
+		let uxt = uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		});
+		let revalidate = |result: fn(H256) -> ValidatedTransactionFor<TestApi>| {
+			let hash = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt.clone())).unwrap();
+			pool.resubmit(vec![(hash, result(hash))].into_iter().collect());
+			hash
+		};
+
+		// when
+		let mut hash = Default::default();
+		for _ in 0..crate::rotator::MAX_FAILURES {
+			hash = revalidate(|hash| ValidatedTransaction::Unknown(
+				hash,
+				error::Error::UnknownTransaction(UnknownTransaction::CannotLookup).into(),
+			));
+		}
+
+		// then
+		assert!(!pool.validated_pool.rotator().is_banned(&hash));
+
+		// when
+		for _ in 0..crate::rotator::MAX_FAILURES {
+			revalidate(|hash| ValidatedTransaction::Invalid(
+				hash,
+				error::Error::InvalidTransaction(InvalidTransaction::Stale).into(),
+			));
+		}
+
+		// then
+		assert!(pool.validated_pool.rotator().is_banned(&hash));
+		let res = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt));
+		assert_matches!(res.unwrap_err(), error::Error::TemporarilyBanned);
+	}
+
 	#[test]
 	fn should_reject_unactionable_transactions() {
 		// given
@@ -20,6 +20,7 @@
 //!
 //! Keeps only recent extrinsic and discard the ones kept for a significant amount of time.
 //! Discarded extrinsics are banned so that they don't get re-imported again.
+//! Extrinsics that repeatedly turn out invalid on revalidation are temporarily banned as well.
 
 use std::{
 	collections::HashMap,
@@ -35,6 +36,9 @@ use crate::base_pool::Transaction;
 /// Expected size of the banned extrinsics cache.
 const EXPECTED_SIZE: usize = 2048;
 
+/// Number of validation failures after which an extrinsic gets temporarily banned.
+pub(crate) const MAX_FAILURES: usize = 3;
+
 /// Pool rotator is responsible to only keep fresh extrinsics in the pool.
 ///
 /// Extrinsics that occupy the pool for too long are culled and temporarily banned from entering
@@ -44,6 +48,9 @@ pub struct PoolRotator<Hash> {
 	ban_time: Duration,
 	/// Currently banned extrinsics.
 	banned_until: RwLock<HashMap<Hash, Instant>>,
+	/// Extrinsics that turned out to be invalid, with the number of failures and the time
+	/// of the most recent one.
+	failures: RwLock<HashMap<Hash, (usize, Instant)>>,
 }
 
 impl<Hash: hash::Hash + Eq> Default for PoolRotator<Hash> {
@@ -51,6 +58,7 @@ impl<Hash: hash::Hash + Eq> Default for PoolRotator<Hash> {
 		PoolRotator {
 			ban_time: Duration::from_secs(60 * 30),
 			banned_until: Default::default(),
+			failures: Default::default(),
 		}
 	}
 }
@@ -79,6 +87,42 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 	}
 
 
+	/// Notes that the given extrinsic turned out to be invalid.
+	///
+	/// Returns `true` if the extrinsic failed too many times and got banned.
+	pub fn note_failure(&self, now: &Instant, hash: Hash) -> bool {
+		let count = {
+			let mut failures = self.failures.write();
+			let count = {
+				let entry = failures.entry(hash.clone()).or_insert((0, *now));
+				entry.0 += 1;
+				entry.1 = *now;
+				entry.0
+			};
+
+			if count >= MAX_FAILURES {
+				failures.remove(&hash);
+			}
+
+			if failures.len() > 2 * EXPECTED_SIZE {
+				while failures.len() > EXPECTED_SIZE {
+					if let Some(key) = failures.keys().next().cloned() {
+						failures.remove(&key);
+					}
+				}
+			}
+
+			count
+		};
+
+		if count >= MAX_FAILURES {
+			self.ban(now, iter::once(hash));
+			true
+		} else {
+			false
+		}
+	}
+
 	/// Bans extrinsic if it's stale.
 	///
 	/// Returns `true` if extrinsic is stale and got banned.
@@ -96,6 +140,9 @@ impl<Hash: hash::Hash + Eq + Clone> PoolRotator<Hash> {
 		let mut banned = self.banned_until.write();
 
 		banned.retain(|_, &mut v| v >= *now);
+
+		let ban_time = self.ban_time;
+		self.failures.write().retain(|_, &mut (_, last)| last + ban_time >= *now);
 	}
 }
 
@@ -178,6 +225,43 @@ mod tests {
 		assert!(!rotator.is_banned(&hash));
 	}
 
+	#[test]
+	fn should_ban_repeatedly_failing_extrinsic() {
+		// given
+		let (hash, _) = tx();
+		let rotator = rotator();
+		let now = Instant::now();
+
+		// when
+		for _ in 1..MAX_FAILURES {
+			assert!(!rotator.note_failure(&now, hash));
+		}
+		assert!(!rotator.is_banned(&hash));
+
+		// then
+		assert!(rotator.note_failure(&now, hash));
+		assert!(rotator.is_banned(&hash));
+	}
+
+	#[test]
+	fn should_forget_old_failures() {
+		// given
+		let (hash, _) = tx();
+		let rotator = rotator();
+		let now = Instant::now();
+		for _ in 1..MAX_FAILURES {
+			assert!(!rotator.note_failure(&now, hash));
+		}
+
+		// when
+		let future = now + rotator.ban_time + rotator.ban_time;
+		rotator.clear_timeouts(&future);
+
+		// then
+		assert!(!rotator.note_failure(&future, hash));
+		assert!(!rotator.is_banned(&hash));
+	}
+
 	#[test]
 	fn should_garbage_collect() {
 		// given
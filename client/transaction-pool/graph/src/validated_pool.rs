@@ -390,7 +390,14 @@ impl<B: ChainApi> ValidatedPool<B> {
 								final_statuses.insert(hash, Status::Failed);
 							},
 						},
-						ValidatedTransaction::Invalid(_, _) | ValidatedTransaction::Unknown(_, _) => {
+						ValidatedTransaction::Invalid(_, _) => {
+							// Transactions that keep turning out invalid are banned for a while.
+							// `Unknown` results are not counted, the transaction may just not be
+							// validatable yet.
+							self.rotator.note_failure(&Instant::now(), hash.clone());
+							final_statuses.insert(hash, Status::Failed);
+						},
+						ValidatedTransaction::Unknown(_, _) => {
 							final_statuses.insert(hash, Status::Failed);
 						},
 					}
@@ -79,6 +79,8 @@ impl pallet_balances::Config for Test {
 	type AccountStore = System;
 	type WeightInfo = ();
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 }
 
 parameter_types! {
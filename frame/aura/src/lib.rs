@@ -29,6 +29,12 @@
 //! ### Public Functions
 //!
 //! - `slot_duration` - Determine the Aura slot-duration based on the Timestamp module configuration.
+//! - `current_slot` - The slot of the current block, as recorded in `on_initialize`.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `report_equivocation` - Report an authority for authoring more than one block in a slot of
+//! the current session. Valid reports are passed on to `Config::HandleEquivocation`.
 //!
 //! ## Related Modules
 //!
@@ -41,12 +47,14 @@ use sp_std::prelude::*;
 use codec::{Encode, Decode};
 use frame_support::{
 	Parameter, traits::{Get, FindAuthor, OneSessionHandler, OnTimestampSet}, ConsensusEngineId,
+	weights::Weight,
 };
 use sp_runtime::{
 	RuntimeAppPublic,
 	traits::{SaturatedConversion, Saturating, Zero, Member, IsMember}, generic::DigestItem,
+	DispatchError, DispatchResult,
 };
-use sp_consensus_aura::{AURA_ENGINE_ID, ConsensusLog, AuthorityIndex, Slot};
+use sp_consensus_aura::{AURA_ENGINE_ID, ConsensusLog, AuthorityIndex, Slot, EquivocationProof};
 
 mod mock;
 mod tests;
@@ -64,6 +72,11 @@ pub mod pallet {
 	pub trait Config: pallet_timestamp::Config + frame_system::Config {
 		/// The identifier type for an authority.
 		type AuthorityId: Member + Parameter + RuntimeAppPublic + Default + MaybeSerializeDeserialize;
+
+		/// The equivocation handling subsystem, called with every valid equivocation report.
+		///
+		/// Use `()` to reject all equivocation reports.
+		type HandleEquivocation: HandleEquivocation<Self::AccountId, Self::AuthorityId>;
 	}
 
 	#[pallet::pallet]
@@ -87,8 +100,51 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The equivocation proof is invalid.
+		InvalidEquivocationProof,
+		/// The offender of the equivocation proof is not a current authority.
+		UnknownOffender,
+		/// The slot of the equivocation proof is not part of the current session.
+		SlotOutsideSession,
+	}
+
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Report authority equivocation, i.e. authoring more than one block in the same slot.
+		///
+		/// This method will verify the equivocation proof and pass it on to
+		/// `Config::HandleEquivocation`. Only proofs for slots of the current session are
+		/// accepted. The reporter does not pay fees if the handler accepts the report.
+		#[pallet::weight(
+			T::DbWeight::get().reads(3).saturating_add(REPORT_EQUIVOCATION_BASE_WEIGHT)
+		)]
+		pub fn report_equivocation(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProof<T::Header, T::AuthorityId>>,
+		) -> DispatchResultWithPostInfo {
+			let reporter = ensure_signed(origin)?;
+
+			let offender = equivocation_proof.offender.clone();
+			let slot = equivocation_proof.slot;
+
+			ensure!(Self::is_member(&offender), Error::<T>::UnknownOffender);
+			ensure!(
+				slot >= SessionStartSlot::<T>::get() && slot <= CurrentSlot::<T>::get(),
+				Error::<T>::SlotOutsideSession,
+			);
+			ensure!(
+				sp_consensus_aura::check_equivocation_proof(*equivocation_proof),
+				Error::<T>::InvalidEquivocationProof,
+			);
+
+			T::HandleEquivocation::handle_equivocation(reporter, offender, slot)?;
+
+			// Waive the fee since the report is new and beneficial
+			Ok(Pays::No.into())
+		}
+	}
 
 	/// The current authority set.
 	#[pallet::storage]
@@ -102,6 +158,12 @@ pub mod pallet {
 	#[pallet::getter(fn current_slot)]
 	pub(super) type CurrentSlot<T: Config> = StorageValue<_, Slot, ValueQuery>;
 
+	/// The slot that was current when the current session started.
+	///
+	/// This will be set when a new session starts.
+	#[pallet::storage]
+	pub(super) type SessionStartSlot<T: Config> = StorageValue<_, Slot, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub authorities: Vec<T::AuthorityId>,
@@ -122,6 +184,32 @@ pub mod pallet {
 	}
 }
 
+/// Base weight of `report_equivocation`, covering the verification of two header signatures.
+const REPORT_EQUIVOCATION_BASE_WEIGHT: Weight = 100_000_000;
+
+/// A trait for handling equivocation reports in Aura.
+pub trait HandleEquivocation<AccountId, AuthorityId> {
+	/// Handle a valid report by `reporter` that `offender` authored more than one block in `slot`.
+	///
+	/// Returns an error if the report is rejected, e.g. because the equivocation was already
+	/// reported. Only accepted reports are free for the reporter.
+	fn handle_equivocation(
+		reporter: AccountId,
+		offender: AuthorityId,
+		slot: Slot,
+	) -> DispatchResult;
+}
+
+impl<AccountId, AuthorityId> HandleEquivocation<AccountId, AuthorityId> for () {
+	fn handle_equivocation(
+		_reporter: AccountId,
+		_offender: AuthorityId,
+		_slot: Slot,
+	) -> DispatchResult {
+		Err(DispatchError::Other("Equivocation reports are not handled"))
+	}
+}
+
 impl<T: Config> Pallet<T> {
 	fn change_authorities(new: Vec<T::AuthorityId>) {
 		<Authorities<T>>::put(&new);
@@ -178,6 +266,8 @@ impl<T: Config> OneSessionHandler<T::AccountId> for Pallet<T> {
 	fn on_new_session<'a, I: 'a>(changed: bool, validators: I, _queued_validators: I)
 		where I: Iterator<Item=(&'a T::AccountId, T::AuthorityId)>
 	{
+		SessionStartSlot::<T>::put(CurrentSlot::<T>::get());
+
 		// instant changes
 		if changed {
 			let next_authorities = validators.map(|(_, k)| k).collect::<Vec<_>>();
@@ -20,10 +20,13 @@
 #![cfg(test)]
 
 use crate as pallet_aura;
-use sp_consensus_aura::ed25519::AuthorityId;
-use sp_runtime::{traits::IdentityLookup, testing::{Header, UintAuthorityId}};
+use sp_consensus_aura::{ed25519::AuthorityId, Slot};
+use sp_runtime::{
+	traits::IdentityLookup, testing::{Header, UintAuthorityId}, DispatchError, DispatchResult,
+};
 use frame_support::{parameter_types, traits::GenesisBuild};
 use sp_core::H256;
+use std::cell::RefCell;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -81,6 +84,27 @@ impl pallet_timestamp::Config for Test {
 
 impl pallet_aura::Config for Test {
 	type AuthorityId = AuthorityId;
+	type HandleEquivocation = RecordEquivocation;
+}
+
+thread_local! {
+	pub static EQUIVOCATIONS: RefCell<Vec<(u64, AuthorityId, Slot)>> = RefCell::new(vec![]);
+}
+
+/// Records every equivocation passed on by the pallet, rejecting duplicate reports.
+pub struct RecordEquivocation;
+
+impl pallet_aura::HandleEquivocation<u64, AuthorityId> for RecordEquivocation {
+	fn handle_equivocation(reporter: u64, offender: AuthorityId, slot: Slot) -> DispatchResult {
+		EQUIVOCATIONS.with(|e| {
+			let mut equivocations = e.borrow_mut();
+			if equivocations.iter().any(|(_, o, s)| *o == offender && *s == slot) {
+				return Err(DispatchError::Other("Duplicate equivocation report"));
+			}
+			equivocations.push((reporter, offender, slot));
+			Ok(())
+		})
+	}
 }
 
 pub fn new_test_ext(authorities: Vec<u64>) -> sp_io::TestExternalities {
@@ -19,7 +19,35 @@
 
 #![cfg(test)]
 
-use crate::mock::{Aura, new_test_ext};
+use crate::{
+	Authorities, CurrentSlot, Error, SessionStartSlot,
+	mock::{Aura, Origin, Test, EQUIVOCATIONS, new_test_ext},
+};
+use frame_support::{assert_noop, assert_ok, weights::Pays};
+use sp_consensus_aura::{
+	EquivocationProof, Slot, digests::CompatibleDigestItem,
+	ed25519::{AuthorityId, AuthorityPair, AuthoritySignature},
+};
+use sp_core::{H256, Pair};
+use sp_runtime::{generic::DigestItem, testing::Header, traits::Header as _, DispatchError};
+
+fn sealed_header(pair: &AuthorityPair, slot: u64, parent_hash: H256) -> Header {
+	let mut header = Header::new(
+		1,
+		Default::default(),
+		Default::default(),
+		parent_hash,
+		Default::default(),
+	);
+	header.digest_mut().push(
+		<DigestItem<H256> as CompatibleDigestItem<AuthoritySignature>>::aura_pre_digest(slot.into()),
+	);
+	let signature = pair.sign(header.hash().as_ref());
+	header.digest_mut().push(
+		<DigestItem<H256> as CompatibleDigestItem<AuthoritySignature>>::aura_seal(signature),
+	);
+	header
+}
 
 #[test]
 fn initial_values() {
@@ -28,3 +56,128 @@ fn initial_values() {
 		assert_eq!(Aura::authorities().len(), 4);
 	});
 }
+
+#[test]
+fn report_equivocation_works() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let offender: AuthorityId = pair.public();
+
+	new_test_ext(vec![]).execute_with(|| {
+		Authorities::<Test>::put(vec![offender.clone()]);
+		CurrentSlot::<Test>::put(Slot::from(10));
+
+		let proof = EquivocationProof {
+			offender: offender.clone(),
+			slot: Slot::from(10),
+			first_header: sealed_header(&pair, 10, H256::repeat_byte(1)),
+			second_header: sealed_header(&pair, 10, H256::repeat_byte(2)),
+		};
+
+		let post_info = Aura::report_equivocation(Origin::signed(7), Box::new(proof.clone()))
+			.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(
+			EQUIVOCATIONS.with(|e| e.borrow().clone()),
+			vec![(7, offender.clone(), Slot::from(10))],
+		);
+
+		// the handler rejects the duplicate report, so the reporter pays for it
+		assert_noop!(
+			Aura::report_equivocation(Origin::signed(8), Box::new(proof)),
+			DispatchError::Other("Duplicate equivocation report"),
+		);
+		assert_eq!(
+			EQUIVOCATIONS.with(|e| e.borrow().clone()),
+			vec![(7, offender, Slot::from(10))],
+		);
+	});
+}
+
+#[test]
+fn report_equivocation_outside_session() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let offender: AuthorityId = pair.public();
+
+	new_test_ext(vec![]).execute_with(|| {
+		Authorities::<Test>::put(vec![offender.clone()]);
+		CurrentSlot::<Test>::put(Slot::from(20));
+		SessionStartSlot::<Test>::put(Slot::from(15));
+
+		let report = |slot: u64| {
+			let proof = EquivocationProof {
+				offender: offender.clone(),
+				slot: Slot::from(slot),
+				first_header: sealed_header(&pair, slot, H256::repeat_byte(1)),
+				second_header: sealed_header(&pair, slot, H256::repeat_byte(2)),
+			};
+			Aura::report_equivocation(Origin::signed(7), Box::new(proof))
+		};
+
+		// slot of the previous session
+		assert_noop!(report(10), Error::<Test>::SlotOutsideSession);
+		// slot in the future
+		assert_noop!(report(21), Error::<Test>::SlotOutsideSession);
+
+		assert_ok!(report(15));
+		assert_ok!(report(20));
+		assert_eq!(EQUIVOCATIONS.with(|e| e.borrow().len()), 2);
+	});
+}
+
+#[test]
+fn report_equivocation_invalid_proof() {
+	let pair = AuthorityPair::from_seed(&[1; 32]);
+	let offender: AuthorityId = pair.public();
+	let other = AuthorityPair::from_seed(&[2; 32]);
+
+	new_test_ext(vec![]).execute_with(|| {
+		Authorities::<Test>::put(vec![offender.clone()]);
+		CurrentSlot::<Test>::put(Slot::from(10));
+
+		let report = |first_header: Header, second_header: Header| {
+			let proof = EquivocationProof {
+				offender: offender.clone(),
+				slot: Slot::from(10),
+				first_header,
+				second_header,
+			};
+			Aura::report_equivocation(Origin::signed(7), Box::new(proof))
+		};
+
+		// the same header twice is not an equivocation
+		let header = sealed_header(&pair, 10, H256::repeat_byte(1));
+		assert_noop!(report(header.clone(), header), Error::<Test>::InvalidEquivocationProof);
+
+		// headers for different slots
+		assert_noop!(
+			report(
+				sealed_header(&pair, 10, H256::repeat_byte(1)),
+				sealed_header(&pair, 11, H256::repeat_byte(2)),
+			),
+			Error::<Test>::InvalidEquivocationProof,
+		);
+
+		// one header not signed by the offender
+		assert_noop!(
+			report(
+				sealed_header(&pair, 10, H256::repeat_byte(1)),
+				sealed_header(&other, 10, H256::repeat_byte(2)),
+			),
+			Error::<Test>::InvalidEquivocationProof,
+		);
+
+		// the offender is not an authority
+		let proof = EquivocationProof {
+			offender: other.public(),
+			slot: Slot::from(10),
+			first_header: sealed_header(&other, 10, H256::repeat_byte(1)),
+			second_header: sealed_header(&other, 10, H256::repeat_byte(2)),
+		};
+		assert_noop!(
+			Aura::report_equivocation(Origin::signed(7), Box::new(proof)),
+			Error::<Test>::UnknownOffender,
+		);
+
+		assert!(EQUIVOCATIONS.with(|e| e.borrow().is_empty()));
+	});
+}
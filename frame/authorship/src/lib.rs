@@ -291,8 +291,6 @@ impl<T: Config> Module<T> {
 			(min, now)
 		};
 
-		let hash = uncle.hash();
-
 		if uncle.number() < &One::one() {
 			return Err(Error::<T>::GenesisUncle.into());
 		}
@@ -313,6 +311,20 @@ impl<T: Config> Module<T> {
 			return Err(Error::<T>::OldUncle.into());
 		}
 
+		Self::verify_uncle_inclusion(uncle, existing_uncles, accumulator)
+	}
+
+	/// The checks of [`Self::verify_uncle`] which do not depend on the current block number.
+	///
+	/// `check_inherent` runs on the state of the parent block whose number and hash differ
+	/// from those of the block that includes the uncles.
+	fn verify_uncle_inclusion<'a, I: IntoIterator<Item=&'a T::Hash>>(
+		uncle: &T::Header,
+		existing_uncles: I,
+		accumulator: &mut <T::FilterUncle as FilterUncle<T::Header, T::AccountId>>::Accumulator,
+	) -> Result<Option<T::AccountId>, dispatch::DispatchError>
+	{
+		let hash = uncle.hash();
 		let duplicate = existing_uncles.into_iter().find(|h| **h == hash).is_some();
 		let in_chain = <frame_system::Module<T>>::block_hash(uncle.number()) == hash;
 
@@ -324,6 +336,16 @@ impl<T: Config> Module<T> {
 		T::FilterUncle::filter_uncle(&uncle, accumulator).map_err(|e| Into::into(e))
 	}
 
+	/// Hashes of all uncles that are still tracked as included.
+	fn existing_uncle_hashes() -> Vec<T::Hash> {
+		<Self as Store>::Uncles::get().into_iter().filter_map(|entry|
+			match entry {
+				UncleEntryItem::InclusionHeight(_) => None,
+				UncleEntryItem::Uncle(h, _) => Some(h),
+			}
+		).collect()
+	}
+
 	fn prune_old_uncles(minimum_height: T::BlockNumber) {
 		let mut uncles = <Self as Store>::Uncles::get();
 		let prune_entries = uncles.iter().take_while(|item| match item {
@@ -347,13 +369,7 @@ impl<T: Config> ProvideInherent for Module<T> {
 		let mut set_uncles = Vec::new();
 
 		if !uncles.is_empty() {
-			let prev_uncles = <Self as Store>::Uncles::get();
-			let mut existing_hashes: Vec<_> = prev_uncles.into_iter().filter_map(|entry|
-				match entry {
-					UncleEntryItem::InclusionHeight(_) => None,
-					UncleEntryItem::Uncle(h, _) => Some(h),
-				}
-			).collect();
+			let mut existing_hashes = Self::existing_uncle_hashes();
 
 			let mut acc: <T::FilterUncle as FilterUncle<_, _>>::Accumulator = Default::default();
 
@@ -387,6 +403,19 @@ impl<T: Config> ProvideInherent for Module<T> {
 			Call::set_uncles(ref uncles) if uncles.len() > MAX_UNCLES => {
 				Err(InherentError::Uncles(Error::<T>::TooManyUncles.as_str().into()))
 			},
+			Call::set_uncles(ref uncles) => {
+				let mut existing_hashes = Self::existing_uncle_hashes();
+				let mut acc: <T::FilterUncle as FilterUncle<_, _>>::Accumulator =
+					Default::default();
+
+				for uncle in uncles {
+					Self::verify_uncle_inclusion(uncle, &existing_hashes, &mut acc)
+						.map_err(|e| InherentError::Uncles(<&'static str>::from(e).into()))?;
+					existing_hashes.push(uncle.hash());
+				}
+
+				Ok(())
+			},
 			_ => {
 				Ok(())
 			},
@@ -673,6 +702,60 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn check_inherent_rejects_invalid_uncles() {
+		new_test_ext().execute_with(|| {
+			let author_a = 69;
+			let mut best_hash = seal_header(
+				create_header(0, Default::default(), Default::default()),
+				999,
+			).hash();
+			let mut canon_hashes = vec![best_hash];
+
+			for number in 1..8 {
+				System::initialize(&number, &best_hash, &Default::default(), Default::default());
+				best_hash = seal_header(System::finalize(), author_a).hash();
+				canon_hashes.push(best_hash);
+			}
+
+			// Inherents are checked on the state of the parent block, block 7.
+
+			// `InherentError` is not comparable, so compare the encoded errors.
+			let check = |uncles: Vec<Header>| Authorship::check_inherent(
+				&Call::set_uncles(uncles),
+				&InherentData::new(),
+			).map_err(|e| e.encode());
+			let uncles_error = |e: &'static str| InherentError::Uncles(e.into()).encode();
+
+			let uncle_a = seal_header(
+				create_header(3, canon_hashes[2], [1; 32].into()),
+				author_a,
+			);
+			assert!(check(vec![uncle_a.clone()]).is_ok());
+
+			// An uncle as high as the checked block is only valid for the block itself.
+			let uncle_b = seal_header(
+				create_header(8, canon_hashes[7], [1; 32].into()),
+				author_a,
+			);
+			assert!(check(vec![uncle_b]).is_ok());
+
+			assert_eq!(
+				check(vec![uncle_a.clone(), uncle_a.clone()]),
+				Err(uncles_error(Error::<Test>::UncleAlreadyIncluded.as_str())),
+			);
+
+			let unsealed = create_header(3, canon_hashes[2], [2; 32].into());
+			assert_eq!(check(vec![unsealed]), Err(uncles_error("no author")));
+
+			let too_many = vec![uncle_a; MAX_UNCLES + 1];
+			assert_eq!(
+				check(too_many),
+				Err(uncles_error(Error::<Test>::TooManyUncles.as_str())),
+			);
+		});
+	}
+
 	#[test]
 	fn sets_author_lazily() {
 		new_test_ext().execute_with(|| {
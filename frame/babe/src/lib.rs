@@ -25,7 +25,9 @@ use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_module, decl_storage,
 	dispatch::DispatchResultWithPostInfo,
-	traits::{FindAuthor, Get, KeyOwnerProofSystem, OneSessionHandler, OnTimestampSet},
+	traits::{
+		DisabledValidators, FindAuthor, Get, KeyOwnerProofSystem, OneSessionHandler, OnTimestampSet,
+	},
 	weights::{Pays, Weight},
 	Parameter,
 };
@@ -108,6 +110,10 @@ pub trait Config: pallet_timestamp::Config {
 	/// definition.
 	type HandleEquivocation: HandleEquivocation<Self>;
 
+	/// Validators that were disabled in the current session (e.g. after an equivocation) and
+	/// whose blocks must be rejected.
+	type DisabledValidators: DisabledValidators;
+
 	type WeightInfo: WeightInfo;
 }
 
@@ -630,6 +636,13 @@ impl<T: Config> Module<T> {
 
 			let authority_index = digest.authority_index();
 
+			if T::DisabledValidators::is_disabled(authority_index) {
+				panic!(
+					"Validator with index {:?} is disabled and should not be attempting to author blocks.",
+					authority_index,
+				);
+			}
+
 			// Extract out the VRF output if we have it
 			digest
 				.vrf_output()
@@ -154,6 +154,8 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = u128;
 	type DustRemoval = ();
 	type Event = Event;
@@ -204,7 +206,7 @@ impl pallet_staking::Config for Test {
 	type SlashCancelOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type SessionInterface = Self;
 	type UnixTime = pallet_timestamp::Module<Test>;
-	type RewardCurve = RewardCurve;
+	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type NextNewSession = Session;
 	type ElectionLookahead = ElectionLookahead;
@@ -214,6 +216,7 @@ impl pallet_staking::Config for Test {
 	type MinSolutionScoreBump = ();
 	type OffchainSolutionWeightLimit = ();
 	type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
 	type WeightInfo = ();
 }
 
@@ -254,6 +257,7 @@ impl Config for Test {
 	type HandleEquivocation =
 		super::EquivocationHandler<Self::KeyOwnerIdentification, Offences, ReportLongevity>;
 
+	type DisabledValidators = Session;
 	type WeightInfo = ();
 }
 
@@ -271,7 +275,13 @@ pub fn go_to_block(n: u64, s: u64) {
 		System::parent_hash()
 	};
 
-	let pre_digest = make_secondary_plain_pre_digest(0, s.into());
+	// author the block with the first authority that isn't disabled
+	let disabled = Session::disabled_validators();
+	let authority_index = (0..)
+		.find(|i| !disabled.contains(i))
+		.expect("not every authority is disabled; qed");
+
+	let pre_digest = make_secondary_plain_pre_digest(authority_index, s.into());
 
 	System::initialize(&n, &parent_hash, &pre_digest, InitKind::Full);
 
@@ -199,6 +199,33 @@ fn authority_index() {
 	})
 }
 
+#[test]
+#[should_panic(
+	expected = "Validator with index 0 is disabled and should not be attempting to author blocks."
+)]
+fn disabled_validators_cannot_author_blocks() {
+	use sp_runtime::traits::Header;
+
+	new_test_ext(4).execute_with(|| {
+		start_era(1);
+
+		// let's disable the validator at index 0
+		Session::disable_index(0);
+
+		// and try to author a block with it
+		let block_number = System::block_number() + 1;
+		let slot = u64::from(Babe::current_slot()) + 1;
+
+		Babe::on_finalize(System::block_number());
+		let parent_hash = System::finalize().hash();
+
+		let pre_digest = make_secondary_plain_pre_digest(0, slot.into());
+		System::initialize(&block_number, &parent_hash, &pre_digest, Default::default());
+
+		Babe::do_initialize(block_number);
+	});
+}
+
 #[test]
 fn can_predict_next_epoch_change() {
 	new_test_ext(1).execute_with(|| {
@@ -0,0 +1,58 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the bags-list pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_system::RawOrigin;
+
+use crate::Pallet as BagsList;
+
+benchmarks! {
+	// The worst case moves a node which is neither head nor tail of its old bag to the tail of a
+	// non-empty bag.
+	rebag {
+		let thresholds = T::BagThresholds::get();
+		let (origin_bag, dest_bag) = (thresholds[0], thresholds[1]);
+
+		let origin_head: T::AccountId = account("origin_head", 0, 0);
+		let dislocated: T::AccountId = account("dislocated", 0, 0);
+		let origin_tail: T::AccountId = account("origin_tail", 0, 0);
+		let dest_head: T::AccountId = account("dest_head", 0, 0);
+		for id in [&origin_head, &dislocated, &origin_tail].iter() {
+			List::<T>::insert((*id).clone(), origin_bag).map_err(|_| "insert failed")?;
+		}
+		List::<T>::insert(dest_head, dest_bag).map_err(|_| "insert failed")?;
+
+		T::VoteWeightProvider::set_vote_weight_of(&dislocated, dest_bag);
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), dislocated.clone())
+	verify {
+		let node = Node::<T>::get(&dislocated).ok_or("dislocated was removed")?;
+		assert!(!node.is_misplaced(dest_bag));
+		List::<T>::sanity_check()?;
+	}
+}
+
+impl_benchmark_test_suite!(
+	BagsList,
+	crate::mock::ExtBuilder::default().build(),
+	crate::mock::Runtime,
+);
@@ -0,0 +1,252 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Bags-List Pallet
+//!
+//! A semi-sorted list, where items hold an `AccountId` based on some `VoteWeight`. The `AccountId`
+//! (`id` for short) might be synonym to a `voter` or `nominator` in some context, and `VoteWeight`
+//! signifies the chance of each id being included in the final
+//! [`SortedListProvider::iter`](sp_election_providers::SortedListProvider::iter).
+//!
+//! It implements [`sp_election_providers::SortedListProvider`] to provide a semi-sorted list of
+//! accounts to another pallet. It needs some other pallet to give it some information about the
+//! weights of accounts via [`sp_election_providers::VoteWeightProvider`].
+//!
+//! This pallet is not configurable at genesis. Whoever uses it should call appropriate functions
+//! of the `SortedListProvider` (e.g. `on_insert`, or `regenerate`) at their genesis.
+//!
+//! ## Goals
+//!
+//! The data structure exposed by this pallet aims to be optimized for:
+//!
+//! - insertions and removals.
+//! - iteration over the top* N items by weight, where the precise ordering of items doesn't
+//!   particularly matter.
+//!
+//! ## Details
+//!
+//! - items are kept in bags, which are delineated by their range of weight (See
+//!   [`Config::BagThresholds`]).
+//! - for iteration, bags are chained together from highest to lowest and elements within the bag
+//!   are iterated from head to tail.
+//! - items within a bag are iterated in order of insertion. Thus removing an item and re-inserting
+//!   it will worsen its position in list iteration; this reduces incentives for some types of spam
+//!   that involve consistently removing and inserting for better position. Further, ordering
+//!   granularity is thus dictated by range between each bag threshold.
+//! - if an item's weight changes to a value no longer within the range of its current bag the
+//!   item's position will need to be updated by an external actor with rebag (update), or removal
+//!   and insertion.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `rebag` - Move an account into the bag matching its current weight. Permissionless.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::traits::Get;
+use sp_election_providers::{SortedListProvider, VoteWeight, VoteWeightProvider};
+use sp_std::prelude::*;
+
+mod benchmarking;
+mod list;
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use list::{notional_bag_for, Bag, Error, List, Node};
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub(crate) const LOG_TARGET: &'static str = "runtime::bags_list";
+
+// syntactic sugar for logging.
+macro_rules! log {
+	($level:tt, $patter:expr $(, $values:expr)* $(,)?) => {
+		log::$level!(
+			target: crate::LOG_TARGET,
+			concat!("👜 ", $patter) $(, $values)*
+		)
+	};
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: weights::WeightInfo;
+
+		/// Something that provides the weights of ids.
+		type VoteWeightProvider: VoteWeightProvider<Self::AccountId>;
+
+		/// The list of thresholds separating the various bags.
+		///
+		/// Ids are separated into unsorted bags according to their vote weight. This specifies
+		/// the thresholds separating the bags. An id's bag is the largest bag for which the id's
+		/// weight exceeds the threshold.
+		///
+		/// - Ids within a bag are iterated in order of insertion.
+		/// - Thresholds must be strictly increasing.
+		/// - If the last threshold is not `VoteWeight::MAX`, an implicit bag with that upper
+		///   bound exists.
+		///
+		/// The bag thresholds are a trade-off between a finer granularity in ordering and the
+		/// number of storage items needed to represent the list. A geometric progression of
+		/// thresholds, e.g. with a constant ratio of ~1.1, is a reasonable choice.
+		///
+		/// Changing the thresholds of a live list requires regenerating it, e.g. through
+		/// `SortedListProvider::regenerate`.
+		#[pallet::constant]
+		type BagThresholds: Get<&'static [VoteWeight]>;
+	}
+
+	/// How many ids are registered.
+	// NOTE: This is merely a counter for `ListNodes`.
+	#[pallet::storage]
+	pub(crate) type CounterForListNodes<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// A single node, within some bag.
+	///
+	/// Nodes store links forward and back within their respective bags.
+	#[pallet::storage]
+	pub(crate) type ListNodes<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, list::Node<T>>;
+
+	/// A bag stored in storage.
+	///
+	/// Stores a `Bag` struct, which stores head and tail pointers to itself.
+	#[pallet::storage]
+	pub(crate) type ListBags<T: Config> = StorageMap<_, Twox64Concat, VoteWeight, list::Bag<T>>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Moved an account from one bag to another. \[who, from, to\].
+		Rebagged(T::AccountId, VoteWeight, VoteWeight),
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Declare that some `dislocated` account has, through rewards or penalties, sufficiently
+		/// changed its weight that it should properly fall into a different bag than its current
+		/// one.
+		///
+		/// Anyone can call this function about any potentially dislocated account.
+		///
+		/// Will never return an error; if `dislocated` does not exist or doesn't need a rebag, then
+		/// it is a noop and fees are still collected from `origin`.
+		#[pallet::weight(T::WeightInfo::rebag())]
+		pub(super) fn rebag(
+			origin: OriginFor<T>,
+			dislocated: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let current_weight = T::VoteWeightProvider::vote_weight(&dislocated);
+			let _ = Pallet::<T>::do_rebag(&dislocated, current_weight);
+			Ok(().into())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			// ensure they are strictly increasing, this also implies that duplicates are detected.
+			assert!(
+				T::BagThresholds::get().windows(2).all(|window| window[1] > window[0]),
+				"thresholds must strictly increase",
+			);
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Move an account from one bag to another, depositing an event on success.
+	///
+	/// If the account changed bags, returns `Some((from, to))`.
+	pub fn do_rebag(
+		account: &T::AccountId,
+		new_weight: VoteWeight,
+	) -> Option<(VoteWeight, VoteWeight)> {
+		// if no voter at that node, don't do anything.
+		// the caller just wasted the fee to call this.
+		let maybe_movement = list::Node::<T>::get(&account)
+			.and_then(|node| List::update_position_for(node, new_weight));
+		if let Some((from, to)) = maybe_movement {
+			Self::deposit_event(Event::<T>::Rebagged(account.clone(), from, to));
+		};
+		maybe_movement
+	}
+}
+
+impl<T: Config> SortedListProvider<T::AccountId> for Pallet<T> {
+	type Error = Error;
+
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(List::<T>::iter().map(|n| n.id().clone()))
+	}
+
+	fn count() -> u32 {
+		CounterForListNodes::<T>::get()
+	}
+
+	fn contains(id: &T::AccountId) -> bool {
+		List::<T>::contains(id)
+	}
+
+	fn on_insert(id: T::AccountId, weight: VoteWeight) -> Result<(), Error> {
+		List::<T>::insert(id, weight)
+	}
+
+	fn on_update(id: &T::AccountId, new_weight: VoteWeight) {
+		Pallet::<T>::do_rebag(id, new_weight);
+	}
+
+	fn on_remove(id: &T::AccountId) {
+		List::<T>::remove(id)
+	}
+
+	fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		weight_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		List::<T>::regenerate(all, weight_of)
+	}
+
+	fn clear() {
+		List::<T>::clear()
+	}
+
+	fn sanity_check() -> Result<(), &'static str> {
+		List::<T>::sanity_check()
+	}
+}
@@ -0,0 +1,537 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of a "bags list": a semi-sorted list where ordering granularity is dictated by
+//! configurable thresholds that delineate the boundaries of bags. It uses a pattern of composite
+//! data structures, where multiple storage items are masked by one outer API. See [`ListNodes`],
+//! [`CounterForListNodes`] and [`ListBags`] for more information.
+//!
+//! The outer API of this module is the [`List`] struct. It wraps all acceptable operations on top
+//! of the aggregate linked list. All operations with the bags list should happen through this
+//! interface.
+
+use crate::{Config, CounterForListNodes, ListBags, ListNodes};
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, CloneNoBound, DebugNoBound, PartialEqNoBound};
+use sp_election_providers::VoteWeight;
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	iter,
+	marker::PhantomData,
+	prelude::*,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The errors that can occur when modifying the list.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+	/// A duplicate id has been detected.
+	Duplicate,
+}
+
+/// Given a certain vote weight, to which bag does it belong to?
+///
+/// Bags are identified by their upper threshold; the value returned by this function is guaranteed
+/// to be a member of `T::BagThresholds`.
+///
+/// Note that even if the thresholds list does not have `VoteWeight::MAX` as its final member, this
+/// function behaves as if it does.
+pub fn notional_bag_for<T: Config>(weight: VoteWeight) -> VoteWeight {
+	let thresholds = T::BagThresholds::get();
+	let idx = match thresholds.binary_search(&weight) {
+		Ok(idx) => idx,
+		Err(idx) => idx,
+	};
+	thresholds.get(idx).copied().unwrap_or(VoteWeight::MAX)
+}
+
+/// The **ONLY** entry point of this module. All operations to the bags-list should happen through
+/// this interface. It is forbidden to access other module members directly.
+//
+// Data structure providing efficient mostly-accurate selection of the top N id by `VoteWeight`.
+//
+// It's implemented as a set of linked lists. Each linked list comprises a bag of ids of
+// arbitrary and unbounded length, all having a vote weight within a particular constant range.
+// This structure means that ids can be added and removed in `O(1)` time.
+//
+// Iteration is accomplished by chaining the iteration of each bag, from greatest to least. While
+// the users within any particular bag are sorted in an entirely arbitrary order, the overall vote
+// weight decreases as successive bags are reached. This means that it is valid to truncate
+// iteration at any desired point; only those ids in the lowest bag can be excluded. This
+// satisfies both the desire for fairness and the requirement for efficiency.
+pub struct List<T: Config>(PhantomData<T>);
+
+impl<T: Config> List<T> {
+	/// Remove all data associated with the list from storage.
+	pub(crate) fn clear() {
+		CounterForListNodes::<T>::kill();
+		ListBags::<T>::remove_all();
+		ListNodes::<T>::remove_all();
+	}
+
+	/// Regenerate all of the data from the given ids.
+	///
+	/// WARNING: this is expensive and should only ever be performed when the list needs to be
+	/// generated from scratch.
+	///
+	/// This may or may not need to be called at genesis as well, based on the configuration of the
+	/// pallet using this `List`.
+	///
+	/// Returns the number of ids migrated.
+	pub fn regenerate(
+		all: impl IntoIterator<Item = T::AccountId>,
+		weight_of: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		Self::clear();
+		Self::insert_many(all, weight_of)
+	}
+
+	/// Returns `true` if the list contains `id`, otherwise returns `false`.
+	pub(crate) fn contains(id: &T::AccountId) -> bool {
+		ListNodes::<T>::contains_key(id)
+	}
+
+	/// Iterate over all nodes in all bags in the list.
+	///
+	/// Full iteration can be expensive; it's recommended to limit the number of items with
+	/// `.take(n)`.
+	pub(crate) fn iter() -> impl Iterator<Item = Node<T>> {
+		// We need a touch of special handling here: because we permit `T::BagThresholds` to
+		// omit the final bound, we need to ensure that we explicitly include that threshold in the
+		// list.
+		//
+		// It's important to retain the ability to omit the final bound because it makes tests much
+		// easier; they can just configure `type BagThresholds = ()`.
+		let thresholds = T::BagThresholds::get();
+		let iter = thresholds.iter().copied();
+		let iter: Box<dyn Iterator<Item = u64>> = if thresholds.last() == Some(&VoteWeight::MAX) {
+			// in the event that they included it, we can just pass the iterator through unchanged.
+			Box::new(iter.rev())
+		} else {
+			// otherwise, insert it here.
+			Box::new(iter.chain(iter::once(VoteWeight::MAX)).rev())
+		};
+
+		iter.filter_map(Bag::get).flat_map(|bag| bag.iter())
+	}
+
+	/// Insert several ids into the appropriate bags in the list. Continues with insertions
+	/// if duplicates are detected.
+	///
+	/// Returns the final count of number of ids inserted.
+	fn insert_many(
+		ids: impl IntoIterator<Item = T::AccountId>,
+		weight_of: impl Fn(&T::AccountId) -> VoteWeight,
+	) -> u32 {
+		let mut count = 0;
+		ids.into_iter().for_each(|v| {
+			let weight = weight_of(&v);
+			if Self::insert(v, weight).is_ok() {
+				count += 1;
+			}
+		});
+
+		count
+	}
+
+	/// Insert a new id into the appropriate bag in the list.
+	///
+	/// Returns an error if the list already contains `id`.
+	pub(crate) fn insert(id: T::AccountId, weight: VoteWeight) -> Result<(), Error> {
+		if Self::contains(&id) {
+			return Err(Error::Duplicate);
+		}
+
+		let bag_weight = notional_bag_for::<T>(weight);
+		let mut bag = Bag::<T>::get_or_make(bag_weight);
+		// unchecked insertion is okay; we just got the correct `notional_bag_for`.
+		bag.insert_unchecked(id.clone());
+
+		// new inserts are always the tail, so we must write the bag.
+		bag.put();
+
+		CounterForListNodes::<T>::mutate(|prev_count| {
+			*prev_count = prev_count.saturating_add(1)
+		});
+
+		crate::log!(
+			debug,
+			"inserted {:?} with weight {} into bag {:?}, new count is {}",
+			id,
+			weight,
+			bag_weight,
+			CounterForListNodes::<T>::get(),
+		);
+
+		Ok(())
+	}
+
+	/// Remove an id from the list. If the id is not in the list, this is a noop.
+	pub(crate) fn remove(id: &T::AccountId) {
+		let node = match Node::<T>::get(id) {
+			Some(node) => node,
+			None => return,
+		};
+
+		// clear the bag head/tail pointers as necessary.
+		let mut bag = Bag::<T>::get(node.bag_upper)
+			.expect("every node must have a bag; qed");
+		bag.remove_node_unchecked(&node);
+		bag.put();
+
+		// now get rid of the node itself.
+		node.remove_from_storage_unchecked();
+
+		CounterForListNodes::<T>::mutate(|prev_count| {
+			*prev_count = prev_count.saturating_sub(1)
+		});
+	}
+
+	/// Update a node's position in the list.
+	///
+	/// If the node was in the correct bag, no effect. If the node was in the incorrect bag, they
+	/// are moved into the correct bag.
+	///
+	/// Returns `Some((old_idx, new_idx))` if the node moved, otherwise `None`.
+	///
+	/// This operation is somewhat more efficient than simply calling [`List::remove`] followed by
+	/// [`List::insert`].
+	pub(crate) fn update_position_for(
+		node: Node<T>,
+		new_weight: VoteWeight,
+	) -> Option<(VoteWeight, VoteWeight)> {
+		node.is_misplaced(new_weight).then(move || {
+			let old_bag_upper = node.bag_upper;
+
+			// clear the old bag head/tail pointers as necessary.
+			let mut bag = Bag::<T>::get(node.bag_upper)
+				.expect("every node must have a bag; qed");
+			bag.remove_node_unchecked(&node);
+			bag.put();
+
+			// put the node into the appropriate new bag.
+			let new_bag_upper = notional_bag_for::<T>(new_weight);
+			let mut bag = Bag::<T>::get_or_make(new_bag_upper);
+			// prev, next, and bag_upper of the node are updated inside `insert_node_unchecked`,
+			// also the node gets written to storage.
+			bag.insert_node_unchecked(node);
+			bag.put();
+
+			(old_bag_upper, new_bag_upper)
+		})
+	}
+
+	/// Sanity check the list.
+	///
+	/// This should be called from the call-site, whenever one of the mutating apis (e.g. `insert`)
+	/// is being used, after all other staking data (such as counter) has been updated. It checks:
+	///
+	/// * there are no duplicate ids,
+	/// * length of this list is in sync with `CounterForListNodes`,
+	/// * and sanity-checks all bags. This will cascade down all the checks and makes sure all bags
+	///   are checked per *any* update to `List`.
+	pub(crate) fn sanity_check() -> Result<(), &'static str> {
+		let mut seen_in_list = BTreeSet::new();
+		frame_support::ensure!(
+			Self::iter().map(|node| node.id).all(|id| seen_in_list.insert(id)),
+			"duplicate identified",
+		);
+
+		let iter_count = Self::iter().count() as u32;
+		let stored_count = CounterForListNodes::<T>::get();
+		let nodes_count = ListNodes::<T>::iter().count() as u32;
+		frame_support::ensure!(iter_count == stored_count, "iter_count != stored_count");
+		frame_support::ensure!(stored_count == nodes_count, "stored_count != nodes_count");
+
+		let thresholds = T::BagThresholds::get().iter().copied();
+		let thresholds: Vec<u64> = if thresholds.clone().last() == Some(VoteWeight::MAX) {
+			// in the event that they included it, we don't need to make any changes
+			thresholds.collect()
+		} else {
+			// otherwise, insert it here.
+			thresholds.chain(iter::once(VoteWeight::MAX)).collect()
+		};
+
+		let mut nodes_per_bag = BTreeMap::<VoteWeight, u32>::new();
+		for bag in thresholds.into_iter().filter_map(Bag::<T>::get) {
+			bag.sanity_check()?;
+			nodes_per_bag.insert(bag.bag_upper, bag.iter().count() as u32);
+		}
+
+		// every node must be in the bag it claims to be in.
+		for node in ListNodes::<T>::iter_values() {
+			frame_support::ensure!(
+				nodes_per_bag.contains_key(&node.bag_upper),
+				"node points to a non-existent bag",
+			);
+		}
+
+		Ok(())
+	}
+}
+
+/// A Bag is a doubly-linked list of ids, where each id is mapped to a [`Node`].
+///
+/// Note that we maintain both head and tail pointers. While it would be possible to get away with
+/// maintaining only a head pointer and cons-ing elements onto the front of the list, it's more
+/// desirable to ensure that there is some element of first-come, first-serve to the list's
+/// iteration so that there's no incentive to churn ids positioning to improve the chances of
+/// appearing within the ids set.
+#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, DebugNoBound)]
+pub struct Bag<T: Config> {
+	head: Option<T::AccountId>,
+	tail: Option<T::AccountId>,
+
+	#[codec(skip)]
+	bag_upper: VoteWeight,
+}
+
+impl<T: Config> Bag<T> {
+	#[cfg(test)]
+	pub(crate) fn new(
+		head: Option<T::AccountId>,
+		tail: Option<T::AccountId>,
+		bag_upper: VoteWeight,
+	) -> Self {
+		Self { head, tail, bag_upper }
+	}
+
+	/// Get a bag by its upper vote weight.
+	pub(crate) fn get(bag_upper: VoteWeight) -> Option<Bag<T>> {
+		ListBags::<T>::try_get(bag_upper).ok().map(|mut bag| {
+			bag.bag_upper = bag_upper;
+			bag
+		})
+	}
+
+	/// Get a bag by its upper vote weight or make it, appropriately initialized. Does not check if
+	/// if `bag_upper` is a valid threshold.
+	fn get_or_make(bag_upper: VoteWeight) -> Bag<T> {
+		Self::get(bag_upper).unwrap_or(Bag { head: None, tail: None, bag_upper })
+	}
+
+	/// `True` if self is empty.
+	fn is_empty(&self) -> bool {
+		self.head.is_none() && self.tail.is_none()
+	}
+
+	/// Put the bag back into storage.
+	fn put(self) {
+		if self.is_empty() {
+			ListBags::<T>::remove(self.bag_upper);
+		} else {
+			ListBags::<T>::insert(self.bag_upper, self);
+		}
+	}
+
+	/// Get the head node in this bag.
+	fn head(&self) -> Option<Node<T>> {
+		self.head.as_ref().and_then(|id| Node::get(id))
+	}
+
+	/// Get the tail node in this bag.
+	fn tail(&self) -> Option<Node<T>> {
+		self.tail.as_ref().and_then(|id| Node::get(id))
+	}
+
+	/// Iterate over the nodes in this bag.
+	pub(crate) fn iter(&self) -> impl Iterator<Item = Node<T>> {
+		sp_std::iter::successors(self.head(), |prev| prev.next())
+	}
+
+	/// Insert a new id into this bag.
+	///
+	/// This is private on purpose because it's naive: it doesn't check whether this is the
+	/// appropriate bag for this id at all. Generally, use [`List::insert`] instead.
+	///
+	/// Storage note: this modifies storage, but only for the nodes. You still need to call
+	/// `self.put()` after use.
+	fn insert_unchecked(&mut self, id: T::AccountId) {
+		// insert_node will overwrite `prev`, `next` and `bag_upper` to the proper values. As long
+		// as this bag is the correct one, we're good. All calls to this must come after getting the
+		// correct [`notional_bag_for`].
+		self.insert_node_unchecked(Node::<T> { id, prev: None, next: None, bag_upper: 0 });
+	}
+
+	/// Insert a node into this bag.
+	///
+	/// This is private on purpose because it's naive; it doesn't check whether this is the
+	/// appropriate bag for this node at all. Generally, use [`List::insert`] instead.
+	///
+	/// Storage note: this modifies storage, but only for the node. You still need to call
+	/// `self.put()` after use.
+	fn insert_node_unchecked(&mut self, mut node: Node<T>) {
+		if let Some(tail) = &self.tail {
+			if *tail == node.id {
+				// this should never happen, but this check prevents one path to a worst case
+				// infinite loop.
+				debug_assert!(false, "system logic error: inserting a node who has the id of tail");
+				crate::log!(warn, "system logic error: inserting a node who has the id of tail");
+				return
+			};
+		}
+
+		// re-set the `bag_upper`. Regardless of whatever the node had previously, now it is going
+		// to be `self.bag_upper`.
+		node.bag_upper = self.bag_upper;
+
+		let id = node.id.clone();
+		// update this node now, treating it as the new tail.
+		node.prev = self.tail.clone();
+		node.next = None;
+		node.put();
+
+		// update the previous tail.
+		if let Some(mut old_tail) = self.tail() {
+			old_tail.next = Some(id.clone());
+			old_tail.put();
+		}
+		self.tail = Some(id.clone());
+
+		// ensure head exist. This is only set when the length of the bag is just 1, i.e. if this is
+		// the first insertion into the bag. In this case, both head and tail should point to the
+		// same node.
+		if self.head.is_none() {
+			self.head = Some(id);
+			debug_assert!(self.iter().count() == 1);
+		}
+	}
+
+	/// Remove a node from this bag.
+	///
+	/// This is private on purpose because it doesn't check whether this bag contains the node in
+	/// the first place. Generally, use [`List::remove`] instead, similar to `insert_unchecked`.
+	///
+	/// Storage note: this modifies storage, but only for adjacent nodes. You still need to call
+	/// `self.put()` and `ListNodes::remove(id)` to update storage for the bag and `node`.
+	fn remove_node_unchecked(&mut self, node: &Node<T>) {
+		// reassign neighboring nodes.
+		node.excise();
+
+		// clear the bag head/tail pointers as necessary.
+		if self.tail.as_ref() == Some(&node.id) {
+			self.tail = node.prev.clone();
+		}
+		if self.head.as_ref() == Some(&node.id) {
+			self.head = node.next.clone();
+		}
+	}
+
+	/// Sanity check this bag.
+	///
+	/// Should be called by the call-site, after any mutating operation on a bag. The call site of
+	/// this struct is always `List`.
+	///
+	/// * Ensures head has no prev.
+	/// * Ensures tail has no next.
+	/// * Ensures there are no loops, traversal from head to tail is correct.
+	/// * Ensures all nodes claim to be in this bag.
+	fn sanity_check(&self) -> Result<(), &'static str> {
+		frame_support::ensure!(
+			self.head().map(|head| head.prev().is_none()).unwrap_or(true),
+			"head has a prev"
+		);
+
+		frame_support::ensure!(
+			self.tail().map(|tail| tail.next().is_none()).unwrap_or(true),
+			"tail has a next"
+		);
+
+		let mut seen_in_bag = BTreeSet::new();
+		frame_support::ensure!(
+			self.iter()
+				.map(|node| node.id)
+				// each voter is only seen once, thus there is no cycle within a bag
+				.all(|voter| seen_in_bag.insert(voter)),
+			"duplicate found in bag"
+		);
+
+		frame_support::ensure!(
+			self.iter().all(|node| node.bag_upper == self.bag_upper),
+			"node in wrong bag"
+		);
+
+		Ok(())
+	}
+}
+
+/// A Node is the fundamental element comprising the doubly-linked list described by `Bag`.
+#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, DebugNoBound)]
+pub struct Node<T: Config> {
+	id: T::AccountId,
+	prev: Option<T::AccountId>,
+	next: Option<T::AccountId>,
+	bag_upper: VoteWeight,
+}
+
+impl<T: Config> Node<T> {
+	/// Get a node by id.
+	pub(crate) fn get(id: &T::AccountId) -> Option<Node<T>> {
+		ListNodes::<T>::try_get(id).ok()
+	}
+
+	/// Put the node back into storage.
+	fn put(self) {
+		ListNodes::<T>::insert(self.id.clone(), self);
+	}
+
+	/// Update neighboring nodes to point to reach other.
+	///
+	/// Only updates storage for adjacent nodes, but not `self`; so the user may need to call
+	/// `self.put`.
+	fn excise(&self) {
+		// Update previous node.
+		if let Some(mut prev) = self.prev() {
+			prev.next = self.next.clone();
+			prev.put();
+		}
+		// Update next self.
+		if let Some(mut next) = self.next() {
+			next.prev = self.prev.clone();
+			next.put();
+		}
+	}
+
+	/// This is a naive function that removes a node from the `ListNodes` storage item.
+	///
+	/// It is naive because it does not check if the node has first been removed from its bag.
+	fn remove_from_storage_unchecked(&self) {
+		ListNodes::<T>::remove(&self.id)
+	}
+
+	/// Get the previous node in the bag.
+	fn prev(&self) -> Option<Node<T>> {
+		self.prev.as_ref().and_then(|id| Node::get(id))
+	}
+
+	/// Get the next node in the bag.
+	fn next(&self) -> Option<Node<T>> {
+		self.next.as_ref().and_then(|id| Node::get(id))
+	}
+
+	/// `true` when this voter is in the wrong bag.
+	pub(crate) fn is_misplaced(&self, current_weight: VoteWeight) -> bool {
+		notional_bag_for::<T>(current_weight) != self.bag_upper
+	}
+
+	/// Get the underlying voter.
+	pub(crate) fn id(&self) -> &T::AccountId {
+		&self.id
+	}
+}
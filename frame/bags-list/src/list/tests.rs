@@ -0,0 +1,522 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::mock::{test_utils::*, *};
+use frame_support::{assert_ok, assert_storage_noop};
+use sp_election_providers::VoteWeightProvider;
+
+#[test]
+fn basic_setup_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		// syntactic sugar to create a raw node
+		let node = |id, prev, next, bag_upper| Node::<Runtime> { id, prev, next, bag_upper };
+
+		assert_eq!(CounterForListNodes::<Runtime>::get(), 4);
+		assert_eq!(ListNodes::<Runtime>::iter().count(), 4);
+		assert_eq!(ListBags::<Runtime>::iter().count(), 2);
+
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4])]);
+
+		// the state of the bags is as expected
+		assert_eq!(
+			ListBags::<Runtime>::get(10).unwrap(),
+			Bag::<Runtime> { head: Some(1), tail: Some(1), bag_upper: 0 }
+		);
+		assert_eq!(
+			ListBags::<Runtime>::get(1_000).unwrap(),
+			Bag::<Runtime> { head: Some(2), tail: Some(4), bag_upper: 0 }
+		);
+
+		assert_eq!(ListNodes::<Runtime>::get(2).unwrap(), node(2, None, Some(3), 1_000));
+		assert_eq!(ListNodes::<Runtime>::get(3).unwrap(), node(3, Some(2), Some(4), 1_000));
+		assert_eq!(ListNodes::<Runtime>::get(4).unwrap(), node(4, Some(3), None, 1_000));
+		assert_eq!(ListNodes::<Runtime>::get(1).unwrap(), node(1, None, None, 10));
+
+		// non-existent id does not have a storage footprint
+		assert_eq!(ListNodes::<Runtime>::get(42), None);
+
+		// iteration of the bags would yield:
+		assert_eq!(get_list_as_ids(), vec![2, 3, 4, 1]);
+	});
+}
+
+#[test]
+fn notional_bag_for_works() {
+	// under a threshold gives the next threshold.
+	assert_eq!(notional_bag_for::<Runtime>(0), 10);
+	assert_eq!(notional_bag_for::<Runtime>(9), 10);
+
+	// at a threshold gives that threshold.
+	assert_eq!(notional_bag_for::<Runtime>(10), 10);
+
+	// above the threshold, gives the next threshold.
+	assert_eq!(notional_bag_for::<Runtime>(11), 20);
+
+	let max_explicit_threshold = *<Runtime as Config>::BagThresholds::get().last().unwrap();
+	assert_eq!(max_explicit_threshold, 10_000);
+
+	// if the max explicit threshold is less than VoteWeight::MAX,
+	assert!(VoteWeight::MAX > max_explicit_threshold);
+
+	// then anything above it will belong to the VoteWeight::MAX bag.
+	assert_eq!(notional_bag_for::<Runtime>(max_explicit_threshold), max_explicit_threshold);
+	assert_eq!(notional_bag_for::<Runtime>(max_explicit_threshold + 1), VoteWeight::MAX);
+}
+
+#[test]
+fn remove_last_node_in_bags_cleans_bag() {
+	ExtBuilder::default().build_and_execute(|| {
+		// given
+		assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4])]);
+
+		// bump 1 to a bigger bag
+		List::<Runtime>::remove(&1);
+		assert_ok!(List::<Runtime>::insert(1, 10_000));
+
+		// then the bag with bound 10 is wiped from storage.
+		assert_eq!(get_bags(), vec![(1_000, vec![2, 3, 4]), (10_000, vec![1])]);
+
+		// and can be recreated again as needed.
+		assert_ok!(List::<Runtime>::insert(77, 10));
+		assert_eq!(get_bags(), vec![(10, vec![77]), (1_000, vec![2, 3, 4]), (10_000, vec![1])]);
+	});
+}
+
+#[test]
+fn migrate_works() {
+	ExtBuilder::default().add_ids(vec![(710, 15), (711, 16), (712, 2_000)]).build_and_execute(|| {
+		// given
+		assert_eq!(
+			get_bags(),
+			vec![(10, vec![1]), (20, vec![710, 711]), (1_000, vec![2, 3, 4]), (2_000, vec![712])]
+		);
+
+		// when the list is regenerated with other weights
+		let weight_of = Box::new(|id: &AccountId| if *id == 1 { 2_000 } else { 15 });
+		assert_eq!(List::<Runtime>::regenerate(vec![1, 2, 710], weight_of), 3);
+
+		// then
+		assert_eq!(get_bags(), vec![(20, vec![2, 710]), (2_000, vec![1])]);
+		assert_eq!(CounterForListNodes::<Runtime>::get(), 3);
+	});
+}
+
+mod list {
+	use super::*;
+
+	#[test]
+	fn iteration_is_semi_sorted() {
+		ExtBuilder::default()
+			.add_ids(vec![(5, 2_000), (6, 2_000)])
+			.build_and_execute(|| {
+				// given
+				assert_eq!(
+					get_bags(),
+					vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (2_000, vec![5, 6])]
+				);
+				assert_eq!(
+					get_list_as_ids(),
+					vec![
+						5, 6, // best bag
+						2, 3, 4, // middle bag
+						1, // last bag.
+					]
+				);
+
+				// when adding an id that has a higher weight than pre-existing ids in the bag
+				assert_ok!(List::<Runtime>::insert(7, 10));
+
+				// then
+				assert_eq!(
+					get_list_as_ids(),
+					vec![
+						5, 6, // best bag
+						2, 3, 4, // middle bag
+						1, 7, // last bag; new id is last.
+					]
+				);
+			})
+	}
+
+	/// This tests that we can `take` x ids, even if that quantity ends midway through a list.
+	#[test]
+	fn take_works() {
+		ExtBuilder::default()
+			.add_ids(vec![(5, 2_000), (6, 2_000)])
+			.build_and_execute(|| {
+				// given
+				assert_eq!(
+					get_bags(),
+					vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (2_000, vec![5, 6])]
+				);
+
+				// when
+				let iteration =
+					List::<Runtime>::iter().map(|node| *node.id()).take(4).collect::<Vec<_>>();
+
+				// then
+				assert_eq!(
+					iteration,
+					vec![
+						5, 6, // best bag, fully iterated
+						2, 3, // middle bag, partially iterated
+					]
+				);
+			})
+	}
+
+	#[test]
+	fn insert_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// when inserting into an existing bag
+			assert_ok!(List::<Runtime>::insert(5, 1_000));
+
+			// then
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4, 5])]);
+			assert_eq!(get_list_as_ids(), vec![2, 3, 4, 5, 1]);
+
+			// when inserting into a non-existent bag
+			assert_ok!(List::<Runtime>::insert(6, 1_001));
+
+			// then
+			assert_eq!(
+				get_bags(),
+				vec![(10, vec![1]), (1_000, vec![2, 3, 4, 5]), (2_000, vec![6])]
+			);
+			assert_eq!(get_list_as_ids(), vec![6, 2, 3, 4, 5, 1]);
+		});
+	}
+
+	#[test]
+	fn insert_errors_with_duplicate_id() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			assert!(get_list_as_ids().contains(&3));
+
+			// then
+			assert_storage_noop!(assert_eq!(
+				List::<Runtime>::insert(3, 20).unwrap_err(),
+				Error::Duplicate
+			));
+		});
+	}
+
+	#[test]
+	fn remove_works() {
+		use crate::{ListBags, ListNodes};
+		let ensure_left = |id, counter| {
+			assert!(!ListNodes::<Runtime>::contains_key(id));
+			assert_eq!(CounterForListNodes::<Runtime>::get(), counter);
+			assert_eq!(ListNodes::<Runtime>::iter().count() as u32, counter);
+		};
+
+		ExtBuilder::default().build_and_execute(|| {
+			// removing a non-existent id is a noop
+			assert!(!ListNodes::<Runtime>::contains_key(42));
+			assert_storage_noop!(List::<Runtime>::remove(&42));
+
+			// when removing a node from a bag with multiple nodes:
+			List::<Runtime>::remove(&2);
+
+			// then
+			assert_eq!(get_list_as_ids(), vec![3, 4, 1]);
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![3, 4])]);
+			ensure_left(2, 3);
+
+			// when removing a node from a bag with only one node:
+			List::<Runtime>::remove(&1);
+
+			// then
+			assert_eq!(get_list_as_ids(), vec![3, 4]);
+			assert_eq!(get_bags(), vec![(1_000, vec![3, 4])]);
+			ensure_left(1, 2);
+			// bag 10 is removed
+			assert!(!ListBags::<Runtime>::contains_key(10));
+
+			// remove remaining ids to make sure storage cleans up as expected
+			List::<Runtime>::remove(&3);
+			ensure_left(3, 1);
+			assert_eq!(get_list_as_ids(), vec![4]);
+
+			List::<Runtime>::remove(&4);
+			ensure_left(4, 0);
+			assert_eq!(get_list_as_ids(), Vec::<AccountId>::new());
+
+			// bags are deleted via removals
+			assert_eq!(ListBags::<Runtime>::iter().count(), 0);
+		});
+	}
+
+	#[test]
+	fn update_position_for_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given a correctly placed account 1 at bag 10.
+			let node = Node::<Runtime>::get(&1).unwrap();
+			assert!(!node.is_misplaced(10));
+
+			// .. it is invalid with weight 20
+			assert!(node.is_misplaced(20));
+
+			// move it to bag 20.
+			assert_eq!(List::<Runtime>::update_position_for(node, 20), Some((10, 20)));
+
+			assert_eq!(get_bags(), vec![(20, vec![1]), (1_000, vec![2, 3, 4])]);
+
+			// get the new updated node; try and update the position with no change in weight.
+			let node = Node::<Runtime>::get(&1).unwrap();
+			assert_storage_noop!(assert_eq!(
+				List::<Runtime>::update_position_for(node.clone(), 20),
+				None,
+			));
+
+			// then move it to bag 1_000 by giving it weight 500.
+			assert_eq!(List::<Runtime>::update_position_for(node.clone(), 500), Some((20, 1_000)));
+			assert_eq!(get_bags(), vec![(1_000, vec![2, 3, 4, 1])]);
+
+			// moving within that bag again is a noop
+			let node = Node::<Runtime>::get(&1).unwrap();
+			assert_storage_noop!(assert_eq!(
+				List::<Runtime>::update_position_for(node.clone(), 750),
+				None,
+			));
+			assert_storage_noop!(assert_eq!(
+				List::<Runtime>::update_position_for(node, 1_000),
+				None,
+			));
+		});
+	}
+
+	#[test]
+	fn sanity_check_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(List::<Runtime>::sanity_check());
+		});
+
+		// make sure there are no duplicates.
+		ExtBuilder::default().build_and_execute(|| {
+			Bag::<Runtime>::get(10).unwrap().insert_unchecked(2);
+			assert_eq!(List::<Runtime>::sanity_check(), Err("duplicate identified"));
+			// restore the state for the post condition of `build_and_execute`.
+			List::<Runtime>::regenerate(
+				GENESIS_IDS.iter().map(|(id, _)| *id),
+				Box::new(StakingMock::vote_weight),
+			);
+		});
+
+		// ensure count is in sync with `CounterForListNodes`.
+		ExtBuilder::default().build_and_execute(|| {
+			crate::CounterForListNodes::<Runtime>::mutate(|counter| *counter += 1);
+			assert_eq!(crate::CounterForListNodes::<Runtime>::get(), 5);
+			assert_eq!(List::<Runtime>::sanity_check(), Err("iter_count != stored_count"));
+			crate::CounterForListNodes::<Runtime>::mutate(|counter| *counter -= 1);
+		});
+	}
+}
+
+mod bags {
+	use super::*;
+
+	#[test]
+	fn get_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			let check_bag = |bag_upper, head, tail, ids| {
+				let bag = Bag::<Runtime>::get(bag_upper).unwrap();
+				let bag_ids = bag.iter().map(|n| *n.id()).collect::<Vec<_>>();
+
+				assert_eq!(bag, Bag::<Runtime> { head, tail, bag_upper });
+				assert_eq!(bag_ids, ids);
+			};
+
+			// given uppers of bags that exist.
+			let existing_bag_uppers = vec![10, 1_000];
+
+			// we can fetch them
+			check_bag(existing_bag_uppers[0], Some(1), Some(1), vec![1]);
+			// (getting the same bag twice has the same results)
+			check_bag(existing_bag_uppers[0], Some(1), Some(1), vec![1]);
+			check_bag(existing_bag_uppers[1], Some(2), Some(4), vec![2, 3, 4]);
+
+			// and all other uppers don't get bags.
+			<Runtime as Config>::BagThresholds::get()
+				.iter()
+				.chain(iter::once(&VoteWeight::MAX))
+				.filter(|bag_upper| !existing_bag_uppers.contains(bag_upper))
+				.for_each(|bag_upper| {
+					assert_storage_noop!(assert_eq!(Bag::<Runtime>::get(*bag_upper), None));
+					assert!(!ListBags::<Runtime>::contains_key(*bag_upper));
+				});
+
+			// when we make a pre-existing bag empty
+			List::<Runtime>::remove(&1);
+
+			// then
+			assert_eq!(Bag::<Runtime>::get(10), None)
+		});
+	}
+
+	#[test]
+	fn insert_node_sets_proper_bag() {
+		ExtBuilder::default().build_and_execute_no_post_check(|| {
+			let node = |id, bag_upper| Node::<Runtime> { id, prev: None, next: None, bag_upper };
+
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4])]);
+
+			let mut bag_10 = Bag::<Runtime>::get(10).unwrap();
+			bag_10.insert_node_unchecked(node(42, 5));
+
+			assert_eq!(
+				ListNodes::<Runtime>::get(&42).unwrap(),
+				Node { bag_upper: 10, prev: Some(1), next: None, id: 42 }
+			);
+		});
+	}
+
+	#[test]
+	fn insert_node_happy_paths_works() {
+		ExtBuilder::default().build_and_execute_no_post_check(|| {
+			let node = |id, bag_upper| Node::<Runtime> { id, prev: None, next: None, bag_upper };
+
+			// when inserting into a bag with 1 node
+			let mut bag_10 = Bag::<Runtime>::get(10).unwrap();
+			bag_10.insert_node_unchecked(node(42, bag_10.bag_upper));
+			// then
+			assert_eq!(bag_as_ids(&bag_10), vec![1, 42]);
+
+			// when inserting into a bag with 3 nodes
+			let mut bag_1000 = Bag::<Runtime>::get(1_000).unwrap();
+			bag_1000.insert_node_unchecked(node(52, bag_1000.bag_upper));
+			// then
+			assert_eq!(bag_as_ids(&bag_1000), vec![2, 3, 4, 52]);
+
+			// when inserting into a new bag
+			let mut bag_20 = Bag::<Runtime>::get_or_make(20);
+			bag_20.insert_node_unchecked(node(62, 20));
+			// then
+			assert_eq!(bag_as_ids(&bag_20), vec![62]);
+
+			// when inserting a node pointing to the accounts not in the bag
+			let node_61 =
+				Node::<Runtime> { id: 61, prev: Some(21), next: Some(101), bag_upper: 20 };
+			bag_20.insert_node_unchecked(node_61);
+			// then ids are in order
+			assert_eq!(bag_as_ids(&bag_20), vec![62, 61]);
+			// and when the node is re-fetched all the info is correct
+			assert_eq!(
+				Node::<Runtime>::get(&61).unwrap(),
+				Node::<Runtime> { id: 61, prev: Some(62), next: None, bag_upper: 20 }
+			);
+
+			// state of all bags is as expected
+			bag_20.put(); // need to put this newly created bag so its in the storage map
+			assert_eq!(
+				get_bags(),
+				vec![(10, vec![1, 42]), (20, vec![62, 61]), (1_000, vec![2, 3, 4, 52])]
+			);
+		});
+	}
+
+	#[test]
+	fn remove_node_happy_paths_works() {
+		ExtBuilder::default()
+			.add_ids(vec![(11, 10), (12, 10), (13, 1_000), (14, 1_000), (15, 2_000)])
+			.build_and_execute_no_post_check(|| {
+				// given
+				assert_eq!(
+					get_bags(),
+					vec![(10, vec![1, 11, 12]), (1_000, vec![2, 3, 4, 13, 14]), (2_000, vec![15])]
+				);
+
+				let mut bag_10 = Bag::<Runtime>::get(10).unwrap();
+				let mut bag_1000 = Bag::<Runtime>::get(1_000).unwrap();
+				let mut bag_2000 = Bag::<Runtime>::get(2_000).unwrap();
+
+				// when removing a node that is not pointing at the head or tail
+				let node_4 = Node::<Runtime>::get(&4).unwrap();
+				let node_4_pre_remove = node_4.clone();
+				bag_1000.remove_node_unchecked(&node_4);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_1000), vec![2, 3, 13, 14]);
+				assert_ok!(bag_1000.sanity_check());
+				// and the node isn't mutated when its removed
+				assert_eq!(node_4, node_4_pre_remove);
+
+				// when removing a head that is not pointing at the tail
+				let node_2 = Node::<Runtime>::get(&2).unwrap();
+				bag_1000.remove_node_unchecked(&node_2);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_1000), vec![3, 13, 14]);
+				assert_ok!(bag_1000.sanity_check());
+
+				// when removing a tail that is not pointing at the head
+				let node_14 = Node::<Runtime>::get(&14).unwrap();
+				bag_1000.remove_node_unchecked(&node_14);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_1000), vec![3, 13]);
+				assert_ok!(bag_1000.sanity_check());
+
+				// when removing a tail that is pointing at the head
+				let node_13 = Node::<Runtime>::get(&13).unwrap();
+				bag_1000.remove_node_unchecked(&node_13);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_1000), vec![3]);
+				assert_ok!(bag_1000.sanity_check());
+
+				// when removing a node that is both the head & tail
+				let node_3 = Node::<Runtime>::get(&3).unwrap();
+				bag_1000.remove_node_unchecked(&node_3);
+				bag_1000.put(); // put into storage so `get` returns the updated bag
+
+				// then
+				assert_eq!(Bag::<Runtime>::get(1_000), None);
+
+				// when removing a node that is pointing at both the head & tail
+				let node_11 = Node::<Runtime>::get(&11).unwrap();
+				bag_10.remove_node_unchecked(&node_11);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_10), vec![1, 12]);
+				assert_ok!(bag_10.sanity_check());
+
+				// when removing a head that is pointing at the tail
+				let node_1 = Node::<Runtime>::get(&1).unwrap();
+				bag_10.remove_node_unchecked(&node_1);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_10), vec![12]);
+				assert_ok!(bag_10.sanity_check());
+				// and since we updated the bag's head/tail, we need to write this storage so we
+				// can correctly `get` it again in later checks
+				bag_10.put();
+
+				// when removing a node that is pointing at the head but not the tail
+				let node_16 =
+					Node::<Runtime> { id: 16, prev: Some(15), next: None, bag_upper: 2_000 };
+				bag_2000.insert_node_unchecked(node_16);
+				let node_15 = Node::<Runtime>::get(&15).unwrap();
+				bag_2000.remove_node_unchecked(&node_15);
+
+				// then
+				assert_eq!(bag_as_ids(&bag_2000), vec![16]);
+				assert_ok!(bag_2000.sanity_check());
+			});
+	}
+}
@@ -0,0 +1,300 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{assert_ok, assert_storage_noop, traits::IntegrityTest};
+
+use super::*;
+use list::Bag;
+use mock::{test_utils::*, *};
+
+mod pallet {
+	use super::*;
+
+	#[test]
+	fn rebag_works() {
+		ExtBuilder::default().add_ids(vec![(42, 20)]).build_and_execute(|| {
+			// given
+			assert_eq!(get_bags(), vec![(10, vec![1]), (20, vec![42]), (1_000, vec![2, 3, 4])]);
+
+			// when increasing vote weight to the level of non-existent bag
+			StakingMock::set_vote_weight_of(&42, 2_000);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 42));
+
+			// then a new bag is created and the id moves into it
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (2_000, vec![42])]);
+			assert_eq!(
+				System::events().last().unwrap().event,
+				Event::bags_list(crate::Event::Rebagged(42, 20, 2_000)),
+			);
+
+			// when decreasing weight within the range of the current bag
+			StakingMock::set_vote_weight_of(&42, 1_001);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 42));
+
+			// then the id does not move
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (2_000, vec![42])]);
+
+			// when reducing weight to the level of a non-existent bag
+			StakingMock::set_vote_weight_of(&42, 30);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 42));
+
+			// then a new bag is created and the id moves into it
+			assert_eq!(get_bags(), vec![(10, vec![1]), (30, vec![42]), (1_000, vec![2, 3, 4])]);
+
+			// when increasing weight to the level of a pre-existing bag
+			StakingMock::set_vote_weight_of(&42, 500);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 42));
+
+			// then the id moves into that bag
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4, 42])]);
+		});
+	}
+
+	#[test]
+	fn rebag_head_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// when
+			StakingMock::set_vote_weight_of(&2, 10);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 2));
+
+			// then
+			assert_eq!(get_bags(), vec![(10, vec![1, 2]), (1_000, vec![3, 4])]);
+			assert_eq!(Bag::<Runtime>::get(1_000).unwrap(), Bag::new(Some(3), Some(4), 1_000));
+
+			// when
+			StakingMock::set_vote_weight_of(&3, 10);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 3));
+
+			// then
+			assert_eq!(get_bags(), vec![(10, vec![1, 2, 3]), (1_000, vec![4])]);
+			assert_eq!(Bag::<Runtime>::get(1_000).unwrap(), Bag::new(Some(4), Some(4), 1_000));
+
+			// when
+			StakingMock::set_vote_weight_of(&4, 10);
+			assert_ok!(BagsList::rebag(Origin::signed(0), 4));
+
+			// then
+			assert_eq!(get_bags(), vec![(10, vec![1, 2, 3, 4])]);
+			assert_eq!(Bag::<Runtime>::get(1_000), None);
+		});
+	}
+
+	#[test]
+	fn rebag_of_unknown_or_well_placed_id_is_noop() {
+		ExtBuilder::default().build_and_execute(|| {
+			// an id that is not in the list is ignored.
+			assert_storage_noop!(assert_ok!(BagsList::rebag(Origin::signed(0), 42)));
+
+			// an id that is already in the correct bag is ignored.
+			assert_storage_noop!(assert_ok!(BagsList::rebag(Origin::signed(0), 2)));
+		});
+	}
+
+	#[test]
+	#[should_panic = "thresholds must strictly increase"]
+	fn duplicate_in_bags_threshold_panics() {
+		const DUPE_THRESH: &[VoteWeight; 4] = &[10, 20, 30, 30];
+		BagThresholds::set(DUPE_THRESH);
+		BagsList::integrity_test();
+	}
+
+	#[test]
+	#[should_panic = "thresholds must strictly increase"]
+	fn decreasing_in_bags_threshold_panics() {
+		const DECREASING_THRESH: &[VoteWeight; 4] = &[10, 30, 20, 40];
+		BagThresholds::set(DECREASING_THRESH);
+		BagsList::integrity_test();
+	}
+}
+
+mod sorted_list_provider {
+	use super::*;
+
+	#[test]
+	fn iter_works() {
+		ExtBuilder::default().add_ids(vec![(6, 2_000), (7, 2_000)]).build_and_execute(|| {
+			let expected = vec![6, 7, 2, 3, 4, 1];
+			for (i, id) in <BagsList as SortedListProvider<AccountId>>::iter().enumerate() {
+				assert_eq!(id, expected[i])
+			}
+		});
+	}
+
+	#[test]
+	fn count_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			assert_eq!(<BagsList as SortedListProvider<AccountId>>::count(), 4);
+
+			// when inserting
+			assert_ok!(BagsList::on_insert(201, 0));
+			// then the count goes up
+			assert_eq!(<BagsList as SortedListProvider<AccountId>>::count(), 5);
+
+			// when removing
+			BagsList::on_remove(&201);
+			// then the count goes down
+			assert_eq!(<BagsList as SortedListProvider<AccountId>>::count(), 4);
+
+			// when updating
+			BagsList::on_update(&201, VoteWeight::MAX);
+			// then the count stays the same
+			assert_eq!(<BagsList as SortedListProvider<AccountId>>::count(), 4);
+		});
+	}
+
+	#[test]
+	fn on_insert_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// when
+			assert_ok!(BagsList::on_insert(6, 1_000));
+
+			// then the bags
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4, 6])]);
+			// and list correctly include the new id,
+			assert_eq!(
+				<BagsList as SortedListProvider<AccountId>>::iter().collect::<Vec<_>>(),
+				vec![2, 3, 4, 6, 1]
+			);
+			// and the count is incremented.
+			assert_eq!(<BagsList as SortedListProvider<AccountId>>::count(), 5);
+
+			// inserting a duplicate id is an error and does not modify the list.
+			assert_storage_noop!(assert_eq!(
+				BagsList::on_insert(3, 20).unwrap_err(),
+				Error::Duplicate
+			));
+		});
+	}
+
+	#[test]
+	fn on_update_works() {
+		ExtBuilder::default().add_ids(vec![(42, 20)]).build_and_execute(|| {
+			// given
+			assert_eq!(get_bags(), vec![(10, vec![1]), (20, vec![42]), (1_000, vec![2, 3, 4])]);
+			assert_eq!(BagsList::count(), 5);
+
+			// when increasing weight to the level of non-existent bag
+			BagsList::on_update(&42, 2_000);
+
+			// then the bag is created with the id in it,
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (2_000, vec![42])]);
+			// and the id position is updated in the list.
+			assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![42, 2, 3, 4, 1]);
+
+			// when increasing weight to the level of a pre-existing bag
+			BagsList::on_update(&42, VoteWeight::MAX);
+
+			// then the id is moved to the implicit `VoteWeight::MAX` bag.
+			assert_eq!(
+				get_bags(),
+				vec![(10, vec![1]), (1_000, vec![2, 3, 4]), (VoteWeight::MAX, vec![42])]
+			);
+			assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![42, 2, 3, 4, 1]);
+
+			// when decreasing weight within the range of the current bag
+			BagsList::on_update(&42, VoteWeight::MAX - 1);
+
+			// then the id does not change position
+			assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![42, 2, 3, 4, 1]);
+
+			// when decreasing weight to the level of a pre-existing bag
+			BagsList::on_update(&42, 1_000);
+
+			// then the id is moved to the tail of the pre-existing bag,
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![2, 3, 4, 42])]);
+			assert_eq!(BagsList::iter().collect::<Vec<_>>(), vec![2, 3, 4, 42, 1]);
+
+			// and the count stays the same.
+			assert_eq!(BagsList::count(), 5);
+		});
+	}
+
+	#[test]
+	fn on_remove_works() {
+		let ensure_left = |id, counter| {
+			assert!(!ListNodes::<Runtime>::contains_key(id));
+			assert_eq!(BagsList::count(), counter);
+			assert_eq!(CounterForListNodes::<Runtime>::get(), counter);
+			assert_eq!(ListNodes::<Runtime>::iter().count() as u32, counter);
+		};
+
+		ExtBuilder::default().build_and_execute(|| {
+			// it is a noop removing a non-existent id
+			assert!(!ListNodes::<Runtime>::contains_key(42));
+			assert_storage_noop!(BagsList::on_remove(&42));
+
+			// when removing a node from a bag with multiple nodes
+			BagsList::on_remove(&2);
+
+			// then
+			assert_eq!(get_list_as_ids(), vec![3, 4, 1]);
+			assert_eq!(get_bags(), vec![(10, vec![1]), (1_000, vec![3, 4])]);
+			ensure_left(2, 3);
+
+			// when removing a node from a bag with only one node
+			BagsList::on_remove(&1);
+
+			// then
+			assert_eq!(get_list_as_ids(), vec![3, 4]);
+			assert_eq!(get_bags(), vec![(1_000, vec![3, 4])]);
+			ensure_left(1, 2);
+
+			// when removing all remaining ids
+			BagsList::on_remove(&4);
+			assert_eq!(get_list_as_ids(), vec![3]);
+			ensure_left(4, 1);
+			BagsList::on_remove(&3);
+
+			// then the storage is completely cleaned up
+			assert_eq!(get_list_as_ids(), Vec::<AccountId>::new());
+			ensure_left(3, 0);
+		});
+	}
+
+	#[test]
+	fn contains_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert!(GENESIS_IDS.iter().all(|(id, _)| BagsList::contains(id)));
+
+			let non_existent_ids = vec![&42, &666, &13];
+			assert!(non_existent_ids.iter().all(|id| !BagsList::contains(id)));
+		})
+	}
+
+	#[test]
+	fn regenerate_and_clear_work() {
+		ExtBuilder::default().build_and_execute(|| {
+			// when regenerating from a different set of ids
+			StakingMock::set_vote_weight_of(&5, 30);
+			let weight_of = Box::new(StakingMock::vote_weight);
+			assert_eq!(BagsList::regenerate(vec![1, 2, 5], weight_of), 3);
+
+			// then only those ids are in the list
+			assert_eq!(get_bags(), vec![(10, vec![1]), (30, vec![5]), (1_000, vec![2])]);
+			assert_eq!(BagsList::count(), 3);
+
+			// when clearing
+			<BagsList as SortedListProvider<AccountId>>::clear();
+
+			// then nothing is left
+			assert_eq!(get_bags(), vec![]);
+			assert_eq!(BagsList::count(), 0);
+			assert_eq!(ListBags::<Runtime>::iter().count(), 0);
+		});
+	}
+}
@@ -79,6 +79,9 @@
 //! - [`Imbalance`](frame_support::traits::Imbalance): Functions for handling
 //! imbalances between total issuance in the system and account balances. Must be used when a function
 //! creates new funds (e.g. a reward) or destroys some funds (e.g. a system fee).
+//! - [`fungible`](frame_support::traits::fungible): The `Inspect`, `Mutate`, `Transfer`,
+//! `InspectHold` and `MutateHold` traits, which new pallets should prefer over `Currency`. Funds
+//! on hold are a part of the reserved balance and are tracked per `Config::HoldReason`.
 //!
 //! ## Interface
 //!
@@ -165,6 +168,7 @@ use frame_support::{
 		WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
 		Imbalance, SignedImbalance, ReservableCurrency, Get, ExistenceRequirement::KeepAlive,
 		ExistenceRequirement::AllowDeath, BalanceStatus as Status,
+		tokens::{fungible, DepositConsequence, WithdrawConsequence},
 	}
 };
 #[cfg(feature = "std")]
@@ -213,6 +217,14 @@ pub mod pallet {
 		/// The maximum number of locks that should exist on an account.
 		/// Not strictly enforced, but used for weight estimation.
 		type MaxLocks: Get<u32>;
+
+		/// The reason for which funds are placed on hold through [`fungible::MutateHold`].
+		///
+		/// This is usually the `HoldReason` aggregated by `construct_runtime`.
+		type HoldReason: Parameter + Member + Copy;
+
+		/// The maximum number of reasons for which funds can be on hold on a single account.
+		type MaxHolds: Get<u32>;
 	}
 
 	#[pallet::pallet]
@@ -413,6 +425,8 @@ pub mod pallet {
 		ExistingVestingSchedule,
 		/// Beneficiary account must pre-exist
 		DeadAccount,
+		/// Funds are already on hold for `MaxHolds` other reasons
+		TooManyHolds,
 	}
 
 	/// The total units issued in the system.
@@ -444,6 +458,18 @@ pub mod pallet {
 		ValueQuery
 	>;
 
+	/// The funds on hold on some account balances, per reason.
+	///
+	/// The funds on hold are a part of the reserved balance of the account.
+	#[pallet::storage]
+	pub type Holds<T: Config<I>, I: 'static = ()> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<IdAmount<T::HoldReason, T::Balance>, T::MaxHolds>,
+		ValueQuery
+	>;
+
 	/// Storage version of the pallet.
 	///
 	/// This is set to v2.0.0 for new networks.
@@ -560,6 +586,15 @@ pub struct BalanceLock<Balance> {
 	pub reasons: Reasons,
 }
 
+/// An amount of funds that is on hold for the reason `id`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct IdAmount<Id, Balance> {
+	/// The reason for which the funds are on hold.
+	pub id: Id,
+	/// The amount that is on hold.
+	pub amount: Balance,
+}
+
 /// All balance information for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
 pub struct AccountData<Balance> {
@@ -1463,3 +1498,320 @@ where
 		Self::update_locks(who, &locks[..]);
 	}
 }
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// The consequence of depositing `amount` into the account `who`, currently described by
+	/// `account`. If `mint` is `true`, then the deposit also increases the total issuance.
+	fn deposit_consequence(
+		_who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+		mint: bool,
+	) -> DepositConsequence {
+		if amount.is_zero() { return DepositConsequence::Success }
+
+		if mint && TotalIssuance::<T, I>::get().checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+
+		let new_total_balance = match account.total().checked_add(&amount) {
+			Some(x) => x,
+			None => return DepositConsequence::Overflow,
+		};
+
+		if new_total_balance < T::ExistentialDeposit::get() {
+			return DepositConsequence::BelowMinimum
+		}
+
+		// NOTE: We assume that we are a provider, so don't need to do any checks in the
+		// case of account creation.
+
+		DepositConsequence::Success
+	}
+
+	/// The consequence of withdrawing `amount` from the free balance of the account `who`,
+	/// currently described by `account`.
+	fn withdraw_consequence(
+		who: &T::AccountId,
+		amount: T::Balance,
+		account: &AccountData<T::Balance>,
+	) -> WithdrawConsequence<T::Balance> {
+		if amount.is_zero() { return WithdrawConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+
+		let new_total_balance = match account.total().checked_sub(&amount) {
+			Some(x) => x,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Provider restriction - total account balance cannot be reduced to zero if it cannot
+		// sustain the loss of a provider reference.
+		// NOTE: This assumes that the pallet is a provider (which is true). If this ever changes,
+		// then this will need to adapt accordingly.
+		let ed = T::ExistentialDeposit::get();
+		let success = if new_total_balance < ed {
+			if system::Pallet::<T>::is_provider_required(who) {
+				return WithdrawConsequence::WouldDie
+			}
+			WithdrawConsequence::ReducedToZero(new_total_balance)
+		} else {
+			WithdrawConsequence::Success
+		};
+
+		// Enough free funds to have them be reduced.
+		let new_free_balance = match account.free.checked_sub(&amount) {
+			Some(b) => b,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Eventual free funds must be no less than the frozen balance.
+		if new_free_balance < account.frozen(Reasons::All) {
+			return WithdrawConsequence::Frozen
+		}
+
+		success
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Inspect<T::AccountId> for Pallet<T, I> {
+	type Balance = T::Balance;
+
+	fn total_issuance() -> Self::Balance {
+		TotalIssuance::<T, I>::get()
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		T::ExistentialDeposit::get()
+	}
+
+	fn balance(who: &T::AccountId) -> Self::Balance {
+		Self::account(who).total()
+	}
+
+	fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+		let a = Self::account(who);
+		// Liquid balance is what is neither reserved nor locked/frozen.
+		let liquid = a.free.saturating_sub(a.frozen(Reasons::All));
+		if !keep_alive && !system::Pallet::<T>::is_provider_required(who) {
+			liquid
+		} else {
+			// `must_remain_to_exist` is the part of liquid balance which must remain to keep total
+			// over ED.
+			let must_remain_to_exist = T::ExistentialDeposit::get()
+				.saturating_sub(a.total().saturating_sub(liquid));
+			liquid.saturating_sub(must_remain_to_exist)
+		}
+	}
+
+	fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
+		Self::deposit_consequence(who, amount, &Self::account(who), true)
+	}
+
+	fn can_withdraw(who: &T::AccountId, amount: Self::Balance) -> WithdrawConsequence<Self::Balance> {
+		Self::withdraw_consequence(who, amount, &Self::account(who))
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
+	fn mint_into(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+
+		Self::try_mutate_account(who, |account, _is_new| -> DispatchResult {
+			Self::deposit_consequence(who, amount, &account, true).into_result()?;
+			account.free = account.free.checked_add(&amount).ok_or(Error::<T, I>::Overflow)?;
+			Ok(())
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t = t.saturating_add(amount));
+
+		Ok(())
+	}
+
+	/// Burn `amount` from the free balance of `who`.
+	///
+	/// NOTE: if the account is reduced below the existential deposit, the remaining dust is
+	/// handled by `DustRemoval` as for any other account reaping, and is not included in the
+	/// returned amount.
+	fn burn_from(who: &T::AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+		if amount.is_zero() { return Ok(Self::Balance::zero()) }
+
+		let actual = Self::try_mutate_account(who, |account, _is_new| -> Result<T::Balance, DispatchError> {
+			Self::withdraw_consequence(who, amount, &account).into_result()?;
+			// Guaranteed not to underflow by `withdraw_consequence`.
+			account.free -= amount;
+			Ok(amount)
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t = t.saturating_sub(actual));
+
+		Ok(actual)
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::Transfer<T::AccountId> for Pallet<T, I> where
+	T::Balance: MaybeSerializeDeserialize + Debug
+{
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let existence_requirement = if keep_alive { KeepAlive } else { AllowDeath };
+		<Self as Currency<T::AccountId>>::transfer(source, dest, amount, existence_requirement)
+			.map(|_| amount)
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Change the amount on hold for `reason` on the account of `who` to `f` of it.
+	///
+	/// Only the bookkeeping of the holds is changed, not the reserved balance of the account.
+	fn mutate_hold(
+		reason: &T::HoldReason,
+		who: &T::AccountId,
+		f: impl FnOnce(T::Balance) -> T::Balance,
+	) -> DispatchResult {
+		Holds::<T, I>::try_mutate_exists(who, |maybe_holds| {
+			let mut holds = maybe_holds.take().unwrap_or_default();
+			match holds.iter().position(|hold| hold.id == *reason) {
+				Some(index) => {
+					holds[index].amount = f(holds[index].amount);
+					if holds[index].amount.is_zero() {
+						holds.remove(index);
+					}
+				},
+				None => {
+					let amount = f(Zero::zero());
+					if !amount.is_zero() {
+						holds.try_push(IdAmount { id: *reason, amount })
+							.map_err(|_| Error::<T, I>::TooManyHolds)?;
+					}
+				},
+			}
+			*maybe_holds = if holds.is_empty() { None } else { Some(holds) };
+			Ok(())
+		})
+	}
+
+	/// Whether funds can be placed on hold for `reason` on the account of `who` without
+	/// exceeding `MaxHolds`.
+	fn can_add_hold(reason: &T::HoldReason, who: &T::AccountId) -> bool {
+		let holds = Holds::<T, I>::get(who);
+		holds.iter().any(|hold| hold.id == *reason) || holds.len() < T::MaxHolds::get() as usize
+	}
+}
+
+/// Funds on hold are a part of the reserved balance of an account which is tracked per reason.
+///
+/// Funds that are reserved or unreserved through [`ReservableCurrency`] are not accounted to any
+/// reason. The funds that can be released for a reason are therefore limited by both, the funds
+/// on hold for that reason and the reserved balance.
+impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
+	type Reason = T::HoldReason;
+
+	fn balance_on_hold(reason: &Self::Reason, who: &T::AccountId) -> T::Balance {
+		Holds::<T, I>::get(who).iter()
+			.find(|hold| hold.id == *reason)
+			.map_or_else(Zero::zero, |hold| hold.amount)
+	}
+
+	fn total_balance_on_hold(who: &T::AccountId) -> T::Balance {
+		Holds::<T, I>::get(who).iter()
+			.fold(Zero::zero(), |total: T::Balance, hold| total.saturating_add(hold.amount))
+	}
+
+	fn can_hold(reason: &Self::Reason, who: &T::AccountId, amount: T::Balance) -> bool {
+		if !Self::can_add_hold(reason, who) { return false }
+		let a = Self::account(who);
+		let min_balance = T::ExistentialDeposit::get().max(a.frozen(Reasons::All));
+		if a.reserved.checked_add(&amount).is_none() { return false }
+		// We require it to be min_balance + amount to ensure that the full reserved funds may be
+		// slashed without compromising locked funds or destroying the account.
+		let required_free = match min_balance.checked_add(&amount) {
+			Some(x) => x,
+			None => return false,
+		};
+		a.free >= required_free
+	}
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> where
+	T::Balance: MaybeSerializeDeserialize + Debug
+{
+	fn hold(reason: &Self::Reason, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+
+		ensure!(Self::can_add_hold(reason, who), Error::<T, I>::TooManyHolds);
+		ensure!(
+			<Self as fungible::InspectHold<_>>::can_hold(reason, who, amount),
+			Error::<T, I>::InsufficientBalance,
+		);
+		Self::mutate_account(who, |a| {
+			a.free -= amount;
+			a.reserved += amount;
+		})?;
+		Self::mutate_hold(reason, who, |held| held.saturating_add(amount))?;
+
+		Self::deposit_event(Event::Reserved(who.clone(), amount));
+		Ok(())
+	}
+
+	fn release(
+		reason: &Self::Reason,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+	) -> Result<T::Balance, DispatchError> {
+		if amount.is_zero() { return Ok(amount) }
+
+		let held = <Self as fungible::InspectHold<_>>::balance_on_hold(reason, who);
+		let actual = Self::try_mutate_account(who, |a, _| -> Result<T::Balance, DispatchError> {
+			let new_free = a.free.saturating_add(amount.min(held).min(a.reserved));
+			let actual = new_free - a.free;
+			ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
+			// ^^^ Guaranteed to be <= amount, <= held and <= a.reserved
+			a.free = new_free;
+			a.reserved = a.reserved.saturating_sub(actual);
+			Ok(actual)
+		})?;
+		Self::mutate_hold(reason, who, |held| held.saturating_sub(actual))?;
+
+		Self::deposit_event(Event::Unreserved(who.clone(), actual));
+		Ok(actual)
+	}
+
+	/// Transfer held funds from `source` to `dest`.
+	///
+	/// NOTE: as with `repatriate_reserved`, the destination account must already exist, even if
+	/// `on_hold` is `false`.
+	fn transfer_held(
+		reason: &Self::Reason,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		let held = <Self as fungible::InspectHold<_>>::balance_on_hold(reason, source)
+			.min(Self::account(source).reserved);
+		ensure!(best_effort || held >= amount, Error::<T, I>::InsufficientBalance);
+		ensure!(!on_hold || Self::can_add_hold(reason, dest), Error::<T, I>::TooManyHolds);
+
+		let amount = amount.min(held);
+		let status = if on_hold { Status::Reserved } else { Status::Free };
+		let remaining = <Self as ReservableCurrency<T::AccountId>>::repatriate_reserved(
+			source,
+			dest,
+			amount,
+			status,
+		)?;
+		let actual = amount - remaining;
+		Self::mutate_hold(reason, source, |held| held.saturating_sub(actual))?;
+		if on_hold {
+			Self::mutate_hold(reason, dest, |held| held.saturating_add(actual))?;
+		}
+		Ok(actual)
+	}
+}
@@ -19,6 +19,17 @@
 
 #![cfg(test)]
 
+use codec::{Encode, Decode};
+use sp_runtime::RuntimeDebug;
+
+/// The reasons for which funds are placed on hold in the tests.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum TestHoldReason {
+	Deposit,
+	Staking,
+	Vote,
+}
+
 #[macro_export]
 macro_rules! decl_tests {
 	($test:ty, $ext_builder:ty, $existential_deposit:expr) => {
@@ -29,9 +40,11 @@ macro_rules! decl_tests {
 			assert_noop, assert_storage_noop, assert_ok, assert_err, StorageValue,
 			traits::{
 				LockableCurrency, LockIdentifier, WithdrawReasons,
-				Currency, ReservableCurrency, ExistenceRequirement::AllowDeath
+				Currency, ReservableCurrency, ExistenceRequirement::AllowDeath,
+				fungible::{Inspect, Mutate, InspectHold, MutateHold},
 			}
 		};
+		use crate::tests::TestHoldReason::{Deposit, Staking, Vote};
 		use pallet_transaction_payment::{ChargeTransactionPayment, Multiplier};
 		use frame_system::RawOrigin;
 
@@ -974,5 +987,102 @@ macro_rules! decl_tests {
 					assert_eq!(Balances::total_balance(&2), 100);
 				});
 		}
+
+		#[test]
+		fn fungible_mint_and_burn_should_work() {
+			<$ext_builder>::default()
+				.existential_deposit(10)
+				.build()
+				.execute_with(|| {
+					assert_eq!(<Balances as Inspect<_>>::can_deposit(&1, 5), DepositConsequence::BelowMinimum);
+					assert_noop!(
+						<Balances as Mutate<_>>::mint_into(&1, 5),
+						DispatchError::Other("BelowMinimum"),
+					);
+					assert_ok!(<Balances as Mutate<_>>::mint_into(&1, 100));
+					assert_eq!(<Balances as Inspect<_>>::balance(&1), 100);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), 100);
+
+					assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, true), 90);
+					assert_eq!(<Balances as Inspect<_>>::reducible_balance(&1, false), 100);
+					assert_eq!(
+						<Balances as Inspect<_>>::can_withdraw(&1, 95),
+						WithdrawConsequence::ReducedToZero(5),
+					);
+
+					assert_eq!(<Balances as Mutate<_>>::burn_from(&1, 40), Ok(40));
+					assert_eq!(<Balances as Inspect<_>>::balance(&1), 60);
+					assert_eq!(<Balances as Inspect<_>>::total_issuance(), 60);
+				});
+		}
+
+		#[test]
+		fn fungible_holds_are_tracked_per_reason() {
+			<$ext_builder>::default()
+				.existential_deposit(1)
+				.monied(true)
+				.build()
+				.execute_with(|| {
+					assert!(<Balances as InspectHold<_>>::can_hold(&Deposit, &1, 9));
+					assert!(!<Balances as InspectHold<_>>::can_hold(&Deposit, &1, 10));
+					assert_ok!(<Balances as MutateHold<_>>::hold(&Deposit, &1, 4));
+					assert_ok!(<Balances as MutateHold<_>>::hold(&Staking, &1, 2));
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&Deposit, &1), 4);
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&Staking, &1), 2);
+					assert_eq!(<Balances as InspectHold<_>>::total_balance_on_hold(&1), 6);
+					assert_eq!(Balances::free_balance(1), 4);
+					assert_eq!(Balances::reserved_balance(1), 6);
+
+					// Only `MaxHolds` reasons can hold funds at the same time.
+					assert!(!<Balances as InspectHold<_>>::can_hold(&Vote, &1, 1));
+					assert_noop!(
+						<Balances as MutateHold<_>>::hold(&Vote, &1, 1),
+						Error::<$test, _>::TooManyHolds,
+					);
+
+					// Funds can only be released for the reason they are held for.
+					assert_noop!(
+						<Balances as MutateHold<_>>::release(&Staking, &1, 3, false),
+						Error::<$test, _>::InsufficientBalance,
+					);
+					assert_eq!(<Balances as MutateHold<_>>::release(&Staking, &1, 3, true), Ok(2));
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&Staking, &1), 0);
+					assert_eq!(Holds::<$test>::get(1).len(), 1);
+					assert_eq!(Balances::free_balance(1), 6);
+
+					// Held funds keep their reason when transferred on hold.
+					assert_eq!(
+						<Balances as MutateHold<_>>::transfer_held(&Deposit, &1, &2, 3, false, true),
+						Ok(3),
+					);
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&Deposit, &1), 1);
+					assert_eq!(<Balances as InspectHold<_>>::balance_on_hold(&Deposit, &2), 3);
+					assert_eq!(Balances::reserved_balance(2), 3);
+
+					assert_eq!(<Balances as MutateHold<_>>::release(&Deposit, &1, 8, true), Ok(1));
+					assert!(!Holds::<$test>::contains_key(1));
+					assert_eq!(Balances::free_balance(1), 10 - 6 + 2 + 1);
+				});
+		}
+
+		#[test]
+		fn fungible_release_is_limited_by_reserved_balance() {
+			<$ext_builder>::default()
+				.existential_deposit(1)
+				.monied(true)
+				.build()
+				.execute_with(|| {
+					assert_ok!(<Balances as MutateHold<_>>::hold(&Deposit, &1, 4));
+					// Unreserving through `ReservableCurrency` is not accounted to any reason.
+					assert_eq!(Balances::unreserve(&1, 3), 0);
+
+					assert_noop!(
+						<Balances as MutateHold<_>>::release(&Deposit, &1, 4, false),
+						Error::<$test, _>::InsufficientBalance,
+					);
+					assert_eq!(<Balances as MutateHold<_>>::release(&Deposit, &1, 4, true), Ok(1));
+					assert_eq!(Balances::reserved_balance(1), 0);
+				});
+		}
 	}
 }
@@ -85,7 +85,9 @@ impl pallet_transaction_payment::Config for Test {
 	type WeightToFee = IdentityFee<u64>;
 	type FeeMultiplierUpdate = ();
 }
-
+parameter_types! {
+	pub const MaxHolds: u32 = 2;
+}
 impl Config for Test {
 	type Balance = u64;
 	type DustRemoval = ();
@@ -93,6 +95,8 @@ impl Config for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = frame_system::Pallet<Test>;
 	type MaxLocks = ();
+	type HoldReason = crate::tests::TestHoldReason;
+	type MaxHolds = MaxHolds;
 	type WeightInfo = ();
 }
 
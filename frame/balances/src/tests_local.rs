@@ -89,6 +89,7 @@ impl pallet_transaction_payment::Config for Test {
 }
 parameter_types! {
 	pub const MaxLocks: u32 = 50;
+	pub const MaxHolds: u32 = 2;
 }
 impl Config for Test {
 	type Balance = u64;
@@ -102,6 +103,8 @@ impl Config for Test {
 		super::AccountData<u64>,
 	>;
 	type MaxLocks = MaxLocks;
+	type HoldReason = crate::tests::TestHoldReason;
+	type MaxHolds = MaxHolds;
 	type WeightInfo = ();
 }
 
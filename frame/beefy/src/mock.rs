@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+#![cfg(test)]
+
+use crate as pallet_beefy;
+use frame_support::{parameter_types, traits::GenesisBuild};
+use sp_beefy::crypto::AuthorityId as BeefyId;
+use sp_core::{ecdsa, Pair, H256};
+use sp_runtime::{testing::Header, traits::IdentityLookup};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Beefy: pallet_beefy::{Module, Call, Storage, Config<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+impl pallet_beefy::Config for Test {
+	type BeefyId = BeefyId;
+}
+
+/// Derive a deterministic BEEFY key for the given index.
+pub fn mock_beefy_id(id: u8) -> BeefyId {
+	ecdsa::Pair::from_string(&format!("//{}", id), None)
+		.expect("static values are valid; qed")
+		.public()
+		.into()
+}
+
+pub fn mock_authorities(ids: Vec<u8>) -> Vec<(u64, BeefyId)> {
+	ids.into_iter().map(|id| (id as u64, mock_beefy_id(id))).collect()
+}
+
+pub fn new_test_ext(ids: Vec<u8>) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_beefy::GenesisConfig::<Test> {
+		authorities: mock_authorities(ids).into_iter().map(|(_, id)| id).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
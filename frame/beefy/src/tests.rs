@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the BEEFY pallet.
+
+#![cfg(test)]
+
+use crate::{
+	DepositMmrRootDigest,
+	mock::{Beefy, Test, System, mock_authorities, mock_beefy_id, new_test_ext},
+};
+use codec::Encode;
+use frame_support::traits::OneSessionHandler;
+use pallet_mmr_primitives::OnNewRoot;
+use sp_beefy::{ConsensusLog, ValidatorSet, BEEFY_ENGINE_ID};
+use sp_runtime::{generic::DigestItem, traits::IsMember};
+
+fn beefy_log(log: ConsensusLog<sp_beefy::crypto::AuthorityId>) -> DigestItem<sp_core::H256> {
+	DigestItem::Consensus(BEEFY_ENGINE_ID, log.encode())
+}
+
+fn rotate_session(validators: Vec<u8>, queued: Vec<u8>) {
+	let validators = mock_authorities(validators);
+	let queued = mock_authorities(queued);
+	<Beefy as OneSessionHandler<u64>>::on_new_session(
+		true,
+		validators.iter().map(|(a, k)| (a, k.clone())),
+		queued.iter().map(|(a, k)| (a, k.clone())),
+	);
+}
+
+#[test]
+fn genesis_session_initializes_authorities() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		let want = vec![mock_beefy_id(1), mock_beefy_id(2), mock_beefy_id(3), mock_beefy_id(4)];
+
+		assert_eq!(Beefy::authorities(), want);
+		assert_eq!(Beefy::next_authorities(), want);
+		assert_eq!(Beefy::validator_set_id(), 0);
+	});
+}
+
+#[test]
+fn session_change_updates_authorities() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		System::initialize(&1, &Default::default(), &Default::default(), Default::default());
+
+		rotate_session(vec![3, 4], vec![4]);
+
+		assert_eq!(Beefy::authorities(), vec![mock_beefy_id(3), mock_beefy_id(4)]);
+		assert_eq!(Beefy::next_authorities(), vec![mock_beefy_id(4)]);
+		assert_eq!(Beefy::validator_set_id(), 1);
+
+		let want = beefy_log(ConsensusLog::AuthoritiesChange(ValidatorSet {
+			validators: vec![mock_beefy_id(3), mock_beefy_id(4)],
+			id: 1,
+		}));
+		assert_eq!(System::digest().logs, vec![want]);
+	});
+}
+
+#[test]
+fn unchanged_validators_do_not_trigger_a_set_change() {
+	new_test_ext(vec![1, 2]).execute_with(|| {
+		System::initialize(&1, &Default::default(), &Default::default(), Default::default());
+
+		rotate_session(vec![1, 2], vec![2, 3]);
+
+		assert_eq!(Beefy::validator_set_id(), 0);
+		assert_eq!(Beefy::next_authorities(), vec![mock_beefy_id(2), mock_beefy_id(3)]);
+		assert!(System::digest().logs.is_empty());
+	});
+}
+
+#[test]
+fn validator_set_at_genesis() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		let vs = Beefy::validator_set();
+
+		assert_eq!(vs.id, 0);
+		assert_eq!(vs.validators.len(), 4);
+		assert!(Beefy::is_member(&mock_beefy_id(2)));
+		assert!(!Beefy::is_member(&mock_beefy_id(5)));
+	});
+}
+
+#[test]
+fn on_disabled_deposits_log() {
+	new_test_ext(vec![1, 2, 3, 4]).execute_with(|| {
+		System::initialize(&1, &Default::default(), &Default::default(), Default::default());
+
+		<Beefy as OneSessionHandler<u64>>::on_disabled(2);
+
+		assert_eq!(System::digest().logs, vec![beefy_log(ConsensusLog::OnDisabled(2))]);
+	});
+}
+
+#[test]
+fn new_mmr_root_deposits_log() {
+	new_test_ext(vec![1, 2]).execute_with(|| {
+		System::initialize(&1, &Default::default(), &Default::default(), Default::default());
+
+		let root = sp_core::H256::repeat_byte(0x42);
+		DepositMmrRootDigest::<Test>::on_new_root(&root);
+
+		assert_eq!(System::digest().logs, vec![beefy_log(ConsensusLog::MmrRoot(root))]);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Authorities are already initialized!")]
+fn authorities_can_only_be_initialized_once() {
+	new_test_ext(vec![1, 2]).execute_with(|| {
+		let authorities = mock_authorities(vec![3]);
+		<Beefy as OneSessionHandler<u64>>::on_genesis_session(
+			authorities.iter().map(|(a, k)| (a, k.clone())),
+		);
+	});
+}
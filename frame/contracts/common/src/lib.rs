@@ -24,6 +24,10 @@ use codec::{Decode, Encode};
 use sp_runtime::{DispatchError, RuntimeDebug};
 use sp_std::prelude::*;
 
+mod profiling;
+
+pub use profiling::*;
+
 /// Result type of a `bare_call` call.
 ///
 /// The result of a contract execution along with a gas consumed.
@@ -33,6 +37,15 @@ pub struct ContractExecResult {
 	pub gas_consumed: u64,
 }
 
+/// Result type of a `bare_instantiate` call.
+///
+/// The result of a contract instantiation along with a gas consumed.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractInstantiateResult<AccountId> {
+	pub exec_result: Result<InstantiateReturnValue<AccountId>, ExecError>,
+	pub gas_consumed: u64,
+}
+
 /// Result type of a `get_storage` call.
 pub type GetStorageResult = Result<Option<Vec<u8>>, ContractAccessError>;
 
@@ -68,6 +81,19 @@ bitflags! {
 	}
 }
 
+bitflags! {
+	/// Flags used by a contract to customize the behaviour of a call into another contract.
+	#[derive(Encode, Decode)]
+	pub struct CallFlags: u32 {
+		/// Execute the callee as a read-only (static) call.
+		///
+		/// Any attempt to mutate state (writing storage, transferring value, emitting
+		/// events, instantiating, terminating or restoring contracts) within the callee
+		/// or any contract it calls in turn fails with an error.
+		const STATIC = 0x0000_0001;
+	}
+}
+
 /// Output of a contract call or instantiation which ran to completion.
 #[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
 pub struct ExecReturnValue {
@@ -84,6 +110,24 @@ impl ExecReturnValue {
 	}
 }
 
+/// Output of a successful contract instantiation.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct InstantiateReturnValue<AccountId> {
+	/// The output of the called constructor.
+	pub result: ExecReturnValue,
+	/// The account id of the new contract.
+	pub account_id: AccountId,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Vec<u8>),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
 /// Origin of the error.
 ///
 /// Call or instantiate both called into other contracts and pass through errors happening
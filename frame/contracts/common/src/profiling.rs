@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Host side profiling of contract executions.
+//!
+//! The runtime reports what the contracts module is doing through the
+//! [`contracts_profiling`] runtime interface. On the node side the reported events are
+//! forwarded to the [`Profiler`] that is registered as [`ProfilingExt`] in the externalities.
+//! When no such extension is registered, the events are silently dropped.
+//!
+//! The runtime interface is a host function that must be provided by every node executing the
+//! runtime. It is therefore only available with the non-default `profiling` feature. Without
+//! it [`contracts_profiling::record`] does nothing and no host function is imported.
+
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+use sp_runtime_interface::pass_by::PassByCodec;
+#[cfg(feature = "profiling")]
+use sp_runtime_interface::runtime_interface;
+
+#[cfg(all(feature = "std", feature = "profiling"))]
+use sp_externalities::ExternalitiesExt;
+
+/// An event reported by the contracts module to the host.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, PassByCodec)]
+pub enum ProfilingEvent {
+	/// A contract is about to be called.
+	Call,
+	/// A contract is about to be instantiated.
+	Instantiate,
+	/// A top level contract execution finished, consuming the given amount of gas.
+	GasConsumed(u64),
+	/// Code was loaded from `CodeStorage` and was already instrumented with the
+	/// current schedule.
+	CodeCacheHit,
+	/// Code was loaded from `CodeStorage` but needed to be re-instrumented.
+	CodeCacheMiss,
+}
+
+/// Something that receives the [`ProfilingEvent`]s reported by the runtime.
+#[cfg(feature = "std")]
+pub trait Profiler: Send + Sync {
+	/// Record the given event.
+	fn record(&self, event: ProfilingEvent);
+}
+
+#[cfg(feature = "std")]
+sp_externalities::decl_extension! {
+	/// The contracts profiling extension to register/retrieve from the externalities.
+	pub struct ProfilingExt(std::sync::Arc<dyn Profiler>);
+}
+
+#[cfg(feature = "std")]
+impl ProfilingExt {
+	/// Create a new instance of `Self`.
+	pub fn new(profiler: std::sync::Arc<dyn Profiler>) -> Self {
+		Self(profiler)
+	}
+}
+
+/// Interface that allows the contracts module to report its execution statistics to the host.
+#[cfg(feature = "profiling")]
+#[runtime_interface]
+pub trait ContractsProfiling {
+	/// Report the given event to the registered [`Profiler`], if any.
+	///
+	/// This has no influence on the execution and thus can be called from consensus
+	/// critical code.
+	fn record(&mut self, event: ProfilingEvent) {
+		if let Some(ext) = self.extension::<ProfilingExt>() {
+			ext.record(event);
+		}
+	}
+}
+
+/// Stand-in for the runtime interface when the `profiling` feature is disabled.
+#[cfg(not(feature = "profiling"))]
+pub mod contracts_profiling {
+	use super::ProfilingEvent;
+
+	/// Does nothing. Enable the `profiling` feature to report the event to the host.
+	pub fn record(_event: ProfilingEvent) {}
+}
@@ -0,0 +1,41 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feeds arbitrary byte blobs into the code preparation of `pallet-contracts`.
+//!
+//! Most inputs will be rejected as invalid wasm early on. This target is mainly about making
+//! sure that the parser and validator never panic.
+//!
+//! # Running
+//! Running this fuzzer can be done with `cargo hfuzz run prepare_raw`. `honggfuzz` CLI options
+//! can be used by setting `HFUZZ_RUN_ARGS`, such as `-n 4` to use 4 threads.
+//!
+//! # Debugging a panic
+//! Once a panic is found, it can be debugged with
+//! `cargo hfuzz run-debug prepare_raw hfuzz_workspace/prepare_raw/*.fuzz`.
+
+use honggfuzz::fuzz;
+
+mod mock;
+
+fn main() {
+	loop {
+		fuzz!(|data: &[u8]| {
+			mock::prepare_and_check(data.to_vec());
+		});
+	}
+}
@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Feeds structurally valid contract modules into the code preparation of `pallet-contracts`.
+//!
+//! The fuzzer input is decoded as the instruction sequence of the `call` function of a module
+//! which otherwise satisfies all of the structural requirements of a contract. This makes sure
+//! that the instrumentation of the code is exercised and not only the parser.
+//!
+//! # Running
+//! Running this fuzzer can be done with `cargo hfuzz run prepare_structured`. `honggfuzz` CLI
+//! options can be used by setting `HFUZZ_RUN_ARGS`, such as `-n 4` to use 4 threads.
+//!
+//! # Debugging a panic
+//! Once a panic is found, it can be debugged with
+//! `cargo hfuzz run-debug prepare_structured hfuzz_workspace/prepare_structured/*.fuzz`.
+
+use honggfuzz::fuzz;
+use parity_wasm::{
+	builder,
+	elements::{Deserialize, FuncBody, Instructions, Local, ValueType, serialize},
+};
+
+mod mock;
+
+/// Build a contract whose `call` function consists of `instructions`.
+///
+/// `num_locals` locals of type `i64` are made available to the instructions.
+fn contract(instructions: Instructions, num_locals: u8) -> Vec<u8> {
+	let module = builder::module()
+		.function()
+			.signature().with_return_type(None).build()
+			.with_body(FuncBody::new(Vec::new(), Instructions::empty()))
+			.build()
+		.function()
+			.signature().with_return_type(None).build()
+			.with_body(FuncBody::new(
+				vec![Local::new(num_locals.into(), ValueType::I64)],
+				instructions,
+			))
+			.build()
+		.export().field("deploy").internal().func(0).build()
+		.export().field("call").internal().func(1).build()
+		.import()
+			.module("env").field("memory")
+			.external().memory(1, Some(16))
+			.build()
+		.build();
+	serialize(module).expect("Module built by the builder can always be serialized; qed")
+}
+
+fn main() {
+	loop {
+		fuzz!(|data: (u8, Vec<u8>)| {
+			let (num_locals, body) = data;
+			let instructions = match Instructions::deserialize(&mut &body[..]) {
+				Ok(instructions) => instructions,
+				Err(_) => return,
+			};
+			mock::prepare_and_check(contract(instructions, num_locals));
+		});
+	}
+}
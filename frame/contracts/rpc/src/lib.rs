@@ -24,7 +24,7 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
 use pallet_contracts_primitives::RentProjection;
 use serde::{Deserialize, Serialize};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, H256};
 use sp_rpc::number;
@@ -34,13 +34,17 @@ use sp_runtime::{
 	DispatchError,
 };
 use std::convert::{TryFrom, TryInto};
-use pallet_contracts_primitives::ContractExecResult;
+use pallet_contracts_primitives::{ContractExecResult, ContractInstantiateResult};
 
 pub use pallet_contracts_rpc_runtime_api::ContractsApi as ContractsRuntimeApi;
+pub use metrics::ContractsMetrics;
+
+mod metrics;
 
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
+const UNSUPPORTED_BY_RUNTIME: i64 = 4;
 
 /// A rough estimate of how much gas a decent hardware consumes per second,
 /// using native execution.
@@ -84,6 +88,39 @@ pub struct CallRequest<AccountId> {
 	input_data: Bytes,
 }
 
+/// Reference to an existing code hash or a new wasm module.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Bytes),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
+impl<Hash> From<Code<Hash>> for pallet_contracts_primitives::Code<Hash> {
+	fn from(code: Code<Hash>) -> Self {
+		match code {
+			Code::Upload(binary) => pallet_contracts_primitives::Code::Upload(binary.to_vec()),
+			Code::Existing(hash) => pallet_contracts_primitives::Code::Existing(hash),
+		}
+	}
+}
+
+/// A struct that encodes RPC parameters required to instantiate a new smart-contract.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Hash> {
+	origin: AccountId,
+	endowment: number::NumberOrHex,
+	gas_limit: number::NumberOrHex,
+	code: Code<Hash>,
+	data: Bytes,
+	salt: Bytes,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
@@ -129,6 +166,47 @@ impl From<ContractExecResult> for RpcContractExecResult {
 	}
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+struct RpcInstantiateReturnValue<AccountId> {
+	/// The output of the called constructor.
+	result: RpcContractExecSuccess,
+	/// The account id of the new contract.
+	account_id: AccountId,
+}
+
+/// An RPC serializable result of contract instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractInstantiateResult<AccountId> {
+	/// How much gas was consumed by the instantiation. In case of an error this is the amount
+	/// that was used up until the error occurred.
+	gas_consumed: u64,
+	/// Indicates whether the instantiation was successful or not.
+	result: std::result::Result<RpcInstantiateReturnValue<AccountId>, DispatchError>,
+}
+
+impl<AccountId> From<ContractInstantiateResult<AccountId>>
+	for RpcContractInstantiateResult<AccountId>
+{
+	fn from(r: ContractInstantiateResult<AccountId>) -> Self {
+		RpcContractInstantiateResult {
+			gas_consumed: r.gas_consumed,
+			result: r.exec_result
+				.map(|val| RpcInstantiateReturnValue {
+					result: RpcContractExecSuccess {
+						flags: val.result.flags.bits(),
+						data: val.result.data.into(),
+					},
+					account_id: val.account_id,
+				})
+				.map_err(|err| err.error),
+		}
+	}
+}
+
 /// Contracts RPC methods.
 #[rpc]
 pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
@@ -145,6 +223,20 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		at: Option<BlockHash>,
 	) -> Result<RpcContractExecResult>;
 
+	/// Instantiate a new contract.
+	///
+	/// This instantiate is performed locally without submitting any transactions. Thus the
+	/// contract is not actually created.
+	///
+	/// This method is useful for precomputing the address of a contract and for checking
+	/// whether its constructor succeeds before submitting the instantiation.
+	#[rpc(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, BlockHash>,
+		at: Option<BlockHash>,
+	) -> Result<RpcContractInstantiateResult<AccountId>>;
+
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
 	#[rpc(name = "contracts_getStorage")]
@@ -199,6 +291,7 @@ where
 		AccountId,
 		Balance,
 		<<Block as BlockT>::Header as HeaderT>::Number,
+		<Block as BlockT>::Hash,
 	>,
 	AccountId: Codec,
 	Balance: Codec + TryFrom<number::NumberOrHex>,
@@ -221,37 +314,68 @@ where
 			input_data,
 		} = call_request;
 
-		// Make sure that value fits into the balance type.
-		let value: Balance = value.try_into().map_err(|_| Error {
-			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit into the balance type", value),
-			data: None,
-		})?;
+		let value: Balance = decode_balance(value)?;
+		let gas_limit = decode_gas_limit(gas_limit)?;
 
-		// Make sure that gas_limit fits into 64 bits.
-		let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
-			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
-			data: None,
-		})?;
+		let exec_result = api
+			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(exec_result.into())
+	}
 
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, <Block as BlockT>::Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcContractInstantiateResult<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let InstantiateRequest {
+			origin,
+			endowment,
+			gas_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+
+		let endowment: Balance = decode_balance(endowment)?;
+		let gas_limit = decode_gas_limit(gas_limit)?;
+
+		let has_instantiate = api
+			.has_api_with::<dyn ContractsRuntimeApi<
+				Block,
+				AccountId,
+				Balance,
+				<<Block as BlockT>::Header as HeaderT>::Number,
+				<Block as BlockT>::Hash,
+			>, _>(&at, |version| version >= 2)
+			.map_err(runtime_error_into_rpc_err)?;
+		if !has_instantiate {
 			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
+				code: ErrorCode::ServerError(UNSUPPORTED_BY_RUNTIME),
+				message: "The runtime does not support instantiating contracts over RPC.".into(),
 				data: None,
 			});
 		}
 
-		let exec_result = api
-			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
+		let result = api
+			.instantiate(
+				&at,
+				origin,
+				endowment,
+				gas_limit,
+				code.into(),
+				data.to_vec(),
+				salt.to_vec(),
+			)
 			.map_err(runtime_error_into_rpc_err)?;
 
-		Ok(exec_result.into())
+		Ok(result.into())
 	}
 
 	fn get_storage(
@@ -296,6 +420,40 @@ where
 	}
 }
 
+/// Makes sure that the given value fits into the balance type.
+fn decode_balance<Balance: TryFrom<number::NumberOrHex>>(
+	value: number::NumberOrHex,
+) -> Result<Balance> {
+	value.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit into the balance type", value),
+		data: None,
+	})
+}
+
+/// Makes sure that the given gas limit fits into 64 bits and doesn't exceed the maximum.
+fn decode_gas_limit(gas_limit: number::NumberOrHex) -> Result<u64> {
+	let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit)
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -326,6 +484,43 @@ mod tests {
 		assert_eq!(req.value.into_u256(), U256::from(1234567890987654321u128));
 	}
 
+	#[test]
+	fn instantiate_request_should_serialize_deserialize_properly() {
+		type Req = InstantiateRequest<String, String>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": "0x88",
+			"gasLimit": 42,
+			"code": { "existing": "0x1122" },
+			"data": "0x4299",
+			"salt": "0x9988"
+		}
+		"#).unwrap();
+
+		assert_eq!(req.origin, "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL");
+		assert_eq!(req.endowment.into_u256(), U256::from(0x88u64));
+		assert_eq!(req.gas_limit.into_u256(), U256::from(42u64));
+		assert_eq!(&*req.data, [0x42, 0x99].as_ref());
+		assert_eq!(&*req.salt, [0x99, 0x88].as_ref());
+		let code = match req.code {
+			Code::Existing(hash) => hash,
+			_ => panic!("json encoded an existing hash"),
+		};
+		assert_eq!(&code, "0x1122");
+	}
+
+	#[test]
+	fn instantiate_result_should_serialize_deserialize_properly() {
+		fn test(expected: &str) {
+			let res: RpcContractInstantiateResult<String> = serde_json::from_str(expected).unwrap();
+			let actual = serde_json::to_string(&res).unwrap();
+			assert_eq!(actual, expected);
+		}
+		test(r#"{"gasConsumed":5000,"result":{"Ok":{"result":{"flags":0,"data":"0x1234"},"accountId":"5CiPP"}}}"#);
+		test(r#"{"gasConsumed":3400,"result":{"Err":"BadOrigin"}}"#);
+	}
+
 	#[test]
 	fn result_should_serialize_deserialize_properly() {
 		fn test(expected: &str) {
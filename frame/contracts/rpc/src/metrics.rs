@@ -0,0 +1,121 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prometheus metrics for contract executions.
+//!
+//! The metrics are fed by the runtime through the
+//! [`contracts_profiling`](pallet_contracts_primitives::contracts_profiling) interface. This
+//! requires [`ContractsMetrics`] to be registered as
+//! [`ProfilingExt`](pallet_contracts_primitives::ProfilingExt) for runtime calls and a runtime
+//! that was built with the `profiling` feature of `pallet-contracts`.
+//!
+//! Note that every execution done by the node is counted. A block authored by this node is
+//! executed once while building it and once more while importing it.
+
+use pallet_contracts_primitives::{Profiler, ProfilingEvent};
+use prometheus_endpoint::{
+	register, exponential_buckets, Counter, CounterVec, Histogram, HistogramOpts, Opts,
+	PrometheusError, Registry, U64,
+};
+
+/// Contract execution metrics.
+#[derive(Clone)]
+pub struct ContractsMetrics {
+	calls: Counter<U64>,
+	instantiations: Counter<U64>,
+	gas_consumed: Histogram,
+	code_cache: CounterVec<U64>,
+}
+
+impl ContractsMetrics {
+	/// Create the metrics and register them in the given `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			calls: register(
+				Counter::new(
+					"contracts_calls_total",
+					"Number of contract calls, including calls made by contracts",
+				)?,
+				registry,
+			)?,
+			instantiations: register(
+				Counter::new(
+					"contracts_instantiations_total",
+					"Number of contract instantiations, including those made by contracts",
+				)?,
+				registry,
+			)?,
+			gas_consumed: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"contracts_gas_consumed",
+						"Gas consumed by top level contract executions",
+					).buckets(exponential_buckets(1_000_000.0, 4.0, 12)?),
+				)?,
+				registry,
+			)?,
+			code_cache: register(
+				CounterVec::new(
+					Opts::new(
+						"contracts_code_cache_lookups_total",
+						"Number of `CodeStorage` lookups, by whether the cached code could be \
+						used without re-instrumentation",
+					),
+					&["result"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+impl Profiler for ContractsMetrics {
+	fn record(&self, event: ProfilingEvent) {
+		match event {
+			ProfilingEvent::Call => self.calls.inc(),
+			ProfilingEvent::Instantiate => self.instantiations.inc(),
+			ProfilingEvent::GasConsumed(gas) => self.gas_consumed.observe(gas as f64),
+			ProfilingEvent::CodeCacheHit => self.code_cache.with_label_values(&["hit"]).inc(),
+			ProfilingEvent::CodeCacheMiss => self.code_cache.with_label_values(&["miss"]).inc(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn events_are_recorded() {
+		let registry = Registry::new();
+		let metrics = ContractsMetrics::register(&registry).unwrap();
+
+		metrics.record(ProfilingEvent::Call);
+		metrics.record(ProfilingEvent::Call);
+		metrics.record(ProfilingEvent::Instantiate);
+		metrics.record(ProfilingEvent::GasConsumed(5_000_000));
+		metrics.record(ProfilingEvent::CodeCacheHit);
+		metrics.record(ProfilingEvent::CodeCacheMiss);
+		metrics.record(ProfilingEvent::CodeCacheHit);
+
+		assert_eq!(metrics.calls.get(), 2);
+		assert_eq!(metrics.instantiations.get(), 1);
+		assert_eq!(metrics.gas_consumed.get_sample_count(), 1);
+		assert_eq!(metrics.code_cache.with_label_values(&["hit"]).get(), 2);
+		assert_eq!(metrics.code_cache.with_label_values(&["miss"]).get(), 1);
+	}
+}
@@ -23,9 +23,14 @@
 //! them as text (.wat) as this will be too slow and consume too much memory. Therefore
 //! we define this simple definition of a contract that can be passed to `create_code` that
 //! compiles it down into a `WasmModule` that can be used as a contract's code.
+//!
+//! A [`ModuleDefinition`] allows to configure the imported memory, the set of imported functions
+//! and the function bodies. The bodies are generated by the functions in [`body`]. Mixes of
+//! different instruction sequences are generated by the [`generator`] module.
 
 use crate::Config;
 use crate::Module as Contracts;
+use super::generator::{self, DynInstr};
 
 use parity_wasm::elements::{
 	Instruction, Instructions, FuncBody, ValueType, BlockType, Section, CustomSection,
@@ -104,6 +109,8 @@ impl ImportedMemory {
 }
 
 pub struct ImportedFunction {
+	/// The module the function is imported from, e.g. `seal0`.
+	pub module: &'static str,
 	pub name: &'static str,
 	pub params: Vec<ValueType>,
 	pub return_type: Option<ValueType>,
@@ -172,7 +179,7 @@ where
 				.build_sig();
 			let sig = contract.push_signature(sig);
 			contract = contract.import()
-				.module("seal0")
+				.module(func.module)
 				.field(func.name)
 				.with_external(parity_wasm::elements::External::Function(sig))
 				.build();
@@ -293,6 +300,7 @@ where
 		ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: getter_name,
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -322,6 +330,7 @@ where
 		ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name,
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -352,27 +361,17 @@ where
 	}
 
 	pub fn unary_instr(instr: Instruction, repeat: u32) -> Self {
-		use body::DynInstr::{RandomI64Repeated, Regular};
-		ModuleDefinition {
-			call_body: Some(body::repeated_dyn(repeat, vec![
-				RandomI64Repeated(1),
-				Regular(instr),
-				Regular(Instruction::Drop),
-			])),
-			.. Default::default()
-		}.into()
+		use generator::DynInstr::{RandomI64Repeated, Regular};
+		generator::module(vec![
+			(1, vec![RandomI64Repeated(1), Regular(instr), Regular(Instruction::Drop)]),
+		], repeat)
 	}
 
 	pub fn binary_instr(instr: Instruction, repeat: u32) -> Self {
-		use body::DynInstr::{RandomI64Repeated, Regular};
-		ModuleDefinition {
-			call_body: Some(body::repeated_dyn(repeat, vec![
-				RandomI64Repeated(2),
-				Regular(instr),
-				Regular(Instruction::Drop),
-			])),
-			.. Default::default()
-		}.into()
+		use generator::DynInstr::{RandomI64Repeated, Regular};
+		generator::module(vec![
+			(1, vec![RandomI64Repeated(2), Regular(instr), Regular(Instruction::Drop)]),
+		], repeat)
 	}
 }
 
@@ -380,42 +379,6 @@ where
 pub mod body {
 	use super::*;
 
-	/// When generating contract code by repeating a wasm sequence, it's sometimes necessary
-	/// to change those instructions on each repetition. The variants of this enum describe
-	/// various ways in which this can happen.
-	pub enum DynInstr {
-		/// Insert the associated instruction.
-		Regular(Instruction),
-		/// Insert a I32Const with incrementing value for each insertion.
-		/// (start_at, increment_by)
-		Counter(u32, u32),
-		/// Insert a I32Const with a random value in [low, high) not divisible by two.
-		/// (low, high)
-		RandomUnaligned(u32, u32),
-		/// Insert a I32Const with a random value in [low, high).
-		/// (low, high)
-		RandomI32(i32, i32),
-		/// Insert the specified amount of I32Const with a random value.
-		RandomI32Repeated(usize),
-		/// Insert the specified amount of I64Const with a random value.
-		RandomI64Repeated(usize),
-		/// Insert a GetLocal with a random offset in [low, high).
-		/// (low, high)
-		RandomGetLocal(u32, u32),
-		/// Insert a SetLocal with a random offset in [low, high).
-		/// (low, high)
-		RandomSetLocal(u32, u32),
-		/// Insert a TeeLocal with a random offset in [low, high).
-		/// (low, high)
-		RandomTeeLocal(u32, u32),
-		/// Insert a GetGlobal with a random offset in [low, high).
-		/// (low, high)
-		RandomGetGlobal(u32, u32),
-		/// Insert a SetGlobal with a random offset in [low, high).
-		/// (low, high)
-		RandomSetGlobal(u32, u32)
-	}
-
 	pub fn plain(instructions: Vec<Instruction>) -> FuncBody {
 		FuncBody::new(Vec::new(), Instructions::new(instructions))
 	}
@@ -433,63 +396,8 @@ pub mod body {
 		FuncBody::new(Vec::new(), instructions)
 	}
 
-	pub fn repeated_dyn(repetitions: u32, mut instructions: Vec<DynInstr>) -> FuncBody {
-		use rand::{prelude::*, distributions::Standard};
-
-		// We do not need to be secure here.
-		let mut rng = rand_pcg::Pcg32::seed_from_u64(8446744073709551615);
-
-		// We need to iterate over indices because we cannot cycle over mutable references
-		let body = (0..instructions.len())
-			.cycle()
-			.take(instructions.len() * usize::try_from(repetitions).unwrap())
-			.flat_map(|idx|
-				match &mut instructions[idx] {
-					DynInstr::Regular(instruction) => vec![instruction.clone()],
-					DynInstr::Counter(offset, increment_by) => {
-						let current = *offset;
-						*offset += *increment_by;
-						vec![Instruction::I32Const(current as i32)]
-					},
-					DynInstr::RandomUnaligned(low, high) => {
-						let unaligned = rng.gen_range(*low, *high) | 1;
-						vec![Instruction::I32Const(unaligned as i32)]
-					},
-					DynInstr::RandomI32(low, high) => {
-						vec![Instruction::I32Const(rng.gen_range(*low, *high))]
-					},
-					DynInstr::RandomI32Repeated(num) => {
-						(&mut rng).sample_iter(Standard).take(*num).map(|val|
-							Instruction::I32Const(val)
-						)
-						.collect()
-					},
-					DynInstr::RandomI64Repeated(num) => {
-						(&mut rng).sample_iter(Standard).take(*num).map(|val|
-							Instruction::I64Const(val)
-						)
-						.collect()
-					},
-					DynInstr::RandomGetLocal(low, high) => {
-						vec![Instruction::GetLocal(rng.gen_range(*low, *high))]
-					},
-					DynInstr::RandomSetLocal(low, high) => {
-						vec![Instruction::SetLocal(rng.gen_range(*low, *high))]
-					},
-					DynInstr::RandomTeeLocal(low, high) => {
-						vec![Instruction::TeeLocal(rng.gen_range(*low, *high))]
-					},
-					DynInstr::RandomGetGlobal(low, high) => {
-						vec![Instruction::GetGlobal(rng.gen_range(*low, *high))]
-					},
-					DynInstr::RandomSetGlobal(low, high) => {
-						vec![Instruction::SetGlobal(rng.gen_range(*low, *high))]
-					},
-				}
-			)
-			.chain(sp_std::iter::once(Instruction::End))
-			.collect();
-		FuncBody::new(Vec::new(), Instructions::new(body))
+	pub fn repeated_dyn(repetitions: u32, instructions: Vec<DynInstr>) -> FuncBody {
+		generator::mixed(repetitions, vec![(1, instructions)])
 	}
 
 	/// Replace the locals of the supplied `body` with `num` i64 locals.
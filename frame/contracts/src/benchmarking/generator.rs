@@ -0,0 +1,177 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generators for the instruction sequences of benchmark contracts.
+//!
+//! An instruction mix is a list of instruction sequences, each paired with how many times it
+//! is inserted in a row per repetition. This allows to generate code with a specific ratio of
+//! different instruction groups in order to calibrate the schedule for every group.
+
+use crate::Config;
+use super::code::{ModuleDefinition, WasmModule};
+
+use parity_wasm::elements::{Instruction, Instructions, FuncBody};
+use sp_core::crypto::UncheckedFrom;
+use sp_std::{prelude::*, convert::TryFrom};
+
+/// When generating contract code by repeating a wasm sequence, it's sometimes necessary
+/// to change those instructions on each repetition. The variants of this enum describe
+/// various ways in which this can happen.
+pub enum DynInstr {
+	/// Insert the associated instruction.
+	Regular(Instruction),
+	/// Insert a I32Const with incrementing value for each insertion.
+	/// (start_at, increment_by)
+	Counter(u32, u32),
+	/// Insert a I32Const with a random value in [low, high) not divisible by two.
+	/// (low, high)
+	RandomUnaligned(u32, u32),
+	/// Insert a I32Const with a random value in [low, high).
+	/// (low, high)
+	RandomI32(i32, i32),
+	/// Insert the specified amount of I32Const with a random value.
+	RandomI32Repeated(usize),
+	/// Insert the specified amount of I64Const with a random value.
+	RandomI64Repeated(usize),
+	/// Insert a GetLocal with a random offset in [low, high).
+	/// (low, high)
+	RandomGetLocal(u32, u32),
+	/// Insert a SetLocal with a random offset in [low, high).
+	/// (low, high)
+	RandomSetLocal(u32, u32),
+	/// Insert a TeeLocal with a random offset in [low, high).
+	/// (low, high)
+	RandomTeeLocal(u32, u32),
+	/// Insert a GetGlobal with a random offset in [low, high).
+	/// (low, high)
+	RandomGetGlobal(u32, u32),
+	/// Insert a SetGlobal with a random offset in [low, high).
+	/// (low, high)
+	RandomSetGlobal(u32, u32)
+}
+
+/// Generates a body that consists of `repetitions` repetitions of the instruction `mix`.
+///
+/// State kept by a `DynInstr` (e.g. `Counter`) is shared by all insertions of its sequence.
+pub fn mixed(repetitions: u32, mix: Vec<(u32, Vec<DynInstr>)>) -> FuncBody {
+	use rand::{prelude::*, distributions::Standard};
+
+	// We do not need to be secure here.
+	let mut rng = rand_pcg::Pcg32::seed_from_u64(8446744073709551615);
+
+	// Flatten the mix into a single list of instructions and the order in which they are
+	// inserted per repetition.
+	let mut instructions = Vec::new();
+	let mut order = Vec::new();
+	for (count, sequence) in mix {
+		let indices = instructions.len() .. instructions.len() + sequence.len();
+		instructions.extend(sequence);
+		for _ in 0 .. count {
+			order.extend(indices.clone());
+		}
+	}
+
+	// We need to iterate over indices because we cannot cycle over mutable references
+	let body = order.iter()
+		.cycle()
+		.take(order.len() * usize::try_from(repetitions).unwrap())
+		.flat_map(|&idx|
+			match &mut instructions[idx] {
+				DynInstr::Regular(instruction) => vec![instruction.clone()],
+				DynInstr::Counter(offset, increment_by) => {
+					let current = *offset;
+					*offset += *increment_by;
+					vec![Instruction::I32Const(current as i32)]
+				},
+				DynInstr::RandomUnaligned(low, high) => {
+					let unaligned = rng.gen_range(*low, *high) | 1;
+					vec![Instruction::I32Const(unaligned as i32)]
+				},
+				DynInstr::RandomI32(low, high) => {
+					vec![Instruction::I32Const(rng.gen_range(*low, *high))]
+				},
+				DynInstr::RandomI32Repeated(num) => {
+					(&mut rng).sample_iter(Standard).take(*num).map(|val|
+						Instruction::I32Const(val)
+					)
+					.collect()
+				},
+				DynInstr::RandomI64Repeated(num) => {
+					(&mut rng).sample_iter(Standard).take(*num).map(|val|
+						Instruction::I64Const(val)
+					)
+					.collect()
+				},
+				DynInstr::RandomGetLocal(low, high) => {
+					vec![Instruction::GetLocal(rng.gen_range(*low, *high))]
+				},
+				DynInstr::RandomSetLocal(low, high) => {
+					vec![Instruction::SetLocal(rng.gen_range(*low, *high))]
+				},
+				DynInstr::RandomTeeLocal(low, high) => {
+					vec![Instruction::TeeLocal(rng.gen_range(*low, *high))]
+				},
+				DynInstr::RandomGetGlobal(low, high) => {
+					vec![Instruction::GetGlobal(rng.gen_range(*low, *high))]
+				},
+				DynInstr::RandomSetGlobal(low, high) => {
+					vec![Instruction::SetGlobal(rng.gen_range(*low, *high))]
+				},
+			}
+		)
+		.chain(sp_std::iter::once(Instruction::End))
+		.collect();
+	FuncBody::new(Vec::new(), Instructions::new(body))
+}
+
+/// Creates a wasm module whose `call` function executes the instruction `mix` `repeat`
+/// times. See [`mixed`] for how the mix is specified.
+pub fn module<T: Config>(mix: Vec<(u32, Vec<DynInstr>)>, repeat: u32) -> WasmModule<T>
+where
+	T: Config,
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+	ModuleDefinition {
+		call_body: Some(mixed(repeat, mix)),
+		.. Default::default()
+	}.into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use DynInstr::{Counter, Regular};
+
+	#[test]
+	fn mixed_inserts_each_sequence_per_repetition() {
+		let body = mixed(2, vec![
+			(2, vec![Regular(Instruction::Nop)]),
+			(1, vec![Counter(0, 4), Regular(Instruction::Drop)]),
+		]);
+		assert_eq!(body.code().elements(), &[
+			Instruction::Nop,
+			Instruction::Nop,
+			Instruction::I32Const(0),
+			Instruction::Drop,
+			Instruction::Nop,
+			Instruction::Nop,
+			Instruction::I32Const(4),
+			Instruction::Drop,
+			Instruction::End,
+		]);
+	}
+}
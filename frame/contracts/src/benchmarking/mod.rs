@@ -20,6 +20,7 @@
 #![cfg(feature = "runtime-benchmarks")]
 
 mod code;
+mod generator;
 mod sandbox;
 
 use crate::{
@@ -30,10 +31,8 @@ use crate::{
 	storage::Storage,
 };
 use self::{
-	code::{
-		body::{self, DynInstr::*},
-		ModuleDefinition, DataSegment, ImportedMemory, ImportedFunction, WasmModule,
-	},
+	code::{body, ModuleDefinition, DataSegment, ImportedMemory, ImportedFunction, WasmModule},
+	generator::{self, DynInstr::*},
 	sandbox::Sandbox,
 };
 use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
@@ -166,12 +165,14 @@ where
 	/// Store the supplied storage items into this contracts storage.
 	fn store(&self, items: &Vec<(StorageKey, Vec<u8>)>) -> Result<(), &'static str> {
 		let info = self.alive_info()?;
+		let limits = Contracts::<T>::current_schedule().limits;
 		for item in items {
 			Storage::<T>::write(
 				&self.account_id,
 				&info.trie_id,
 				&item.0,
 				Some(item.1.clone()),
+				&limits,
 			)
 			.map_err(|_| "Failed to write storage to restoration dest")?;
 		}
@@ -469,6 +470,66 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
 
+	seal_own_code_hash {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(WasmModule::getter(
+			"seal_own_code_hash", r * API_BENCHMARK_BATCH_SIZE
+		), vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	seal_reentrance_count {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "seal_reentrance_count",
+				params: vec![],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
+	// The account is never on the call stack which forces a walk of the whole stack.
+	seal_account_reentrance_count {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let account = account::<T::AccountId>("account", 0, 0);
+		let account_bytes = account.encode();
+		let account_len = account_bytes.len();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "seal_account_reentrance_count",
+				params: vec![ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: account_bytes,
+				},
+			],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // account_ptr
+				Instruction::I32Const(account_len as i32), // account_len
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+
 	seal_gas_left {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let instance = Contract::<T>::new(WasmModule::getter(
@@ -547,6 +608,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_weight_to_fee",
 				params: vec![ValueType::I64, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -571,6 +633,7 @@ benchmarks! {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "gas",
 				params: vec![ValueType::I32],
 				return_type: None,
@@ -594,6 +657,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_input",
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -622,6 +686,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_input",
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -651,6 +716,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_return",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -672,6 +738,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_return",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -698,6 +765,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_terminate",
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -735,6 +803,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_terminate",
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -786,6 +855,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_restore_to",
 				params: vec![
 					ValueType::I32,
@@ -870,6 +940,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_restore_to",
 				params: vec![
 					ValueType::I32,
@@ -941,6 +1012,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_random",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -971,6 +1043,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_deposit_event",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -1002,6 +1075,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_deposit_event",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -1032,6 +1106,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory { min_pages: 1, max_pages: 1 }),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_set_rent_allowance",
 				params: vec![ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -1066,6 +1141,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_set_storage",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -1095,6 +1171,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_set_storage",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: None,
@@ -1130,6 +1207,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_clear_storage",
 				params: vec![ValueType::I32],
 				return_type: None,
@@ -1148,12 +1226,14 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let trie_id = instance.alive_info()?.trie_id;
+		let limits = Contracts::<T>::current_schedule().limits;
 		for key in keys {
 			Storage::<T>::write(
 				&instance.account_id,
 				&trie_id,
 				key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-				Some(vec![42; T::MaxValueSize::get() as usize])
+				Some(vec![42; T::MaxValueSize::get() as usize]),
+				&limits,
 			)
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
@@ -1172,6 +1252,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_get_storage",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: Some(ValueType::I32),
@@ -1193,12 +1274,14 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let trie_id = instance.alive_info()?.trie_id;
+		let limits = Contracts::<T>::current_schedule().limits;
 		for key in keys {
 			Storage::<T>::write(
 				&instance.account_id,
 				&trie_id,
 				key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-				Some(vec![])
+				Some(vec![]),
+				&limits,
 			)
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
@@ -1212,6 +1295,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_get_storage",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: Some(ValueType::I32),
@@ -1242,7 +1326,8 @@ benchmarks! {
 			&instance.account_id,
 			&trie_id,
 			key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-			Some(vec![42u8; (n * 1024) as usize])
+			Some(vec![42u8; (n * 1024) as usize]),
+			&Contracts::<T>::current_schedule().limits,
 		)
 		.map_err(|_| "Failed to write to storage during setup.")?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
@@ -1263,6 +1348,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_transfer",
 				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
 				return_type: Some(ValueType::I32),
@@ -1314,6 +1400,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_call",
 				params: vec![
 					ValueType::I32,
@@ -1365,6 +1452,7 @@ benchmarks! {
 		let callee_code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_return",
 				params: vec![
 					ValueType::I32,
@@ -1395,6 +1483,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_call",
 				params: vec![
 					ValueType::I32,
@@ -1480,6 +1569,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_instantiate",
 				params: vec![
 					ValueType::I32,
@@ -1562,6 +1652,7 @@ benchmarks! {
 		let callee_code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_return",
 				params: vec![
 					ValueType::I32,
@@ -1605,6 +1696,7 @@ benchmarks! {
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
+				module: "seal0",
 				name: "seal_instantiate",
 				params: vec![
 					ValueType::I32,
@@ -1757,13 +1849,9 @@ benchmarks! {
 	// w_i{32,64}const = w_drop = w_bench / 2
 	instr_i64const {
 		let r in 0 .. INSTR_BENCHMARK_BATCHES;
-		let mut sbox = Sandbox::from(&WasmModule::<T>::from(ModuleDefinition {
-			call_body: Some(body::repeated_dyn(r * INSTR_BENCHMARK_BATCH_SIZE, vec![
-				RandomI64Repeated(1),
-				Regular(Instruction::Drop),
-			])),
-			.. Default::default()
-		}));
+		let mut sbox = Sandbox::from(&generator::module::<T>(vec![
+			(1, vec![RandomI64Repeated(1), Regular(Instruction::Drop)]),
+		], r * INSTR_BENCHMARK_BATCH_SIZE));
 	}: {
 		sbox.invoke();
 	}
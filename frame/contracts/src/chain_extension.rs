@@ -36,6 +36,8 @@
 //! appropriate amount of weight. In order to do so benchmarks must be written and the
 //! [`charge_weight`](Environment::charge_weight) function must be called **before**
 //! carrying out any action that causes the consumption of the chargeable weight.
+//! When the actual weight is only known afterwards the worst case should be charged
+//! up front and the difference refunded through [`adjust_weight`](Environment::adjust_weight).
 //! It cannot be overstated how delicate of a process the creation of a chain extension
 //! is. Check whether using [`bare_call`](crate::Pallet::bare_call) suffices for the
 //! use case at hand.
@@ -63,7 +65,7 @@ use sp_std::{
 pub use frame_system::Config as SysConfig;
 pub use pallet_contracts_primitives::ReturnFlags;
 pub use sp_core::crypto::UncheckedFrom;
-pub use crate::{Config, exec::Ext};
+pub use crate::{Config, exec::Ext, gas::ChargedAmount};
 pub use state::Init as InitState;
 
 /// Result that returns a [`DispatchError`] on error.
@@ -161,11 +163,23 @@ where
 	/// `weight`. It returns `Err` otherwise. In this case the chain extension should
 	/// abort the execution and pass through the error.
 	///
+	/// The returned value can be passed to [`Self::adjust_weight`] in order to refund
+	/// weight that was charged in excess, e.g. when the worst case was charged up front.
+	///
 	/// # Note
 	///
 	/// Weight is synonymous with gas in substrate.
-	pub fn charge_weight(&mut self, amount: Weight) -> Result<()> {
-		self.inner.runtime.charge_gas(RuntimeToken::ChainExtension(amount)).map(|_| ())
+	pub fn charge_weight(&mut self, amount: Weight) -> Result<ChargedAmount> {
+		self.inner.runtime.charge_gas(RuntimeToken::ChainExtension(amount))
+	}
+
+	/// Adjust a previously charged amount down to its actual amount.
+	///
+	/// This is when a maximum a priori amount was charged and then should be partially
+	/// refunded to match the actual amount. An `actual_weight` that is larger than the
+	/// originally charged amount is ignored: This function can only refund weight.
+	pub fn adjust_weight(&mut self, charged: ChargedAmount, actual_weight: Weight) {
+		self.inner.runtime.adjust_gas(charged, RuntimeToken::ChainExtension(actual_weight))
 	}
 
 	/// Grants access to the execution environment of the current contract call.
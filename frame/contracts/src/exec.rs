@@ -28,11 +28,14 @@ use sp_std::{
 use sp_runtime::{Perbill, traits::{Bounded, Zero, Convert, Saturating}};
 use frame_support::{
 	dispatch::{DispatchResult, DispatchError},
-	traits::{ExistenceRequirement, Currency, Time, Randomness, Get},
+	traits::{ExistenceRequirement, Currency, Time, Randomness, Get, Filter},
 	weights::Weight,
 	ensure,
 };
-use pallet_contracts_primitives::{ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags};
+use pallet_contracts_primitives::{
+	CallFlags, ErrorOrigin, ExecError, ExecReturnValue, ExecResult, ReturnFlags, ProfilingEvent,
+	contracts_profiling,
+};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
@@ -141,6 +144,8 @@ pub trait Ext: sealing::Sealed {
 
 	/// Sets the storage entry by the given key to the specified value. If `value` is `None` then
 	/// the storage entry is deleted.
+	///
+	/// Fails with `StateChangeDenied` when executing within a read-only call.
 	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) -> DispatchResult;
 
 	/// Instantiate a contract from the given code.
@@ -189,6 +194,9 @@ pub trait Ext: sealing::Sealed {
 	///
 	/// Returns the original code size of the called contract.
 	///
+	/// When `flags` contains [`CallFlags::STATIC`] the callee and all contracts called by it
+	/// are executed read-only: any attempt to mutate state fails with `StateChangeDenied`.
+	///
 	/// # Return Value
 	///
 	/// Result<(ExecReturnValue, CodeSize), (ExecError, CodeSize)>
@@ -198,6 +206,7 @@ pub trait Ext: sealing::Sealed {
 		value: BalanceOf<Self::T>,
 		gas_meter: &mut GasMeter<Self::T>,
 		input_data: Vec<u8>,
+		flags: CallFlags,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)>;
 
 	/// Restores the given destination contract sacrificing the current one.
@@ -225,6 +234,24 @@ pub trait Ext: sealing::Sealed {
 	/// Returns a reference to the account id of the current contract.
 	fn address(&self) -> &AccountIdOf<Self::T>;
 
+	/// Returns a reference to the code hash of the current contract.
+	fn own_code_hash(&self) -> &CodeHash<Self::T>;
+
+	/// Returns how many times the current contract is already on the call stack.
+	///
+	/// This does not include the current call. Hence a return value of `0` means that
+	/// the current contract wasn't reentered.
+	fn reentrance_count(&self) -> u32;
+
+	/// Returns how many times the contract identified by `account` is on the call stack.
+	///
+	/// This includes the current call. The top level caller is not counted as it is never
+	/// a contract.
+	fn account_reentrance_count(&self, account: &AccountIdOf<Self::T>) -> u32;
+
+	/// Returns `true` if the current call is part of a read-only call.
+	fn is_read_only(&self) -> bool;
+
 	/// Returns the balance of the current contract.
 	///
 	/// The `value_transferred` is already added.
@@ -243,15 +270,18 @@ pub trait Ext: sealing::Sealed {
 	fn tombstone_deposit(&self) -> BalanceOf<Self::T>;
 
 	/// Returns a random number for the current block with the given subject.
-	fn random(&self, subject: &[u8]) -> SeedOf<Self::T>;
+	///
+	/// Alongside the seed the block number is returned since when the seed was
+	/// determinable by chain observers.
+	fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>);
 
 	/// Deposit an event with the given topics.
 	///
 	/// There should not be any duplicates in `topics`.
-	fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>);
+	fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>) -> DispatchResult;
 
 	/// Set rent allowance of the contract
-	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<Self::T>);
+	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<Self::T>) -> DispatchResult;
 
 	/// Rent allowance of the contract
 	fn rent_allowance(&self) -> BalanceOf<Self::T>;
@@ -263,6 +293,8 @@ pub trait Ext: sealing::Sealed {
 	fn max_value_size(&self) -> u32;
 
 	/// Returns the price for the specified amount of weight.
+	///
+	/// This uses `Config::WeightPrice` and is for informational purposes only.
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T>;
 
 	/// Get a reference to the schedule used by the current call.
@@ -360,6 +392,8 @@ pub struct ExecutionContext<'a, T: Config + 'a, E> {
 	self_account: T::AccountId,
 	self_trie_id: Option<TrieId>,
 	depth: usize,
+	/// Set when this context or one of its callers was entered through a read-only call.
+	read_only: bool,
 	schedule: &'a Schedule<T>,
 	timestamp: MomentOf<T>,
 	block_number: T::BlockNumber,
@@ -382,6 +416,7 @@ where
 			self_trie_id: None,
 			self_account: origin,
 			depth: 0,
+			read_only: false,
 			schedule,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
@@ -389,7 +424,7 @@ where
 		}
 	}
 
-	fn nested<'b, 'c: 'b>(&'c self, dest: T::AccountId, trie_id: TrieId)
+	fn nested<'b, 'c: 'b>(&'c self, dest: T::AccountId, trie_id: TrieId, read_only: bool)
 		-> ExecutionContext<'b, T, E>
 	{
 		ExecutionContext {
@@ -397,6 +432,7 @@ where
 			self_trie_id: Some(trie_id),
 			self_account: dest,
 			depth: self.depth + 1,
+			read_only: self.read_only || read_only,
 			schedule: self.schedule,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
@@ -416,6 +452,23 @@ where
 		gas_meter: &mut GasMeter<T>,
 		input_data: Vec<u8>,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		self.call_with_flags(dest, value, gas_meter, input_data, CallFlags::empty())
+	}
+
+	/// Same as [`Self::call`] but allows the caller to specify [`CallFlags`].
+	///
+	/// A call with [`CallFlags::STATIC`] set executes the callee and all of its nested calls
+	/// read-only.
+	fn call_with_flags(
+		&mut self,
+		dest: T::AccountId,
+		value: BalanceOf<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+		flags: CallFlags,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "call");
+
 		if self.depth == T::MaxDepth::get() as usize {
 			return Err((Error::<T>::MaxCallDepthReached.into(), 0));
 		}
@@ -424,6 +477,8 @@ where
 			.and_then(|contract| contract.get_alive())
 			.ok_or((Error::<T>::NotCallable.into(), 0))?;
 
+		contracts_profiling::record(ProfilingEvent::Call);
+
 		let executable = E::from_storage(contract.code_hash, &self.schedule, gas_meter)
 			.map_err(|e| (e.into(), 0))?;
 		let code_len = executable.code_len();
@@ -433,15 +488,23 @@ where
 		// changes would be rolled back in case this contract is called by another
 		// contract.
 		// See: https://github.com/paritytech/substrate/issues/6439#issuecomment-648754324
-		let contract = Rent::<T, E>::charge(&dest, contract, executable.occupied_storage())
-			.map_err(|e| (e.into(), code_len))?
-			.ok_or((Error::<T>::NotCallable.into(), code_len))?;
+		//
+		// Read-only calls cannot pay rent. They still deny access to such a contract.
+		let read_only = flags.contains(CallFlags::STATIC);
+		let contract = if self.read_only || read_only {
+			Rent::<T, E>::check(&dest, contract, executable.occupied_storage())
+		} else {
+			Rent::<T, E>::charge(&dest, contract, executable.occupied_storage())
+				.map_err(|e| (e.into(), code_len))?
+		}.ok_or((Error::<T>::NotCallable.into(), code_len))?;
 
 		let transactor_kind = self.transactor_kind();
 		let caller = self.self_account.clone();
 
-		let result = self.with_nested_context(dest.clone(), contract.trie_id.clone(), |nested| {
+		let trie_id = contract.trie_id.clone();
+		let result = self.with_nested_context(dest.clone(), trie_id, read_only, |nested| {
 			if value > BalanceOf::<T>::zero() {
+				nested.ensure_mutable()?;
 				transfer::<T>(
 					TransferCause::Call,
 					transactor_kind,
@@ -452,15 +515,26 @@ where
 			}
 
 			let call_context = nested.new_call_context(
-				caller, &dest, value, &contract, &executable,
+				caller.clone(), &dest, value, &contract, &executable,
 			);
 
-			let output = executable.execute(
-				call_context,
-				&ExportedFunction::Call,
-				input_data,
-				gas_meter,
+			let output = sp_tracing::within_span!(
+				sp_tracing::Level::TRACE, "execute";
+				executable.execute(
+					call_context,
+					&ExportedFunction::Call,
+					input_data,
+					gas_meter,
+				)
 			).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
+
+			// The event is rolled back together with all other changes in case the
+			// callee reverted. Therefore, it is only observable for successful calls.
+			// Read-only calls do not deposit events.
+			if !nested.read_only {
+				deposit_event::<T>(vec![], Event::Called(caller, dest.clone()));
+			}
+
 			Ok(output)
 		}).map_err(|e| (e, code_len))?;
 		Ok((result, code_len))
@@ -474,9 +548,15 @@ where
 		input_data: Vec<u8>,
 		salt: &[u8],
 	) -> Result<(T::AccountId, ExecReturnValue), ExecError> {
+		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "instantiate");
+
 		if self.depth == T::MaxDepth::get() as usize {
 			Err(Error::<T>::MaxCallDepthReached)?
 		}
+		self.ensure_mutable()?;
+		ensure!(T::InstantiateFilter::filter(executable.code_hash()), Error::<T>::CodeNotAllowed);
+
+		contracts_profiling::record(ProfilingEvent::Instantiate);
 
 		let transactor_kind = self.transactor_kind();
 		let caller = self.self_account.clone();
@@ -486,7 +566,7 @@ where
 			// Generate the trie id in a new transaction to only increment the counter on success.
 			let dest_trie_id = Storage::<T>::generate_trie_id(&dest);
 
-			let output = self.with_nested_context(dest.clone(), dest_trie_id, |nested| {
+			let output = self.with_nested_context(dest.clone(), dest_trie_id, false, |nested| {
 				let contract = Storage::<T>::place_contract(
 					&dest,
 					nested
@@ -520,11 +600,14 @@ where
 					&executable,
 				);
 
-				let output = executable.execute(
-					call_context,
-					&ExportedFunction::Constructor,
-					input_data,
-					gas_meter,
+				let output = sp_tracing::within_span!(
+					sp_tracing::Level::TRACE, "execute";
+					executable.execute(
+						call_context,
+						&ExportedFunction::Constructor,
+						input_data,
+						gas_meter,
+					)
 				).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
 
 				// We need to re-fetch the contract because changes are written to storage
@@ -574,17 +657,25 @@ where
 			timestamp,
 			block_number,
 			rent_params: RentParams::new(dest, contract, executable),
+			code_hash: executable.code_hash().clone(),
 			_phantom: Default::default(),
 		}
 	}
 
 	/// Execute the given closure within a nested execution context.
-	fn with_nested_context<F>(&mut self, dest: T::AccountId, trie_id: TrieId, func: F)
-		-> ExecResult
+	///
+	/// The nested context is read-only if `read_only` is set or this context is read-only.
+	fn with_nested_context<F>(
+		&mut self,
+		dest: T::AccountId,
+		trie_id: TrieId,
+		read_only: bool,
+		func: F,
+	) -> ExecResult
 		where F: FnOnce(&mut ExecutionContext<T, E>) -> ExecResult
 	{
 		use frame_support::storage::TransactionOutcome::*;
-		let mut nested = self.nested(dest, trie_id);
+		let mut nested = self.nested(dest, trie_id, read_only);
 		frame_support::storage::with_transaction(|| {
 			let output = func(&mut nested);
 			match output {
@@ -601,6 +692,21 @@ where
 			self.caller.map_or(false, |caller| caller.is_live(account))
 	}
 
+	/// Returns how many contexts in the execution stack, starting with this one, execute the
+	/// contract identified by `account`.
+	///
+	/// The top level context is never counted because it belongs to a plain account.
+	fn account_reentrance_count(&self, account: &T::AccountId) -> u32 {
+		let own = (self.depth > 0 && &self.self_account == account) as u32;
+		own + self.caller.map_or(0, |caller| caller.account_reentrance_count(account))
+	}
+
+	/// Fails with `StateChangeDenied` if this context is part of a read-only call.
+	fn ensure_mutable(&self) -> DispatchResult {
+		ensure!(!self.read_only, Error::<T>::StateChangeDenied);
+		Ok(())
+	}
+
 	fn transactor_kind(&self) -> TransactorKind {
 		if self.depth == 0 {
 			debug_assert!(self.self_trie_id.is_none());
@@ -687,6 +793,7 @@ struct CallContext<'a, 'b: 'a, T: Config + 'b, E> {
 	timestamp: MomentOf<T>,
 	block_number: T::BlockNumber,
 	rent_params: RentParams<T>,
+	code_hash: CodeHash<T>,
 	_phantom: PhantomData<E>,
 }
 
@@ -709,6 +816,7 @@ where
 	}
 
 	fn set_storage(&mut self, key: StorageKey, value: Option<Vec<u8>>) -> DispatchResult {
+		self.ctx.ensure_mutable()?;
 		let trie_id = self.ctx.self_trie_id.as_ref().expect(
 			"`ctx.self_trie_id` points to an alive contract within the `CallContext`;\
 				it cannot be `None`;\
@@ -720,7 +828,13 @@ where
 		// the contract cannot be absent in storage;
 		// write cannot return `None`;
 		// qed
-		Storage::<T>::write(&self.ctx.self_account, trie_id, &key, value)
+		Storage::<T>::write(
+			&self.ctx.self_account,
+			trie_id,
+			&key,
+			value,
+			&self.ctx.schedule.limits,
+		)
 	}
 
 	fn instantiate(
@@ -744,6 +858,7 @@ where
 		to: &T::AccountId,
 		value: BalanceOf<T>,
 	) -> DispatchResult {
+		self.ctx.ensure_mutable()?;
 		transfer::<T>(
 			TransferCause::Call,
 			TransactorKind::Contract,
@@ -757,6 +872,7 @@ where
 		&mut self,
 		beneficiary: &AccountIdOf<Self::T>,
 	) -> Result<u32, (DispatchError, u32)> {
+		self.ctx.ensure_mutable().map_err(|e| (e, 0))?;
 		let self_id = self.ctx.self_account.clone();
 		let value = T::Currency::free_balance(&self_id);
 		if let Some(caller_ctx) = self.ctx.caller {
@@ -791,8 +907,9 @@ where
 		value: BalanceOf<T>,
 		gas_meter: &mut GasMeter<T>,
 		input_data: Vec<u8>,
+		flags: CallFlags,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
-		self.ctx.call(to.clone(), value, gas_meter, input_data)
+		self.ctx.call_with_flags(to.clone(), value, gas_meter, input_data, flags)
 	}
 
 	fn restore_to(
@@ -802,6 +919,7 @@ where
 		rent_allowance: BalanceOf<Self::T>,
 		delta: Vec<StorageKey>,
 	) -> Result<(u32, u32), (DispatchError, u32, u32)> {
+		self.ctx.ensure_mutable().map_err(|e| (e, 0, 0))?;
 		if let Some(caller_ctx) = self.ctx.caller {
 			if caller_ctx.is_live(&self.ctx.self_account) {
 				return Err((Error::<T>::ReentranceDenied.into(), 0, 0));
@@ -837,6 +955,22 @@ where
 		&self.caller
 	}
 
+	fn own_code_hash(&self) -> &CodeHash<T> {
+		&self.code_hash
+	}
+
+	fn reentrance_count(&self) -> u32 {
+		self.ctx.account_reentrance_count(&self.ctx.self_account).saturating_sub(1)
+	}
+
+	fn account_reentrance_count(&self, account: &T::AccountId) -> u32 {
+		self.ctx.account_reentrance_count(account)
+	}
+
+	fn is_read_only(&self) -> bool {
+		self.ctx.read_only
+	}
+
 	fn balance(&self) -> BalanceOf<T> {
 		T::Currency::free_balance(&self.ctx.self_account)
 	}
@@ -845,10 +979,8 @@ where
 		self.value_transferred
 	}
 
-	fn random(&self, subject: &[u8]) -> SeedOf<T> {
-		// TODO: change API to expose randomness freshness
-		// https://github.com/paritytech/substrate/issues/8297
-		T::Randomness::random(subject).0
+	fn random(&self, subject: &[u8]) -> (SeedOf<T>, BlockNumberOf<T>) {
+		T::Randomness::random(subject)
 	}
 
 	fn now(&self) -> &MomentOf<T> {
@@ -863,14 +995,17 @@ where
 		T::TombstoneDeposit::get()
 	}
 
-	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
+	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) -> DispatchResult {
+		self.ctx.ensure_mutable()?;
 		deposit_event::<Self::T>(
 			topics,
 			Event::ContractEmitted(self.ctx.self_account.clone(), data)
 		);
+		Ok(())
 	}
 
-	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<T>) {
+	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<T>) -> DispatchResult {
+		self.ctx.ensure_mutable()?;
 		if let Err(storage::ContractAbsentError) =
 			Storage::<T>::set_rent_allowance(&self.ctx.self_account, rent_allowance)
 		{
@@ -879,6 +1014,7 @@ where
 					set_rent_allowance cannot return `Err`; qed"
 			);
 		}
+		Ok(())
 	}
 
 	fn rent_allowance(&self) -> BalanceOf<T> {
@@ -941,7 +1077,7 @@ mod tests {
 		gas::GasMeter, tests::{ExtBuilder, Test, Event as MetaEvent},
 		storage::Storage,
 		tests::{
-			ALICE, BOB, CHARLIE,
+			ALICE, BOB, CHARLIE, DJANGO,
 			test_utils::{place_contract, set_balance, get_balance},
 		},
 		exec::ExportedFunction::*,
@@ -1360,7 +1496,7 @@ mod tests {
 		let value = Default::default();
 		let recurse_ch = MockLoader::insert(Call, |ctx, _| {
 			// Try to call into yourself.
-			let r = ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![]);
+			let r = ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![], CallFlags::empty());
 
 			REACHED_BOTTOM.with(|reached_bottom| {
 				let mut reached_bottom = reached_bottom.borrow_mut();
@@ -1416,7 +1552,7 @@ mod tests {
 
 			// Call into CHARLIE contract.
 			assert_matches!(
-				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![]),
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::empty()),
 				Ok(_)
 			);
 			exec_success()
@@ -1457,7 +1593,7 @@ mod tests {
 
 			// Call into charlie contract.
 			assert_matches!(
-				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![]),
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::empty()),
 				Ok(_)
 			);
 			exec_success()
@@ -1617,7 +1753,8 @@ mod tests {
 			// there are instantiation event.
 			assert_eq!(Storage::<Test>::code_hash(&instantiated_contract_address).unwrap(), dummy_ch);
 			assert_eq!(&events(), &[
-				Event::Instantiated(BOB, instantiated_contract_address)
+				Event::Instantiated(BOB, instantiated_contract_address),
+				Event::Called(ALICE, BOB),
 			]);
 		});
 	}
@@ -1663,7 +1800,7 @@ mod tests {
 
 			// The contract wasn't instantiated so we don't expect to see an instantiation
 			// event here.
-			assert_eq!(&events(), &[]);
+			assert_eq!(&events(), &[Event::Called(ALICE, BOB)]);
 		});
 	}
 
@@ -1710,7 +1847,7 @@ mod tests {
 			let subsistence = Contracts::<Test>::subsistence_threshold();
 			let allowance = subsistence * 3;
 			assert_eq!(ctx.ext.rent_allowance(), <BalanceOf<Test>>::max_value());
-			ctx.ext.set_rent_allowance(allowance);
+			ctx.ext.set_rent_allowance(allowance).unwrap();
 			assert_eq!(ctx.ext.rent_allowance(), allowance);
 			exec_success()
 		});
@@ -1776,7 +1913,7 @@ mod tests {
 			// Changing the allowance during the call: rent params stay unchanged.
 			let allowance = 42;
 			assert_ne!(allowance, rent_params.rent_allowance);
-			ctx.ext.set_rent_allowance(allowance);
+			ctx.ext.set_rent_allowance(allowance).unwrap();
 			assert_eq!(ctx.ext.rent_params(), &rent_params);
 
 			// Creating another instance from the same code_hash increases the refcount.
@@ -1810,4 +1947,181 @@ mod tests {
 			).unwrap();
 		});
 	}
+
+	#[test]
+	fn static_call_denies_state_changes() {
+		let code_bob = MockLoader::insert(Call, |ctx, _| {
+			assert_matches!(
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::STATIC),
+				Ok(_)
+			);
+			// The caller itself is not affected by the flag it passed to its callee.
+			assert_matches!(ctx.ext.set_storage([1; 32], Some(vec![1])), Ok(()));
+			exec_success()
+		});
+		let code_charlie = MockLoader::insert(Call, |ctx, _| {
+			let denied: DispatchError = Error::<Test>::StateChangeDenied.into();
+			assert_eq!(ctx.ext.get_storage(&[1; 32]), None);
+			assert_eq!(ctx.ext.set_storage([1; 32], Some(vec![1])), Err(denied));
+			assert_eq!(ctx.ext.transfer(&ALICE, 1), Err(denied));
+			assert_eq!(ctx.ext.deposit_event(vec![], vec![1]), Err(denied));
+			assert_eq!(ctx.ext.set_rent_allowance(1), Err(denied));
+			assert_eq!(ctx.ext.terminate(&ALICE), Err((denied, 0)));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			place_contract(&BOB, code_bob);
+			place_contract(&CHARLIE, code_charlie);
+
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			assert_eq!(get_balance(&ALICE), 0);
+			// Only the call that was not read-only is recorded.
+			assert_eq!(events(), vec![Event::Called(ALICE, BOB)]);
+		});
+	}
+
+	#[test]
+	fn static_call_propagates_to_nested_calls() {
+		let code_bob = MockLoader::insert(Call, |ctx, _| {
+			assert_matches!(
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::STATIC),
+				Ok(_)
+			);
+			exec_success()
+		});
+		let code_charlie = MockLoader::insert(Call, |ctx, _| {
+			// Calling without the flag does not lift the restriction.
+			assert_matches!(
+				ctx.ext.call(&DJANGO, 0, ctx.gas_meter, vec![], CallFlags::empty()),
+				Ok(_)
+			);
+			// Transferring value along with a call is a state change.
+			assert_matches!(
+				ctx.ext.call(&DJANGO, 1, ctx.gas_meter, vec![], CallFlags::empty()),
+				Err((ExecError {
+					error,
+					origin: ErrorOrigin::Caller,
+				}, _)) if error == Error::<Test>::StateChangeDenied.into()
+			);
+			exec_success()
+		});
+		let code_django = MockLoader::insert(Call, |ctx, _| {
+			assert_eq!(
+				ctx.ext.set_storage([1; 32], Some(vec![1])),
+				Err(Error::<Test>::StateChangeDenied.into()),
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			place_contract(&BOB, code_bob);
+			place_contract(&CHARLIE, code_charlie);
+			place_contract(&DJANGO, code_django);
+
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+		});
+	}
+
+	#[test]
+	fn static_call_denies_instantiation() {
+		let dummy_ch = MockLoader::insert(Constructor, |_, _| exec_success());
+		let code_bob = MockLoader::insert(Call, |ctx, _| {
+			assert_matches!(
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::STATIC),
+				Ok(_)
+			);
+			exec_success()
+		});
+		let code_charlie = MockLoader::insert(Call, move |ctx, _| {
+			assert_matches!(
+				ctx.ext.instantiate(
+					dummy_ch,
+					Contracts::<Test>::subsistence_threshold() * 3,
+					ctx.gas_meter,
+					vec![],
+					&[],
+				),
+				Err((ExecError {
+					error,
+					origin: ErrorOrigin::Caller,
+				}, _)) if error == Error::<Test>::StateChangeDenied.into()
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			place_contract(&BOB, code_bob);
+			place_contract(&CHARLIE, code_charlie);
+
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			// Only the call that was not read-only is recorded.
+			assert_eq!(events(), vec![Event::Called(ALICE, BOB)]);
+		});
+	}
+
+	#[test]
+	fn own_code_hash_and_reentrance_count_work() {
+		thread_local! {
+			static WITNESSED_CODE_HASH: RefCell<Option<CodeHash<Test>>> = RefCell::new(None);
+			static WITNESSED_COUNTS: RefCell<Vec<(u32, u32)>> = RefCell::new(Vec::new());
+		}
+
+		let code_bob = MockLoader::insert(Call, |ctx, _| {
+			WITNESSED_CODE_HASH.with(|hash| *hash.borrow_mut() = Some(*ctx.ext.own_code_hash()));
+			WITNESSED_COUNTS.with(|counts| counts.borrow_mut().push(
+				(ctx.ext.reentrance_count(), ctx.ext.account_reentrance_count(&CHARLIE))
+			));
+
+			// Only call into CHARLIE when we are not reentered yet.
+			if ctx.input_data.is_empty() {
+				assert_matches!(
+					ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], CallFlags::empty()),
+					Ok(_)
+				);
+			}
+			exec_success()
+		});
+		let code_charlie = MockLoader::insert(Call, |ctx, _| {
+			assert_eq!(ctx.ext.reentrance_count(), 0);
+			assert_eq!(ctx.ext.account_reentrance_count(&BOB), 1);
+			assert_eq!(ctx.ext.account_reentrance_count(&ALICE), 0);
+			assert_matches!(
+				ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![1], CallFlags::empty()),
+				Ok(_)
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = Contracts::current_schedule();
+			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			place_contract(&BOB, code_bob);
+			place_contract(&CHARLIE, code_charlie);
+
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+		});
+
+		WITNESSED_CODE_HASH.with(|hash| assert_eq!(*hash.borrow(), Some(code_bob)));
+		WITNESSED_COUNTS.with(|counts| assert_eq!(*counts.borrow(), vec![(0, 0), (1, 1)]));
+	}
+
 }
@@ -24,12 +24,15 @@ use frame_support::{
 	},
 	weights::Weight,
 };
-use pallet_contracts_primitives::ExecError;
+use pallet_contracts_primitives::{ExecError, ProfilingEvent, contracts_profiling};
 use sp_core::crypto::UncheckedFrom;
 
 #[cfg(test)]
 use std::{any::Any, fmt::Debug};
 
+/// The amount of weight that was charged from a [`GasMeter`].
+///
+/// It can be handed back to the meter in order to refund or adjust the charged amount.
 #[derive(Debug, PartialEq, Eq)]
 pub struct ChargedAmount(Weight);
 
@@ -211,6 +214,8 @@ where
 	where
 		E: Into<ExecError>,
 	{
+		contracts_profiling::record(ProfilingEvent::GasConsumed(self.gas_spent()));
+
 		let post_info = PostDispatchInfo {
 			actual_weight: Some(self.gas_spent().saturating_add(base_weight)),
 			pays_fee: Default::default(),
@@ -90,9 +90,9 @@ mod wasm;
 mod rent;
 mod benchmarking;
 mod schedule;
-mod migration;
 
 pub mod chain_extension;
+pub mod migration;
 pub mod weights;
 
 #[cfg(test)]
@@ -103,12 +103,15 @@ pub use crate::{
 	schedule::{Schedule, HostFnWeights, InstructionWeights, Limits},
 	pallet::*,
 };
+#[cfg(feature = "fuzzing")]
+pub use crate::wasm::fuzzing;
 use crate::{
 	gas::GasMeter,
 	exec::{ExecutionContext, Executable},
 	rent::Rent,
 	storage::{Storage, DeletedContract},
 	weights::WeightInfo,
+	migration::Migration,
 };
 use sp_core::crypto::UncheckedFrom;
 use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
@@ -120,13 +123,15 @@ use sp_runtime::{
 	RuntimeDebug, Perbill,
 };
 use frame_support::{
+	ensure,
 	storage::child::ChildInfo,
-	traits::{OnUnbalanced, Currency, Get, Time, Randomness},
+	traits::{OnUnbalanced, Currency, Get, Time, Randomness, Filter},
 	weights::{Weight, PostDispatchInfo, WithPostDispatchInfo},
 };
 use frame_system::Module as System;
 use pallet_contracts_primitives::{
 	RentProjectionResult, GetStorageResult, ContractAccessError, ContractExecResult,
+	ContractInstantiateResult, Code, InstantiateReturnValue, ProfilingEvent, contracts_profiling,
 };
 
 pub type CodeHash<T> = <T as frame_system::Config>::Hash;
@@ -140,6 +145,50 @@ pub type AliveContractInfo<T> =
 pub type TombstoneContractInfo<T> =
 	RawTombstoneContractInfo<<T as frame_system::Config>::Hash, <T as frame_system::Config>::Hashing>;
 
+/// Provides the contract address generation method.
+///
+/// See [`DefaultAddressGenerator`] for the default implementation.
+pub trait AddressGenerator<T: frame_system::Config> {
+	/// Generate the address of a contract based on the given instantiate parameters.
+	///
+	/// The result must only depend on its inputs so that the address of a contract can be
+	/// predicted before it is instantiated.
+	fn generate_address(
+		deploying_address: &T::AccountId,
+		code_hash: &CodeHash<T>,
+		salt: &[u8],
+	) -> T::AccountId;
+}
+
+/// Default address generator.
+///
+/// This is the default address generator used by contract instantiation. Its result
+/// is only dependend on its inputs. It can therefore be used to reliably predict the
+/// address of a contract. This is akin to the formular of eth's CREATE2 opcode. There
+/// is no CREATE equivalent because CREATE2 is strictly more powerful.
+///
+/// Formula: `hash(deploying_address ++ code_hash ++ salt)`
+pub struct DefaultAddressGenerator;
+
+impl<T> AddressGenerator<T> for DefaultAddressGenerator
+where
+	T: frame_system::Config,
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+	fn generate_address(
+		deploying_address: &T::AccountId,
+		code_hash: &CodeHash<T>,
+		salt: &[u8],
+	) -> T::AccountId {
+		let buf: Vec<_> = deploying_address.as_ref().iter()
+			.chain(code_hash.as_ref())
+			.chain(salt)
+			.cloned()
+			.collect();
+		UncheckedFrom::unchecked_from(T::Hashing::hash(&buf))
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::pallet_prelude::*;
@@ -226,6 +275,11 @@ pub mod pallet {
 
 		/// Used to answer contracts's queries regarding the current weight price. This is **not**
 		/// used to calculate the actual fee and is only for informational purposes.
+		///
+		/// The conversion is expected to account for the current fee multiplier so that the
+		/// answer reflects what a transaction would actually be charged. Runtimes that use
+		/// `pallet-transaction-payment` should set this to its `Module` which applies the
+		/// live `NextFeeMultiplier` to the weight fee.
 		type WeightPrice: Convert<Weight, BalanceOf<Self>>;
 
 		/// Describes the weights of the dispatchables of this module and is also used to
@@ -248,6 +302,20 @@ pub mod pallet {
 		/// a wasm binary below this maximum size.
 		#[pallet::constant]
 		type MaxCodeSize: Get<u32>;
+
+		/// The address generator used to generate the addresses of contracts.
+		///
+		/// Use [`DefaultAddressGenerator`] to keep the address derivation of this pallet.
+		/// Chains that need a different address format (e.g. for compatibility with
+		/// other account schemes) can supply their own implementation.
+		type AddressGenerator: AddressGenerator<Self>;
+
+		/// Filter that decides which code hashes are allowed to be instantiated.
+		///
+		/// It is consulted whenever a contract is instantiated: Either through a dispatchable
+		/// or by another contract calling `seal_instantiate`. Instantiating code that doesn't
+		/// pass the filter fails with [`Error::CodeNotAllowed`]. Use `()` to allow all code.
+		type InstantiateFilter: Filter<CodeHash<Self>>;
 	}
 
 	#[pallet::pallet]
@@ -269,10 +337,6 @@ pub mod pallet {
 			Storage::<T>::process_deletion_queue_batch(weight_limit)
 				.saturating_add(T::WeightInfo::on_initialize())
 		}
-
-		fn on_runtime_upgrade() -> Weight {
-			migration::migrate::<T>()
-		}
 	}
 
 	#[pallet::call]
@@ -317,6 +381,7 @@ pub mod pallet {
 			data: Vec<u8>
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
+			Migration::<T>::ensure_migrated()?;
 			let dest = T::Lookup::lookup(dest)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 			let schedule = <Module<T>>::current_schedule();
@@ -365,6 +430,7 @@ pub mod pallet {
 			salt: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
+			Migration::<T>::ensure_migrated()?;
 			let code_len = code.len() as u32;
 			ensure!(code_len <= T::MaxCodeSize::get(), Error::<T>::CodeTooLarge);
 			let mut gas_meter = GasMeter::new(gas_limit);
@@ -399,6 +465,7 @@ pub mod pallet {
 			salt: Vec<u8>,
 		) -> DispatchResultWithPostInfo {
 			let origin = ensure_signed(origin)?;
+			Migration::<T>::ensure_migrated()?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 			let schedule = <Module<T>>::current_schedule();
 			let executable = PrefabWasmModule::from_storage(code_hash, &schedule, &mut gas_meter)?;
@@ -437,6 +504,7 @@ pub mod pallet {
 				},
 				_ => Err(Error::<T>::InvalidSurchargeClaim)?,
 			};
+			Migration::<T>::ensure_migrated()?;
 
 			// Add some advantage for block producers (who send unsigned extrinsics) by
 			// adding a handicap: for signed extrinsics we use a slightly older block number
@@ -528,6 +596,44 @@ pub mod pallet {
 		///
 		/// This happens when the last contract that uses this code hash was removed or evicted.
 		CodeRemoved(T::Hash),
+
+		/// A storage migration has completed.
+		/// \[version\]
+		///
+		/// # Params
+		///
+		/// - `version`: The storage version that was reached.
+		MigrationCompleted(u16),
+
+		/// A contract was called either by a plain account or another contract.
+		/// \[caller, contract\]
+		///
+		/// # Params
+		///
+		/// - `caller`: The account that called the `contract`.
+		/// - `contract`: The contract that was called.
+		///
+		/// # Note
+		///
+		/// This is emitted once per call frame. Like all events it is only emitted for
+		/// successful calls because all storage changes including events are rolled back
+		/// on failure.
+		Called(T::AccountId, T::AccountId),
+
+		/// A batch of the deletion queue was processed in `on_initialize`.
+		/// \[tries_removed, keys_removed, tries_remaining\]
+		///
+		/// # Params
+		///
+		/// - `tries_removed`: The number of child tries that were completely removed.
+		/// - `keys_removed`: The number of storage items that were removed.
+		/// - `tries_remaining`: The number of child tries still awaiting deletion.
+		///
+		/// # Note
+		///
+		/// This is emitted at most once per block and only when there was something to delete.
+		/// It allows to tune `Config::DeletionWeightLimit` based on on-chain data.
+		DeletionQueueProcessed(u32, u32, u32),
 	}
 
 	#[pallet::error]
@@ -613,6 +719,24 @@ pub mod pallet {
 		StorageExhausted,
 		/// A contract with the same AccountId already exists.
 		DuplicateContract,
+		/// A storage write was rejected because it would have grown the storage of the
+		/// contract beyond the `storage_items` or `storage_size` limit of the schedule.
+		StorageLimitExceeded,
+		/// The code is not allowed to be instantiated by `Config::InstantiateFilter`.
+		CodeNotAllowed,
+		/// Contracts cannot be used until all storage migrations are applied.
+		MigrationInProgress,
+		/// A contract tried to modify state while executing within a read-only call.
+		///
+		/// This happens when a contract called with `CallFlags::STATIC` (or any contract
+		/// called by it) tries to write storage, transfer value, deposit an event, set its
+		/// rent allowance, instantiate, terminate or restore a contract.
+		StateChangeDenied,
+		/// The trie id of a contract is too long to be queued for deletion.
+		///
+		/// Trie ids are generated by the pallet and never exceed the limit. This error
+		/// guards against a changed hasher producing longer ids.
+		TrieIdTooLong,
 	}
 
 	/// Current cost schedule for contracts.
@@ -643,7 +767,16 @@ pub mod pallet {
 	/// Child trie deletion is a heavy operation depending on the amount of storage items
 	/// stored in said trie. Therefore this operation is performed lazily in `on_initialize`.
 	#[pallet::storage]
-	pub type DeletionQueue<T: Config> = StorageValue<_, Vec<DeletedContract>, ValueQuery>;
+	pub type DeletionQueue<T: Config> =
+		StorageValue<_, BoundedVec<DeletedContract, T::DeletionQueueDepth>, ValueQuery>;
+
+	/// The storage version of this pallet.
+	///
+	/// It is only ever written once a [`migration`] has completed. When absent the major
+	/// version of the pallet stored on-chain is assumed. Contracts can't be called or
+	/// instantiated until this reaches [`migration::LATEST_VERSION`].
+	#[pallet::storage]
+	pub type MigrationVersion<T: Config> = StorageValue<_, u16>;
 
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
@@ -664,6 +797,7 @@ pub mod pallet {
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
 			<CurrentSchedule<T>>::put(&self.current_schedule);
+			Migration::<T>::set_latest_version();
 		}
 	}
 }
@@ -685,17 +819,71 @@ where
 		gas_limit: Weight,
 		input_data: Vec<u8>,
 	) -> ContractExecResult {
+		if let Err(err) = Migration::<T>::ensure_migrated() {
+			return ContractExecResult { exec_result: Err(err.into()), gas_consumed: 0 };
+		}
 		let mut gas_meter = GasMeter::new(gas_limit);
 		let schedule = <Module<T>>::current_schedule();
 		let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
 		let result = ctx.call(dest, value, &mut gas_meter, input_data);
 		let gas_consumed = gas_meter.gas_spent();
+		contracts_profiling::record(ProfilingEvent::GasConsumed(gas_consumed));
 		ContractExecResult {
 			exec_result: result.map(|r| r.0).map_err(|r| r.0),
 			gas_consumed,
 		}
 	}
 
+	/// Instantiate a new contract.
+	///
+	/// This function is similar to [`Self::instantiate`] respectively
+	/// [`Self::instantiate_with_code`], but better suitable for calling directly from Rust.
+	/// Next to the execution result it returns the address of the new contract, which
+	/// allows to predict it before submitting the instantiation.
+	pub fn bare_instantiate(
+		origin: T::AccountId,
+		endowment: BalanceOf<T>,
+		gas_limit: Weight,
+		code: Code<CodeHash<T>>,
+		data: Vec<u8>,
+		salt: Vec<u8>,
+	) -> ContractInstantiateResult<T::AccountId> {
+		if let Err(err) = Migration::<T>::ensure_migrated() {
+			return ContractInstantiateResult { exec_result: Err(err.into()), gas_consumed: 0 };
+		}
+		let mut gas_meter = GasMeter::new(gas_limit);
+		let schedule = <Module<T>>::current_schedule();
+		let executable = match code {
+			Code::Upload(binary) if binary.len() as u32 > T::MaxCodeSize::get() =>
+				Err(Error::<T>::CodeTooLarge.into()),
+			Code::Upload(binary) => PrefabWasmModule::from_code(binary, &schedule)
+				.and_then(|executable| {
+					ensure!(
+						executable.code_len() <= T::MaxCodeSize::get(),
+						Error::<T>::CodeTooLarge,
+					);
+					Ok(executable)
+				}),
+			Code::Existing(hash) => PrefabWasmModule::from_storage(hash, &schedule, &mut gas_meter),
+		};
+		let executable = match executable {
+			Ok(executable) => executable,
+			Err(error) => return ContractInstantiateResult {
+				exec_result: Err(error.into()),
+				gas_consumed: gas_meter.gas_spent(),
+			},
+		};
+		let mut ctx = ExecutionContext::<T, PrefabWasmModule<T>>::top_level(origin, &schedule);
+		let result = ctx.instantiate(endowment, &mut gas_meter, executable, data, &salt)
+			.map(|(account_id, result)| InstantiateReturnValue { result, account_id });
+		let gas_consumed = gas_meter.gas_spent();
+		contracts_profiling::record(ProfilingEvent::GasConsumed(gas_consumed));
+		ContractInstantiateResult {
+			exec_result: result,
+			gas_consumed,
+		}
+	}
+
 	/// Query storage of a specified contract under a specified key.
 	pub fn get_storage(address: T::AccountId, key: [u8; 32]) -> GetStorageResult {
 		let contract_info = ContractInfoOf::<T>::get(&address)
@@ -715,24 +903,16 @@ where
 
 	/// Determine the address of a contract,
 	///
-	/// This is the address generation function used by contract instantiation. Its result
-	/// is only dependend on its inputs. It can therefore be used to reliably predict the
-	/// address of a contract. This is akin to the formular of eth's CREATE2 opcode. There
-	/// is no CREATE equivalent because CREATE2 is strictly more powerful.
-	///
-	/// Formula: `hash(deploying_address ++ code_hash ++ salt)`
+	/// This is the address generation function used by contract instantiation. It delegates
+	/// to [`Config::AddressGenerator`] whose result is only dependend on its inputs. It can
+	/// therefore be used to reliably predict the address of a contract.
 	pub fn contract_address(
 		deploying_address: &T::AccountId,
 		code_hash: &CodeHash<T>,
 		salt: &[u8],
 	) -> T::AccountId
 	{
-		let buf: Vec<_> = deploying_address.as_ref().iter()
-			.chain(code_hash.as_ref())
-			.chain(salt)
-			.cloned()
-			.collect();
-		UncheckedFrom::unchecked_from(T::Hashing::hash(&buf))
+		T::AddressGenerator::generate_address(deploying_address, code_hash, salt)
 	}
 
 	/// Subsistence threshold is the extension of the minimum balance (aka existential deposit)
@@ -15,31 +15,236 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Config, Weight, CurrentSchedule, Pallet, Schedule};
-use frame_support::traits::{GetPalletVersion, PalletVersion, Get};
-
-pub fn migrate<T: Config>() -> Weight {
-	let mut weight: Weight = 0;
-
-	match <Pallet<T>>::storage_version() {
-		// Replace the schedule with the new default and increment its version.
-		Some(version) if version == PalletVersion::new(3, 0, 0) => {
-			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
-			let _ = <CurrentSchedule<T>>::translate::<u32, _>(|version| {
-				if let Some(version) = version {
-					Some(Schedule {
-						version: version.saturating_add(1),
-						// Default limits were not decreased. Therefore it is OK to overwrite
-						// the schedule with the new defaults.
-						.. Default::default()
-					})
-				} else {
-					None
-				}
-			});
+//! Storage migrations of this pallet.
+//!
+//! Every storage migration is a [`SteppedMigration`] that is registered with the executive of
+//! the runtime which executes it over multiple blocks after the next runtime upgrade. The
+//! migrations have to be registered in ascending order of their version. A migration that was
+//! already applied is skipped, so that all migrations can stay registered.
+//!
+//! Contracts cannot be called or instantiated until all migrations are applied.
+
+use crate::{
+	Config, Weight, CurrentSchedule, Schedule, Limits, MigrationVersion, Error, Event, Pallet,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::DispatchResult, ensure, storage::unhashed,
+	traits::{Get, GetPalletVersion, SteppedMigration},
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// The storage version that is assumed when neither [`MigrationVersion`] nor the pallet
+/// version was ever written.
+///
+/// This is the storage layout of the last release before pallet versions were tracked.
+pub const LEGACY_VERSION: u16 = 3;
+
+/// The storage version that is reached once all migrations of this module are applied.
+pub const LATEST_VERSION: u16 = 7;
+
+/// Bookkeeping of the storage version of the pallet.
+pub struct Migration<T>(PhantomData<T>);
+
+impl<T: Config> Migration<T> {
+	/// The storage version of the pallet as recorded on-chain.
+	///
+	/// Before the first migration completes this is seeded from the major pallet version
+	/// that is stored on-chain.
+	pub fn on_chain_version() -> u16 {
+		<MigrationVersion<T>>::get()
+			.or_else(|| <Pallet<T>>::storage_version().map(|version| version.major))
+			.unwrap_or(LEGACY_VERSION)
+	}
+
+	/// Mark the storage as fully migrated.
+	///
+	/// Used on genesis where there is no storage that needs to be migrated.
+	pub fn set_latest_version() {
+		<MigrationVersion<T>>::put(LATEST_VERSION);
+	}
+
+	/// Fails with [`Error::MigrationInProgress`] until all migrations are applied.
+	pub fn ensure_migrated() -> DispatchResult {
+		ensure!(Self::on_chain_version() >= LATEST_VERSION, Error::<T>::MigrationInProgress);
+		Ok(())
+	}
+
+	/// Apply `migrate` and record `version` as reached, unless the storage is already at
+	/// `version` or beyond.
+	///
+	/// Returns the weight that was consumed, given the weight of `migrate`.
+	fn migrate_to(version: u16, migrate: impl FnOnce() -> Weight) -> Weight {
+		let mut weight = T::DbWeight::get().reads(2);
+		if Self::on_chain_version() >= version {
+			return weight;
 		}
-		_ => (),
+
+		weight = weight.saturating_add(migrate());
+		<MigrationVersion<T>>::put(version);
+		<Pallet<T>>::deposit_event(Event::MigrationCompleted(version));
+		log::info!(
+			target: "runtime::contracts",
+			"Migration to storage version {} completed",
+			version,
+		);
+		weight.saturating_add(T::DbWeight::get().writes(2))
 	}
+}
+
+/// Migrates the stored schedule to storage version `V`.
+///
+/// All storage versions so far only changed the encoding of the schedule. The changes of every
+/// version are described by the `vN` module naming an instance of this migration. Instances for
+/// any other version only record the version.
+pub struct MigrateSchedule<T, const V: u16>(PhantomData<T>);
+
+impl<T: Config, const V: u16> SteppedMigration for MigrateSchedule<T, V> {
+	type Cursor = ();
+	const ID: &'static [u8] = match V {
+		4 => b"pallet_contracts::v4" as &[u8],
+		6 => b"pallet_contracts::v6",
+		7 => b"pallet_contracts::v7",
+		_ => b"pallet_contracts::unknown",
+	};
 
-	weight
+	fn step(_cursor: Option<()>, _remaining_weight: Weight) -> (Option<()>, Weight) {
+		let weight = Migration::<T>::migrate_to(V, || match V {
+			4 => reset_schedule::<T>(),
+			6 => translate_schedule::<T, _>(add_storage_limits::<T>),
+			7 => translate_schedule::<T, _>(add_host_fn_weights::<T>),
+			_ => 0,
+		});
+		(None, weight)
+	}
+}
+
+/// Migration to storage version 4.
+///
+/// Replaces the schedule with the new default and increments its version.
+pub mod v4 {
+	/// Migrates the stored schedule to storage version 4.
+	pub type Migration<T> = super::MigrateSchedule<T, 4>;
+}
+
+/// Migration to storage version 6.
+///
+/// Adds the new limits to the stored schedule, keeping all of its other values. The
+/// [`Limits::storage_items`](crate::Limits::storage_items) and
+/// [`Limits::storage_size`](crate::Limits::storage_size) fields were added which changes the
+/// encoding of the schedule.
+pub mod v6 {
+	/// Migrates the stored schedule to storage version 6.
+	pub type Migration<T> = super::MigrateSchedule<T, 6>;
+}
+
+/// Migration to storage version 7.
+///
+/// Adds the weights of the new host functions to the stored schedule, keeping all of its other
+/// values. The [`HostFnWeights::own_code_hash`](crate::HostFnWeights::own_code_hash),
+/// [`HostFnWeights::reentrance_count`](crate::HostFnWeights::reentrance_count) and
+/// [`HostFnWeights::account_reentrance_count`](crate::HostFnWeights::account_reentrance_count)
+/// fields were added which changes the encoding of the schedule.
+pub mod v7 {
+	/// Migrates the stored schedule to storage version 7.
+	pub type Migration<T> = super::MigrateSchedule<T, 7>;
+}
+
+/// [`Limits`] before the storage limits were added in version 6.
+#[derive(Decode)]
+struct LimitsV5 {
+	event_topics: u32,
+	stack_height: u32,
+	globals: u32,
+	parameters: u32,
+	memory_pages: u32,
+	table_size: u32,
+	br_table_size: u32,
+	subject_len: u32,
+}
+
+/// Add the default storage limits to a schedule encoded before version 6.
+fn add_storage_limits<T: Config>(old: &[u8]) -> Option<Vec<u8>> {
+	// The limits follow the version and `enable_println`, everything after them is kept as is.
+	let mut input = old;
+	let (version, enable_println, limits) = <(u32, bool, LimitsV5)>::decode(&mut input).ok()?;
+	let limits = Limits {
+		event_topics: limits.event_topics,
+		stack_height: limits.stack_height,
+		globals: limits.globals,
+		parameters: limits.parameters,
+		memory_pages: limits.memory_pages,
+		table_size: limits.table_size,
+		br_table_size: limits.br_table_size,
+		subject_len: limits.subject_len,
+		.. Schedule::<T>::default().limits
+	};
+	let mut new = (version, enable_println, limits).encode();
+	new.extend_from_slice(input);
+	Some(new)
+}
+
+/// Add the default weights of the new host functions to a schedule encoded before version 7.
+fn add_host_fn_weights<T: Config>(old: &[u8]) -> Option<Vec<u8>> {
+	// The host function weights are the last field of the schedule, the new ones are appended.
+	let defaults = Schedule::<T>::default().host_fn_weights;
+	let mut new = old.to_vec();
+	defaults.own_code_hash.encode_to(&mut new);
+	defaults.reentrance_count.encode_to(&mut new);
+	defaults.account_reentrance_count.encode_to(&mut new);
+	Some(new)
+}
+
+/// Translate the raw encoding of the stored schedule with `translate`.
+///
+/// The schedule only consists of fixed size fields, so a schedule that already decodes as the
+/// latest [`Schedule`] was written by an earlier migration and is left untouched. If `translate`
+/// fails the schedule can't be recovered and is replaced with the default schedule.
+fn translate_schedule<T, F>(translate: F) -> Weight
+where
+	T: Config,
+	F: FnOnce(&[u8]) -> Option<Vec<u8>>,
+{
+	let key = <CurrentSchedule<T>>::hashed_key();
+	let old = match unhashed::get_raw(&key) {
+		Some(old) => old,
+		None => return T::DbWeight::get().reads(1),
+	};
+
+	let mut input = &old[..];
+	if Schedule::<T>::decode(&mut input).is_ok() && input.is_empty() {
+		return T::DbWeight::get().reads(1);
+	}
+
+	match translate(&old) {
+		Some(new) => unhashed::put_raw(&key, &new),
+		None => {
+			log::error!(
+				target: "runtime::contracts",
+				"Stored schedule could not be migrated, replacing it with the default schedule",
+			);
+			<CurrentSchedule<T>>::put(Schedule::<T>::default());
+		},
+	}
+	T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Overwrite the stored schedule with the default schedule and increment its version.
+///
+/// Only the version is decoded from the stored schedule which is its first field. This works
+/// regardless of how the encoding of the rest of the schedule changed.
+fn reset_schedule<T: Config>() -> Weight {
+	let _ = <CurrentSchedule<T>>::translate::<u32, _>(|version| {
+		if let Some(version) = version {
+			Some(Schedule {
+				version: version.saturating_add(1),
+				// Default limits were not decreased. Therefore it is OK to overwrite
+				// the schedule with the new defaults.
+				.. Default::default()
+			})
+		} else {
+			None
+		}
+	});
+	T::DbWeight::get().reads_writes(1, 1)
 }
@@ -309,6 +309,29 @@ where
 		Self::enact_verdict(account, contract, current_block_number, verdict, None)
 	}
 
+	/// Same as [`charge`] but leaves the storage unmodified.
+	///
+	/// Used for read-only calls which cannot pay rent. It still returns `None` in case the
+	/// contract is in need of eviction.
+	pub fn check(
+		account: &T::AccountId,
+		contract: AliveContractInfo<T>,
+		code_size: u32,
+	) -> Option<AliveContractInfo<T>> {
+		let current_block_number = <frame_system::Module<T>>::block_number();
+		let verdict = Self::consider_case(
+			account,
+			current_block_number,
+			Zero::zero(),
+			&contract,
+			code_size,
+		);
+		match verdict {
+			Verdict::Evict { .. } => None,
+			Verdict::Exempt | Verdict::Charge { .. } => Some(contract),
+		}
+	}
+
 	/// Process a report that a contract under the given address should be evicted.
 	///
 	/// Enact the eviction right away if the contract should be evicted and return the amount
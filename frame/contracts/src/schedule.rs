@@ -117,6 +117,16 @@ pub struct Limits {
 
 	/// The maximum length of a subject in bytes used for PRNG generation.
 	pub subject_len: u32,
+
+	/// The maximum number of storage items a single contract can hold.
+	///
+	/// Writes that would add a new item beyond this limit fail.
+	pub storage_items: u32,
+
+	/// The maximum accumulated size in bytes of all storage values a single contract can hold.
+	///
+	/// Writes that would grow the storage of a contract beyond this limit fail.
+	pub storage_size: u32,
 }
 
 impl Limits {
@@ -365,6 +375,15 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight of calling `seal_rent_params`.
 	pub rent_params: Weight,
 
+	/// Weight of calling `seal_own_code_hash`.
+	pub own_code_hash: Weight,
+
+	/// Weight of calling `seal_reentrance_count`.
+	pub reentrance_count: Weight,
+
+	/// Weight of calling `seal_account_reentrance_count`.
+	pub account_reentrance_count: Weight,
+
 	/// The type parameter is used in the default implementation.
 	#[codec(skip)]
 	pub _phantom: PhantomData<T>
@@ -472,6 +491,9 @@ impl Default for Limits {
 			table_size: 4096,
 			br_table_size: 256,
 			subject_len: 32,
+			storage_items: 32 * 1024,
+			// 32 MiB
+			storage_size: 32 * 1024 * 1024,
 		}
 	}
 }
@@ -591,6 +613,9 @@ impl<T: Config> Default for HostFnWeights<T> {
 			hash_blake2_128: cost_batched!(seal_hash_blake2_128),
 			hash_blake2_128_per_byte: cost_byte_batched!(seal_hash_blake2_128_per_kb),
 			rent_params: cost_batched!(seal_rent_params),
+			own_code_hash: cost_batched!(seal_own_code_hash),
+			reentrance_count: cost_batched!(seal_reentrance_count),
+			account_reentrance_count: cost_batched!(seal_account_reentrance_count),
 			_phantom: PhantomData,
 		}
 	}
@@ -20,20 +20,22 @@
 use crate::{
 	exec::{AccountIdOf, StorageKey},
 	AliveContractInfo, BalanceOf, CodeHash, ContractInfo, ContractInfoOf, Config, TrieId,
-	AccountCounter, DeletionQueue, Error,
+	AccountCounter, DeletionQueue, Error, Event, Limits, Module,
 	weights::WeightInfo,
 };
 use codec::{Encode, Decode};
 use sp_std::prelude::*;
-use sp_std::marker::PhantomData;
+use sp_std::{convert::TryInto, marker::PhantomData};
 use sp_io::hashing::blake2_256;
 use sp_runtime::traits::{Bounded, Saturating, Zero};
 use sp_core::crypto::UncheckedFrom;
 use frame_support::{
 	dispatch::{DispatchError, DispatchResult},
-	storage::child::{self, KillChildStorageResult},
-	traits::Get,
+	storage::{child::{self, KillChildStorageResult}, unhashed},
+	BoundedVec,
+	traits::{ConstU32, Get},
 	weights::Weight,
+	ensure,
 };
 
 /// An error that means that the account requested either doesn't exist or represents a tombstone
@@ -41,10 +43,21 @@ use frame_support::{
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
 pub struct ContractAbsentError;
 
+/// The maximum length of a trie id in the deletion queue.
+///
+/// Trie ids are the output of [`Config::Hashing`](frame_system::Config::Hashing) which stays
+/// well below this limit for all hashers in use.
+pub type MaxTrieIdLen = ConstU32<128>;
+
+/// A trie id bounded by [`MaxTrieIdLen`].
+///
+/// It encodes the same as [`TrieId`] so that no migration of the deletion queue is needed.
+pub type DeletedTrieId = BoundedVec<u8, MaxTrieIdLen>;
+
 #[derive(Encode, Decode)]
 pub struct DeletedContract {
 	pair_count: u32,
-	trie_id: TrieId,
+	trie_id: DeletedTrieId,
 }
 
 pub struct Storage<T>(PhantomData<T>);
@@ -72,6 +85,10 @@ where
 	///
 	/// If the contract specified by the id `account` doesn't exist `Err` is returned.`
 	///
+	/// A write that grows the number of storage items or the total storage size of the
+	/// contract beyond `limits.storage_items` or `limits.storage_size` respectively fails
+	/// with `StorageLimitExceeded`. Writes that shrink the storage are always allowed.
+	///
 	/// # Panics
 	///
 	/// Panics iff the `account` specified is not alive and in storage.
@@ -80,6 +97,7 @@ where
 		trie_id: &TrieId,
 		key: &StorageKey,
 		opt_new_value: Option<Vec<u8>>,
+		limits: &Limits,
 	) -> DispatchResult {
 		let mut new_info = match <ContractInfoOf<T>>::get(account) {
 			Some(ContractInfo::Alive(alive)) => alive,
@@ -100,6 +118,10 @@ where
 			(None, Some(_)) => {
 				new_info.pair_count = new_info.pair_count.checked_add(1)
 					.ok_or_else(|| Error::<T>::StorageExhausted)?;
+				ensure!(
+					new_info.pair_count <= limits.storage_items,
+					Error::<T>::StorageLimitExceeded,
+				);
 			},
 			(Some(_), Some(_)) => {},
 			(None, None) => {},
@@ -116,6 +138,10 @@ where
 			.checked_sub(prev_value_len)
 			.and_then(|val| val.checked_add(new_value_len))
 			.ok_or_else(|| Error::<T>::StorageExhausted)?;
+		ensure!(
+			new_value_len <= prev_value_len || new_info.storage_size <= limits.storage_size,
+			Error::<T>::StorageLimitExceeded,
+		);
 
 		new_info.last_write = Some(<frame_system::Module<T>>::block_number());
 		<ContractInfoOf<T>>::insert(&account, ContractInfo::Alive(new_info));
@@ -195,15 +221,13 @@ where
 	/// You must make sure that the contract is also removed or converted into a tombstone
 	/// when queuing the trie for deletion.
 	pub fn queue_trie_for_deletion(contract: &AliveContractInfo<T>) -> DispatchResult {
-		if <DeletionQueue<T>>::decode_len().unwrap_or(0) >= T::DeletionQueueDepth::get() as usize {
-			Err(Error::<T>::DeletionQueueFull.into())
-		} else {
-			<DeletionQueue<T>>::append(DeletedContract {
-				pair_count: contract.pair_count,
-				trie_id: contract.trie_id.clone(),
-			});
-			Ok(())
-		}
+		let trie_id = contract.trie_id.clone().try_into()
+			.map_err(|_| Error::<T>::TrieIdTooLong)?;
+		<DeletionQueue<T>>::try_append(DeletedContract {
+			pair_count: contract.pair_count,
+			trie_id,
+		})
+		.map_err(|_| Error::<T>::DeletionQueueFull.into())
 	}
 
 	/// Calculates the weight that is necessary to remove one key from the trie and how many
@@ -250,7 +274,9 @@ where
 			return weight_limit;
 		}
 
-		let mut queue = <DeletionQueue<T>>::get();
+		let mut queue = Self::deletion_queue();
+		let mut tries_removed = 0u32;
+		let mut keys_removed = 0u32;
 
 		while !queue.is_empty() && remaining_key_budget > 0 {
 			// Cannot panic due to loop condition
@@ -260,6 +286,10 @@ where
 				&crate::child_trie_info(&trie.trie_id),
 				Some(remaining_key_budget),
 			);
+			keys_removed = keys_removed.saturating_add(match outcome {
+				KillChildStorageResult::AllRemoved(removed) |
+				KillChildStorageResult::SomeRemaining(removed) => removed,
+			});
 			if pair_count > remaining_key_budget {
 				// Cannot underflow because of the if condition
 				trie.pair_count -= remaining_key_budget;
@@ -267,6 +297,7 @@ where
 				// We do not care to preserve order. The contract is deleted already and
 				// noone waits for the trie to be deleted.
 				let removed = queue.swap_remove(0);
+				tries_removed = tries_removed.saturating_add(1);
 				match outcome {
 					// This should not happen as our budget was large enough to remove all keys.
 					KillChildStorageResult::SomeRemaining(_) => {
@@ -283,10 +314,40 @@ where
 				.saturating_sub(remaining_key_budget.min(pair_count));
 		}
 
-		<DeletionQueue<T>>::put(queue);
+		<Module<T>>::deposit_event(Event::DeletionQueueProcessed(
+			tries_removed,
+			keys_removed,
+			queue.len() as u32,
+		));
+		if queue.len() <= BoundedVec::<DeletedContract, T::DeletionQueueDepth>::bound() {
+			let queue: BoundedVec<_, _> = queue.try_into()
+				.expect("The length was checked against the bound; qed");
+			<DeletionQueue<T>>::put(queue);
+		} else {
+			// Still over the depth: Keep the excess until it is drained by later batches.
+			unhashed::put(&<DeletionQueue<T>>::hashed_key(), &queue);
+		}
 		weight_limit.saturating_sub(weight_per_key.saturating_mul(remaining_key_budget as Weight))
 	}
 
+	/// The items of the deletion queue.
+	///
+	/// The queue can hold more items than [`Config::DeletionQueueDepth`] when it was lowered
+	/// by a runtime upgrade. The queue then fails to decode as a bounded vector. It is decoded
+	/// without the bound instead so that no trie is leaked. Appending to such a queue fails
+	/// until it is drained below its depth.
+	fn deletion_queue() -> Vec<DeletedContract> {
+		<DeletionQueue<T>>::try_get()
+			.map(|queue| queue.into_inner())
+			.unwrap_or_else(|_| {
+				log::warn!(
+					target: "runtime::contracts",
+					"The deletion queue exceeds its depth. It will be drained in later blocks.",
+				);
+				unhashed::get(&<DeletionQueue<T>>::hashed_key()).unwrap_or_default()
+			})
+	}
+
 	/// This generator uses inner counter for account id and applies the hash over `AccountId +
 	/// accountid_counter`.
 	pub fn generate_trie_id(account_id: &AccountIdOf<T>) -> TrieId {
@@ -319,9 +380,22 @@ where
 	pub fn fill_queue_with_dummies() {
 		let queue: Vec<_> = (0..T::DeletionQueueDepth::get()).map(|_| DeletedContract {
 			pair_count: 0,
-			trie_id: vec![],
+			trie_id: Default::default(),
+		})
+		.collect();
+		let bounded: frame_support::BoundedVec<_, _> = queue.try_into()
+			.expect("The number of dummies is equal to the bound");
+		<DeletionQueue<T>>::put(bounded);
+	}
+
+	/// Queue the tries beyond the depth of the queue as if it was lowered by a runtime upgrade.
+	#[cfg(test)]
+	pub fn overfill_queue(tries: &[TrieId]) {
+		let queue: Vec<_> = tries.iter().map(|trie_id| DeletedContract {
+			pair_count: 1,
+			trie_id: trie_id.clone().try_into().expect("Test tries are shorter than the bound"),
 		})
 		.collect();
-		<DeletionQueue<T>>::put(queue);
+		unhashed::put(&<DeletionQueue<T>>::hashed_key(), &queue);
 	}
 }
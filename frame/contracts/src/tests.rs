@@ -16,8 +16,9 @@
 // limitations under the License.
 
 use crate::{
-	BalanceOf, ContractInfo, ContractInfoOf, Module,
-	RawAliveContractInfo, Config, Schedule,
+	BalanceOf, ContractInfo, ContractInfoOf, Module, Code,
+	RawAliveContractInfo, Config, Schedule, AddressGenerator, DefaultAddressGenerator, Limits,
+	MigrationVersion, migration, InstructionWeights, HostFnWeights,
 	Error, storage::Storage,
 	chain_extension::{
 		Result as ExtensionResult, Environment, ChainExtension, Ext, SysConfig, RetVal,
@@ -28,6 +29,7 @@ use crate::{
 	wasm::ReturnCode as RuntimeReturnCode,
 };
 use assert_matches::assert_matches;
+use pallet_contracts_primitives::CallFlags;
 use codec::Encode;
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
@@ -38,7 +40,7 @@ use sp_io::hashing::blake2_256;
 use frame_support::{
 	assert_ok, assert_err, assert_err_ignore_postinfo,
 	parameter_types, assert_storage_noop,
-	traits::{Currency, ReservableCurrency, OnInitialize, GenesisBuild},
+	traits::{Currency, ReservableCurrency, OnInitialize, GenesisBuild, Filter, SteppedMigration},
 	weights::{Weight, PostDispatchInfo, DispatchClass, constants::WEIGHT_PER_SECOND},
 	dispatch::DispatchErrorWithPostInfo,
 	storage::child,
@@ -69,7 +71,7 @@ frame_support::construct_runtime!(
 pub mod test_utils {
 	use super::{Test, Balances};
 	use crate::{
-		ContractInfoOf, CodeHash,
+		ContractInfoOf, CodeHash, Limits,
 		storage::Storage,
 		exec::{StorageKey, AccountIdOf},
 		Module as Contracts,
@@ -78,7 +80,8 @@ pub mod test_utils {
 
 	pub fn set_storage(addr: &AccountIdOf<Test>, key: &StorageKey, value: Option<Vec<u8>>) {
 		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
-		Storage::<Test>::write(addr, &contract_info.trie_id, key, value).unwrap();
+		Storage::<Test>::write(addr, &contract_info.trie_id, key, value, &Limits::default())
+			.unwrap();
 	}
 	pub fn get_storage(addr: &AccountIdOf<Test>, key: &StorageKey) -> Option<Vec<u8>> {
 		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
@@ -114,6 +117,55 @@ pub mod test_utils {
 
 thread_local! {
 	static TEST_EXTENSION: sp_std::cell::RefCell<TestExtension> = Default::default();
+	static BLOCKED_CODE: sp_std::cell::RefCell<Option<CodeHash<Test>>> = Default::default();
+	static IGNORE_DEPLOYER: sp_std::cell::Cell<bool> = Default::default();
+}
+
+/// Address generator that ignores the deploying address once
+/// [`TestAddressGenerator::ignore_deployer`] was called.
+pub struct TestAddressGenerator;
+
+impl TestAddressGenerator {
+	/// The deploying address that is used in place of the actual one.
+	const DEPLOYER: AccountId32 = AccountId32::new([0u8; 32]);
+
+	pub fn ignore_deployer() {
+		IGNORE_DEPLOYER.with(|i| i.set(true));
+	}
+}
+
+impl AddressGenerator<Test> for TestAddressGenerator {
+	fn generate_address(
+		deploying_address: &AccountId32,
+		code_hash: &CodeHash<Test>,
+		salt: &[u8],
+	) -> AccountId32 {
+		let deploying_address = if IGNORE_DEPLOYER.with(|i| i.get()) {
+			&Self::DEPLOYER
+		} else {
+			deploying_address
+		};
+		<DefaultAddressGenerator as AddressGenerator<Test>>::generate_address(
+			deploying_address,
+			code_hash,
+			salt,
+		)
+	}
+}
+
+/// Instantiate filter that rejects the code hash set by [`TestInstantiateFilter::block`].
+pub struct TestInstantiateFilter;
+
+impl TestInstantiateFilter {
+	pub fn block(code_hash: CodeHash<Test>) {
+		BLOCKED_CODE.with(|b| *b.borrow_mut() = Some(code_hash));
+	}
+}
+
+impl Filter<CodeHash<Test>> for TestInstantiateFilter {
+	fn filter(code_hash: &CodeHash<Test>) -> bool {
+		BLOCKED_CODE.with(|b| b.borrow().as_ref() != Some(code_hash))
+	}
 }
 
 pub struct TestExtension {
@@ -175,6 +227,14 @@ impl ChainExtension<Test> for TestExtension {
 				env.charge_weight(weight)?;
 				Ok(RetVal::Converging(func_id))
 			},
+			4 => {
+				// charge the worst case and refund down to the amount supplied in the second byte
+				let mut env = env.buf_in_buf_out();
+				let charged = env.charge_weight(u8::MAX.into())?;
+				let weight = env.read(2)?[1].into();
+				env.adjust_weight(charged, weight);
+				Ok(RetVal::Converging(func_id))
+			},
 			3 => {
 				Ok(RetVal::Diverging{
 					flags: ReturnFlags::REVERT,
@@ -224,6 +284,8 @@ impl frame_system::Config for Test {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -286,6 +348,8 @@ impl Config for Test {
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type MaxCodeSize = MaxCodeSize;
+	type AddressGenerator = TestAddressGenerator;
+	type InstantiateFilter = TestInstantiateFilter;
 }
 
 pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
@@ -450,7 +514,7 @@ fn account_removal_does_not_remove_storage() {
 
 #[test]
 fn instantiate_and_call_and_deposit_event() {
-	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	let (wasm, code_hash) = compile_module::<Test>("event_and_return_on_deploy").unwrap();
 
 	ExtBuilder::default()
 		.existential_deposit(100)
@@ -1495,11 +1559,16 @@ fn restoration(
 						phase: Phase::Initialization,
 						event: Event::pallet_contracts(
 							crate::Event::Restored(
-								addr_django, addr_bob, bob_contract.code_hash, 50
+								addr_django.clone(), addr_bob, bob_contract.code_hash, 50
 							)
 						),
 						topics: vec![],
 					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: Event::pallet_contracts(crate::Event::Called(ALICE, addr_django)),
+						topics: vec![],
+					},
 				]);
 			}
 		});
@@ -1745,6 +1814,11 @@ fn self_destruct_works() {
 					),
 					topics: vec![],
 				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet_contracts(crate::Event::Called(ALICE, addr.clone())),
+					topics: vec![],
+				},
 			]);
 
 			// Check that account is gone
@@ -2031,6 +2105,92 @@ fn call_return_code() {
 	});
 }
 
+#[test]
+fn call_with_flags_works() {
+	let (caller_code, caller_hash) = compile_module::<Test>("call_with_flags").unwrap();
+	let (store_code, store_hash) = compile_module::<Test>("storage_size").unwrap();
+	let (ok_code, ok_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		for code in vec![caller_code, store_code, ok_code] {
+			assert_ok!(
+				Contracts::instantiate_with_code(
+					Origin::signed(ALICE),
+					subsistence * 100,
+					GAS_LIMIT,
+					code,
+					vec![0],
+					vec![],
+				),
+			);
+		}
+		let addr_caller = Contracts::contract_address(&ALICE, &caller_hash, &[]);
+		let addr_store = Contracts::contract_address(&ALICE, &store_hash, &[]);
+		let addr_ok = Contracts::contract_address(&ALICE, &ok_hash, &[]);
+		let input = |flags: u32, callee: &AccountId32| -> Vec<u8> {
+			flags.to_le_bytes().iter()
+				.chain(AsRef::<[u8]>::as_ref(callee))
+				.chain(&4u32.to_le_bytes())
+				.cloned()
+				.collect()
+		};
+		let mut key = [0u8; 32];
+		key[0] = 1;
+		initialize_block(2);
+
+		// The callee traps when it modifies storage during a static call.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr_caller.clone(),
+			0,
+			GAS_LIMIT,
+			input(CallFlags::STATIC.bits(), &addr_store),
+		).exec_result.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
+		assert_eq!(Contracts::get_storage(addr_store.clone(), key), Ok(None));
+
+		// Without flags the callee can modify storage.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr_caller.clone(),
+			0,
+			GAS_LIMIT,
+			input(0, &addr_store),
+		).exec_result.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		assert_eq!(Contracts::get_storage(addr_store, key), Ok(Some(vec![1, 0, 0, 0])));
+
+		// A static call succeeds as long as the callee does not modify state. It is not
+		// recorded as an event.
+		System::reset_events();
+		let result = Contracts::bare_call(
+			ALICE,
+			addr_caller.clone(),
+			0,
+			GAS_LIMIT,
+			input(CallFlags::STATIC.bits(), &addr_ok),
+		).exec_result.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		assert_eq!(
+			System::events().into_iter().map(|r| r.event).collect::<Vec<_>>(),
+			vec![Event::pallet_contracts(crate::Event::Called(ALICE, addr_caller.clone()))],
+		);
+
+		// Reserved flags trap the caller.
+		assert_err!(
+			Contracts::bare_call(
+				ALICE,
+				addr_caller,
+				0,
+				GAS_LIMIT,
+				input(1 << 1, &addr_ok),
+			).exec_result.map_err(|e| e.error),
+			sp_runtime::DispatchError::Other("used reserved bit in call flags"),
+		);
+	});
+}
+
 #[test]
 fn instantiate_return_code() {
 	let (caller_code, caller_hash) = compile_module::<Test>("instantiate_return_code").unwrap();
@@ -2241,6 +2401,17 @@ fn chain_extension_works() {
 		).exec_result.unwrap();
 		assert_eq!(result.flags, ReturnFlags::REVERT);
 		assert_eq!(result.data, vec![42, 99]);
+
+		// 4 = charge the worst case and refund down to the amount supplied in the second byte
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			vec![4, 42],
+		);
+		assert_ok!(result.exec_result);
+		assert_eq!(result.gas_consumed, gas_consumed + 42);
 	});
 }
 
@@ -2289,6 +2460,12 @@ fn lazy_removal_works() {
 
 		// Value should be gone now
 		assert_matches!(child::get::<i32>(trie, &[99]), None);
+
+		// The whole trie was removed and nothing is left in the queue
+		assert_matches!(
+			System::events().last().map(|r| r.event.clone()),
+			Some(Event::pallet_contracts(crate::Event::DeletionQueueProcessed(1, _, 0)))
+		);
 	});
 }
 
@@ -2333,6 +2510,7 @@ fn lazy_removal_partial_remove_works() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				&Limits::default(),
 			).unwrap();
 		}
 
@@ -2382,6 +2560,12 @@ fn lazy_removal_partial_remove_works() {
 		assert_eq!(num_deleted + num_remaining, vals.len() as u32);
 		assert_eq!(num_deleted, max_keys);
 		assert_eq!(num_remaining, extra_keys);
+
+		// The trie is only partially removed and stays in the queue
+		assert_eq!(
+			System::events().last().map(|r| r.event.clone()),
+			Some(Event::pallet_contracts(crate::Event::DeletionQueueProcessed(0, max_keys, 1))),
+		);
 	});
 }
 
@@ -2421,6 +2605,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				&Limits::default(),
 			).unwrap();
 		}
 
@@ -2506,6 +2691,7 @@ fn lazy_removal_does_not_use_all_weight() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				&Limits::default(),
 			).unwrap();
 		}
 
@@ -2591,6 +2777,70 @@ fn deletion_queue_full() {
 	});
 }
 
+#[test]
+fn deletion_queue_exceeding_its_depth_is_drained() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let tries: Vec<crate::TrieId> = (0..<Test as Config>::DeletionQueueDepth::get() + 2)
+			.map(|i| i.encode())
+			.collect();
+		for trie_id in &tries {
+			child::put(&crate::child_trie_info(trie_id), &[99], &42);
+		}
+		Storage::<Test>::overfill_queue(&tries);
+		assert!(crate::DeletionQueue::<Test>::try_get().is_err());
+
+		let len = tries.len() as u32;
+		let weight_limit = <<Test as Config>::WeightInfo as WeightInfo>::on_initialize() +
+			<<Test as Config>::WeightInfo as WeightInfo>::on_initialize_per_queue_item(len) +
+			<<Test as Config>::WeightInfo as WeightInfo>::on_initialize_per_trie_key(len);
+		Storage::<Test>::process_deletion_queue_batch(weight_limit);
+
+		// No trie was leaked.
+		for trie_id in &tries {
+			assert_eq!(child::get::<i32>(&crate::child_trie_info(trie_id), &[99]), None);
+		}
+		assert_eq!(crate::DeletionQueue::<Test>::decode_len(), Some(0));
+		assert_matches!(
+			System::events().last().map(|r| r.event.clone()),
+			Some(Event::pallet_contracts(crate::Event::DeletionQueueProcessed(n, _, 0)))
+				if n == len
+		);
+	});
+}
+
+#[test]
+fn overlong_trie_id_is_not_queued_for_deletion() {
+	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = Module::<Test>::subsistence_threshold();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		assert_ok!(
+			Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				subsistence * 100,
+				GAS_LIMIT,
+				code,
+				vec![],
+				vec![],
+			),
+		);
+		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
+		let mut info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+
+		info.trie_id = vec![0; crate::storage::MaxTrieIdLen::get() as usize + 1];
+		assert_err!(
+			Storage::<Test>::queue_trie_for_deletion(&info),
+			Error::<Test>::TrieIdTooLong,
+		);
+		assert_eq!(crate::DeletionQueue::<Test>::decode_len(), None);
+
+		info.trie_id.pop();
+		assert_ok!(Storage::<Test>::queue_trie_for_deletion(&info));
+		assert_eq!(crate::DeletionQueue::<Test>::decode_len(), Some(1));
+	});
+}
+
 #[test]
 fn not_deployed_if_endowment_too_low_for_first_rent() {
 	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
@@ -2800,3 +3050,244 @@ fn reinstrument_does_charge() {
 		);
 	});
 }
+
+#[test]
+fn storage_limits_are_enforced() {
+	ExtBuilder::default().build().execute_with(|| {
+		test_utils::place_contract(&BOB, Default::default());
+		let trie_id = ContractInfoOf::<Test>::get(&BOB).unwrap().get_alive().unwrap().trie_id;
+		let limits = Limits { storage_items: 2, storage_size: 8, .. Default::default() };
+		let write = |key: u8, value: Option<Vec<u8>>| {
+			Storage::<Test>::write(&BOB, &trie_id, &[key; 32], value, &limits)
+		};
+
+		assert_ok!(write(1, Some(vec![1; 4])));
+		assert_ok!(write(2, Some(vec![2; 4])));
+
+		// Adding a third item exceeds the item limit.
+		assert_err!(write(3, Some(vec![3; 1])), Error::<Test>::StorageLimitExceeded);
+
+		// Growing an existing item exceeds the size limit.
+		assert_err!(write(1, Some(vec![1; 5])), Error::<Test>::StorageLimitExceeded);
+
+		// Shrinking and removing items is always possible and makes room for new ones.
+		assert_ok!(write(1, Some(vec![1; 2])));
+		assert_ok!(write(2, None));
+		assert_ok!(write(3, Some(vec![3; 6])));
+
+		let info = ContractInfoOf::<Test>::get(&BOB).unwrap().get_alive().unwrap();
+		assert_eq!(info.pair_count, 2);
+		assert_eq!(info.storage_size, 8);
+	});
+}
+
+#[test]
+fn instantiate_filter_rejects_code() {
+	let (wasm, code_hash) = compile_module::<Test>("event_and_return_on_deploy").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		TestInstantiateFilter::block(code_hash);
+
+		assert_err_ignore_postinfo!(
+			Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				wasm,
+				vec![],
+				vec![],
+			),
+			Error::<Test>::CodeNotAllowed,
+		);
+		assert!(crate::CodeStorage::<Test>::get(&code_hash).is_none());
+		assert!(ContractInfoOf::<Test>::get(
+			&Contracts::contract_address(&ALICE, &code_hash, &[])
+		).is_none());
+	});
+}
+
+#[test]
+fn instantiate_uses_configured_address_generator() {
+	let (wasm, code_hash) = compile_module::<Test>("event_and_return_on_deploy").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		TestAddressGenerator::ignore_deployer();
+
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			100_000,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![42],
+		));
+
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[42]);
+		assert_eq!(addr, Contracts::contract_address(&BOB, &code_hash, &[42]));
+		assert_ne!(
+			addr,
+			<DefaultAddressGenerator as AddressGenerator<Test>>::generate_address(
+				&ALICE,
+				&code_hash,
+				&[42],
+			),
+		);
+		assert_matches!(ContractInfoOf::<Test>::get(&addr), Some(ContractInfo::Alive(_)));
+	});
+}
+
+#[test]
+fn genesis_is_fully_migrated() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(MigrationVersion::<Test>::get(), Some(migration::LATEST_VERSION));
+		assert_ok!(migration::Migration::<Test>::ensure_migrated());
+	});
+}
+
+#[test]
+fn contracts_are_locked_until_migrated() {
+	let (wasm, code_hash) = compile_module::<Test>("event_and_return_on_deploy").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			100_000,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		// Pretend that the chain was upgraded from the version before the last migration.
+		MigrationVersion::<Test>::put(migration::LATEST_VERSION - 1);
+
+		// The pallet is unusable while the migration is pending.
+		assert_err_ignore_postinfo!(
+			Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, vec![]),
+			Error::<Test>::MigrationInProgress,
+		);
+
+		// Migrations that were already applied are skipped.
+		let schedule = crate::CurrentSchedule::<Test>::get();
+		assert_eq!(migration::v6::Migration::<Test>::step(None, Weight::max_value()).0, None);
+		assert_eq!(crate::CurrentSchedule::<Test>::get(), schedule);
+
+		assert_eq!(migration::v7::Migration::<Test>::step(None, Weight::max_value()).0, None);
+		assert_eq!(MigrationVersion::<Test>::get(), Some(migration::LATEST_VERSION));
+		assert!(System::events().iter().any(|record|
+			record.event == Event::pallet_contracts(
+				crate::Event::MigrationCompleted(migration::LATEST_VERSION)
+			)
+		));
+		assert_ok!(Contracts::call(Origin::signed(ALICE), addr, 0, GAS_LIMIT, vec![]));
+	});
+}
+
+#[test]
+fn missing_migration_version_is_seeded_from_pallet_version() {
+	ExtBuilder::default().build().execute_with(|| {
+		MigrationVersion::<Test>::kill();
+		frame_support::traits::PalletVersion::new(migration::LATEST_VERSION - 1, 0, 0)
+			.put_into_storage::<PalletInfo, Contracts>();
+		assert_eq!(
+			migration::Migration::<Test>::on_chain_version(),
+			migration::LATEST_VERSION - 1,
+		);
+		assert_err!(
+			migration::Migration::<Test>::ensure_migrated(),
+			Error::<Test>::MigrationInProgress,
+		);
+	});
+}
+
+#[test]
+fn legacy_schedule_migration_replaces_schedule_and_bumps_version() {
+	ExtBuilder::default().build().execute_with(|| {
+		// A schedule stored with an outdated encoding: Only the version can be decoded.
+		let key = crate::CurrentSchedule::<Test>::hashed_key();
+		frame_support::storage::unhashed::put_raw(&key, &(7u32, [0xffu8; 12]).encode());
+		assert!(crate::CurrentSchedule::<Test>::try_get().is_err());
+
+		MigrationVersion::<Test>::put(migration::LEGACY_VERSION);
+		migration::v4::Migration::<Test>::step(None, Weight::max_value());
+		// The default schedule is already in the latest encoding.
+		migration::v6::Migration::<Test>::step(None, Weight::max_value());
+		migration::v7::Migration::<Test>::step(None, Weight::max_value());
+
+		assert_eq!(
+			crate::CurrentSchedule::<Test>::get(),
+			Schedule { version: 8, .. Default::default() },
+		);
+	});
+}
+
+#[test]
+fn schedule_migrations_keep_governance_set_values() {
+	ExtBuilder::default().build().execute_with(|| {
+		let schedule = Schedule::<Test> {
+			version: 7,
+			enable_println: true,
+			limits: Limits { event_topics: 7, subject_len: 64, .. Default::default() },
+			instruction_weights: InstructionWeights { i64const: 42, .. Default::default() },
+			host_fn_weights: HostFnWeights { caller: 4242, .. Default::default() },
+		};
+
+		// The encoding before version 6: Without the storage limits, which follow the version,
+		// `enable_println` and eight other limits, and without the three last host functions.
+		let mut old = schedule.encode();
+		old.truncate(old.len() - 3 * 8);
+		old.drain(4 + 1 + 8 * 4 .. 4 + 1 + 10 * 4);
+		let key = crate::CurrentSchedule::<Test>::hashed_key();
+		frame_support::storage::unhashed::put_raw(&key, &old);
+		assert!(crate::CurrentSchedule::<Test>::try_get().is_err());
+
+		MigrationVersion::<Test>::put(5);
+		migration::v6::Migration::<Test>::step(None, Weight::max_value());
+		migration::v7::Migration::<Test>::step(None, Weight::max_value());
+
+		assert_eq!(MigrationVersion::<Test>::get(), Some(migration::LATEST_VERSION));
+		assert_eq!(crate::CurrentSchedule::<Test>::get(), schedule);
+	});
+}
+
+#[test]
+fn bare_instantiate_returns_address_and_output() {
+	let (wasm, code_hash) = compile_module::<Test>("event_and_return_on_deploy").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(100)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let subsistence = Module::<Test>::subsistence_threshold();
+
+			// Instantiating from a code hash that isn't stored fails.
+			let result = Contracts::bare_instantiate(
+				ALICE,
+				subsistence * 100,
+				GAS_LIMIT,
+				Code::Existing(code_hash),
+				vec![],
+				vec![],
+			);
+			assert_err!(result.exec_result, Error::<Test>::CodeNotFound);
+
+			let result = Contracts::bare_instantiate(
+				ALICE,
+				subsistence * 100,
+				GAS_LIMIT,
+				Code::Upload(wasm),
+				vec![],
+				vec![42],
+			);
+			let output = result.exec_result.unwrap();
+			assert!(result.gas_consumed > 0);
+			assert_eq!(output.account_id, Contracts::contract_address(&ALICE, &code_hash, &[42]));
+			assert_eq!(output.result.data, vec![1, 2, 3, 4]);
+			assert!(ContractInfoOf::<Test>::get(&output.account_id).is_some());
+		});
+}
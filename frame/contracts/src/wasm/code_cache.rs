@@ -35,6 +35,7 @@ use crate::{
 };
 use sp_core::crypto::UncheckedFrom;
 use frame_support::dispatch::DispatchError;
+use pallet_contracts_primitives::{ProfilingEvent, contracts_profiling};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::private::reinstrument as reinstrument;
 
@@ -137,8 +138,11 @@ where
 			// in the storage.
 			//
 			// We need to re-instrument the code with the latest schedule here.
+			contracts_profiling::record(ProfilingEvent::CodeCacheMiss);
 			gas_meter.charge(&(), InstrumentToken(prefab_module.original_code_len))?;
 			private::reinstrument(&mut prefab_module, schedule)?;
+		} else {
+			contracts_profiling::record(ProfilingEvent::CodeCacheHit);
 		}
 	}
 	Ok(prefab_module)
@@ -38,6 +38,8 @@ use pallet_contracts_primitives::ExecResult;
 pub use self::runtime::{ReturnCode, Runtime, RuntimeToken};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::code_cache::reinstrument;
+#[cfg(feature = "fuzzing")]
+pub use self::prepare::fuzzing;
 #[cfg(test)]
 pub use tests::MockExt;
 
@@ -256,7 +258,9 @@ mod tests {
 	use sp_runtime::DispatchError;
 	use frame_support::{dispatch::DispatchResult, weights::Weight};
 	use assert_matches::assert_matches;
-	use pallet_contracts_primitives::{ExecReturnValue, ReturnFlags, ExecError, ErrorOrigin};
+	use pallet_contracts_primitives::{
+		CallFlags, ExecReturnValue, ReturnFlags, ExecError, ErrorOrigin,
+	};
 	use pretty_assertions::assert_eq;
 
 	const GAS_LIMIT: Weight = 10_000_000_000;
@@ -305,6 +309,8 @@ mod tests {
 		events: Vec<(Vec<H256>, Vec<u8>)>,
 		schedule: Schedule<Test>,
 		rent_params: RentParams<Test>,
+		code_hash: CodeHash<Test>,
+		read_only: bool,
 	}
 
 	impl Ext for MockExt {
@@ -359,6 +365,7 @@ mod tests {
 			value: u64,
 			_gas_meter: &mut GasMeter<Test>,
 			data: Vec<u8>,
+			_flags: CallFlags,
 		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
 			self.transfers.push(TransferEntry {
 				to: to.clone(),
@@ -399,6 +406,18 @@ mod tests {
 		fn address(&self) -> &AccountIdOf<Self::T> {
 			&BOB
 		}
+		fn own_code_hash(&self) -> &CodeHash<Self::T> {
+			&self.code_hash
+		}
+		fn reentrance_count(&self) -> u32 {
+			12
+		}
+		fn account_reentrance_count(&self, account: &AccountIdOf<Self::T>) -> u32 {
+			if account == &BOB { 13 } else { 0 }
+		}
+		fn is_read_only(&self) -> bool {
+			self.read_only
+		}
 		fn balance(&self) -> u64 {
 			228
 		}
@@ -414,14 +433,16 @@ mod tests {
 		fn tombstone_deposit(&self) -> u64 {
 			16
 		}
-		fn random(&self, subject: &[u8]) -> H256 {
-			H256::from_slice(subject)
+		fn random(&self, subject: &[u8]) -> (H256, u64) {
+			(H256::from_slice(subject), 42)
 		}
-		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
-			self.events.push((topics, data))
+		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) -> DispatchResult {
+			self.events.push((topics, data));
+			Ok(())
 		}
-		fn set_rent_allowance(&mut self, rent_allowance: u64) {
+		fn set_rent_allowance(&mut self, rent_allowance: u64) -> DispatchResult {
 			self.rent_allowance = rent_allowance;
+			Ok(())
 		}
 		fn rent_allowance(&self) -> u64 {
 			self.rent_allowance
@@ -477,8 +498,9 @@ mod tests {
 			value: u64,
 			gas_meter: &mut GasMeter<Test>,
 			input_data: Vec<u8>,
+			flags: CallFlags,
 		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
-			(**self).call(to, value, gas_meter, input_data)
+			(**self).call(to, value, gas_meter, input_data, flags)
 		}
 		fn restore_to(
 			&mut self,
@@ -500,6 +522,18 @@ mod tests {
 		fn address(&self) -> &AccountIdOf<Self::T> {
 			(**self).address()
 		}
+		fn own_code_hash(&self) -> &CodeHash<Self::T> {
+			(**self).own_code_hash()
+		}
+		fn reentrance_count(&self) -> u32 {
+			(**self).reentrance_count()
+		}
+		fn account_reentrance_count(&self, account: &AccountIdOf<Self::T>) -> u32 {
+			(**self).account_reentrance_count(account)
+		}
+		fn is_read_only(&self) -> bool {
+			(**self).is_read_only()
+		}
 		fn balance(&self) -> u64 {
 			(**self).balance()
 		}
@@ -515,13 +549,13 @@ mod tests {
 		fn tombstone_deposit(&self) -> u64 {
 			(**self).tombstone_deposit()
 		}
-		fn random(&self, subject: &[u8]) -> H256 {
+		fn random(&self, subject: &[u8]) -> (H256, u64) {
 			(**self).random(subject)
 		}
-		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
+		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) -> DispatchResult {
 			(**self).deposit_event(topics, data)
 		}
-		fn set_rent_allowance(&mut self, rent_allowance: u64) {
+		fn set_rent_allowance(&mut self, rent_allowance: u64) -> DispatchResult {
 			(**self).set_rent_allowance(rent_allowance)
 		}
 		fn rent_allowance(&self) -> u64 {
@@ -1055,6 +1089,108 @@ mod tests {
 		).unwrap();
 	}
 
+	const CODE_OWN_CODE_HASH: &str = r#"
+(module
+	(import "seal0" "seal_own_code_hash" (func $seal_own_code_hash (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; size of our buffer is 32 bytes
+	(data (i32.const 32) "\20")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; fill the buffer with the code hash.
+		(call $seal_own_code_hash (i32.const 0) (i32.const 32))
+
+		;; assert size == 32
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 32))
+				(i32.const 32)
+			)
+		)
+
+		;; return the code hash
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 32))
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn own_code_hash() {
+		let code_hash = H256::repeat_byte(0x10);
+		let output = execute(
+			CODE_OWN_CODE_HASH,
+			vec![],
+			MockExt { code_hash, ..MockExt::default() },
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(output, ExecReturnValue { flags: ReturnFlags::empty(), data: code_hash.encode() });
+	}
+
+	const CODE_REENTRANCE_COUNT: &str = r#"
+(module
+	(import "seal0" "seal_reentrance_count" (func $seal_reentrance_count (result i32)))
+	(import "seal0" "seal_account_reentrance_count"
+		(func $seal_account_reentrance_count (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; Address of bob
+	(data (i32.const 0)
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+	)
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $assert
+			(i32.eq
+				(call $seal_reentrance_count)
+				(i32.const 12)
+			)
+		)
+		(call $assert
+			(i32.eq
+				(call $seal_account_reentrance_count (i32.const 0) (i32.const 32))
+				(i32.const 13)
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn reentrance_count() {
+		let _ = execute(
+			CODE_REENTRANCE_COUNT,
+			vec![],
+			MockExt::default(),
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+	}
+
 	const CODE_BALANCE: &str = r#"
 (module
 	(import "seal0" "seal_balance" (func $seal_balance (param i32 i32)))
@@ -1292,10 +1428,31 @@ mod tests {
 
 	#[test]
 	fn return_from_start_fn() {
-		let output = execute(
-			CODE_RETURN_FROM_START_FN,
-			vec![],
+		let wasm = wat::parse_str(CODE_RETURN_FROM_START_FN).unwrap();
+		let schedule = crate::Schedule::default();
+
+		// New code with a start function is rejected.
+		assert_eq!(
+			PrefabWasmModule::<Test>::from_code(wasm.clone(), &schedule).err(),
+			Some("module declares a start function".into()),
+		);
+
+		// Code that is already on-chain still runs its start function after re-instrumentation.
+		let mut executable = PrefabWasmModule::<Test>::from_code(
+			wat::parse_str(r#"
+				(module
+					(import "env" "memory" (memory 1 1))
+					(func (export "call"))
+					(func (export "deploy"))
+				)
+			"#).unwrap(),
+			&schedule,
+		).unwrap();
+		executable.code = prepare::reinstrument_contract::<Test>(wasm, &schedule).unwrap();
+		let output = executable.execute(
 			MockExt::default(),
+			&ExportedFunction::Call,
+			vec![],
 			&mut GasMeter::new(GAS_LIMIT),
 		).unwrap();
 
@@ -1531,6 +1688,85 @@ mod tests {
 		);
 	}
 
+	const CODE_RANDOM_WITH_BLOCK_NUMBER: &str = r#"
+(module
+	(import "seal0" "seal_random_with_block_number"
+		(func $seal_random_with_block_number (param i32 i32 i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0,128) is reserved for the result of PRNG.
+
+	;; the subject used for the PRNG. [128,160)
+	(data (i32.const 128)
+		"\00\01\02\03\04\05\06\07\08\09\0A\0B\0C\0D\0E\0F"
+		"\00\01\02\03\04\05\06\07\08\09\0A\0B\0C\0D\0E\0F"
+	)
+
+	;; size of our buffer is 128 bytes
+	(data (i32.const 160) "\80")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; This stores the block random seed and block number in the buffer
+		(call $seal_random_with_block_number
+			(i32.const 128) ;; Pointer in memory to the start of the subject buffer
+			(i32.const 32) ;; The subject buffer's length
+			(i32.const 0) ;; Pointer to the output buffer
+			(i32.const 160) ;; Pointer to the output buffer length
+		)
+
+		;; assert len == 40
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 160))
+				(i32.const 40)
+			)
+		)
+
+		;; return the random data and block number
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 0)
+			(i32.const 40)
+		)
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn random_with_block_number() {
+		let mut gas_meter = GasMeter::new(GAS_LIMIT);
+
+		let output = execute(
+			CODE_RANDOM_WITH_BLOCK_NUMBER,
+			vec![],
+			MockExt::default(),
+			&mut gas_meter,
+		).unwrap();
+
+		// The mock ext just returns the same data that was passed as the subject.
+		assert_eq!(
+			output,
+			ExecReturnValue {
+				flags: ReturnFlags::empty(),
+				data: (
+					hex!("000102030405060708090A0B0C0D0E0F000102030405060708090A0B0C0D0E0F"),
+					42u64,
+				).encode(),
+			},
+		);
+	}
+
 	const CODE_DEPOSIT_EVENT: &str = r#"
 (module
 	(import "seal0" "seal_deposit_event" (func $seal_deposit_event (param i32 i32 i32 i32)))
@@ -1886,4 +2122,54 @@ mod tests {
 		let rent_params = <RentParams<Test>>::default().encode();
 		assert_eq!(output, ExecReturnValue { flags: ReturnFlags::empty(), data: rent_params });
 	}
+
+	const CODE_CHAIN_EXTENSION: &str = r#"
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) len of the output buffer
+	(data (i32.const 0) "\04")
+
+	(func (export "call")
+		;; Func id 1 makes the test extension store its inputs.
+		(drop
+			(call $seal_call_chain_extension
+				(i32.const 1)	;; func_id
+				(i32.const 4)	;; input_ptr
+				(i32.const 0)	;; input_len
+				(i32.const 4)	;; output_ptr
+				(i32.const 0)	;; output_len_ptr
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn chain_extension_is_denied_in_read_only_call() {
+		assert_eq!(
+			execute(
+				CODE_CHAIN_EXTENSION,
+				vec![],
+				MockExt { read_only: true, ..Default::default() },
+				&mut GasMeter::new(GAS_LIMIT),
+			),
+			Err(ExecError {
+				error: Error::<Test>::StateChangeDenied.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+
+		assert!(execute(
+			CODE_CHAIN_EXTENSION,
+			vec![],
+			MockExt::default(),
+			&mut GasMeter::new(GAS_LIMIT),
+		).is_ok());
+	}
 }
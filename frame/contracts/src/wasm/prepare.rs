@@ -82,6 +82,19 @@ impl<'a, T: Config> ContractModule<'a, T> {
 		Ok(())
 	}
 
+	/// Ensures that the module doesn't declare a start function.
+	///
+	/// The start function is executed implicitly whenever the module is instantiated. This
+	/// happens before any of the exported entry points is invoked and would therefore
+	/// run on every call. Contracts are required to do all their work from within
+	/// `deploy` and `call`.
+	fn ensure_no_start_function(&self) -> Result<(), &'static str> {
+		if self.module.start_section().is_some() {
+			return Err("module declares a start function");
+		}
+		Ok(())
+	}
+
 	/// Ensures that tables declared in the module are not too big.
 	fn ensure_table_size_limit(&self, limit: u32) -> Result<(), &'static str> {
 		if let Some(table_section) = self.module.table_section() {
@@ -439,6 +452,9 @@ fn do_preparation<C: ImportSatisfyCheck, T: Config>(
 	original_code: Vec<u8>,
 	schedule: &Schedule<T>,
 ) -> Result<PrefabWasmModule<T>, &'static str> {
+	// Only newly uploaded code is checked for a start function. Code that is already on-chain
+	// must still pass re-instrumentation in order to keep pre-existing contracts working.
+	ContractModule::new(&original_code, schedule)?.ensure_no_start_function()?;
 	let (code, (initial, maximum)) = check_and_instrument::<C, T>(
 		original_code.as_ref(),
 		schedule,
@@ -463,6 +479,7 @@ fn do_preparation<C: ImportSatisfyCheck, T: Config>(
 ///
 /// - provided code is a valid wasm module.
 /// - the module doesn't define an internal memory instance,
+/// - the module doesn't declare a start function,
 /// - imported memory (if any) doesn't reserve more memory than permitted by the `schedule`,
 /// - all imported functions from the external environment matches defined by `env` module,
 ///
@@ -523,6 +540,25 @@ pub mod benchmarking {
 	}
 }
 
+/// Code preparation exposed to the fuzzer living in `fuzzer/`.
+///
+/// The fuzzer is a separate crate and therefore needs a public entry point into code
+/// preparation. It is hidden behind a feature that must never be enabled in production code.
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing {
+	use super::*;
+
+	/// Run all checks and the instrumentation on `original_code` exactly like it is done
+	/// for code uploaded by a user.
+	///
+	/// Returns the instrumented code on success.
+	pub fn prepare<T: Config>(original_code: Vec<u8>, schedule: &Schedule<T>)
+		-> Result<Vec<u8>, &'static str>
+	{
+		prepare_contract(original_code, schedule).map(|module| module.code)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1053,6 +1089,18 @@ mod tests {
 			Err("entry point has wrong signature")
 		);
 
+		prepare_test!(start_function,
+			r#"
+			(module
+				(func $start)
+				(start $start)
+				(func (export "call"))
+				(func (export "deploy"))
+			)
+			"#,
+			Err("module declares a start function")
+		);
+
 		prepare_test!(unknown_exports,
 			r#"
 			(module
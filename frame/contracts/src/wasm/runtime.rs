@@ -35,7 +35,9 @@ use sp_io::hashing::{
 	blake2_128,
 	sha2_256,
 };
-use pallet_contracts_primitives::{ExecResult, ExecReturnValue, ReturnFlags, ExecError};
+use pallet_contracts_primitives::{
+	CallFlags, ExecResult, ExecReturnValue, ReturnFlags, ExecError,
+};
 
 /// Every error that can be returned to a contract when it calls any of the host functions.
 ///
@@ -45,6 +47,8 @@ use pallet_contracts_primitives::{ExecResult, ExecReturnValue, ReturnFlags, Exec
 /// will not be changed or removed. This means that any contract **must not** exhaustively
 /// match return codes. Instead, contracts should prepare for unknown variants and deal with
 /// those errors gracefuly in order to be forward compatible.
+///
+/// This enum is mirrored in the `pallet-contracts-uapi` crate. Both must be kept in sync.
 #[repr(u32)]
 pub enum ReturnCode {
 	/// API call successful.
@@ -139,6 +143,12 @@ pub enum RuntimeToken {
 	Caller,
 	/// Weight of calling `seal_address`.
 	Address,
+	/// Weight of calling `seal_own_code_hash`.
+	OwnCodeHash,
+	/// Weight of calling `seal_reentrance_count`.
+	ReentranceCount,
+	/// Weight of calling `seal_account_reentrance_count`.
+	AccountReentranceCount,
 	/// Weight of calling `seal_gas_left`.
 	GasLeft,
 	/// Weight of calling `seal_balance`.
@@ -171,7 +181,8 @@ pub enum RuntimeToken {
 	RestoreTo(u32),
 	/// Weight that is added to `seal_restore_to` for the involved code sizes.
 	RestoreToSurchargeCodeSize{caller_code: u32, tombstone_code: u32},
-	/// Weight of calling `seal_random`. It includes the weight for copying the subject.
+	/// Weight of calling `seal_random` or `seal_random_with_block_number`.
+	/// It includes the weight for copying the subject.
 	Random,
 	/// Weight of calling `seal_deposit_event` with the given number of topics and event size.
 	DepositEvent{num_topic: u32, len: u32},
@@ -231,6 +242,9 @@ where
 			MeteringBlock(amount) => s.gas.saturating_add(amount.into()),
 			Caller => s.caller,
 			Address => s.address,
+			OwnCodeHash => s.own_code_hash,
+			ReentranceCount => s.reentrance_count,
+			AccountReentranceCount => s.account_reentrance_count,
 			GasLeft => s.gas_left,
 			Balance => s.balance,
 			ValueTransferred => s.value_transferred,
@@ -607,6 +621,65 @@ where
 			(err, _) => Self::err_into_return_code(err)
 		}
 	}
+
+	/// Shared implementation of `seal_call` and `seal_call_with_flags`.
+	fn call(
+		&mut self,
+		flags: CallFlags,
+		callee_ptr: u32,
+		callee_len: u32,
+		gas: u64,
+		value_ptr: u32,
+		value_len: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> Result<ReturnCode, TrapReason> {
+		self.charge_gas(RuntimeToken::CallBase(input_data_len))?;
+		let callee: <<E as Ext>::T as frame_system::Config>::AccountId =
+			self.read_sandbox_memory_as(callee_ptr, callee_len)?;
+		let value: BalanceOf<<E as Ext>::T> = self.read_sandbox_memory_as(value_ptr, value_len)?;
+		let input_data = self.read_sandbox_memory(input_data_ptr, input_data_len)?;
+		if value > 0u32.into() {
+			self.charge_gas(RuntimeToken::CallSurchargeTransfer)?;
+		}
+		let charged = self.charge_gas(
+			RuntimeToken::CallSurchargeCodeSize(<E::T as Config>::MaxCodeSize::get())
+		)?;
+		let nested_gas_limit = if gas == 0 {
+			self.gas_meter.gas_left()
+		} else {
+			gas.saturated_into()
+		};
+		let ext = &mut self.ext;
+		let call_outcome = self.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
+			match nested_meter {
+				Some(nested_meter) => {
+					ext.call(
+						&callee,
+						value,
+						nested_meter,
+						input_data,
+						flags,
+					)
+				}
+				// there is not enough gas to allocate for the nested call.
+				None => Err((Error::<<E as Ext>::T>::OutOfGas.into(), 0)),
+			}
+		});
+		let code_len = match &call_outcome {
+			Ok((_, len)) => len,
+			Err((_, len)) => len,
+		};
+		self.adjust_gas(charged, RuntimeToken::CallSurchargeCodeSize(*code_len));
+		if let Ok((output, _)) = &call_outcome {
+			self.write_sandbox_output(output_ptr, output_len_ptr, &output.data, true, |len| {
+				Some(RuntimeToken::CallCopyOut(len))
+			})?;
+		}
+		Ok(Runtime::<E>::exec_into_return_code(call_outcome.map(|r| r.0).map_err(|r| r.0))?)
+	}
 }
 
 // ***********************************************************
@@ -779,48 +852,66 @@ define_env!(Env, <E: Ext>,
 		output_ptr: u32,
 		output_len_ptr: u32
 	) -> ReturnCode => {
-		ctx.charge_gas(RuntimeToken::CallBase(input_data_len))?;
-		let callee: <<E as Ext>::T as frame_system::Config>::AccountId =
-			ctx.read_sandbox_memory_as(callee_ptr, callee_len)?;
-		let value: BalanceOf<<E as Ext>::T> = ctx.read_sandbox_memory_as(value_ptr, value_len)?;
-		let input_data = ctx.read_sandbox_memory(input_data_ptr, input_data_len)?;
-		if value > 0u32.into() {
-			ctx.charge_gas(RuntimeToken::CallSurchargeTransfer)?;
-		}
-		let charged = ctx.charge_gas(
-			RuntimeToken::CallSurchargeCodeSize(<E::T as Config>::MaxCodeSize::get())
+		ctx.call(
+			CallFlags::empty(),
+			callee_ptr,
+			callee_len,
+			gas,
+			value_ptr,
+			value_len,
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+		)
+	},
+
+	// Make a call to another contract with the specified flags.
+	//
+	// Behaves like `seal_call` but takes an additional `flags` argument.
+	//
+	// # Parameters
+	//
+	// - flags: a bitfield of `CallFlags`. Setting any unknown bit traps.
+	//   `STATIC` (bit 0): executes the callee read-only. The callee and all contracts it calls
+	//   in turn fail when they try to modify state: writing storage, transferring value,
+	//   depositing events, setting the rent allowance, instantiating, terminating or restoring.
+	//   A value transfer must not be requested together with this flag. Contracts called
+	//   read-only do not pay rent and their calls are not recorded as `Called` events.
+	// - All other parameters are the same as for `seal_call`.
+	//
+	// # Errors
+	//
+	// Same as for `seal_call`. A callee that attempts a state change during a static call traps
+	// which results in `ReturnCode::CalleeTrapped`.
+	seal_call_with_flags(
+		ctx,
+		flags: u32,
+		callee_ptr: u32,
+		callee_len: u32,
+		gas: u64,
+		value_ptr: u32,
+		value_len: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> ReturnCode => {
+		let flags = CallFlags::from_bits(flags).ok_or_else(||
+			"used reserved bit in call flags"
 		)?;
-		let nested_gas_limit = if gas == 0 {
-			ctx.gas_meter.gas_left()
-		} else {
-			gas.saturated_into()
-		};
-		let ext = &mut ctx.ext;
-		let call_outcome = ctx.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
-			match nested_meter {
-				Some(nested_meter) => {
-					ext.call(
-						&callee,
-						value,
-						nested_meter,
-						input_data,
-					)
-				}
-				// there is not enough gas to allocate for the nested call.
-				None => Err((Error::<<E as Ext>::T>::OutOfGas.into(), 0)),
-			}
-		});
-		let code_len = match &call_outcome {
-			Ok((_, len)) => len,
-			Err((_, len)) => len,
-		};
-		ctx.adjust_gas(charged, RuntimeToken::CallSurchargeCodeSize(*code_len));
-		if let Ok((output, _)) = &call_outcome {
-			ctx.write_sandbox_output(output_ptr, output_len_ptr, &output.data, true, |len| {
-				Some(RuntimeToken::CallCopyOut(len))
-			})?;
-		}
-		Ok(Runtime::<E>::exec_into_return_code(call_outcome.map(|r| r.0).map_err(|r| r.0))?)
+		ctx.call(
+			flags,
+			callee_ptr,
+			callee_len,
+			gas,
+			value_ptr,
+			value_len,
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+		)
 	},
 
 	// Instantiate a contract with the specified code hash.
@@ -1048,6 +1139,48 @@ define_env!(Env, <E: Ext>,
 		)?)
 	},
 
+	// Stores the code hash of the current contract into the supplied buffer.
+	//
+	// The value is stored to linear memory at the address pointed to by `out_ptr`.
+	// `out_len_ptr` must point to a u32 value that describes the available space at
+	// `out_ptr`. This call overwrites it with the size of the value. If the available
+	// space at `out_ptr` is less than the size of the value a trap is triggered.
+	//
+	// The value is encoded as T::Hash. Contracts that are called through a proxy can use
+	// this to verify their own identity.
+	seal_own_code_hash(ctx, out_ptr: u32, out_len_ptr: u32) => {
+		ctx.charge_gas(RuntimeToken::OwnCodeHash)?;
+		Ok(ctx.write_sandbox_output(
+			out_ptr, out_len_ptr, &ctx.ext.own_code_hash().encode(), false, already_charged
+		)?)
+	},
+
+	// Returns how many times the current contract is already on the call stack.
+	//
+	// The current call is not included. Therefore `0` is returned when the contract
+	// wasn't reentered.
+	seal_reentrance_count(ctx) -> u32 => {
+		ctx.charge_gas(RuntimeToken::ReentranceCount)?;
+		Ok(ctx.ext.reentrance_count())
+	},
+
+	// Returns how many times the specified contract is on the call stack.
+	//
+	// The current call is included when `account` is the current contract. Accounts
+	// that don't belong to a contract always yield `0`.
+	//
+	// # Parameters
+	//
+	// - account_ptr: a pointer to the address of the contract. Should be decodable as an
+	//   `T::AccountId`. Traps otherwise.
+	// - account_len: length of the address buffer.
+	seal_account_reentrance_count(ctx, account_ptr: u32, account_len: u32) -> u32 => {
+		ctx.charge_gas(RuntimeToken::AccountReentranceCount)?;
+		let account: <<E as Ext>::T as frame_system::Config>::AccountId =
+			ctx.read_sandbox_memory_as(account_ptr, account_len)?;
+		Ok(ctx.ext.account_reentrance_count(&account))
+	},
+
 	// Stores the price for the specified amount of gas into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -1061,6 +1194,11 @@ define_env!(Env, <E: Ext>,
 	//
 	// It is recommended to avoid specifying very small values for `gas` as the prices for a single
 	// gas can be smaller than one.
+	//
+	// The returned price is for informational purposes only. It is computed by the runtime's
+	// `Config::WeightPrice` which usually includes the fee multiplier of the current block. The
+	// price can therefore change from block to block and is not what is charged for the gas
+	// used by the current call.
 	seal_weight_to_fee(ctx, gas: u64, out_ptr: u32, out_len_ptr: u32) => {
 		ctx.charge_gas(RuntimeToken::WeightToFee)?;
 		Ok(ctx.write_sandbox_output(
@@ -1121,7 +1259,50 @@ define_env!(Env, <E: Ext>,
 	// space at `out_ptr` is less than the size of the value a trap is triggered.
 	//
 	// The data is encoded as T::Hash.
+	//
+	// # Deprecation
+	//
+	// This function does not tell since when the returned seed was determinable by chain
+	// observers. Use `seal_random_with_block_number` instead.
 	seal_random(ctx, subject_ptr: u32, subject_len: u32, out_ptr: u32, out_len_ptr: u32) => {
+		ctx.charge_gas(RuntimeToken::Random)?;
+		if subject_len > ctx.ext.schedule().limits.subject_len {
+			Err(Error::<E::T>::RandomSubjectTooLong)?;
+		}
+		let subject_buf = ctx.read_sandbox_memory(subject_ptr, subject_len)?;
+		Ok(ctx.write_sandbox_output(
+			out_ptr, out_len_ptr, &ctx.ext.random(&subject_buf).0.encode(), false, already_charged
+		)?)
+	},
+
+	// Stores a random number for the current block and the given subject into the supplied buffer.
+	//
+	// The value is stored to linear memory at the address pointed to by `out_ptr`.
+	// `out_len_ptr` must point to a u32 value that describes the available space at
+	// `out_ptr`. This call overwrites it with the size of the value. If the available
+	// space at `out_ptr` is less than the size of the value a trap is triggered.
+	//
+	// The data is encoded as (T::Hash, T::BlockNumber).
+	//
+	// # Changes from `seal_random`
+	//
+	// In addition to the seed it returns the block number since which it was determinable
+	// by chain observers.
+	//
+	// # Note
+	//
+	// The returned seed should only be used to distinguish commitments made before
+	// the returned block number. If the block number is too early (i.e. commitments were
+	// made afterwards), then ensure no further commitments may be made and repeatedly
+	// call this on later blocks until the block number returned is later than the latest
+	// commitment.
+	seal_random_with_block_number(
+		ctx,
+		subject_ptr: u32,
+		subject_len: u32,
+		out_ptr: u32,
+		out_len_ptr: u32
+	) => {
 		ctx.charge_gas(RuntimeToken::Random)?;
 		if subject_len > ctx.ext.schedule().limits.subject_len {
 			Err(Error::<E::T>::RandomSubjectTooLong)?;
@@ -1326,7 +1507,7 @@ define_env!(Env, <E: Ext>,
 
 		let event_data = ctx.read_sandbox_memory(data_ptr, data_len)?;
 
-		ctx.ext.deposit_event(topics, event_data);
+		ctx.ext.deposit_event(topics, event_data)?;
 
 		Ok(())
 	},
@@ -1340,7 +1521,7 @@ define_env!(Env, <E: Ext>,
 		ctx.charge_gas(RuntimeToken::SetRentAllowance)?;
 		let value: BalanceOf<<E as Ext>::T> =
 			ctx.read_sandbox_memory_as(value_ptr, value_len)?;
-		ctx.ext.set_rent_allowance(value);
+		ctx.ext.set_rent_allowance(value)?;
 
 		Ok(())
 	},
@@ -1495,6 +1676,9 @@ define_env!(Env, <E: Ext>,
 	//
 	// If no chain extension exists the contract will trap with the `NoChainExtension`
 	// module error.
+	//
+	// A chain extension can change state. Calling it from within a read-only call traps
+	// with the `StateChangeDenied` module error.
 	seal_call_chain_extension(
 		ctx,
 		func_id: u32,
@@ -1507,6 +1691,9 @@ define_env!(Env, <E: Ext>,
 		if <E::T as Config>::ChainExtension::enabled() == false {
 			Err(Error::<E::T>::NoChainExtension)?;
 		}
+		if ctx.ext.is_read_only() {
+			Err(Error::<E::T>::StateChangeDenied)?;
+		}
 		let env = Environment::new(ctx, input_ptr, input_len, output_ptr, output_len_ptr);
 		match <E::T as Config>::ChainExtension::call(func_id, env)? {
 			RetVal::Converging(val) => Ok(val),
@@ -55,6 +55,9 @@ pub trait WeightInfo {
 	fn claim_surcharge(c: u32, ) -> Weight;
 	fn seal_caller(r: u32, ) -> Weight;
 	fn seal_address(r: u32, ) -> Weight;
+	fn seal_own_code_hash(r: u32, ) -> Weight;
+	fn seal_reentrance_count(r: u32, ) -> Weight;
+	fn seal_account_reentrance_count(r: u32, ) -> Weight;
 	fn seal_gas_left(r: u32, ) -> Weight;
 	fn seal_balance(r: u32, ) -> Weight;
 	fn seal_value_transferred(r: u32, ) -> Weight;
@@ -227,6 +230,18 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((266_051_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 	}
+	fn seal_own_code_hash(r: u32, ) -> Weight {
+		// Not benchmarked yet: Returns a value of the same size as `seal_address`.
+		Self::seal_address(r)
+	}
+	fn seal_reentrance_count(r: u32, ) -> Weight {
+		// Not benchmarked yet: Upper bound by a getter that also writes to sandbox memory.
+		Self::seal_gas_left(r)
+	}
+	fn seal_account_reentrance_count(r: u32, ) -> Weight {
+		// Not benchmarked yet: Upper bound by a getter that also queries the balance.
+		Self::seal_balance(r)
+	}
 	fn seal_gas_left(r: u32, ) -> Weight {
 		(144_833_000 as Weight)
 			// Standard Error: 124_000
@@ -853,6 +868,18 @@ impl WeightInfo for () {
 			.saturating_add((266_051_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 	}
+	fn seal_own_code_hash(r: u32, ) -> Weight {
+		// Not benchmarked yet: Returns a value of the same size as `seal_address`.
+		Self::seal_address(r)
+	}
+	fn seal_reentrance_count(r: u32, ) -> Weight {
+		// Not benchmarked yet: Upper bound by a getter that also writes to sandbox memory.
+		Self::seal_gas_left(r)
+	}
+	fn seal_account_reentrance_count(r: u32, ) -> Weight {
+		// Not benchmarked yet: Upper bound by a getter that also queries the balance.
+		Self::seal_balance(r)
+	}
 	fn seal_gas_left(r: u32, ) -> Weight {
 		(144_833_000 as Weight)
 			// Standard Error: 124_000
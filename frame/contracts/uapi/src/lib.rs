@@ -0,0 +1,326 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarations of the host functions that `pallet-contracts` exposes to contracts.
+//!
+//! This crate is the reference for the ABI between a contract and the runtime. It is meant
+//! to be used by contracts which don't use ink! and therefore need to declare the imports
+//! themselves. All definitions here mirror the ones found in the `wasm::runtime` module of
+//! `pallet-contracts` and are kept in sync with them.
+//!
+//! # Conventions
+//!
+//! All pointers and lengths are offsets into the contract's linear memory which must be
+//! imported as `env.memory`. Functions that write a variable sized value expect an `out_ptr`
+//! together with an `out_len_ptr`. The latter must point to a `u32` holding the size of the
+//! buffer at `out_ptr` and is overwritten with the actual size of the value. The call traps
+//! when the buffer is too small.
+//!
+//! Values are passed to and returned from the runtime SCALE encoded.
+
+#![no_std]
+
+bitflags::bitflags! {
+	/// Flags used by a contract to customize exit behaviour.
+	///
+	/// Passed to [`seal0::seal_return`].
+	pub struct ReturnFlags: u32 {
+		/// If this bit is set all changes made by the contract execution are rolled back.
+		const REVERT = 0x0000_0001;
+	}
+}
+
+bitflags::bitflags! {
+	/// Flags used by a contract to customize the behaviour of a call into another contract.
+	///
+	/// Passed to [`seal0::seal_call_with_flags`].
+	pub struct CallFlags: u32 {
+		/// Execute the callee as a read-only (static) call.
+		///
+		/// Any attempt to change state from within the callee or any of its nested calls
+		/// makes that call fail.
+		const STATIC = 0x0000_0001;
+	}
+}
+
+/// Every error that can be returned to a contract when it calls any of the host functions.
+///
+/// This enum can be extended in the future: New codes can be added but existing codes
+/// will not be changed or removed. This means that any contract **must not** exhaustively
+/// match return codes. Instead, contracts should prepare for unknown variants and deal with
+/// those errors gracefuly in order to be forward compatible.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ReturnCode {
+	/// API call successful.
+	Success = 0,
+	/// The called function trapped and has its state changes reverted.
+	/// In this case no output buffer is returned.
+	CalleeTrapped = 1,
+	/// The called function ran to completion but decided to revert its state.
+	/// An output buffer is returned when one was supplied.
+	CalleeReverted = 2,
+	/// The passed key does not exist in storage.
+	KeyNotFound = 3,
+	/// Transfer failed because it would have brought the sender's total balance below the
+	/// subsistence threshold.
+	BelowSubsistenceThreshold = 4,
+	/// Transfer failed for other reasons. Most probably reserved or locked balance of the
+	/// sender prevents the transfer.
+	TransferFailed = 5,
+	/// The newly created contract is below the subsistence threshold after executing
+	/// its constructor.
+	NewContractNotFunded = 6,
+	/// No code could be found at the supplied code hash.
+	CodeNotFound = 7,
+	/// The contract that was called is either no contract at all (a plain account)
+	/// or is a tombstone.
+	NotCallable = 8,
+}
+
+impl ReturnCode {
+	/// Convert the raw value returned by a host function into a `ReturnCode`.
+	///
+	/// Returns `None` for codes that are unknown to this version of the crate.
+	pub fn from_raw(code: u32) -> Option<Self> {
+		let code = match code {
+			0 => Self::Success,
+			1 => Self::CalleeTrapped,
+			2 => Self::CalleeReverted,
+			3 => Self::KeyNotFound,
+			4 => Self::BelowSubsistenceThreshold,
+			5 => Self::TransferFailed,
+			6 => Self::NewContractNotFunded,
+			7 => Self::CodeNotFound,
+			8 => Self::NotCallable,
+			_ => return None,
+		};
+		Some(code)
+	}
+}
+
+/// Host functions available under the `seal0` import module.
+///
+/// Functions returning a `u32` return a raw [`ReturnCode`] unless noted otherwise.
+pub mod seal0 {
+	#[link(wasm_import_module = "seal0")]
+	extern "C" {
+		/// Set the value at the given key in the contract storage.
+		pub fn seal_set_storage(key_ptr: u32, value_ptr: u32, value_len: u32);
+
+		/// Clear the value at the given key in the contract storage.
+		pub fn seal_clear_storage(key_ptr: u32);
+
+		/// Retrieve the value under the given key from storage.
+		pub fn seal_get_storage(key_ptr: u32, out_ptr: u32, out_len_ptr: u32) -> u32;
+
+		/// Transfer some value to another account.
+		pub fn seal_transfer(
+			account_ptr: u32,
+			account_len: u32,
+			value_ptr: u32,
+			value_len: u32,
+		) -> u32;
+
+		/// Make a call to another contract.
+		///
+		/// Passing `u32::max_value()` as `output_ptr` skips copying the output.
+		pub fn seal_call(
+			callee_ptr: u32,
+			callee_len: u32,
+			gas: u64,
+			value_ptr: u32,
+			value_len: u32,
+			input_data_ptr: u32,
+			input_data_len: u32,
+			output_ptr: u32,
+			output_len_ptr: u32,
+		) -> u32;
+
+		/// Make a call to another contract customized by [`CallFlags`](super::CallFlags).
+		///
+		/// Using a reserved bit in `flags` triggers a trap.
+		pub fn seal_call_with_flags(
+			flags: u32,
+			callee_ptr: u32,
+			callee_len: u32,
+			gas: u64,
+			value_ptr: u32,
+			value_len: u32,
+			input_data_ptr: u32,
+			input_data_len: u32,
+			output_ptr: u32,
+			output_len_ptr: u32,
+		) -> u32;
+
+		/// Instantiate a contract with the specified code hash.
+		///
+		/// Passing `u32::max_value()` as `address_ptr` or `output_ptr` skips copying
+		/// the respective value.
+		pub fn seal_instantiate(
+			code_hash_ptr: u32,
+			code_hash_len: u32,
+			gas: u64,
+			value_ptr: u32,
+			value_len: u32,
+			input_data_ptr: u32,
+			input_data_len: u32,
+			address_ptr: u32,
+			address_len_ptr: u32,
+			output_ptr: u32,
+			output_len_ptr: u32,
+			salt_ptr: u32,
+			salt_len: u32,
+		) -> u32;
+
+		/// Remove the calling account and transfer remaining balance to the beneficiary.
+		///
+		/// This function never returns.
+		pub fn seal_terminate(beneficiary_ptr: u32, beneficiary_len: u32) -> !;
+
+		/// Stores the input passed by the caller into the supplied buffer.
+		pub fn seal_input(out_ptr: u32, out_len_ptr: u32);
+
+		/// Cease contract execution and save a data buffer as a result of the execution.
+		///
+		/// `flags` are [`ReturnFlags`](super::ReturnFlags). This function never returns.
+		pub fn seal_return(flags: u32, data_ptr: u32, data_len: u32) -> !;
+
+		/// Stores the address of the caller into the supplied buffer.
+		pub fn seal_caller(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the address of the current contract into the supplied buffer.
+		pub fn seal_address(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the code hash of the current contract into the supplied buffer.
+		pub fn seal_own_code_hash(out_ptr: u32, out_len_ptr: u32);
+
+		/// Returns how many times the current contract is already on the call stack.
+		///
+		/// The current call is not included.
+		pub fn seal_reentrance_count() -> u32;
+
+		/// Returns how many times the specified contract is on the call stack.
+		///
+		/// The current call is included when `account` is the current contract.
+		pub fn seal_account_reentrance_count(account_ptr: u32, account_len: u32) -> u32;
+
+		/// Stores the price for the specified amount of gas into the supplied buffer.
+		///
+		/// The price includes the fee multiplier of the current block and is for
+		/// informational purposes only.
+		pub fn seal_weight_to_fee(gas: u64, out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the amount of gas left into the supplied buffer.
+		pub fn seal_gas_left(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the balance of the current account into the supplied buffer.
+		pub fn seal_balance(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the value transferred along with this call or as endowment into the
+		/// supplied buffer.
+		pub fn seal_value_transferred(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores a random number for the current block and the given subject into the
+		/// supplied buffer.
+		pub fn seal_random(subject_ptr: u32, subject_len: u32, out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores a random number for the current block and the given subject into the
+		/// supplied buffer together with the block number since which it was determinable.
+		pub fn seal_random_with_block_number(
+			subject_ptr: u32,
+			subject_len: u32,
+			out_ptr: u32,
+			out_len_ptr: u32,
+		);
+
+		/// Load the latest block timestamp into the supplied buffer.
+		pub fn seal_now(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the minimum balance (a.k.a. existential deposit) into the supplied buffer.
+		pub fn seal_minimum_balance(out_ptr: u32, out_len_ptr: u32);
+
+		/// Stores the tombstone deposit into the supplied buffer.
+		pub fn seal_tombstone_deposit(out_ptr: u32, out_len_ptr: u32);
+
+		/// Try to restore the given destination contract sacrificing the caller.
+		///
+		/// This function never returns.
+		pub fn seal_restore_to(
+			dest_ptr: u32,
+			dest_len: u32,
+			code_hash_ptr: u32,
+			code_hash_len: u32,
+			rent_allowance_ptr: u32,
+			rent_allowance_len: u32,
+			delta_ptr: u32,
+			delta_count: u32,
+		) -> !;
+
+		/// Deposit a contract event with the data buffer and optional list of topics.
+		pub fn seal_deposit_event(topics_ptr: u32, topics_len: u32, data_ptr: u32, data_len: u32);
+
+		/// Set rent allowance of the contract.
+		pub fn seal_set_rent_allowance(value_ptr: u32, value_len: u32);
+
+		/// Stores the rent allowance into the supplied buffer.
+		pub fn seal_rent_allowance(out_ptr: u32, out_len_ptr: u32);
+
+		/// Prints utf8 encoded string from the data buffer.
+		///
+		/// Only available on `--dev` chains.
+		pub fn seal_println(str_ptr: u32, str_len: u32);
+
+		/// Stores the current block number of the current contract into the supplied buffer.
+		pub fn seal_block_number(out_ptr: u32, out_len_ptr: u32);
+
+		/// Computes the SHA2 256-bit hash on the given input buffer.
+		///
+		/// The 32 byte result is written to `output_ptr`.
+		pub fn seal_hash_sha2_256(input_ptr: u32, input_len: u32, output_ptr: u32);
+
+		/// Computes the KECCAK 256-bit hash on the given input buffer.
+		///
+		/// The 32 byte result is written to `output_ptr`.
+		pub fn seal_hash_keccak_256(input_ptr: u32, input_len: u32, output_ptr: u32);
+
+		/// Computes the BLAKE2 256-bit hash on the given input buffer.
+		///
+		/// The 32 byte result is written to `output_ptr`.
+		pub fn seal_hash_blake2_256(input_ptr: u32, input_len: u32, output_ptr: u32);
+
+		/// Computes the BLAKE2 128-bit hash on the given input buffer.
+		///
+		/// The 16 byte result is written to `output_ptr`.
+		pub fn seal_hash_blake2_128(input_ptr: u32, input_len: u32, output_ptr: u32);
+
+		/// Call into the chain extension provided by the chain if any.
+		///
+		/// The return value is defined by the chain extension and is not a
+		/// [`ReturnCode`](super::ReturnCode).
+		pub fn seal_call_chain_extension(
+			func_id: u32,
+			input_ptr: u32,
+			input_len: u32,
+			output_ptr: u32,
+			output_len_ptr: u32,
+		) -> u32;
+
+		/// Stores the rent params into the supplied buffer.
+		pub fn seal_rent_params(out_ptr: u32, out_len_ptr: u32);
+	}
+}
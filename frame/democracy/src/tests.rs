@@ -122,6 +122,8 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = u64;
 	type Event = Event;
 	type DustRemoval = ();
@@ -101,7 +101,9 @@
 //!
 //! Validators will only submit solutions if the one that they have computed is sufficiently better
 //! than the best queued one (see [`pallet::Config::SolutionImprovementThreshold`]) and will limit
-//! the weigh of the solution to [`pallet::Config::MinerMaxWeight`].
+//! the weigh of the solution to [`pallet::Config::MinerMaxWeight`] and its length to
+//! [`pallet::Config::MinerMaxLength`]. The election algorithm used by the miner is configured via
+//! [`pallet::Config::Solver`].
 //!
 //! The unsigned phase can be made passive depending on how the previous signed phase went, by
 //! setting the first inner value of [`Phase`] to `false`. For now, the signed phase is always
@@ -214,7 +216,7 @@ use frame_system::{ensure_none, offchain::SendTransactionTypes};
 use sp_election_providers::{ElectionDataProvider, ElectionProvider, onchain};
 use sp_npos_elections::{
 	assignment_ratio_to_staked_normalized, is_score_better, CompactSolution, ElectionScore,
-	EvaluateSupport, PerThing128, Supports, VoteWeight,
+	EvaluateSupport, NposSolver, PerThing128, Supports, VoteWeight,
 };
 use sp_runtime::{
 	transaction_validity::{
@@ -524,10 +526,23 @@ pub mod pallet {
 		/// The miner will ensure that the total weight of the unsigned solution will not exceed
 		/// this values, based on [`WeightInfo::submit_unsigned`].
 		type MinerMaxWeight: Get<Weight>;
+		/// Maximum length (bytes) that the mined solution should consume.
+		///
+		/// The miner will ensure that the encoded compact solution will not exceed this value.
+		type MinerMaxLength: Get<u32>;
+		/// The election algorithm used by the miner to compute unsigned solutions.
+		type Solver: NposSolver<AccountId = Self::AccountId, Accuracy = CompactAccuracyOf<Self>>;
 
 		/// Something that will provide the election data.
 		type DataProvider: ElectionDataProvider<Self::AccountId, Self::BlockNumber>;
 
+		/// The maximum number of voters to put in the snapshot.
+		///
+		/// The data provider is expected to return the most relevant voters first, e.g. sorted by
+		/// their vote weight.
+		#[pallet::constant]
+		type VoterSnapshotPerBlock: Get<u32>;
+
 		/// The compact solution type
 		type CompactSolution: codec::Codec
 			+ Default
@@ -874,7 +889,7 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn create_snapshot() {
 		// if any of them don't exist, create all of them. This is a bit conservative.
 		let targets = T::DataProvider::targets();
-		let voters = T::DataProvider::voters();
+		let voters = T::DataProvider::voters(Some(T::VoterSnapshotPerBlock::get() as usize));
 		let desired_targets = T::DataProvider::desired_targets();
 
 		<SnapshotMetadata<T>>::put(SolutionOrSnapshotSize {
@@ -1399,6 +1414,18 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn snapshot_is_bounded() {
+		ExtBuilder::default().voter_snapshot_per_block(3).build_and_execute(|| {
+			roll_to(15);
+			assert_eq!(MultiPhase::current_phase(), Phase::Signed);
+
+			let snapshot = MultiPhase::snapshot().unwrap();
+			assert_eq!(snapshot.voters, Voters::get().into_iter().take(3).collect::<Vec<_>>());
+			assert_eq!(MultiPhase::snapshot_metadata().unwrap().voters, 3);
+		})
+	}
+
 	#[test]
 	fn fallback_strategy_works() {
 		ExtBuilder::default().fallabck(FallbackStrategy::OnChain).build_and_execute(|| {
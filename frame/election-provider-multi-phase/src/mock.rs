@@ -34,7 +34,7 @@ use sp_core::{
 use sp_election_providers::ElectionDataProvider;
 use sp_npos_elections::{
 	assignment_ratio_to_staked_normalized, seq_phragmen, to_supports, to_without_backing,
-	CompactSolution, ElectionResult, EvaluateSupport,
+	CompactSolution, ElectionResult, EvaluateSupport, SequentialPhragmen,
 };
 use sp_runtime::{
 	testing::Header,
@@ -175,6 +175,8 @@ impl pallet_balances::Config for Runtime {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type WeightInfo = ();
 }
 
@@ -202,6 +204,8 @@ parameter_types! {
 	pub static MinerTxPriority: u64 = 100;
 	pub static SolutionImprovementThreshold: Perbill = Perbill::zero();
 	pub static MinerMaxWeight: Weight = BlockWeights::get().max_block;
+	pub static MinerMaxLength: u32 = 256;
+	pub static VoterSnapshotPerBlock: u32 = u32::max_value();
 	pub static MockWeightInfo: bool = false;
 
 
@@ -267,7 +271,10 @@ impl crate::Config for Runtime {
 	type SolutionImprovementThreshold = SolutionImprovementThreshold;
 	type MinerMaxIterations = MinerMaxIterations;
 	type MinerMaxWeight = MinerMaxWeight;
+	type MinerMaxLength = MinerMaxLength;
+	type VoterSnapshotPerBlock = VoterSnapshotPerBlock;
 	type MinerTxPriority = MinerTxPriority;
+	type Solver = SequentialPhragmen<AccountId, CompactAccuracyOf<Runtime>>;
 	type DataProvider = StakingMock;
 	type WeightInfo = DualMockWeightInfo;
 	type BenchmarkingConfig = ();
@@ -294,8 +301,12 @@ impl ElectionDataProvider<AccountId, u64> for StakingMock {
 	fn targets() -> Vec<AccountId> {
 		Targets::get()
 	}
-	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
-		Voters::get()
+	fn voters(maybe_max_len: Option<usize>) -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+		let voters = Voters::get();
+		match maybe_max_len {
+			Some(max_len) => voters.into_iter().take(max_len).collect(),
+			None => voters,
+		}
 	}
 	fn desired_targets() -> u32 {
 		DesiredTargets::get()
@@ -327,6 +338,14 @@ impl ExtBuilder {
 		<MinerMaxWeight>::set(weight);
 		self
 	}
+	pub fn miner_length(self, length: u32) -> Self {
+		<MinerMaxLength>::set(length);
+		self
+	}
+	pub fn voter_snapshot_per_block(self, voters: u32) -> Self {
+		<VoterSnapshotPerBlock>::set(voters);
+		self
+	}
 	pub fn mock_weight_info(self, mock: bool) -> Self {
 		<MockWeightInfo>::set(mock);
 		self
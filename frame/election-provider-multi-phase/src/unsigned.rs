@@ -21,7 +21,7 @@ use crate::*;
 use frame_support::dispatch::DispatchResult;
 use frame_system::offchain::SubmitTransaction;
 use sp_npos_elections::{
-	seq_phragmen, CompactSolution, ElectionResult, assignment_ratio_to_staked_normalized,
+	CompactSolution, ElectionResult, NposSolver, assignment_ratio_to_staked_normalized,
 	assignment_staked_to_ratio_normalized,
 };
 use sp_runtime::{offchain::storage::StorageValueRef, traits::TrailingZeroInput};
@@ -46,6 +46,8 @@ pub enum MinerError {
 	PreDispatchChecksFailed,
 	/// The solution generated from the miner is not feasible.
 	Feasibility(FeasibilityError),
+	/// There are no more voters to remove to trim the solution.
+	NoMoreVoters,
 }
 
 impl From<sp_npos_elections::Error> for MinerError {
@@ -108,12 +110,7 @@ impl<T: Config> Pallet<T> {
 			Self::snapshot().ok_or(MinerError::SnapshotUnAvailable)?;
 		let desired_targets = Self::desired_targets().ok_or(MinerError::SnapshotUnAvailable)?;
 
-		seq_phragmen::<_, CompactAccuracyOf<T>>(
-			desired_targets as usize,
-			targets,
-			voters,
-			Some((iters, 0)),
-		)
+		T::Solver::solve(desired_targets as usize, targets, voters, Some((iters, 0)))
 		.map_err(Into::into)
 		.and_then(Self::prepare_election_result)
 	}
@@ -166,6 +163,7 @@ impl<T: Config> Pallet<T> {
 			maximum_allowed_voters,
 		);
 		let compact = Self::trim_compact(maximum_allowed_voters, compact, &voter_index)?;
+		let compact = Self::trim_compact_length(T::MinerMaxLength::get(), compact, &voter_index)?;
 
 		// re-calc score.
 		let winners = sp_npos_elections::to_without_backing(winners);
@@ -252,6 +250,59 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Greedily reduce the size of the solution to fit into the block w.r.t length.
+	///
+	/// The length of the solution is largely a function of the number of voters. The number of
+	/// winners cannot be changed. Thus, to reduce the solution size, we need to strip voters.
+	///
+	/// Note that this solution is already computed, and winners are elected based on the merit of
+	/// the total stake in the system. Nevertheless, some of the voters may be removed here.
+	///
+	/// Sometimes, removing a voter can cause a validator to also be implicitly removed, if
+	/// that voter was the only backer of that winner. In such cases, this solution is invalid,
+	/// which will be caught prior to submission.
+	///
+	/// The score must be computed **after** this step. If this step reduces the score too much,
+	/// then the solution must be discarded.
+	pub fn trim_compact_length<FN>(
+		max_allowed_length: u32,
+		mut compact: CompactOf<T>,
+		voter_index: FN,
+	) -> Result<CompactOf<T>, MinerError>
+	where
+		for<'r> FN: Fn(&'r T::AccountId) -> Option<CompactVoterIndexOf<T>>,
+	{
+		// short-circuit to avoid getting the voters if possible.
+		if compact.encoded_size().saturated_into::<u32>() <= max_allowed_length {
+			return Ok(compact);
+		}
+
+		// grab all voters and sort them by least stake, with the least staked last so it can be
+		// popped.
+		let RoundSnapshot { voters, .. } =
+			Self::snapshot().ok_or(MinerError::SnapshotUnAvailable)?;
+		let mut voters_sorted = voters
+			.into_iter()
+			.map(|(who, stake, _)| (who, stake))
+			.collect::<Vec<_>>();
+		voters_sorted.sort_by_key(|(_, y)| sp_std::cmp::Reverse(*y));
+
+		while compact.encoded_size().saturated_into::<u32>() > max_allowed_length {
+			let (smallest_stake_voter, _) = voters_sorted.pop().ok_or(MinerError::NoMoreVoters)?;
+			let index = voter_index(&smallest_stake_voter).ok_or(MinerError::SnapshotUnAvailable)?;
+			compact.remove_voter(index);
+		}
+
+		log!(
+			debug,
+			"miner: trimmed compact solution to {} voters, length = {}",
+			compact.voter_count(),
+			compact.encoded_size(),
+		);
+
+		Ok(compact)
+	}
+
 	/// Find the maximum `len` that a compact can have in order to fit into the block weight.
 	///
 	/// This only returns a value between zero and `size.nominators`.
@@ -718,6 +769,35 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn miner_trims_length() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(25);
+			assert!(MultiPhase::current_phase().is_unsigned());
+
+			let (solution, _) = MultiPhase::mine_solution(2).unwrap();
+			assert_eq!(solution.compact.voter_count(), 5);
+			let full_length = solution.compact.encoded_size() as u32;
+
+			// now reduce the max length, such that at least one voter must be removed.
+			<MinerMaxLength>::set(full_length - 1);
+
+			let (solution, _) = MultiPhase::mine_solution(2).unwrap();
+			assert!(solution.compact.encoded_size() as u32 <= full_length - 1);
+			assert!(solution.compact.voter_count() < 5);
+		})
+	}
+
+	#[test]
+	fn trim_compact_length_fails_if_no_voters_left() {
+		ExtBuilder::default().miner_length(0).build_and_execute(|| {
+			roll_to(25);
+			assert!(MultiPhase::current_phase().is_unsigned());
+
+			assert_eq!(MultiPhase::mine_solution(2).unwrap_err(), MinerError::NoMoreVoters);
+		})
+	}
+
 	#[test]
 	fn miner_will_not_submit_if_not_enough_winners() {
 		let (mut ext, _) = ExtBuilder::default().desired_targets(8).build_offchainify(0);
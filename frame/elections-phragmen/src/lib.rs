@@ -1098,6 +1098,8 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = frame_system::Module<Test>;
 		type MaxLocks = ();
+		type HoldReason = ();
+		type MaxHolds = ();
 		type WeightInfo = ();
 	}
 
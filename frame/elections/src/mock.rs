@@ -65,6 +65,8 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = u64;
 	type DustRemoval = ();
 	type Event = Event;
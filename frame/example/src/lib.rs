@@ -798,6 +798,8 @@ mod tests {
 	}
 	impl pallet_balances::Config for Test {
 		type MaxLocks = ();
+		type HoldReason = ();
+		type MaxHolds = ();
 		type Balance = u64;
 		type DustRemoval = ();
 		type Event = Event;
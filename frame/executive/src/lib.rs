@@ -113,13 +113,69 @@
 //!
 //! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllModules, CustomOnRuntimeUpgrade>;
 //! ```
+//!
+//! ### Multi-block migrations
+//!
+//! Migrations that are too heavy for a single block can be implemented as
+//! [`SteppedMigration`](frame_support::traits::SteppedMigration)s and registered through another
+//! optional generic parameter. They are started on the next runtime upgrade and executed one step
+//! per block, one migration after another. The progress is stored in
+//! [`frame_system::OngoingMigration`].
+//!
+//! A mandatory migration is stepped at the beginning of each block and no transaction other than
+//! mandatory ones is applied until it is done. Other migrations are stepped with the weight that is
+//! left at the end of the block. While any migration is ongoing, the `on_poll` hooks of the
+//! modules are not called.
+//!
+//! ```
+//! # use sp_runtime::generic;
+//! # use frame_executive as executive;
+//! # pub struct UncheckedExtrinsic {};
+//! # pub struct Header {};
+//! # type Context = frame_system::ChainContext<Runtime>;
+//! # pub type Block = generic::Block<Header, UncheckedExtrinsic>;
+//! # pub type Balances = u64;
+//! # pub type AllModules = u64;
+//! # pub enum Runtime {};
+//! # use sp_runtime::transaction_validity::{
+//! #    TransactionValidity, UnknownTransaction, TransactionSource,
+//! # };
+//! # use sp_runtime::traits::ValidateUnsigned;
+//! # impl ValidateUnsigned for Runtime {
+//! #     type Call = ();
+//! #
+//! #     fn validate_unsigned(_source: TransactionSource, _call: &Self::Call) -> TransactionValidity {
+//! #         UnknownTransaction::NoUnsignedValidator.into()
+//! #     }
+//! # }
+//! use frame_support::weights::Weight;
+//!
+//! struct MigrateAccounts;
+//! impl frame_support::traits::SteppedMigration for MigrateAccounts {
+//!     // The last migrated account.
+//!     type Cursor = u64;
+//!     const ID: &'static [u8] = b"MigrateAccounts";
+//!
+//!     fn step(_cursor: Option<u64>, _remaining_weight: Weight) -> (Option<u64>, Weight) {
+//!         // Migrate as many accounts as the weight allows, starting after `cursor`.
+//!         (None, 0)
+//!     }
+//! }
+//!
+//! pub type Executive = executive::Executive<
+//!     Runtime, Block, Context, Runtime, AllModules, (), (MigrateAccounts,),
+//! >;
+//! ```
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::{prelude::*, marker::PhantomData};
 use frame_support::{
 	weights::{GetDispatchInfo, DispatchInfo, DispatchClass},
-	traits::{OnInitialize, OnIdle, OnFinalize, OnRuntimeUpgrade, OffchainWorker, ExecuteBlock},
+	traits::{
+		OnInitialize, OnPoll, OnIdle, OnFinalize, OnRuntimeUpgrade, OffchainWorker, ExecuteBlock,
+		SteppedMigrations,
+	},
 	dispatch::PostDispatchInfo,
 };
 use sp_runtime::{
@@ -128,7 +184,7 @@ use sp_runtime::{
 		self, Header, Zero, One, Checkable, Applyable, CheckEqual, ValidateUnsigned, NumberFor,
 		Dispatchable, Saturating,
 	},
-	transaction_validity::{TransactionValidity, TransactionSource},
+	transaction_validity::{TransactionValidity, TransactionSource, InvalidTransaction},
 };
 use codec::{Codec, Encode};
 use frame_system::DigestOf;
@@ -148,8 +204,17 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::Origin;
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///                       already called by `AllModules`. It will be called before all modules will
 ///                       be called.
-pub struct Executive<System, Block, Context, UnsignedValidator, AllModules, OnRuntimeUpgrade = ()>(
-	PhantomData<(System, Block, Context, UnsignedValidator, AllModules, OnRuntimeUpgrade)>
+/// - `Migrations`: Multi-block migrations that are started on the next runtime upgrade.
+pub struct Executive<
+	System,
+	Block,
+	Context,
+	UnsignedValidator,
+	AllModules,
+	OnRuntimeUpgrade = (),
+	Migrations = (),
+>(
+	PhantomData<(System, Block, Context, UnsignedValidator, AllModules, OnRuntimeUpgrade, Migrations)>
 );
 
 impl<
@@ -160,12 +225,14 @@ impl<
 	AllModules:
 		OnRuntimeUpgrade +
 		OnInitialize<System::BlockNumber> +
+		OnPoll<System::BlockNumber> +
 		OnIdle<System::BlockNumber> +
 		OnFinalize<System::BlockNumber> +
 		OffchainWorker<System::BlockNumber>,
 	COnRuntimeUpgrade: OnRuntimeUpgrade,
+	Migrations: SteppedMigrations,
 > ExecuteBlock<Block> for
-	Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade>
+	Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade, Migrations>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>:
@@ -176,7 +243,15 @@ where
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
 	fn execute_block(block: Block) {
-		Executive::<System, Block, Context, UnsignedValidator, AllModules>::execute_block(block);
+		Executive::<
+			System,
+			Block,
+			Context,
+			UnsignedValidator,
+			AllModules,
+			COnRuntimeUpgrade,
+			Migrations,
+		>::execute_block(block);
 	}
 }
 
@@ -187,11 +262,13 @@ impl<
 		UnsignedValidator,
 		AllModules: OnRuntimeUpgrade
 			+ OnInitialize<System::BlockNumber>
+			+ OnPoll<System::BlockNumber>
 			+ OnIdle<System::BlockNumber>
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade>
+		Migrations: SteppedMigrations,
+	> Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade, Migrations>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -264,6 +341,7 @@ where
 		let mut weight = 0;
 		if Self::runtime_upgraded() {
 			weight = weight.saturating_add(Self::execute_on_runtime_upgrade());
+			Self::start_migrations();
 		}
 		<frame_system::Module<System>>::initialize(
 			block_number,
@@ -282,9 +360,135 @@ where
 		);
 		<frame_system::Module::<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 
+		Self::poll_hook(*block_number);
+
 		frame_system::Module::<System>::note_finished_initialize();
 	}
 
+	/// Either step the ongoing mandatory migration or, if no migration is ongoing, call the
+	/// `on_poll` hooks, with all the weight that is left in the block.
+	fn poll_hook(block_number: System::BlockNumber) {
+		let weight = <frame_system::Module<System>>::block_weight();
+		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let remaining_weight = max_weight.saturating_sub(weight.total());
+
+		let used_weight = if Self::mandatory_migration_ongoing() {
+			Self::step_migration(remaining_weight)
+		} else if frame_system::OngoingMigration::<System>::exists() {
+			0
+		} else {
+			let used_weight =
+				<frame_system::Module<System> as OnPoll<System::BlockNumber>>::on_poll(
+					block_number,
+					remaining_weight,
+				);
+			<AllModules as OnPoll<System::BlockNumber>>::on_poll(
+				block_number,
+				remaining_weight.saturating_sub(used_weight),
+			)
+			.saturating_add(used_weight)
+		};
+		<frame_system::Module::<System>>::register_extra_weight_unchecked(used_weight, DispatchClass::Mandatory);
+	}
+
+	/// Start executing the multi-block migrations, unless there are none or they are already
+	/// ongoing.
+	fn start_migrations() {
+		if Migrations::IS_MANDATORY.is_empty() || frame_system::OngoingMigration::<System>::exists() {
+			return
+		}
+
+		frame_system::OngoingMigration::<System>::put(Self::migration_cursor(0, None));
+	}
+
+	/// Cursor of the `index`th multi-block migration.
+	fn migration_cursor(index: u32, inner: Option<Vec<u8>>) -> frame_system::MigrationCursor {
+		frame_system::MigrationCursor {
+			index,
+			id: Migrations::IDS.get(index as usize).map(|id| id.to_vec()).unwrap_or_default(),
+			inner,
+		}
+	}
+
+	/// Returns if the currently executed multi-block migration is mandatory.
+	fn mandatory_migration_ongoing() -> bool {
+		frame_system::OngoingMigration::<System>::get()
+			.and_then(|cursor| Migrations::IS_MANDATORY.get(cursor.index as usize).copied())
+			.unwrap_or(false)
+	}
+
+	/// Execute the next step of the ongoing multi-block migration and return the weight used.
+	fn step_migration(
+		remaining_weight: frame_support::weights::Weight,
+	) -> frame_support::weights::Weight {
+		let db_weight = <System::DbWeight as frame_support::traits::Get<_>>::get();
+		let cursor = match frame_system::OngoingMigration::<System>::get() {
+			Some(cursor) => cursor,
+			None => return db_weight.reads(1),
+		};
+		let remaining_weight = remaining_weight.saturating_sub(db_weight.reads_writes(1, 1));
+
+		// The sequence of migrations might have changed with a runtime upgrade. Resume the
+		// migration the cursor belongs to or abort if it is gone, since it is unknown which of
+		// the migrations already completed.
+		let position = Migrations::IDS.get(cursor.index as usize)
+			.filter(|id| **id == &cursor.id[..])
+			.map(|_| cursor.index as usize)
+			.or_else(|| Migrations::IDS.iter().position(|id| *id == &cursor.id[..]));
+		let (index, inner) = match position {
+			Some(index) => (index as u32, cursor.inner),
+			None => {
+				frame_support::log::error!(
+					target: "runtime::executive",
+					"Aborting multi-block migrations, the migration {:?} is unknown",
+					cursor.id,
+				);
+				frame_system::OngoingMigration::<System>::kill();
+				<frame_system::Module<System>>::deposit_event(
+					frame_system::Event::<System>::MigrationAborted(cursor.id),
+				);
+				return db_weight.reads_writes(1, 2)
+			},
+		};
+
+		let (next, used_weight) = match Migrations::nth_step(index, inner, remaining_weight) {
+			Some(Ok((Some(inner), used_weight))) => (Some((index, Some(inner))), used_weight),
+			Some(Ok((None, used_weight))) => {
+				let index = index.saturating_add(1);
+				let next = if (index as usize) < Migrations::IS_MANDATORY.len() {
+					Some((index, None))
+				} else {
+					None
+				};
+				(next, used_weight)
+			},
+			Some(Err(e)) => {
+				let id = Migrations::IDS.get(index as usize).copied().unwrap_or_default();
+				frame_support::log::error!(
+					target: "runtime::executive",
+					"Aborting multi-block migrations, failed to decode the cursor of {:?}: {:?}",
+					id,
+					e,
+				);
+				<frame_system::Module<System>>::deposit_event(
+					frame_system::Event::<System>::MigrationAborted(id.to_vec()),
+				);
+				(None, 0)
+			},
+			// There are no migrations left to execute.
+			None => (None, 0),
+		};
+
+		match next {
+			Some((index, inner)) => frame_system::OngoingMigration::<System>::put(
+				Self::migration_cursor(index, inner),
+			),
+			None => frame_system::OngoingMigration::<System>::kill(),
+		}
+
+		used_weight.saturating_add(db_weight.reads_writes(1, 1))
+	}
+
 	/// Returns if the runtime was upgraded since the last time this function was called.
 	fn runtime_upgraded() -> bool {
 		let last = frame_system::LastRuntimeUpgrade::<System>::get();
@@ -373,6 +577,15 @@ where
 		let max_weight =  <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
 		let mut remaining_weight = max_weight.saturating_sub(weight.total());
 
+		if remaining_weight > 0
+			&& !Self::mandatory_migration_ongoing()
+			&& frame_system::OngoingMigration::<System>::exists()
+		{
+			let used_weight = Self::step_migration(remaining_weight);
+			remaining_weight = remaining_weight.saturating_sub(used_weight);
+			<frame_system::Module::<System>>::register_extra_weight_unchecked(used_weight, DispatchClass::Mandatory);
+		}
+
 		if remaining_weight > 0 {
 			let mut used_weight =
 				<frame_system::Module<System> as OnIdle<System::BlockNumber>>::on_idle(
@@ -416,6 +629,12 @@ where
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default())?;
 
+		// Only mandatory extrinsics may be applied while a mandatory migration is ongoing.
+		let dispatch_info = xt.get_dispatch_info();
+		if dispatch_info.class != DispatchClass::Mandatory && Self::mandatory_migration_ongoing() {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+
 		// We don't need to make sure to `note_extrinsic` only after we know it's going to be
 		// executed to prevent it from leaking in storage since at this point, it will either
 		// execute or panic (and revert storage changes).
@@ -424,7 +643,6 @@ where
 		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// Decode parameters and dispatch
-		let dispatch_info = xt.get_dispatch_info();
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 		<frame_system::Module<System>>::note_applied_extrinsic(&r, dispatch_info);
@@ -530,7 +748,7 @@ mod tests {
 	use frame_support::{
 		parameter_types,
 		weights::{Weight, RuntimeDbWeight, IdentityFee, WeightToFeePolynomial},
-		traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReasons},
+		traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReasons, SteppedMigration},
 	};
 	use frame_system::{
 		Call as SystemCall, ChainContext, LastRuntimeUpgradeInfo,
@@ -683,6 +901,8 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = ();
+		type HoldReason = ();
+		type MaxHolds = ();
 		type WeightInfo = ();
 	}
 
@@ -745,6 +965,43 @@ mod tests {
 		CustomOnRuntimeUpgrade
 	>;
 
+	/// Steps a migration that is done after two steps.
+	fn step_twice(cursor: Option<u32>) -> (Option<u32>, Weight) {
+		let steps = cursor.unwrap_or(0) + 1;
+		(if steps < 2 { Some(steps) } else { None }, 10)
+	}
+
+	struct MandatoryMigration;
+	impl SteppedMigration for MandatoryMigration {
+		type Cursor = u32;
+		const ID: &'static [u8] = b"mandatory";
+		const MANDATORY: bool = true;
+
+		fn step(cursor: Option<u32>, _remaining_weight: Weight) -> (Option<u32>, Weight) {
+			step_twice(cursor)
+		}
+	}
+
+	struct OptionalMigration;
+	impl SteppedMigration for OptionalMigration {
+		type Cursor = u32;
+		const ID: &'static [u8] = b"optional";
+
+		fn step(cursor: Option<u32>, _remaining_weight: Weight) -> (Option<u32>, Weight) {
+			step_twice(cursor)
+		}
+	}
+
+	type MigratingExecutive = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		ChainContext<Runtime>,
+		Runtime,
+		AllModules,
+		(),
+		(MandatoryMigration, OptionalMigration),
+	>;
+
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
 		(
 			frame_system::CheckEra::from(Era::Immortal),
@@ -1202,6 +1459,105 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn multi_block_migrations_are_stepped_over_several_blocks() {
+		let xt = TestXt::new(Call::Balances(BalancesCall::transfer(2, 69)), sign_extra(1, 0, 0));
+		let cursor = |index, inner: Option<u32>| Some(frame_system::MigrationCursor {
+			index,
+			id: [&b"mandatory"[..], &b"optional"[..]][index as usize].to_vec(),
+			inner: inner.map(|inner| inner.encode()),
+		});
+		let initialize = |n| MigratingExecutive::initialize_block(&Header::new(
+			n,
+			H256::default(),
+			H256::default(),
+			[69u8; 32].into(),
+			Digest::default(),
+		));
+
+		new_test_ext(2).execute_with(|| {
+			// No runtime upgrade, no migrations.
+			initialize(1);
+			assert!(!frame_system::OngoingMigration::<Runtime>::exists());
+			MigratingExecutive::finalize_block();
+
+			RUNTIME_VERSION.with(|v| *v.borrow_mut() = sp_version::RuntimeVersion {
+				spec_version: 1,
+				..Default::default()
+			});
+
+			// The mandatory migration starts and keeps transactions out of the block.
+			initialize(2);
+			assert_eq!(frame_system::OngoingMigration::<Runtime>::get(), cursor(0, Some(1)));
+			assert_eq!(
+				MigratingExecutive::apply_extrinsic(xt.clone()),
+				Err(InvalidTransaction::ExhaustsResources.into()),
+			);
+			MigratingExecutive::finalize_block();
+			assert_eq!(frame_system::OngoingMigration::<Runtime>::get(), cursor(0, Some(1)));
+
+			// The mandatory migration is done, the next one is stepped at the end of the block.
+			initialize(3);
+			assert_eq!(frame_system::OngoingMigration::<Runtime>::get(), cursor(1, None));
+			assert!(MigratingExecutive::apply_extrinsic(xt.clone()).unwrap().is_ok());
+			MigratingExecutive::finalize_block();
+			assert_eq!(frame_system::OngoingMigration::<Runtime>::get(), cursor(1, Some(1)));
+
+			initialize(4);
+			assert_eq!(frame_system::OngoingMigration::<Runtime>::get(), cursor(1, Some(1)));
+			MigratingExecutive::finalize_block();
+			assert!(!frame_system::OngoingMigration::<Runtime>::exists());
+		});
+	}
+
+	#[test]
+	fn multi_block_migration_cursors_are_matched_by_id() {
+		let put_cursor = |index, id: &[u8], inner: Option<Vec<u8>>| {
+			frame_system::OngoingMigration::<Runtime>::put(frame_system::MigrationCursor {
+				index,
+				id: id.to_vec(),
+				inner,
+			})
+		};
+
+		new_test_ext(1).execute_with(|| {
+			System::set_block_number(1);
+
+			// A migration that moved to another index is resumed where it left off.
+			put_cursor(0, b"optional", Some(1u32.encode()));
+			MigratingExecutive::step_migration(Weight::max_value());
+			assert!(!frame_system::OngoingMigration::<Runtime>::exists());
+			assert!(System::events().is_empty());
+
+			// The migrations are aborted instead of started over if the migration is gone.
+			put_cursor(0, b"removed", Some(7u32.encode()));
+			MigratingExecutive::step_migration(Weight::max_value());
+			assert!(!frame_system::OngoingMigration::<Runtime>::exists());
+			assert!(System::events().iter().any(|record| record.event == Event::frame_system(
+				frame_system::Event::MigrationAborted(b"removed".to_vec()),
+			)));
+		});
+	}
+
+	#[test]
+	fn undecodable_multi_block_migration_cursor_aborts_migrations() {
+		new_test_ext(1).execute_with(|| {
+			System::set_block_number(1);
+			frame_system::OngoingMigration::<Runtime>::put(frame_system::MigrationCursor {
+				index: 0,
+				id: b"mandatory".to_vec(),
+				inner: Some(vec![1]),
+			});
+
+			MigratingExecutive::step_migration(Weight::max_value());
+
+			assert!(!frame_system::OngoingMigration::<Runtime>::exists());
+			assert!(System::events().iter().any(|record| record.event == Event::frame_system(
+				frame_system::Event::MigrationAborted(b"mandatory".to_vec()),
+			)));
+		});
+	}
+
 	#[test]
 	fn calculating_storage_root_twice_works() {
 		let call = Call::Custom(custom::Call::calculate_storage_root());
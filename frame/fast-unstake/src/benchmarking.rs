@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the fast-unstake pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+
+use crate::Pallet as FastUnstake;
+
+const SEED: u32 = 0;
+
+/// Create a funded stash that is its own controller and nominates.
+fn create_nominator<T: Config>() -> Result<T::AccountId, &'static str> {
+	let stash: T::AccountId = account("stash", 0, SEED);
+	let target: T::AccountId = account("target", 0, SEED);
+	let bond = T::Staking::minimum_bond() * 10u32.into();
+	T::Currency::make_free_balance_be(&stash, bond + T::Deposit::get() * 10u32.into());
+	T::Staking::bond(stash.clone(), stash.clone(), bond, stash.clone())?;
+	T::Staking::nominate(stash.clone(), vec![target])?;
+	Ok(stash)
+}
+
+/// Register a new nominator and move it to the head of the queue. Returns the stash and the
+/// number of eras it has to be checked in.
+fn setup_head<T: Config>() -> Result<(T::AccountId, u32), &'static str> {
+	let stash = create_nominator::<T>()?;
+	FastUnstake::<T>::register_fast_unstake(RawOrigin::Signed(stash.clone()).into())?;
+	T::Staking::set_current_era(T::Staking::bonding_duration());
+	<Head<T>>::put(FastUnstake::<T>::next_request().ok_or("queue is empty")?);
+	Ok((stash, T::Staking::bonding_duration() + 1))
+}
+
+benchmarks! {
+	register_fast_unstake {
+		let stash = create_nominator::<T>()?;
+	}: _(RawOrigin::Signed(stash.clone()))
+	verify {
+		assert!(<Queue<T>>::contains_key(&stash));
+	}
+
+	deregister {
+		let stash = create_nominator::<T>()?;
+		FastUnstake::<T>::register_fast_unstake(RawOrigin::Signed(stash.clone()).into())?;
+	}: _(RawOrigin::Signed(stash.clone()))
+	verify {
+		assert!(!<Queue<T>>::contains_key(&stash));
+	}
+
+	// The stash is not exposed in any of the checked eras, so it stays at the head.
+	on_idle_check {
+		let e in 1 .. T::MaxErasToCheckPerBlock::get();
+		let (_, eras) = setup_head::<T>()?;
+		let e = e.min(eras);
+	}: {
+		FastUnstake::<T>::process_head(e);
+	}
+	verify {
+		assert_eq!(<Head<T>>::get().map(|head| head.checked.len() as u32), Some(e));
+	}
+
+	// All eras have been checked, so the stash is unstaked.
+	on_idle_unstake {
+		let (stash, eras) = setup_head::<T>()?;
+		<Head<T>>::mutate(|head| head.as_mut().map(|head| head.checked = (0..eras).collect()));
+	}: {
+		FastUnstake::<T>::process_head(1);
+	}
+	verify {
+		assert!(<Head<T>>::get().is_none());
+		assert!(T::Staking::stash_by_ctrl(&stash).is_err());
+	}
+}
+
+impl_benchmark_test_suite!(
+	FastUnstake,
+	crate::mock::new_test_ext(),
+	crate::mock::Runtime,
+);
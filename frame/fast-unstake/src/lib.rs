@@ -0,0 +1,303 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Fast Unstake Pallet
+//!
+//! Allows stashes that have not been exposed in any recent era to unstake without waiting for
+//! the bonding duration.
+//!
+//! ## Overview
+//!
+//! A stash can only be slashed for offences in eras in which it was exposed, i.e. in which it was
+//! an active validator or backed one as a nominator. Funds of a stash that has not been exposed
+//! in any of the last `BondingDuration` eras can therefore be released immediately.
+//!
+//! The controller of a stash calls `register_fast_unstake` to join the queue of stashes to be
+//! checked. This chills the stash and fully unbonds it, so it can not be elected while it is
+//! being checked, and places `Deposit` of the controller on hold for [`HoldReason::Deposit`].
+//!
+//! Checking the exposure of a stash takes some reads for each era within the bonding duration,
+//! so it happens in `on_idle` with the weight that is left in a block. Stashes are checked one at a time, at most `MaxErasToCheckPerBlock`
+//! eras per block:
+//!
+//! - If the stash was not exposed in any of the eras, it is unstaked and its deposit returned.
+//! - If the stash was exposed in one of the eras, the deposit is slashed and the stash is removed
+//!   from the queue. Its funds remain unbonding as usual.
+//!
+//! A stash that is queued but not yet being checked can leave the queue with `deregister`, which
+//! returns the deposit. Its funds remain unbonding.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `register_fast_unstake` - Queue the stash of the caller to be fast unstaked.
+//! - `deregister` - Remove the stash of the caller from the queue.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod weights;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::{Encode, Decode};
+use frame_support::traits::{Currency, Get, OnUnbalanced, fungible::MutateHold};
+use sp_runtime::{RuntimeDebug, traits::Zero};
+use sp_staking::{EraIndex, StakingInterface};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// A type alias for the balance type from this pallet's point of view.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// A stash that is being checked.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct UnstakeRequest<AccountId, Balance> {
+	/// The stash to unstake.
+	pub stash: AccountId,
+	/// The account whose deposit is on hold.
+	pub depositor: AccountId,
+	/// The deposit on hold.
+	pub deposit: Balance,
+	/// The eras in which the stash has been found to not be exposed.
+	pub checked: Vec<EraIndex>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency used for deposits.
+		type Currency: Currency<Self::AccountId> + MutateHold<
+			Self::AccountId,
+			Balance = BalanceOf<Self>,
+			Reason = Self::HoldReason,
+		>;
+		/// The overarching hold reason.
+		type HoldReason: From<HoldReason>;
+		/// The staking system whose stashes are unstaked.
+		type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+		/// Deposit placed on hold when registering a stash, slashed if the stash turns out to be
+		/// exposed.
+		#[pallet::constant]
+		type Deposit: Get<BalanceOf<Self>>;
+		/// What to do with slashed deposits.
+		type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Maximum number of eras to check in a single block.
+		#[pallet::constant]
+		type MaxErasToCheckPerBlock: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The reasons for which this pallet places funds on hold.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// The deposit of a stash that is queued or being checked.
+		Deposit,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The caller is not a controller.
+		NotController,
+		/// The stash is already queued or being checked.
+		AlreadyQueued,
+		/// The stash has funds that are already unbonding.
+		NotFullyBonded,
+		/// The stash is not queued.
+		NotQueued,
+		/// The stash is already being checked and can not leave the queue anymore.
+		AlreadyHead,
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A stash has been queued to be fast unstaked. \[stash\]
+		Registered(T::AccountId),
+		/// A stash has left the queue. \[stash\]
+		Deregistered(T::AccountId),
+		/// A stash has been found to not be exposed in the given eras. \[stash, eras\]
+		Checked(T::AccountId, Vec<EraIndex>),
+		/// A stash has been unstaked. \[stash, result\]
+		Unstaked(T::AccountId, DispatchResult),
+		/// A stash has been found to be exposed and its deposit slashed. \[stash, amount\]
+		Slashed(T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let max_eras = T::MaxErasToCheckPerBlock::get();
+			match (1..=max_eras)
+				.rev()
+				.find(|eras| T::WeightInfo::on_idle_check(*eras) <= remaining_weight)
+			{
+				Some(eras) if T::WeightInfo::on_idle_unstake() <= remaining_weight =>
+					Self::process_head(eras),
+				_ => 0,
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Queue the stash controlled by the caller to be fast unstaked. The stash is chilled and
+		/// fully unbonded right away, and `Deposit` of the caller is placed on hold.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller of a stash that
+		/// has no funds unbonding.
+		#[pallet::weight(T::WeightInfo::register_fast_unstake())]
+		pub(super) fn register_fast_unstake(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_by_ctrl(&controller)
+				.map_err(|_| Error::<T>::NotController)?;
+			ensure!(
+				!<Queue<T>>::contains_key(&stash) &&
+					<Head<T>>::get().map_or(true, |head| head.stash != stash),
+				Error::<T>::AlreadyQueued,
+			);
+			let active = T::Staking::active_stake(&controller).unwrap_or_else(Zero::zero);
+			ensure!(
+				T::Staking::total_stake(&controller) == Some(active),
+				Error::<T>::NotFullyBonded,
+			);
+
+			let deposit = T::Deposit::get();
+			T::Currency::hold(&HoldReason::Deposit.into(), &controller, deposit)?;
+			T::Staking::chill(controller.clone())?;
+			T::Staking::unbond(controller.clone(), active)?;
+
+			<Queue<T>>::insert(&stash, (controller, deposit));
+			Self::deposit_event(Event::Registered(stash));
+			Ok(().into())
+		}
+
+		/// Remove the stash controlled by the caller from the queue and return the deposit. The
+		/// funds of the stash remain unbonding.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the controller of a queued
+		/// stash that is not being checked yet.
+		#[pallet::weight(T::WeightInfo::deregister())]
+		pub(super) fn deregister(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let controller = ensure_signed(origin)?;
+			let stash = T::Staking::stash_by_ctrl(&controller)
+				.map_err(|_| Error::<T>::NotController)?;
+			ensure!(
+				<Head<T>>::get().map_or(true, |head| head.stash != stash),
+				Error::<T>::AlreadyHead,
+			);
+			let (depositor, deposit) = <Queue<T>>::take(&stash).ok_or(Error::<T>::NotQueued)?;
+			Self::release_deposit(&depositor, deposit);
+			Self::deposit_event(Event::Deregistered(stash));
+			Ok(().into())
+		}
+	}
+
+	/// Stashes waiting to be checked, with the account whose deposit is on hold and the
+	/// deposit.
+	#[pallet::storage]
+	pub type Queue<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::AccountId,
+		(T::AccountId, BalanceOf<T>),
+		OptionQuery,
+	>;
+
+	/// The stash that is currently being checked.
+	#[pallet::storage]
+	pub type Head<T: Config> =
+		StorageValue<_, UnstakeRequest<T::AccountId, BalanceOf<T>>, OptionQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// Check up to `eras_per_block` eras of the current head, and unstake or slash it once done.
+	/// Returns the consumed weight.
+	fn process_head(eras_per_block: u32) -> Weight {
+		let mut request = match <Head<T>>::take().or_else(Self::next_request) {
+			Some(request) => request,
+			None => return T::DbWeight::get().reads(2),
+		};
+
+		// Only exposures within the bonding duration can still lead to a slash.
+		let current_era = T::Staking::current_era();
+		let first_era = current_era.saturating_sub(T::Staking::bonding_duration());
+		request.checked.retain(|era| *era >= first_era);
+		let eras_to_check = (first_era..=current_era)
+			.rev()
+			.filter(|era| !request.checked.contains(era))
+			.take(eras_per_block as usize)
+			.collect::<Vec<_>>();
+
+		if eras_to_check.is_empty() {
+			let result = T::Staking::force_unstake(request.stash.clone());
+			Self::release_deposit(&request.depositor, request.deposit);
+			Self::deposit_event(Event::Unstaked(request.stash, result));
+			return T::WeightInfo::on_idle_unstake();
+		}
+
+		let weight = T::WeightInfo::on_idle_check(eras_to_check.len() as u32);
+		if eras_to_check.iter().any(|era| T::Staking::is_exposed_in_era(&request.stash, era)) {
+			// The deposit is released first, so that it can be slashed from the free balance.
+			let released = Self::release_deposit(&request.depositor, request.deposit);
+			let (imbalance, _) = T::Currency::slash(&request.depositor, released);
+			T::Slashed::on_unbalanced(imbalance);
+			Self::deposit_event(Event::Slashed(request.stash, request.deposit));
+		} else {
+			request.checked.extend(eras_to_check.iter().cloned());
+			Self::deposit_event(Event::Checked(request.stash.clone(), eras_to_check));
+			<Head<T>>::put(request);
+		}
+		weight
+	}
+
+	/// Release up to `deposit` of `depositor` from hold and return the released amount.
+	fn release_deposit(depositor: &T::AccountId, deposit: BalanceOf<T>) -> BalanceOf<T> {
+		T::Currency::release(&HoldReason::Deposit.into(), depositor, deposit, true)
+			.unwrap_or_else(|_| Zero::zero())
+	}
+
+	/// Take the next stash out of the queue.
+	fn next_request() -> Option<UnstakeRequest<T::AccountId, BalanceOf<T>>> {
+		let (stash, (depositor, deposit)) = <Queue<T>>::iter().next()?;
+		<Queue<T>>::remove(&stash);
+		Some(UnstakeRequest { stash, depositor, deposit, checked: Vec::new() })
+	}
+}
@@ -0,0 +1,271 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test environment for fast-unstake pallet.
+
+use crate as pallet_fast_unstake;
+use sp_core::H256;
+use sp_runtime::{
+	DispatchError, DispatchResult,
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use sp_staking::{EraIndex, StakingInterface};
+use frame_support::{parameter_types, traits::{OnIdle, OnInitialize}, weights::Weight};
+use std::{cell::RefCell, collections::{BTreeMap, BTreeSet}};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Config<T>, Storage, Event<T>},
+		FastUnstake: pallet_fast_unstake::{Module, Call, Storage, Event<T>, HoldReason},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxHolds: u32 = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type HoldReason = HoldReason;
+	type MaxHolds = MaxHolds;
+}
+
+pub const BONDING_DURATION: EraIndex = 3;
+
+/// A stake of an account in [`StakingMock`].
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Ledger {
+	pub active: Balance,
+	pub unlocking: Balance,
+	pub nominating: bool,
+}
+
+thread_local! {
+	static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+	static LEDGERS: RefCell<BTreeMap<AccountId, Ledger>> = RefCell::new(Default::default());
+	static EXPOSURES: RefCell<BTreeSet<(EraIndex, AccountId)>> = RefCell::new(Default::default());
+}
+
+/// A minimal staking system in which every account is its own controller.
+pub struct StakingMock;
+
+impl StakingMock {
+	pub fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+
+	pub fn ledger(who: &AccountId) -> Option<Ledger> {
+		LEDGERS.with(|l| l.borrow().get(who).cloned())
+	}
+
+	/// Bond `value` of `who` and nominate with it.
+	pub fn add_nominator(who: AccountId, value: Balance) {
+		LEDGERS.with(|l| {
+			l.borrow_mut().insert(who, Ledger { active: value, unlocking: 0, nominating: true })
+		});
+	}
+
+	pub fn add_exposure(era: EraIndex, who: AccountId) {
+		EXPOSURES.with(|e| e.borrow_mut().insert((era, who)));
+	}
+}
+
+impl StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Balance {
+		1
+	}
+
+	fn bonding_duration() -> EraIndex {
+		BONDING_DURATION
+	}
+
+	fn current_era() -> EraIndex {
+		CURRENT_ERA.with(|e| *e.borrow())
+	}
+
+	fn stash_by_ctrl(controller: &AccountId) -> Result<AccountId, DispatchError> {
+		Self::ledger(controller).map(|_| *controller).ok_or("not bonded".into())
+	}
+
+	fn is_exposed_in_era(who: &AccountId, era: &EraIndex) -> bool {
+		EXPOSURES.with(|e| e.borrow().contains(&(*era, *who)))
+	}
+
+	fn active_stake(controller: &AccountId) -> Option<Balance> {
+		Self::ledger(controller).map(|l| l.active)
+	}
+
+	fn total_stake(controller: &AccountId) -> Option<Balance> {
+		Self::ledger(controller).map(|l| l.active + l.unlocking)
+	}
+
+	fn bond(stash: AccountId, _: AccountId, value: Balance, _: AccountId) -> DispatchResult {
+		LEDGERS.with(|l| {
+			l.borrow_mut().insert(stash, Ledger { active: value, unlocking: 0, nominating: false })
+		});
+		Ok(())
+	}
+
+	fn bond_extra(_: AccountId, _: Balance) -> DispatchResult {
+		unimplemented!("not used by fast-unstake")
+	}
+
+	fn unbond(controller: AccountId, value: Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or("not bonded")?;
+			ledger.active = ledger.active.checked_sub(value).ok_or("not enough bonded")?;
+			ledger.unlocking += value;
+			Ok(())
+		})
+	}
+
+	fn withdraw_unbonded(_: AccountId) -> DispatchResult {
+		unimplemented!("not used by fast-unstake")
+	}
+
+	fn nominate(controller: AccountId, _: Vec<AccountId>) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			ledgers.get_mut(&controller).ok_or("not bonded")?.nominating = true;
+			Ok(())
+		})
+	}
+
+	fn chill(controller: AccountId) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			ledgers.get_mut(&controller).ok_or("not bonded")?.nominating = false;
+			Ok(())
+		})
+	}
+
+	fn force_unstake(stash: AccountId) -> DispatchResult {
+		LEDGERS.with(|l| l.borrow_mut().remove(&stash)).map(|_| ()).ok_or("not bonded".into())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+}
+
+parameter_types! {
+	pub const Deposit: Balance = 10;
+	pub const MaxErasToCheckPerBlock: u32 = 2;
+}
+
+impl pallet_fast_unstake::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type HoldReason = HoldReason;
+	type Staking = StakingMock;
+	type Deposit = Deposit;
+	type Slashed = ();
+	type MaxErasToCheckPerBlock = MaxErasToCheckPerBlock;
+	type WeightInfo = ();
+}
+
+/// Create a test environment with nominators `1` and `2`, bonding 50 each, in era `3`.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(1, 100), (2, 100), (3, 5)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		LEDGERS.with(|l| l.borrow_mut().clear());
+		EXPOSURES.with(|e| e.borrow_mut().clear());
+		StakingMock::set_current_era(3);
+		StakingMock::add_nominator(1, 50);
+		StakingMock::add_nominator(2, 50);
+		System::set_block_number(1);
+	});
+	ext
+}
+
+/// Run `on_idle` in the following `n` blocks with unlimited weight.
+pub fn next_blocks(n: u64) {
+	for _ in 0..n {
+		System::set_block_number(System::block_number() + 1);
+		System::on_initialize(System::block_number());
+		FastUnstake::on_idle(System::block_number(), Weight::max_value());
+	}
+}
+
+/// Events of this pallet that have been deposited so far.
+pub fn unstake_events() -> Vec<pallet_fast_unstake::Event<Runtime>> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| match r.event {
+			Event::pallet_fast_unstake(inner) => Some(inner),
+			_ => None,
+		})
+		.collect()
+}
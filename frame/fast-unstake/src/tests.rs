@@ -0,0 +1,194 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for fast-unstake pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnIdle, fungible::InspectHold},
+};
+
+type Event = crate::Event<Runtime>;
+
+/// The funds of `who` on hold for the deposit of this pallet.
+fn deposit_on_hold(who: AccountId) -> Balance {
+	Balances::balance_on_hold(&crate::HoldReason::Deposit.into(), &who)
+}
+
+#[test]
+fn register_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(deposit_on_hold(1), 10);
+		assert_eq!(Queue::<Runtime>::get(1), Some((1, 10)));
+		assert_eq!(
+			StakingMock::ledger(&1),
+			Some(Ledger { active: 0, unlocking: 50, nominating: false }),
+		);
+		assert_eq!(unstake_events(), vec![Event::Registered(1)]);
+	});
+}
+
+#[test]
+fn register_checks_stash() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(5)),
+			Error::<Runtime>::NotController,
+		);
+
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(1)),
+			Error::<Runtime>::AlreadyQueued,
+		);
+		next_blocks(1);
+		assert!(Head::<Runtime>::get().is_some());
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(1)),
+			Error::<Runtime>::AlreadyQueued,
+		);
+
+		assert_ok!(StakingMock::unbond(2, 10));
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(2)),
+			Error::<Runtime>::NotFullyBonded,
+		);
+
+		StakingMock::add_nominator(3, 5);
+		assert_noop!(
+			FastUnstake::register_fast_unstake(Origin::signed(3)),
+			pallet_balances::Error::<Runtime>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn deregister_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(FastUnstake::deregister(Origin::signed(1)), Error::<Runtime>::NotQueued);
+
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+		assert_ok!(FastUnstake::deregister(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Queue::<Runtime>::get(1), None);
+		// Funds keep unbonding.
+		assert_eq!(StakingMock::ledger(&1).unwrap().unlocking, 50);
+		assert_eq!(unstake_events().last(), Some(&Event::Deregistered(1)));
+
+		// Once the stash is being checked it can not leave anymore.
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+		next_blocks(1);
+		assert_noop!(FastUnstake::deregister(Origin::signed(2)), Error::<Runtime>::AlreadyHead);
+	});
+}
+
+#[test]
+fn unstakes_after_all_eras_are_checked() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+
+		next_blocks(1);
+		assert_eq!(Queue::<Runtime>::get(1), None);
+		assert_eq!(Head::<Runtime>::get().unwrap().checked, vec![3, 2]);
+		next_blocks(1);
+		assert_eq!(Head::<Runtime>::get().unwrap().checked, vec![3, 2, 1, 0]);
+		assert!(StakingMock::ledger(&1).is_some());
+
+		next_blocks(1);
+		assert_eq!(Head::<Runtime>::get(), None);
+		assert_eq!(StakingMock::ledger(&1), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(unstake_events(), vec![
+			Event::Registered(1),
+			Event::Checked(1, vec![3, 2]),
+			Event::Checked(1, vec![1, 0]),
+			Event::Unstaked(1, Ok(())),
+		]);
+
+		// Nothing left to do.
+		next_blocks(1);
+		assert_eq!(unstake_events().len(), 4);
+	});
+}
+
+#[test]
+fn new_eras_are_checked_while_in_progress() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+		next_blocks(1);
+
+		StakingMock::set_current_era(4);
+		next_blocks(1);
+		assert_eq!(Head::<Runtime>::get().unwrap().checked, vec![3, 2, 4, 1]);
+		next_blocks(1);
+		assert_eq!(unstake_events().last(), Some(&Event::Unstaked(1, Ok(()))));
+	});
+}
+
+#[test]
+fn exposed_stash_is_slashed() {
+	new_test_ext().execute_with(|| {
+		StakingMock::add_exposure(1, 1);
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+
+		next_blocks(1);
+		assert!(Head::<Runtime>::get().is_some());
+		next_blocks(1);
+		assert_eq!(Head::<Runtime>::get(), None);
+		assert_eq!(unstake_events().last(), Some(&Event::Slashed(1, 10)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(deposit_on_hold(1), 0);
+		assert_eq!(Balances::free_balance(1), 90);
+		// Funds keep unbonding.
+		assert_eq!(StakingMock::ledger(&1).unwrap().unlocking, 50);
+	});
+}
+
+#[test]
+fn queue_is_processed_one_stash_at_a_time() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(2)));
+
+		next_blocks(3);
+		assert_eq!(Queue::<Runtime>::iter().count(), 1);
+		next_blocks(3);
+		assert_eq!(Queue::<Runtime>::iter().count(), 0);
+		assert_eq!(StakingMock::ledger(&1), None);
+		assert_eq!(StakingMock::ledger(&2), None);
+	});
+}
+
+#[test]
+fn on_idle_respects_remaining_weight() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(FastUnstake::register_fast_unstake(Origin::signed(1)));
+
+		assert_eq!(FastUnstake::on_idle(2, 0), 0);
+		assert_eq!(Head::<Runtime>::get(), None);
+
+		let weight = <() as WeightInfo>::on_idle_check(1);
+		assert_eq!(FastUnstake::on_idle(2, weight), weight);
+		assert_eq!(Head::<Runtime>::get().unwrap().checked, vec![3]);
+	});
+}
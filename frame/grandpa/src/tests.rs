@@ -24,7 +24,7 @@ use crate::mock::*;
 use codec::{Decode, Encode};
 use fg_primitives::ScheduledChange;
 use frame_support::{
-	assert_err, assert_ok,
+	assert_err, assert_noop, assert_ok,
 	traits::{Currency, OnFinalize, OneSessionHandler},
 	weights::{GetDispatchInfo, Pays},
 };
@@ -852,6 +852,38 @@ fn always_schedules_a_change_on_new_session_when_stalled() {
 	});
 }
 
+#[test]
+fn note_stalled_forces_change_on_next_session() {
+	new_test_ext(vec![(1, 1), (2, 1), (3, 1)]).execute_with(|| {
+		start_era(1);
+
+		assert_noop!(
+			Grandpa::note_stalled(Origin::signed(1), 10, 1),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(Grandpa::note_stalled(Origin::root(), 10, 1));
+		assert_eq!(Grandpa::stalled(), Some((10, 1)));
+
+		// the forced change is scheduled on the next session, using the given
+		// best finalized block as the median.
+		start_session(4);
+		assert_eq!(Grandpa::stalled(), None);
+		let pending = Grandpa::pending_change().unwrap();
+		assert_eq!(pending.scheduled_at, 4);
+		assert_eq!(pending.delay, 10);
+		assert_eq!(pending.forced, Some(1));
+		assert_eq!(Grandpa::current_set_id(), 2);
+
+		// and signalled to the client in the digest of that block.
+		Grandpa::on_finalize(4);
+		let header = System::finalize();
+		assert!(header.digest.logs.contains(&grandpa_log(ConsensusLog::ForcedChange(
+			1,
+			ScheduledChange { delay: 10, next_authorities: pending.next_authorities },
+		))));
+	});
+}
+
 #[test]
 fn report_equivocation_has_valid_weight() {
 	// the weight depends on the size of the validator set,
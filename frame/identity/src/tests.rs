@@ -82,6 +82,8 @@ impl pallet_balances::Config for Test {
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type WeightInfo = ();
 }
 parameter_types! {
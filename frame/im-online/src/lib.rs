@@ -749,7 +749,7 @@ impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
 
 			// check signature (this is expensive so we do it last).
 			let signature_valid = heartbeat.using_encoded(|encoded_heartbeat| {
-				authority_id.verify(&encoded_heartbeat, &signature)
+				authority_id.batch_verify(&encoded_heartbeat, &signature)
 			});
 
 			if !signature_valid {
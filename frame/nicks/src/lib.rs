@@ -297,6 +297,8 @@ mod tests {
 	}
 	impl pallet_balances::Config for Test {
 		type MaxLocks = ();
+		type HoldReason = ();
+		type MaxHolds = ();
 		type Balance = u64;
 		type Event = Event;
 		type DustRemoval = ();
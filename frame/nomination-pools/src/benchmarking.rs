@@ -0,0 +1,175 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for the nomination pools pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_system::RawOrigin;
+
+use crate::Pallet as Pools;
+
+const SEED: u32 = 0;
+
+/// The maximum number of nominations of `pallet-staking`.
+const MAX_NOMINATIONS: u32 = 16;
+
+fn create_bond<T: Config>() -> BalanceOf<T> {
+	T::MinCreateBond::get().max(T::Staking::minimum_bond())
+}
+
+fn join_bond<T: Config>() -> BalanceOf<T> {
+	T::MinJoinBond::get().max(T::Staking::minimum_bond())
+}
+
+/// Give `who` enough funds to bond `amount` a few times.
+fn fund<T: Config>(who: &T::AccountId, amount: BalanceOf<T>) {
+	T::Currency::make_free_balance_be(
+		who,
+		amount * 10u32.into() + T::Currency::minimum_balance() * 10u32.into(),
+	);
+}
+
+/// Create a pool whose depositor has all roles.
+fn create_pool<T: Config>() -> Result<(T::AccountId, PoolId), &'static str> {
+	let depositor: T::AccountId = account("depositor", 0, SEED);
+	fund::<T>(&depositor, create_bond::<T>());
+	Pools::<T>::create(
+		RawOrigin::Signed(depositor.clone()).into(),
+		create_bond::<T>(),
+		depositor.clone(),
+		depositor.clone(),
+		depositor.clone(),
+	)?;
+	Ok((depositor, <LastPoolId<T>>::get()))
+}
+
+/// Let a new member join `pool_id`.
+fn join_pool<T: Config>(pool_id: PoolId) -> Result<T::AccountId, &'static str> {
+	let member: T::AccountId = account("member", 0, SEED);
+	fund::<T>(&member, join_bond::<T>());
+	Pools::<T>::join(RawOrigin::Signed(member.clone()).into(), join_bond::<T>(), pool_id)?;
+	Ok(member)
+}
+
+/// Pay rewards to `pool_id` so that its members have pending rewards.
+fn reward_pool<T: Config>(pool_id: PoolId) {
+	let _ = T::Currency::deposit_creating(&Pools::<T>::reward_account(pool_id), join_bond::<T>());
+}
+
+benchmarks! {
+	create {
+		let depositor: T::AccountId = account("depositor", 0, SEED);
+		fund::<T>(&depositor, create_bond::<T>());
+	}: _(
+		RawOrigin::Signed(depositor.clone()),
+		create_bond::<T>(),
+		depositor.clone(),
+		depositor.clone(),
+		depositor.clone()
+	)
+	verify {
+		let pool_id = <LastPoolId<T>>::get();
+		assert_eq!(<PoolMembers<T>>::get(&depositor).map(|m| m.pool_id), Some(pool_id));
+	}
+
+	// Rewards that have not been recorded yet have to be distributed before joining.
+	join {
+		let (_, pool_id) = create_pool::<T>()?;
+		reward_pool::<T>(pool_id);
+		let member: T::AccountId = account("member", 0, SEED);
+		fund::<T>(&member, join_bond::<T>());
+	}: _(RawOrigin::Signed(member.clone()), join_bond::<T>(), pool_id)
+	verify {
+		assert_eq!(<PoolMembers<T>>::get(&member).map(|m| m.pool_id), Some(pool_id));
+	}
+
+	claim_payout {
+		let (_, pool_id) = create_pool::<T>()?;
+		let member = join_pool::<T>(pool_id)?;
+		reward_pool::<T>(pool_id);
+		let before = T::Currency::free_balance(&member);
+	}: _(RawOrigin::Signed(member.clone()))
+	verify {
+		assert!(T::Currency::free_balance(&member) > before);
+	}
+
+	// The member unbonds all points and has pending rewards.
+	unbond {
+		let (_, pool_id) = create_pool::<T>()?;
+		let member = join_pool::<T>(pool_id)?;
+		reward_pool::<T>(pool_id);
+		let points = <PoolMembers<T>>::get(&member).ok_or("member not found")?.points;
+	}: _(RawOrigin::Signed(member.clone()), member.clone(), points)
+	verify {
+		let member = <PoolMembers<T>>::get(&member).ok_or("member not found")?;
+		assert!(member.points.is_zero());
+		assert_eq!(member.unbonding_eras.len(), 1);
+	}
+
+	// The last member of a destroying pool withdraws, which removes the pool.
+	withdraw_unbonded {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		Pools::<T>::set_state(
+			RawOrigin::Signed(depositor.clone()).into(),
+			pool_id,
+			PoolState::Destroying,
+		)?;
+		Pools::<T>::unbond(
+			RawOrigin::Signed(depositor.clone()).into(),
+			depositor.clone(),
+			create_bond::<T>(),
+		)?;
+		T::Staking::set_current_era(
+			T::Staking::current_era().saturating_add(T::Staking::bonding_duration()),
+		);
+	}: _(RawOrigin::Signed(depositor.clone()), depositor.clone())
+	verify {
+		assert!(!<PoolMembers<T>>::contains_key(&depositor));
+		assert!(!<BondedPools<T>>::contains_key(pool_id));
+	}
+
+	nominate {
+		let n in 1 .. MAX_NOMINATIONS;
+		let (depositor, pool_id) = create_pool::<T>()?;
+		let validators = (0..n).map(|i| account("validator", i, SEED)).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(depositor), pool_id, validators)
+
+	chill {
+		let (depositor, pool_id) = create_pool::<T>()?;
+		Pools::<T>::nominate(
+			RawOrigin::Signed(depositor.clone()).into(),
+			pool_id,
+			vec![account("validator", 0, SEED)],
+		)?;
+	}: _(RawOrigin::Signed(depositor), pool_id)
+
+	set_state {
+		let (depositor, pool_id) = create_pool::<T>()?;
+	}: _(RawOrigin::Signed(depositor), pool_id, PoolState::Blocked)
+	verify {
+		assert_eq!(<BondedPools<T>>::get(pool_id).map(|p| p.state), Some(PoolState::Blocked));
+	}
+}
+
+impl_benchmark_test_suite!(
+	Pools,
+	crate::mock::new_test_ext(),
+	crate::mock::Runtime,
+);
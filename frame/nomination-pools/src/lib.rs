@@ -0,0 +1,814 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Nomination Pools Pallet
+//!
+//! Allows accounts with small balances to stake together through a shared pool account.
+//!
+//! ## Overview
+//!
+//! A pool is created by a _depositor_ with `create`, who bonds the initial stake and assigns the
+//! pool roles. Other accounts `join` an open pool by transferring funds to it. Each pool has
+//! three accounts derived from `ModuleId`:
+//!
+//! - The _bonded_ account is both stash and controller of the pool's stake. All funds of members
+//!   are bonded from it through [`sp_staking::StakingInterface`].
+//! - The _reward_ account is the payee of the pool's staking rewards.
+//! - Unbonding funds stay in the bonded account, but are tracked in per-era _unbonding pools_
+//!   until they can be withdrawn.
+//!
+//! An account can only be a member of a single pool at a time.
+//!
+//! ### Points
+//!
+//! Members do not own a fixed balance of the pool but _points_. When joining, points are issued
+//! at the current ratio of points to actively bonded balance, and when unbonding, points are
+//! converted back into balance at that same ratio. Unbonding pools use the same mechanism, with a
+//! separate set of points for every era in which funds are unlocked.
+//!
+//! ### Rewards
+//!
+//! Rewards are shared by bonded points. The pool keeps a counter of the reward earned per point
+//! over its whole lifetime and each member remembers the value of that counter at the time they
+//! last claimed. The pending rewards of a member are therefore the difference of the two
+//! counters multiplied by their points. Rewards are claimed with `claim_payout` and are also
+//! paid out automatically whenever the points of a member change.
+//!
+//! ### Slashing
+//!
+//! Slashes of the bonded stake lower the balance backing each bonded point, so they are shared
+//! pro-rata by all members. Slashes of unbonding funds are detected by comparing the unbonding
+//! pools with the funds of the bonded account that are not actively bonded, in which case all
+//! unbonding pools are reduced proportionally.
+//!
+//! ### Roles
+//!
+//! - The _depositor_ creates the pool. Their bond must stay in the pool until every other member
+//!   has left, so they can only fully unbond from a destroying pool of which they are the last
+//!   member.
+//! - The _root_ can do everything the other roles can.
+//! - The _nominator_ selects the validators the pool nominates.
+//! - The _state toggler_ can block the pool, which stops new members from joining and allows
+//!   the state toggler to unbond existing members, or start destroying the pool, after which
+//!   anyone can unbond and withdraw members. A destroying pool is removed once the last member
+//!   withdraws.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create` - Create a new pool and become its depositor.
+//! - `join` - Join a pool with some balance.
+//! - `claim_payout` - Claim the pending rewards of the caller.
+//! - `unbond` - Unbond points of a member.
+//! - `withdraw_unbonded` - Withdraw the unlocked funds of a member.
+//! - `nominate` - Nominate validators on behalf of a pool.
+//! - `chill` - Stop nominating on behalf of a pool.
+//! - `set_state` - Change the state of a pool.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod weights;
+
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use codec::{Encode, Decode};
+use frame_support::traits::{Currency, ExistenceRequirement, Get};
+use sp_runtime::{
+	DispatchError, FixedPointNumber, FixedU128, ModuleId, RuntimeDebug,
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, Bounded, Saturating, UniqueSaturatedFrom,
+		UniqueSaturatedInto, Zero,
+	},
+};
+use sp_staking::{EraIndex, StakingInterface};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// A type alias for the balance type from this pallet's point of view.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of a pool.
+pub type PoolId = u32;
+
+/// The unbonding pools of a pool, keyed by the era in which their funds are unlocked.
+pub type SubPools<Balance> = BTreeMap<EraIndex, UnbondPool<Balance>>;
+
+/// The accounts that are derived for every pool.
+#[derive(Encode, Decode)]
+enum AccountType {
+	Bonded,
+	Reward,
+}
+
+/// State of a pool.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum PoolState {
+	/// The pool is open to be joined.
+	Open,
+	/// The pool can not be joined and the state toggler can unbond members.
+	Blocked,
+	/// The pool is being destroyed. Anyone can unbond and withdraw members, and the pool is
+	/// removed once the last member has withdrawn.
+	Destroying,
+}
+
+/// The accounts with special permissions in a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PoolRoles<AccountId> {
+	/// Creator of the pool. Only allowed to leave once every other member has left.
+	pub depositor: AccountId,
+	/// Can change the state of the pool and nominate.
+	pub root: AccountId,
+	/// Can nominate and chill.
+	pub nominator: AccountId,
+	/// Can change the state of the pool.
+	pub state_toggler: AccountId,
+}
+
+/// A pool of bonded funds.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BondedPool<AccountId, Balance> {
+	/// Total points of all the members of the pool that are still bonded.
+	pub points: Balance,
+	/// Current state of the pool.
+	pub state: PoolState,
+	/// Number of members of the pool, including members that are only unbonding.
+	pub member_counter: u32,
+	/// Accounts with special permissions in the pool.
+	pub roles: PoolRoles<AccountId>,
+}
+
+impl<AccountId: PartialEq, Balance> BondedPool<AccountId, Balance> {
+	fn is_root(&self, who: &AccountId) -> bool {
+		*who == self.roles.root
+	}
+
+	fn can_nominate(&self, who: &AccountId) -> bool {
+		self.is_root(who) || *who == self.roles.nominator
+	}
+
+	fn can_toggle_state(&self, who: &AccountId) -> bool {
+		self.is_root(who) || *who == self.roles.state_toggler
+	}
+
+	/// Whether `who` may unbond and withdraw on behalf of another member.
+	fn can_kick(&self, who: &AccountId) -> bool {
+		match self.state {
+			PoolState::Open => false,
+			PoolState::Blocked => self.is_root(who) || *who == self.roles.state_toggler,
+			PoolState::Destroying => true,
+		}
+	}
+}
+
+/// Reward accounting of a pool.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct RewardPool<Balance> {
+	/// Reward earned per bonded point since the creation of the pool, as of the last update.
+	pub last_recorded_reward_counter: FixedU128,
+	/// Total rewards received by the pool, as of the last update.
+	pub last_recorded_total_payouts: Balance,
+	/// Total rewards paid out to members.
+	pub total_rewards_claimed: Balance,
+}
+
+/// Funds that are unlocked in the same era.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct UnbondPool<Balance> {
+	/// Total points of the members unbonding in this pool.
+	pub points: Balance,
+	/// Balance that is unlocked for the members of this pool.
+	pub balance: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> UnbondPool<Balance> {
+	/// Add `new_funds` to the pool and return the points issued for them.
+	fn issue(&mut self, new_funds: Balance) -> Balance {
+		let points = if self.points.is_zero() || self.balance.is_zero() {
+			new_funds
+		} else {
+			mul_div(new_funds, self.points, self.balance)
+		};
+		self.points = self.points.saturating_add(points);
+		self.balance = self.balance.saturating_add(new_funds);
+		points
+	}
+
+	/// Remove `points` from the pool and return the balance they were backed by.
+	fn dissolve(&mut self, points: Balance) -> Balance {
+		let balance = mul_div(points, self.balance, self.points);
+		self.points = self.points.saturating_sub(points);
+		self.balance = self.balance.saturating_sub(balance);
+		balance
+	}
+}
+
+/// A member of a pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PoolMember<Balance> {
+	/// The pool the member belongs to.
+	pub pool_id: PoolId,
+	/// Bonded points of the member.
+	pub points: Balance,
+	/// Reward counter of the pool when the member last claimed their rewards.
+	pub last_recorded_reward_counter: FixedU128,
+	/// Points of the member in the unbonding pools, keyed by the era of the pool.
+	pub unbonding_eras: BTreeMap<EraIndex, Balance>,
+}
+
+/// Compute `a * b / c` without intermediate overflow, saturating the result.
+fn mul_div<Balance: AtLeast32BitUnsigned + Copy>(a: Balance, b: Balance, c: Balance) -> Balance {
+	if c.is_zero() {
+		return Zero::zero();
+	}
+	let (a, b, c): (u128, u128, u128) =
+		(a.unique_saturated_into(), b.unique_saturated_into(), c.unique_saturated_into());
+	multiply_by_rational(a, b, c)
+		.map(Balance::unique_saturated_from)
+		.unwrap_or_else(|_| Balance::max_value())
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency that is staked.
+		type Currency: Currency<Self::AccountId>;
+		/// The staking system the pools bond and nominate with.
+		type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+		/// The identifier the pool accounts are derived from.
+		#[pallet::constant]
+		type ModuleId: Get<ModuleId>;
+		/// Minimum balance required to join a pool.
+		#[pallet::constant]
+		type MinJoinBond: Get<BalanceOf<Self>>;
+		/// Minimum balance required to create a pool. This is also the minimum balance the
+		/// depositor has to keep bonded while the pool has other members.
+		#[pallet::constant]
+		type MinCreateBond: Get<BalanceOf<Self>>;
+		/// Maximum number of eras in which a member can have funds unbonding at the same time.
+		#[pallet::constant]
+		type MaxUnbonding: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The pool does not exist.
+		PoolNotFound,
+		/// The account is not a member of any pool.
+		PoolMemberNotFound,
+		/// The account is already a member of a pool.
+		AccountBelongsToOtherPool,
+		/// The bond is below the minimum required to join or create a pool.
+		MinimumBondNotMet,
+		/// The pool is not open to be joined.
+		NotOpen,
+		/// All stake of the pool has been slashed, so no new points can be issued.
+		FullySlashed,
+		/// The member does not have enough bonded points.
+		NotEnoughPoints,
+		/// The member has funds unbonding in too many eras.
+		MaxUnbondingLimit,
+		/// None of the unbonding funds of the member are unlocked yet.
+		CannotWithdrawAny,
+		/// The depositor can only leave a destroying pool of which they are the last member.
+		NotOnlyMember,
+		/// The caller does not have the required permissions.
+		DoesNotHavePermission,
+		/// The state of the pool can not be changed to the requested state.
+		CanNotChangeState,
+		/// No more pools can be created.
+		Overflow,
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pool has been created. \[depositor, pool_id\]
+		Created(T::AccountId, PoolId),
+		/// A member bonded funds in a pool. \[member, pool_id, bonded\]
+		Bonded(T::AccountId, PoolId, BalanceOf<T>),
+		/// A member claimed rewards. \[member, pool_id, payout\]
+		PaidOut(T::AccountId, PoolId, BalanceOf<T>),
+		/// Points of a member have been unbonded, to be unlocked in `era`.
+		/// \[member, pool_id, points, balance, era\]
+		Unbonded(T::AccountId, PoolId, BalanceOf<T>, BalanceOf<T>, EraIndex),
+		/// A member withdrew unlocked funds. \[member, pool_id, balance\]
+		Withdrawn(T::AccountId, PoolId, BalanceOf<T>),
+		/// The state of a pool changed. \[pool_id, new_state\]
+		StateChanged(PoolId, PoolState),
+		/// A pool has been removed after its last member left. \[pool_id\]
+		Destroyed(PoolId),
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a new pool, bonding `amount` of the caller's funds. The caller becomes the
+		/// depositor of the pool and its first member.
+		///
+		/// The dispatch origin for this call must be _Signed_. In addition to `amount`, the
+		/// existential deposit is transferred to the reward account of the pool.
+		#[pallet::weight(T::WeightInfo::create())]
+		pub(super) fn create(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+			root: T::AccountId,
+			nominator: T::AccountId,
+			state_toggler: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				amount >= T::MinCreateBond::get().max(T::Staking::minimum_bond()),
+				Error::<T>::MinimumBondNotMet,
+			);
+			ensure!(!<PoolMembers<T>>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+			let pool_id = <LastPoolId<T>>::get().checked_add(1).ok_or(Error::<T>::Overflow)?;
+
+			let bonded_account = Self::bonded_account(pool_id);
+			let reward_account = Self::reward_account(pool_id);
+			T::Currency::transfer(
+				&who,
+				&bonded_account,
+				amount,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			T::Currency::transfer(
+				&who,
+				&reward_account,
+				T::Currency::minimum_balance(),
+				ExistenceRequirement::AllowDeath,
+			)?;
+			T::Staking::bond(bonded_account.clone(), bonded_account, amount, reward_account)?;
+
+			<LastPoolId<T>>::put(pool_id);
+			<BondedPools<T>>::insert(pool_id, BondedPool {
+				points: amount,
+				state: PoolState::Open,
+				member_counter: 1,
+				roles: PoolRoles { depositor: who.clone(), root, nominator, state_toggler },
+			});
+			<RewardPools<T>>::insert(pool_id, RewardPool::default());
+			<PoolMembers<T>>::insert(&who, PoolMember {
+				pool_id,
+				points: amount,
+				last_recorded_reward_counter: Zero::zero(),
+				unbonding_eras: BTreeMap::new(),
+			});
+			Self::deposit_event(Event::Created(who.clone(), pool_id));
+			Self::deposit_event(Event::Bonded(who, pool_id, amount));
+			Ok(().into())
+		}
+
+		/// Join the open pool `pool_id`, bonding `amount` of the caller's funds.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must not be a member of any
+		/// pool yet.
+		#[pallet::weight(T::WeightInfo::join())]
+		pub(super) fn join(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+			pool_id: PoolId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(amount >= T::MinJoinBond::get(), Error::<T>::MinimumBondNotMet);
+			ensure!(!<PoolMembers<T>>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+			let mut bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state == PoolState::Open, Error::<T>::NotOpen);
+			let mut reward_pool = <RewardPools<T>>::get(pool_id).unwrap_or_default();
+
+			let bonded_account = Self::bonded_account(pool_id);
+			let active = T::Staking::active_stake(&bonded_account).unwrap_or_else(Zero::zero);
+			let points = if bonded_pool.points.is_zero() {
+				amount
+			} else {
+				ensure!(!active.is_zero(), Error::<T>::FullySlashed);
+				mul_div(amount, bonded_pool.points, active)
+			};
+			// Rewards received so far belong to the existing members only.
+			let reward_counter =
+				Self::update_recorded_rewards(pool_id, bonded_pool.points, &mut reward_pool);
+
+			T::Currency::transfer(&who, &bonded_account, amount, ExistenceRequirement::KeepAlive)?;
+			T::Staking::bond_extra(bonded_account, amount)?;
+
+			bonded_pool.points = bonded_pool.points.saturating_add(points);
+			bonded_pool.member_counter = bonded_pool.member_counter.saturating_add(1);
+			<BondedPools<T>>::insert(pool_id, bonded_pool);
+			<RewardPools<T>>::insert(pool_id, reward_pool);
+			<PoolMembers<T>>::insert(&who, PoolMember {
+				pool_id,
+				points,
+				last_recorded_reward_counter: reward_counter,
+				unbonding_eras: BTreeMap::new(),
+			});
+			Self::deposit_event(Event::Bonded(who, pool_id, amount));
+			Ok(().into())
+		}
+
+		/// Claim the pending rewards of the caller.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be a member of a pool.
+		#[pallet::weight(T::WeightInfo::claim_payout())]
+		pub(super) fn claim_payout(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut member = <PoolMembers<T>>::get(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let bonded_pool =
+				<BondedPools<T>>::get(member.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut reward_pool = <RewardPools<T>>::get(member.pool_id).unwrap_or_default();
+
+			Self::do_reward_payout(&who, &mut member, bonded_pool.points, &mut reward_pool)?;
+
+			<RewardPools<T>>::insert(member.pool_id, reward_pool);
+			<PoolMembers<T>>::insert(&who, member);
+			Ok(().into())
+		}
+
+		/// Unbond `points` of `member_account`. The corresponding balance is unlocked after the
+		/// bonding duration of the staking system and can then be withdrawn with
+		/// `withdraw_unbonded`. Pending rewards of the member are paid out.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be `member_account`,
+		/// unless the pool is blocked and the caller is its root or state toggler, or the pool is
+		/// being destroyed.
+		///
+		/// The remaining bond of the member must either be zero or at least `MinJoinBond`
+		/// (`MinCreateBond` for the depositor).
+		#[pallet::weight(T::WeightInfo::unbond())]
+		pub(super) fn unbond(
+			origin: OriginFor<T>,
+			member_account: T::AccountId,
+			#[pallet::compact] points: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut member =
+				<PoolMembers<T>>::get(&member_account).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == member_account || bonded_pool.can_kick(&who),
+				Error::<T>::DoesNotHavePermission,
+			);
+			ensure!(!points.is_zero() && points <= member.points, Error::<T>::NotEnoughPoints);
+
+			let bonded_account = Self::bonded_account(pool_id);
+			let active = T::Staking::active_stake(&bonded_account).unwrap_or_else(Zero::zero);
+			let balance = mul_div(points, active, bonded_pool.points);
+			let remaining_points = member.points.saturating_sub(points);
+			let is_depositor = member_account == bonded_pool.roles.depositor;
+			if remaining_points.is_zero() {
+				ensure!(
+					!is_depositor || (
+						bonded_pool.state == PoolState::Destroying &&
+						bonded_pool.member_counter == 1
+					),
+					Error::<T>::NotOnlyMember,
+				);
+			} else {
+				let min_bond = if is_depositor {
+					T::MinCreateBond::get()
+				} else {
+					T::MinJoinBond::get()
+				};
+				let remaining = mul_div(remaining_points, active, bonded_pool.points);
+				ensure!(remaining >= min_bond, Error::<T>::MinimumBondNotMet);
+			}
+
+			let era = T::Staking::current_era().saturating_add(T::Staking::bonding_duration());
+			ensure!(
+				member.unbonding_eras.contains_key(&era) ||
+					(member.unbonding_eras.len() as u32) < T::MaxUnbonding::get(),
+				Error::<T>::MaxUnbondingLimit,
+			);
+
+			// Rewards have to be settled before the points of the member change.
+			let mut reward_pool = <RewardPools<T>>::get(pool_id).unwrap_or_default();
+			Self::do_reward_payout(
+				&member_account,
+				&mut member,
+				bonded_pool.points,
+				&mut reward_pool,
+			)?;
+
+			// Account for any slash of the unbonding funds before issuing new unbonding points.
+			let mut sub_pools = <SubPoolsStorage<T>>::get(pool_id);
+			Self::reconcile_unbonding_pools(&bonded_account, &mut sub_pools);
+			T::Staking::unbond(bonded_account, balance)?;
+			let unbonding_points = sub_pools.entry(era).or_default().issue(balance);
+
+			bonded_pool.points = bonded_pool.points.saturating_sub(points);
+			member.points = remaining_points;
+			let member_unbonding = member.unbonding_eras.entry(era).or_insert_with(Zero::zero);
+			*member_unbonding = member_unbonding.saturating_add(unbonding_points);
+
+			<BondedPools<T>>::insert(pool_id, bonded_pool);
+			<RewardPools<T>>::insert(pool_id, reward_pool);
+			<SubPoolsStorage<T>>::insert(pool_id, sub_pools);
+			<PoolMembers<T>>::insert(&member_account, member);
+			Self::deposit_event(Event::Unbonded(member_account, pool_id, points, balance, era));
+			Ok(().into())
+		}
+
+		/// Withdraw all unlocked funds of `member_account`. The member is removed from the pool
+		/// once they have no bonded or unbonding funds left, and a destroying pool is removed
+		/// together with its last member.
+		///
+		/// The dispatch origin for this call must be _Signed_ and must be `member_account`,
+		/// unless the pool is blocked and the caller is its root or state toggler, or the pool is
+		/// being destroyed.
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+		pub(super) fn withdraw_unbonded(
+			origin: OriginFor<T>,
+			member_account: T::AccountId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut member =
+				<PoolMembers<T>>::get(&member_account).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_id = member.pool_id;
+			let mut bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				who == member_account || bonded_pool.can_kick(&who),
+				Error::<T>::DoesNotHavePermission,
+			);
+
+			let current_era = T::Staking::current_era();
+			let unlocked_eras = member.unbonding_eras.keys()
+				.filter(|era| **era <= current_era)
+				.cloned()
+				.collect::<Vec<_>>();
+			ensure!(!unlocked_eras.is_empty(), Error::<T>::CannotWithdrawAny);
+
+			let bonded_account = Self::bonded_account(pool_id);
+			let mut sub_pools = <SubPoolsStorage<T>>::get(pool_id);
+			Self::reconcile_unbonding_pools(&bonded_account, &mut sub_pools);
+			if T::Staking::total_stake(&bonded_account).is_some() {
+				T::Staking::withdraw_unbonded(bonded_account.clone())?;
+			}
+
+			let mut balance: BalanceOf<T> = Zero::zero();
+			for era in unlocked_eras {
+				let points = member.unbonding_eras.remove(&era).unwrap_or_else(Zero::zero);
+				if let Some(unbond_pool) = sub_pools.get_mut(&era) {
+					balance = balance.saturating_add(unbond_pool.dissolve(points));
+					if unbond_pool.points.is_zero() {
+						sub_pools.remove(&era);
+					}
+				}
+			}
+			T::Currency::transfer(
+				&bonded_account,
+				&member_account,
+				balance,
+				ExistenceRequirement::AllowDeath,
+			)?;
+			Self::deposit_event(Event::Withdrawn(member_account.clone(), pool_id, balance));
+
+			if member.points.is_zero() && member.unbonding_eras.is_empty() {
+				<PoolMembers<T>>::remove(&member_account);
+				bonded_pool.member_counter = bonded_pool.member_counter.saturating_sub(1);
+			} else {
+				<PoolMembers<T>>::insert(&member_account, member);
+			}
+
+			if bonded_pool.member_counter == 0 {
+				Self::dissolve_pool(pool_id, &bonded_pool.roles.depositor);
+			} else {
+				<BondedPools<T>>::insert(pool_id, bonded_pool);
+				<SubPoolsStorage<T>>::insert(pool_id, sub_pools);
+			}
+			Ok(().into())
+		}
+
+		/// Nominate `validators` with the stake of pool `pool_id`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the root or the nominator of
+		/// the pool.
+		#[pallet::weight(T::WeightInfo::nominate(validators.len() as u32))]
+		pub(super) fn nominate(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			validators: Vec<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_nominate(&who), Error::<T>::DoesNotHavePermission);
+			T::Staking::nominate(Self::bonded_account(pool_id), validators)?;
+			Ok(().into())
+		}
+
+		/// Stop nominating with the stake of pool `pool_id`.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the root or the nominator of
+		/// the pool.
+		#[pallet::weight(T::WeightInfo::chill())]
+		pub(super) fn chill(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_nominate(&who), Error::<T>::DoesNotHavePermission);
+			T::Staking::chill(Self::bonded_account(pool_id))?;
+			Ok(().into())
+		}
+
+		/// Set the state of pool `pool_id`. A destroying pool can not change its state anymore.
+		///
+		/// The dispatch origin for this call must be _Signed_ by the root or the state toggler
+		/// of the pool.
+		#[pallet::weight(T::WeightInfo::set_state())]
+		pub(super) fn set_state(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			state: PoolState,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut bonded_pool = <BondedPools<T>>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_toggle_state(&who), Error::<T>::DoesNotHavePermission);
+			ensure!(
+				bonded_pool.state != state && bonded_pool.state != PoolState::Destroying,
+				Error::<T>::CanNotChangeState,
+			);
+			bonded_pool.state = state;
+			<BondedPools<T>>::insert(pool_id, bonded_pool);
+			Self::deposit_event(Event::StateChanged(pool_id, state));
+			Ok(().into())
+		}
+	}
+
+	/// Id of the most recently created pool.
+	#[pallet::storage]
+	pub type LastPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+	/// Bonded pools, keyed by pool id.
+	#[pallet::storage]
+	pub type BondedPools<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		PoolId,
+		BondedPool<T::AccountId, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Reward accounting of the pools, keyed by pool id.
+	#[pallet::storage]
+	pub type RewardPools<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, RewardPool<BalanceOf<T>>, OptionQuery>;
+
+	/// Unbonding pools of the pools, keyed by pool id.
+	#[pallet::storage]
+	pub type SubPoolsStorage<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, SubPools<BalanceOf<T>>, ValueQuery>;
+
+	/// Members of the pools.
+	#[pallet::storage]
+	pub type PoolMembers<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, PoolMember<BalanceOf<T>>, OptionQuery>;
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account that bonds the funds of pool `pool_id`.
+	pub fn bonded_account(pool_id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Bonded, pool_id))
+	}
+
+	/// The account that receives the staking rewards of pool `pool_id`.
+	pub fn reward_account(pool_id: PoolId) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((AccountType::Reward, pool_id))
+	}
+
+	/// Rewards received by the reward account of `pool_id` since its creation.
+	fn total_payouts(pool_id: PoolId, reward_pool: &RewardPool<BalanceOf<T>>) -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::reward_account(pool_id))
+			.saturating_sub(T::Currency::minimum_balance())
+			.saturating_add(reward_pool.total_rewards_claimed)
+	}
+
+	/// Distribute the rewards received since the last update over `bonded_points` and return
+	/// the updated reward counter of the pool.
+	///
+	/// Must be called before the bonded points of the pool change. Rewards received while the
+	/// pool has no bonded points are not distributed.
+	fn update_recorded_rewards(
+		pool_id: PoolId,
+		bonded_points: BalanceOf<T>,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+	) -> FixedU128 {
+		let total_payouts = Self::total_payouts(pool_id, reward_pool);
+		let new_earnings = total_payouts.saturating_sub(reward_pool.last_recorded_total_payouts);
+		if !bonded_points.is_zero() {
+			let new_earnings: u128 = new_earnings.unique_saturated_into();
+			let bonded_points: u128 = bonded_points.unique_saturated_into();
+			reward_pool.last_recorded_reward_counter = reward_pool.last_recorded_reward_counter
+				.saturating_add(FixedU128::saturating_from_rational(new_earnings, bonded_points));
+		}
+		reward_pool.last_recorded_total_payouts = total_payouts;
+		reward_pool.last_recorded_reward_counter
+	}
+
+	/// Pay out the pending rewards of `member_account` and return the paid amount.
+	fn do_reward_payout(
+		member_account: &T::AccountId,
+		member: &mut PoolMember<BalanceOf<T>>,
+		bonded_points: BalanceOf<T>,
+		reward_pool: &mut RewardPool<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let reward_counter =
+			Self::update_recorded_rewards(member.pool_id, bonded_points, reward_pool);
+		let member_points: u128 = member.points.unique_saturated_into();
+		let pending = reward_counter
+			.saturating_sub(member.last_recorded_reward_counter)
+			.saturating_mul_int(member_points);
+		let pending = BalanceOf::<T>::unique_saturated_from(pending);
+		member.last_recorded_reward_counter = reward_counter;
+		if pending.is_zero() {
+			return Ok(pending);
+		}
+
+		T::Currency::transfer(
+			&Self::reward_account(member.pool_id),
+			member_account,
+			pending,
+			ExistenceRequirement::KeepAlive,
+		)?;
+		reward_pool.total_rewards_claimed =
+			reward_pool.total_rewards_claimed.saturating_add(pending);
+		Self::deposit_event(Event::PaidOut(member_account.clone(), member.pool_id, pending));
+		Ok(pending)
+	}
+
+	/// Scale down the unbonding pools if the funds of `bonded_account` that are not actively
+	/// bonded anymore do not cover them, i.e. if unbonding funds have been slashed.
+	///
+	/// Funds are only released from the staking system to the free balance of the bonded
+	/// account, so this must be called before any funds are transferred out of it.
+	fn reconcile_unbonding_pools(
+		bonded_account: &T::AccountId,
+		sub_pools: &mut SubPools<BalanceOf<T>>,
+	) {
+		let active = T::Staking::active_stake(bonded_account).unwrap_or_else(Zero::zero);
+		let available = T::Currency::free_balance(bonded_account).saturating_sub(active);
+		let recorded = sub_pools.values()
+			.fold(BalanceOf::<T>::zero(), |acc, pool| acc.saturating_add(pool.balance));
+		if available >= recorded {
+			return;
+		}
+		for unbond_pool in sub_pools.values_mut() {
+			unbond_pool.balance = mul_div(unbond_pool.balance, available, recorded);
+		}
+	}
+
+	/// Remove pool `pool_id`, sending any funds left in its accounts to `depositor`.
+	fn dissolve_pool(pool_id: PoolId, depositor: &T::AccountId) {
+		for account in [Self::reward_account(pool_id), Self::bonded_account(pool_id)].iter() {
+			let remaining = T::Currency::free_balance(account);
+			// Only rounding dust and undistributed rewards are left at this point, failing to
+			// move them must not prevent the last member from leaving.
+			let _ = T::Currency::transfer(
+				account,
+				depositor,
+				remaining,
+				ExistenceRequirement::AllowDeath,
+			);
+		}
+		<BondedPools<T>>::remove(pool_id);
+		<RewardPools<T>>::remove(pool_id);
+		<SubPoolsStorage<T>>::remove(pool_id);
+		Self::deposit_event(Event::Destroyed(pool_id));
+	}
+}
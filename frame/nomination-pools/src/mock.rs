@@ -0,0 +1,300 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test environment for nomination-pools pallet.
+
+use crate as pallet_nomination_pools;
+use crate::PoolId;
+use sp_core::H256;
+use sp_runtime::{
+	DispatchError, DispatchResult, ModuleId,
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use sp_staking::{EraIndex, StakingInterface};
+use frame_support::{parameter_types, traits::Currency};
+use std::{cell::RefCell, collections::BTreeMap};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+pub type AccountId = u128;
+pub type Balance = u128;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Config<T>, Storage, Event<T>},
+		Pools: pallet_nomination_pools::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 5;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
+}
+
+pub const BONDING_DURATION: EraIndex = 3;
+
+/// A stake of an account in [`StakingMock`].
+#[derive(Clone, Default)]
+pub struct Ledger {
+	pub active: Balance,
+	pub unlocking: Vec<(EraIndex, Balance)>,
+}
+
+thread_local! {
+	static CURRENT_ERA: RefCell<EraIndex> = RefCell::new(0);
+	static LEDGERS: RefCell<BTreeMap<AccountId, Ledger>> = RefCell::new(Default::default());
+	static NOMINATIONS: RefCell<BTreeMap<AccountId, Vec<AccountId>>> =
+		RefCell::new(Default::default());
+}
+
+/// A minimal staking system that only keeps track of the bonded balances. Funds are not locked.
+pub struct StakingMock;
+
+impl StakingMock {
+	pub fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+
+	pub fn ledger(who: &AccountId) -> Option<Ledger> {
+		LEDGERS.with(|l| l.borrow().get(who).cloned())
+	}
+
+	pub fn nominations(who: &AccountId) -> Option<Vec<AccountId>> {
+		NOMINATIONS.with(|n| n.borrow().get(who).cloned())
+	}
+
+	/// Slash `active` from the active stake and `unlocking` from the unlocking chunks of `who`,
+	/// oldest chunk first.
+	pub fn slash(who: &AccountId, active: Balance, unlocking: Balance) {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(who).expect("only bonded accounts are slashed");
+			ledger.active -= active;
+			let mut remaining = unlocking;
+			for (_, value) in ledger.unlocking.iter_mut() {
+				let slashed = remaining.min(*value);
+				*value -= slashed;
+				remaining -= slashed;
+			}
+		});
+		let _ = Balances::slash(who, active + unlocking);
+	}
+}
+
+impl StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Balance {
+		10
+	}
+
+	fn bonding_duration() -> EraIndex {
+		BONDING_DURATION
+	}
+
+	fn current_era() -> EraIndex {
+		CURRENT_ERA.with(|e| *e.borrow())
+	}
+
+	fn stash_by_ctrl(controller: &AccountId) -> Result<AccountId, DispatchError> {
+		Self::ledger(controller).map(|_| *controller).ok_or("not bonded".into())
+	}
+
+	fn is_exposed_in_era(_who: &AccountId, _era: &EraIndex) -> bool {
+		false
+	}
+
+	fn active_stake(controller: &AccountId) -> Option<Balance> {
+		Self::ledger(controller).map(|l| l.active)
+	}
+
+	fn total_stake(controller: &AccountId) -> Option<Balance> {
+		Self::ledger(controller)
+			.map(|l| l.active + l.unlocking.iter().map(|(_, value)| value).sum::<Balance>())
+	}
+
+	fn bond(
+		stash: AccountId,
+		_controller: AccountId,
+		value: Balance,
+		_payee: AccountId,
+	) -> DispatchResult {
+		LEDGERS.with(|l| {
+			l.borrow_mut().insert(stash, Ledger { active: value, unlocking: vec![] })
+		});
+		Ok(())
+	}
+
+	fn bond_extra(stash: AccountId, extra: Balance) -> DispatchResult {
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&stash).ok_or("not bonded")?;
+			ledger.active += extra;
+			Ok(())
+		})
+	}
+
+	fn unbond(controller: AccountId, value: Balance) -> DispatchResult {
+		let era = Self::current_era() + BONDING_DURATION;
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or("not bonded")?;
+			ledger.active = ledger.active.checked_sub(value).ok_or("not enough bonded")?;
+			ledger.unlocking.push((era, value));
+			Ok(())
+		})
+	}
+
+	fn withdraw_unbonded(controller: AccountId) -> DispatchResult {
+		let current_era = Self::current_era();
+		LEDGERS.with(|l| {
+			let mut ledgers = l.borrow_mut();
+			let ledger = ledgers.get_mut(&controller).ok_or("not bonded")?;
+			ledger.unlocking.retain(|(era, _)| *era > current_era);
+			if ledger.active == 0 && ledger.unlocking.is_empty() {
+				ledgers.remove(&controller);
+			}
+			Ok(())
+		})
+	}
+
+	fn nominate(controller: AccountId, validators: Vec<AccountId>) -> DispatchResult {
+		NOMINATIONS.with(|n| n.borrow_mut().insert(controller, validators));
+		Ok(())
+	}
+
+	fn chill(controller: AccountId) -> DispatchResult {
+		NOMINATIONS.with(|n| n.borrow_mut().remove(&controller));
+		Ok(())
+	}
+
+	fn force_unstake(stash: AccountId) -> DispatchResult {
+		LEDGERS.with(|l| l.borrow_mut().remove(&stash)).map(|_| ()).ok_or("not bonded".into())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_current_era(era: EraIndex) {
+		CURRENT_ERA.with(|e| *e.borrow_mut() = era);
+	}
+}
+
+parameter_types! {
+	pub const PoolsModuleId: ModuleId = ModuleId(*b"py/nopls");
+	pub const MinJoinBond: Balance = 10;
+	pub const MinCreateBond: Balance = 10;
+	pub const MaxUnbonding: u32 = 2;
+}
+
+impl pallet_nomination_pools::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Staking = StakingMock;
+	type ModuleId = PoolsModuleId;
+	type MinJoinBond = MinJoinBond;
+	type MinCreateBond = MinCreateBond;
+	type MaxUnbonding = MaxUnbonding;
+	type WeightInfo = ();
+}
+
+/// Depositor, root, nominator and state toggler of the pool created in `new_test_ext`.
+pub const DEPOSITOR: AccountId = 10;
+pub const ROOT: AccountId = 900;
+pub const NOMINATOR: AccountId = 901;
+pub const STATE_TOGGLER: AccountId = 902;
+
+/// Create a test environment with pool `1` created by `DEPOSITOR` with a bond of `10`.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(DEPOSITOR, 100), (20, 100), (30, 100), (40, 100), (ROOT, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		StakingMock::set_current_era(0);
+		LEDGERS.with(|l| l.borrow_mut().clear());
+		NOMINATIONS.with(|n| n.borrow_mut().clear());
+		System::set_block_number(1);
+		Pools::create(Origin::signed(DEPOSITOR), 10, ROOT, NOMINATOR, STATE_TOGGLER).unwrap();
+	});
+	ext
+}
+
+/// Add `amount` of rewards to pool `pool_id`.
+pub fn reward_pool(pool_id: PoolId, amount: Balance) {
+	let reward_account = Pools::reward_account(pool_id);
+	let _ = Balances::deposit_creating(&reward_account, amount);
+}
+
+/// Events of this pallet that have been deposited so far.
+pub fn pool_events() -> Vec<pallet_nomination_pools::Event<Runtime>> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| match r.event {
+			Event::pallet_nomination_pools(inner) => Some(inner),
+			_ => None,
+		})
+		.collect()
+}
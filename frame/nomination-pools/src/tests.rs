@@ -0,0 +1,378 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for nomination-pools pallet.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{assert_noop, assert_ok};
+use pallet_balances::Error as BalancesError;
+
+type Event = crate::Event<Runtime>;
+
+fn bonded_account() -> AccountId {
+	Pools::bonded_account(1)
+}
+
+fn active_stake() -> Balance {
+	StakingMock::ledger(&bonded_account()).map_or(0, |l| l.active)
+}
+
+#[test]
+fn create_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(BondedPools::<Runtime>::get(1), Some(BondedPool {
+			points: 10,
+			state: PoolState::Open,
+			member_counter: 1,
+			roles: PoolRoles {
+				depositor: DEPOSITOR,
+				root: ROOT,
+				nominator: NOMINATOR,
+				state_toggler: STATE_TOGGLER,
+			},
+		}));
+		assert_eq!(RewardPools::<Runtime>::get(1), Some(RewardPool::default()));
+		assert_eq!(PoolMembers::<Runtime>::get(DEPOSITOR).unwrap().points, 10);
+		assert_eq!(LastPoolId::<Runtime>::get(), 1);
+		assert_eq!(active_stake(), 10);
+		assert_eq!(Balances::free_balance(bonded_account()), 10);
+		assert_eq!(Balances::free_balance(Pools::reward_account(1)), ExistentialDeposit::get());
+		assert_eq!(Balances::free_balance(DEPOSITOR), 85);
+		assert_eq!(pool_events(), vec![
+			Event::Created(DEPOSITOR, 1),
+			Event::Bonded(DEPOSITOR, 1, 10),
+		]);
+
+		assert_noop!(
+			Pools::create(Origin::signed(DEPOSITOR), 10, ROOT, NOMINATOR, STATE_TOGGLER),
+			Error::<Runtime>::AccountBelongsToOtherPool,
+		);
+		assert_noop!(
+			Pools::create(Origin::signed(20), 9, ROOT, NOMINATOR, STATE_TOGGLER),
+			Error::<Runtime>::MinimumBondNotMet,
+		);
+		assert_noop!(
+			Pools::create(Origin::signed(20), 200, ROOT, NOMINATOR, STATE_TOGGLER),
+			BalancesError::<Runtime>::InsufficientBalance,
+		);
+
+		assert_ok!(Pools::create(Origin::signed(20), 20, 20, 20, 20));
+		assert_eq!(LastPoolId::<Runtime>::get(), 2);
+		assert_ne!(Pools::bonded_account(2), bonded_account());
+		assert_ne!(Pools::bonded_account(2), Pools::reward_account(2));
+		assert_eq!(StakingMock::ledger(&Pools::bonded_account(2)).unwrap().active, 20);
+	});
+}
+
+#[test]
+fn join_issues_points_at_current_ratio() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, 1));
+		assert_eq!(PoolMembers::<Runtime>::get(20).unwrap().points, 20);
+		assert_eq!(BondedPools::<Runtime>::get(1).unwrap().points, 30);
+		assert_eq!(BondedPools::<Runtime>::get(1).unwrap().member_counter, 2);
+		assert_eq!(active_stake(), 30);
+		assert_eq!(Balances::free_balance(20), 80);
+
+		// After half of the stake is slashed, each point is backed by half the balance.
+		StakingMock::slash(&bonded_account(), 15, 0);
+		assert_ok!(Pools::join(Origin::signed(30), 15, 1));
+		assert_eq!(PoolMembers::<Runtime>::get(30).unwrap().points, 30);
+		assert_eq!(BondedPools::<Runtime>::get(1).unwrap().points, 60);
+		assert_eq!(active_stake(), 30);
+	});
+}
+
+#[test]
+fn join_checks_pool_and_member() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pools::join(Origin::signed(20), 9, 1),
+			Error::<Runtime>::MinimumBondNotMet,
+		);
+		assert_noop!(Pools::join(Origin::signed(20), 10, 2), Error::<Runtime>::PoolNotFound);
+		assert_noop!(
+			Pools::join(Origin::signed(DEPOSITOR), 10, 1),
+			Error::<Runtime>::AccountBelongsToOtherPool,
+		);
+
+		assert_ok!(Pools::set_state(Origin::signed(STATE_TOGGLER), 1, PoolState::Blocked));
+		assert_noop!(Pools::join(Origin::signed(20), 10, 1), Error::<Runtime>::NotOpen);
+
+		assert_ok!(Pools::set_state(Origin::signed(STATE_TOGGLER), 1, PoolState::Open));
+		StakingMock::slash(&bonded_account(), 10, 0);
+		assert_noop!(Pools::join(Origin::signed(20), 10, 1), Error::<Runtime>::FullySlashed);
+	});
+}
+
+#[test]
+fn rewards_are_shared_by_points() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 30, 1));
+		reward_pool(1, 40);
+
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 95);
+		assert_eq!(Balances::free_balance(20), 100);
+
+		// A new member does not earn any of the rewards received before they joined.
+		assert_ok!(Pools::join(Origin::signed(30), 40, 1));
+		assert_ok!(Pools::claim_payout(Origin::signed(30)));
+		assert_eq!(Balances::free_balance(30), 60);
+
+		reward_pool(1, 80);
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_ok!(Pools::claim_payout(Origin::signed(30)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 105);
+		assert_eq!(Balances::free_balance(20), 130);
+		assert_eq!(Balances::free_balance(30), 100);
+
+		// Nothing is left to claim.
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_eq!(Balances::free_balance(20), 130);
+		assert_eq!(Balances::free_balance(Pools::reward_account(1)), ExistentialDeposit::get());
+		assert_eq!(RewardPools::<Runtime>::get(1).unwrap().total_rewards_claimed, 120);
+
+		let payouts = pool_events()
+			.into_iter()
+			.filter(|e| matches!(e, Event::PaidOut(..)))
+			.collect::<Vec<_>>();
+		assert_eq!(payouts, vec![
+			Event::PaidOut(DEPOSITOR, 1, 10),
+			Event::PaidOut(20, 1, 30),
+			Event::PaidOut(DEPOSITOR, 1, 10),
+			Event::PaidOut(20, 1, 30),
+			Event::PaidOut(30, 1, 40),
+		]);
+		assert_noop!(
+			Pools::claim_payout(Origin::signed(40)),
+			Error::<Runtime>::PoolMemberNotFound,
+		);
+	});
+}
+
+#[test]
+fn unbond_pays_out_pending_rewards() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 10, 1));
+		reward_pool(1, 20);
+
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 10));
+		assert_eq!(Balances::free_balance(20), 100);
+
+		// Rewards received afterwards only go to the remaining bonded points.
+		reward_pool(1, 20);
+		assert_ok!(Pools::claim_payout(Origin::signed(DEPOSITOR)));
+		assert_eq!(Balances::free_balance(DEPOSITOR), 85 + 10 + 20);
+		assert_ok!(Pools::claim_payout(Origin::signed(20)));
+		assert_eq!(Balances::free_balance(20), 100);
+	});
+}
+
+#[test]
+fn unbond_and_withdraw_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, 1));
+
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+		assert_eq!(pool_events().last(), Some(&Event::Unbonded(20, 1, 20, 20, 3)));
+		let member = PoolMembers::<Runtime>::get(20).unwrap();
+		assert_eq!(member.points, 0);
+		assert_eq!(member.unbonding_eras.into_iter().collect::<Vec<_>>(), vec![(3, 20)]);
+		assert_eq!(
+			SubPoolsStorage::<Runtime>::get(1).into_iter().collect::<Vec<_>>(),
+			vec![(3, UnbondPool { points: 20, balance: 20 })],
+		);
+		assert_eq!(BondedPools::<Runtime>::get(1).unwrap().points, 10);
+		assert_eq!(active_stake(), 10);
+
+		StakingMock::set_current_era(2);
+		assert_noop!(
+			Pools::withdraw_unbonded(Origin::signed(20), 20),
+			Error::<Runtime>::CannotWithdrawAny,
+		);
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(pool_events().last(), Some(&Event::Withdrawn(20, 1, 20)));
+		assert_eq!(Balances::free_balance(20), 100);
+		assert_eq!(PoolMembers::<Runtime>::get(20), None);
+		assert_eq!(BondedPools::<Runtime>::get(1).unwrap().member_counter, 1);
+		assert!(SubPoolsStorage::<Runtime>::get(1).is_empty());
+		assert_eq!(StakingMock::total_stake(&bonded_account()), Some(10));
+	});
+}
+
+#[test]
+fn unbond_checks_points_and_limits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 40, 1));
+
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 0), Error::<Runtime>::NotEnoughPoints);
+		assert_noop!(Pools::unbond(Origin::signed(20), 20, 41), Error::<Runtime>::NotEnoughPoints);
+		assert_noop!(
+			Pools::unbond(Origin::signed(20), 20, 35),
+			Error::<Runtime>::MinimumBondNotMet,
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 10),
+			Error::<Runtime>::DoesNotHavePermission,
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 30, 10),
+			Error::<Runtime>::PoolMemberNotFound,
+		);
+
+		// Unbonding twice in the same era uses the same unbonding pool.
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 5));
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 5));
+		StakingMock::set_current_era(1);
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 5));
+		StakingMock::set_current_era(2);
+		assert_noop!(
+			Pools::unbond(Origin::signed(20), 20, 5),
+			Error::<Runtime>::MaxUnbondingLimit,
+		);
+
+		let member = PoolMembers::<Runtime>::get(20).unwrap();
+		assert_eq!(member.points, 25);
+		assert_eq!(member.unbonding_eras.into_iter().collect::<Vec<_>>(), vec![(3, 10), (4, 5)]);
+	});
+}
+
+#[test]
+fn slashes_are_applied_pro_rata() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, 1));
+		assert_ok!(Pools::join(Origin::signed(30), 30, 1));
+		assert_ok!(Pools::unbond(Origin::signed(30), 30, 30));
+		assert_eq!(active_stake(), 30);
+
+		// Half of both the bonded and the unbonding funds are slashed.
+		StakingMock::slash(&bonded_account(), 15, 15);
+
+		// 20 points of 30 are now backed by 10 of the 15 bonded.
+		assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+		assert_eq!(pool_events().last(), Some(&Event::Unbonded(20, 1, 20, 10, 3)));
+		assert_eq!(
+			SubPoolsStorage::<Runtime>::get(1).into_iter().collect::<Vec<_>>(),
+			vec![(3, UnbondPool { points: 50, balance: 25 })],
+		);
+
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(30), 30));
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20));
+		assert_eq!(Balances::free_balance(30), 100 - 30 + 15);
+		assert_eq!(Balances::free_balance(20), 100 - 20 + 10);
+		assert_eq!(active_stake(), 5);
+		assert_eq!(Balances::free_balance(bonded_account()), 5);
+	});
+}
+
+#[test]
+fn roles_are_enforced() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, 1));
+
+		assert_noop!(
+			Pools::nominate(Origin::signed(20), 1, vec![1, 2]),
+			Error::<Runtime>::DoesNotHavePermission,
+		);
+		assert_noop!(
+			Pools::nominate(Origin::signed(NOMINATOR), 2, vec![1, 2]),
+			Error::<Runtime>::PoolNotFound,
+		);
+		assert_ok!(Pools::nominate(Origin::signed(NOMINATOR), 1, vec![1, 2]));
+		assert_eq!(StakingMock::nominations(&bonded_account()), Some(vec![1, 2]));
+		assert_ok!(Pools::nominate(Origin::signed(ROOT), 1, vec![3]));
+		assert_eq!(StakingMock::nominations(&bonded_account()), Some(vec![3]));
+		assert_noop!(Pools::chill(Origin::signed(20), 1), Error::<Runtime>::DoesNotHavePermission);
+		assert_ok!(Pools::chill(Origin::signed(NOMINATOR), 1));
+		assert_eq!(StakingMock::nominations(&bonded_account()), None);
+
+		assert_noop!(
+			Pools::set_state(Origin::signed(NOMINATOR), 1, PoolState::Blocked),
+			Error::<Runtime>::DoesNotHavePermission,
+		);
+		assert_noop!(
+			Pools::set_state(Origin::signed(ROOT), 1, PoolState::Open),
+			Error::<Runtime>::CanNotChangeState,
+		);
+		assert_ok!(Pools::set_state(Origin::signed(ROOT), 1, PoolState::Blocked));
+		assert_eq!(pool_events().last(), Some(&Event::StateChanged(1, PoolState::Blocked)));
+
+		// Only the root and the state toggler can unbond other members of a blocked pool.
+		assert_noop!(
+			Pools::unbond(Origin::signed(30), 20, 20),
+			Error::<Runtime>::DoesNotHavePermission,
+		);
+		assert_ok!(Pools::unbond(Origin::signed(STATE_TOGGLER), 20, 10));
+
+		assert_ok!(Pools::set_state(Origin::signed(STATE_TOGGLER), 1, PoolState::Destroying));
+		assert_noop!(
+			Pools::set_state(Origin::signed(ROOT), 1, PoolState::Open),
+			Error::<Runtime>::CanNotChangeState,
+		);
+	});
+}
+
+#[test]
+fn destroying_pool_is_removed_with_last_member() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::join(Origin::signed(20), 20, 1));
+		reward_pool(1, 3);
+
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 10),
+			Error::<Runtime>::NotOnlyMember,
+		);
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 5),
+			Error::<Runtime>::MinimumBondNotMet,
+		);
+
+		assert_ok!(Pools::set_state(Origin::signed(STATE_TOGGLER), 1, PoolState::Destroying));
+		assert_noop!(
+			Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 10),
+			Error::<Runtime>::NotOnlyMember,
+		);
+
+		// Anyone can unbond and withdraw the members of a destroying pool.
+		assert_ok!(Pools::unbond(Origin::signed(40), 20, 20));
+		StakingMock::set_current_era(3);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(40), 20));
+		assert_eq!(Balances::free_balance(20), 100 + 2);
+
+		assert_ok!(Pools::unbond(Origin::signed(DEPOSITOR), DEPOSITOR, 10));
+		StakingMock::set_current_era(6);
+		assert_ok!(Pools::withdraw_unbonded(Origin::signed(DEPOSITOR), DEPOSITOR));
+		assert_eq!(pool_events().last(), Some(&Event::Destroyed(1)));
+
+		assert_eq!(BondedPools::<Runtime>::get(1), None);
+		assert_eq!(RewardPools::<Runtime>::get(1), None);
+		assert_eq!(PoolMembers::<Runtime>::get(DEPOSITOR), None);
+		assert!(StakingMock::ledger(&bonded_account()).is_none());
+		assert_eq!(Balances::free_balance(bonded_account()), 0);
+		assert_eq!(Balances::free_balance(Pools::reward_account(1)), 0);
+		// The deposit, the existential deposit of the reward account and all rewards that were
+		// not claimed by other members are returned.
+		assert_eq!(Balances::free_balance(DEPOSITOR), 100 + 1);
+	});
+}
@@ -78,6 +78,8 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = u128;
 	type DustRemoval = ();
 	type Event = Event;
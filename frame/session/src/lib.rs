@@ -125,7 +125,7 @@ use frame_support::{
 	ensure, decl_module, decl_event, decl_storage, decl_error, ConsensusEngineId, Parameter,
 	traits::{
 		Get, FindAuthor, ValidatorRegistration, EstimateNextSessionRotation, EstimateNextNewSession,
-		OneSessionHandler, ValidatorSet,
+		OneSessionHandler, ValidatorSet, DisabledValidators,
 	},
 	dispatch::{self, DispatchResult, DispatchError},
 	weights::Weight,
@@ -841,6 +841,12 @@ impl<T: Config> ValidatorSet<T::AccountId> for Module<T> {
 	}
 }
 
+impl<T: Config> DisabledValidators for Module<T> {
+	fn is_disabled(index: u32) -> bool {
+		<Module<T>>::disabled_validators().binary_search(&index).is_ok()
+	}
+}
+
 /// Wraps the author-scraping logic for consensus engines that can recover
 /// the canonical index of an author. This then transforms it into the
 /// registering account-ID of that session key index.
@@ -70,6 +70,8 @@ parameter_types! {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = ();
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = Balance;
 	type Event = Event;
 	type DustRemoval = ();
@@ -180,7 +182,7 @@ impl pallet_staking::Config for Test {
 	type SlashCancelOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type BondingDuration = ();
 	type SessionInterface = Self;
-	type RewardCurve = RewardCurve;
+	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type NextNewSession = Session;
 	type ElectionLookahead = ();
 	type Call = Call;
@@ -191,4 +193,5 @@ impl pallet_staking::Config for Test {
 	type OffchainSolutionWeightLimit = ();
 	type WeightInfo = ();
 	type ElectionProvider = MockElectionProvider;
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
 }
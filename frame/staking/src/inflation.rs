@@ -19,9 +19,15 @@
 //! the total payout for the era given the era duration and the staking rate in NPoS.
 //! The staking rate in NPoS is the total amount of tokens staked by nominators and validators,
 //! divided by the total token supply.
+//!
+//! It also exposes `compute_fixed_payout`, which returns the payout for the era given a fixed
+//! yearly inflation rate, regardless of the staking rate.
 
 use sp_runtime::{Perbill, traits::AtLeast32BitUnsigned, curve::PiecewiseLinear};
 
+/// Milliseconds per year for the Julian year (365.25 days).
+const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
+
 /// The total payout to all validators (and their nominators) per era and maximum payout.
 ///
 /// Defined as such:
@@ -35,9 +41,6 @@ pub fn compute_total_payout<N>(
 	total_tokens: N,
 	era_duration: u64
 ) -> (N, N) where N: AtLeast32BitUnsigned + Clone {
-	// Milliseconds per year for the Julian year (365.25 days).
-	const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
-
 	let portion = Perbill::from_rational_approximation(era_duration as u64, MILLISECONDS_PER_YEAR);
 	let payout = portion * yearly_inflation.calculate_for_fraction_times_denominator(
 		npos_token_staked,
@@ -47,6 +50,22 @@ pub fn compute_total_payout<N>(
 	(payout, maximum)
 }
 
+/// The total payout to all validators (and their nominators) per era, for a fixed yearly
+/// inflation rate.
+///
+/// Defined as such:
+/// `staker-payout = yearly_inflation * total_tokens / era_per_year`
+///
+/// `era_duration` is expressed in millisecond.
+pub fn compute_fixed_payout<N>(
+	yearly_inflation: Perbill,
+	total_tokens: N,
+	era_duration: u64
+) -> N where N: AtLeast32BitUnsigned + Clone {
+	let portion = Perbill::from_rational_approximation(era_duration, MILLISECONDS_PER_YEAR);
+	portion * (yearly_inflation * total_tokens)
+}
+
 #[cfg(test)]
 mod test {
 	use sp_runtime::curve::PiecewiseLinear;
@@ -102,4 +121,19 @@ mod test {
 			57_038_500_000_000_000_000_000
 		);
 	}
+
+	#[test]
+	fn fixed_payout_is_sensible() {
+		use sp_runtime::Perbill;
+		const YEAR: u64 = 365 * 24 * 60 * 60 * 1000;
+		const DAY: u64 = 24 * 60 * 60 * 1000;
+
+		let ten_percent = Perbill::from_percent(10);
+
+		// not 10_000 due to rounding error, the same way as the maximum of the curve.
+		assert_eq!(super::compute_fixed_payout(ten_percent, 100_000u64, YEAR), 9_993);
+		assert_eq!(super::compute_fixed_payout(ten_percent, 1_000_000_000u64, DAY), 273_785);
+		assert_eq!(super::compute_fixed_payout(Perbill::zero(), 100_000u64, YEAR), 0);
+		assert_eq!(super::compute_fixed_payout(ten_percent, 100_000u64, 0), 0);
+	}
 }
@@ -174,8 +174,12 @@
 //!
 //! ### Era payout
 //!
-//! The era payout is computed using yearly inflation curve defined at
-//! [`T::RewardCurve`](./trait.Config.html#associatedtype.RewardCurve) as such:
+//! The era payout is computed by
+//! [`T::EraPayout`](./trait.Config.html#associatedtype.EraPayout), which returns the payout of
+//! the stakers and a remaining payout.
+//!
+//! Using [`ConvertCurve`](./struct.ConvertCurve.html), the era payout is computed using a
+//! yearly inflation curve as such:
 //!
 //! ```nocompile
 //! staker_payout = yearly_inflation(npos_token_staked / total_tokens) * total_tokens / era_per_year
@@ -185,6 +189,14 @@
 //! ```nocompile
 //! remaining_payout = max_yearly_inflation * total_tokens / era_per_year - staker_payout
 //! ```
+//!
+//! Using [`FixedInflation`](./struct.FixedInflation.html), the stakers are paid a fixed yearly
+//! inflation rate of the total tokens, and there is no remaining payout:
+//!
+//! ```nocompile
+//! staker_payout = yearly_inflation * total_tokens / era_per_year
+//! ```
+//!
 //! The remaining reward is send to the configurable end-point
 //! [`T::RewardRemainder`](./trait.Config.html#associatedtype.RewardRemainder).
 //!
@@ -293,7 +305,7 @@ use codec::{HasCompact, Encode, Decode};
 use frame_support::{
 	decl_module, decl_event, decl_storage, ensure, decl_error,
 	weights::{Weight, constants::{WEIGHT_PER_MICROS, WEIGHT_PER_NANOS}},
-	storage::IterableStorageMap,
+	storage::{IterableStorageMap, IterableStorageDoubleMap},
 	dispatch::{
 		DispatchResult, DispatchResultWithPostInfo, DispatchErrorWithPostInfo,
 		WithPostDispatchInfo,
@@ -317,7 +329,7 @@ use sp_runtime::{
 	},
 };
 use sp_staking::{
-	SessionIndex,
+	SessionIndex, StakingInterface,
 	offence::{OnOffenceHandler, OffenceDetails, Offence, ReportOffence, OffenceError},
 };
 #[cfg(feature = "std")]
@@ -331,7 +343,7 @@ use sp_npos_elections::{
 	to_supports, EvaluateSupport, seq_phragmen, generate_solution_type, is_score_better, Supports,
 	VoteWeight, CompactSolution, PerThing128,
 };
-use sp_election_providers::ElectionProvider;
+use sp_election_providers::{ElectionProvider, SortedListProvider, VoteWeightProvider};
 pub use weights::WeightInfo;
 
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -368,8 +380,7 @@ pub const MAX_NOMINATIONS: usize =
 
 pub const MAX_UNLOCKING_CHUNKS: usize = 32;
 
-/// Counter for the number of eras that have passed.
-pub type EraIndex = u32;
+pub use sp_staking::EraIndex;
 
 /// Counter for the number of "reward" points earned by a given validator.
 pub type RewardPoint = u32;
@@ -782,6 +793,77 @@ impl<T: Config> SessionInterface<<T as frame_system::Config>::AccountId> for T w
 	}
 }
 
+/// Handler for determining how much of a balance should be paid out on the current era.
+pub trait EraPayout<Balance> {
+	/// Determine the payout for this era.
+	///
+	/// Returns the amount to be paid to stakers in this era, as well as whatever else should be
+	/// paid out ("the rest"), which is given to `Config::RewardRemainder`.
+	///
+	/// `era_duration_millis` is the duration of the era, in milliseconds.
+	fn era_payout(
+		total_staked: Balance,
+		total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance);
+}
+
+impl<Balance: Default> EraPayout<Balance> for () {
+	fn era_payout(
+		_total_staked: Balance,
+		_total_issuance: Balance,
+		_era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		(Default::default(), Default::default())
+	}
+}
+
+/// Adaptor to turn a `PiecewiseLinear` curve definition into an `EraPayout` impl, used for
+/// backwards compatibility.
+///
+/// The stakers are paid according to the staking rate, and the rest of the maximum yearly
+/// inflation of the curve is paid to `Config::RewardRemainder`.
+pub struct ConvertCurve<T>(sp_std::marker::PhantomData<T>);
+impl<
+	Balance: AtLeast32BitUnsigned + Clone,
+	T: Get<&'static PiecewiseLinear<'static>>,
+> EraPayout<Balance> for ConvertCurve<T> {
+	fn era_payout(
+		total_staked: Balance,
+		total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		let (validator_payout, max_payout) = inflation::compute_total_payout(
+			&T::get(),
+			total_staked,
+			total_issuance,
+			era_duration_millis,
+		);
+		let rest = max_payout.saturating_sub(validator_payout.clone());
+		(validator_payout, rest)
+	}
+}
+
+/// An `EraPayout` impl paying a fixed yearly inflation rate of the total issuance to the
+/// stakers, regardless of the staking rate. Nothing is paid to `Config::RewardRemainder`.
+pub struct FixedInflation<Rate>(sp_std::marker::PhantomData<Rate>);
+impl<Balance: AtLeast32BitUnsigned + Clone, Rate: Get<Perbill>> EraPayout<Balance>
+	for FixedInflation<Rate>
+{
+	fn era_payout(
+		_total_staked: Balance,
+		total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		let payout = inflation::compute_fixed_payout(
+			Rate::get(),
+			total_issuance,
+			era_duration_millis,
+		);
+		(payout, Zero::zero())
+	}
+}
+
 pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 	/// The staking balance.
 	type Currency: LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
@@ -807,6 +889,11 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 		DataProvider = Module<Self>,
 	>;
 
+	/// Something that can provide a sorted list of voters in a somewhat sorted way. The original
+	/// use case for this was designed with `pallet_bags_list::Pallet` in mind. If the bags-list is
+	/// not desired, [`UseNominatorsMap`] is likely the desired option.
+	type SortedListProvider: SortedListProvider<Self::AccountId>;
+
 	/// Tokens have been minted and are unused for validator-reward.
 	/// See [Era payout](./index.html#era-payout).
 	type RewardRemainder: OnUnbalanced<NegativeImbalanceOf<Self>>;
@@ -838,9 +925,9 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 	/// Interface for interacting with a session module.
 	type SessionInterface: self::SessionInterface<Self::AccountId>;
 
-	/// The NPoS reward curve used to define yearly inflation.
+	/// The payout for validators and the system for the current era.
 	/// See [Era payout](./index.html#era-payout).
-	type RewardCurve: Get<&'static PiecewiseLinear<'static>>;
+	type EraPayout: EraPayout<BalanceOf<Self>>;
 
 	/// Something that can estimate the next session change, accurately or as a best effort guess.
 	type NextNewSession: EstimateNextNewSession<Self::BlockNumber>;
@@ -918,11 +1005,13 @@ enum Releases {
 	V3_0_0,
 	V4_0_0,
 	V5_0_0,
+	V6_0_0,
+	V7_0_0,
 }
 
 impl Default for Releases {
 	fn default() -> Self {
-		Releases::V5_0_0
+		Releases::V7_0_0
 	}
 }
 
@@ -1011,6 +1100,18 @@ decl_storage! {
 			double_map hasher(twox_64_concat) EraIndex, hasher(twox_64_concat) T::AccountId
 			=> Exposure<T::AccountId, BalanceOf<T>>;
 
+		/// The nominators that back any validator in [`ErasStakers`] at era.
+		///
+		/// This allows to check whether a nominator was exposed in an era without iterating over
+		/// all exposures of the era.
+		///
+		/// This is keyed first by the era index to allow bulk deletion and then the stash account.
+		///
+		/// Is it removed after `HISTORY_DEPTH` eras.
+		pub ErasExposedNominators get(fn eras_exposed_nominators):
+			double_map hasher(twox_64_concat) EraIndex, hasher(twox_64_concat) T::AccountId
+			=> bool;
+
 		/// Similar to `ErasStakers`, this holds the preferences of validators.
 		///
 		/// This is keyed first by the era index to allow bulk deletion and then the stash account.
@@ -1126,8 +1227,8 @@ decl_storage! {
 		/// True if network has been upgraded to this version.
 		/// Storage version of the pallet.
 		///
-		/// This is set to v5.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V5_0_0): Releases;
+		/// This is set to v7.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V7_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -1184,6 +1285,34 @@ pub mod migrations {
 		ErasValidatorPrefs::<T>::translate::<OldValidatorPrefs, _>(|_, _, p| Some(p.upgraded()));
 		T::BlockWeights::get().max_block
 	}
+
+	/// Insert all existing nominators into [`Config::SortedListProvider`].
+	///
+	/// Before the sorted list was introduced nominators were only tracked in [`Nominators`].
+	pub fn populate_sorted_list<T: Config>() -> frame_support::weights::Weight {
+		let inserted = T::SortedListProvider::regenerate(
+			Nominators::<T>::iter().map(|(nominator, _)| nominator),
+			Box::new(Module::<T>::weight_of),
+		);
+		log!(info, "inserted {} nominators into the sorted list", inserted);
+		T::BlockWeights::get().max_block
+	}
+
+	/// Index the nominators of all exposures that are still kept in [`ErasStakers`].
+	///
+	/// Before [`ErasExposedNominators`] was introduced the exposures of an era had to be iterated
+	/// in order to find out whether a nominator was exposed.
+	pub fn populate_exposed_nominators<T: Config>() -> frame_support::weights::Weight {
+		let mut indexed = 0u32;
+		ErasStakers::<T>::iter().for_each(|(era, _, exposure)| {
+			for individual in exposure.others {
+				ErasExposedNominators::<T>::insert(era, individual.who, true);
+				indexed = indexed.saturating_add(1);
+			}
+		});
+		log!(info, "indexed {} exposed nominators", indexed);
+		T::BlockWeights::get().max_block
+	}
 }
 
 decl_event!(
@@ -1339,12 +1468,20 @@ decl_module! {
 		fn deposit_event() = default;
 
 		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut weight = 0;
 			if StorageVersion::get() == Releases::V4_0_0 {
 				StorageVersion::put(Releases::V5_0_0);
-				migrations::migrate_to_blockable::<T>()
-			} else {
-				0
+				weight = weight.saturating_add(migrations::migrate_to_blockable::<T>());
+			}
+			if StorageVersion::get() == Releases::V5_0_0 {
+				StorageVersion::put(Releases::V6_0_0);
+				weight = weight.saturating_add(migrations::populate_sorted_list::<T>());
 			}
+			if StorageVersion::get() == Releases::V6_0_0 {
+				StorageVersion::put(Releases::V7_0_0);
+				weight = weight.saturating_add(migrations::populate_exposed_nominators::<T>());
+			}
+			weight
 		}
 
 		/// sets `ElectionStatus` to `Open(now)` where `now` is the block number at which the
@@ -1724,7 +1861,7 @@ decl_module! {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
+			Self::do_remove_nominator(stash);
 			<Validators<T>>::insert(stash, prefs);
 		}
 
@@ -1775,7 +1912,7 @@ decl_module! {
 			};
 
 			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_add_nominator(stash, nominations);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -2474,12 +2611,22 @@ impl<T: Config> Module<T> {
 			WithdrawReasons::all(),
 		);
 		<Ledger<T>>::insert(controller, ledger);
+
+		// a change in the active stake of a nominator may move it within the voter list.
+		if T::SortedListProvider::contains(&ledger.stash) {
+			T::SortedListProvider::on_update(&ledger.stash, Self::weight_of(&ledger.stash));
+		}
+	}
+
+	/// Number of slashing spans of `stash`, as required to remove its staking information.
+	fn num_slashing_spans(stash: &T::AccountId) -> u32 {
+		Self::slashing_spans(stash).map(|spans| spans.iter().count() as u32).unwrap_or(0)
 	}
 
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2838,14 +2985,12 @@ impl<T: Config> Module<T> {
 			let now_as_millis_u64 = T::UnixTime::now().as_millis().saturated_into::<u64>();
 
 			let era_duration = now_as_millis_u64 - active_era_start;
-			let (validator_payout, max_payout) = inflation::compute_total_payout(
-				&T::RewardCurve::get(),
+			let (validator_payout, rest) = T::EraPayout::era_payout(
 				Self::eras_total_stake(&active_era.index),
 				T::Currency::total_issuance(),
 				// Duration of era; more than u64::MAX is rewarded as u64::MAX.
 				era_duration.saturated_into::<u64>(),
 			);
-			let rest = max_payout.saturating_sub(validator_payout);
 
 			Self::deposit_event(RawEvent::EraPayout(active_era.index, validator_payout, rest));
 
@@ -2916,6 +3061,9 @@ impl<T: Config> Module<T> {
 			exposures.into_iter().for_each(|(stash, exposure)| {
 				total_stake = total_stake.saturating_add(exposure.total);
 				<ErasStakers<T>>::insert(current_era, &stash, &exposure);
+				for individual in &exposure.others {
+					<ErasExposedNominators<T>>::insert(current_era, &individual.who, true);
+				}
 
 				let mut exposure_clipped = exposure;
 				let clipped_max_len = T::MaxNominatorRewardedPerValidator::get() as usize;
@@ -3149,6 +3297,9 @@ impl<T: Config> Module<T> {
 		exposures.into_iter().for_each(|(stash, exposure)| {
 			total_stake = total_stake.saturating_add(exposure.total);
 			<ErasStakers<T>>::insert(current_era, &stash, &exposure);
+			for individual in &exposure.others {
+				<ErasExposedNominators<T>>::insert(current_era, &individual.who, true);
+			}
 
 			let mut exposure_clipped = exposure;
 			let clipped_max_len = T::MaxNominatorRewardedPerValidator::get() as usize;
@@ -3210,7 +3361,7 @@ impl<T: Config> Module<T> {
 
 		<Payee<T>>::remove(stash);
 		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_nominator(stash);
 
 		system::Module::<T>::dec_consumers(stash);
 
@@ -3221,6 +3372,7 @@ impl<T: Config> Module<T> {
 	fn clear_era_information(era_index: EraIndex) {
 		<ErasStakers<T>>::remove_prefix(era_index);
 		<ErasStakersClipped<T>>::remove_prefix(era_index);
+		<ErasExposedNominators<T>>::remove_prefix(era_index);
 		<ErasValidatorPrefs<T>>::remove_prefix(era_index);
 		<ErasValidatorReward<T>>::remove(era_index);
 		<ErasRewardPoints<T>>::remove(era_index);
@@ -3290,6 +3442,9 @@ impl<T: Config> Module<T> {
 		controller: T::AccountId,
 		exposure: Exposure<T::AccountId, BalanceOf<T>>,
 	) {
+		for individual in &exposure.others {
+			<ErasExposedNominators<T>>::insert(&current_era, &individual.who, true);
+		}
 		<ErasStakers<T>>::insert(&current_era, &controller, &exposure);
 	}
 
@@ -3303,27 +3458,76 @@ impl<T: Config> Module<T> {
 		SlashRewardFraction::put(fraction);
 	}
 
+	/// Same as [`Self::slashable_balance_of_vote_weight`], using the current total issuance.
+	pub fn weight_of(who: &T::AccountId) -> VoteWeight {
+		Self::slashable_balance_of_vote_weight(who, T::Currency::total_issuance())
+	}
+
+	/// Add `who` as a nominator with the given `nominations`, keeping
+	/// [`Config::SortedListProvider`] in sync.
+	///
+	/// If `who` is already a nominator, only its nominations are updated.
+	pub fn do_add_nominator(who: &T::AccountId, nominations: Nominations<T::AccountId>) {
+		if !Nominators::<T>::contains_key(who) {
+			// maybe update the sorted list. Errors are only logged, the nominator is still added.
+			let _ = T::SortedListProvider::on_insert(who.clone(), Self::weight_of(who)).map_err(
+				|err| log!(warn, "failed to insert {:?} into the sorted list: {:?}", who, err),
+			);
+		}
+		Nominators::<T>::insert(who, nominations);
+	}
+
+	/// Remove `who` as a nominator, keeping [`Config::SortedListProvider`] in sync.
+	///
+	/// Returns `true` if `who` was a nominator.
+	pub fn do_remove_nominator(who: &T::AccountId) -> bool {
+		if Nominators::<T>::contains_key(who) {
+			Nominators::<T>::remove(who);
+			T::SortedListProvider::on_remove(who);
+			true
+		} else {
+			false
+		}
+	}
+
 	/// Get all of the voters that are eligible for the npos election.
 	///
-	/// This will use all on-chain nominators, and all the validators will inject a self vote.
+	/// This will use all on-chain validators, which inject a self vote, and all nominators in the
+	/// order given by [`Config::SortedListProvider`].
 	///
 	/// ### Slashing
 	///
 	/// All nominations that have been submitted before the last non-zero slash of the validator are
 	/// auto-chilled.
 	///
+	/// If `maybe_max_len` is `Some(max_len)`, at most `max_len` voters are returned. Validators
+	/// take precedence over nominators, which are taken in the order of the sorted list.
+	///
 	/// Note that this is VERY expensive. Use with care.
-	pub fn get_npos_voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+	pub fn get_npos_voters(
+		maybe_max_len: Option<usize>,
+	) -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		let max_len = maybe_max_len.unwrap_or_else(usize::max_value);
 		let weight_of = Self::slashable_balance_of_fn();
 		let mut all_voters = Vec::new();
 
-		for (validator, _) in <Validators<T>>::iter() {
+		for (validator, _) in <Validators<T>>::iter().take(max_len) {
 			// append self vote
 			let self_vote = (validator.clone(), weight_of(&validator), vec![validator.clone()]);
 			all_voters.push(self_vote);
 		}
 
-		for (nominator, nominations) in <Nominators<T>>::iter() {
+		let nominators = T::SortedListProvider::iter().filter_map(|nominator| {
+			match Nominators::<T>::get(&nominator) {
+				Some(nominations) => Some((nominator, nominations)),
+				None => {
+					log!(warn, "{:?} is in the sorted list but not a nominator", nominator);
+					None
+				},
+			}
+		});
+		let nominators_quota = max_len.saturating_sub(all_voters.len());
+		for (nominator, nominations) in nominators.take(nominators_quota) {
 			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
 
 			// Filter out nomination targets which were nominated before the most recent
@@ -3352,8 +3556,8 @@ impl<T: Config> sp_election_providers::ElectionDataProvider<T::AccountId, T::Blo
 		Self::validator_count()
 	}
 
-	fn voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
-		Self::get_npos_voters()
+	fn voters(maybe_max_len: Option<usize>) -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		Self::get_npos_voters(maybe_max_len)
 	}
 
 	fn targets() -> Vec<T::AccountId> {
@@ -3400,14 +3604,77 @@ impl<T: Config> sp_election_providers::ElectionDataProvider<T::AccountId, T::Blo
 		});
 
 		voters.into_iter().for_each(|(v, _s, t)| {
-			<Nominators<T>>::insert(
-				v,
+			Self::do_add_nominator(
+				&v,
 				Nominations { targets: t, submitted_in: 0, suppressed: false },
 			);
 		});
 	}
 }
 
+impl<T: Config> VoteWeightProvider<T::AccountId> for Module<T> {
+	fn vote_weight(who: &T::AccountId) -> VoteWeight {
+		Self::weight_of(who)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_vote_weight_of(who: &T::AccountId, weight: VoteWeight) {
+		// Only the ledger is written, the locked balance of `who` is not updated.
+		let active = T::CurrencyToVote::to_currency(weight as u128, T::Currency::total_issuance());
+		let controller = Self::bonded(who).unwrap_or_else(|| who.clone());
+		<Bonded<T>>::insert(who, &controller);
+		<Ledger<T>>::insert(&controller, StakingLedger {
+			stash: who.clone(),
+			total: active,
+			active,
+			unlocking: vec![],
+			claimed_rewards: vec![],
+		});
+	}
+}
+
+/// A simple voter list implementation that does not require any additional pallets. Note, this
+/// does not provide nominators in sorted order. If you desire nominators in a sorted order take
+/// a look at `pallet-bags-list`.
+pub struct UseNominatorsMap<T>(sp_std::marker::PhantomData<T>);
+impl<T: Config> SortedListProvider<T::AccountId> for UseNominatorsMap<T> {
+	type Error = ();
+
+	/// Returns iterator over voter list, which can have `take` called on it.
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(Nominators::<T>::iter().map(|(n, _)| n))
+	}
+	fn count() -> u32 {
+		Nominators::<T>::iter().count() as u32
+	}
+	fn contains(id: &T::AccountId) -> bool {
+		Nominators::<T>::contains_key(id)
+	}
+	fn on_insert(_: T::AccountId, _weight: VoteWeight) -> Result<(), Self::Error> {
+		// nothing to do on insert.
+		Ok(())
+	}
+	fn on_update(_: &T::AccountId, _weight: VoteWeight) {
+		// nothing to do on update.
+	}
+	fn on_remove(_: &T::AccountId) {
+		// nothing to do on remove.
+	}
+	fn regenerate(
+		_: impl IntoIterator<Item = T::AccountId>,
+		_: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		// nothing to do upon regenerate.
+		0
+	}
+	fn clear() {
+		// nothing to do upon clear; the nominators map is owned by staking.
+	}
+	fn sanity_check() -> Result<(), &'static str> {
+		Ok(())
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
@@ -3488,6 +3755,89 @@ where
 	}
 }
 
+impl<T: Config> StakingInterface for Module<T> {
+	type Balance = BalanceOf<T>;
+	type AccountId = T::AccountId;
+
+	fn minimum_bond() -> Self::Balance {
+		T::Currency::minimum_balance()
+	}
+
+	fn bonding_duration() -> EraIndex {
+		T::BondingDuration::get()
+	}
+
+	fn current_era() -> EraIndex {
+		Self::current_era().unwrap_or(0)
+	}
+
+	fn stash_by_ctrl(controller: &Self::AccountId) -> Result<Self::AccountId, DispatchError> {
+		Self::ledger(controller).map(|l| l.stash).ok_or(Error::<T>::NotController.into())
+	}
+
+	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool {
+		<ErasStakers<T>>::contains_key(era, who) || Self::eras_exposed_nominators(era, who)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_current_era(era: EraIndex) {
+		CurrentEra::put(era);
+	}
+
+	fn active_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.active)
+	}
+
+	fn total_stake(controller: &Self::AccountId) -> Option<Self::Balance> {
+		Self::ledger(controller).map(|l| l.total)
+	}
+
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+		payee: Self::AccountId,
+	) -> DispatchResult {
+		Self::bond(
+			system::RawOrigin::Signed(stash).into(),
+			T::Lookup::unlookup(controller),
+			value,
+			RewardDestination::Account(payee),
+		)
+	}
+
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		Self::bond_extra(system::RawOrigin::Signed(stash).into(), extra)
+	}
+
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		Self::unbond(system::RawOrigin::Signed(controller).into(), value)
+	}
+
+	fn withdraw_unbonded(controller: Self::AccountId) -> DispatchResult {
+		let stash = <Self as StakingInterface>::stash_by_ctrl(&controller)?;
+		let num_slashing_spans = Self::num_slashing_spans(&stash);
+		Self::withdraw_unbonded(system::RawOrigin::Signed(controller).into(), num_slashing_spans)
+			.map(|_| ())
+			.map_err(|e| e.error)
+	}
+
+	fn nominate(controller: Self::AccountId, targets: Vec<Self::AccountId>) -> DispatchResult {
+		let targets = targets.into_iter().map(T::Lookup::unlookup).collect::<Vec<_>>();
+		Self::nominate(system::RawOrigin::Signed(controller).into(), targets)
+	}
+
+	fn chill(controller: Self::AccountId) -> DispatchResult {
+		Self::chill(system::RawOrigin::Signed(controller).into())
+	}
+
+	fn force_unstake(stash: Self::AccountId) -> DispatchResult {
+		Self::kill_stash(&stash, Self::num_slashing_spans(&stash))?;
+		T::Currency::remove_lock(STAKING_ID, &stash);
+		Ok(())
+	}
+}
+
 /// A `Convert` implementation that finds the stash of the given controller account,
 /// if any.
 pub struct StashOf<T>(sp_std::marker::PhantomData<T>);
@@ -158,6 +158,8 @@ impl frame_system::Config for Test {
 }
 impl pallet_balances::Config for Test {
 	type MaxLocks = MaxLocks;
+	type HoldReason = ();
+	type MaxHolds = ();
 	type Balance = Balance;
 	type Event = Event;
 	type DustRemoval = ();
@@ -259,7 +261,7 @@ impl Config for Test {
 	type SlashCancelOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type BondingDuration = BondingDuration;
 	type SessionInterface = Self;
-	type RewardCurve = RewardCurve;
+	type EraPayout = ConvertCurve<RewardCurve>;
 	type NextNewSession = Session;
 	type ElectionLookahead = ElectionLookahead;
 	type Call = Call;
@@ -269,6 +271,7 @@ impl Config for Test {
 	type UnsignedPriority = UnsignedPriority;
 	type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
 	type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
+	type SortedListProvider = crate::UseNominatorsMap<Self>;
 	type WeightInfo = ();
 }
 
@@ -670,7 +673,7 @@ pub(crate) fn start_active_era(era_index: EraIndex) {
 
 pub(crate) fn current_total_payout_for_duration(duration: u64) -> Balance {
 	let reward = inflation::compute_total_payout(
-		<Test as Config>::RewardCurve::get(),
+		RewardCurve::get(),
 		Staking::eras_total_stake(active_era()),
 		Balances::total_issuance(),
 		duration,
@@ -682,7 +685,7 @@ pub(crate) fn current_total_payout_for_duration(duration: u64) -> Balance {
 
 pub(crate) fn maximum_payout_for_duration(duration: u64) -> Balance {
 	inflation::compute_total_payout(
-		<Test as Config>::RewardCurve::get(),
+		RewardCurve::get(),
 		0,
 		Balances::total_issuance(),
 		duration,
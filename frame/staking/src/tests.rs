@@ -5024,7 +5024,7 @@ mod election_data_provider {
 	#[test]
 	fn voters_include_self_vote() {
 		ExtBuilder::default().nominate(false).build().execute_with(|| {
-			assert!(<Validators<Test>>::iter().map(|(x, _)| x).all(|v| Staking::voters()
+			assert!(<Validators<Test>>::iter().map(|(x, _)| x).all(|v| Staking::voters(None)
 				.into_iter()
 				.find(|(w, _, t)| { v == *w && t[0] == *w })
 				.is_some()))
@@ -5036,7 +5036,7 @@ mod election_data_provider {
 		ExtBuilder::default().build().execute_with(|| {
 			assert_eq!(Staking::nominators(101).unwrap().targets, vec![11, 21]);
 			assert_eq!(
-				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters()
+				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters(None)
 					.iter()
 					.find(|x| x.0 == 101)
 					.unwrap()
@@ -5050,7 +5050,7 @@ mod election_data_provider {
 			// 11 is gone.
 			start_active_era(2);
 			assert_eq!(
-				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters()
+				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters(None)
 					.iter()
 					.find(|x| x.0 == 101)
 					.unwrap()
@@ -5061,7 +5061,7 @@ mod election_data_provider {
 			// resubmit and it is back
 			assert_ok!(Staking::nominate(Origin::signed(100), vec![11, 21]));
 			assert_eq!(
-				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters()
+				<Staking as ElectionDataProvider<AccountId, BlockNumber>>::voters(None)
 					.iter()
 					.find(|x| x.0 == 101)
 					.unwrap()
@@ -5071,6 +5071,23 @@ mod election_data_provider {
 		})
 	}
 
+	#[test]
+	fn voters_respect_max_len() {
+		ExtBuilder::default().build().execute_with(|| {
+			let all_voters = Staking::voters(None);
+			let validators = <Validators<Test>>::iter().count();
+			assert!(all_voters.len() > validators);
+
+			// nominators are cut off first.
+			let voters = Staking::voters(Some(validators));
+			assert_eq!(voters.len(), validators);
+			assert!(voters.iter().all(|(v, _, t)| t == &vec![*v]));
+
+			assert_eq!(Staking::voters(Some(1)).len(), 1);
+			assert_eq!(Staking::voters(Some(all_voters.len() + 1)), all_voters);
+		})
+	}
+
 	#[test]
 	fn estimate_next_election_works() {
 		ExtBuilder::default().session_per_era(5).period(5).build().execute_with(|| {
@@ -5105,3 +5122,83 @@ mod election_data_provider {
 		})
 	}
 }
+
+#[test]
+fn era_payout_implementations_work() {
+	const YEAR: u64 = 365 * 24 * 60 * 60 * 1000;
+	frame_support::parameter_types! {
+		pub const TenPercent: Perbill = Perbill::from_percent(10);
+	}
+
+	// The curve pays the stakers according to the staking rate, and the rest of the maximum
+	// inflation to the remainder.
+	let (staker_payout, rest) =
+		<ConvertCurve<RewardCurve> as EraPayout<Balance>>::era_payout(25_000, 100_000, YEAR);
+	let (expected_payout, maximum_payout) =
+		inflation::compute_total_payout(RewardCurve::get(), 25_000, 100_000u128, YEAR);
+	assert_eq!(staker_payout, expected_payout);
+	assert_eq!(staker_payout + rest, maximum_payout);
+
+	// A fixed inflation pays the same whatever the staking rate, and nothing to the remainder.
+	assert_eq!(
+		<FixedInflation<TenPercent> as EraPayout<Balance>>::era_payout(25_000, 100_000, YEAR),
+		(9_993, 0),
+	);
+	assert_eq!(
+		<FixedInflation<TenPercent> as EraPayout<Balance>>::era_payout(75_000, 100_000, YEAR),
+		(9_993, 0),
+	);
+
+	assert_eq!(<() as EraPayout<Balance>>::era_payout(25_000, 100_000, YEAR), (0, 0));
+}
+
+#[test]
+fn runtime_upgrade_populates_sorted_list() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(StorageVersion::get(), Releases::V7_0_0);
+
+		StorageVersion::put(Releases::V5_0_0);
+		<Staking as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Releases::V7_0_0);
+		assert_eq!(
+			<Test as Config>::SortedListProvider::iter().collect::<Vec<_>>(),
+			Nominators::<Test>::iter().map(|(n, _)| n).collect::<Vec<_>>(),
+		);
+	})
+}
+
+#[test]
+fn is_exposed_in_era_uses_the_exposure_index() {
+	ExtBuilder::default().build_and_execute(|| {
+		mock::start_active_era(1);
+		let exposed = |who, era| <Staking as sp_staking::StakingInterface>::is_exposed_in_era(
+			&who,
+			&era,
+		);
+
+		// Validators and their nominators are exposed, everyone else is not.
+		assert!(exposed(11, 1));
+		assert!(exposed(21, 1));
+		assert!(exposed(101, 1));
+		assert!(!exposed(31, 1));
+		assert!(!exposed(101, 2));
+		assert!(ErasExposedNominators::<Test>::get(1, 101));
+		assert!(!ErasExposedNominators::<Test>::get(1, 11));
+	})
+}
+
+#[test]
+fn runtime_upgrade_populates_exposed_nominators() {
+	ExtBuilder::default().build_and_execute(|| {
+		mock::start_active_era(1);
+		ErasExposedNominators::<Test>::remove_prefix(0);
+		ErasExposedNominators::<Test>::remove_prefix(1);
+		assert!(!<Staking as sp_staking::StakingInterface>::is_exposed_in_era(&101, &1));
+
+		StorageVersion::put(Releases::V6_0_0);
+		<Staking as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Releases::V7_0_0);
+		assert!(ErasExposedNominators::<Test>::get(0, 101));
+		assert!(<Staking as sp_staking::StakingInterface>::is_exposed_in_era(&101, &1));
+	})
+}
@@ -173,6 +173,7 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 		&scrate,
 	);
 	let validate_unsigned = decl_validate_unsigned(&name, modules.iter(), &scrate);
+	let hold_reason = decl_composite_enum("HoldReason", modules.iter(), &scrate)?;
 	let integrity_test = decl_integrity_test(&scrate);
 
 	let res = quote!(
@@ -211,6 +212,8 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 
 		#validate_unsigned
 
+		#hold_reason
+
 		#integrity_test
 	);
 
@@ -234,6 +237,72 @@ fn decl_validate_unsigned<'a>(
 	)
 }
 
+/// Aggregate the composite enum `name` of all modules declaring the part of the same name into
+/// a runtime level enum.
+///
+/// Each variant is encoded with the index of its module. Nothing is generated if no module
+/// declares the part.
+fn decl_composite_enum<'a>(
+	name: &str,
+	module_declarations: impl Iterator<Item = &'a Module>,
+	scrate: &'a TokenStream2,
+) -> Result<TokenStream2> {
+	let enum_name = Ident::new(name, proc_macro2::Span::call_site());
+	let mut variants = Vec::new();
+	let mut conversions = Vec::new();
+	for module_declaration in module_declarations {
+		if !module_declaration.exists_part(name) {
+			continue
+		}
+		if module_declaration.instance.is_some() {
+			let msg = format!(
+				"`{}` is not supported for instantiable modules because all instances would \
+				share the same type",
+				name,
+			);
+			return Err(syn::Error::new(module_declaration.name.span(), msg));
+		}
+
+		let variant_name = &module_declaration.name;
+		let module = &module_declaration.module;
+		let index = proc_macro2::Literal::u8_unsuffixed(module_declaration.index);
+		variants.push(quote!(
+			#[codec(index = #index)]
+			#variant_name(#module::#enum_name),
+		));
+		conversions.push(quote!(
+			impl From<#module::#enum_name> for #enum_name {
+				fn from(reason: #module::#enum_name) -> Self {
+					#enum_name::#variant_name(reason)
+				}
+			}
+		));
+	}
+
+	if variants.is_empty() {
+		return Ok(Default::default())
+	}
+
+	let doc = format!("The aggregated `{}` of all modules of the runtime.", name);
+	Ok(quote!(
+		#[doc = #doc]
+		#[derive(
+			Copy,
+			Clone,
+			Eq,
+			PartialEq,
+			#scrate::RuntimeDebug,
+			#scrate::codec::Encode,
+			#scrate::codec::Decode,
+		)]
+		pub enum #enum_name {
+			#( #variants )*
+		}
+
+		#( #conversions )*
+	))
+}
+
 fn decl_outer_inherent<'a>(
 	block: &'a syn::TypePath,
 	unchecked_extrinsic: &'a syn::TypePath,
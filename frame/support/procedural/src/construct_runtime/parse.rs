@@ -36,6 +36,7 @@ mod keyword {
 	syn::custom_keyword!(Origin);
 	syn::custom_keyword!(Inherent);
 	syn::custom_keyword!(ValidateUnsigned);
+	syn::custom_keyword!(HoldReason);
 }
 
 #[derive(Debug)]
@@ -228,6 +229,7 @@ pub enum ModulePartKeyword {
 	Origin(keyword::Origin),
 	Inherent(keyword::Inherent),
 	ValidateUnsigned(keyword::ValidateUnsigned),
+	HoldReason(keyword::HoldReason),
 }
 
 impl Parse for ModulePartKeyword {
@@ -250,6 +252,8 @@ impl Parse for ModulePartKeyword {
 			Ok(Self::Inherent(input.parse()?))
 		} else if lookahead.peek(keyword::ValidateUnsigned) {
 			Ok(Self::ValidateUnsigned(input.parse()?))
+		} else if lookahead.peek(keyword::HoldReason) {
+			Ok(Self::HoldReason(input.parse()?))
 		} else {
 			Err(lookahead.error())
 		}
@@ -268,6 +272,7 @@ impl ModulePartKeyword {
 			Self::Origin(_) => "Origin",
 			Self::Inherent(_) => "Inherent",
 			Self::ValidateUnsigned(_) => "ValidateUnsigned",
+			Self::HoldReason(_) => "HoldReason",
 		}
 	}
 
@@ -298,6 +303,7 @@ impl Spanned for ModulePartKeyword {
 			Self::Origin(inner) => inner.span(),
 			Self::Inherent(inner) => inner.span(),
 			Self::ValidateUnsigned(inner) => inner.span(),
+			Self::HoldReason(inner) => inner.span(),
 		}
 	}
 }
@@ -0,0 +1,55 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::{Def, parse::helper::get_doc_literals};
+
+/// * Derive the traits required by the aggregated runtime enum on each composite enum.
+pub fn expand_composites(def: &mut Def) -> proc_macro2::TokenStream {
+	let frame_support = &def.frame_support;
+
+	for composite in &def.composites {
+		let item = &mut def.item.content.as_mut().expect("Checked by def parser").1[composite.index];
+		let item = if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			unreachable!("Checked by composite parser")
+		};
+
+		if get_doc_literals(&item.attrs).is_empty() {
+			let doc = format!(
+				"The `{}` of this pallet. It is aggregated into the runtime level enum of the \
+				same name by `construct_runtime`.",
+				composite.ident,
+			);
+			item.attrs.push(syn::parse_quote!(#[doc = #doc]));
+		}
+
+		item.attrs.push(syn::parse_quote!(
+			#[derive(
+				Copy,
+				Clone,
+				Eq,
+				PartialEq,
+				#frame_support::RuntimeDebug,
+				#frame_support::codec::Encode,
+				#frame_support::codec::Decode,
+			)]
+		));
+	}
+
+	Default::default()
+}
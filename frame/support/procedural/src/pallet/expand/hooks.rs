@@ -79,6 +79,22 @@ pub fn expand_hooks(def: &mut Def) -> proc_macro2::TokenStream {
 			}
 		}
 
+		impl<#type_impl_gen>
+			#frame_support::traits::OnPoll<<T as #frame_system::Config>::BlockNumber>
+			for #pallet_ident<#type_use_gen> #where_clause
+		{
+			fn on_poll(
+				n: <T as #frame_system::Config>::BlockNumber,
+				remaining_weight: #frame_support::weights::Weight
+			) -> #frame_support::weights::Weight {
+				<
+					Self as #frame_support::traits::Hooks<
+						<T as #frame_system::Config>::BlockNumber
+					>
+				>::on_poll(n, remaining_weight)
+			}
+		}
+
 		impl<#type_impl_gen>
 			#frame_support::traits::OnInitialize<<T as #frame_system::Config>::BlockNumber>
 			for #pallet_ident<#type_use_gen> #where_clause
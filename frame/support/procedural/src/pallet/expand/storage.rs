@@ -25,8 +25,24 @@ fn prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
 	syn::Ident::new(&format!("_GeneratedPrefixForStorage{}", storage_ident), storage_ident.span())
 }
 
+/// Generate the counter_prefix_ident related to the counted storage map.
+/// counter_prefix_ident is used for the prefix struct of the storage value holding the counter.
+fn counter_prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
+	syn::Ident::new(
+		&format!("_GeneratedCounterPrefixForStorage{}", storage_ident),
+		storage_ident.span(),
+	)
+}
+
+/// Generate the name of the storage value holding the counter of a counted storage map.
+fn counter_prefix(storage_ident: &syn::Ident) -> String {
+	format!("CounterFor{}", storage_ident)
+}
+
 /// * generate StoragePrefix structs (e.g. for a storage `MyStorage` a struct with the name
 ///   `_GeneratedPrefixForStorage$NameOfStorage` is generated) and implements StorageInstance trait.
+/// * for counted storage maps, also generate the counter StoragePrefix struct (named
+///   `_GeneratedCounterPrefixForStorage$NameOfStorage`) and implements CountedStorageMapInstance.
 /// * replace the first generic `_` by the generated prefix structure
 /// * generate metadatas
 pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
@@ -71,7 +87,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 	}
 
 	let entries = def.storages.iter()
-		.map(|storage| {
+		.flat_map(|storage| {
 			let docs = &storage.docs;
 
 			let ident = &storage.ident;
@@ -85,9 +101,15 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 				Metadata::Map { .. } => quote::quote_spanned!(storage.attr_span =>
 					#frame_support::storage::types::StorageMapMetadata
 				),
+				Metadata::CountedMap { .. } => quote::quote_spanned!(storage.attr_span =>
+					#frame_support::storage::types::CountedStorageMapMetadata
+				),
 				Metadata::DoubleMap { .. } => quote::quote_spanned!(storage.attr_span =>
 					#frame_support::storage::types::StorageDoubleMapMetadata
 				),
+				Metadata::NMap { .. } => quote::quote_spanned!(storage.attr_span =>
+					#frame_support::storage::types::StorageNMapMetadata
+				),
 			};
 
 			let ty = match &storage.metadata {
@@ -99,7 +121,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						)
 					)
 				},
-				Metadata::Map { key, value } => {
+				Metadata::Map { key, value } | Metadata::CountedMap { key, value } => {
 					let value = clean_type_string(&quote::quote!(#value).to_string());
 					let key = clean_type_string(&quote::quote!(#key).to_string());
 					quote::quote_spanned!(storage.attr_span =>
@@ -124,10 +146,27 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							value: #frame_support::metadata::DecodeDifferent::Encode(#value),
 						}
 					)
+				},
+				Metadata::NMap { keys, value, .. } => {
+					let keys = keys
+						.iter()
+						.map(|key| clean_type_string(&quote::quote!(#key).to_string()));
+					let value = clean_type_string(&quote::quote!(#value).to_string());
+					quote::quote_spanned!(storage.attr_span =>
+						#frame_support::metadata::StorageEntryType::NMap {
+							keys: #frame_support::metadata::DecodeDifferent::Encode(&[
+								#( #keys, )*
+							]),
+							hashers: #frame_support::metadata::DecodeDifferent::Encode(
+								<#full_ident as #metadata_trait>::HASHERS,
+							),
+							value: #frame_support::metadata::DecodeDifferent::Encode(#value),
+						}
+					)
 				}
 			};
 
-			quote::quote_spanned!(storage.attr_span =>
+			let entry = quote::quote_spanned!(storage.attr_span =>
 				#frame_support::metadata::StorageEntryMetadata {
 					name: #frame_support::metadata::DecodeDifferent::Encode(
 						<#full_ident as #metadata_trait>::NAME
@@ -141,7 +180,32 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						#( #docs, )*
 					]),
 				}
-			)
+			);
+
+			if let Metadata::CountedMap { .. } = &storage.metadata {
+				let counter_doc =
+					format!("Counter for the related counted storage map `{}`.", ident);
+				let counter_entry = quote::quote_spanned!(storage.attr_span =>
+					#frame_support::metadata::StorageEntryMetadata {
+						name: #frame_support::metadata::DecodeDifferent::Encode(
+							<#full_ident as #metadata_trait>::COUNTER_NAME
+						),
+						modifier: #frame_support::metadata::StorageEntryModifier::Default,
+						ty: #frame_support::metadata::StorageEntryType::Plain(
+							#frame_support::metadata::DecodeDifferent::Encode("u32")
+						),
+						default: #frame_support::metadata::DecodeDifferent::Encode(
+							<#full_ident as #metadata_trait>::COUNTER_DEFAULT
+						),
+						documentation: #frame_support::metadata::DecodeDifferent::Encode(&[
+							#counter_doc,
+						]),
+					}
+				);
+				vec![entry, counter_entry]
+			} else {
+				vec![entry]
+			}
 		});
 
 	let getters = def.storages.iter()
@@ -198,6 +262,24 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					)
 				},
+				Metadata::CountedMap { key, value } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
+							Option<#value>
+						),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote_spanned!(storage.attr_span =>
+						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
+							#( #docs )*
+							pub fn #getter<KArg>(k: KArg) -> #query where
+								KArg: #frame_support::codec::EncodeLike<#key>,
+							{
+								<#full_ident>::get(k)
+							}
+						}
+					)
+				},
 				Metadata::DoubleMap { key1, key2, value } => {
 					let query = match storage.query_kind.as_ref().expect("Checked by def") {
 						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
@@ -220,6 +302,31 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					)
 				},
+				Metadata::NMap { keygen, value, .. } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
+							Option<#value>
+						),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote_spanned!(storage.attr_span =>
+						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
+							#( #docs )*
+							pub fn #getter<KArg>(key: KArg) -> #query
+							where
+								KArg: #frame_support::storage::types::EncodeLikeTuple<
+									<#keygen as #frame_support::storage::types::KeyGenerator>::KArg
+								>
+									+ #frame_support::storage::types::TupleToEncodedIter,
+							{
+								<
+									#full_ident as
+									#frame_support::storage::StorageNMap<#keygen, #value>
+								>::get(key)
+							}
+						}
+					)
+				},
 			}
 		} else {
 			Default::default()
@@ -233,6 +340,38 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 		let prefix_struct_const = storage_def.ident.to_string();
 		let config_where_clause = &def.config.where_clause;
 
+		let counter_prefix_struct = if let Metadata::CountedMap { .. } = &storage_def.metadata {
+			let counter_prefix_struct_ident = counter_prefix_ident(&storage_def.ident);
+			let counter_prefix_struct_const = counter_prefix(&storage_def.ident);
+
+			quote::quote_spanned!(storage_def.attr_span =>
+				#prefix_struct_vis struct #counter_prefix_struct_ident<#type_use_gen>(
+					core::marker::PhantomData<(#type_use_gen,)>
+				);
+				impl<#type_impl_gen> #frame_support::traits::StorageInstance
+					for #counter_prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					fn pallet_prefix() -> &'static str {
+						<
+							<T as #frame_system::Config>::PalletInfo
+							as #frame_support::traits::PalletInfo
+						>::name::<Pallet<#type_use_gen>>()
+							.expect("Every active pallet has a name in the runtime; qed")
+					}
+					const STORAGE_PREFIX: &'static str = #counter_prefix_struct_const;
+				}
+				impl<#type_impl_gen> #frame_support::storage::types::CountedStorageMapInstance
+					for #prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					type CounterPrefix = #counter_prefix_struct_ident<#type_use_gen>;
+				}
+			)
+		} else {
+			Default::default()
+		};
+
 		quote::quote_spanned!(storage_def.attr_span =>
 			#prefix_struct_vis struct #prefix_struct_ident<#type_use_gen>(
 				core::marker::PhantomData<(#type_use_gen,)>
@@ -250,6 +389,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 				}
 				const STORAGE_PREFIX: &'static str = #prefix_struct_const;
 			}
+			#counter_prefix_struct
 		)
 	});
 
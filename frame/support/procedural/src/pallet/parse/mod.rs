@@ -34,6 +34,7 @@ pub mod genesis_build;
 pub mod validate_unsigned;
 pub mod type_value;
 pub mod extra_constants;
+pub mod composite;
 
 use syn::spanned::Spanned;
 use frame_support_procedural_tools::generate_crate_access_2018;
@@ -57,6 +58,7 @@ pub struct Def {
 	pub validate_unsigned: Option<validate_unsigned::ValidateUnsignedDef>,
 	pub extra_constants: Option<extra_constants::ExtraConstantsDef>,
 	pub type_values: Vec<type_value::TypeValueDef>,
+	pub composites: Vec<composite::CompositeDef>,
 	pub frame_system: syn::Ident,
 	pub frame_support: syn::Ident,
 }
@@ -87,6 +89,7 @@ impl Def {
 		let mut extra_constants = None;
 		let mut storages = vec![];
 		let mut type_values = vec![];
+		let mut composites: Vec<composite::CompositeDef> = vec![];
 
 		for (index, item) in items.iter_mut().enumerate() {
 			let pallet_attr: Option<PalletAttr> = helper::take_first_item_attr(item)?;
@@ -132,6 +135,14 @@ impl Def {
 					extra_constants =
 						Some(extra_constants::ExtraConstantsDef::try_from(index, item)?)
 				},
+				Some(PalletAttr::CompositeEnum(span)) => {
+					let c = composite::CompositeDef::try_from(span, index, item)?;
+					if composites.iter().any(|other| other.ident == c.ident) {
+						let msg = format!("Invalid duplicated composite enum `{}`", c.ident);
+						return Err(syn::Error::new(c.attr_span, msg));
+					}
+					composites.push(c);
+				},
 				Some(attr) => {
 					let msg = "Invalid duplicated attribute";
 					return Err(syn::Error::new(attr.span(), msg));
@@ -169,6 +180,7 @@ impl Def {
 			inherent,
 			storages,
 			type_values,
+			composites,
 			frame_system,
 			frame_support,
 		};
@@ -379,6 +391,7 @@ mod keyword {
 	syn::custom_keyword!(generate_store);
 	syn::custom_keyword!(Store);
 	syn::custom_keyword!(extra_constants);
+	syn::custom_keyword!(composite_enum);
 }
 
 /// Parse attributes for item in pallet module
@@ -398,6 +411,7 @@ enum PalletAttr {
 	ValidateUnsigned(proc_macro2::Span),
 	TypeValue(proc_macro2::Span),
 	ExtraConstants(proc_macro2::Span),
+	CompositeEnum(proc_macro2::Span),
 }
 
 impl PalletAttr {
@@ -417,6 +431,7 @@ impl PalletAttr {
 			Self::ValidateUnsigned(span) => *span,
 			Self::TypeValue(span) => *span,
 			Self::ExtraConstants(span) => *span,
+			Self::CompositeEnum(span) => *span,
 		}
 	}
 }
@@ -458,6 +473,8 @@ impl syn::parse::Parse for PalletAttr {
 			Ok(PalletAttr::TypeValue(content.parse::<keyword::type_value>()?.span()))
 		} else if lookahead.peek(keyword::extra_constants) {
 			Ok(PalletAttr::ExtraConstants(content.parse::<keyword::extra_constants>()?.span()))
+		} else if lookahead.peek(keyword::composite_enum) {
+			Ok(PalletAttr::CompositeEnum(content.parse::<keyword::composite_enum>()?.span()))
 		} else {
 			Err(lookahead.error())
 		}
@@ -1626,6 +1626,18 @@ macro_rules! decl_module {
 		}
 	};
 
+	(@impl_on_poll
+		{ $system:ident }
+		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
+		{ $( $other_where_bounds:tt )* }
+	) => {
+		impl<$trait_instance: $system::Config + $trait_name$(<I>, $instance: $instantiable)?>
+			$crate::traits::OnPoll<<$trait_instance as $system::Config>::BlockNumber>
+			for $module<$trait_instance$(, $instance)?> where $( $other_where_bounds )*
+		{
+		}
+	};
+
 	(@impl_offchain
 		{ $system:ident }
 		$module:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>;
@@ -1891,6 +1903,13 @@ macro_rules! decl_module {
 			$( $on_idle )*
 		}
 
+		$crate::decl_module! {
+			@impl_on_poll
+			{ $system }
+			$mod_type<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?>;
+			{ $( $other_where_bounds )* }
+		}
+
 		$crate::decl_module! {
 			@impl_offchain
 			{ $system }
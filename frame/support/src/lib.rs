@@ -74,8 +74,9 @@ pub use self::hash::{
 	StorageHasher, ReversibleStorageHasher
 };
 pub use self::storage::{
-	StorageValue, StorageMap, StorageDoubleMap, StoragePrefixedMap, IterableStorageMap,
-	IterableStorageDoubleMap, migration
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, StoragePrefixedMap,
+	IterableStorageMap, IterableStorageDoubleMap, IterableStorageNMap, migration,
+	bounded_vec::BoundedVec,
 };
 pub use self::dispatch::{Parameter, Callable};
 pub use sp_runtime::{self, ConsensusEngineId, print, traits::Printable};
@@ -1076,11 +1077,14 @@ pub mod pallet_prelude {
 	pub use frame_support::{
 		EqNoBound, PartialEqNoBound, RuntimeDebugNoBound, DebugNoBound, CloneNoBound, Twox256,
 		Twox128, Blake2_256, Blake2_128, Identity, Twox64Concat, Blake2_128Concat, ensure,
-		RuntimeDebug, storage,
+		RuntimeDebug, storage, BoundedVec,
 		traits::{Get, Hooks, IsType, GetPalletVersion, EnsureOrigin},
 		dispatch::{DispatchResultWithPostInfo, Parameter, DispatchError, DispatchResult},
 		weights::{DispatchClass, Pays, Weight},
-		storage::types::{StorageValue, StorageMap, StorageDoubleMap, ValueQuery, OptionQuery},
+		storage::types::{
+			StorageValue, StorageMap, StorageDoubleMap, StorageNMap, Key as NMapKey, ValueQuery,
+			OptionQuery, CountedStorageMap,
+		},
 	};
 	pub use codec::{Encode, Decode};
 	pub use sp_inherents::{InherentData, InherentIdentifier, ProvideInherent};
@@ -1223,8 +1227,8 @@ pub mod pallet_prelude {
 ///
 /// ### Macro expansion:
 ///
-/// The macro implements the traits `OnInitialize`, `OnIdle`, `OnFinalize`, `OnRuntimeUpgrade`,
-/// `OffchainWorker`, `IntegrityTest` using `Hooks` implementation.
+/// The macro implements the traits `OnInitialize`, `OnPoll`, `OnIdle`, `OnFinalize`,
+/// `OnRuntimeUpgrade`, `OffchainWorker`, `IntegrityTest` using `Hooks` implementation.
 ///
 /// NOTE: OnRuntimeUpgrade is implemented with `Hooks::on_runtime_upgrade` and some additional
 /// logic. E.g. logic to write pallet version into storage.
@@ -1400,8 +1404,9 @@ pub mod pallet_prelude {
 /// 	= $StorageType<_, $some_generics, ...>;
 /// ```
 /// I.e. it must be a type alias, with generics: `T` or `T: Config`, aliased type must be one
-/// of `StorageValue`, `StorageMap` or `StorageDoubleMap` (defined in frame_support).
-/// Their first generic must be `_` as it is written by the macro itself.
+/// of `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap` or `StorageNMap`
+/// (defined in frame_support). Their first generic must be `_` as it is written by the macro
+/// itself.
 ///
 /// The Prefix generic written by the macro is generated using `PalletInfo::name::<Pallet<..>>()`
 /// and the name of the storage type.
@@ -1418,6 +1423,25 @@ pub mod pallet_prelude {
 /// pub(super) type MyStorage<T> = StorageMap<_, Blake2_128Concat, u32, u32>;
 /// ```
 ///
+/// The keys of a `StorageNMap` are given as a tuple of `NMapKey<Hasher, Key>`, or a single one
+/// for a map with one key, and the getter takes them as a tuple too:
+/// ```ignore
+/// #[pallet::storage]
+/// #[pallet::getter(fn my_n_map)]
+/// pub(super) type MyNMap<T> = StorageNMap<
+/// 	_,
+/// 	(NMapKey<Blake2_128Concat, u32>, NMapKey<Twox64Concat, u8>, NMapKey<Identity, u16>),
+/// 	u64,
+/// >;
+/// ```
+///
+/// A `CountedStorageMap` takes the same generics as a `StorageMap`, and additionally stores the
+/// number of items of the map in a storage value named `CounterFor$NameOfStorage`:
+/// ```ignore
+/// #[pallet::storage]
+/// pub(super) type MyCountedMap<T> = CountedStorageMap<_, Blake2_128Concat, u32, u32>;
+/// ```
+///
 /// NOTE: If the `QueryKind` generic parameter is still generic at this stage or is using some type
 /// alias then the generation of the getter might fail. In this case the getter can be implemented
 /// manually.
@@ -1433,6 +1457,10 @@ pub mod pallet_prelude {
 /// on it using the pallet and storage name. It then uses it as the first generic of the aliased
 /// type.
 ///
+/// For a `CountedStorageMap` it also generates a struct named
+/// `_GeneratedCounterPrefixForStorage$NameOfStorage` for the counter, and implements
+/// [`CountedStorageMapInstance`](storage::types::CountedStorageMapInstance) on the prefix struct
+/// of the map.
 ///
 /// The macro implements the function `storage_metadata` on `Pallet` implementing the metadata for
 /// all storage items based on their kind:
@@ -1440,6 +1468,8 @@ pub mod pallet_prelude {
 /// * for a storage map, the type of the values and the key's type is copied into the metadata
 /// * for a storage double map, the type of the values, and the types of key1 and key2 are copied into
 ///   the metadata.
+/// * for a counted storage map, the map is described as a storage map, and the counter as a storage
+///   value of type `u32`.
 ///
 /// # Type value: `#[pallet::type_value]` optional
 ///
@@ -1583,6 +1613,34 @@ pub mod pallet_prelude {
 ///
 /// NOTE: for instantiable pallet, origin must be generic over T and I.
 ///
+/// # Composite enum: `#[pallet::composite_enum]` optional
+///
+/// Allow to define a reason enum which is aggregated by `construct_runtime` into an outer runtime
+/// enum of the same name. The only composite enum is `HoldReason`: The reasons for which the
+/// pallet places funds on hold through `fungible::MutateHold`, e.g. `pallet_balances` with its
+/// `HoldReason` set to the runtime enum.
+///
+/// Item must be a public non-generic enum named `HoldReason`.
+///
+/// E.g.:
+/// ```ignore
+/// #[pallet::composite_enum]
+/// pub enum HoldReason {
+/// 	/// Funds are held as a deposit for some storage item.
+/// 	StorageDeposit,
+/// }
+/// ```
+///
+/// ### Macro expansion
+///
+/// The macro derives `Copy, Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode` on the enum.
+///
+/// In `construct_runtime` the pallet must declare the `HoldReason` part, the runtime then gets a
+/// `HoldReason` enum with one variant per pallet wrapping the pallet enum, encoded with the pallet
+/// index, and an implementation of `From` for each pallet enum.
+///
+/// NOTE: composite enums are not supported for instantiable pallet.
+///
 /// # General notes on instantiable pallet
 ///
 /// An instantiable pallet is one where Config is generic, i.e. `Config<I>`. This allow runtime to
@@ -1700,7 +1758,8 @@ pub mod pallet_prelude {
 ///
 /// 	// Declare a storage item. Any amount of storage items can be declared (optional).
 /// 	//
-/// 	// Is expected either `StorageValue`, `StorageMap` or `StorageDoubleMap`.
+/// 	// Is expected either `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap`
+/// 	// or `StorageNMap`.
 /// 	// The macro generates the prefix type and replaces the first generic `_`.
 /// 	//
 /// 	// The macro expands the metadata for the storage item with the type used:
@@ -1708,6 +1767,7 @@ pub mod pallet_prelude {
 /// 	// * for a storage map the type of the values and the type of the key is copied into the metadata
 /// 	// * for a storage double map the types of the values and keys are copied into the
 /// 	//   metadata.
+/// 	// * for a storage n map the types of the values and keys are copied into the metadata.
 /// 	//
 /// 	// NOTE: The generic `Hasher` must implement the `StorageHasher` trait (or the type is not
 /// 	// usable at all). We use [`StorageHasher::METADATA`] for the metadata of the hasher of the
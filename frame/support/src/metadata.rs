@@ -19,7 +19,7 @@ pub use frame_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ModuleErrorMetadata,
-	ExtrinsicMetadata,
+	ExtrinsicMetadata, LATEST_METADATA_VERSION,
 };
 
 /// Implements the metadata support for the given runtime and all its modules.
@@ -89,6 +89,23 @@ macro_rules! impl_runtime_metadata {
 						},
 				}.into()
 			}
+
+			/// Returns the metadata in the format of the given `version`.
+			///
+			/// Returns `None` if the runtime can't provide metadata in this format.
+			pub fn metadata_at_version(version: u32)
+				-> Option<$crate::metadata::RuntimeMetadataPrefixed>
+			{
+				match version {
+					$crate::metadata::LATEST_METADATA_VERSION => Some(Self::metadata()),
+					_ => None,
+				}
+			}
+
+			/// Returns all metadata versions supported by [`Self::metadata_at_version`].
+			pub fn metadata_versions() -> $crate::sp_std::vec::Vec<u32> {
+				$crate::sp_std::vec![$crate::metadata::LATEST_METADATA_VERSION]
+			}
 		}
 	}
 }
@@ -657,4 +674,15 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	#[test]
+	fn metadata_at_version_works() {
+		assert_eq!(TestRuntime::metadata_versions(), vec![LATEST_METADATA_VERSION]);
+		assert_eq!(
+			TestRuntime::metadata_at_version(LATEST_METADATA_VERSION).map(|m| m.encode()),
+			Some(TestRuntime::metadata().encode()),
+		);
+		assert!(TestRuntime::metadata_at_version(LATEST_METADATA_VERSION + 1).is_none());
+		assert!(TestRuntime::metadata_at_version(11).is_none());
+	}
 }
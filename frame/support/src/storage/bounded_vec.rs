@@ -0,0 +1,302 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits, types and structs to support putting a bounded vector into storage, as a raw value, map
+//! or a double map.
+
+use sp_std::prelude::*;
+use sp_std::{convert::TryFrom, marker::PhantomData, slice::SliceIndex};
+use codec::{Encode, Decode, EncodeLike};
+use crate::{
+	traits::Get,
+	storage::{StorageDecodeLength, private::Sealed},
+};
+
+/// A bounded vector.
+///
+/// It has implementations for efficient append and length decoding, as with a normal `Vec<_>`,
+/// once put into storage as a raw value, map or double-map.
+///
+/// As the name suggests, the length of the vector is always bounded. All internal operations ensure
+/// this bound is respected.
+pub struct BoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S: Get<u32>> BoundedVec<T, S> {
+	/// Get the bound of the type in `usize`.
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+
+	/// Consume self, and return the inner `Vec`. Henceforth, the `Vec<_>` can be altered in an
+	/// arbitrary way. At some point, if the reverse conversion is required, `TryFrom<Vec<_>>` can
+	/// be used.
+	pub fn into_inner(self) -> Vec<T> {
+		self.0
+	}
+
+	/// Exactly the same semantics as [`Vec::push`], but returns an error and does nothing if the
+	/// bound is reached.
+	pub fn try_push(&mut self, element: T) -> Result<(), ()> {
+		if self.len() < Self::bound() {
+			self.0.push(element);
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Exactly the same semantics as [`Vec::insert`], but returns an error and does nothing if the
+	/// bound is reached.
+	///
+	/// # Panics
+	///
+	/// Panics if `index > len`.
+	pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), ()> {
+		if self.len() < Self::bound() {
+			self.0.insert(index, element);
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+
+	/// Exactly the same semantics as [`Vec::remove`].
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds.
+	pub fn remove(&mut self, index: usize) -> T {
+		self.0.remove(index)
+	}
+
+	/// Exactly the same semantics as [`Vec::swap_remove`].
+	///
+	/// # Panics
+	///
+	/// Panics if `index` is out of bounds.
+	pub fn swap_remove(&mut self, index: usize) -> T {
+		self.0.swap_remove(index)
+	}
+
+	/// Exactly the same semantics as [`Vec::retain`].
+	pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+		self.0.retain(f)
+	}
+}
+
+impl<T, S> Default for BoundedVec<T, S> {
+	fn default() -> Self {
+		// the bound cannot be below 0, which is satisfied by an empty vector
+		Self(Vec::default(), PhantomData)
+	}
+}
+
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone(), PhantomData)
+	}
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: PartialEq, S> PartialEq<Vec<T>> for BoundedVec<T, S> {
+	fn eq(&self, other: &Vec<T>) -> bool {
+		&self.0 == other
+	}
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+#[cfg(feature = "std")]
+impl<T: std::fmt::Debug, S: Get<u32>> std::fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_tuple("BoundedVec").field(&self.0).field(&Self::bound()).finish()
+	}
+}
+
+#[cfg(not(feature = "std"))]
+impl<T, S> sp_std::fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter<'_>) -> sp_std::fmt::Result {
+		f.write_str("<wasm:stripped>")
+	}
+}
+
+impl<T: Encode, S> Encode for BoundedVec<T, S> {
+	fn size_hint(&self) -> usize {
+		self.0.size_hint()
+	}
+
+	fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+		self.0.using_encoded(f)
+	}
+}
+
+impl<T: Encode, S> EncodeLike for BoundedVec<T, S> {}
+
+// `BoundedVec`s encode to something which will always decode into a `Vec`.
+impl<T: Encode, S> EncodeLike<Vec<T>> for BoundedVec<T, S> {}
+
+impl<T: Decode, S: Get<u32>> Decode for BoundedVec<T, S> {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let inner = Vec::<T>::decode(input)?;
+		if inner.len() > S::get() as usize {
+			return Err("BoundedVec exceeds its limit".into());
+		}
+		Ok(Self(inner, PhantomData))
+	}
+}
+
+impl<T, S: Get<u32>> TryFrom<Vec<T>> for BoundedVec<T, S> {
+	type Error = ();
+
+	fn try_from(t: Vec<T>) -> Result<Self, Self::Error> {
+		if t.len() <= Self::bound() {
+			Ok(Self(t, PhantomData))
+		} else {
+			Err(())
+		}
+	}
+}
+
+impl<T, S> sp_std::ops::Deref for BoundedVec<T, S> {
+	type Target = Vec<T>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl<T, S> AsRef<Vec<T>> for BoundedVec<T, S> {
+	fn as_ref(&self) -> &Vec<T> {
+		&self.0
+	}
+}
+
+impl<T, S> From<BoundedVec<T, S>> for Vec<T> {
+	fn from(x: BoundedVec<T, S>) -> Vec<T> {
+		x.0
+	}
+}
+
+// Allows for indexing similar to a normal `Vec`. Can panic if out of bound. Mutable indexing
+// can't change the length and thus can't violate the bound.
+impl<T, S, I: SliceIndex<[T]>> sp_std::ops::Index<I> for BoundedVec<T, S> {
+	type Output = I::Output;
+
+	#[inline]
+	fn index(&self, index: I) -> &Self::Output {
+		self.0.index(index)
+	}
+}
+
+impl<T, S, I: SliceIndex<[T]>> sp_std::ops::IndexMut<I> for BoundedVec<T, S> {
+	#[inline]
+	fn index_mut(&mut self, index: I) -> &mut Self::Output {
+		self.0.index_mut(index)
+	}
+}
+
+impl<T, S> codec::DecodeLength for BoundedVec<T, S> {
+	fn len(self_encoded: &[u8]) -> Result<usize, codec::Error> {
+		// `BoundedVec<T, _>` stored just a `Vec<T>`, thus the length is at the beginning in
+		// `Compact` form, and same implementation as `Vec<T>` can be used.
+		<Vec<T> as codec::DecodeLength>::len(self_encoded)
+	}
+}
+
+impl<T, S> Sealed for BoundedVec<T, S> {}
+
+impl<T, S> StorageDecodeLength for BoundedVec<T, S> {}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+	use sp_io::TestExternalities;
+	use sp_std::convert::TryInto;
+	use crate::{assert_ok, storage::types::{StorageValue, ValueQuery}, traits::StorageInstance};
+
+	crate::parameter_types! {
+		pub const Seven: u32 = 7;
+	}
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str { "test" }
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	type Foo = StorageValue<Prefix, BoundedVec<u32, Seven>, ValueQuery>;
+
+	#[test]
+	fn decode_len_works() {
+		TestExternalities::default().execute_with(|| {
+			let bounded: BoundedVec<u32, Seven> = vec![1, 2, 3].try_into().unwrap();
+			Foo::put(bounded);
+			assert_eq!(Foo::decode_len().unwrap(), 3);
+		});
+	}
+
+	#[test]
+	fn try_append_works() {
+		TestExternalities::default().execute_with(|| {
+			let bounded: BoundedVec<u32, Seven> = vec![1, 2, 3, 4, 5, 6].try_into().unwrap();
+			Foo::put(bounded);
+			assert_ok!(Foo::try_append(7u32));
+			assert!(Foo::try_append(8u32).is_err());
+			assert_eq!(Foo::get(), vec![1, 2, 3, 4, 5, 6, 7]);
+		});
+	}
+
+	#[test]
+	fn try_push_works() {
+		let mut bounded: BoundedVec<u32, Seven> = vec![1, 2, 3].try_into().unwrap();
+		assert_ok!(bounded.try_push(0));
+		assert_eq!(*bounded, vec![1, 2, 3, 0]);
+
+		bounded.try_insert(0, 9).unwrap();
+		bounded.try_push(4).unwrap();
+		bounded.try_push(5).unwrap();
+		assert_eq!(bounded.len(), 7);
+		assert!(bounded.try_push(6).is_err());
+		assert!(bounded.try_insert(0, 6).is_err());
+		assert_eq!(*bounded, vec![9, 1, 2, 3, 0, 4, 5]);
+	}
+
+	#[test]
+	fn too_big_vec_fails() {
+		let v: Result<BoundedVec<u32, Seven>, _> = vec![1, 2, 3, 4, 5, 6, 7, 8].try_into();
+		assert!(v.is_err());
+	}
+
+	#[test]
+	fn too_big_fail_to_decode() {
+		let v: Vec<u32> = vec![1, 2, 3, 4, 5, 6, 7, 8];
+		assert!(BoundedVec::<u32, Seven>::decode(&mut &v.encode()[..]).is_err());
+	}
+
+	#[test]
+	fn indexing_works() {
+		let mut bounded: BoundedVec<u32, Seven> = vec![1, 2, 3].try_into().unwrap();
+		bounded[1] = 5;
+		assert_eq!(bounded[1], 5);
+		assert_eq!(&bounded[1..], &[5, 3]);
+	}
+}
@@ -355,6 +355,15 @@ impl<
 		}
 	}
 
+	fn iter_prefix_from(
+		k1: impl EncodeLike<K1>,
+		starting_raw_key: Vec<u8>,
+	) -> Self::PrefixIterator {
+		let mut iter = Self::iter_prefix(k1);
+		iter.set_last_raw_key(starting_raw_key);
+		iter
+	}
+
 	fn drain_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator {
 		let mut iterator = Self::iter_prefix(k1);
 		iterator.drain = true;
@@ -377,6 +386,12 @@ impl<
 		}
 	}
 
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
+		let mut iter = Self::iter();
+		iter.set_last_raw_key(starting_raw_key);
+		iter
+	}
+
 	fn drain() -> Self::Iterator {
 		let mut iterator = Self::iter();
 		iterator.drain = true;
@@ -519,6 +534,20 @@ mod test_iterators {
 				vec![1, 2, 0, 3],
 			);
 
+			// Resume the iterations after the last iterated key
+			let mut iter = DoubleMap::iter_prefix(k1);
+			assert_eq!(iter.next(), Some((1, 1)));
+			let last_raw_key = iter.last_raw_key().to_vec();
+			assert_eq!(last_raw_key, DoubleMap::storage_double_map_final_key(k1, 1u32));
+			assert_eq!(
+				DoubleMap::iter_prefix_from(k1, last_raw_key.clone()).collect::<Vec<_>>(),
+				vec![(2, 2), (0, 0), (3, 3)],
+			);
+			assert_eq!(
+				DoubleMap::iter_from(last_raw_key).collect::<Vec<_>>(),
+				vec![(k1, 2, 2), (k1, 0, 0), (k1, 3, 3)],
+			);
+
 			assert_eq!(
 				DoubleMap::drain_prefix(k1).collect::<Vec<_>>(),
 				vec![(1, 1), (2, 2), (0, 0), (3, 3)],
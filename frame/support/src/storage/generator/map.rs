@@ -155,6 +155,13 @@ impl<
 		}
 	}
 
+	/// Enumerate all elements in the map after a given key.
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
+		let mut iter = Self::iter();
+		iter.set_last_raw_key(starting_raw_key);
+		iter
+	}
+
 	/// Enumerate all elements in the map.
 	fn drain() -> Self::Iterator {
 		let mut iterator = Self::iter();
@@ -380,6 +387,14 @@ mod test_iterators {
 
 			assert_eq!(Map::iter_values().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
 
+			// Resume the iteration after the last iterated key
+			let mut iter = Map::iter();
+			assert_eq!(iter.next(), Some((3, 3)));
+			assert_eq!(iter.next(), Some((0, 0)));
+			let last_raw_key = iter.last_raw_key().to_vec();
+			assert_eq!(last_raw_key, Map::storage_map_final_key(0u16));
+			assert_eq!(Map::iter_from(last_raw_key).collect::<Vec<_>>(), vec![(2, 2), (1, 1)]);
+
 			assert_eq!(Map::drain().collect::<Vec<_>>(), vec![(3, 3), (0, 0), (2, 2), (1, 1)]);
 
 			assert_eq!(Map::iter().collect::<Vec<_>>(), vec![]);
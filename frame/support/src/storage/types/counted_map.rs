@@ -0,0 +1,617 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Counted storage map type. Wraps a `StorageMap` together with a `StorageValue` holding the
+//! number of items in the map, and keeps both consistent on every write.
+
+use codec::{FullCodec, Decode, EncodeLike, Encode};
+use crate::{
+	storage::{
+		StorageAppend, StorageDecodeLength, PrefixIterator, unhashed,
+		types::{OptionQuery, QueryKindTrait, OnEmptyGetter, StorageMap, StorageValue, ValueQuery},
+	},
+	traits::{GetDefault, StorageInstance},
+	Never,
+};
+use frame_metadata::{DefaultByteGetter, StorageEntryModifier};
+use sp_std::prelude::*;
+
+/// A wrapper around a `StorageMap` and a `StorageValue<_, u32>` to keep track of how many items
+/// are in the map, without needing to iterate over all of the values.
+///
+/// The counter is incremented whenever a new key is written and decremented whenever a key is
+/// removed, so `count` is a single storage read.
+///
+/// Values are stored at the same location as for a `StorageMap`:
+/// ```nocompile
+/// Twox128(Prefix::pallet_prefix())
+///		++ Twox128(Prefix::STORAGE_PREFIX)
+///		++ Hasher1(encode(key))
+/// ```
+///
+/// And the counter is stored at:
+/// ```nocompile
+/// Twox128(Prefix::pallet_prefix())
+///		++ Twox128(Prefix::CounterPrefix::STORAGE_PREFIX)
+/// ```
+///
+/// # Warning
+///
+/// If the keys are not trusted (e.g. can be set by a user), a cryptographic `hasher` such as
+/// `blake2_128_concat` must be used.  Otherwise, other values in storage can be compromised.
+///
+/// The counter is only kept in sync when the map is accessed through this type. Writing to the
+/// map storage directly (e.g. with `unhashed` or through another storage type with the same
+/// prefix) will leave the counter out of date, see `initialize_counter`.
+pub struct CountedStorageMap<
+	Prefix, Hasher, Key, Value, QueryKind=OptionQuery, OnEmpty=GetDefault
+>(
+	core::marker::PhantomData<(Prefix, Hasher, Key, Value, QueryKind, OnEmpty)>
+);
+
+/// The requirement for an instance of `CountedStorageMap`.
+///
+/// On top of the prefix of the map, it provides the prefix of the storage value holding the
+/// counter.
+pub trait CountedStorageMapInstance: StorageInstance {
+	/// The prefix to use for the counter storage value.
+	type CounterPrefix: StorageInstance;
+}
+
+/// The storage value holding the number of items in the map.
+type CounterFor<Prefix> =
+	StorageValue<<Prefix as CountedStorageMapInstance>::CounterPrefix, u32, ValueQuery>;
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>
+	CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+{
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Vec<u8> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::hashed_key_for(key)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KeyArg: EncodeLike<Key>>(key: KeyArg) -> bool {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::contains_key(key)
+	}
+
+	/// Load the value associated with the given key from the map.
+	pub fn get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::get(key)
+	}
+
+	/// Try to get the value for the given key from the map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<Value, ()> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::try_get(key)
+	}
+
+	/// Swap the values of two keys.
+	pub fn swap<KeyArg1: EncodeLike<Key>, KeyArg2: EncodeLike<Key>>(key1: KeyArg1, key2: KeyArg2) {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::swap(key1, key2)
+	}
+
+	/// Store a value to be associated with the given key from the map.
+	pub fn insert<KeyArg: EncodeLike<Key>, ValArg: EncodeLike<Value>>(key: KeyArg, val: ValArg) {
+		let final_key = Self::hashed_key_for(key);
+		if !unhashed::exists(&final_key) {
+			CounterFor::<Prefix>::mutate(|count| *count = count.saturating_add(1));
+		}
+		unhashed::put(&final_key, &val)
+	}
+
+	/// Remove the value under a key.
+	pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
+		let final_key = Self::hashed_key_for(key);
+		if unhashed::exists(&final_key) {
+			CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1));
+			unhashed::kill(&final_key)
+		}
+	}
+
+	/// Mutate the value under a key.
+	pub fn mutate<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut QueryKind::Query) -> R>(
+		key: KeyArg,
+		f: F
+	) -> R {
+		Self::try_mutate(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned.
+	pub fn try_mutate<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		Self::try_mutate_exists(key, |maybe_value| {
+			let mut query = QueryKind::from_optional_value_to_query(maybe_value.take());
+			let res = f(&mut query);
+			*maybe_value = QueryKind::from_query_to_optional_value(query);
+			res
+		})
+	}
+
+	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
+	pub fn mutate_exists<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut Option<Value>) -> R>(
+		key: KeyArg,
+		f: F
+	) -> R {
+		Self::try_mutate_exists(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
+	pub fn try_mutate_exists<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::try_mutate_exists(
+			key,
+			|maybe_value| {
+				let existed = maybe_value.is_some();
+				let res = f(maybe_value);
+				if res.is_ok() {
+					match (existed, maybe_value.is_some()) {
+						(false, true) =>
+							CounterFor::<Prefix>::mutate(|count| *count = count.saturating_add(1)),
+						(true, false) =>
+							CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1)),
+						_ => (),
+					}
+				}
+				res
+			},
+		)
+	}
+
+	/// Take the value under a key.
+	pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		let final_key = Self::hashed_key_for(key);
+		let value = unhashed::take(&final_key);
+		if value.is_some() {
+			CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1));
+		}
+		QueryKind::from_optional_value_to_query(value)
+	}
+
+	/// Append the given items to the value in the storage.
+	///
+	/// `Value` is required to implement `codec::EncodeAppend`.
+	///
+	/// # Warning
+	///
+	/// If the storage item is not encoded properly, the storage will be overwritten
+	/// and set to `[item]`. Any default value set for the storage item will be ignored
+	/// on overwrite.
+	pub fn append<Item, EncodeLikeItem, EncodeLikeKey>(key: EncodeLikeKey, item: EncodeLikeItem)
+	where
+		EncodeLikeKey: EncodeLike<Key>,
+		Item: Encode,
+		EncodeLikeItem: EncodeLike<Item>,
+		Value: StorageAppend<Item>
+	{
+		let final_key = Self::hashed_key_for(key);
+		if !unhashed::exists(&final_key) {
+			CounterFor::<Prefix>::mutate(|count| *count = count.saturating_add(1));
+		}
+		sp_io::storage::append(&final_key, item.encode());
+	}
+
+	/// Read the length of the storage value without decoding the entire value under the
+	/// given `key`.
+	///
+	/// `Value` is required to implement [`StorageDecodeLength`].
+	///
+	/// If the value does not exists or it fails to decode the length, `None` is returned.
+	/// Otherwise `Some(len)` is returned.
+	///
+	/// # Warning
+	///
+	/// `None` does not mean that `get()` does not return a value. The default value is completly
+	/// ignored by this function.
+	pub fn decode_len<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Option<usize>
+		where Value: StorageDecodeLength,
+	{
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::decode_len(key)
+	}
+
+	/// Migrate an item with the given `key` from a defunct `OldHasher` to the current hasher.
+	///
+	/// If the key doesn't exist, then it's a no-op. If it does, then it returns its value.
+	///
+	/// The item is only moved, so the counter is left untouched.
+	pub fn migrate_key<OldHasher: crate::hash::StorageHasher, KeyArg: EncodeLike<Key>>(
+		key: KeyArg
+	) -> Option<Value> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>
+			::migrate_key::<OldHasher, _>(key)
+	}
+
+	/// Remove all value of the storage, and reset the counter.
+	pub fn remove_all() {
+		CounterFor::<Prefix>::kill();
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::remove_all()
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode becaues storage is corrupted then it is skipped.
+	pub fn iter_values() -> PrefixIterator<Value> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::iter_values()
+	}
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	///
+	/// By returning `None` from `f` for an element, you'll remove it from the map and the counter
+	/// is decremented accordingly.
+	///
+	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
+	///
+	/// # Warning
+	///
+	/// This function must be used with care, before being updated the storage still contains the
+	/// old type, thus other calls (such as `get`) will fail at decoding it.
+	///
+	/// # Usage
+	///
+	/// This would typically be called inside the module implementation of on_runtime_upgrade.
+	pub fn translate_values<OldValue: Decode, F: FnMut(OldValue) -> Option<Value>>(mut f: F) {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::translate_values(|old| {
+			let res = f(old);
+			if res.is_none() {
+				CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1));
+			}
+			res
+		})
+	}
+
+	/// Return the number of items in the map.
+	///
+	/// This is a single storage read of the counter, no iteration is involved.
+	pub fn count() -> u32 {
+		CounterFor::<Prefix>::get()
+	}
+
+	/// Initialize the counter from the actual number of items in the map, and return it.
+	///
+	/// This iterates over all the keys of the map. It is meant to be used in a migration, when a
+	/// `StorageMap` is turned into a `CountedStorageMap`, or to fix a counter which got out of
+	/// sync.
+	pub fn initialize_counter() -> u32 {
+		let prefix = <
+			StorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty> as
+			crate::storage::StoragePrefixedMap<Value>
+		>::final_prefix();
+		let mut previous_key = prefix.to_vec();
+		let mut count = 0u32;
+		while let Some(next) = sp_io::storage::next_key(&previous_key)
+			.filter(|n| n.starts_with(&prefix))
+		{
+			count = count.saturating_add(1);
+			previous_key = next;
+		}
+		CounterFor::<Prefix>::put(count);
+		count
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>
+	CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>
+where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+{
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter() -> PrefixIterator<(Key, Value)> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::iter()
+	}
+
+	/// Enumerate all elements in the map after a specified `starting_raw_key` in no particular
+	/// order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter_from(starting_raw_key: Vec<u8>) -> PrefixIterator<(Key, Value)> {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::iter_from(starting_raw_key)
+	}
+
+	/// Remove all elements from the map and iterate through them in no particular order.
+	///
+	/// The counter is decremented for each element returned by the iterator.
+	///
+	/// If you add elements to the map while doing this, you'll get undefined results.
+	pub fn drain() -> CountedDrain<Prefix, (Key, Value)> {
+		CountedDrain {
+			inner: StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::drain(),
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Translate the values of all elements by a function `f`, in the map in no particular order.
+	///
+	/// By returning `None` from `f` for an element, you'll remove it from the map and the counter
+	/// is decremented accordingly.
+	///
+	/// NOTE: If a value fail to decode because storage is corrupted then it is skipped.
+	pub fn translate<O: Decode, F: FnMut(Key, O) -> Option<Value>>(mut f: F) {
+		StorageMap::<Prefix, Hasher, Key, Value, QueryKind, OnEmpty>::translate(|key, old| {
+			let res = f(key, old);
+			if res.is_none() {
+				CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1));
+			}
+			res
+		})
+	}
+}
+
+/// Iterator returned by `CountedStorageMap::drain`, decrementing the counter for every element
+/// it removes from the map.
+pub struct CountedDrain<Prefix, T> {
+	inner: PrefixIterator<T>,
+	_phantom: core::marker::PhantomData<Prefix>,
+}
+
+impl<Prefix: CountedStorageMapInstance, T> Iterator for CountedDrain<Prefix, T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let item = self.inner.next();
+		if item.is_some() {
+			CounterFor::<Prefix>::mutate(|count| *count = count.saturating_sub(1));
+		}
+		item
+	}
+}
+
+/// Part of storage metadata for a counted storage map.
+///
+/// NOTE: Generic hasher is supported.
+pub trait CountedStorageMapMetadata {
+	const MODIFIER: StorageEntryModifier;
+	const NAME: &'static str;
+	const DEFAULT: DefaultByteGetter;
+	const HASHER: frame_metadata::StorageHasher;
+	const COUNTER_NAME: &'static str;
+	const COUNTER_DEFAULT: DefaultByteGetter;
+}
+
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty> CountedStorageMapMetadata
+	for CountedStorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty> where
+	Prefix: CountedStorageMapInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+{
+	const MODIFIER: StorageEntryModifier = QueryKind::METADATA;
+	const HASHER: frame_metadata::StorageHasher = Hasher::METADATA;
+	const NAME: &'static str = Prefix::STORAGE_PREFIX;
+	const DEFAULT: DefaultByteGetter =
+		DefaultByteGetter(&OnEmptyGetter::<QueryKind::Query, OnEmpty>(core::marker::PhantomData));
+	const COUNTER_NAME: &'static str = Prefix::CounterPrefix::STORAGE_PREFIX;
+	const COUNTER_DEFAULT: DefaultByteGetter =
+		DefaultByteGetter(&OnEmptyGetter::<u32, GetDefault>(core::marker::PhantomData));
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use sp_io::{TestExternalities, hashing::twox_128};
+	use crate::hash::*;
+	use frame_metadata::StorageEntryModifier;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str { "test" }
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+	impl CountedStorageMapInstance for Prefix {
+		type CounterPrefix = CounterPrefix;
+	}
+
+	struct CounterPrefix;
+	impl StorageInstance for CounterPrefix {
+		fn pallet_prefix() -> &'static str { "test" }
+		const STORAGE_PREFIX: &'static str = "counter_for_foo";
+	}
+
+	struct ADefault;
+	impl crate::traits::Get<u32> for ADefault {
+		fn get() -> u32 {
+			97
+		}
+	}
+
+	#[test]
+	fn test() {
+		type A = CountedStorageMap<Prefix, Blake2_128Concat, u16, u32, OptionQuery>;
+		type AValueQueryWithAnOnEmpty = CountedStorageMap<
+			Prefix, Blake2_128Concat, u16, u32, ValueQuery, ADefault
+		>;
+		type B = CountedStorageMap<Prefix, Blake2_128Concat, u16, u8, ValueQuery>;
+		type WithLen = CountedStorageMap<Prefix, Blake2_128Concat, u16, Vec<u32>>;
+
+		TestExternalities::default().execute_with(|| {
+			let mut k: Vec<u8> = vec![];
+			k.extend(&twox_128(b"test"));
+			k.extend(&twox_128(b"foo"));
+			k.extend(&3u16.blake2_128_concat());
+			assert_eq!(A::hashed_key_for(3).to_vec(), k);
+
+			let mut counter_key: Vec<u8> = vec![];
+			counter_key.extend(&twox_128(b"test"));
+			counter_key.extend(&twox_128(b"counter_for_foo"));
+
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::get(3), None);
+			assert_eq!(AValueQueryWithAnOnEmpty::get(3), 97);
+			assert_eq!(A::count(), 0);
+
+			A::insert(3, 10);
+			assert_eq!(A::get(3), Some(10));
+			assert_eq!(A::try_get(3), Ok(10));
+			assert_eq!(A::count(), 1);
+			assert_eq!(unhashed::get::<u32>(&counter_key), Some(1));
+
+			// Overwriting a value doesn't change the counter.
+			A::insert(3, 11);
+			assert_eq!(A::get(3), Some(11));
+			assert_eq!(A::count(), 1);
+
+			A::swap(3, 2);
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::get(2), Some(11));
+			assert_eq!(A::count(), 1);
+
+			A::remove(2);
+			assert_eq!(A::contains_key(2), false);
+			assert_eq!(A::count(), 0);
+
+			// Removing a non-existing value doesn't change the counter.
+			A::remove(2);
+			assert_eq!(A::count(), 0);
+
+			AValueQueryWithAnOnEmpty::mutate(2, |v| *v = *v * 2);
+			assert_eq!(A::get(2), Some(97 * 2));
+			assert_eq!(A::count(), 1);
+			AValueQueryWithAnOnEmpty::mutate(2, |v| *v = *v * 2);
+			assert_eq!(A::get(2), Some(97 * 4));
+			assert_eq!(A::count(), 1);
+
+			A::mutate(2, |v| *v = None);
+			assert_eq!(A::contains_key(2), false);
+			assert_eq!(A::count(), 0);
+
+			let _: Result<(), ()> = AValueQueryWithAnOnEmpty::try_mutate(2, |v| {
+				*v = *v * 2; Err(())
+			});
+			assert_eq!(A::contains_key(2), false);
+			assert_eq!(A::count(), 0);
+
+			let _: Result<(), ()> = AValueQueryWithAnOnEmpty::try_mutate(2, |v| {
+				*v = *v * 2; Ok(())
+			});
+			assert_eq!(A::get(2), Some(97 * 2));
+			assert_eq!(A::count(), 1);
+
+			A::mutate_exists(3, |v| {
+				assert!(v.is_none());
+				*v = Some(10);
+			});
+			assert_eq!(A::get(3), Some(10));
+			assert_eq!(A::count(), 2);
+
+			A::mutate_exists(3, |v| *v = None);
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::count(), 1);
+
+			let _: Result<(), ()> = A::try_mutate_exists(3, |v| {
+				*v = Some(10);
+				Err(())
+			});
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::count(), 1);
+
+			let _: Result<(), ()> = A::try_mutate_exists(3, |v| {
+				*v = Some(10);
+				Ok(())
+			});
+			assert_eq!(A::get(3), Some(10));
+			assert_eq!(A::count(), 2);
+
+			assert_eq!(A::take(3), Some(10));
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::count(), 1);
+			assert_eq!(A::take(3), None);
+			assert_eq!(A::count(), 1);
+			assert_eq!(AValueQueryWithAnOnEmpty::take(3), 97);
+			assert_eq!(A::count(), 1);
+
+			A::insert(3, 10);
+			A::insert(4, 10);
+			assert_eq!(A::count(), 3);
+			A::remove_all();
+			assert_eq!(A::contains_key(3), false);
+			assert_eq!(A::count(), 0);
+			assert_eq!(unhashed::get::<u32>(&counter_key), None);
+
+			A::insert(3, 10);
+			A::insert(4, 10);
+			assert_eq!(A::iter_values().collect::<Vec<_>>(), vec![10, 10]);
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![(4, 10), (3, 10)]);
+
+			A::translate_values::<u32, _>(|v| if v == 10 { None } else { Some(v) });
+			assert_eq!(A::count(), 0);
+
+			B::insert(3, 10);
+			B::insert(4, 20);
+			assert_eq!(A::count(), 2);
+			A::translate::<u8, _>(|k, v| if k == 3 { None } else { Some(v as u32 * 2) });
+			assert_eq!(A::iter().collect::<Vec<_>>(), vec![(4, 40)]);
+			assert_eq!(A::count(), 1);
+
+			A::insert(3, 10);
+			let mut drain = A::drain();
+			assert!(drain.next().is_some());
+			assert_eq!(A::count(), 1);
+			assert!(drain.next().is_some());
+			assert!(drain.next().is_none());
+			assert_eq!(A::count(), 0);
+
+			// Writing to the map storage directly leaves the counter out of date.
+			unhashed::put(&A::hashed_key_for(5), &10u32);
+			unhashed::put(&A::hashed_key_for(6), &10u32);
+			assert_eq!(A::count(), 0);
+			assert_eq!(A::initialize_counter(), 2);
+			assert_eq!(A::count(), 2);
+
+			WithLen::remove_all();
+			assert_eq!(WithLen::decode_len(3), None);
+			WithLen::append(0, 10);
+			assert_eq!(WithLen::decode_len(0), Some(1));
+			assert_eq!(WithLen::count(), 1);
+			WithLen::append(0, 10);
+			assert_eq!(WithLen::decode_len(0), Some(2));
+			assert_eq!(WithLen::count(), 1);
+
+			assert_eq!(A::MODIFIER, StorageEntryModifier::Optional);
+			assert_eq!(AValueQueryWithAnOnEmpty::MODIFIER, StorageEntryModifier::Default);
+			assert_eq!(A::HASHER, frame_metadata::StorageHasher::Blake2_128Concat);
+			assert_eq!(A::NAME, "foo");
+			assert_eq!(A::COUNTER_NAME, "counter_for_foo");
+			assert_eq!(AValueQueryWithAnOnEmpty::DEFAULT.0.default_byte(), 97u32.encode());
+			assert_eq!(A::DEFAULT.0.default_byte(), Option::<u32>::None.encode());
+			assert_eq!(A::COUNTER_DEFAULT.0.default_byte(), 0u32.encode());
+		})
+	}
+}
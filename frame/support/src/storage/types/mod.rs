@@ -24,10 +24,21 @@ use frame_metadata::{DefaultByte, StorageEntryModifier};
 mod value;
 mod map;
 mod double_map;
+mod key;
+mod nmap;
+mod counted_map;
 
 pub use value::{StorageValue, StorageValueMetadata};
 pub use map::{StorageMap, StorageMapMetadata};
 pub use double_map::{StorageDoubleMap, StorageDoubleMapMetadata};
+pub use key::{
+	EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, Key, KeyGenerator,
+	KeyGeneratorInner, ReversibleKeyGenerator, TupleToEncodedIter,
+};
+pub use nmap::{StorageNMap, StorageNMapMetadata};
+pub use counted_map::{
+	CountedDrain, CountedStorageMap, CountedStorageMapInstance, CountedStorageMapMetadata,
+};
 
 /// Trait implementing how the storage optional value is converted into the queried type.
 ///
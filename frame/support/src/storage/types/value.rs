@@ -21,6 +21,7 @@ use codec::{FullCodec, Decode, EncodeLike, Encode};
 use crate::{
 	storage::{
 		StorageAppend, StorageDecodeLength,
+		bounded_vec::BoundedVec,
 		types::{OptionQuery, QueryKindTrait, OnEmptyGetter},
 	},
 	traits::{GetDefault, StorageInstance},
@@ -171,6 +172,30 @@ where
 	}
 }
 
+impl<Prefix, Item, Bound, QueryKind, OnEmpty>
+	StorageValue<Prefix, BoundedVec<Item, Bound>, QueryKind, OnEmpty>
+where
+	Prefix: StorageInstance,
+	Item: FullCodec,
+	Bound: crate::traits::Get<u32>,
+	QueryKind: QueryKindTrait<BoundedVec<Item, Bound>, OnEmpty>,
+	OnEmpty: crate::traits::Get<QueryKind::Query> + 'static,
+{
+	/// Try and append the given item to the bounded vector in storage.
+	///
+	/// Like [`Self::append`] this doesn't decode the whole vector, only its length. Returns
+	/// `Err(())` and leaves the storage untouched if the bound would be exceeded.
+	pub fn try_append<EncodeLikeItem: EncodeLike<Item>>(item: EncodeLikeItem) -> Result<(), ()> {
+		let len = Self::decode_len().unwrap_or(0);
+		if len < BoundedVec::<Item, Bound>::bound() {
+			sp_io::storage::append(&Self::hashed_key(), item.encode());
+			Ok(())
+		} else {
+			Err(())
+		}
+	}
+}
+
 /// Part of storage metadata for storage value.
 pub trait StorageValueMetadata {
 	const MODIFIER: StorageEntryModifier;
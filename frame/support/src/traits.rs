@@ -42,6 +42,9 @@ use impl_trait_for_tuples::impl_for_tuples;
 #[doc(hidden)]
 pub use sp_std::{mem::{swap, take}, cell::RefCell, vec::Vec, boxed::Box};
 
+pub mod tokens;
+pub use tokens::{fungible, fungibles};
+
 /// A trait for online node inspection in a session.
 ///
 /// Something that can give information about the current validator set.
@@ -586,6 +589,35 @@ impl<T: Default> Get<T> for () {
 	fn get() -> T { T::default() }
 }
 
+macro_rules! impl_const_get {
+	($name:ident, $t:ty) => {
+		/// Implements [`Get`] by returning the constant `T`.
+		///
+		/// Allows to set a constant directly in a config trait implementation without
+		/// going through `parameter_types!`, e.g. `type MaxDepth = ConstU32<32>;`.
+		#[derive(RuntimeDebug)]
+		pub struct $name<const T: $t>;
+
+		impl<const T: $t> Get<$t> for $name<T> {
+			fn get() -> $t {
+				T
+			}
+		}
+	}
+}
+
+impl_const_get!(ConstBool, bool);
+impl_const_get!(ConstU8, u8);
+impl_const_get!(ConstU16, u16);
+impl_const_get!(ConstU32, u32);
+impl_const_get!(ConstU64, u64);
+impl_const_get!(ConstU128, u128);
+impl_const_get!(ConstI8, i8);
+impl_const_get!(ConstI16, i16);
+impl_const_get!(ConstI32, i32);
+impl_const_get!(ConstI64, i64);
+impl_const_get!(ConstI128, i128);
+
 /// A trait for querying whether a type can be said to "contain" a value.
 pub trait Contains<T: Ord> {
 	/// Return `true` if this "contains" the given value `t`.
@@ -1563,6 +1595,40 @@ impl<BlockNumber: Clone> OnIdle<BlockNumber> for Tuple {
 	}
 }
 
+/// The block polling trait.
+///
+/// Implementing this lets you express what should happen for your pallet in every block, right
+/// after `on_initialize`, as long as no multi-block migration is ongoing.
+///
+/// Pallets declared with `decl_module!` implement this trait with the default no-op.
+pub trait OnPoll<BlockNumber> {
+	/// The block is being initialized and no multi-block migration is ongoing.
+	/// Implement to have something happen with the given `remaining_weight`.
+	///
+	/// Return the weight used, it is registered as mandatory weight of the block.
+	///
+	/// NOTE: This function is not called while a [`SteppedMigration`] is executed, as the storage
+	/// the pallet relies on might be in the middle of being migrated.
+	fn on_poll(
+		_n: BlockNumber,
+		_remaining_weight: crate::weights::Weight
+	) -> crate::weights::Weight {
+		0
+	}
+}
+
+#[impl_for_tuples(30)]
+impl<BlockNumber: Clone> OnPoll<BlockNumber> for Tuple {
+	fn on_poll(n: BlockNumber, remaining_weight: crate::weights::Weight) -> crate::weights::Weight {
+		let mut weight = 0;
+		for_tuples!( #(
+			let adjusted_remaining_weight = remaining_weight.saturating_sub(weight);
+			weight = weight.saturating_add(Tuple::on_poll(n.clone(), adjusted_remaining_weight));
+		)* );
+		weight
+	}
+}
+
 /// The block initialization trait.
 ///
 /// Implementing this lets you express what should happen for your pallet when the block is
@@ -1700,6 +1766,86 @@ impl OnRuntimeUpgrade for Tuple {
 	}
 }
 
+/// A migration that is too heavy to be executed within a single block and is thus executed in
+/// steps, one per block, instead.
+///
+/// Multi-block migrations are registered with the executive as [`SteppedMigrations`] and are
+/// started on the next runtime upgrade.
+pub trait SteppedMigration {
+	/// The progress of the migration that is persisted in between two steps.
+	type Cursor: FullCodec;
+
+	/// A unique and stable identifier of the migration.
+	///
+	/// It is persisted along with the cursor, so that a cursor is never handed to another
+	/// migration when the sequence of migrations changed with a runtime upgrade.
+	const ID: &'static [u8];
+
+	/// Whether blocks may not contain any transaction apart from mandatory ones (e.g. inherents)
+	/// until this migration is done.
+	///
+	/// Mandatory migrations are stepped at the beginning of the block with all the block's
+	/// remaining weight, others with the weight that is left after all extrinsics are applied.
+	const MANDATORY: bool = false;
+
+	/// Execute the next step of the migration, continuing from `cursor` or starting from the
+	/// beginning if it is `None`.
+	///
+	/// The step should not consume more than `remaining_weight`. Return the cursor to continue
+	/// from in the next block, or `None` if the migration is done, along with the weight used.
+	fn step(cursor: Option<Self::Cursor>, remaining_weight: Weight) -> (Option<Self::Cursor>, Weight);
+}
+
+/// A sequence of [`SteppedMigration`]s that are executed one after another.
+///
+/// Implemented for tuples of [`SteppedMigration`]s.
+pub trait SteppedMigrations {
+	/// For each migration of the sequence, whether it is [`SteppedMigration::MANDATORY`].
+	const IS_MANDATORY: &'static [bool];
+
+	/// For each migration of the sequence, its [`SteppedMigration::ID`].
+	const IDS: &'static [&'static [u8]];
+
+	/// Execute the next step of the `n`th migration, with the cursor in its encoded form.
+	///
+	/// Returns `None` if there is no `n`th migration and an error if the cursor cannot be
+	/// decoded.
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		remaining_weight: Weight,
+	) -> Option<Result<(Option<Vec<u8>>, Weight), codec::Error>>;
+}
+
+/// Type of [`SteppedMigration::step`] operating on encoded cursors.
+type EncodedStep = fn(Option<Vec<u8>>, Weight) -> Result<(Option<Vec<u8>>, Weight), codec::Error>;
+
+/// Execute the next step of the migration `M` with the cursor in its encoded form.
+fn encoded_step<M: SteppedMigration>(
+	cursor: Option<Vec<u8>>,
+	remaining_weight: Weight,
+) -> Result<(Option<Vec<u8>>, Weight), codec::Error> {
+	let cursor = cursor.map(|c| M::Cursor::decode(&mut &c[..])).transpose()?;
+	let (cursor, weight) = M::step(cursor, remaining_weight);
+	Ok((cursor.map(|c| c.encode()), weight))
+}
+
+#[impl_for_tuples(30)]
+#[tuple_types_custom_trait_bound(SteppedMigration)]
+impl SteppedMigrations for Tuple {
+	for_tuples!( const IS_MANDATORY: &'static [bool] = &[ #( Tuple::MANDATORY ),* ]; );
+	for_tuples!( const IDS: &'static [&'static [u8]] = &[ #( Tuple::ID ),* ]; );
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		remaining_weight: Weight,
+	) -> Option<Result<(Option<Vec<u8>>, Weight), codec::Error>> {
+		let steps: &[EncodedStep] = for_tuples!( &[ #( encoded_step::<Tuple> ),* ] );
+		steps.get(n as usize).map(|step| step(cursor, remaining_weight))
+	}
+}
+
 /// Off-chain computation trait.
 ///
 /// Implementing this trait on a module allows you to perform long-running tasks
@@ -2097,6 +2243,16 @@ pub trait Hooks<BlockNumber> {
 	/// Return the non-negotiable weight consumed in the block.
 	fn on_initialize(_n: BlockNumber) -> crate::weights::Weight { 0 }
 
+	/// This will be run right after `on_initialize` in every block in which no multi-block
+	/// migration is ongoing. Implement to have something happen using the remaining weight.
+	/// Return the weight used, it is registered as mandatory weight of the block.
+	fn on_poll(
+		_n: BlockNumber,
+		_remaining_weight: crate::weights::Weight
+	) -> crate::weights::Weight {
+		0
+	}
+
 	/// Perform a module upgrade.
 	///
 	/// NOTE: this doesn't include all pallet logic triggered on runtime upgrade. For instance it
@@ -2307,10 +2463,32 @@ pub trait OnTimestampSet<Moment> {
 	fn on_timestamp_set(moment: Moment);
 }
 
+/// Something that can tell whether a validator is currently disabled.
+pub trait DisabledValidators {
+	/// Returns `true` if the validator with the given index in the current validator set is
+	/// disabled and must not author blocks.
+	fn is_disabled(index: u32) -> bool;
+}
+
+impl DisabledValidators for () {
+	fn is_disabled(_index: u32) -> bool {
+		false
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	#[test]
+	fn const_get_works() {
+		assert!(ConstBool::<true>::get());
+		assert_eq!(ConstU8::<255>::get(), u8::max_value());
+		assert_eq!(ConstU32::<32>::get(), 32u32);
+		assert_eq!(ConstU128::<{ u128::max_value() }>::get(), u128::max_value());
+		assert_eq!(ConstI64::<-1>::get(), -1i64);
+	}
+
 	#[test]
 	fn on_initialize_and_on_runtime_upgrade_weight_merge_works() {
 		struct Test;
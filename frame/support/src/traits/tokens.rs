@@ -0,0 +1,117 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits for working with tokens and their associated datastructures.
+
+pub mod fungible;
+pub mod fungibles;
+
+use codec::{Encode, Decode, FullCodec};
+use sp_std::fmt::Debug;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize},
+	DispatchError, RuntimeDebug,
+};
+
+/// Simple amalgamation trait to collect together properties for a balance type.
+pub trait Balance:
+	AtLeast32BitUnsigned + FullCodec + Copy + Default + Debug + MaybeSerializeDeserialize
+{}
+
+impl<
+	T: AtLeast32BitUnsigned + FullCodec + Copy + Default + Debug + MaybeSerializeDeserialize
+> Balance for T {}
+
+/// One of a number of consequences of withdrawing a fungible from an account.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum WithdrawConsequence<Balance> {
+	/// Withdraw could not happen since the amount to be withdrawn is less than the total funds in
+	/// the account.
+	NoFunds,
+	/// The withdraw would mean the account dying when it needs to exist (usually because it is a
+	/// provider and there are consumer references on it).
+	WouldDie,
+	/// The asset is unknown. Usually because an `AssetId` has been presented which doesn't exist
+	/// on the system.
+	UnknownAsset,
+	/// There has been an underflow in the system. This is indicative of a corrupt state and
+	/// likely unrecoverable.
+	Underflow,
+	/// There has been an overflow in the system. This is indicative of a corrupt state and
+	/// likely unrecoverable.
+	Overflow,
+	/// Not enough of the funds in the account are unavailable for withdrawal.
+	Frozen,
+	/// Account balance would reduce to zero, potentially destroying it. The parameter is the
+	/// amount of balance which is destroyed.
+	ReducedToZero(Balance),
+	/// Account continued in existence.
+	Success,
+}
+
+impl<Balance: Default> WithdrawConsequence<Balance> {
+	/// Convert the type into a `Result` with `DispatchError` as the error or the additional
+	/// `Balance` by which the account will be reduced.
+	pub fn into_result(self) -> Result<Balance, DispatchError> {
+		use WithdrawConsequence::*;
+		match self {
+			NoFunds => Err(DispatchError::Other("NoFunds")),
+			WouldDie => Err(DispatchError::Other("WouldDie")),
+			UnknownAsset => Err(DispatchError::Other("UnknownAsset")),
+			Underflow => Err(DispatchError::Other("Underflow")),
+			Overflow => Err(DispatchError::Other("Overflow")),
+			Frozen => Err(DispatchError::Other("Frozen")),
+			ReducedToZero(result) => Ok(result),
+			Success => Ok(Balance::default()),
+		}
+	}
+}
+
+/// One of a number of consequences of depositing a fungible into an account.
+#[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum DepositConsequence {
+	/// Deposit couldn't happen due to the amount being too low. This is usually because the
+	/// account doesn't yet exist and the deposit wouldn't bring it to at least the minimum needed
+	/// for existence.
+	BelowMinimum,
+	/// Deposit cannot happen since the account cannot be created (usually because it's a consumer
+	/// and there exists no provider reference).
+	CannotCreate,
+	/// The asset is unknown. Usually because an `AssetId` has been presented which doesn't exist
+	/// on the system.
+	UnknownAsset,
+	/// An overflow would occur. This is practically unexpected, but could happen in test systems
+	/// with extremely small balance types or balances that approach the max value of the balance
+	/// type.
+	Overflow,
+	/// Account continued in existence.
+	Success,
+}
+
+impl DepositConsequence {
+	/// Convert the type into a `Result` with `DispatchError` as the error.
+	pub fn into_result(self) -> Result<(), DispatchError> {
+		use DepositConsequence::*;
+		Err(match self {
+			BelowMinimum => DispatchError::Other("BelowMinimum"),
+			CannotCreate => DispatchError::Other("CannotCreate"),
+			UnknownAsset => DispatchError::Other("UnknownAsset"),
+			Overflow => DispatchError::Other("Overflow"),
+			Success => return Ok(()),
+		})
+	}
+}
@@ -371,6 +371,14 @@ pub fn extract_actual_weight(result: &DispatchResultWithPostInfo, info: &Dispatc
 	}.calc_actual_weight(info)
 }
 
+/// Extract the actual pays_fee from a dispatch result if any or fall back to the pre dispatch value.
+pub fn extract_actual_pays_fee(result: &DispatchResultWithPostInfo, info: &DispatchInfo) -> Pays {
+	match result {
+		Ok(post_info) => &post_info,
+		Err(err) => &err.post_info,
+	}.pays_fee(info)
+}
+
 impl From<(Option<Weight>, Pays)> for PostDispatchInfo {
 	fn from(post_weight_info: (Option<Weight>, Pays)) -> Self {
 		let (actual_weight, pays_fee) = post_weight_info;
@@ -953,6 +961,27 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn extract_actual_pays_fee_works() {
+		let pre = DispatchInfo {
+			weight: 1000,
+			.. Default::default()
+		};
+		assert_eq!(extract_actual_pays_fee(&Ok(Some(7).into()), &pre), Pays::Yes);
+		assert_eq!(extract_actual_pays_fee(&Ok((Some(1000), Pays::No).into()), &pre), Pays::No);
+		assert_eq!(
+			extract_actual_pays_fee(&Err(DispatchError::BadOrigin.with_weight(9)), &pre),
+			Pays::Yes
+		);
+
+		let pre = DispatchInfo {
+			weight: 1000,
+			pays_fee: Pays::No,
+			.. Default::default()
+		};
+		assert_eq!(extract_actual_pays_fee(&Ok(Some(7).into()), &pre), Pays::No);
+	}
+
 	type Balance = u64;
 
 	// 0.5x^3 + 2.333x^2 + 7x - 10_000
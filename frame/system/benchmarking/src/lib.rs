@@ -67,6 +67,13 @@ benchmarks! {
 		assert_eq!(current_code.len(), 4_000_000 as usize);
 	}
 
+	authorize_upgrade {
+		let code_hash = T::Hashing::hash(&[1u8; 32]);
+	}: _(RawOrigin::Root, code_hash)
+	verify {
+		assert_eq!(System::<T>::authorized_upgrade(), Some(code_hash));
+	}
+
 	set_changes_trie_config {
 		let d = 1000;
 
@@ -92,7 +92,7 @@ use frame_support::{
 	},
 	weights::{
 		Weight, RuntimeDbWeight, DispatchInfo, DispatchClass,
-		extract_actual_weight, PerDispatchClass,
+		extract_actual_weight, extract_actual_pays_fee, PerDispatchClass,
 	},
 	dispatch::DispatchResultWithPostInfo,
 };
@@ -353,6 +353,56 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Authorize an upgrade to the runtime with the given `code_hash`.
+		///
+		/// The code can then be applied by anyone with `enact_authorized_upgrade`. This
+		/// allows a governance decision to only carry the hash of a runtime, which may be
+		/// too big to fit into a single governance call.
+		///
+		/// Replaces any previously authorized upgrade.
+		///
+		/// # <weight>
+		/// - `O(1)`.
+		/// - 1 storage write.
+		/// - 1 event.
+		/// # </weight>
+		#[pallet::weight((T::SystemWeightInfo::authorize_upgrade(), DispatchClass::Operational))]
+		pub fn authorize_upgrade(origin: OriginFor<T>, code_hash: T::Hash) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			AuthorizedUpgrade::<T>::put(&code_hash);
+			Self::deposit_event(Event::UpgradeAuthorized(code_hash));
+			Ok(().into())
+		}
+
+		/// Provide the runtime code of an upgrade authorized with `authorize_upgrade`.
+		///
+		/// Can be called by any origin. The hash of `code` must match the authorized one and
+		/// `code` must pass the same version checks as in `set_code`. The call is free if
+		/// it succeeds.
+		///
+		/// # <weight>
+		/// - `O(C + S)` where `C` length of `code` and `S` complexity of `can_set_code`
+		/// - 1 storage read and 2 storage writes (codec `O(C)`).
+		/// - 1 call to `can_set_code`: `O(S)` (calls `sp_io::misc::runtime_version` which is expensive).
+		/// - 1 event.
+		/// The weight of this function is dependent on the runtime, but generally this is very expensive.
+		/// We will treat this as a full block.
+		/// # </weight>
+		#[pallet::weight((T::BlockWeights::get().max_block, DispatchClass::Operational))]
+		pub fn enact_authorized_upgrade(
+			_origin: OriginFor<T>,
+			code: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let authorized = AuthorizedUpgrade::<T>::get().ok_or(Error::<T>::NothingAuthorized)?;
+			ensure!(T::Hashing::hash(&code) == authorized, Error::<T>::Unauthorized);
+			Self::can_set_code(&code)?;
+
+			storage::unhashed::put_raw(well_known_keys::CODE, &code);
+			AuthorizedUpgrade::<T>::kill();
+			Self::deposit_event(Event::CodeUpdated);
+			Ok(Pays::No.into())
+		}
+
 		/// Set the new changes trie configuration.
 		///
 		/// # <weight>
@@ -469,8 +519,12 @@ pub mod pallet {
 	#[pallet::metadata(T::AccountId = "AccountId", T::Hash = "Hash")]
 	pub enum Event<T: Config> {
 		/// An extrinsic completed successfully. \[info\]
+		///
+		/// The weight and `pays_fee` of `info` are the actual post-dispatch values.
 		ExtrinsicSuccess(DispatchInfo),
 		/// An extrinsic failed. \[error, info\]
+		///
+		/// The weight and `pays_fee` of `info` are the actual post-dispatch values.
 		ExtrinsicFailed(DispatchError, DispatchInfo),
 		/// `:code` was updated.
 		CodeUpdated,
@@ -480,6 +534,11 @@ pub mod pallet {
 		KilledAccount(T::AccountId),
 		/// On on-chain remark happened. \[origin, remark_hash\]
 		Remarked(T::AccountId, T::Hash),
+		/// An upgrade was authorized. \[code_hash\]
+		UpgradeAuthorized(T::Hash),
+		/// The multi-block migrations were aborted because the cursor of the migration
+		/// could not be decoded or the migration is no longer known. \[id\]
+		MigrationAborted(Vec<u8>),
 	}
 
 	/// Old name generated by `decl_event`.
@@ -503,6 +562,10 @@ pub mod pallet {
 		NonDefaultComposite,
 		/// There is a non-zero reference count preventing the account from being purged.
 		NonZeroRefCount,
+		/// No upgrade has been authorized.
+		NothingAuthorized,
+		/// The given code does not match the authorized upgrade.
+		Unauthorized,
 	}
 
 	/// Exposed trait-generic origin type.
@@ -590,6 +653,10 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type LastRuntimeUpgrade<T: Config> = StorageValue<_, LastRuntimeUpgradeInfo>;
 
+	/// The progress of the multi-block migrations executed by the executive, if any is ongoing.
+	#[pallet::storage]
+	pub type OngoingMigration<T: Config> = StorageValue<_, MigrationCursor>;
+
 	/// True if we have upgraded so that `type RefCount` is `u32`. False (default) if not.
 	#[pallet::storage]
 	pub(super) type UpgradedToU32RefCount<T: Config> = StorageValue<_, bool, ValueQuery>;
@@ -603,6 +670,11 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type ExecutionPhase<T: Config> = StorageValue<_, Phase>;
 
+	/// The hash of the runtime code authorized by `authorize_upgrade`, if any.
+	#[pallet::storage]
+	#[pallet::getter(fn authorized_upgrade)]
+	pub type AuthorizedUpgrade<T: Config> = StorageValue<_, T::Hash>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig {
 		pub changes_trie_config: Option<ChangesTrieConfiguration>,
@@ -812,6 +884,17 @@ impl From<sp_version::RuntimeVersion> for LastRuntimeUpgradeInfo {
 	}
 }
 
+/// Stores the progress of the multi-block migrations that are currently executed.
+#[derive(sp_runtime::RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct MigrationCursor {
+	/// Index of the migration that is currently executed.
+	pub index: u32,
+	/// [`SteppedMigration::ID`](frame_support::traits::SteppedMigration::ID) of that migration.
+	pub id: Vec<u8>,
+	/// Encoded cursor of that migration, `None` if it did not execute any step yet.
+	pub inner: Option<Vec<u8>>,
+}
+
 pub struct EnsureRoot<AccountId>(sp_std::marker::PhantomData<AccountId>);
 impl<
 	O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
@@ -1445,6 +1528,7 @@ impl<T: Config> Module<T> {
 	/// To be called immediately after an extrinsic has been applied.
 	pub fn note_applied_extrinsic(r: &DispatchResultWithPostInfo, mut info: DispatchInfo) {
 		info.weight = extract_actual_weight(r, &info);
+		info.pays_fee = extract_actual_pays_fee(r, &info);
 		Self::deposit_event(
 			match r {
 				Ok(_) => Event::ExtrinsicSuccess(info),
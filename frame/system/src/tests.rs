@@ -19,7 +19,9 @@ use crate::*;
 use mock::{*, Origin};
 use sp_core::H256;
 use sp_runtime::{DispatchError, DispatchErrorWithPostInfo, traits::{Header, BlakeTwo256}};
-use frame_support::{assert_noop, weights::WithPostDispatchInfo, dispatch::PostDispatchInfo};
+use frame_support::{
+	assert_noop, assert_ok, weights::{Pays, WithPostDispatchInfo}, dispatch::PostDispatchInfo,
+};
 
 #[test]
 fn origin_works() {
@@ -307,6 +309,61 @@ fn deposit_event_uses_actual_weight() {
 	});
 }
 
+#[test]
+fn deposit_event_uses_actual_pays_fee() {
+	new_test_ext().execute_with(|| {
+		System::initialize(
+			&1,
+			&[0u8; 32].into(),
+			&Default::default(),
+			InitKind::Full,
+		);
+		System::note_finished_initialize();
+
+		let pre_info = DispatchInfo {
+			weight: 1000,
+			.. Default::default()
+		};
+		System::note_applied_extrinsic(
+			&Ok((Some(300), Pays::No).into()),
+			pre_info,
+		);
+		System::note_applied_extrinsic(
+			&Err(DispatchError::BadOrigin.with_weight(999)),
+			DispatchInfo { pays_fee: Pays::No, .. pre_info },
+		);
+
+		assert_eq!(
+			System::events(),
+			vec![
+				EventRecord {
+					phase: Phase::ApplyExtrinsic(0),
+					event: SysEvent::ExtrinsicSuccess(
+						DispatchInfo {
+							weight: 300,
+							pays_fee: Pays::No,
+							.. Default::default()
+						},
+					).into(),
+					topics: vec![]
+				},
+				EventRecord {
+					phase: Phase::ApplyExtrinsic(1),
+					event: SysEvent::ExtrinsicFailed(
+						DispatchError::BadOrigin.into(),
+						DispatchInfo {
+							weight: 999,
+							pays_fee: Pays::No,
+							.. Default::default()
+						},
+					).into(),
+					topics: vec![]
+				},
+			]
+		);
+	});
+}
+
 #[test]
 fn deposit_event_topics() {
 	new_test_ext().execute_with(|| {
@@ -477,6 +534,96 @@ fn set_code_with_real_wasm_blob() {
 	});
 }
 
+#[test]
+fn authorized_upgrade_can_be_enacted_by_anyone() {
+	let executor = substrate_test_runtime_client::new_native_executor();
+	let mut ext = new_test_ext();
+	ext.register_extension(sp_core::traits::CallInWasmExt::new(executor));
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		let code = substrate_test_runtime_client::runtime::wasm_binary_unwrap().to_vec();
+		let code_hash = <Test as Config>::Hashing::hash(&code);
+
+		assert_noop!(
+			System::authorize_upgrade(RawOrigin::Signed(1).into(), code_hash),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			System::enact_authorized_upgrade(RawOrigin::Signed(1).into(), code.clone()),
+			Error::<Test>::NothingAuthorized,
+		);
+
+		assert_ok!(System::authorize_upgrade(RawOrigin::Root.into(), code_hash));
+		assert_eq!(System::authorized_upgrade(), Some(code_hash));
+
+		assert_noop!(
+			System::enact_authorized_upgrade(RawOrigin::Signed(1).into(), vec![1, 2, 3, 4]),
+			Error::<Test>::Unauthorized,
+		);
+
+		let post_info = System::enact_authorized_upgrade(RawOrigin::Signed(1).into(), code.clone())
+			.unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+		assert_eq!(System::authorized_upgrade(), None);
+		assert_eq!(storage::unhashed::get_raw(well_known_keys::CODE), Some(code));
+
+		assert_eq!(
+			System::events(),
+			vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: SysEvent::UpgradeAuthorized(code_hash).into(),
+					topics: vec![],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: SysEvent::CodeUpdated.into(),
+					topics: vec![],
+				},
+			],
+		);
+	});
+}
+
+#[test]
+fn authorized_upgrade_keeps_version_checks() {
+	struct CallInWasm(Vec<u8>);
+
+	impl sp_core::traits::CallInWasm for CallInWasm {
+		fn call_in_wasm(
+			&self,
+			_: &[u8],
+			_: Option<Vec<u8>>,
+			_: &str,
+			_: &[u8],
+			_: &mut dyn sp_externalities::Externalities,
+			_: sp_core::traits::MissingHostFunctions,
+		) -> Result<Vec<u8>, String> {
+			Ok(self.0.clone())
+		}
+	}
+
+	let version = RuntimeVersion {
+		spec_name: "test".into(),
+		spec_version: 1,
+		impl_version: 1,
+		..Default::default()
+	};
+	let mut ext = new_test_ext();
+	ext.register_extension(sp_core::traits::CallInWasmExt::new(CallInWasm(version.encode())));
+	ext.execute_with(|| {
+		let code = vec![1, 2, 3, 4];
+		assert_ok!(System::authorize_upgrade(
+			RawOrigin::Root.into(),
+			<Test as Config>::Hashing::hash(&code),
+		));
+		assert_noop!(
+			System::enact_authorized_upgrade(RawOrigin::Signed(1).into(), code),
+			Error::<Test>::SpecVersionNeedsToIncrease,
+		);
+	});
+}
+
 #[test]
 fn runtime_upgraded_with_set_storage() {
 	let executor = substrate_test_runtime_client::new_native_executor();
@@ -20,6 +20,8 @@
 //! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
 //! DATE: 2021-02-28, STEPS: \[50, \], REPEAT: 20, LOW RANGE: [], HIGH RANGE: []
 //! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
+//!
+//! `authorize_upgrade` was added after this run and is not generated yet, see its entry.
 
 // Executed Command:
 // target/release/substrate
@@ -52,6 +54,7 @@ pub trait WeightInfo {
 	fn set_storage(i: u32, ) -> Weight;
 	fn kill_storage(i: u32, ) -> Weight;
 	fn kill_prefix(p: u32, ) -> Weight;
+	fn authorize_upgrade() -> Weight;
 }
 
 /// Weights for frame_system using the Substrate node and recommended hardware.
@@ -92,6 +95,12 @@ impl<T: crate::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((862_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
 	}
+	// Not generated: priced as `remark_with_event(0)`, which also deposits an event, plus
+	// the write of `AuthorizedUpgrade`. Regenerate from the `authorize_upgrade` benchmark.
+	fn authorize_upgrade() -> Weight {
+		(9_697_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -131,4 +140,10 @@ impl WeightInfo for () {
 			.saturating_add((862_000 as Weight).saturating_mul(p as Weight))
 			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
 	}
+	// Not generated: priced as `remark_with_event(0)`, which also deposits an event, plus
+	// the write of `AuthorizedUpgrade`. Regenerate from the `authorize_upgrade` benchmark.
+	fn authorize_upgrade() -> Weight {
+		(9_697_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 }
@@ -700,6 +700,8 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = ();
+		type HoldReason = ();
+		type MaxHolds = ();
 		type WeightInfo = ();
 	}
 
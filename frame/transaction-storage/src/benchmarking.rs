@@ -0,0 +1,97 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for transaction-storage Pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use sp_std::prelude::*;
+use super::*;
+use sp_runtime::traits::{One, Bounded};
+use sp_transaction_storage_proof::build_proof;
+use frame_system::{RawOrigin, Pallet as System, EventRecord};
+use frame_benchmarking::{benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+use frame_support::traits::{Currency, OnFinalize, OnInitialize};
+
+use crate::Pallet as TransactionStorage;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = System::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	let EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+pub fn run_to_block<T: Config>(n: T::BlockNumber) {
+	while System::<T>::block_number() < n {
+		TransactionStorage::<T>::on_finalize(System::<T>::block_number());
+		System::<T>::on_finalize(System::<T>::block_number());
+		System::<T>::set_block_number(System::<T>::block_number() + One::one());
+		System::<T>::on_initialize(System::<T>::block_number());
+		TransactionStorage::<T>::on_initialize(System::<T>::block_number());
+	}
+}
+
+benchmarks! {
+	store {
+		let l in 1 .. T::MaxTransactionSize::get();
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	}: _(RawOrigin::Signed(caller.clone()), vec![0u8; l as usize])
+	verify {
+		assert!(!BlockTransactions::<T>::get().is_empty());
+		assert_last_event::<T>(Event::Stored(0).into());
+	}
+
+	renew {
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let block = System::<T>::block_number();
+		TransactionStorage::<T>::store(
+			RawOrigin::Signed(caller.clone()).into(),
+			vec![0u8; T::MaxTransactionSize::get() as usize],
+		)?;
+		run_to_block::<T>(block + One::one());
+	}: _(RawOrigin::Signed(caller.clone()), block, 0)
+	verify {
+		assert_last_event::<T>(Event::Renewed(0).into());
+	}
+
+	check_proof_max {
+		// Data stored in block zero is never proved.
+		run_to_block::<T>(One::one());
+		let caller: T::AccountId = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		let block = System::<T>::block_number();
+		let data = vec![0u8; T::MaxTransactionSize::get() as usize];
+		TransactionStorage::<T>::store(RawOrigin::Signed(caller.clone()).into(), data.clone())?;
+		run_to_block::<T>(block + StoragePeriod::<T>::get());
+		let parent_hash = System::<T>::parent_hash();
+		let proof = build_proof(parent_hash.as_ref(), vec![data])
+			.map_err(|_| "Failed to build the proof")?
+			.expect("Data was stored in the target block; qed");
+	}: _(RawOrigin::None, proof)
+	verify {
+		assert_last_event::<T>(Event::ProofChecked.into());
+	}
+}
+
+impl_benchmark_test_suite!(
+	TransactionStorage,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,451 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Transaction Storage Pallet
+//!
+//! Indexes transactions and manages storage proofs.
+//!
+//! ## Overview
+//!
+//! Users pay a per-byte fee to `store` arbitrary data. The data is indexed by the client with
+//! the transaction index API, so that nodes running with `--storage-chain` keep it available
+//! (e.g. over bitswap) for `StoragePeriod` blocks. Data may be kept for longer by calling
+//! `renew`, which indexes the same data again in the current block.
+//!
+//! To make sure the data is actually retained, each block `n` must include a `check_proof`
+//! inherent proving that the block author has a chunk of the data stored in block
+//! `n - StoragePeriod`. The chunk is selected pseudo-randomly from the parent block hash. The
+//! proof is built by the client with
+//! `sp_transaction_storage_proof::registration::new_data_provider`, which requires the runtime
+//! to implement `sp_transaction_storage_proof::TransactionStorageApi`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod benchmarking;
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::traits::{Currency, OnUnbalanced, ExistenceRequirement, WithdrawReasons};
+use sp_std::{prelude::*, result};
+use codec::{Encode, Decode};
+use sp_runtime::traits::{Saturating, BlakeTwo256, Hash, Zero, One};
+use sp_transaction_storage_proof::{
+	TransactionStorageProof, InherentError,
+	random_chunk, encode_index, num_chunks,
+	CHUNK_SIZE, INHERENT_IDENTIFIER,
+};
+
+/// A type alias for the balance type from this pallet's point of view.
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>
+	::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+/// Maximum bytes that can be stored in one transaction.
+// Setting higher limit also requires raising the allocator limit.
+pub const DEFAULT_MAX_TRANSACTION_SIZE: u32 = 8 * 1024 * 1024;
+/// Maximum number of transactions that can be stored or renewed in one block.
+pub const DEFAULT_MAX_BLOCK_TRANSACTIONS: u32 = 512;
+
+/// State data for a stored transaction.
+#[derive(Encode, Decode, Clone, sp_runtime::RuntimeDebug, PartialEq, Eq)]
+pub struct TransactionInfo {
+	/// Chunk trie root.
+	chunk_root: <BlakeTwo256 as Hash>::Output,
+	/// Plain hash of indexed data.
+	content_hash: <BlakeTwo256 as Hash>::Output,
+	/// Size of indexed data in bytes.
+	size: u32,
+	/// Total number of chunks added in the block with this transaction. This is used to find
+	/// the transaction info by block chunk index using binary search.
+	block_chunks: u32,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency used to pay for storage.
+		type Currency: Currency<Self::AccountId>;
+		/// Handler for the storage fees.
+		type FeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Maximum number of transactions that can be stored or renewed in one block.
+		#[pallet::constant]
+		type MaxBlockTransactions: Get<u32>;
+		/// Maximum size of the data stored in a single transaction, in bytes.
+		#[pallet::constant]
+		type MaxTransactionSize: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Insufficient account balance.
+		InsufficientFunds,
+		/// Renewed extrinsic is not found.
+		RenewedNotFound,
+		/// Attempting to store empty transaction.
+		EmptyTransaction,
+		/// Proof was not expected in this block.
+		UnexpectedProof,
+		/// Proof failed verification.
+		InvalidProof,
+		/// Unable to verify proof because state data is missing.
+		MissingStateData,
+		/// Double proof check in the block.
+		DoubleCheck,
+		/// Transaction is too large.
+		TransactionTooLarge,
+		/// Too many transactions in the block.
+		TooManyTransactions,
+		/// Attempted to call `store` or `renew` outside of block execution.
+		BadContext,
+		/// Renewed data reaches the end of its storage period in this block.
+		RenewalExpired,
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			// Drop obsolete roots. The proof for `obsolete + 1` is checked in this block, so
+			// the data stored in it must be kept until then.
+			let period = <StoragePeriod<T>>::get();
+			let obsolete = n.saturating_sub(period.saturating_add(One::one()));
+			if obsolete > Zero::zero() {
+				<Transactions<T>>::remove(obsolete);
+				<ChunkCount<T>>::remove(obsolete);
+			}
+			// 2 writes in `on_initialize` and 2 writes + 2 reads in `on_finalize`
+			T::DbWeight::get().reads_writes(2, 4)
+		}
+
+		fn on_finalize(n: T::BlockNumber) {
+			assert!(
+				<ProofChecked<T>>::take() || !Self::proof_required(n),
+				"Storage proof must be checked once in the block",
+			);
+			// Insert new transactions.
+			let transactions = <BlockTransactions<T>>::take();
+			let total_chunks = transactions.last().map_or(0, |t| t.block_chunks);
+			if total_chunks != 0 {
+				<ChunkCount<T>>::insert(n, total_chunks);
+				<Transactions<T>>::insert(n, transactions);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Index and store data on chain. Minimum data size is 1 byte, maximum is
+		/// `MaxTransactionSize`. Data will be removed after `StoragePeriod` blocks, unless
+		/// `renew` is called.
+		///
+		/// The dispatch origin for this call must be _Signed_. The sender pays `EntryFee` plus
+		/// `ByteFee` for every byte of data.
+		///
+		/// # <weight>
+		/// - n*log(n) of data size, as all data is pushed to an in-memory trie.
+		/// - 1 storage read and 1 storage mutation.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::store(data.len() as u32))]
+		pub(super) fn store(
+			origin: OriginFor<T>,
+			data: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(data.len() > 0, Error::<T>::EmptyTransaction);
+			ensure!(
+				data.len() <= T::MaxTransactionSize::get() as usize,
+				Error::<T>::TransactionTooLarge,
+			);
+			let extrinsic_index = <frame_system::Pallet<T>>::extrinsic_index()
+				.ok_or(Error::<T>::BadContext)?;
+			let mut transactions = <BlockTransactions<T>>::get();
+			ensure!(
+				(transactions.len() as u32) < T::MaxBlockTransactions::get(),
+				Error::<T>::TooManyTransactions,
+			);
+			Self::apply_fee(sender, data.len() as u32)?;
+
+			// Chunk data and compute storage root.
+			let chunk_count = num_chunks(data.len() as u32);
+			let chunks = data.chunks(CHUNK_SIZE).map(|c| c.to_vec()).collect();
+			let root = sp_io::trie::blake2_256_ordered_root(chunks);
+
+			// Indexing can't be reverted, so it must happen after all the checks.
+			let content_hash = sp_io::hashing::blake2_256(&data);
+			sp_io::transaction_index::index(extrinsic_index, data.len() as u32, content_hash);
+
+			let index = transactions.len() as u32;
+			let total_chunks = transactions.last().map_or(0, |t| t.block_chunks) + chunk_count;
+			transactions.push(TransactionInfo {
+				chunk_root: root,
+				content_hash: content_hash.into(),
+				size: data.len() as u32,
+				block_chunks: total_chunks,
+			});
+			<BlockTransactions<T>>::put(transactions);
+			Self::deposit_event(Event::Stored(index));
+			Ok(().into())
+		}
+
+		/// Renew previously stored data. Parameters are the block number that contains
+		/// previous `store` or `renew` call and transaction index within that block.
+		/// Transaction index is emitted in the `Stored` or `Renewed` event.
+		///
+		/// Data must be renewed before the last block of its storage period, in which it is
+		/// proven for the last time.
+		///
+		/// The dispatch origin for this call must be _Signed_. Applies the same fee as `store`.
+		///
+		/// # <weight>
+		/// - Constant.
+		/// - 2 storage reads and 1 storage mutation.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::renew())]
+		pub(super) fn renew(
+			origin: OriginFor<T>,
+			block: T::BlockNumber,
+			index: u32,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			// Nodes only keep data for `StoragePeriod` blocks. Renewing data they may already
+			// have discarded would make the proof for the renewing block impossible to build.
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(
+				block.saturating_add(<StoragePeriod<T>>::get()) > now,
+				Error::<T>::RenewalExpired,
+			);
+			let info = <Transactions<T>>::get(block)
+				.and_then(|transactions| transactions.get(index as usize).cloned())
+				.ok_or(Error::<T>::RenewedNotFound)?;
+			let extrinsic_index = <frame_system::Pallet<T>>::extrinsic_index()
+				.ok_or(Error::<T>::BadContext)?;
+			let mut transactions = <BlockTransactions<T>>::get();
+			ensure!(
+				(transactions.len() as u32) < T::MaxBlockTransactions::get(),
+				Error::<T>::TooManyTransactions,
+			);
+			Self::apply_fee(sender, info.size)?;
+
+			// Indexing can't be reverted, so it must happen after all the checks.
+			sp_io::transaction_index::renew(extrinsic_index, info.content_hash.into());
+
+			let index = transactions.len() as u32;
+			let chunks = num_chunks(info.size);
+			let total_chunks = transactions.last().map_or(0, |t| t.block_chunks) + chunks;
+			transactions.push(TransactionInfo {
+				block_chunks: total_chunks,
+				..info
+			});
+			<BlockTransactions<T>>::put(transactions);
+			Self::deposit_event(Event::Renewed(index));
+			Ok(().into())
+		}
+
+		/// Check storage proof for block number `block_number() - StoragePeriod`.
+		/// If such block does not exist the proof is expected to be `None`.
+		///
+		/// The dispatch origin for this call must be _None_, i.e. it is an inherent.
+		///
+		/// # <weight>
+		/// - Logarithmic in the number of transactions stored in the proved block and in the
+		///   size of the proved transaction.
+		/// - 4 storage reads and 1 storage mutation.
+		/// # </weight>
+		#[pallet::weight((T::WeightInfo::check_proof_max(), DispatchClass::Mandatory))]
+		pub(super) fn check_proof(
+			origin: OriginFor<T>,
+			proof: TransactionStorageProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			ensure!(!<ProofChecked<T>>::get(), Error::<T>::DoubleCheck);
+			let number = <frame_system::Pallet<T>>::block_number();
+			let period = <StoragePeriod<T>>::get();
+			let target_number = number.saturating_sub(period);
+			ensure!(!target_number.is_zero(), Error::<T>::UnexpectedProof);
+			let total_chunks = <ChunkCount<T>>::get(target_number);
+			ensure!(total_chunks != 0, Error::<T>::UnexpectedProof);
+			let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+			let selected_chunk_index = random_chunk(parent_hash.as_ref(), total_chunks);
+
+			let infos = <Transactions<T>>::get(target_number).ok_or(Error::<T>::MissingStateData)?;
+			// `block_chunks` is the exclusive end of the chunk range of each transaction.
+			let index = match infos.binary_search_by_key(&selected_chunk_index, |info| info.block_chunks) {
+				Ok(index) => index + 1,
+				Err(index) => index,
+			};
+			let info = infos.get(index).ok_or(Error::<T>::MissingStateData)?;
+			let chunk_index = selected_chunk_index - (info.block_chunks - num_chunks(info.size));
+
+			ensure!(
+				sp_io::trie::blake2_256_verify_proof(
+					info.chunk_root,
+					&proof.proof,
+					&encode_index(chunk_index),
+					&proof.chunk,
+				),
+				Error::<T>::InvalidProof,
+			);
+			<ProofChecked<T>>::put(true);
+			Self::deposit_event(Event::ProofChecked);
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// Stored data under specified index.
+		Stored(u32),
+		/// Renewed data under specified index.
+		Renewed(u32),
+		/// Storage proof was successfully checked.
+		ProofChecked,
+	}
+
+	/// Collection of transaction metadata by block number.
+	#[pallet::storage]
+	#[pallet::getter(fn transaction_roots)]
+	pub(super) type Transactions<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		Vec<TransactionInfo>,
+		OptionQuery,
+	>;
+
+	/// Count indexed chunks for each block.
+	#[pallet::storage]
+	pub(super) type ChunkCount<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Storage fee per byte.
+	#[pallet::storage]
+	#[pallet::getter(fn byte_fee)]
+	pub(super) type ByteFee<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Storage fee per transaction.
+	#[pallet::storage]
+	#[pallet::getter(fn entry_fee)]
+	pub(super) type EntryFee<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Storage period for data in blocks. Exposed to block authors with the
+	/// `sp_transaction_storage_proof::TransactionStorageApi` runtime API.
+	#[pallet::storage]
+	#[pallet::getter(fn storage_period)]
+	pub(super) type StoragePeriod<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Transactions stored or renewed in the current block.
+	#[pallet::storage]
+	pub(super) type BlockTransactions<T: Config> = StorageValue<_, Vec<TransactionInfo>, ValueQuery>;
+
+	/// Was the proof checked in this block?
+	#[pallet::storage]
+	pub(super) type ProofChecked<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub byte_fee: BalanceOf<T>,
+		pub entry_fee: BalanceOf<T>,
+		pub storage_period: T::BlockNumber,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				byte_fee: 10u32.into(),
+				entry_fee: 1000u32.into(),
+				storage_period: sp_transaction_storage_proof::DEFAULT_STORAGE_PERIOD.into(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			<ByteFee<T>>::put(&self.byte_fee);
+			<EntryFee<T>>::put(&self.entry_fee);
+			<StoragePeriod<T>>::put(&self.storage_period);
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = InherentError;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			let proof = data.get_data::<TransactionStorageProof>(&Self::INHERENT_IDENTIFIER)
+				.unwrap_or(None);
+			proof.map(Call::check_proof)
+		}
+
+		fn check_inherent(_call: &Self::Call, _data: &InherentData) -> result::Result<(), Self::Error> {
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether a storage proof must be checked in block `n`, i.e. whether any data was
+		/// stored in block `n - StoragePeriod`.
+		pub(super) fn proof_required(n: T::BlockNumber) -> bool {
+			let target_number = n.saturating_sub(<StoragePeriod<T>>::get());
+			!target_number.is_zero() && <ChunkCount<T>>::get(target_number) != 0
+		}
+
+		fn apply_fee(sender: T::AccountId, size: u32) -> DispatchResult {
+			let fee = Self::byte_fee()
+				.saturating_mul(size.into())
+				.saturating_add(Self::entry_fee());
+			let credit = T::Currency::withdraw(
+				&sender,
+				fee,
+				WithdrawReasons::TRANSACTION_PAYMENT,
+				ExistenceRequirement::KeepAlive,
+			).map_err(|_| Error::<T>::InsufficientFunds)?;
+			T::FeeDestination::on_unbalanced(credit);
+			Ok(())
+		}
+	}
+}
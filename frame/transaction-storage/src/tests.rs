@@ -0,0 +1,202 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for transaction-storage pallet.
+
+use super::*;
+use crate::mock::{self, *};
+use frame_support::{assert_ok, assert_noop};
+use sp_transaction_storage_proof::build_proof;
+
+fn last_event() -> mock::Event {
+	System::events().pop().expect("Event expected").event
+}
+
+fn proof_for(transactions: Vec<Vec<u8>>) -> TransactionStorageProof {
+	let parent_hash = System::parent_hash();
+	build_proof(parent_hash.as_ref(), transactions).unwrap().unwrap()
+}
+
+#[test]
+fn discards_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		let proof_provider = || {
+			if System::block_number() == 11 {
+				Some(proof_for(vec![vec![0u8; 2000], vec![0u8; 2000]]))
+			} else {
+				None
+			}
+		};
+		run_to_block(11, proof_provider);
+		assert_eq!(Transactions::<Test>::get(1).map(|t| t.len()), Some(2));
+		assert_eq!(ChunkCount::<Test>::get(1), 16);
+		run_to_block(12, proof_provider);
+		assert!(Transactions::<Test>::get(1).is_none());
+		assert_eq!(ChunkCount::<Test>::get(1), 0);
+	});
+}
+
+#[test]
+fn burns_fee() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_noop!(
+			TransactionStorage::store(Origin::signed(5), vec![0u8; 2000]),
+			Error::<Test>::InsufficientFunds,
+		);
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		assert_eq!(Balances::free_balance(1), 1_000_000_000 - 2000 * 2 - 200);
+		assert_eq!(last_event(), mock::Event::pallet_transaction_storage(crate::Event::Stored(0)));
+	});
+}
+
+#[test]
+fn rejects_invalid_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_noop!(
+			TransactionStorage::store(Origin::signed(1), Vec::new()),
+			Error::<Test>::EmptyTransaction,
+		);
+		assert_noop!(
+			TransactionStorage::store(
+				Origin::signed(1),
+				vec![0u8; MaxTransactionSize::get() as usize + 1],
+			),
+			Error::<Test>::TransactionTooLarge,
+		);
+		for _ in 0 .. MaxBlockTransactions::get() {
+			assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 1]));
+		}
+		assert_noop!(
+			TransactionStorage::store(Origin::signed(1), vec![0u8; 1]),
+			Error::<Test>::TooManyTransactions,
+		);
+	});
+}
+
+#[test]
+fn checks_proof() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::store(
+			Origin::signed(1),
+			vec![0u8; MaxTransactionSize::get() as usize],
+		));
+		run_to_block(10, || None);
+		let parent_hash = System::parent_hash();
+		let proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![0u8; MaxTransactionSize::get() as usize]],
+		).unwrap().unwrap();
+		// No data was stored in block zero.
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), proof),
+			Error::<Test>::UnexpectedProof,
+		);
+		run_to_block(11, || None);
+		let parent_hash = System::parent_hash();
+
+		let invalid_proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![0u8; 1000]],
+		).unwrap().unwrap();
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), invalid_proof),
+			Error::<Test>::InvalidProof,
+		);
+
+		let proof = build_proof(
+			parent_hash.as_ref(),
+			vec![vec![0u8; MaxTransactionSize::get() as usize]],
+		).unwrap().unwrap();
+		assert_ok!(TransactionStorage::check_proof(Origin::none(), proof.clone()));
+		assert_eq!(last_event(), mock::Event::pallet_transaction_storage(crate::Event::ProofChecked));
+		assert_noop!(
+			TransactionStorage::check_proof(Origin::none(), proof),
+			Error::<Test>::DoubleCheck,
+		);
+	});
+}
+
+#[test]
+#[should_panic(expected = "Storage proof must be checked once in the block")]
+fn requires_proof() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		run_to_block(12, || None);
+	});
+}
+
+#[test]
+fn renews_data() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		let fee = 2000 * 2 + 200;
+		assert_eq!(Balances::free_balance(1), 1_000_000_000 - fee);
+		run_to_block(6, || None);
+		assert_noop!(
+			TransactionStorage::renew(Origin::signed(1), 1, 1),
+			Error::<Test>::RenewedNotFound,
+		);
+		assert_ok!(TransactionStorage::renew(Origin::signed(1), 1, 0));
+		assert_eq!(Balances::free_balance(1), 1_000_000_000 - fee * 2);
+		assert_eq!(last_event(), mock::Event::pallet_transaction_storage(crate::Event::Renewed(0)));
+		let proof_provider = || {
+			let block_num = System::block_number();
+			if block_num == 11 || block_num == 16 {
+				Some(proof_for(vec![vec![0u8; 2000]]))
+			} else {
+				None
+			}
+		};
+		run_to_block(16, proof_provider);
+		assert!(Transactions::<Test>::get(1).is_none());
+		assert_eq!(Transactions::<Test>::get(6).map(|t| t.len()), Some(1));
+		run_to_block(17, proof_provider);
+		assert!(Transactions::<Test>::get(6).is_none());
+	});
+}
+
+#[test]
+fn rejects_expired_renewal() {
+	new_test_ext().execute_with(|| {
+		run_to_block(1, || None);
+		assert_ok!(TransactionStorage::store(Origin::signed(1), vec![0u8; 2000]));
+		let proof_provider = || {
+			if System::block_number() == 11 {
+				Some(proof_for(vec![vec![0u8; 2000]]))
+			} else {
+				None
+			}
+		};
+		run_to_block(10, proof_provider);
+		assert_ok!(TransactionStorage::renew(Origin::signed(1), 1, 0));
+		run_to_block(11, proof_provider);
+		// The data of block 1 is proven for the last time in this block.
+		assert!(Transactions::<Test>::get(1).is_some());
+		assert_noop!(
+			TransactionStorage::renew(Origin::signed(1), 1, 0),
+			Error::<Test>::RenewalExpired,
+		);
+	});
+}
@@ -453,6 +453,8 @@ mod tests {
 		type ExistentialDeposit = ExistentialDeposit;
 		type AccountStore = System;
 		type MaxLocks = MaxLocks;
+		type HoldReason = ();
+		type MaxHolds = ();
 		type WeightInfo = ();
 	}
 	parameter_types! {
@@ -47,7 +47,8 @@ const HIDDEN_INCLUDES_ID: &str = "DECL_RUNTIME_APIS";
 const CORE_TRAIT_ATTRIBUTE: &str = "core_trait";
 /// The `api_version` attribute.
 ///
-/// Is used to set the current version of the trait.
+/// Is used to set the current version of the trait. When placed on a method, it declares the
+/// version of the trait in which the method was added.
 const API_VERSION_ATTRIBUTE: &str = "api_version";
 /// The `changed_in` attribute.
 ///
@@ -343,7 +344,9 @@ fn parse_renamed_attribute(renamed: &Attribute) -> Result<(String, u32)> {
 }
 
 /// Generate the functions that call the api at a given block for a given trait method.
-fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
+///
+/// `trait_api_version` is the current version of the trait.
+fn generate_call_api_at_calls(decl: &ItemTrait, trait_api_version: u64) -> Result<TokenStream> {
 	let fns = decl.items.iter().filter_map(|i| match i {
 		TraitItem::Method(ref m) => Some((&m.attrs, &m.sig)),
 		_ => None,
@@ -373,9 +376,33 @@ fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
 
 		// We do not need to generate this function for a method that signature was changed.
 		if attrs.contains_key(CHANGED_IN_ATTRIBUTE) {
+			if attrs.contains_key(API_VERSION_ATTRIBUTE) {
+				return Err(Error::new(
+					fn_.span(),
+					format!(
+						"`{}` and `{}` are not supported at once.",
+						API_VERSION_ATTRIBUTE,
+						CHANGED_IN_ATTRIBUTE
+					)
+				));
+			}
+
 			continue;
 		}
 
+		// The version of the trait that introduced this method, if it was added later on.
+		let mut added_in = Vec::new();
+		if let Some(a) = attrs.get(&API_VERSION_ATTRIBUTE) {
+			let version = parse_runtime_api_version(a)?;
+			if version > trait_api_version {
+				return Err(Error::new(
+					a.span(),
+					"`api_version` of a method can not be greater than the `api_version` of the trait",
+				));
+			}
+			added_in.push(version as u32);
+		}
+
 		let skip_initialize_block = attrs.contains_key(SKIP_INITIALIZE_BLOCK_ATTRIBUTE);
 		let update_initialized_block = if attrs.contains_key(INITIALIZE_BLOCK_ATTRIBUTE) {
 			quote!(
@@ -429,6 +456,17 @@ fn generate_call_api_at_calls(decl: &ItemTrait) -> Result<TokenStream> {
 				recorder: &Option<#crate_::ProofRecorder<Block>>,
 			) -> std::result::Result<#crate_::NativeOrEncoded<R>, #crate_::ApiError> {
 				let version = call_runtime_at.runtime_version_at(at)?;
+
+				#(
+					// Check that the runtime implements a version of the api that has this function.
+					if !version.apis.iter().any(|(s, v)| s == &ID && *v >= #added_in) {
+						return Err(#crate_::ApiError::UnsupportedApiVersion {
+							function: #trait_fn_name,
+							required: #added_in,
+						})
+					}
+				)*
+
 				use #crate_::InitializeBlock;
 				let initialize_block = if #skip_initialize_block {
 					InitializeBlock::Skip
@@ -495,12 +533,11 @@ fn generate_runtime_decls(decls: &[ItemTrait]) -> Result<TokenStream> {
 		extend_generics_with_block(&mut decl.generics);
 		let mod_name = generate_runtime_mod_name_for_trait(&decl.ident);
 		let found_attributes = remove_supported_attributes(&mut decl.attrs);
-		let api_version = get_api_version(&found_attributes).map(|v| {
-			generate_runtime_api_version(v as u32)
-		})?;
+		let trait_api_version = get_api_version(&found_attributes)?;
+		let api_version = generate_runtime_api_version(trait_api_version as u32);
 		let id = generate_runtime_api_id(&decl.ident.to_string());
 
-		let call_api_at_calls = generate_call_api_at_calls(&decl)?;
+		let call_api_at_calls = generate_call_api_at_calls(&decl, trait_api_version)?;
 
 		// Remove methods that have the `changed_in` attribute as they are not required for the
 		// runtime anymore.
@@ -137,6 +137,11 @@ pub const MAX_EXTRINSIC_DEPTH: u32 = 256;
 /// required that there exist the "default" of the method without the `#[changed_in(_)]` attribute,
 /// this method will be used to call the current default implementation.
 ///
+/// A method that was added in a later version of the trait can be tagged with the
+/// `#[api_version(_)]` attribute, set to the version that introduced it. Calling such a method
+/// against a runtime that implements an older version of the trait returns
+/// `ApiError::UnsupportedApiVersion` instead of failing inside the runtime.
+///
 /// ```rust
 /// sp_api::decl_runtime_apis! {
 ///     /// Declare the api trait.
@@ -152,6 +157,7 @@ pub const MAX_EXTRINSIC_DEPTH: u32 = 256;
 ///         #[changed_in(2)]
 ///         fn set_balance(val: u16);
 ///         /// In version 2, we added this new function.
+///         #[api_version(2)]
 ///         fn increase_balance(val: u64);
 ///     }
 /// }
@@ -428,6 +434,11 @@ pub enum ApiError {
 		#[source]
 		error: codec::Error,
 	},
+	#[error("{function} requires version {required} of its runtime api, which the runtime does not implement")]
+	UnsupportedApiVersion {
+		function: &'static str,
+		required: u32,
+	},
 	#[error(transparent)]
 	Application(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -668,8 +679,20 @@ decl_runtime_apis! {
 	}
 
 	/// The `Metadata` api trait that returns metadata for the runtime.
+	#[api_version(2)]
 	pub trait Metadata {
 		/// Returns the metadata of a runtime.
 		fn metadata() -> OpaqueMetadata;
+
+		/// Returns the metadata in the format of the given `version`.
+		///
+		/// Returns `None` if the runtime doesn't support this version. Tooling can use
+		/// [`metadata_versions`](Self::metadata_versions) to find a common version.
+		#[api_version(2)]
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata>;
+
+		/// Returns the metadata versions supported by the runtime.
+		#[api_version(2)]
+		fn metadata_versions() -> sp_std::vec::Vec<u32>;
 	}
 }
@@ -19,10 +19,17 @@ use sp_api::{
 	RuntimeApiInfo, decl_runtime_apis, impl_runtime_apis, mock_impl_runtime_apis,
 	ApiError,
 	ApiExt,
+	CallApiAt,
+	CallApiAtParams,
+	ConstructRuntimeApi,
+	Core,
 };
-use sp_runtime::{traits::{GetNodeBlockType, Block as BlockT}, generic::BlockId};
+use sp_runtime::{traits::{GetNodeBlockType, Block as BlockT, HashFor}, generic::BlockId};
 use sp_core::NativeOrEncoded;
+use sp_version::RuntimeVersion;
 use substrate_test_runtime_client::runtime::Block;
+use codec::{Encode, Decode};
+use std::panic::UnwindSafe;
 
 /// The declaration of the `Runtime` type and the implementation of the `GetNodeBlockType`
 /// trait are done by the `construct_runtime!` macro in a real runtime.
@@ -45,6 +52,8 @@ decl_runtime_apis! {
 		fn same_name();
 		#[changed_in(2)]
 		fn same_name() -> String;
+		#[api_version(2)]
+		fn added_in_version_2();
 	}
 }
 
@@ -69,6 +78,7 @@ impl_runtime_apis! {
 
 	impl self::ApiWithCustomVersion<Block> for Runtime {
 		fn same_name() {}
+		fn added_in_version_2() {}
 	}
 
 	impl sp_api::Core<Block> for Runtime {
@@ -130,6 +140,7 @@ mock_impl_runtime_apis! {
 
 	impl ApiWithCustomVersion<Block> for MockApi {
 		fn same_name() {}
+		fn added_in_version_2() {}
 	}
 }
 
@@ -212,3 +223,43 @@ fn mock_runtime_api_works_with_advanced() {
 		mock.wild_card(&BlockId::Number(1336), 1).unwrap_err().to_string(),
 	);
 }
+
+/// Something that reports a runtime implementing version 1 of `ApiWithCustomVersion`.
+struct OldRuntime;
+
+impl CallApiAt<Block> for OldRuntime {
+	type StateBackend = sp_api::InMemoryBackend<HashFor<Block>>;
+
+	fn call_api_at<
+		'a,
+		R: Encode + Decode + PartialEq,
+		NC: FnOnce() -> Result<R, ApiError> + UnwindSafe,
+		C: Core<Block>,
+	>(
+		&self,
+		_: CallApiAtParams<'a, Block, C, NC, Self::StateBackend>,
+	) -> Result<NativeOrEncoded<R>, ApiError> {
+		panic!("The runtime should not be called")
+	}
+
+	fn runtime_version_at(&self, _: &BlockId<Block>) -> Result<RuntimeVersion, ApiError> {
+		Ok(RuntimeVersion {
+			apis: vec![(ApiWithCustomVersion::<Block>::ID, 1)].into(),
+			..Default::default()
+		})
+	}
+}
+
+#[test]
+fn calling_method_added_in_newer_api_version_fails() {
+	let runtime = OldRuntime;
+	let api = RuntimeApi::construct_runtime_api(&runtime);
+
+	match api.added_in_version_2(&BlockId::Number(0)) {
+		Err(ApiError::UnsupportedApiVersion { function, required }) => {
+			assert_eq!(function, "ApiWithCustomVersion_added_in_version_2");
+			assert_eq!(required, 2);
+		},
+		r => panic!("Expected `UnsupportedApiVersion`, got: {:?}", r),
+	}
+}
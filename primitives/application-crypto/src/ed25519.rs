@@ -56,6 +56,10 @@ impl RuntimePublic for Public {
 		sp_io::crypto::ed25519_verify(&signature, msg.as_ref(), self)
 	}
 
+	fn batch_verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool {
+		sp_io::crypto::ed25519_batch_verify(&signature, msg.as_ref(), self)
+	}
+
 	fn to_raw_vec(&self) -> Vec<u8> {
 		sp_core::crypto::Public::to_raw_vec(self)
 	}
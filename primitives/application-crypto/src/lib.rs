@@ -304,6 +304,14 @@ macro_rules! app_crypto_public_common {
 				<$public as $crate::RuntimePublic>::verify(self.as_ref(), msg, &signature.as_ref())
 			}
 
+			fn batch_verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool {
+				<$public as $crate::RuntimePublic>::batch_verify(
+					self.as_ref(),
+					msg,
+					&signature.as_ref(),
+				)
+			}
+
 			fn to_raw_vec(&self) -> $crate::Vec<u8> {
 				<$public as $crate::RuntimePublic>::to_raw_vec(&self.0)
 			}
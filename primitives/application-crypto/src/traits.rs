@@ -110,6 +110,16 @@ pub trait RuntimePublic: Sized {
 	/// Verify that the given signature matches the given message using this public key.
 	fn verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool;
 
+	/// Verify that the given signature matches the given message using this public key, as part
+	/// of a batch.
+	///
+	/// If batch verification was started with `sp_io::crypto::start_batch_verify`, the check is
+	/// deferred until `sp_io::crypto::finish_batch_verify` and this returns `true`. Otherwise, it
+	/// is the same as `verify`.
+	fn batch_verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool {
+		self.verify(msg, signature)
+	}
+
 	/// Returns `Self` as raw vec.
 	fn to_raw_vec(&self) -> Vec<u8>;
 }
@@ -145,6 +155,16 @@ pub trait RuntimeAppPublic: Sized {
 	/// Verify that the given signature matches the given message using this public key.
 	fn verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool;
 
+	/// Verify that the given signature matches the given message using this public key, as part
+	/// of a batch.
+	///
+	/// If batch verification was started with `sp_io::crypto::start_batch_verify`, the check is
+	/// deferred until `sp_io::crypto::finish_batch_verify` and this returns `true`. Otherwise, it
+	/// is the same as `verify`.
+	fn batch_verify<M: AsRef<[u8]>>(&self, msg: &M, signature: &Self::Signature) -> bool {
+		self.verify(msg, signature)
+	}
+
 	/// Returns `Self` as raw vec.
 	fn to_raw_vec(&self) -> Vec<u8>;
 }
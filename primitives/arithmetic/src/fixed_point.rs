@@ -20,7 +20,7 @@
 use sp_std::{ops::{self, Add, Sub, Mul, Div}, fmt::Debug, prelude::*, convert::{TryInto, TryFrom}};
 use codec::{Encode, Decode, CompactAs};
 use crate::{
-	helpers_128bit::multiply_by_rational, PerThing,
+	helpers_128bit::{multiply_by_rational, multiply_by_rational_with_remainder}, PerThing,
 	traits::{
 		SaturatedConversion, CheckedSub, CheckedAdd, CheckedMul, CheckedDiv, CheckedNeg,
 		Bounded, Saturating, UniqueSaturatedInto, Zero, One
@@ -45,6 +45,61 @@ impl FixedPointOperand for u16 {}
 impl FixedPointOperand for i8 {}
 impl FixedPointOperand for u8 {}
 
+/// The rounding method to use when the result of an operation can not be represented exactly.
+///
+/// Fixed point numbers can be negative, so the rounding is expressed either relative to the sign
+/// of the number (`High`/`Low`) or relative to zero (`Major`/`Minor`).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SignedRounding {
+	/// Round towards positive infinity.
+	High,
+	/// Round towards negative infinity.
+	Low,
+	/// Round away from zero.
+	Major,
+	/// Round towards zero, i.e. truncate.
+	Minor,
+	/// Round to the nearest value, half-way cases are rounded away from zero.
+	Nearest,
+}
+
+impl SignedRounding {
+	/// Whether the magnitude of a result, computed as `quotient + remainder / divisor` and
+	/// truncated to `quotient`, must be incremented.
+	fn round_up_magnitude(self, negative: bool, remainder: u128, divisor: u128) -> bool {
+		if remainder.is_zero() {
+			return false
+		}
+		match self {
+			SignedRounding::High => !negative,
+			SignedRounding::Low => negative,
+			SignedRounding::Major => true,
+			SignedRounding::Minor => false,
+			// `remainder < divisor` so this can't underflow.
+			SignedRounding::Nearest => remainder >= divisor - remainder,
+		}
+	}
+}
+
+/// Compute `a * b / c` for the magnitudes of signed values, rounding with `rounding`.
+///
+/// Returns `None` if `c` is zero or if the result does not fit in `N`.
+fn rational_mul_with_rounding<N: FixedPointOperand>(
+	a: I129,
+	b: I129,
+	c: I129,
+	rounding: SignedRounding,
+) -> Option<N> {
+	let negative = (a.negative != b.negative) != c.negative;
+	let (quotient, remainder) = multiply_by_rational_with_remainder(a.value, b.value, c.value)?;
+	let value = if rounding.round_up_magnitude(negative, remainder, c.value) {
+		quotient.checked_add(1)?
+	} else {
+		quotient
+	};
+	from_i129(I129 { value, negative })
+}
+
 /// Something that implements a decimal fixed point number.
 ///
 /// The precision is given by `Self::DIV`, i.e. `1 / DIV` can be represented.
@@ -122,6 +177,60 @@ pub trait FixedPointNumber:
 			.map(Self::from_inner)
 	}
 
+	/// Creates `self` from a rational number. Equal to `n / d`, rounded with `rounding`.
+	///
+	/// Returns `None` if `d == 0` or `n / d` exceeds accuracy.
+	fn checked_from_rational_with_rounding<N: FixedPointOperand, D: FixedPointOperand>(
+		n: N,
+		d: D,
+		rounding: SignedRounding,
+	) -> Option<Self> {
+		rational_mul_with_rounding(n.into(), Self::DIV.into(), d.into(), rounding)
+			.map(Self::from_inner)
+	}
+
+	/// Checked multiplication for integer type `N`. Equal to `self * n`, rounded with `rounding`.
+	///
+	/// Returns `None` if the result does not fit in `N`.
+	fn checked_mul_int_with_rounding<N: FixedPointOperand>(
+		self,
+		n: N,
+		rounding: SignedRounding,
+	) -> Option<N> {
+		rational_mul_with_rounding(self.into_inner().into(), n.into(), Self::DIV.into(), rounding)
+	}
+
+	/// Converts `self` into a `PerThing`, rounded with `rounding`.
+	///
+	/// Returns `None` if `self` is negative or greater than one.
+	fn checked_into_perthing<P: PerThing>(self, rounding: SignedRounding) -> Option<P> {
+		if self.is_negative() || self > Self::one() {
+			return None
+		}
+		let accuracy: u128 = P::ACCURACY.into();
+		let parts: u128 = rational_mul_with_rounding(
+			self.into_inner().into(),
+			accuracy.into(),
+			Self::DIV.into(),
+			rounding,
+		)?;
+		parts.try_into().ok().map(P::from_parts)
+	}
+
+	/// Converts `self` into a `PerThing`, rounded with `rounding`.
+	///
+	/// Returns `P::zero()` if `self` is negative and `P::one()` if `self` is greater than one.
+	fn saturating_into_perthing<P: PerThing>(self, rounding: SignedRounding) -> P {
+		if self.is_negative() {
+			P::zero()
+		} else if self >= Self::one() {
+			P::one()
+		} else {
+			self.checked_into_perthing(rounding)
+				.expect("`self` is within `[0, 1]`, so the conversion can not fail; qed")
+		}
+	}
+
 	/// Checked multiplication for integer type `N`. Equal to `self * n`.
 	///
 	/// Returns `None` if the result does not fit in `N`.
@@ -1560,6 +1669,118 @@ macro_rules! implement_fixed {
 				assert_eq!(ten_percent_perquintill.into_inner(), $name::accuracy() / 10);
 			}
 
+			#[test]
+			fn checked_from_rational_with_rounding_works() {
+				let accuracy = $name::accuracy();
+				let from_rational = |n: i32, d: i32, rounding| {
+					$name::checked_from_rational_with_rounding(n, d, rounding)
+						.map(|x| x.into_inner())
+				};
+
+				// 1 / 3 = 0.333...
+				assert_eq!(from_rational(1, 3, SignedRounding::Minor), Some(accuracy / 3));
+				assert_eq!(from_rational(1, 3, SignedRounding::Low), Some(accuracy / 3));
+				assert_eq!(from_rational(1, 3, SignedRounding::Nearest), Some(accuracy / 3));
+				assert_eq!(from_rational(1, 3, SignedRounding::Major), Some(accuracy / 3 + 1));
+				assert_eq!(from_rational(1, 3, SignedRounding::High), Some(accuracy / 3 + 1));
+
+				// 2 / 3 = 0.666...
+				assert_eq!(from_rational(2, 3, SignedRounding::Minor), Some(2 * accuracy / 3));
+				assert_eq!(
+					from_rational(2, 3, SignedRounding::Nearest),
+					Some(2 * accuracy / 3 + 1),
+				);
+
+				// Exact results are not rounded.
+				assert_eq!(from_rational(1, 2, SignedRounding::Major), Some(accuracy / 2));
+				assert_eq!(from_rational(1, 2, SignedRounding::Minor), Some(accuracy / 2));
+
+				assert_eq!(from_rational(1, 0, SignedRounding::Minor), None);
+
+				if $name::SIGNED {
+					// -1 / 3 = -0.333...
+					let third = accuracy / 3;
+					assert_eq!(
+						from_rational(1, -3, SignedRounding::Minor),
+						Some(0.saturating_sub(third)),
+					);
+					assert_eq!(
+						from_rational(-1, 3, SignedRounding::High),
+						Some(0.saturating_sub(third)),
+					);
+					assert_eq!(
+						from_rational(-1, 3, SignedRounding::Low),
+						Some(0.saturating_sub(third + 1)),
+					);
+					assert_eq!(
+						from_rational(1, -3, SignedRounding::Major),
+						Some(0.saturating_sub(third + 1)),
+					);
+					assert_eq!(
+						from_rational(-2, 3, SignedRounding::Nearest),
+						Some(0.saturating_sub(2 * third + 1)),
+					);
+				} else {
+					assert_eq!(from_rational(-1, 3, SignedRounding::Minor), None);
+				}
+			}
+
+			#[test]
+			fn checked_mul_int_with_rounding_works() {
+				use SignedRounding::*;
+				let third = $name::saturating_from_rational(1, 3);
+
+				// 10 / 3 = 3.333...
+				assert_eq!(third.checked_mul_int_with_rounding(10i32, Minor), Some(3));
+				assert_eq!(third.checked_mul_int_with_rounding(10i32, Nearest), Some(3));
+				assert_eq!(third.checked_mul_int_with_rounding(10i32, Major), Some(4));
+				assert_eq!(third.checked_mul_int_with_rounding(10i32, High), Some(4));
+				assert_eq!(third.checked_mul_int_with_rounding(-10i32, High), Some(-3));
+				assert_eq!(third.checked_mul_int_with_rounding(-10i32, Low), Some(-4));
+
+				// 2 / 3 = 0.666...
+				assert_eq!(third.checked_mul_int_with_rounding(2u8, Nearest), Some(1));
+				assert_eq!(third.checked_mul_int_with_rounding(2u8, Minor), Some(0));
+
+				// `u128::max_value() / 2` overflows the intermediate product.
+				let half = $name::saturating_from_rational(1, 2);
+				let max = u128::max_value();
+				assert_eq!(half.checked_mul_int_with_rounding(max, Minor), Some(max / 2));
+				assert_eq!(half.checked_mul_int_with_rounding(max, Nearest), Some(max / 2 + 1));
+				let two = $name::saturating_from_integer(2);
+				assert_eq!(two.checked_mul_int_with_rounding(max, Minor), None);
+			}
+
+			#[test]
+			fn into_perthing_works() {
+				use SignedRounding::*;
+				let third = $name::saturating_from_rational(1, 3);
+				let two_thirds = $name::saturating_from_rational(2, 3);
+				let half = $name::saturating_from_rational(1, 2);
+				let two = $name::saturating_from_integer(2);
+				let minus_one = $name::saturating_from_integer(-1);
+
+				assert_eq!(third.checked_into_perthing(Minor), Some(Percent::from_percent(33)));
+				assert_eq!(third.checked_into_perthing(Major), Some(Percent::from_percent(34)));
+				assert_eq!(
+					two_thirds.checked_into_perthing(Nearest),
+					Some(Percent::from_percent(67)),
+				);
+				assert_eq!(half.checked_into_perthing(Major), Some(Perquintill::from_percent(50)));
+				assert_eq!($name::one().checked_into_perthing(Minor), Some(Permill::one()));
+				assert_eq!(two.checked_into_perthing::<Permill>(Minor), None);
+
+				assert_eq!(two.saturating_into_perthing::<Permill>(Minor), Permill::one());
+				assert_eq!(
+					minus_one.saturating_into_perthing::<Perquintill>(Minor),
+					Perquintill::zero(),
+				);
+				assert_eq!(
+					two_thirds.saturating_into_perthing::<Perbill>(Minor),
+					Perbill::from_parts(666_666_666),
+				);
+			}
+
 			#[test]
 			fn fmt_should_work() {
 				let zero = $name::zero();
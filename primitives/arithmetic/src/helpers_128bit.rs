@@ -111,3 +111,37 @@ pub fn multiply_by_rational(mut a: u128, mut b: u128, mut c: u128) -> Result<u12
 		q.try_into().map_err(|_| "result cannot fit in u128")
 	}
 }
+
+/// Compute `a * b / c` rounded down, along with the remainder `(a * b) % c`.
+///
+/// Unlike [`multiply_by_rational`], the quotient is never rounded up, so the remainder can be
+/// used by the caller to apply its own rounding.
+///
+/// Returns `None` if `c` is zero or if the quotient cannot fit in `u128`.
+pub fn multiply_by_rational_with_remainder(a: u128, b: u128, c: u128) -> Option<(u128, u128)> {
+	if c.is_zero() {
+		return None
+	}
+
+	if let Some(x) = a.checked_mul(b) {
+		return Some((x / c, x % c))
+	}
+
+	let mut ab = to_big_uint(a) * to_big_uint(b);
+	ab.lstrip();
+	let c_num = to_big_uint(c);
+	let (mut q, mut r) = if c_num.len() == 1 {
+		// PROOF: if `c_num.len() == 1` then `c` fits in one limb.
+		let mut q = ab.clone().div_unit(c as biguint::Single);
+		q.lstrip();
+		let r = ab.sub(&(q.clone() * c_num)).ok()?;
+		(q, r)
+	} else {
+		// PROOF: `a.checked_mul(b)` has failed, hence `ab` is at least one limb bigger than `c`.
+		ab.div(&c_num, true)?
+	};
+	q.lstrip();
+	r.lstrip();
+	let r: u128 = r.try_into().ok()?;
+	Some((q.try_into().ok()?, r))
+}
@@ -40,7 +40,9 @@ pub mod per_things;
 pub mod fixed_point;
 pub mod rational;
 
-pub use fixed_point::{FixedPointNumber, FixedPointOperand, FixedI64, FixedI128, FixedU128};
+pub use fixed_point::{
+	FixedPointNumber, FixedPointOperand, FixedI64, FixedI128, FixedU128, SignedRounding,
+};
 pub use per_things::{PerThing, InnerOf, UpperOf, Percent, PerU16, Permill, Perbill, Perquintill};
 pub use rational::{Rational128, RationalInfinite};
 
@@ -57,36 +57,40 @@ impl<H: Decode> UnclesInherentData<H> for InherentData {
 
 /// Provider for inherent data.
 #[cfg(feature = "std")]
-pub struct InherentDataProvider<F, H> {
-	inner: F,
-	_marker: std::marker::PhantomData<H>,
+pub struct InherentDataProvider<H> {
+	uncles: Vec<H>,
 }
 
 #[cfg(feature = "std")]
-impl<F, H> InherentDataProvider<F, H> {
-	pub fn new(uncles_oracle: F) -> Self {
-		InherentDataProvider { inner: uncles_oracle, _marker: Default::default() }
+impl<H> InherentDataProvider<H> {
+	/// Create a new inherent data provider with the given `uncles`.
+	pub fn new(uncles: Vec<H>) -> Self {
+		InherentDataProvider { uncles }
 	}
 }
 
 #[cfg(feature = "std")]
-impl<F, H: Encode + std::fmt::Debug> sp_inherents::ProvideInherentData for InherentDataProvider<F, H>
-where F: Fn() -> Vec<H>
-{
-	fn inherent_identifier(&self) -> &'static InherentIdentifier {
-		&INHERENT_IDENTIFIER
-	}
-
+#[async_trait::async_trait]
+impl<H: Encode + Send + Sync> sp_inherents::InherentDataProvider for InherentDataProvider<H> {
 	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
-		let uncles = (self.inner)();
-		if !uncles.is_empty() {
-			inherent_data.put_data(INHERENT_IDENTIFIER, &uncles)
+		if !self.uncles.is_empty() {
+			inherent_data.put_data(INHERENT_IDENTIFIER, &self.uncles)
 		} else {
 			Ok(())
 		}
 	}
 
-	fn error_to_string(&self, _error: &[u8]) -> Option<String> {
-		Some(format!("no further information"))
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), Error>> {
+		if *identifier != INHERENT_IDENTIFIER {
+			return None
+		}
+
+		let error = InherentError::decode(&mut &error[..]).ok()?;
+
+		Some(Err(format!("Failed to check uncles inherent: {:?}", error).into()))
 	}
 }
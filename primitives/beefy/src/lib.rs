@@ -0,0 +1,158 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Primitives for BEEFY protocol.
+//!
+//! BEEFY (Bridge Efficiency Enabling Finality Yielder) is a gadget that runs alongside another
+//! finality gadget (e.g. GRANDPA) and produces finality proofs that are cheap to verify for light
+//! clients on other chains, e.g. in Ethereum smart contracts.
+//!
+//! The BEEFY validator set tracks the GRANDPA validator set, but uses different keys: BEEFY uses
+//! `secp256k1` (ECDSA) signatures, which can be verified efficiently on Ethereum, while GRANDPA
+//! uses `ed25519`.
+//!
+//! Validators sign [`Commitment`]s on blocks that have been finalized by GRANDPA. The payload of
+//! a commitment is the root of the Merkle Mountain Range of all blocks at that point, which is
+//! signalled in the block digest with [`ConsensusLog::MmrRoot`]. A commitment signed by more
+//! than 2/3 of the validator set is a [`SignedCommitment`] and proves the finality of the block
+//! as well as, through the MMR, the ancestry of all blocks before it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+mod commitment;
+
+pub use commitment::{Commitment, SignedCommitment};
+
+use codec::{Codec, Decode, Encode};
+use sp_core::H256;
+use sp_std::prelude::*;
+
+/// Key type for BEEFY module.
+pub const KEY_TYPE: sp_application_crypto::KeyTypeId = sp_application_crypto::key_types::BEEFY;
+
+/// BEEFY cryptographic types.
+///
+/// This module basically introduces three crypto types:
+/// - `crypto::Pair`
+/// - `crypto::Public`
+/// - `crypto::Signature`
+///
+/// Your code should use the above types as concrete types for all crypto related functionality.
+///
+/// The current underlying crypto scheme used is ECDSA. This can be changed, without affecting
+/// code restricted against the above listed crypto types.
+pub mod crypto {
+	use sp_application_crypto::{app_crypto, ecdsa};
+	app_crypto!(ecdsa, crate::KEY_TYPE);
+
+	/// Identity of a BEEFY authority using ECDSA as its crypto.
+	pub type AuthorityId = Public;
+
+	/// Signature for a BEEFY authority using ECDSA as its crypto.
+	pub type AuthoritySignature = Signature;
+}
+
+/// The `ConsensusEngineId` of BEEFY.
+pub const BEEFY_ENGINE_ID: sp_runtime::ConsensusEngineId = *b"BEEF";
+
+/// Authority set id starts with zero at genesis.
+pub const GENESIS_AUTHORITY_SET_ID: u64 = 0;
+
+/// A typedef for validator set id.
+pub type ValidatorSetId = u64;
+
+/// The index of an authority.
+pub type AuthorityIndex = u32;
+
+/// The type used to represent an MMR root hash.
+pub type MmrRootHash = H256;
+
+/// A set of BEEFY authorities, a.k.a. validators.
+#[derive(Decode, Encode, Debug, PartialEq, Clone)]
+pub struct ValidatorSet<AuthorityId> {
+	/// Public keys of the validator set elements.
+	pub validators: Vec<AuthorityId>,
+	/// Identifier of the validator set.
+	pub id: ValidatorSetId,
+}
+
+impl<AuthorityId> ValidatorSet<AuthorityId> {
+	/// Return an empty validator set with id of 0.
+	pub fn empty() -> Self {
+		Self { validators: Default::default(), id: Default::default() }
+	}
+
+	/// Minimum number of signatures required for a commitment to be considered final.
+	///
+	/// This is more than 2/3 of the validator set.
+	pub fn threshold(&self) -> usize {
+		let faulty = self.validators.len().saturating_sub(1) / 3;
+		self.validators.len() - faulty
+	}
+}
+
+/// A consensus log item for BEEFY.
+#[derive(Decode, Encode)]
+pub enum ConsensusLog<AuthorityId: Codec> {
+	/// The authorities have changed.
+	#[codec(index = 1)]
+	AuthoritiesChange(ValidatorSet<AuthorityId>),
+	/// Disable the authority with given index.
+	#[codec(index = 2)]
+	OnDisabled(AuthorityIndex),
+	/// MMR root hash.
+	#[codec(index = 3)]
+	MmrRoot(MmrRootHash),
+}
+
+/// BEEFY vote message.
+///
+/// A vote message is a direct vote created by a BEEFY node on every voting round
+/// and is gossiped to its peers.
+#[derive(Debug, Decode, Encode)]
+pub struct VoteMessage<Hash, Number, Id, Signature> {
+	/// Commit to information extracted from a finalized block
+	pub commitment: Commitment<Number, Hash>,
+	/// Node authority id
+	pub id: Id,
+	/// Node signature
+	pub signature: Signature,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API necessary for BEEFY voters.
+	pub trait BeefyApi {
+		/// Return the current active BEEFY validator set
+		fn validator_set() -> ValidatorSet<crypto::AuthorityId>;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn threshold_is_more_than_two_thirds() {
+		let set = |n: usize| ValidatorSet { validators: vec![0u8; n], id: 0 }.threshold();
+		assert_eq!(set(1), 1);
+		assert_eq!(set(3), 3);
+		assert_eq!(set(4), 3);
+		assert_eq!(set(7), 5);
+		assert_eq!(set(10), 7);
+	}
+}
@@ -226,6 +226,18 @@ pub trait Backend<Block: BlockT>: HeaderBackend<Block> + HeaderMetadata<Block, E
 	fn have_extrinsic(&self, hash: &Block::Hash) -> Result<bool> {
 		Ok(self.extrinsic(hash)?.is_some())
 	}
+
+	/// Get indexed transaction data by content hash. Returns `None` if the data is not
+	/// indexed or the backend does not support transaction indexing.
+	fn indexed_transaction(&self, _hash: &Block::Hash) -> Result<Option<Vec<u8>>> {
+		Ok(None)
+	}
+
+	/// Get all data indexed by the given block, in the order it was indexed. Returns `None`
+	/// if the block body is not stored or the backend does not support transaction indexing.
+	fn block_indexed_body(&self, _id: BlockId<Block>) -> Result<Option<Vec<Vec<u8>>>> {
+		Ok(None)
+	}
 }
 
 /// Provides access to the optional cache.
@@ -21,7 +21,8 @@
 
 use codec::{Encode, Decode, Codec};
 use sp_std::vec::Vec;
-use sp_runtime::ConsensusEngineId;
+use sp_runtime::{ConsensusEngineId, traits::Header};
+use sp_application_crypto::RuntimeAppPublic;
 
 pub mod digests;
 pub mod inherents;
@@ -81,17 +82,86 @@ pub enum ConsensusLog<AuthorityId: Codec> {
 	OnDisabled(AuthorityIndex),
 }
 
+/// An equivocation proof for multiple block authorships on the same slot (i.e. double vote).
+pub type EquivocationProof<Header, AuthorityId> =
+	sp_consensus_slots::EquivocationProof<Header, AuthorityId>;
+
+/// Verifies the equivocation proof by making sure that: both headers have
+/// different hashes, are targetting the same slot, and have valid signatures by
+/// the same authority.
+pub fn check_equivocation_proof<H, P>(proof: EquivocationProof<H, P>) -> bool
+where
+	H: Header,
+	P: RuntimeAppPublic,
+	P::Signature: Codec,
+{
+	use digests::CompatibleDigestItem;
+
+	let find_pre_digest = |header: &H| {
+		header
+			.digest()
+			.logs()
+			.iter()
+			.find_map(|log| CompatibleDigestItem::<P::Signature>::as_aura_pre_digest(log))
+	};
+
+	let verify_seal_signature = |mut header: H, offender: &P| {
+		let seal: P::Signature = header.digest_mut().pop()?.as_aura_seal()?;
+		let pre_hash = header.hash();
+
+		if !offender.verify(&pre_hash.as_ref(), &seal) {
+			return None;
+		}
+
+		Some(())
+	};
+
+	let verify_proof = || {
+		// we must have different headers for the equivocation to be valid
+		if proof.first_header.hash() == proof.second_header.hash() {
+			return None;
+		}
+
+		let first_slot = find_pre_digest(&proof.first_header)?;
+		let second_slot = find_pre_digest(&proof.second_header)?;
+
+		// both headers must be targetting the same slot and it must
+		// be the same as the one in the proof.
+		if proof.slot != first_slot || first_slot != second_slot {
+			return None;
+		}
+
+		// we finally verify that the expected authority has signed both headers and
+		// that the signature is valid.
+		verify_seal_signature(proof.first_header, &proof.offender)?;
+		verify_seal_signature(proof.second_header, &proof.offender)?;
+
+		Some(())
+	};
+
+	// NOTE: we isolate the verification code into an helper function that
+	// returns `Option<()>` so that we can use `?` to deal with any intermediate
+	// errors and discard the proof as invalid.
+	verify_proof().is_some()
+}
+
 sp_api::decl_runtime_apis! {
 	/// API necessary for block authorship with aura.
+	#[api_version(2)]
 	pub trait AuraApi<AuthorityId: Codec> {
-		/// Return the slot duration in seconds for Aura.
+		/// Return the slot duration in milliseconds for Aura.
 		/// Currently, only the value provided by this type at genesis
 		/// will be used.
 		///
 		/// Dynamic slot duration may be supported in the future.
 		fn slot_duration() -> u64;
 
-		// Return the current set of authorities.
+		/// Return the current set of authorities.
 		fn authorities() -> Vec<AuthorityId>;
+
+		/// Return the slot recorded on-chain for the best block, i.e. the slot of the pre-runtime
+		/// digest of the block on which this is called.
+		#[api_version(2)]
+		fn current_slot() -> Slot;
 	}
 }
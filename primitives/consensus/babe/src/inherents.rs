@@ -18,13 +18,6 @@
 //! Inherents for BABE
 
 use sp_inherents::{Error, InherentData, InherentIdentifier};
-#[cfg(feature = "std")]
-use sp_inherents::{InherentDataProviders, ProvideInherentData};
-#[cfg(feature = "std")]
-use sp_timestamp::TimestampInherentData;
-
-#[cfg(feature = "std")]
-use codec::Decode;
 use sp_std::result::Result;
 
 /// The BABE inherent identifier.
@@ -51,43 +44,62 @@ impl BabeInherentData for InherentData {
 	}
 }
 
-/// Provides the slot duration inherent data for BABE.
-// TODO: Remove in the future. https://github.com/paritytech/substrate/issues/8029
+/// Provides the slot inherent data for `BABE`.
 #[cfg(feature = "std")]
 pub struct InherentDataProvider {
-	slot_duration: u64,
+	slot: InherentType,
 }
 
 #[cfg(feature = "std")]
 impl InherentDataProvider {
-	/// Constructs `Self`
-	pub fn new(slot_duration: u64) -> Self {
-		Self { slot_duration }
+	/// Create a new instance with the given slot.
+	pub fn new(slot: InherentType) -> Self {
+		Self {
+			slot,
+		}
 	}
-}
 
-#[cfg(feature = "std")]
-impl ProvideInherentData for InherentDataProvider {
-	fn on_register(&self, providers: &InherentDataProviders) -> Result<(), Error> {
-		if !providers.has_provider(&sp_timestamp::INHERENT_IDENTIFIER) {
-			// Add the timestamp inherent data provider, as we require it.
-			providers.register_provider(sp_timestamp::InherentDataProvider)
-		} else {
-			Ok(())
+	/// Creates the inherent data provider by calculating the slot from the given
+	/// `timestamp` and `duration`.
+	pub fn from_timestamp_and_duration(
+		timestamp: sp_timestamp::Timestamp,
+		duration: std::time::Duration,
+	) -> Self {
+		let slot = InherentType::from(
+			(timestamp.as_duration().as_millis() / duration.as_millis()) as u64
+		);
+
+		Self {
+			slot,
 		}
 	}
+}
+
+#[cfg(feature = "std")]
+impl sp_std::ops::Deref for InherentDataProvider {
+	type Target = InherentType;
 
-	fn inherent_identifier(&self) -> &'static InherentIdentifier {
-		&INHERENT_IDENTIFIER
+	fn deref(&self) -> &Self::Target {
+		&self.slot
 	}
+}
 
-	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
-		let timestamp = inherent_data.timestamp_inherent_data()?;
-		let slot = *timestamp / self.slot_duration;
-		inherent_data.put_data(INHERENT_IDENTIFIER, &slot)
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), Error> {
+		inherent_data.put_data(INHERENT_IDENTIFIER, &self.slot)
 	}
 
-	fn error_to_string(&self, error: &[u8]) -> Option<String> {
-		Error::decode(&mut &error[..]).map(|e| e.into_string()).ok()
+	async fn try_handle_error(
+		&self,
+		_: &InherentIdentifier,
+		_: &[u8],
+	) -> Option<Result<(), Error>> {
+		// There is no error anymore
+		None
 	}
 }
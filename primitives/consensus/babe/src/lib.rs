@@ -372,7 +372,7 @@ pub struct Epoch {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for block authorship with BABE.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait BabeApi {
 		/// Return the genesis configuration for BABE. The configuration is only read on genesis.
 		fn configuration() -> BabeGenesisConfiguration;
@@ -419,5 +419,9 @@ sp_api::decl_runtime_apis! {
 			equivocation_proof: EquivocationProof<Block::Header>,
 			key_owner_proof: OpaqueKeyOwnershipProof,
 		) -> Option<()>;
+
+		/// Returns the indices of the authorities that are disabled in the current session.
+		/// Blocks authored by them are rejected by the runtime. New in version 3.
+		fn disabled_validators() -> Vec<AuthorityIndex>;
 	}
 }
@@ -111,6 +111,26 @@ pub struct BlockCheckParams<Block: BlockT> {
 	pub import_existing: bool,
 }
 
+/// State of a block downloaded by state sync, to be imported together with the block.
+#[derive(PartialEq, Eq, Clone)]
+pub struct ImportedState<B: BlockT> {
+	/// Target block hash.
+	pub block: B::Hash,
+	/// Keys and values of the top trie and of the child tries. The roots of the child tries are
+	/// not part of the top trie, they are calculated on import.
+	pub state: sp_core::storage::Storage,
+}
+
+impl<B: BlockT> std::fmt::Debug for ImportedState<B> {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.debug_struct("ImportedState")
+			.field("block", &self.block)
+			.field("keys", &self.state.top.len())
+			.field("child_tries", &self.state.children_default.len())
+			.finish()
+	}
+}
+
 /// Data required to import a Block.
 #[non_exhaustive]
 pub struct BlockImportParams<Block: BlockT, Transaction> {
@@ -162,6 +182,9 @@ pub struct BlockImportParams<Block: BlockT, Transaction> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// State downloaded by state sync. If this is `Some(_)`, the block is imported without
+	/// execution and the given state is used as the state of the block.
+	pub imported_state: Option<ImportedState<Block>>,
 	/// Cached full header hash (with post-digests applied).
 	pub post_hash: Option<Block::Hash>,
 }
@@ -184,6 +207,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			fork_choice: None,
 			allow_missing_state: false,
 			import_existing: false,
+			imported_state: None,
 			post_hash: None,
 		}
 	}
@@ -229,6 +253,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			allow_missing_state: self.allow_missing_state,
 			fork_choice: self.fork_choice,
 			import_existing: self.import_existing,
+			imported_state: self.imported_state,
 			post_hash: self.post_hash,
 		}
 	}
@@ -108,6 +108,18 @@ impl Public {
 			.map(Self)
 			.map_err(|_| ())
 	}
+
+	/// Return the full, uncompressed public key, without the `0x04` prefix.
+	#[cfg(feature = "std")]
+	pub fn to_full(&self) -> Result<[u8; 64], ()> {
+		secp256k1::PublicKey::parse_slice(&self.0, Some(secp256k1::PublicKeyFormat::Compressed))
+			.map(|k| {
+				let mut full = [0u8; 64];
+				full.copy_from_slice(&k.serialize()[1..]);
+				full
+			})
+			.map_err(|_| ())
+	}
 }
 
 impl TraitPublic for Public {
@@ -531,6 +543,15 @@ impl Pair {
 			Self::from_seed(&padded_seed)
 		})
 	}
+
+	/// Sign a pre-hashed message.
+	///
+	/// Useful when the message has to be hashed with something other than blake2-256, e.g. the
+	/// keccak-256 used by Ethereum.
+	pub fn sign_prehashed(&self, message: &[u8; 32]) -> Signature {
+		let message = secp256k1::Message::parse(message);
+		secp256k1::sign(&message, &self.secret).into()
+	}
 }
 
 impl CryptoType for Public {
@@ -102,7 +102,7 @@
 //!         fn desired_targets() -> u32 {
 //!             1
 //!         }
-//!         fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+//!         fn voters(_: Option<usize>) -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
 //!             Default::default()
 //!         }
 //!         fn targets() -> Vec<AccountId> {
@@ -173,7 +173,9 @@ pub trait ElectionDataProvider<AccountId, BlockNumber> {
 	/// All possible voters for the election.
 	///
 	/// Note that if a notion of self-vote exists, it should be represented here.
-	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
+	///
+	/// If `maybe_max_len` is `Some(v)` then the resulting vector MUST NOT be longer than `v` items.
+	fn voters(maybe_max_len: Option<usize>) -> Vec<(AccountId, VoteWeight, Vec<AccountId>)>;
 
 	/// The number of targets to elect.
 	fn desired_targets() -> u32;
@@ -201,7 +203,7 @@ impl<AccountId, BlockNumber> ElectionDataProvider<AccountId, BlockNumber> for ()
 	fn targets() -> Vec<AccountId> {
 		Default::default()
 	}
-	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+	fn voters(_: Option<usize>) -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
 		Default::default()
 	}
 	fn desired_targets() -> u32 {
@@ -239,3 +241,63 @@ impl<AccountId, BlockNumber> ElectionProvider<AccountId, BlockNumber> for () {
 		Err("<() as ElectionProvider> cannot do anything.")
 	}
 }
+
+/// A utility trait for something to implement `ElectionDataProvider` in a sensible way.
+///
+/// This is generic over `AccountId` and it can represent a validator, a nominator, or any other
+/// entity.
+///
+/// To simplify the trait, the `VoteWeight` is hardcoded as the weight of each entity. The weights
+/// are ascending, the higher, the better. In the long term, if this trait ends up having use cases
+/// outside of the election context, it is easy enough to make it generic over the `VoteWeight`.
+///
+/// Something that implements this trait will do a best-effort sort over ids, and thus can be
+/// used on the implementing side of [`ElectionDataProvider`].
+pub trait SortedListProvider<AccountId> {
+	/// The list's error type.
+	type Error: Debug;
+
+	/// An iterator over the list, which can have `take` called on it.
+	fn iter() -> Box<dyn Iterator<Item = AccountId>>;
+
+	/// The current count of ids in the list.
+	fn count() -> u32;
+
+	/// Return true if the list already contains `id`.
+	fn contains(id: &AccountId) -> bool;
+
+	/// Hook for inserting a new id.
+	fn on_insert(id: AccountId, weight: VoteWeight) -> Result<(), Self::Error>;
+
+	/// Hook for updating a single id.
+	fn on_update(id: &AccountId, weight: VoteWeight);
+
+	/// Hook for removing an id from the list.
+	fn on_remove(id: &AccountId);
+
+	/// Regenerate this list from scratch. Returns the count of items inserted.
+	///
+	/// This should typically only be used at a runtime upgrade.
+	fn regenerate(
+		all: impl IntoIterator<Item = AccountId>,
+		weight_of: Box<dyn Fn(&AccountId) -> VoteWeight>,
+	) -> u32;
+
+	/// Remove everything from the list.
+	fn clear();
+
+	/// Sanity check internal state of list. Only meant for debug compilation.
+	fn sanity_check() -> Result<(), &'static str>;
+}
+
+/// Something that can provide the `VoteWeight` of an account. Similar to [`ElectionProvider`] and
+/// [`ElectionDataProvider`], this should typically be implementing by whoever is supposed to *use*
+/// `SortedListProvider`.
+pub trait VoteWeightProvider<AccountId> {
+	/// Get the current `VoteWeight` of `who`.
+	fn vote_weight(who: &AccountId) -> VoteWeight;
+
+	/// Set the `VoteWeight` of `who`. Only used to set up benchmarks.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_vote_weight_of(who: &AccountId, weight: VoteWeight);
+}
@@ -62,7 +62,7 @@ impl<T: Config> ElectionProvider<T::AccountId, T::BlockNumber> for OnChainSequen
 	type DataProvider = T::DataProvider;
 
 	fn elect() -> Result<Supports<T::AccountId>, Self::Error> {
-		let voters = Self::DataProvider::voters();
+		let voters = Self::DataProvider::voters(None);
 		let targets = Self::DataProvider::targets();
 		let desired_targets = Self::DataProvider::desired_targets() as usize;
 
@@ -112,7 +112,7 @@ mod tests {
 		pub struct DataProvider;
 
 		impl ElectionDataProvider<AccountId, BlockNumber> for DataProvider {
-			fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+			fn voters(_: Option<usize>) -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
 				vec![
 					(1, 10, vec![10, 20]),
 					(2, 20, vec![30, 20]),
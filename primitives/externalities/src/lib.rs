@@ -228,6 +228,21 @@ pub trait Externalities: ExtensionStore {
 	/// no transaction is open that can be closed.
 	fn storage_commit_transaction(&mut self) -> Result<(), ()>;
 
+	/// Index the last `size` bytes of the extrinsic with the given `index` in the current block
+	/// under `hash`, so the client can store them and serve them by content hash.
+	///
+	/// Unlike storage changes, indexing operations are not reverted with storage transactions.
+	///
+	/// The default implementation does nothing, for externalities that are not used to build
+	/// blocks and therefore have no indexing to record.
+	fn storage_index_transaction(&mut self, _index: u32, _hash: &[u8], _size: u32) {}
+
+	/// Renew the indexed transaction data with the given `hash`, previously indexed in an
+	/// earlier block, from the extrinsic with the given `index` in the current block.
+	///
+	/// The default implementation does nothing, see `storage_index_transaction`.
+	fn storage_renew_transaction_index(&mut self, _index: u32, _hash: &[u8]) {}
+
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
 	/// Benchmarking related functionality and shouldn't be used anywhere else!
 	/// !!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!!
@@ -22,8 +22,10 @@
 //! is stored in the `InherentData`. This `InherentData` is constructed by the node and given to
 //! the runtime.
 //!
-//! Types that provide data for inherents, should implement `InherentDataProvider` and need to be
-//! registered at `InherentDataProviders`.
+//! Types that provide data for inherents, should implement [`InherentDataProvider`]. They are
+//! created for every block that is built or imported through [`CreateInherentDataProviders`],
+//! which gets the parent block hash and can thus provide data that depends on the chain state.
+//! Multiple providers are combined by putting them into a tuple.
 //!
 //! In the runtime, modules need to implement `ProvideInherent` when they can create and/or check
 //! inherents. By implementing `ProvideInherent`, a module is not enforced to create an inherent.
@@ -39,10 +41,10 @@ use codec::{Encode, Decode};
 use sp_std::{collections::btree_map::{BTreeMap, IntoIter, Entry}, vec::Vec};
 
 #[cfg(feature = "std")]
-use parking_lot::RwLock;
+mod client_side;
 
 #[cfg(feature = "std")]
-use std::{sync::Arc, format};
+pub use client_side::*;
 
 /// An error that can occur within the inherent data system.
 #[cfg(feature = "std")]
@@ -257,118 +259,6 @@ impl PartialEq for CheckInherentsResult {
 	}
 }
 
-/// All `InherentData` providers.
-#[cfg(feature = "std")]
-#[derive(Clone, Default)]
-pub struct InherentDataProviders {
-	providers: Arc<RwLock<Vec<Box<dyn ProvideInherentData + Send + Sync>>>>,
-}
-
-#[cfg(feature = "std")]
-impl InherentDataProviders {
-	/// Create a new instance.
-	pub fn new() -> Self {
-		Self::default()
-	}
-
-	/// Register an `InherentData` provider.
-	///
-	/// The registration order is preserved and this order will also be used when creating the
-	/// inherent data.
-	///
-	/// # Result
-	///
-	/// Will return an error, if a provider with the same identifier already exists.
-	pub fn register_provider<P: ProvideInherentData + Send + Sync +'static>(
-		&self,
-		provider: P,
-	) -> Result<(), Error> {
-		if self.has_provider(&provider.inherent_identifier()) {
-			Err(
-				format!(
-					"Inherent data provider with identifier {:?} already exists!",
-					&provider.inherent_identifier()
-				).into()
-			)
-		} else {
-			provider.on_register(self)?;
-			self.providers.write().push(Box::new(provider));
-			Ok(())
-		}
-	}
-
-	/// Returns if a provider for the given identifier exists.
-	pub fn has_provider(&self, identifier: &InherentIdentifier) -> bool {
-		self.providers.read().iter().any(|p| p.inherent_identifier() == identifier)
-	}
-
-	/// Create inherent data.
-	pub fn create_inherent_data(&self) -> Result<InherentData, Error> {
-		let mut data = InherentData::new();
-		self.providers.read().iter().try_for_each(|p| {
-			p.provide_inherent_data(&mut data)
-				.map_err(|e| format!("Error for `{:?}`: {:?}", p.inherent_identifier(), e))
-		})?;
-		Ok(data)
-	}
-
-	/// Converts a given encoded error into a `String`.
-	///
-	/// Useful if the implementation encounters an error for an identifier it does not know.
-	pub fn error_to_string(&self, identifier: &InherentIdentifier, error: &[u8]) -> String {
-		let res = self.providers.read().iter().filter_map(|p|
-			if p.inherent_identifier() == identifier {
-				Some(
-					p.error_to_string(error)
-						.unwrap_or_else(|| error_to_string_fallback(identifier))
-				)
-			} else {
-				None
-			}
-		).next();
-
-		match res {
-			Some(res) => res,
-			None => format!(
-				"Error while checking inherent of type \"{}\", but this inherent type is unknown.",
-				String::from_utf8_lossy(identifier)
-			)
-		}
-	}
-}
-
-/// Something that provides inherent data.
-#[cfg(feature = "std")]
-pub trait ProvideInherentData {
-	/// Is called when this inherent data provider is registered at the given
-	/// `InherentDataProviders`.
-	fn on_register(&self, _: &InherentDataProviders) -> Result<(), Error> {
-		Ok(())
-	}
-
-	/// The identifier of the inherent for that data will be provided.
-	fn inherent_identifier(&self) -> &'static InherentIdentifier;
-
-	/// Provide inherent data that should be included in a block.
-	///
-	/// The data should be stored in the given `InherentData` structure.
-	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error>;
-
-	/// Convert the given encoded error to a string.
-	///
-	/// If the given error could not be decoded, `None` should be returned.
-	fn error_to_string(&self, error: &[u8]) -> Option<String>;
-}
-
-/// A fallback function, if the decoding of an error fails.
-#[cfg(feature = "std")]
-fn error_to_string_fallback(identifier: &InherentIdentifier) -> String {
-	format!(
-		"Error while checking inherent of type \"{}\", but error could not be decoded.",
-		String::from_utf8_lossy(identifier)
-	)
-}
-
 /// Did we encounter a fatal error while checking an inherent?
 ///
 /// A fatal error is everything that fails while checking an inherent error, e.g. the inherent
@@ -478,97 +368,6 @@ mod tests {
 		assert!(data.put_data(TEST_INHERENT_0, &10).is_err());
 	}
 
-	#[derive(Clone)]
-	struct TestInherentDataProvider {
-		registered: Arc<RwLock<bool>>,
-	}
-
-	impl TestInherentDataProvider {
-		fn new() -> Self {
-			let inst = Self {
-				registered: Default::default(),
-			};
-
-			// just make sure
-			assert!(!inst.is_registered());
-
-			inst
-		}
-
-		fn is_registered(&self) -> bool {
-			*self.registered.read()
-		}
-	}
-
-	const ERROR_TO_STRING: &str = "Found error!";
-
-	impl ProvideInherentData for TestInherentDataProvider {
-		fn on_register(&self, _: &InherentDataProviders) -> Result<(), Error> {
-			*self.registered.write() = true;
-			Ok(())
-		}
-
-		fn inherent_identifier(&self) -> &'static InherentIdentifier {
-			&TEST_INHERENT_0
-		}
-
-		fn provide_inherent_data(&self, data: &mut InherentData) -> Result<(), Error> {
-			data.put_data(TEST_INHERENT_0, &42)
-		}
-
-		fn error_to_string(&self, _: &[u8]) -> Option<String> {
-			Some(ERROR_TO_STRING.into())
-		}
-	}
-
-	#[test]
-	fn registering_inherent_provider() {
-		let provider = TestInherentDataProvider::new();
-		let providers = InherentDataProviders::new();
-
-		providers.register_provider(provider.clone()).unwrap();
-		assert!(provider.is_registered());
-		assert!(providers.has_provider(provider.inherent_identifier()));
-
-		// Second time should fail
-		assert!(providers.register_provider(provider.clone()).is_err());
-	}
-
-	#[test]
-	fn create_inherent_data_from_all_providers() {
-		let provider = TestInherentDataProvider::new();
-		let providers = InherentDataProviders::new();
-
-		providers.register_provider(provider.clone()).unwrap();
-		assert!(provider.is_registered());
-
-		let inherent_data = providers.create_inherent_data().unwrap();
-
-		assert_eq!(
-			inherent_data.get_data::<u32>(provider.inherent_identifier()).unwrap().unwrap(),
-			42u32
-		);
-	}
-
-	#[test]
-	fn encoded_error_to_string() {
-		let provider = TestInherentDataProvider::new();
-		let providers = InherentDataProviders::new();
-
-		providers.register_provider(provider.clone()).unwrap();
-		assert!(provider.is_registered());
-
-		assert_eq!(
-			&providers.error_to_string(&TEST_INHERENT_0, &[1, 2]), ERROR_TO_STRING
-		);
-
-		assert!(
-			providers
-				.error_to_string(&TEST_INHERENT_1, &[1, 2])
-				.contains("inherent type is unknown")
-		);
-	}
-
 	#[test]
 	fn check_inherents_result_encodes_and_decodes() {
 		let mut result = CheckInherentsResult::new();
@@ -427,6 +427,15 @@ pub trait Trie {
 	fn keccak_256_ordered_root(input: Vec<Vec<u8>>) -> H256 {
 		Layout::<sp_core::KeccakHasher>::ordered_trie_root(input)
 	}
+
+	/// Verify that `proof` shows `key` maps to `value` in the Blake2-256 trie with the given `root`.
+	fn blake2_256_verify_proof(root: H256, proof: &[Vec<u8>], key: &[u8], value: &[u8]) -> bool {
+		sp_trie::verify_trie_proof::<Layout<sp_core::Blake2Hasher>, _, _, _>(
+			&root,
+			proof,
+			&[(key, Some(value))],
+		).is_ok()
+	}
 }
 
 /// Interface that provides miscellaneous functions for communicating between the runtime and the node.
@@ -813,6 +822,22 @@ pub trait Hashing {
 	}
 }
 
+/// Interface that provides transaction indexing API.
+#[runtime_interface]
+pub trait TransactionIndex {
+	/// Index the last `size` bytes of the extrinsic with the given index in the current
+	/// block under `context_hash`.
+	fn index(&mut self, extrinsic: u32, size: u32, context_hash: [u8; 32]) {
+		self.storage_index_transaction(extrinsic, &context_hash, size);
+	}
+
+	/// Renew the data previously indexed under `context_hash`, referencing the extrinsic
+	/// with the given index in the current block.
+	fn renew(&mut self, extrinsic: u32, context_hash: [u8; 32]) {
+		self.storage_renew_transaction_index(extrinsic, &context_hash);
+	}
+}
+
 /// Interface that provides functions to access the Offchain DB.
 #[runtime_interface]
 pub trait OffchainIndex {
@@ -1049,6 +1074,20 @@ pub trait Offchain {
 	}
 }
 
+/// Wasm only interface that is used by the runtime to report a panic to the host.
+///
+/// The runtime only calls it with the `improved_panic_error_reporting` feature enabled.
+#[runtime_interface(wasm_only, no_tracing)]
+pub trait PanicHandler {
+	/// Register the message of the panic that is currently unwinding the runtime.
+	///
+	/// The executor reports this message as error of the runtime call instead of the opaque
+	/// wasm trap that follows the panic.
+	fn register_panic_message(&mut self, message: &str) {
+		self.register_panic_error_message(message);
+	}
+}
+
 /// Wasm only interface that provides functions for calling into the allocator.
 #[runtime_interface(wasm_only)]
 trait Allocator {
@@ -1382,6 +1421,8 @@ pub fn panic(info: &core::panic::PanicInfo) -> ! {
 	unsafe {
 		let message = sp_std::alloc::format!("{}", info);
 		logging::log(LogLevel::Error, "runtime", message.as_bytes());
+		#[cfg(feature = "improved_panic_error_reporting")]
+		panic_handler::register_panic_message(&message);
 		core::arch::wasm32::unreachable();
 	}
 }
@@ -1413,11 +1454,13 @@ pub type SubstrateHostFunctions = (
 	crypto::HostFunctions,
 	hashing::HostFunctions,
 	allocator::HostFunctions,
+	panic_handler::HostFunctions,
 	logging::HostFunctions,
 	sandbox::HostFunctions,
 	crate::trie::HostFunctions,
 	offchain_index::HostFunctions,
 	runtime_tasks::HostFunctions,
+	transaction_index::HostFunctions,
 );
 
 #[cfg(test)]
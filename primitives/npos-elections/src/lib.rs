@@ -25,6 +25,10 @@
 //!   process can push a solution toward being more "balanced", which in turn can increase its
 //!   score.
 //!
+//! Both election methods are also exposed through the [`NposSolver`] trait, via
+//! [`SequentialPhragmen`] and [`PhragMMS`], so that a consumer (e.g. an election provider) can be
+//! made generic over the algorithm used.
+//!
 //! ### Terminology
 //!
 //! This crate uses context-independent words, not to be confused with staking. This is because the
@@ -441,6 +445,64 @@ pub struct ElectionResult<AccountId, P: PerThing> {
 	pub assignments: Vec<Assignment<AccountId, P>>,
 }
 
+/// Something that can compute the result of an NPoS election.
+pub trait NposSolver {
+	/// The account identifier type of this solver.
+	type AccountId: IdentifierT;
+	/// The accuracy of this solver. This will affect the accuracy of the output.
+	type Accuracy: PerThing128;
+
+	/// Solve an NPoS election, electing up to `to_elect` of `targets` given the votes of
+	/// `voters`.
+	///
+	/// If `balancing` is `Some((iterations, tolerance))`, the result is balanced using the star
+	/// balancing algorithm with the given configuration.
+	fn solve(
+		to_elect: usize,
+		targets: Vec<Self::AccountId>,
+		voters: Vec<(Self::AccountId, VoteWeight, Vec<Self::AccountId>)>,
+		balancing: Option<(usize, ExtendedBalance)>,
+	) -> Result<ElectionResult<Self::AccountId, Self::Accuracy>, Error>;
+}
+
+/// A wrapper for [`seq_phragmen`] that implements [`NposSolver`].
+pub struct SequentialPhragmen<AccountId, Accuracy>(
+	sp_std::marker::PhantomData<(AccountId, Accuracy)>,
+);
+
+impl<AccountId: IdentifierT, Accuracy: PerThing128> NposSolver
+	for SequentialPhragmen<AccountId, Accuracy>
+{
+	type AccountId = AccountId;
+	type Accuracy = Accuracy;
+
+	fn solve(
+		to_elect: usize,
+		targets: Vec<Self::AccountId>,
+		voters: Vec<(Self::AccountId, VoteWeight, Vec<Self::AccountId>)>,
+		balancing: Option<(usize, ExtendedBalance)>,
+	) -> Result<ElectionResult<Self::AccountId, Self::Accuracy>, Error> {
+		seq_phragmen(to_elect, targets, voters, balancing)
+	}
+}
+
+/// A wrapper for [`phragmms()`] that implements [`NposSolver`].
+pub struct PhragMMS<AccountId, Accuracy>(sp_std::marker::PhantomData<(AccountId, Accuracy)>);
+
+impl<AccountId: IdentifierT, Accuracy: PerThing128> NposSolver for PhragMMS<AccountId, Accuracy> {
+	type AccountId = AccountId;
+	type Accuracy = Accuracy;
+
+	fn solve(
+		to_elect: usize,
+		targets: Vec<Self::AccountId>,
+		voters: Vec<(Self::AccountId, VoteWeight, Vec<Self::AccountId>)>,
+		balancing: Option<(usize, ExtendedBalance)>,
+	) -> Result<ElectionResult<Self::AccountId, Self::Accuracy>, Error> {
+		phragmms(to_elect, targets, voters, balancing)
+	}
+}
+
 /// A voter's stake assignment among a set of targets, represented as ratios.
 #[derive(RuntimeDebug, Clone, Default)]
 #[cfg_attr(feature = "std", derive(PartialEq, Eq, Encode, Decode))]
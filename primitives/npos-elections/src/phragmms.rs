@@ -23,7 +23,7 @@
 
 use crate::{
 	IdentifierT, ElectionResult, ExtendedBalance, setup_inputs, VoteWeight, Voter, CandidatePtr,
-	balance, PerThing128,
+	balance, PerThing128, Error,
 };
 use sp_arithmetic::{PerThing, Rational128, traits::Bounded};
 use sp_std::{prelude::*, rc::Rc};
@@ -46,7 +46,7 @@ pub fn phragmms<AccountId: IdentifierT, P: PerThing128>(
 	initial_candidates: Vec<AccountId>,
 	initial_voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)>,
 	balancing_config: Option<(usize, ExtendedBalance)>,
-) -> Result<ElectionResult<AccountId, P>, &'static str> {
+) -> Result<ElectionResult<AccountId, P>, Error> {
 	let (candidates, mut voters) = setup_inputs(initial_candidates, initial_voters);
 
 	let mut winners = vec![];
@@ -67,7 +67,10 @@ pub fn phragmms<AccountId: IdentifierT, P: PerThing128>(
 	}
 
 	let mut assignments = voters.into_iter().filter_map(|v| v.into_assignment()).collect::<Vec<_>>();
-	let _ = assignments.iter_mut().map(|a| a.try_normalize()).collect::<Result<(), _>>()?;
+	let _ = assignments
+		.iter_mut()
+		.map(|a| a.try_normalize().map_err(|e| Error::ArithmeticError(e)))
+		.collect::<Result<(), _>>()?;
 	let winners = winners.into_iter().map(|w_ptr|
 		(w_ptr.borrow().who.clone(), w_ptr.borrow().backed_stake)
 	).collect();
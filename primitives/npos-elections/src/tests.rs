@@ -20,7 +20,7 @@
 use crate::{
 	balancing, helpers::*, is_score_better, mock::*, seq_phragmen, seq_phragmen_core, setup_inputs,
 	to_support_map, to_supports, Assignment, ElectionResult, ExtendedBalance, StakedAssignment,
-	Support, Voter, EvaluateSupport,
+	Support, Voter, EvaluateSupport, phragmms, NposSolver, SequentialPhragmen, PhragMMS,
 };
 use sp_arithmetic::{PerU16, Perbill, Percent, Permill};
 use substrate_test_utils::assert_eq_uvec;
@@ -866,6 +866,35 @@ fn support_map_and_vec_can_be_evaluated() {
 	assert_eq!(support_map.evaluate(), support_vec.evaluate());
 }
 
+#[test]
+fn solvers_match_underlying_algorithms() {
+	let candidates = vec![1, 2, 3, 4];
+	let voters = vec![
+		(10, 10, vec![1, 2]),
+		(20, 20, vec![1, 3]),
+		(30, 30, vec![2, 3, 4]),
+		(40, 15, vec![4]),
+	];
+
+	let direct = seq_phragmen::<_, Perbill>(2, candidates.clone(), voters.clone(), Some((2, 0)))
+		.unwrap();
+	let solved = <SequentialPhragmen<u64, Perbill> as NposSolver>::solve(
+		2,
+		candidates.clone(),
+		voters.clone(),
+		Some((2, 0)),
+	)
+	.unwrap();
+	assert_eq!(solved.winners, direct.winners);
+	assert_eq!(solved.assignments, direct.assignments);
+
+	let direct = phragmms::<_, Perbill>(3, candidates.clone(), voters.clone(), None).unwrap();
+	let solved =
+		<PhragMMS<u64, Perbill> as NposSolver>::solve(3, candidates, voters, None).unwrap();
+	assert_eq!(solved.winners, direct.winners);
+	assert_eq!(solved.assignments, direct.assignments);
+}
+
 mod assignment_convert_normalize {
 	use super::*;
 	#[test]
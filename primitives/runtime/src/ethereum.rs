@@ -0,0 +1,174 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2017-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ethereum-compatible accounts and signatures.
+//!
+//! Chains that want their accounts to be interchangeable with Ethereum ones can use
+//! [`EthereumSignature`] as their signature type. Signers are ECDSA/SECP256k1 keys, and accounts
+//! are the last 20 bytes of the keccak-256 hash of the uncompressed public key ([`AccountId20`]).
+
+use codec::{Encode, Decode};
+use sp_core::{ecdsa, RuntimeDebug};
+use crate::traits::{IdentifyAccount, Lazy, Verify};
+
+/// A 20 byte, Ethereum-style account identifier.
+#[derive(
+	Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Default, Encode, Decode, RuntimeDebug,
+	sp_std::hash::Hash,
+)]
+pub struct AccountId20(pub [u8; 20]);
+
+impl AccountId20 {
+	/// Derive the account of the given full, uncompressed public key (without the `0x04` prefix).
+	pub fn from_full_public(public: &[u8; 64]) -> Self {
+		let hash = sp_io::hashing::keccak_256(&public[..]);
+		let mut account = [0u8; 20];
+		account.copy_from_slice(&hash[12..]);
+		AccountId20(account)
+	}
+}
+
+impl From<[u8; 20]> for AccountId20 {
+	fn from(x: [u8; 20]) -> Self {
+		AccountId20(x)
+	}
+}
+
+impl From<AccountId20> for [u8; 20] {
+	fn from(x: AccountId20) -> [u8; 20] {
+		x.0
+	}
+}
+
+impl AsRef<[u8]> for AccountId20 {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl AsMut<[u8]> for AccountId20 {
+	fn as_mut(&mut self) -> &mut [u8] {
+		&mut self.0[..]
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for AccountId20 {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "0x{}", sp_core::hexdisplay::HexDisplay::from(&self.0))
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::str::FromStr for AccountId20 {
+	type Err = &'static str;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let s = s.strip_prefix("0x").unwrap_or(s);
+		let bytes = sp_core::bytes::from_hex(s).map_err(|_| "invalid hex address")?;
+		let mut account = [0u8; 20];
+		if bytes.len() != account.len() {
+			return Err("invalid address length")
+		}
+		account.copy_from_slice(&bytes);
+		Ok(AccountId20(account))
+	}
+}
+
+#[cfg(feature = "std")]
+impl serde::Serialize for AccountId20 {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&self.to_string())
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for AccountId20 {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		s.parse().map_err(serde::de::Error::custom)
+	}
+}
+
+/// The signer of an [`EthereumSignature`], identified by its Ethereum address.
+///
+/// The public key is only needed to derive the address, so that is all that is kept.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct EthereumSigner(AccountId20);
+
+impl From<AccountId20> for EthereumSigner {
+	fn from(x: AccountId20) -> Self {
+		EthereumSigner(x)
+	}
+}
+
+#[cfg(feature = "std")]
+impl From<ecdsa::Public> for EthereumSigner {
+	fn from(x: ecdsa::Public) -> Self {
+		let full = x.to_full().expect("ecdsa::Public is always a valid compressed key; qed");
+		EthereumSigner(AccountId20::from_full_public(&full))
+	}
+}
+
+impl AsRef<[u8]> for EthereumSigner {
+	fn as_ref(&self) -> &[u8] {
+		self.0.as_ref()
+	}
+}
+
+impl IdentifyAccount for EthereumSigner {
+	type AccountId = AccountId20;
+	fn into_account(self) -> AccountId20 {
+		self.0
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for EthereumSigner {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(fmt, "ethereum: {}", self.0)
+	}
+}
+
+/// An ECDSA signature over the keccak-256 hash of a message, verified against an [`AccountId20`].
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct EthereumSignature(ecdsa::Signature);
+
+impl From<ecdsa::Signature> for EthereumSignature {
+	fn from(x: ecdsa::Signature) -> Self {
+		EthereumSignature(x)
+	}
+}
+
+impl Default for EthereumSignature {
+	fn default() -> Self {
+		EthereumSignature(Default::default())
+	}
+}
+
+impl Verify for EthereumSignature {
+	type Signer = EthereumSigner;
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId20) -> bool {
+		let m = sp_io::hashing::keccak_256(msg.get());
+		match sp_io::crypto::secp256k1_ecdsa_recover(self.0.as_ref(), &m) {
+			Ok(public) => AccountId20::from_full_public(&public) == *signer,
+			Err(_) => false,
+		}
+	}
+}
@@ -57,12 +57,14 @@ pub mod transaction_validity;
 pub mod random_number_generator;
 mod runtime_string;
 mod multiaddress;
+mod ethereum;
 pub mod runtime_logger;
 
 pub use crate::runtime_string::*;
 
 // Re-export Multiaddress
 pub use multiaddress::MultiAddress;
+pub use ethereum::{AccountId20, EthereumSignature, EthereumSigner};
 
 /// Re-export these since they're only "kind of" generic.
 pub use generic::{DigestItem, Digest};
@@ -344,6 +346,18 @@ impl Verify for MultiSignature {
 			}
 		}
 	}
+
+	fn batch_verify<L: Lazy<[u8]>>(&self, msg: L, signer: &AccountId32) -> bool {
+		match (self, signer) {
+			(MultiSignature::Ed25519(ref sig), who) =>
+				sig.batch_verify(msg, &ed25519::Public::from_slice(who.as_ref())),
+			(MultiSignature::Sr25519(ref sig), who) =>
+				sig.batch_verify(msg, &sr25519::Public::from_slice(who.as_ref())),
+			// The public key has to be recovered to check it against the account, which can't be
+			// deferred.
+			(MultiSignature::Ecdsa(_), _) => self.verify(msg, signer),
+		}
+	}
 }
 
 /// Signature verify that can work with any known signature types..
@@ -784,6 +798,26 @@ mod tests {
 		assert!(multi_sig.verify(msg, &multi_signer.into_account()));
 	}
 
+	#[test]
+	fn ethereum_signature_verify_works() {
+		let msg = &b"test-message"[..];
+		let mut seed = [0u8; 32];
+		seed[31] = 1;
+		let pair = ecdsa::Pair::from_seed(&seed);
+
+		let signer = EthereumSigner::from(pair.public());
+		let account = signer.into_account();
+		assert_eq!(account.to_string(), "0x7e5f4552091a69125d5dfcb7b8c2659029395bdf");
+		assert_eq!("0x7e5f4552091a69125d5dfcb7b8c2659029395bdf".parse(), Ok(account));
+
+		let signature = EthereumSignature::from(
+			pair.sign_prehashed(&sp_core::hashing::keccak_256(msg)),
+		);
+		assert!(signature.verify(msg, &account));
+		assert!(!signature.verify(&b"other-message"[..], &account));
+		assert!(!signature.verify(msg, &AccountId20::default()));
+	}
+
 
 	#[test]
 	#[should_panic(expected = "Signature verification has not been called")]
@@ -816,4 +850,33 @@ mod tests {
 			panic!("Hey, I'm an error");
 		});
 	}
+
+	#[test]
+	fn multi_signature_batch_verify_defers_checks() {
+		let mut ext = sp_state_machine::BasicExternalities::default();
+		ext.register_extension(
+			sp_core::traits::TaskExecutorExt::new(sp_core::testing::TaskExecutor::new()),
+		);
+
+		ext.execute_with(|| {
+			let msg = &b"test-message"[..];
+			let (pair, _) = sr25519::Pair::generate();
+			let signer = MultiSigner::from(pair.public()).into_account();
+			let good = MultiSignature::from(pair.sign(msg));
+			let bad = MultiSignature::from(pair.sign(&b"other-message"[..]));
+
+			let batching = SignatureBatching::start();
+			assert!(good.batch_verify(msg, &signer));
+			assert!(batching.verify());
+
+			let batching = SignatureBatching::start();
+			assert!(good.batch_verify(msg, &signer));
+			// The failure only shows up when the batch is verified.
+			assert!(bad.batch_verify(msg, &signer));
+			assert!(!batching.verify());
+
+			// Without batching, signatures are checked right away.
+			assert!(!bad.batch_verify(msg, &signer));
+		});
+	}
 }
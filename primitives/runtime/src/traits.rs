@@ -86,6 +86,19 @@ pub trait Verify {
 	///
 	/// Return `true` if signature is valid for the value.
 	fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &<Self::Signer as IdentifyAccount>::AccountId) -> bool;
+
+	/// Verify a signature as part of a batch.
+	///
+	/// If batch verification was started with `sp_io::crypto::start_batch_verify`, the check is
+	/// deferred until `sp_io::crypto::finish_batch_verify` and this returns `true`. Otherwise, it
+	/// is the same as `verify`.
+	fn batch_verify<L: Lazy<[u8]>>(
+		&self,
+		msg: L,
+		signer: &<Self::Signer as IdentifyAccount>::AccountId,
+	) -> bool {
+		self.verify(msg, signer)
+	}
 }
 
 impl Verify for sp_core::ed25519::Signature {
@@ -94,6 +107,10 @@ impl Verify for sp_core::ed25519::Signature {
 	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &sp_core::ed25519::Public) -> bool {
 		sp_io::crypto::ed25519_verify(self, msg.get(), signer)
 	}
+
+	fn batch_verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &sp_core::ed25519::Public) -> bool {
+		sp_io::crypto::ed25519_batch_verify(self, msg.get(), signer)
+	}
 }
 
 impl Verify for sp_core::sr25519::Signature {
@@ -102,6 +119,10 @@ impl Verify for sp_core::sr25519::Signature {
 	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &sp_core::sr25519::Public) -> bool {
 		sp_io::crypto::sr25519_verify(self, msg.get(), signer)
 	}
+
+	fn batch_verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &sp_core::sr25519::Public) -> bool {
+		sp_io::crypto::sr25519_batch_verify(self, msg.get(), signer)
+	}
 }
 
 impl Verify for sp_core::ecdsa::Signature {
@@ -115,6 +136,10 @@ impl Verify for sp_core::ecdsa::Signature {
 			_ => false,
 		}
 	}
+
+	fn batch_verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &sp_core::ecdsa::Public) -> bool {
+		sp_io::crypto::ecdsa_batch_verify(self, msg.get(), signer)
+	}
 }
 
 /// Means of signature verification of an application key.
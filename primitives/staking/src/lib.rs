@@ -22,5 +22,86 @@
 
 pub mod offence;
 
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::prelude::*;
+
 /// Simple index type with which we can count sessions.
 pub type SessionIndex = u32;
+
+/// Counter for the number of eras that have passed.
+pub type EraIndex = u32;
+
+/// A generic representation of a staking implementation.
+///
+/// This interface is meant to be used by other pallets that want to stake on behalf of other
+/// accounts (e.g. nomination pools) without being coupled to a concrete staking pallet. All
+/// operations act on the given accounts directly, i.e. the caller is responsible for any
+/// permission checks.
+pub trait StakingInterface {
+	/// Balance type used by the staking system.
+	type Balance;
+
+	/// AccountId type used by the staking system.
+	type AccountId;
+
+	/// The minimum amount required to bond in order to be a nominator.
+	fn minimum_bond() -> Self::Balance;
+
+	/// Number of eras that staked funds must remain bonded for after calling unbond.
+	fn bonding_duration() -> EraIndex;
+
+	/// The current era index.
+	///
+	/// This should be the latest planned era that the staking system knows about.
+	fn current_era() -> EraIndex;
+
+	/// The stash that is controlled by `controller`.
+	fn stash_by_ctrl(controller: &Self::AccountId) -> Result<Self::AccountId, DispatchError>;
+
+	/// Whether `who` was exposed in `era`, either as a validator or as a nominator.
+	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool;
+
+	/// Balance of the stash controlled by `controller` that is actively staked, if any.
+	fn active_stake(controller: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// Total balance of the stash controlled by `controller`, including funds that are in the
+	/// process of unbonding, if any.
+	fn total_stake(controller: &Self::AccountId) -> Option<Self::Balance>;
+
+	/// Bond (lock) `value` of `stash`'s balance. `controller` can be `stash`. Rewards are paid
+	/// out to `payee`.
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+		payee: Self::AccountId,
+	) -> DispatchResult;
+
+	/// Bond some extra amount of `stash`'s free balance.
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult;
+
+	/// Schedule a portion of the stash controlled by `controller` to be unlocked once the
+	/// bonding duration has passed.
+	fn unbond(controller: Self::AccountId, value: Self::Balance) -> DispatchResult;
+
+	/// Unlock any funds of the stash controlled by `controller` whose bonding duration has
+	/// passed.
+	fn withdraw_unbonded(controller: Self::AccountId) -> DispatchResult;
+
+	/// Have the stash controlled by `controller` nominate `validators`.
+	fn nominate(controller: Self::AccountId, validators: Vec<Self::AccountId>) -> DispatchResult;
+
+	/// Stop the stash controlled by `controller` from nominating or validating.
+	fn chill(controller: Self::AccountId) -> DispatchResult;
+
+	/// Remove all staking information of `stash` and release its funds immediately, including
+	/// funds that are still unbonding.
+	///
+	/// This skips the bonding duration, so the caller must make sure that `stash` can not be
+	/// slashed anymore.
+	fn force_unstake(stash: Self::AccountId) -> DispatchResult;
+
+	/// Set the current era. Only used to set up benchmarks.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_current_era(era: EraIndex);
+}
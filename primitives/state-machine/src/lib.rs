@@ -46,7 +46,9 @@ pub use std_reexport::*;
 #[cfg(feature = "std")]
 pub use execution::*;
 #[cfg(feature = "std")]
-pub use log::{debug, warn, trace, error as log_error};
+pub use log::{debug, warn, error as log_error};
+#[cfg(feature = "std")]
+pub use tracing::trace;
 
 /// In no_std we skip logs for state_machine, this macro
 /// is a noops.
@@ -121,6 +123,7 @@ pub use crate::overlayed_changes::{
 	StorageChanges, StorageTransactionCache,
 	OffchainChangesCollection,
 	OffchainOverlayedChanges,
+	IndexOperation,
 };
 pub use crate::backend::Backend;
 pub use crate::trie_backend_essence::{TrieBackendStorage, Storage};
@@ -784,6 +787,139 @@ mod execution {
 		Ok(proving_backend.extract_proof())
 	}
 
+	/// Generate a read proof for a range of keys, stopping once the proof exceeds `size_limit`.
+	///
+	/// Reads the keys of the top trie, or of the child trie if `child_info` is given, that start
+	/// with `prefix`, in lexicographic order and beginning after `start_at`. The key that makes the
+	/// proof exceed `size_limit` is still included, so that every call makes progress.
+	///
+	/// Returns the proof and the number of keys it covers. Use `read_range_proof_check` to check
+	/// the proof and read the key-value pairs back.
+	pub fn prove_range_read_with_size_limit<B, H>(
+		mut backend: B,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend()
+			.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+		prove_range_read_with_size_limit_on_trie_backend(
+			trie_backend,
+			child_info,
+			prefix,
+			size_limit,
+			start_at,
+		)
+	}
+
+	/// Generate a range read proof on pre-created trie backend.
+	///
+	/// See `prove_range_read_with_size_limit`.
+	pub fn prove_range_read_with_size_limit_on_trie_backend<S, H>(
+		trie_backend: &TrieBackend<S, H>,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		size_limit: usize,
+		start_at: Option<&[u8]>,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		S: trie_backend_essence::TrieBackendStorage<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = proving_backend::ProvingBackend::<S, H>::new(trie_backend);
+		let mut count = 0;
+		read_range(&proving_backend, child_info, prefix, start_at, |_, _| {
+			count += 1;
+			proving_backend.estimate_encoded_size() <= size_limit
+		})?;
+		Ok((proving_backend.extract_proof(), count))
+	}
+
+	/// Check a range read proof, generated by `prove_range_read_with_size_limit` call.
+	///
+	/// `count` is the number of keys covered by the proof. If `None`, all the keys in the range are
+	/// read, which fails if the proof doesn't cover the whole range.
+	///
+	/// Returns the key-value pairs of the range, and whether the end of the range was reached.
+	pub fn read_range_proof_check<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		count: Option<u32>,
+		start_at: Option<&[u8]>,
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+		let mut values = Vec::new();
+		let completed = read_range(&proving_backend, child_info, prefix, start_at, |key, value| {
+			values.push((key, value));
+			count.map_or(true, |count| values.len() < count as usize)
+		})?;
+		Ok((values, completed))
+	}
+
+	/// Iterate over the key-value pairs of a range of keys, see `prove_range_read_with_size_limit`.
+	///
+	/// Stops when `f` returns `false`. The key following the last key passed to `f` is always read,
+	/// so that the proof shows whether the end of the range was reached, which is returned.
+	fn read_range<H, B>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		prefix: Option<&[u8]>,
+		start_at: Option<&[u8]>,
+		mut f: impl FnMut(Vec<u8>, Vec<u8>) -> bool,
+	) -> Result<bool, Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+		B: Backend<H>,
+	{
+		let next_key = |key: &[u8]| match child_info {
+			Some(child_info) => backend.next_child_storage_key(child_info, key),
+			None => backend.next_storage_key(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>);
+		let read_value = |key: &[u8]| match child_info {
+			Some(child_info) => backend.child_storage(child_info, key),
+			None => backend.storage(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>);
+
+		let prefix = prefix.unwrap_or(&[]);
+		let mut current = match start_at {
+			Some(start_at) => next_key(start_at)?,
+			None => match read_value(prefix)? {
+				Some(_) => Some(prefix.to_vec()),
+				None => next_key(prefix)?,
+			},
+		};
+
+		while let Some(key) = current {
+			if !key.starts_with(prefix) {
+				return Ok(true)
+			}
+
+			let value = read_value(&key)?.ok_or_else(|| {
+				Box::new(ExecutionError::InvalidProof) as Box<dyn Error>
+			})?;
+			current = next_key(&key)?;
+			if !f(key, value) {
+				return Ok(current.map_or(true, |key| !key.starts_with(prefix)))
+			}
+		}
+
+		Ok(true)
+	}
+
 	/// Check storage read proof, generated by `prove_read` call.
 	pub fn read_proof_check<H, I>(
 		root: H::Out,
@@ -1458,6 +1594,70 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn prove_range_with_size_limit_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let (proof, count) = prove_range_read_with_size_limit(
+			remote_backend,
+			None,
+			Some(b"value"),
+			0,
+			None,
+		).unwrap();
+		// Always reads at least one key, even with a zero size limit.
+		assert_eq!(count, 1);
+
+		let (values, completed) = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			proof,
+			None,
+			Some(b"value"),
+			Some(count),
+			None,
+		).unwrap();
+		assert_eq!(values, vec![(b"value1".to_vec(), vec![42])]);
+		assert!(!completed);
+
+		// Continue from the last key, without any limit.
+		let remote_backend = trie_backend::tests::test_trie();
+		let (proof, count) = prove_range_read_with_size_limit(
+			remote_backend,
+			None,
+			Some(b"value"),
+			usize::max_value(),
+			Some(b"value1"),
+		).unwrap();
+		assert_eq!(count, 1);
+
+		let (values, completed) = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			proof.clone(),
+			None,
+			Some(b"value"),
+			None,
+			Some(b"value1"),
+		).unwrap();
+		assert_eq!(values, vec![(b"value2".to_vec(), vec![24])]);
+		assert!(completed);
+
+		// The compact proof decodes back to a proof that can be checked the same way.
+		let compact_proof = proof.into_compact_proof::<BlakeTwo256>(remote_root).unwrap();
+		let (proof, root) = compact_proof.to_storage_proof::<BlakeTwo256>(Some(&remote_root))
+			.unwrap();
+		assert_eq!(root, remote_root);
+		let (values, completed) = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			proof,
+			None,
+			Some(b"value"),
+			None,
+			Some(b"value1"),
+		).unwrap();
+		assert_eq!(values, vec![(b"value2".to_vec(), vec![24])]);
+		assert!(completed);
+	}
+
 	#[test]
 	fn child_storage_uuid() {
 
@@ -69,6 +69,27 @@ pub type ChildStorageCollection = Vec<(StorageKey, StorageCollection)>;
 /// In memory array of storage values.
 pub type OffchainChangesCollection = Vec<((Vec<u8>, Vec<u8>), OffchainOverlayedChange)>;
 
+/// Transaction index operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexOperation {
+	/// Insert transaction into index.
+	Insert {
+		/// Extrinsic index in the current block.
+		extrinsic: u32,
+		/// Data content hash.
+		hash: Vec<u8>,
+		/// Indexed data size. The data is the last `size` bytes of the encoded extrinsic.
+		size: u32,
+	},
+	/// Renew existing transaction storage.
+	Renew {
+		/// Extrinsic index in the current block.
+		extrinsic: u32,
+		/// Referenced index hash.
+		hash: Vec<u8>,
+	},
+}
+
 /// Keep trace of extrinsics index for a modified value.
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
 pub struct Extrinsics(Vec<u32>);
@@ -107,6 +128,8 @@ pub struct OverlayedChanges {
 	collect_extrinsics: bool,
 	/// Collect statistic on this execution.
 	stats: StateMachineStats,
+	/// Transaction index changes.
+	transaction_index_ops: Vec<IndexOperation>,
 }
 
 /// A storage changes structure that can be generated by the data collected in [`OverlayedChanges`].
@@ -137,6 +160,8 @@ pub struct StorageChanges<Transaction, H: Hasher, N: BlockNumber> {
 	/// Phantom data for block number until change trie support no_std.
 	#[cfg(not(feature = "std"))]
 	pub _ph: sp_std::marker::PhantomData<N>,
+	/// Changes to the transaction index.
+	pub transaction_index_changes: Vec<IndexOperation>,
 }
 
 #[cfg(feature = "std")]
@@ -149,6 +174,7 @@ impl<Transaction, H: Hasher, N: BlockNumber> StorageChanges<Transaction, H, N> {
 		Transaction,
 		H::Out,
 		Option<ChangesTrieTransaction<H, N>>,
+		Vec<IndexOperation>,
 	) {
 		(
 			self.main_storage_changes,
@@ -157,6 +183,7 @@ impl<Transaction, H: Hasher, N: BlockNumber> StorageChanges<Transaction, H, N> {
 			self.transaction,
 			self.transaction_storage_root,
 			self.changes_trie_transaction,
+			self.transaction_index_changes,
 		)
 	}
 }
@@ -214,6 +241,7 @@ impl<Transaction: Default, H: Hasher, N: BlockNumber> Default for StorageChanges
 			changes_trie_transaction: None,
 			#[cfg(not(feature = "std"))]
 			_ph: Default::default(),
+			transaction_index_changes: Default::default(),
 		}
 	}
 }
@@ -542,6 +570,7 @@ impl OverlayedChanges {
 
 		let (main_storage_changes, child_storage_changes) = self.drain_committed();
 		let offchain_storage_changes = self.offchain_drain_committed().collect();
+		let transaction_index_changes = sp_std::mem::take(&mut self.transaction_index_ops);
 
 		Ok(StorageChanges {
 			main_storage_changes: main_storage_changes.collect(),
@@ -553,6 +582,7 @@ impl OverlayedChanges {
 			changes_trie_transaction,
 			#[cfg(not(feature = "std"))]
 			_ph: Default::default(),
+			transaction_index_changes,
 		})
 	}
 
@@ -666,6 +696,11 @@ impl OverlayedChanges {
 			None => self.offchain.remove(STORAGE_PREFIX, key),
 		}
 	}
+
+	/// Add transaction index operation.
+	pub fn add_transaction_index(&mut self, op: IndexOperation) {
+		self.transaction_index_ops.push(op)
+	}
 }
 
 #[cfg(feature = "std")]
@@ -19,7 +19,7 @@
 
 use std::{sync::Arc, collections::HashMap};
 use parking_lot::RwLock;
-use codec::{Decode, Codec};
+use codec::{Decode, Codec, Encode};
 use log::debug;
 use hash_db::{Hasher, HashDB, EMPTY_PREFIX, Prefix};
 use sp_trie::{
@@ -157,6 +157,17 @@ impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> ProvingBackend<'a, S, H>
 			.collect();
 		StorageProof::new(trie_nodes)
 	}
+
+	/// Returns the estimated encoded size of the proof.
+	///
+	/// The estimation is an upper bound, as compact encoding of the proof is not taken into
+	/// account.
+	pub fn estimate_encoded_size(&self) -> usize {
+		let recorder = self.0.essence().backend_storage().proof_recorder.read();
+		let nodes = recorder.values().filter_map(|v| v.as_ref());
+		codec::Compact(recorder.len() as u32).encoded_size()
+			+ nodes.map(|n| n.encoded_size()).sum::<usize>()
+	}
 }
 
 impl<'a, S: 'a + TrieBackendStorage<H>, H: 'a + Hasher> TrieBackendStorage<H>
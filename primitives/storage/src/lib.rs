@@ -131,7 +131,7 @@ pub struct StorageChild {
 
 /// Struct containing data needed for a storage.
 #[cfg(feature = "std")]
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct Storage {
 	/// Top trie storage data.
 	pub top: StorageMap,
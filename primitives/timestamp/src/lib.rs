@@ -20,11 +20,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Encode, Decode};
-#[cfg(feature = "std")]
-use sp_inherents::ProvideInherentData;
 use sp_inherents::{InherentIdentifier, IsFatalError, InherentData};
 
 use sp_runtime::RuntimeString;
+use sp_std::time::Duration;
 
 /// The identifier for the `timestamp` inherent.
 pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"timstap0";
@@ -43,6 +42,11 @@ impl Timestamp {
 	pub const fn new(inner: u64) -> Self {
 		Self(inner)
 	}
+
+	/// Returns `self` as [`Duration`].
+	pub fn as_duration(self) -> Duration {
+		Duration::from_millis(self.0)
+	}
 }
 
 impl sp_std::ops::Deref for Timestamp {
@@ -100,8 +104,8 @@ impl From<Timestamp> for u64 {
 	}
 }
 
-impl From<sp_std::time::Duration> for Timestamp {
-	fn from(duration: sp_std::time::Duration) -> Self {
+impl From<Duration> for Timestamp {
+	fn from(duration: Duration) -> Self {
 		Timestamp(duration.as_millis() as u64)
 	}
 }
@@ -151,33 +155,107 @@ impl TimestampInherentData for InherentData {
 	}
 }
 
+/// The current timestamp using the system time.
+///
+/// This timestamp is the time since the UNIX epoch.
+#[cfg(feature = "std")]
+fn current_timestamp() -> Duration {
+	use wasm_timer::SystemTime;
+
+	let now = SystemTime::now();
+	now.duration_since(SystemTime::UNIX_EPOCH)
+		.expect("Current time is always after unix epoch; qed")
+}
+
 /// Provide duration since unix epoch in millisecond for timestamp inherent.
 #[cfg(feature = "std")]
-pub struct InherentDataProvider;
+pub struct InherentDataProvider {
+	max_drift: InherentType,
+	timestamp: InherentType,
+}
 
 #[cfg(feature = "std")]
-impl ProvideInherentData for InherentDataProvider {
-	fn inherent_identifier(&self) -> &'static InherentIdentifier {
-		&INHERENT_IDENTIFIER
+impl InherentDataProvider {
+	/// Create `Self` while using the system time to get the timestamp.
+	pub fn from_system_time() -> Self {
+		Self {
+			max_drift: Duration::from_secs(60).into(),
+			timestamp: current_timestamp().into(),
+		}
+	}
+
+	/// Override the max drift. A too big drift will lead to the block being rejected.
+	///
+	/// The default max drift is 60 seconds.
+	pub fn with_max_drift(mut self, max_drift: Duration) -> Self {
+		self.max_drift = max_drift.into();
+		self
 	}
 
+	/// Create `Self` using the given `timestamp`.
+	pub fn new(timestamp: InherentType) -> Self {
+		Self {
+			max_drift: Duration::from_secs(60).into(),
+			timestamp,
+		}
+	}
+
+	/// Returns the timestamp of this inherent data provider.
+	pub fn timestamp(&self) -> InherentType {
+		self.timestamp
+	}
+}
+
+#[cfg(feature = "std")]
+impl sp_std::ops::Deref for InherentDataProvider {
+	type Target = InherentType;
+
+	fn deref(&self) -> &Self::Target {
+		&self.timestamp
+	}
+}
+
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
 	fn provide_inherent_data(
 		&self,
 		inherent_data: &mut InherentData,
 	) -> Result<(), sp_inherents::Error> {
-		use wasm_timer::SystemTime;
-
-		let now = SystemTime::now();
-		now.duration_since(SystemTime::UNIX_EPOCH)
-			.map_err(|_| {
-				"Current time is before unix epoch".into()
-			}).and_then(|d| {
-				inherent_data.put_data(INHERENT_IDENTIFIER, &InherentType::from(d))
-			})
+		inherent_data.put_data(INHERENT_IDENTIFIER, &self.timestamp)
 	}
 
-	fn error_to_string(&self, error: &[u8]) -> Option<String> {
-		InherentError::try_from(&INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		if *identifier != INHERENT_IDENTIFIER {
+			return None
+		}
+
+		match InherentError::try_from(&INHERENT_IDENTIFIER, error)? {
+			InherentError::ValidAtTimestamp(valid) => {
+				// halt import until timestamp is valid.
+				// reject when too far ahead.
+				if valid > self.timestamp + self.max_drift {
+					return Some(Err(
+						format!("Block timestamp {} is too far in the future", valid).into()
+					))
+				}
+
+				let diff = valid.saturating_sub(*self.timestamp);
+				log::info!(
+					target: "timestamp",
+					"halting for block {} milliseconds in the future",
+					diff,
+				);
+
+				futures_timer::Delay::new(Duration::from_millis(diff)).await;
+
+				Some(Ok(()))
+			},
+			o => Some(Err(format!("{:?}", o).into())),
+		}
 	}
 }
-
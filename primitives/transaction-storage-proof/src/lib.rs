@@ -0,0 +1,280 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage proof primitives. Contains types and basic code to extract storage
+//! proofs for indexed transactions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::{result::Result, prelude::*};
+
+use codec::{Encode, Decode};
+use sp_inherents::{InherentIdentifier, InherentData, IsFatalError};
+use sp_runtime::{traits::{Block as BlockT, NumberFor}};
+use sp_trie::TrieMut;
+
+pub use sp_inherents::Error;
+
+/// The identifier for the proof inherent.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"tx_proof";
+/// Storage period for data.
+pub const DEFAULT_STORAGE_PERIOD: u32 = 100800;
+/// Proof trie value size.
+pub const CHUNK_SIZE: usize = 256;
+
+/// Errors that can occur while checking the storage proof.
+#[derive(Encode, sp_runtime::RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode))]
+pub enum InherentError {
+	/// The proof does not match any of the data stored in the target block.
+	InvalidProof,
+	/// The proof could not be decoded or verified.
+	TrieError,
+}
+
+impl IsFatalError for InherentError {
+	fn is_fatal_error(&self) -> bool {
+		true
+	}
+}
+
+/// A chunk of indexed data along with a proof that the chunk is part of the chunk trie of its
+/// transaction.
+#[derive(Encode, Decode, Clone, PartialEq, Debug)]
+pub struct TransactionStorageProof {
+	/// Data chunk that is proved to exist.
+	pub chunk: Vec<u8>,
+	/// Trie nodes that compose the proof.
+	pub proof: Vec<Vec<u8>>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Runtime API of the transaction storage pallet.
+	pub trait TransactionStorageApi {
+		/// The number of blocks for which stored data must be kept and proven.
+		fn storage_period() -> NumberFor<Block>;
+	}
+}
+
+/// Auxiliary trait to extract storage proof.
+pub trait TransactionStorageProofInherentData {
+	/// Get the proof.
+	fn storage_proof(&self) -> Result<Option<TransactionStorageProof>, Error>;
+}
+
+impl TransactionStorageProofInherentData for InherentData {
+	fn storage_proof(&self) -> Result<Option<TransactionStorageProof>, Error> {
+		Ok(self.get_data(&INHERENT_IDENTIFIER)?)
+	}
+}
+
+/// Provider for inherent data.
+#[cfg(feature = "std")]
+pub struct InherentDataProvider {
+	proof: Option<TransactionStorageProof>,
+}
+
+#[cfg(feature = "std")]
+impl InherentDataProvider {
+	/// Create `Self` with the given proof, if any.
+	pub fn new(proof: Option<TransactionStorageProof>) -> Self {
+		InherentDataProvider { proof }
+	}
+}
+
+#[cfg(feature = "std")]
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
+		if let Some(proof) = &self.proof {
+			inherent_data.put_data(INHERENT_IDENTIFIER, proof)
+		} else {
+			Ok(())
+		}
+	}
+
+	async fn try_handle_error(
+		&self,
+		identifier: &InherentIdentifier,
+		error: &[u8],
+	) -> Option<Result<(), Error>> {
+		if *identifier != INHERENT_IDENTIFIER {
+			return None
+		}
+
+		let error = InherentError::decode(&mut &error[..]).ok()?;
+
+		Some(Err(Error::from(format!("{:?}", error))))
+	}
+}
+
+/// Select the chunk to prove, given a source of randomness and the total number of chunks
+/// stored in the target block.
+pub fn random_chunk(random_hash: &[u8], total_chunks: u32) -> u32 {
+	let mut buf = [0u8; 8];
+	buf.copy_from_slice(&random_hash[0..8]);
+	let random_u64 = u64::from_be_bytes(buf);
+	(random_u64 % total_chunks as u64) as u32
+}
+
+/// Encode chunk index as a key in the chunk trie.
+pub fn encode_index(input: u32) -> Vec<u8> {
+	codec::Encode::encode(&codec::Compact(input))
+}
+
+/// Number of chunks needed to store `bytes` of data.
+pub fn num_chunks(bytes: u32) -> u32 {
+	((bytes as u64 + CHUNK_SIZE as u64 - 1) / CHUNK_SIZE as u64) as u32
+}
+
+/// Client side access to the indexed data needed to build storage proofs.
+pub trait IndexedBody<B: BlockT> {
+	/// Get all indexed transactions of the block with the given number, in the order they
+	/// were indexed.
+	///
+	/// Note that this will only fetch transactions that are indexed by the runtime with
+	/// `storage_index_transaction`.
+	fn block_indexed_body(
+		&self,
+		number: NumberFor<B>,
+	) -> Result<Option<Vec<Vec<u8>>>, Error>;
+
+	/// Get block number for a block hash.
+	fn number(
+		&self,
+		hash: B::Hash,
+	) -> Result<Option<NumberFor<B>>, Error>;
+}
+
+type Hasher = sp_core::Blake2Hasher;
+type TrieLayout = sp_trie::Layout<Hasher>;
+
+/// Build a proof for the chunk selected by `random_hash` out of the given indexed
+/// transactions. Returns `None` if there is no data to prove.
+pub fn build_proof(
+	random_hash: &[u8],
+	transactions: Vec<Vec<u8>>,
+) -> Result<Option<TransactionStorageProof>, Error> {
+	let total_chunks: u32 = transactions.iter().map(|t| num_chunks(t.len() as u32)).sum();
+	if total_chunks == 0 {
+		return Ok(None)
+	}
+
+	let mut target_chunk_index = random_chunk(random_hash, total_chunks);
+	let transaction = transactions.into_iter()
+		.find(|t| {
+			let chunks = num_chunks(t.len() as u32);
+			if target_chunk_index < chunks {
+				true
+			} else {
+				target_chunk_index -= chunks;
+				false
+			}
+		})
+		.expect("The selected chunk is less than the total number of chunks; qed");
+
+	let mut db = sp_trie::MemoryDB::<Hasher>::default();
+	let mut root = sp_trie::empty_trie_root::<TrieLayout>();
+	let mut target_chunk = None;
+	{
+		let mut trie = sp_trie::TrieDBMut::<TrieLayout>::new(&mut db, &mut root);
+		for (index, chunk) in transaction.chunks(CHUNK_SIZE).enumerate() {
+			let index = index as u32;
+			trie.insert(&encode_index(index), chunk)
+				.map_err(|_| Error::from("Failed to build the chunk trie"))?;
+			if index == target_chunk_index {
+				target_chunk = Some(chunk.to_vec());
+			}
+		}
+	}
+
+	let proof = sp_trie::generate_trie_proof::<TrieLayout, _, _, _>(
+		&db,
+		root,
+		&[encode_index(target_chunk_index)],
+	).map_err(|_| Error::from("Failed to generate the chunk proof"))?;
+
+	Ok(Some(TransactionStorageProof {
+		chunk: target_chunk.expect("The target chunk is within the transaction; qed"),
+		proof,
+	}))
+}
+
+#[cfg(feature = "std")]
+pub mod registration {
+	//! Building of storage proofs on the client side.
+
+	use sp_api::ProvideRuntimeApi;
+	use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Saturating, Zero, One}};
+	use super::*;
+
+	/// Create a new inherent data provider for the block built on top of `parent`.
+	///
+	/// The storage period is read from the runtime at `parent`.
+	pub fn new_data_provider<B, C>(
+		client: &C,
+		parent: &B::Hash,
+	) -> Result<InherentDataProvider, Error>
+		where
+			B: BlockT,
+			C: IndexedBody<B> + ProvideRuntimeApi<B>,
+			C::Api: TransactionStorageApi<B>,
+	{
+		let storage_period = client.runtime_api()
+			.storage_period(&BlockId::Hash(parent.clone()))
+			.map_err(|e| Error::from(format!("Failed to read the storage period: {:?}", e)))?;
+		let parent_number = client.number(parent.clone())?.unwrap_or(Zero::zero());
+		let number = parent_number
+			.saturating_add(One::one())
+			.saturating_sub(storage_period);
+		if number.is_zero() {
+			// Too early to collect proofs.
+			return Ok(InherentDataProvider::new(None))
+		}
+
+		let proof = match client.block_indexed_body(number)? {
+			Some(transactions) => build_proof(parent.as_ref(), transactions)?,
+			None => None,
+		};
+
+		Ok(InherentDataProvider::new(proof))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn build_proof_check() {
+		use sp_trie::TrieConfiguration;
+
+		let transactions = vec![vec![1u8; CHUNK_SIZE * 2], vec![2u8; CHUNK_SIZE + 1]];
+		// Select the last of the four chunks, which belongs to the second transaction.
+		let mut random = [0u8; 32];
+		random[7] = 3;
+		let proof = build_proof(&random, transactions.clone()).unwrap().unwrap();
+		assert_eq!(proof.chunk, vec![2u8]);
+
+		let root = TrieLayout::ordered_trie_root(transactions[1].chunks(CHUNK_SIZE));
+		assert!(sp_trie::verify_trie_proof::<TrieLayout, _, _, _>(
+			&root,
+			&proof.proof,
+			&[(encode_index(1), Some(&proof.chunk))],
+		).is_ok());
+	}
+}
@@ -18,6 +18,7 @@
 use sp_std::vec::Vec;
 use codec::{Encode, Decode};
 use hash_db::{Hasher, HashDB};
+use crate::Layout;
 
 /// A proof that some set of key-value pairs are included in the storage trie. The proof contains
 /// the storage values so that the partial storage backend can be reconstructed by a verifier that
@@ -31,6 +32,16 @@ pub struct StorageProof {
 	trie_nodes: Vec<Vec<u8>>,
 }
 
+/// Storage proof in compact form.
+///
+/// Contains the same trie nodes as a [`StorageProof`], but nodes that can be recomputed by the
+/// verifier (e.g. hashes of child nodes that are part of the proof) are omitted.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+pub struct CompactProof {
+	/// The compact encoded trie nodes.
+	pub encoded_nodes: Vec<Vec<u8>>,
+}
+
 impl StorageProof {
 	/// Constructs a storage proof from a subset of encoded trie nodes in a storage backend.
 	pub fn new(trie_nodes: Vec<Vec<u8>>) -> Self {
@@ -75,6 +86,53 @@ impl StorageProof {
 
 		Self { trie_nodes }
 	}
+
+	/// Encode as a compact proof, for the trie with the given `root`.
+	pub fn into_compact_proof<H: Hasher>(
+		self,
+		root: H::Out,
+	) -> Result<CompactProof, crate::CompactProofError<Layout<H>>> {
+		crate::encode_compact::<Layout<H>>(self, root)
+	}
+
+	/// Returns the estimated encoded size of the compact proof.
+	///
+	/// This builds the whole compact proof, so it is slow and should only be used
+	/// outside of performance sensitive paths.
+	/// Return `None` on error.
+	pub fn encoded_compact_size<H: Hasher>(self, root: H::Out) -> Option<usize> {
+		let compact_proof = self.into_compact_proof::<H>(root);
+		compact_proof.ok().map(|p| p.encoded_size())
+	}
+}
+
+impl CompactProof {
+	/// Return an iterator on the compact encoded nodes.
+	pub fn iter_compact_encoded_nodes(&self) -> impl Iterator<Item = &[u8]> {
+		self.encoded_nodes.iter().map(Vec::as_slice)
+	}
+
+	/// Decode to a full storage proof, checking it against the `expected_root` if one is given.
+	///
+	/// Returns the storage proof and the root of the trie it was built from.
+	pub fn to_storage_proof<H: Hasher>(
+		&self,
+		expected_root: Option<&H::Out>,
+	) -> Result<(StorageProof, H::Out), crate::CompactProofError<Layout<H>>> {
+		let mut db = crate::MemoryDB::<H>::default();
+		let root = crate::decode_compact::<Layout<H>, _, _>(
+			&mut db,
+			self.iter_compact_encoded_nodes(),
+			expected_root,
+		)?;
+		Ok((StorageProof::new(db.drain().into_iter().filter_map(|kv|
+			if (kv.1).1 > 0 {
+				Some((kv.1).0)
+			} else {
+				None
+			}
+		).collect()), root))
+	}
 }
 
 /// An iterator over trie nodes constructed from a storage proof. The nodes are not guaranteed to
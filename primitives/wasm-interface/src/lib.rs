@@ -290,6 +290,11 @@ pub trait FunctionContext {
 	fn deallocate_memory(&mut self, ptr: Pointer<u8>) -> Result<()>;
 	/// Provides access to the sandbox.
 	fn sandbox(&mut self) -> &mut dyn Sandbox;
+	/// Registers the message of a panic that occurred inside the runtime.
+	///
+	/// The runtime traps right after calling this. The executor should then report this message
+	/// instead of the generic trap.
+	fn register_panic_error_message(&mut self, message: &str);
 }
 
 /// Sandbox memory identifier.
@@ -550,6 +550,7 @@ impl pallet_babe::Config for Runtime {
 
 	type HandleEquivocation = ();
 
+	type DisabledValidators = ();
 	type WeightInfo = ();
 }
 
@@ -627,6 +628,14 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_version: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn metadata_versions() -> Vec<u32> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -769,6 +778,9 @@ cfg_if! {
 						AuraId::from(authority)
 					}).collect()
 				}
+				fn current_slot() -> sp_consensus_aura::Slot {
+					<pallet_babe::Module<Runtime>>::current_slot()
+				}
 			}
 
 			impl sp_consensus_babe::BabeApi<Block> for Runtime {
@@ -811,6 +823,10 @@ cfg_if! {
 				) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
 					None
 				}
+
+				fn disabled_validators() -> Vec<sp_consensus_babe::AuthorityIndex> {
+					Vec::new()
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
@@ -881,6 +897,14 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_version: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
+
+				fn metadata_versions() -> Vec<u32> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -1027,6 +1051,9 @@ cfg_if! {
 						AuraId::from(authority)
 					}).collect()
 				}
+				fn current_slot() -> sp_consensus_aura::Slot {
+					<pallet_babe::Module<Runtime>>::current_slot()
+				}
 			}
 
 			impl sp_consensus_babe::BabeApi<Block> for Runtime {
@@ -1069,6 +1096,10 @@ cfg_if! {
 				) -> Option<sp_consensus_babe::OpaqueKeyOwnershipProof> {
 					None
 				}
+
+				fn disabled_validators() -> Vec<sp_consensus_babe::AuthorityIndex> {
+					Vec::new()
+				}
 			}
 
 			impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
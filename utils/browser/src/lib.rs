@@ -96,6 +96,7 @@ where
 		state_pruning: Default::default(),
 		keep_blocks: KeepBlocks::All,
 		transaction_storage: TransactionStorageMode::BlockBody,
+		db_storage_threshold: 0,
 		rpc_cors: Default::default(),
 		rpc_http: Default::default(),
 		rpc_ipc: Default::default(),
@@ -109,11 +110,13 @@ where
 		transaction_pool: Default::default(),
 		wasm_method: Default::default(),
 		wasm_runtime_overrides: Default::default(),
+		wasm_runtime_cache_path: Default::default(),
 		max_runtime_instances: 8,
 		announce_block: true,
 		base_path: None,
 		informant_output_format: sc_informant::OutputFormat {
 			enable_color: false,
+			structured: false,
 		},
 		disable_log_reloading: false,
 	};
@@ -16,11 +16,20 @@
 // limitations under the License.
 
 mod command;
+mod machine;
+mod overhead;
+mod stats;
+mod storage;
 mod writer;
 
 use sc_cli::{ExecutionStrategy, WasmExecutionMethod};
 use std::fmt::Debug;
 
+pub use machine::{MachineCmd, Metric, Requirement, Requirements};
+pub use overhead::{ExtrinsicBuilder, OverheadCmd};
+pub use stats::Stats;
+pub use storage::StorageCmd;
+
 // Add a more relaxed parsing for pallet names by allowing pallet directory names with `-` to be used
 // like crate names with `_`
 fn parse_pallet_name(pallet: &str) -> String {
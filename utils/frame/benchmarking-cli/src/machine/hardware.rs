@@ -0,0 +1,281 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the hardware benchmarks and the reference requirements that they are checked against.
+
+use serde::{Deserialize, Serialize};
+use sp_core::{sr25519, Pair};
+use std::{
+	fmt,
+	fs::{self, File},
+	io::{Seek, SeekFrom, Write},
+	path::Path,
+	time::{Duration, Instant},
+};
+
+/// Size of the data processed by the CPU benchmarks per iteration.
+const CPU_CHUNK_SIZE: usize = 32 * 1024;
+/// Size of the buffer copied by the memory benchmark.
+const MEMORY_SIZE: usize = 64 * 1024 * 1024;
+/// Size of the file written by the disk benchmarks.
+const DISK_FILE_SIZE: usize = 64 * 1024 * 1024;
+/// Size of a single write of the sequential disk benchmark.
+const DISK_SEQ_CHUNK_SIZE: usize = 1024 * 1024;
+/// Size of a single write of the random disk benchmark.
+const DISK_RND_CHUNK_SIZE: usize = 4 * 1024;
+/// Size of a message signed and verified by the SR25519 benchmark.
+const SR25519_MSG_SIZE: usize = 32;
+/// Number of distinct signatures verified by the SR25519 benchmark.
+const SR25519_NUM_SIGS: usize = 128;
+
+/// A hardware metric that can be benchmarked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Metric {
+	/// BLAKE2-256 hashing throughput.
+	Blake2256,
+	/// SR25519 signature verification throughput of 32 byte messages.
+	Sr25519Verify,
+	/// Memory copy throughput.
+	MemCopy,
+	/// Sequential disk write throughput, including `fsync`.
+	DiskSeqWrite,
+	/// Random 4 KiB disk write throughput, including `fsync`.
+	DiskRndWrite,
+}
+
+impl Metric {
+	/// The category of the metric.
+	pub fn category(&self) -> &'static str {
+		match self {
+			Self::Blake2256 | Self::Sr25519Verify => "CPU",
+			Self::MemCopy => "Memory",
+			Self::DiskSeqWrite | Self::DiskRndWrite => "Disk",
+		}
+	}
+
+	/// Human readable name of the metric.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Blake2256 => "BLAKE2-256",
+			Self::Sr25519Verify => "SR25519-Verify",
+			Self::MemCopy => "Copy",
+			Self::DiskSeqWrite => "Seq Write",
+			Self::DiskRndWrite => "Rnd Write",
+		}
+	}
+}
+
+/// The minimal throughput in MiB/s that a machine needs to reach for a [`Metric`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Requirement {
+	/// The benchmarked metric.
+	pub metric: Metric,
+	/// Minimal throughput in MiB/s.
+	pub minimum: f64,
+}
+
+/// The requirements of a machine, checked by the `benchmark-machine` command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Requirements(pub Vec<Requirement>);
+
+impl Default for Requirements {
+	/// The throughput of the reference hardware that the default weights were measured on.
+	fn default() -> Self {
+		Self(vec![
+			Requirement { metric: Metric::Blake2256, minimum: 1000.0 },
+			Requirement { metric: Metric::Sr25519Verify, minimum: 0.6 },
+			Requirement { metric: Metric::MemCopy, minimum: 14000.0 },
+			Requirement { metric: Metric::DiskSeqWrite, minimum: 450.0 },
+			Requirement { metric: Metric::DiskRndWrite, minimum: 200.0 },
+		])
+	}
+}
+
+/// Runs `f` until either `max_iterations` or `max_duration` is reached and returns the
+/// throughput in MiB/s, given that every call of `f` processes `size` bytes.
+fn benchmark<E>(
+	size: usize,
+	max_iterations: usize,
+	max_duration: Duration,
+	mut f: impl FnMut() -> Result<(), E>,
+) -> Result<f64, E> {
+	// Warm up the caches.
+	f()?;
+
+	let timestamp = Instant::now();
+	let mut iterations = 0;
+	while iterations < max_iterations {
+		f()?;
+		iterations += 1;
+		if timestamp.elapsed() >= max_duration {
+			break
+		}
+	}
+
+	let elapsed = timestamp.elapsed().as_secs_f64();
+	Ok((size * iterations) as f64 / (1024.0 * 1024.0) / elapsed.max(f64::EPSILON))
+}
+
+/// Benchmarks the BLAKE2-256 hashing throughput.
+pub fn benchmark_blake2_256(max_duration: Duration) -> f64 {
+	let data = vec![0x42u8; CPU_CHUNK_SIZE];
+	benchmark(CPU_CHUNK_SIZE, usize::MAX, max_duration, || -> Result<(), ()> {
+		let hash = sp_core::hashing::blake2_256(&data);
+		// Prevent the optimizer from removing the hashing.
+		unsafe { std::ptr::read_volatile(&hash) };
+		Ok(())
+	}).expect("hashing cannot fail; qed")
+}
+
+/// Benchmarks the SR25519 signature verification throughput.
+pub fn benchmark_sr25519_verify(max_duration: Duration) -> f64 {
+	let pair = sr25519::Pair::from_seed(&[7u8; 32]);
+	let public = pair.public();
+	let signed = (0..SR25519_NUM_SIGS)
+		.map(|i| {
+			let msg = [i as u8; SR25519_MSG_SIZE];
+			(msg, pair.sign(&msg))
+		})
+		.collect::<Vec<_>>();
+
+	let mut index = 0;
+	benchmark(SR25519_MSG_SIZE, usize::MAX, max_duration, || -> Result<(), ()> {
+		let (msg, sig) = &signed[index % SR25519_NUM_SIGS];
+		index += 1;
+		if sr25519::Pair::verify(sig, msg, &public) {
+			Ok(())
+		} else {
+			Err(())
+		}
+	}).expect("signatures created above are valid; qed")
+}
+
+/// Benchmarks the memory copy throughput.
+pub fn benchmark_memory(max_duration: Duration) -> f64 {
+	let src = vec![0x42u8; MEMORY_SIZE];
+	let mut dst = vec![0u8; MEMORY_SIZE];
+	benchmark(MEMORY_SIZE, usize::MAX, max_duration, || -> Result<(), ()> {
+		dst.copy_from_slice(&src);
+		// Prevent the optimizer from removing the copy.
+		unsafe { std::ptr::read_volatile(&dst[MEMORY_SIZE - 1]) };
+		Ok(())
+	}).expect("copying cannot fail; qed")
+}
+
+/// Benchmarks the sequential write throughput of a file in `directory`.
+pub fn benchmark_disk_sequential_writes(
+	directory: &Path,
+	max_duration: Duration,
+) -> std::io::Result<f64> {
+	let path = directory.join(".disk_bench_seq_wr.tmp");
+	let data = vec![0x42u8; DISK_SEQ_CHUNK_SIZE];
+
+	let result = benchmark(DISK_FILE_SIZE, usize::MAX, max_duration, || {
+		let mut file = File::create(&path)?;
+		for _ in 0..DISK_FILE_SIZE / DISK_SEQ_CHUNK_SIZE {
+			file.write_all(&data)?;
+		}
+		file.sync_all()
+	});
+
+	let _ = fs::remove_file(&path);
+	result
+}
+
+/// Benchmarks the throughput of writes at random positions of a file in `directory`.
+pub fn benchmark_disk_random_writes(
+	directory: &Path,
+	max_duration: Duration,
+) -> std::io::Result<f64> {
+	let path = directory.join(".disk_bench_rand_wr.tmp");
+	let data = vec![0x42u8; DISK_RND_CHUNK_SIZE];
+	let num_chunks = DISK_FILE_SIZE / DISK_RND_CHUNK_SIZE;
+
+	let mut file = File::create(&path)?;
+	file.set_len(DISK_FILE_SIZE as u64)?;
+	file.sync_all()?;
+
+	// Cheap linear congruential generator, only used to spread the writes over the file.
+	let mut position: u64 = 0;
+	let mut next_chunk = move || {
+		position = position.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+		(position >> 33) % num_chunks as u64
+	};
+
+	let result = benchmark(DISK_FILE_SIZE, usize::MAX, max_duration, || {
+		for _ in 0..num_chunks {
+			file.seek(SeekFrom::Start(next_chunk() * DISK_RND_CHUNK_SIZE as u64))?;
+			file.write_all(&data)?;
+		}
+		file.sync_all()
+	});
+
+	drop(file);
+	let _ = fs::remove_file(&path);
+	result
+}
+
+impl fmt::Display for Metric {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {}", self.category(), self.name())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn default_requirements_cover_all_metrics() {
+		let requirements = Requirements::default();
+		for metric in &[
+			Metric::Blake2256,
+			Metric::Sr25519Verify,
+			Metric::MemCopy,
+			Metric::DiskSeqWrite,
+			Metric::DiskRndWrite,
+		] {
+			assert_eq!(requirements.0.iter().filter(|r| r.metric == *metric).count(), 1);
+		}
+	}
+
+	#[test]
+	fn requirements_json_roundtrip_works() {
+		let requirements = Requirements::default();
+		let json = serde_json::to_string(&requirements).unwrap();
+		assert_eq!(serde_json::from_str::<Requirements>(&json).unwrap(), requirements);
+	}
+
+	#[test]
+	fn benchmark_stops_after_max_iterations() {
+		let mut calls = 0;
+		let throughput = benchmark(1024 * 1024, 10, Duration::from_secs(60), || -> Result<(), ()> {
+			calls += 1;
+			Ok(())
+		}).unwrap();
+
+		// One warmup call plus the measured iterations.
+		assert_eq!(calls, 11);
+		assert!(throughput > 0.0);
+	}
+
+	#[test]
+	fn benchmark_stops_at_first_error() {
+		let result = benchmark(1, usize::MAX, Duration::from_secs(60), || Err(()));
+		assert_eq!(result, Err(()));
+	}
+}
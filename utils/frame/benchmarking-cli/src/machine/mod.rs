@@ -0,0 +1,192 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`MachineCmd`] which benchmarks the hardware of the machine and compares the
+//! results with reference requirements.
+
+mod hardware;
+
+use sc_cli::{CliConfiguration, Result, SharedParams};
+use sc_service::Configuration;
+use std::{fs, path::PathBuf, time::Duration};
+use structopt::StructOpt;
+
+pub use hardware::{Metric, Requirement, Requirements};
+
+/// The `benchmark-machine` command used to benchmark the hardware of the machine.
+///
+/// Checks the CPU, memory and disk throughput against the reference requirements and fails if
+/// any of them is not met.
+#[derive(Debug, StructOpt)]
+pub struct MachineCmd {
+	/// Do not return an error if any check fails.
+	///
+	/// Should only be used for debugging.
+	#[structopt(long)]
+	pub allow_fail: bool,
+
+	/// Set a fault tolerance for passing a requirement in percent.
+	///
+	/// 10% means that the test would pass even when only 90% of the required throughput was
+	/// achieved.
+	#[structopt(long, default_value = "10.0")]
+	pub tolerance: f64,
+
+	/// Time limit for each of the benchmarks in seconds.
+	#[structopt(long, default_value = "5.0", parse(try_from_str = parse_duration))]
+	pub duration: Duration,
+
+	/// JSON file with the requirements to check against, instead of the reference hardware.
+	#[structopt(long)]
+	pub requirements: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+/// The result of benchmarking a single [`Requirement`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+	/// The requirement that was checked.
+	pub requirement: Requirement,
+	/// The measured throughput in MiB/s.
+	pub score: f64,
+	/// Whether the score met the requirement, including the tolerance.
+	pub passed: bool,
+}
+
+impl MachineCmd {
+	/// Benchmark the hardware and check the results against the requirements.
+	///
+	/// The disk benchmarks write temporary files to the database directory of `config`.
+	pub fn run(&self, config: &Configuration) -> Result<()> {
+		self.validate_args()?;
+
+		let requirements = match &self.requirements {
+			Some(path) => {
+				let content = fs::read_to_string(path)?;
+				serde_json::from_str::<Requirements>(&content)
+					.map_err(|e| format!("Invalid requirements file: {}", e))?
+			},
+			None => Requirements::default(),
+		};
+
+		let dir = config.database.path()
+			.ok_or("Disk benchmarks require a database path")?
+			.to_path_buf();
+		fs::create_dir_all(&dir)?;
+
+		let results = requirements.0.iter()
+			.map(|requirement| self.run_benchmark(requirement, &dir))
+			.collect::<Result<Vec<_>>>()?;
+
+		println!("{:<8} {:<16} {:>14} {:>14} {:>8}", "Category", "Function", "Score", "Minimum", "Result");
+		for result in &results {
+			println!(
+				"{:<8} {:<16} {:>9.2} MiB/s {:>9.2} MiB/s {:>8}",
+				result.requirement.metric.category(),
+				result.requirement.metric.name(),
+				result.score,
+				result.requirement.minimum,
+				if result.passed { "✅ Pass" } else { "❌ Fail" },
+			);
+		}
+
+		let failed = results.iter().filter(|r| !r.passed).count();
+		if failed == 0 {
+			println!("The hardware meets the requirements.");
+			Ok(())
+		} else if self.allow_fail {
+			println!("The hardware fails to meet {} requirement(s), ignoring.", failed);
+			Ok(())
+		} else {
+			Err(format!("The hardware fails to meet {} requirement(s).", failed).into())
+		}
+	}
+
+	/// Benchmark the metric of `requirement` and check the score.
+	fn run_benchmark(&self, requirement: &Requirement, dir: &PathBuf) -> Result<BenchResult> {
+		let score = match requirement.metric {
+			Metric::Blake2256 => hardware::benchmark_blake2_256(self.duration),
+			Metric::Sr25519Verify => hardware::benchmark_sr25519_verify(self.duration),
+			Metric::MemCopy => hardware::benchmark_memory(self.duration),
+			Metric::DiskSeqWrite => hardware::benchmark_disk_sequential_writes(dir, self.duration)?,
+			Metric::DiskRndWrite => hardware::benchmark_disk_random_writes(dir, self.duration)?,
+		};
+
+		Ok(BenchResult {
+			requirement: *requirement,
+			score,
+			passed: passes(score, requirement.minimum, self.tolerance),
+		})
+	}
+
+	fn validate_args(&self) -> Result<()> {
+		if !(0.0..=100.0).contains(&self.tolerance) {
+			return Err("The tolerance must be in the range of 0 to 100".into())
+		}
+		Ok(())
+	}
+}
+
+/// Parse a positive number of seconds.
+///
+/// Rejects values that `Duration::from_secs_f64` would panic on.
+fn parse_duration(s: &str) -> std::result::Result<Duration, String> {
+	let secs = s.parse::<f64>().map_err(|e| e.to_string())?;
+	if !(secs > 0.0 && secs <= u32::max_value() as f64) {
+		return Err(format!(
+			"The duration must be positive and at most {} seconds",
+			u32::max_value(),
+		))
+	}
+	Ok(Duration::from_secs_f64(secs))
+}
+
+/// Whether `score` reaches `minimum` with a `tolerance` given in percent.
+fn passes(score: f64, minimum: f64, tolerance: f64) -> bool {
+	score >= minimum * (1.0 - tolerance / 100.0)
+}
+
+impl CliConfiguration for MachineCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn passes_respects_tolerance() {
+		assert!(passes(100.0, 100.0, 0.0));
+		assert!(!passes(99.0, 100.0, 0.0));
+		assert!(passes(90.0, 100.0, 10.0));
+		assert!(!passes(89.0, 100.0, 10.0));
+		assert!(passes(0.0, 100.0, 100.0));
+	}
+
+	#[test]
+	fn parse_duration_rejects_invalid_seconds() {
+		assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+		for invalid in &["0", "-1", "NaN", "inf", "1e300", "five"] {
+			assert!(parse_duration(invalid).is_err(), "{} was accepted", invalid);
+		}
+	}
+}
@@ -0,0 +1,178 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Builds the blocks of the overhead benchmark and measures their execution time.
+
+use sc_block_builder::BlockBuilderProvider;
+use sc_cli::Result;
+use sc_client_api::Backend as ClientBackend;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::{ApplyExtrinsicFailed, Error};
+use sp_inherents::InherentData;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+use std::{marker::PhantomData, sync::Arc, time::Instant};
+
+use super::{ExtrinsicBuilder, OverheadCmd};
+use crate::stats::Stats;
+
+/// The type of the overhead benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BenchmarkType {
+	/// Measures the execution time of an empty block.
+	Block,
+	/// Measures the execution time of a `System::remark` extrinsic, minus the block overhead.
+	Extrinsic,
+}
+
+impl BenchmarkType {
+	/// Short name of the benchmark, used as file name.
+	pub(crate) fn short_name(&self) -> &'static str {
+		match self {
+			Self::Block => "block",
+			Self::Extrinsic => "extrinsic",
+		}
+	}
+
+	/// Name of the weight constant that is generated by the benchmark.
+	pub(crate) fn long_name(&self) -> &'static str {
+		match self {
+			Self::Block => "BlockExecutionWeight",
+			Self::Extrinsic => "ExtrinsicBaseWeight",
+		}
+	}
+}
+
+/// Execution times of the benchmark runs in nanoseconds.
+type BenchRecord = Vec<u64>;
+
+/// Builds and executes the blocks of the overhead benchmark.
+pub(crate) struct Benchmark<Block, BA, C> {
+	client: Arc<C>,
+	warmup: u32,
+	repeat: u32,
+	max_ext_per_block: u32,
+	inherent_data: InherentData,
+	_p: PhantomData<(Block, BA)>,
+}
+
+impl<Block, BA, C> Benchmark<Block, BA, C>
+where
+	Block: BlockT,
+	BA: ClientBackend<Block>,
+	C: BlockBuilderProvider<BA, Block, C> + ProvideRuntimeApi<Block>,
+	C::Api: ApiExt<Block, StateBackend = BA::State> + BlockBuilderApi<Block>,
+{
+	/// Create a new benchmark with the parameters of `cmd`.
+	pub(crate) fn new(client: Arc<C>, cmd: &OverheadCmd, inherent_data: InherentData) -> Self {
+		Self {
+			client,
+			warmup: cmd.warmup,
+			repeat: cmd.repeat,
+			max_ext_per_block: cmd.max_ext_per_block,
+			inherent_data,
+			_p: PhantomData,
+		}
+	}
+
+	/// Run the benchmark of the given type and return the statistics of its execution times.
+	pub(crate) fn bench(
+		&self,
+		bench_type: BenchmarkType,
+		ext_builder: Option<&dyn ExtrinsicBuilder<Block>>,
+	) -> Result<Stats> {
+		let (empty_block, _) = self.build_block(None)?;
+		let empty_record = self.measure_block(&empty_block)?;
+
+		let record = match (bench_type, ext_builder) {
+			(BenchmarkType::Block, _) => empty_record,
+			(BenchmarkType::Extrinsic, Some(ext_builder)) => {
+				let (full_block, num_ext) = self.build_block(Some(ext_builder))?;
+				let full_record = self.measure_block(&full_block)?;
+				let block_overhead = Stats::new(&empty_record)?.median;
+
+				full_record.into_iter()
+					.map(|t| t.saturating_sub(block_overhead) / num_ext as u64)
+					.collect()
+			},
+			(BenchmarkType::Extrinsic, None) =>
+				return Err("The extrinsic benchmark requires an extrinsic builder".into()),
+		};
+
+		Stats::new(&record).map_err(Into::into)
+	}
+
+	/// Build a block containing the inherents and, if `ext_builder` is given, as many remarks as
+	/// fit into the block or `max_ext_per_block`.
+	///
+	/// Returns the block and the number of remarks in it.
+	fn build_block(
+		&self,
+		ext_builder: Option<&dyn ExtrinsicBuilder<Block>>,
+	) -> Result<(Block, u32)> {
+		let mut builder = self.client.new_block(Default::default())?;
+		for inherent in builder.create_inherents(self.inherent_data.clone())? {
+			builder.push(inherent)?;
+		}
+
+		let mut num_ext = 0;
+		if let Some(ext_builder) = ext_builder {
+			for nonce in 0..self.max_ext_per_block {
+				match builder.push(ext_builder.remark(nonce)?) {
+					Ok(()) => num_ext += 1,
+					Err(Error::ApplyExtrinsicFailed(ApplyExtrinsicFailed::Validity(
+						TransactionValidityError::Invalid(InvalidTransaction::ExhaustsResources)
+					))) => break,
+					Err(e) => return Err(e.into()),
+				}
+			}
+			if num_ext == 0 {
+				return Err("A block cannot fit a single remark".into())
+			}
+			log::info!("Remarks per block: {}", num_ext);
+		}
+
+		Ok((builder.build()?.block, num_ext))
+	}
+
+	/// Execute `block` on top of its parent `warmup + repeat` times and record the execution
+	/// times of the last `repeat` runs.
+	fn measure_block(&self, block: &Block) -> Result<BenchRecord> {
+		let parent = BlockId::Hash(*block.header().parent_hash());
+		let mut record = BenchRecord::new();
+
+		for _ in 0..self.warmup {
+			self.client.runtime_api().execute_block(&parent, block.clone())
+				.map_err(|e| format!("Error executing block: {:?}", e))?;
+		}
+
+		for _ in 0..self.repeat {
+			let block = block.clone();
+			let runtime_api = self.client.runtime_api();
+			let start = Instant::now();
+			runtime_api.execute_block(&parent, block)
+				.map_err(|e| format!("Error executing block: {:?}", e))?;
+			record.push(start.elapsed().as_nanos() as u64);
+		}
+
+		Ok(record)
+	}
+}
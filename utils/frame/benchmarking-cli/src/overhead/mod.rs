@@ -0,0 +1,138 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`OverheadCmd`] which measures the execution overhead of blocks and extrinsics.
+//!
+//! The results are written as `block_weights.rs` and `extrinsic_weights.rs` files containing the
+//! `BlockExecutionWeight` and `ExtrinsicBaseWeight` constants of the machine that ran them.
+
+mod bench;
+mod template;
+
+use sc_block_builder::BlockBuilderProvider;
+use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
+use sc_client_api::Backend as ClientBackend;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_inherents::InherentData;
+use sp_runtime::traits::Block as BlockT;
+use std::{fmt::Debug, path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+use bench::{Benchmark, BenchmarkType};
+use template::TemplateData;
+
+/// Creates the extrinsics that are executed by the extrinsic overhead benchmark.
+pub trait ExtrinsicBuilder<Block: BlockT> {
+	/// Returns a signed `System::remark` extrinsic with the given `nonce`.
+	///
+	/// The signer must be funded in the genesis of the benchmarked chain.
+	fn remark(&self, nonce: u32) -> std::result::Result<Block::Extrinsic, &'static str>;
+}
+
+/// The `benchmark-overhead` command used to measure the execution overhead of blocks and
+/// extrinsics.
+#[derive(Debug, StructOpt)]
+pub struct OverheadCmd {
+	/// Rounds of warmups before measuring.
+	#[structopt(long, default_value = "10")]
+	pub warmup: u32,
+
+	/// How many times the benchmark should be repeated.
+	#[structopt(long, default_value = "100")]
+	pub repeat: u32,
+
+	/// Maximum number of extrinsics put into a block by the extrinsic benchmark.
+	#[structopt(long, default_value = "10000")]
+	pub max_ext_per_block: u32,
+
+	/// Path of the directory to write the `block_weights.rs` and `extrinsic_weights.rs` files to.
+	///
+	/// Defaults to the current directory.
+	#[structopt(long)]
+	pub weight_path: Option<PathBuf>,
+
+	/// Add a header file to the generated weight files.
+	#[structopt(long)]
+	pub header: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl OverheadCmd {
+	/// Measure the execution time of an empty block and of a block full of `System::remark`
+	/// extrinsics created by `ext_builder`, and write the resulting weights.
+	///
+	/// The blocks are built on top of the best block of `client` and are never imported.
+	pub fn run<Block, BA, C>(
+		&self,
+		client: Arc<C>,
+		inherent_data: InherentData,
+		ext_builder: &dyn ExtrinsicBuilder<Block>,
+	) -> Result<()>
+	where
+		Block: BlockT,
+		BA: ClientBackend<Block>,
+		C: BlockBuilderProvider<BA, Block, C> + ProvideRuntimeApi<Block>,
+		C::Api: ApiExt<Block, StateBackend = BA::State> + BlockBuilderApi<Block>,
+	{
+		let weight_path = self.weight_path.clone().unwrap_or_else(|| PathBuf::from("."));
+		if !weight_path.is_dir() {
+			return Err("The weight path must be a directory".into())
+		}
+		if let Some(header_file) = &self.header {
+			if !header_file.is_file() {
+				return Err("Header file is invalid!".into())
+			}
+		}
+		if self.repeat == 0 {
+			return Err("At least one repetition is required".into())
+		}
+
+		let bench = Benchmark::new(client, self, inherent_data);
+
+		// Per-block execution overhead.
+		let block_stats = bench.bench(BenchmarkType::Block, None)?;
+		println!("Per-block execution overhead [ns]:\n{}", block_stats);
+		let template = TemplateData::new(BenchmarkType::Block, self, &block_stats)?;
+		template.write(&self.header, &weight_path)?;
+
+		// Per-extrinsic execution overhead, relative to an empty block.
+		let ext_stats = bench.bench(BenchmarkType::Extrinsic, Some(ext_builder))?;
+		println!("Per-extrinsic execution overhead [ns]:\n{}", ext_stats);
+		let template = TemplateData::new(BenchmarkType::Extrinsic, self, &ext_stats)?;
+		template.write(&self.header, &weight_path)?;
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for OverheadCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
@@ -0,0 +1,100 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Converts the results of the overhead benchmark into a weight file.
+
+use sc_cli::Result;
+use serde::Serialize;
+use std::{fs, path::{Path, PathBuf}};
+
+use super::{bench::BenchmarkType, OverheadCmd};
+use crate::{stats::Stats, writer::UnderscoreHelper};
+
+static VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static TEMPLATE: &str = include_str!("./weights.hbs");
+
+/// Data consumed by Handlebar to fill out the `weights.hbs` template.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct TemplateData {
+	/// Short name of the benchmark. Can be "block" or "extrinsic".
+	short_name: String,
+	/// Name of the weight constant. Can be "BlockExecutionWeight" or "ExtrinsicBaseWeight".
+	long_name: String,
+	/// Version of the benchmarking CLI used.
+	version: String,
+	/// Date that the template was filled out.
+	date: String,
+	/// Command line arguments that were passed to the CLI.
+	args: Vec<String>,
+	/// Params of the executed command.
+	params: TemplateParams,
+	/// Stats about the benchmark result.
+	stats: Stats,
+	/// The resulting weight in nanoseconds.
+	weight: u64,
+	/// Content of the header file, if any.
+	header: String,
+}
+
+/// Parameters of the executed command that are shown in the weight file.
+#[derive(Serialize, Debug, Clone)]
+struct TemplateParams {
+	warmup: u32,
+	repeat: u32,
+	weight_path: String,
+}
+
+impl TemplateData {
+	/// Returns a new [`Self`] from the given params.
+	pub(crate) fn new(t: BenchmarkType, cmd: &OverheadCmd, stats: &Stats) -> Result<Self> {
+		Ok(TemplateData {
+			short_name: t.short_name().into(),
+			long_name: t.long_name().into(),
+			version: VERSION.into(),
+			date: chrono::Utc::now().format("%Y-%m-%d (Y/M/D)").to_string(),
+			args: std::env::args().collect::<Vec<String>>(),
+			params: TemplateParams {
+				warmup: cmd.warmup,
+				repeat: cmd.repeat,
+				weight_path: cmd.weight_path.as_ref()
+					.map(|p| p.display().to_string())
+					.unwrap_or_else(|| ".".into()),
+			},
+			stats: stats.clone(),
+			weight: stats.median,
+			header: String::new(),
+		})
+	}
+
+	/// Fill out the `weights.hbs` template and write it to `<weight_path>/<short_name>_weights.rs`.
+	pub(crate) fn write(mut self, header: &Option<PathBuf>, weight_path: &Path) -> Result<()> {
+		if let Some(header_file) = header {
+			self.header = fs::read_to_string(header_file)?;
+		}
+
+		let mut handlebars = handlebars::Handlebars::new();
+		handlebars.register_helper("underscore", Box::new(UnderscoreHelper));
+		// Don't HTML escape any characters.
+		handlebars.register_escape_fn(|s| -> String { s.to_string() });
+
+		let out_path = weight_path.join(format!("{}_weights.rs", self.short_name));
+		let mut fd = fs::File::create(&out_path)?;
+		println!("Writing weights to {:?}", out_path);
+		handlebars.render_template_to_write(TEMPLATE, &self, &mut fd)
+			.map_err(|e| format!("HBS template write: {:?}", e).into())
+	}
+}
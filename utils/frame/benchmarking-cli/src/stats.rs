@@ -0,0 +1,120 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics over the execution times recorded by the overhead, machine and storage benchmarks.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Statistics over a series of measurements.
+#[derive(Serialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+	/// Number of measurements.
+	pub count: usize,
+	/// Minimum value.
+	pub min: u64,
+	/// Maximum value.
+	pub max: u64,
+	/// Average value.
+	pub avg: u64,
+	/// Median value.
+	pub median: u64,
+	/// 99th percentile.
+	pub p99: u64,
+	/// Standard deviation.
+	pub stddev: u64,
+}
+
+impl Stats {
+	/// Calculates the statistics of the given measurements.
+	///
+	/// Returns an error if `xs` is empty.
+	pub fn new(xs: &[u64]) -> Result<Self, &'static str> {
+		if xs.is_empty() {
+			return Err("Empty input is invalid")
+		}
+
+		let mut xs = xs.to_vec();
+		xs.sort_unstable();
+
+		let count = xs.len();
+		let sum = xs.iter().map(|x| *x as u128).sum::<u128>();
+		let avg = (sum / count as u128) as u64;
+		let variance = xs.iter()
+			.map(|x| {
+				let diff = *x as f64 - avg as f64;
+				diff * diff
+			})
+			.sum::<f64>() / count as f64;
+
+		Ok(Self {
+			count,
+			min: xs[0],
+			max: xs[count - 1],
+			avg,
+			median: Self::percentile(&xs, 0.5),
+			p99: Self::percentile(&xs, 0.99),
+			stddev: variance.sqrt() as u64,
+		})
+	}
+
+	/// Returns the value at the given percentile of the sorted `xs`.
+	fn percentile(xs: &[u64], p: f64) -> u64 {
+		let index = ((xs.len() as f64 * p).ceil() as usize).saturating_sub(1);
+		xs[index.min(xs.len() - 1)]
+	}
+}
+
+impl fmt::Display for Stats {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"Total: {}, Min: {}, Max: {}, Average: {}, Median: {}, 99th: {}, Stddev: {}",
+			self.count, self.min, self.max, self.avg, self.median, self.p99, self.stddev,
+		)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn stats_of_empty_input_fails() {
+		assert!(Stats::new(&[]).is_err());
+	}
+
+	#[test]
+	fn stats_works() {
+		let stats = Stats::new(&[5, 1, 4, 2, 3]).unwrap();
+
+		assert_eq!(stats.count, 5);
+		assert_eq!(stats.min, 1);
+		assert_eq!(stats.max, 5);
+		assert_eq!(stats.avg, 3);
+		assert_eq!(stats.median, 3);
+		assert_eq!(stats.p99, 5);
+		assert_eq!(stats.stddev, 1);
+	}
+
+	#[test]
+	fn stats_of_single_value_works() {
+		let stats = Stats::new(&[7]).unwrap();
+
+		assert_eq!(stats, Stats { count: 1, min: 7, max: 7, avg: 7, median: 7, p99: 7, stddev: 0 });
+	}
+}
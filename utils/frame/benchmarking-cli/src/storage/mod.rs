@@ -0,0 +1,226 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`StorageCmd`] which measures the cost of reading the storage of the live
+//! database and of writing to a copy of it.
+//!
+//! The results are written as a `db_weights.rs` file containing the `RuntimeDbWeight` constant
+//! for the database that was benchmarked.
+
+mod read;
+mod template;
+mod write;
+
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, Result, SharedParams};
+use sc_client_api::Backend as ClientBackend;
+use sc_client_db::{DatabaseSettings, DatabaseSettingsSrc};
+use sc_service::Configuration;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{fs, io, path::{Path, PathBuf}, sync::Arc};
+use structopt::StructOpt;
+
+use crate::stats::Stats;
+use template::TemplateData;
+
+/// The `benchmark-storage` command used to measure the storage read and write cost of the
+/// database.
+///
+/// A sample of the keys stored at the best block is read and rewritten. The writes go to a
+/// temporary copy of the database, which needs as much free disk space as the database itself.
+/// The state cache should be disabled with `--state-cache-size 0` to measure the database and
+/// not the cache.
+#[derive(Debug, StructOpt)]
+pub struct StorageCmd {
+	/// Maximum number of keys that are read and written.
+	///
+	/// The keys are sampled evenly from all keys of the state of the best block.
+	#[structopt(long, default_value = "10000")]
+	pub sample_size: usize,
+
+	/// Path of the directory to write the `db_weights.rs` file to.
+	///
+	/// Defaults to the current directory.
+	#[structopt(long)]
+	pub weight_path: Option<PathBuf>,
+
+	/// Add a header file to the generated weight file.
+	#[structopt(long)]
+	pub header: Option<PathBuf>,
+
+	/// Size of the state cache in bytes. Should be zero to measure the database itself.
+	#[structopt(long = "state-cache-size", value_name = "Bytes", default_value = "0")]
+	pub state_cache_size: usize,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl StorageCmd {
+	/// Measure the storage read and write cost of the database of `backend` and write the
+	/// resulting weights.
+	///
+	/// Only reads are done on the database of `backend`, writes go to a temporary copy of it.
+	pub fn run<Block>(
+		&self,
+		config: Configuration,
+		backend: Arc<sc_client_db::Backend<Block>>,
+	) -> Result<()>
+	where
+		Block: BlockT,
+	{
+		let weight_path = self.weight_path.clone().unwrap_or_else(|| PathBuf::from("."));
+		if !weight_path.is_dir() {
+			return Err("The weight path must be a directory".into())
+		}
+		if let Some(header_file) = &self.header {
+			if !header_file.is_file() {
+				return Err("Header file is invalid!".into())
+			}
+		}
+		if self.sample_size == 0 {
+			return Err("The sample size must be positive".into())
+		}
+		let db_name = db_name(&config.database)?;
+
+		let best_hash = backend.blockchain().info().best_hash;
+		let state = backend.state_at(BlockId::Hash(best_hash))?;
+		let pairs = read::sample_pairs::<Block, _>(&state, self.sample_size)?;
+		log::info!("Benchmarking {} keys of block {:?}", pairs.len(), best_hash);
+
+		let read_stats = Stats::new(&read::bench_read::<Block, _>(&state, &pairs)?)?;
+		println!("Time to read a storage item [ns]:\n{}", read_stats);
+
+		// The copy is dropped before the directory holding it is removed.
+		let scratch = tempfile::tempdir()?;
+		let copy = open_copy::<Block>(&config, scratch.path())?;
+		let copy_state = copy.state_at(BlockId::Hash(best_hash))?;
+		let (db, column) = copy.expose_db();
+		let ref_counted = config.database.supports_ref_counting();
+		let write_stats = Stats::new(
+			&write::bench_write::<Block, _>(&copy_state, &pairs, &*db, column, ref_counted)?,
+		)?;
+		println!("Time to write a storage item [ns]:\n{}", write_stats);
+
+		let template = TemplateData::new(db_name, self, &read_stats, &write_stats);
+		template.write(&self.header, &weight_path)
+	}
+}
+
+/// Copy the database of `config` into `dir` and open the copy.
+///
+/// The copy is opened with the same database backend and pruning settings as the original.
+fn open_copy<Block: BlockT>(
+	config: &Configuration,
+	dir: &Path,
+) -> Result<Arc<sc_client_db::Backend<Block>>> {
+	/// Same as the delay used by the service when opening the backend.
+	const CANONICALIZATION_DELAY: u64 = 4096;
+
+	let path = config.database.path().ok_or("Cannot benchmark a database without a path")?;
+	copy_dir(path, dir)?;
+
+	let source = match &config.database {
+		source if source.supports_ref_counting() =>
+			DatabaseSettingsSrc::ParityDb { path: dir.to_path_buf() },
+		DatabaseSettingsSrc::RocksDb { cache_size, .. } |
+		DatabaseSettingsSrc::Auto { cache_size, .. } =>
+			DatabaseSettingsSrc::RocksDb { path: dir.to_path_buf(), cache_size: *cache_size },
+		_ => return Err("Cannot benchmark a custom database".into()),
+	};
+	let settings = DatabaseSettings {
+		state_cache_size: 0,
+		state_cache_child_ratio: None,
+		state_pruning: config.state_pruning.clone(),
+		source,
+		keep_blocks: config.keep_blocks.clone(),
+		transaction_storage: config.transaction_storage.clone(),
+	};
+	Ok(Arc::new(sc_client_db::Backend::new(settings, CANONICALIZATION_DELAY)?))
+}
+
+/// Recursively copy the contents of the directory `from` into `to`.
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+	fs::create_dir_all(to)?;
+	for entry in fs::read_dir(from)? {
+		let entry = entry?;
+		let target = to.join(entry.file_name());
+		if entry.file_type()?.is_dir() {
+			copy_dir(&entry.path(), &target)?;
+		} else {
+			fs::copy(entry.path(), target)?;
+		}
+	}
+	Ok(())
+}
+
+/// Name of the database backend that `source` resolves to.
+///
+/// `Auto` is resolved to the database that was opened, i.e. the one that exists on disk.
+fn db_name(source: &DatabaseSettingsSrc) -> Result<&'static str> {
+	match source {
+		DatabaseSettingsSrc::Custom(_) => Err("Cannot benchmark a custom database".into()),
+		source if source.supports_ref_counting() => Ok("ParityDb"),
+		_ => Ok("RocksDb"),
+	}
+}
+
+impl CliConfiguration for StorageCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn state_cache_size(&self) -> sc_cli::Result<usize> {
+		Ok(self.state_cache_size)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn copy_dir_copies_nested_files() {
+		let from = tempfile::tempdir().unwrap();
+		fs::create_dir(from.path().join("col")).unwrap();
+		fs::write(from.path().join("CURRENT"), b"current").unwrap();
+		fs::write(from.path().join("col").join("data"), b"data").unwrap();
+
+		let to = tempfile::tempdir().unwrap();
+		copy_dir(from.path(), &to.path().join("copy")).unwrap();
+
+		assert_eq!(fs::read(to.path().join("copy").join("CURRENT")).unwrap(), b"current");
+		assert_eq!(fs::read(to.path().join("copy").join("col").join("data")).unwrap(), b"data");
+	}
+}
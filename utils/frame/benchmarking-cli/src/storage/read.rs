@@ -0,0 +1,72 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Samples the keys of the state and measures the time to read them.
+
+use sc_cli::Result;
+use sp_runtime::traits::Block as BlockT;
+use sp_state_machine::Backend as StateBackend;
+use std::time::Instant;
+
+/// Key-value pairs of the state that are benchmarked.
+pub(crate) type Pairs = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Returns up to `sample_size` key-value pairs, spread evenly over all keys of `state`.
+pub(crate) fn sample_pairs<Block, S>(state: &S, sample_size: usize) -> Result<Pairs>
+where
+	Block: BlockT,
+	S: StateBackend<sp_runtime::traits::HashFor<Block>>,
+{
+	let keys = state.keys(&[]);
+	if keys.is_empty() {
+		return Err("The state does not contain any keys".into())
+	}
+	let step = (keys.len() / sample_size).max(1);
+
+	keys.into_iter()
+		.step_by(step)
+		.take(sample_size)
+		.map(|key| {
+			let value = state.storage(&key)
+				.map_err(|e| format!("Error reading storage: {}", e))?
+				.ok_or("Listed key has no value")?;
+			Ok((key, value))
+		})
+		.collect()
+}
+
+/// Reads every key of `pairs` once and returns the time of each read in nanoseconds.
+pub(crate) fn bench_read<Block, S>(state: &S, pairs: &Pairs) -> Result<Vec<u64>>
+where
+	Block: BlockT,
+	S: StateBackend<sp_runtime::traits::HashFor<Block>>,
+{
+	let mut record = Vec::with_capacity(pairs.len());
+
+	for (key, original) in pairs {
+		let start = Instant::now();
+		let value = state.storage(key)
+			.map_err(|e| format!("Error reading storage: {}", e))?;
+		record.push(start.elapsed().as_nanos() as u64);
+
+		if value.as_ref() != Some(original) {
+			return Err("The value of a read key changed".into())
+		}
+	}
+
+	Ok(record)
+}
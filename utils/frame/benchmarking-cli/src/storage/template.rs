@@ -0,0 +1,101 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Converts the results of the storage benchmark into a weight file.
+
+use sc_cli::Result;
+use serde::Serialize;
+use std::{fs, path::{Path, PathBuf}};
+
+use super::StorageCmd;
+use crate::{stats::Stats, writer::UnderscoreHelper};
+
+static VERSION: &'static str = env!("CARGO_PKG_VERSION");
+static TEMPLATE: &str = include_str!("./weights.hbs");
+
+/// Data consumed by Handlebar to fill out the `weights.hbs` template.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct TemplateData {
+	/// Name of the database. Can be "RocksDb" or "ParityDb".
+	db_name: String,
+	/// Version of the benchmarking CLI used.
+	version: String,
+	/// Date that the template was filled out.
+	date: String,
+	/// Command line arguments that were passed to the CLI.
+	args: Vec<String>,
+	/// Params of the executed command.
+	params: TemplateParams,
+	/// Stats about the read benchmark.
+	read: Stats,
+	/// Stats about the write benchmark.
+	write: Stats,
+	/// The resulting read weight in nanoseconds.
+	read_weight: u64,
+	/// The resulting write weight in nanoseconds.
+	write_weight: u64,
+	/// Content of the header file, if any.
+	header: String,
+}
+
+/// Parameters of the executed command that are shown in the weight file.
+#[derive(Serialize, Debug, Clone)]
+struct TemplateParams {
+	sample_size: usize,
+	weight_path: String,
+}
+
+impl TemplateData {
+	/// Returns a new [`Self`] from the given params.
+	pub(crate) fn new(db_name: &str, cmd: &StorageCmd, read: &Stats, write: &Stats) -> Self {
+		TemplateData {
+			db_name: db_name.into(),
+			version: VERSION.into(),
+			date: chrono::Utc::now().format("%Y-%m-%d (Y/M/D)").to_string(),
+			args: std::env::args().collect::<Vec<String>>(),
+			params: TemplateParams {
+				sample_size: cmd.sample_size,
+				weight_path: cmd.weight_path.as_ref()
+					.map(|p| p.display().to_string())
+					.unwrap_or_else(|| ".".into()),
+			},
+			read: read.clone(),
+			write: write.clone(),
+			read_weight: read.median,
+			write_weight: write.median,
+			header: String::new(),
+		}
+	}
+
+	/// Fill out the `weights.hbs` template and write it to `<weight_path>/db_weights.rs`.
+	pub(crate) fn write(mut self, header: &Option<PathBuf>, weight_path: &Path) -> Result<()> {
+		if let Some(header_file) = header {
+			self.header = fs::read_to_string(header_file)?;
+		}
+
+		let mut handlebars = handlebars::Handlebars::new();
+		handlebars.register_helper("underscore", Box::new(UnderscoreHelper));
+		// Don't HTML escape any characters.
+		handlebars.register_escape_fn(|s| -> String { s.to_string() });
+
+		let out_path = weight_path.join("db_weights.rs");
+		let mut fd = fs::File::create(&out_path)?;
+		println!("Writing weights to {:?}", out_path);
+		handlebars.render_template_to_write(TEMPLATE, &self, &mut fd)
+			.map_err(|e| format!("HBS template write: {:?}", e).into())
+	}
+}
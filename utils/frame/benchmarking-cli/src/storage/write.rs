@@ -0,0 +1,189 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Measures the time to write storage items into the database.
+
+use sc_cli::Result;
+use sc_client_db::DbHash;
+use sp_database::{ColumnId, Database, Transaction};
+use sp_runtime::traits::{Block as BlockT, HashFor};
+use sp_state_machine::Backend as StateBackend;
+use sp_trie::PrefixedMemoryDB;
+use std::time::Instant;
+
+use super::read::Pairs;
+
+/// Overwrites the value of every key of `pairs` with a changed value of the same size and
+/// returns the time of each write in nanoseconds.
+///
+/// A write consists of calculating the new storage root and committing the changed trie nodes
+/// to `db`, which must not be the live database but a copy of it. Every write is reverted
+/// afterwards, so that all writes are measured against the same database: If `column` is
+/// `ref_counted`, each written node is removed again to release the reference that was added
+/// by writing it. Otherwise only the nodes that did not exist before are removed.
+pub(crate) fn bench_write<Block, S>(
+	state: &S,
+	pairs: &Pairs,
+	db: &dyn Database<DbHash>,
+	column: ColumnId,
+	ref_counted: bool,
+) -> Result<Vec<u64>>
+where
+	Block: BlockT,
+	S: StateBackend<HashFor<Block>, Transaction = PrefixedMemoryDB<HashFor<Block>>>,
+{
+	let mut record = Vec::with_capacity(pairs.len());
+
+	for (key, original) in pairs {
+		let value = original.iter().map(|b| b.wrapping_add(1)).collect::<Vec<_>>();
+
+		let start = Instant::now();
+		let (_, mut changes) = state.storage_root(std::iter::once((&key[..], Some(&value[..]))));
+		let calculation = start.elapsed();
+
+		let mut tx = Transaction::new();
+		let mut written = Vec::new();
+		for (node_key, (node, rc)) in changes.drain() {
+			if rc > 0 {
+				if ref_counted || !db.contains(column, node_key.as_ref()) {
+					written.push(node_key.clone());
+				}
+				tx.set_from_vec(column, node_key.as_ref(), node);
+			}
+		}
+
+		let start = Instant::now();
+		db.commit(tx).map_err(|e| format!("Error writing to the database: {:?}", e))?;
+		record.push((calculation + start.elapsed()).as_nanos() as u64);
+
+		let mut cleanup = Transaction::new();
+		for node_key in written {
+			cleanup.remove(column, node_key.as_ref());
+		}
+		db.commit(cleanup).map_err(|e| format!("Error cleaning up the database: {:?}", e))?;
+	}
+
+	Ok(record)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use sp_database::Change;
+	use sp_runtime::{testing::{Block as RawBlock, ExtrinsicWrapper}, traits::BlakeTwo256};
+	use sp_state_machine::TrieBackend;
+	use sp_trie::{TrieMut, TrieDBMut, Layout};
+	use std::{collections::HashMap, sync::Mutex};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	const COLUMN: ColumnId = 1;
+
+	/// A single column database which optionally counts the references of every value like a
+	/// ref counted ParityDb column.
+	struct TestDb {
+		ref_counted: bool,
+		entries: Mutex<HashMap<Vec<u8>, (Vec<u8>, u32)>>,
+	}
+
+	impl TestDb {
+		fn new(ref_counted: bool) -> Self {
+			TestDb { ref_counted, entries: Default::default() }
+		}
+
+		fn entries(&self) -> HashMap<Vec<u8>, (Vec<u8>, u32)> {
+			self.entries.lock().unwrap().clone()
+		}
+	}
+
+	impl Database<DbHash> for TestDb {
+		fn commit(&self, transaction: Transaction<DbHash>) -> sp_database::error::Result<()> {
+			let mut entries = self.entries.lock().unwrap();
+			for change in transaction.0 {
+				match change {
+					Change::Set(_, key, value) => {
+						let entry = entries.entry(key).or_insert((value, 0));
+						entry.1 = if self.ref_counted { entry.1 + 1 } else { 1 };
+					},
+					Change::Remove(_, key) => {
+						let remove = entries.get_mut(&key).map_or(false, |(_, rc)| {
+							*rc -= 1;
+							!self.ref_counted || *rc == 0
+						});
+						if remove {
+							entries.remove(&key);
+						}
+					},
+					_ => unimplemented!(),
+				}
+			}
+			Ok(())
+		}
+
+		fn get(&self, _: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+			self.entries.lock().unwrap().get(key).map(|(value, _)| value.clone())
+		}
+
+		fn lookup(&self, _: &DbHash) -> Option<Vec<u8>> {
+			unimplemented!()
+		}
+	}
+
+	/// Build a state of `pairs` and write its trie nodes to `db`.
+	fn state_in(
+		db: &TestDb,
+		pairs: &Pairs,
+	) -> TrieBackend<PrefixedMemoryDB<BlakeTwo256>, BlakeTwo256> {
+		let mut mdb = PrefixedMemoryDB::<BlakeTwo256>::default();
+		let mut root = Default::default();
+		{
+			let mut trie = TrieDBMut::<Layout<BlakeTwo256>>::new(&mut mdb, &mut root);
+			for (key, value) in pairs {
+				trie.insert(key, value).unwrap();
+			}
+		}
+		let mut tx = Transaction::new();
+		for (key, (node, rc)) in mdb.clone().drain() {
+			if rc > 0 {
+				tx.set_from_vec(COLUMN, key.as_ref(), node);
+			}
+		}
+		db.commit(tx).unwrap();
+		TrieBackend::new(mdb, root)
+	}
+
+	fn bench_write_leaves_db_unchanged(ref_counted: bool) {
+		let db = TestDb::new(ref_counted);
+		let pairs: Pairs = (0u8 .. 32).map(|i| (vec![i; 8], vec![i; 40])).collect();
+		let state = state_in(&db, &pairs);
+		let before = db.entries();
+
+		let record = bench_write::<Block, _>(&state, &pairs, &db, COLUMN, ref_counted).unwrap();
+		assert_eq!(record.len(), pairs.len());
+		assert_eq!(db.entries(), before);
+	}
+
+	#[test]
+	fn bench_write_removes_new_nodes() {
+		bench_write_leaves_db_unchanged(false);
+	}
+
+	#[test]
+	fn bench_write_releases_references() {
+		bench_write_leaves_db_unchanged(true);
+	}
+}
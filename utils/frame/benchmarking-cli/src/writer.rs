@@ -351,7 +351,7 @@ fn underscore<Number>(i: Number) -> String
 // A Handlebars helper to add an underscore after every 3rd character,
 // i.e. a separator for large numbers.
 #[derive(Clone, Copy)]
-struct UnderscoreHelper;
+pub(crate) struct UnderscoreHelper;
 impl handlebars::HelperDef for UnderscoreHelper {
 	fn call<'reg: 'rc, 'rc>(
 		&self, h: &handlebars::Helper,
@@ -110,7 +110,7 @@ use sp_core::{hashing::twox_128};
 pub use sp_io::TestExternalities;
 use sp_core::{
 	hexdisplay::HexDisplay,
-	storage::{StorageKey, StorageData},
+	storage::{StorageKey, StorageData, StorageChangeSet},
 };
 use codec::{Encode, Decode};
 use jsonrpsee_http_client::{HttpClient, HttpConfig};
@@ -121,11 +121,22 @@ type Hash = sp_core::H256;
 
 const LOG_TARGET: &str = "remote-ext";
 const TARGET: &str = "http://localhost:9933";
+/// The number of keys requested per `state_getKeysPaged` call.
+const PAGE: u32 = 512;
 
 jsonrpsee_proc_macros::rpc_client_api! {
 	RpcApi {
 		#[rpc(method = "state_getPairs", positional_params)]
 		fn storage_pairs(prefix: StorageKey, hash: Option<Hash>) -> Vec<(StorageKey, StorageData)>;
+		#[rpc(method = "state_getKeysPaged", positional_params)]
+		fn storage_keys_paged(
+			prefix: Option<StorageKey>,
+			count: u32,
+			start_key: Option<StorageKey>,
+			hash: Option<Hash>,
+		) -> Vec<StorageKey>;
+		#[rpc(method = "state_queryStorageAt", positional_params)]
+		fn query_storage_at(keys: Vec<StorageKey>, at: Option<Hash>) -> Vec<StorageChangeSet<Hash>>;
 		#[rpc(method = "chain_getFinalizedHead")]
 		fn finalized_head() -> Hash;
 	}
@@ -162,11 +173,23 @@ pub struct OnlineConfig {
 	pub cache: Option<CacheConfig>,
 	/// The modules to scrape. If empty, entire chain state will be scraped.
 	pub modules: Vec<String>,
+	/// Scrape the state using `state_getKeysPaged` and `state_queryStorageAt` instead of the
+	/// unsafe `state_getPairs`.
+	///
+	/// This is slower, but works against nodes that don't expose unsafe RPCs and doesn't hit
+	/// the response size limit on large states.
+	pub paged: bool,
 }
 
 impl Default for OnlineConfig {
 	fn default() -> Self {
-		Self { uri: TARGET.to_owned(), at: None, cache: None, modules: Default::default() }
+		Self {
+			uri: TARGET.to_owned(),
+			at: None,
+			cache: None,
+			modules: Default::default(),
+			paged: false,
+		}
 	}
 }
 
@@ -257,6 +280,81 @@ impl Builder {
 			"rpc storage_pairs failed"
 			})
 	}
+
+	/// Get all the keys under `prefix` at `at`, one page at a time.
+	async fn rpc_get_keys_paged(
+		&self,
+		prefix: StorageKey,
+		at: Hash,
+	) -> Result<Vec<StorageKey>, &'static str> {
+		let mut all_keys = vec![];
+		let mut last_key: Option<StorageKey> = None;
+		loop {
+			trace!(target: LOG_TARGET, "rpc: storage_keys_paged: {:?} / {:?}", prefix, last_key);
+			let page = RpcApi::storage_keys_paged(
+				&self.as_online().rpc(),
+				Some(prefix.clone()),
+				PAGE,
+				last_key.clone(),
+				Some(at),
+			)
+			.await
+			.map_err(|e| {
+				error!("Error = {:?}", e);
+				"rpc storage_keys_paged failed"
+			})?;
+			let page_len = page.len();
+			last_key = page.last().cloned();
+			all_keys.extend(page);
+
+			if page_len < PAGE as usize {
+				break
+			}
+		}
+		Ok(all_keys)
+	}
+
+	/// Get all the key-value pairs under `prefix` at `at`, using paged RPC calls.
+	async fn rpc_get_pairs_paged(
+		&self,
+		prefix: StorageKey,
+		at: Hash,
+	) -> Result<Vec<KeyPair>, &'static str> {
+		let keys = self.rpc_get_keys_paged(prefix, at).await?;
+		let mut key_values = Vec::with_capacity(keys.len());
+		for chunk in keys.chunks(PAGE as usize) {
+			trace!(target: LOG_TARGET, "rpc: query_storage_at: {} keys", chunk.len());
+			let change_sets = RpcApi::query_storage_at(
+				&self.as_online().rpc(),
+				chunk.to_vec(),
+				Some(at),
+			)
+			.await
+			.map_err(|e| {
+				error!("Error = {:?}", e);
+				"rpc query_storage_at failed"
+			})?;
+			for change_set in change_sets {
+				key_values.extend(
+					change_set.changes.into_iter().filter_map(|(k, v)| v.map(|v| (k, v)))
+				);
+			}
+		}
+		Ok(key_values)
+	}
+
+	/// Get all the key-value pairs under `prefix` at `at`, in the way configured for this builder.
+	async fn get_pairs(
+		&self,
+		prefix: StorageKey,
+		at: Hash,
+	) -> Result<Vec<KeyPair>, &'static str> {
+		if self.as_online().paged {
+			self.rpc_get_pairs_paged(prefix, at).await
+		} else {
+			self.rpc_get_pairs(prefix, at).await
+		}
+	}
 }
 
 // Internal methods
@@ -289,7 +387,7 @@ impl Builder {
 			let mut filtered_kv = vec![];
 			for f in config.modules.iter() {
 				let hashed_prefix = StorageKey(twox_128(f.as_bytes()).to_vec());
-				let module_kv = self.rpc_get_pairs(hashed_prefix.clone(), at).await?;
+				let module_kv = self.get_pairs(hashed_prefix.clone(), at).await?;
 				info!(
 					target: LOG_TARGET,
 					"downloaded data for module {} (count: {} / prefix: {:?}).",
@@ -302,7 +400,7 @@ impl Builder {
 			filtered_kv
 		} else {
 			info!(target: LOG_TARGET, "downloading data for all modules.");
-			self.rpc_get_pairs(StorageKey(vec![]), at).await?.into_iter().collect::<Vec<_>>()
+			self.get_pairs(StorageKey(vec![]), at).await?.into_iter().collect::<Vec<_>>()
 		};
 
 		Ok(keys_and_values)
@@ -401,6 +499,21 @@ mod tests {
 			.execute_with(|| {});
 	}
 
+	#[async_std::test]
+	async fn can_build_one_pallet_paged() {
+		init_logger();
+		Builder::new()
+			.mode(Mode::Online(OnlineConfig {
+				modules: vec!["Proxy".into()],
+				paged: true,
+				..Default::default()
+			}))
+			.build()
+			.await
+			.unwrap()
+			.execute_with(|| {});
+	}
+
 	#[async_std::test]
 	async fn can_load_cache() {
 		init_logger();
@@ -24,7 +24,7 @@ use sc_cli::{CliConfiguration, ExecutionStrategy, WasmExecutionMethod};
 use sc_executor::NativeExecutor;
 use sc_service::NativeExecutionDispatch;
 use sp_state_machine::StateMachine;
-use sp_runtime::traits::{Block as BlockT, NumberFor};
+use sp_runtime::{RuntimeString, traits::{Block as BlockT, NumberFor}};
 use sp_core::storage::{StorageData, StorageKey, well_known_keys};
 
 /// Various commands to try out the new runtime, over configurable states.
@@ -151,8 +151,10 @@ impl TryRuntimeCmd {
 		.execute(execution.into())
 		.map_err(|e| format!("failed to execute 'TryRuntime_on_runtime_upgrade' due to {:?}", e))?;
 
-		let (weight, total_weight) = <(u64, u64) as Decode>::decode(&mut &*encoded_result)
-			.map_err(|e| format!("failed to decode output due to {:?}", e))?;
+		let (weight, total_weight) =
+			<Result<(u64, u64), RuntimeString> as Decode>::decode(&mut &*encoded_result)
+				.map_err(|e| format!("failed to decode output due to {:?}", e))?
+				.map_err(|e| format!("runtime upgrade checks failed: {}", e))?;
 		log::info!(
 			"try-runtime executed without errors. Consumed weight = {}, total weight = {} ({})",
 			weight,
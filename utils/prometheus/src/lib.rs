@@ -28,7 +28,7 @@ pub use prometheus::{
 	}
 };
 use prometheus::{Encoder, TextEncoder, core::Collector};
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 
 #[cfg(not(target_os = "unknown"))]
 mod networking;
@@ -37,9 +37,58 @@ mod sourced;
 pub use sourced::{SourcedCounter, SourcedGauge, MetricSource, SourcedMetric};
 
 #[cfg(target_os = "unknown")]
-pub use unknown_os::init_prometheus;
+pub use unknown_os::{init_prometheus, init_prometheus_with_health};
 #[cfg(not(target_os = "unknown"))]
-pub use known_os::init_prometheus;
+pub use known_os::{init_prometheus, init_prometheus_with_health};
+
+/// Snapshot of the node's health served by the `/health` and `/readiness` endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeHealth {
+	/// Number of connected peers.
+	pub peers: usize,
+	/// Whether the node is performing a major sync.
+	pub is_syncing: bool,
+	/// Whether the node is expected to be connected to other peers.
+	pub should_have_peers: bool,
+	/// Number of the best block.
+	pub best_number: u64,
+	/// Number of the last finalized block.
+	pub finalized_number: u64,
+}
+
+impl NodeHealth {
+	/// Number of blocks the finalized block lags behind the best block.
+	pub fn finality_lag(&self) -> u64 {
+		self.best_number.saturating_sub(self.finalized_number)
+	}
+
+	/// Returns `true` if the node is connected to peers, or isn't expected to be.
+	pub fn is_healthy(&self) -> bool {
+		self.peers > 0 || !self.should_have_peers
+	}
+
+	/// Returns `true` if the node is healthy and not performing a major sync.
+	pub fn is_ready(&self) -> bool {
+		self.is_healthy() && !self.is_syncing
+	}
+
+	/// Encodes the health as a JSON object.
+	pub fn to_json(&self) -> String {
+		format!(
+			"{{\"peers\":{},\"isSyncing\":{},\"shouldHavePeers\":{},\"bestNumber\":{},\
+			\"finalizedNumber\":{},\"finalityLag\":{}}}",
+			self.peers,
+			self.is_syncing,
+			self.should_have_peers,
+			self.best_number,
+			self.finalized_number,
+			self.finality_lag(),
+		)
+	}
+}
+
+/// Source of the health reported by the `/health` and `/readiness` endpoints.
+pub type HealthSource = Arc<dyn Fn() -> NodeHealth + Send + Sync>;
 
 pub fn register<T: Clone + Collector + 'static>(metric: T, registry: &Registry) -> Result<T, PrometheusError> {
 	registry.register(Box::new(metric.clone()))?;
@@ -56,6 +105,14 @@ mod unknown_os {
 	pub async fn init_prometheus(_: SocketAddr, _registry: Registry) -> Result<(), Error> {
 		Ok(())
 	}
+
+	pub async fn init_prometheus_with_health(
+		_: SocketAddr,
+		_registry: Registry,
+		_health: Option<HealthSource>,
+	) -> Result<(), Error> {
+		Ok(())
+	}
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -87,7 +144,34 @@ mod known_os {
 		}
 	}
 
-	async fn request_metrics(req: Request<Body>, registry: Registry) -> Result<Response<Body>, Error> {
+	fn health_response(health: NodeHealth, ok: bool) -> Result<Response<Body>, Error> {
+		let status = if ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+		Response::builder().status(status)
+			.header("Content-Type", "application/json")
+			.body(Body::from(health.to_json()))
+			.map_err(Error::Http)
+	}
+
+	async fn request_metrics(
+		req: Request<Body>,
+		registry: Registry,
+		health: Option<HealthSource>,
+	) -> Result<Response<Body>, Error> {
+		match (req.uri().path(), health) {
+			("/health", Some(health)) => {
+				let health = health();
+				let ok = health.is_healthy();
+				return health_response(health, ok)
+			},
+			("/readiness", Some(health)) => {
+				let health = health();
+				let ok = health.is_ready();
+				return health_response(health, ok)
+			},
+			_ => {},
+		}
+
 		if req.uri().path() == "/metrics" {
 			let metric_families = registry.gather();
 			let mut buffer = vec![];
@@ -122,6 +206,19 @@ mod known_os {
 	/// Initializes the metrics context, and starts an HTTP server
 	/// to serve metrics.
 	pub async fn init_prometheus(prometheus_addr: SocketAddr, registry: Registry) -> Result<(), Error>{
+		init_prometheus_with_health(prometheus_addr, registry, None).await
+	}
+
+	/// Same as [`init_prometheus`], but additionally serves the node's health.
+	///
+	/// If `health` is given, `/health` and `/readiness` report the current [`NodeHealth`]
+	/// as JSON. They respond with `503 Service Unavailable` if the node isn't healthy
+	/// respectively ready, which makes them usable as liveness and readiness probes.
+	pub async fn init_prometheus_with_health(
+		prometheus_addr: SocketAddr,
+		registry: Registry,
+		health: Option<HealthSource>,
+	) -> Result<(), Error> {
 		use networking::Incoming;
 		let listener = async_std::net::TcpListener::bind(&prometheus_addr)
 			.await
@@ -131,10 +228,11 @@ mod known_os {
 
 		let service = make_service_fn(move |_| {
 			let registry = registry.clone();
+			let health = health.clone();
 
 			async move {
 				Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
-					request_metrics(req, registry.clone())
+					request_metrics(req, registry.clone(), health.clone())
 				}))
 			}
 		});
@@ -149,3 +247,37 @@ mod known_os {
 		result
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn health(peers: usize, is_syncing: bool) -> NodeHealth {
+		NodeHealth {
+			peers,
+			is_syncing,
+			should_have_peers: true,
+			best_number: 12,
+			finalized_number: 10,
+		}
+	}
+
+	#[test]
+	fn readiness_requires_peers_and_no_major_sync() {
+		assert!(health(3, false).is_ready());
+		assert!(health(3, true).is_healthy());
+		assert!(!health(3, true).is_ready());
+		assert!(!health(0, false).is_healthy());
+		assert!(!health(0, false).is_ready());
+		assert!(NodeHealth { should_have_peers: false, ..health(0, false) }.is_ready());
+	}
+
+	#[test]
+	fn health_is_encoded_as_json() {
+		assert_eq!(
+			health(3, false).to_json(),
+			"{\"peers\":3,\"isSyncing\":false,\"shouldHavePeers\":true,\"bestNumber\":12,\
+			\"finalizedNumber\":10,\"finalityLag\":2}",
+		);
+	}
+}